@@ -15,9 +15,11 @@ pub fn criterion_benchmark(c: &mut Criterion) {
     builder.init();
 
     // Setup global config to suppress output
-    let mut config = Config::default();
-    config.api_mode = true;
-    config.verbose = 0;
+    let config = Config {
+        api_mode: true,
+        verbose: 0,
+        ..Default::default()
+    };
     set_global_config(config);
 
     let decode_base64 = Decoder::<Base64Decoder>::new();
@@ -19,6 +19,8 @@ use std::time::Duration;
 // Test cases for different decoders
 struct DecoderTestCase<'a> {
     encoded: &'a str,
+    // Kept alongside the input so the cases document what they decode to
+    #[allow(dead_code)]
     expected: &'a str,
     description: &'a str,
 }
@@ -76,9 +78,11 @@ pub fn benchmark_decoders(c: &mut Criterion) {
     builder.init();
 
     // Setup global config to suppress output
-    let mut config = Config::default();
-    config.api_mode = true;
-    config.verbose = 0;
+    let config = Config {
+        api_mode: true,
+        verbose: 0,
+        ..Default::default()
+    };
     set_global_config(config);
 
     // Create a benchmark group with appropriate measurement time
@@ -1,4 +1,5 @@
 use ciphey::config::Config;
+use ciphey::corpus::CorpusGenerator;
 use ciphey::perform_cracking;
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
 use env_logger::Builder;
@@ -82,12 +83,30 @@ fn benchmark_with_config(
     };
     let timeout_str = format!("timeout_{}", timeout);
 
-    for (text, description) in TEST_CASES {
+    // Layered cases from the corpus, seeded so every run benchmarks the same chains
+    let corpus: Vec<(String, String)> = CorpusGenerator::new(0)
+        .with_layers(2, 3)
+        .cases(3)
+        .into_iter()
+        .enumerate()
+        .map(|(i, case)| {
+            (
+                case.ciphertext,
+                format!("corpus_{}_layers_{}", i, case.encoders.len()),
+            )
+        })
+        .collect();
+    let cases = TEST_CASES
+        .iter()
+        .map(|(text, description)| (text.to_string(), description.to_string()))
+        .chain(corpus);
+
+    for (text, description) in cases {
         let id = BenchmarkId::new(
             format!("{}_{}_{}", config_name, timeout_str, description),
             text.len(),
         );
-        group.bench_with_input(id, text, |b, text| {
+        group.bench_with_input(id, &text, |b, text| {
             b.iter_batched_ref(
                 || {
                     let _test_db = ciphey::TestDatabase::default();
@@ -95,12 +114,14 @@ fn benchmark_with_config(
                 },
                 |_| {
                     // Create config and set necessary parameters
-                    let mut config = Config::default();
-                    config.timeout = timeout;
-                    config.top_results = top_results;
-                    config.verbose = 0;
-                    config.human_checker_on = false;
-                    config.api_mode = true; // Set to true to suppress output
+                    let config = Config {
+                        timeout,
+                        top_results,
+                        verbose: 0,
+                        human_checker_on: false,
+                        api_mode: true, // Set to true to suppress output
+                        ..Default::default()
+                    };
 
                     // Use perform_cracking with the configuration
                     perform_cracking(black_box(text), config)
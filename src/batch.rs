@@ -0,0 +1,246 @@
+//! Cracks many inputs in one go, such as thousands of tokens pulled out of logs
+//! The config and database are set up once for the whole batch, and inputs which
+//! have already been solved in the same batch are answered without searching again.
+//! That includes inputs which turn up part way down another input's decoding path,
+//! so `aGVsbG8=` and its base64 encoded `YUdWc2JHOD0=` only cost one search.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::config::Config;
+use crate::storage::history::RunOutcome;
+use crate::{crack_prepared_text, prepare_cracking, CancellationToken, DecoderResult};
+
+/// The most inputs a single batch will accept
+pub const MAX_BATCH_SIZE: usize = 10_000;
+
+/// The result of cracking one input of a batch
+#[derive(Debug, Clone)]
+pub struct BatchItem {
+    /// The input exactly as it was given
+    pub input: String,
+    /// The plaintext and the path to it, None if the input could not be cracked
+    pub result: Option<DecoderResult>,
+    /// How the input's run ended, cached if it was answered by an earlier input of the batch
+    pub outcome: RunOutcome,
+    /// How long this input took to crack
+    pub duration: Duration,
+    /// True if the result was reused from an earlier input of the batch instead of searched for
+    pub deduplicated: bool,
+}
+
+/// Errors from cracking a batch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BatchError {
+    /// More inputs were given than `MAX_BATCH_SIZE`
+    TooLarge {
+        /// How many inputs were given
+        size: usize,
+        /// The most inputs a batch accepts
+        max: usize,
+    },
+}
+
+impl std::fmt::Display for BatchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BatchError::TooLarge { size, max } => {
+                write!(
+                    f,
+                    "Batch has {} inputs but at most {} are allowed",
+                    size, max
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for BatchError {}
+
+/// Cracks every input, returning a result for each in the same order
+///
+/// Batches are for bulk triage, so the human checker, `top_results` and `all_results` are
+/// turned off.
+/// ```rust
+/// use ciphey::batch::perform_batch_cracking;
+/// use ciphey::config::Config;
+/// # let _test_db = ciphey::TestDatabase::default();
+/// # ciphey::set_test_db_path();
+/// let inputs = ["aGVsbG8gdGhlcmUgZ2VuZXJhbA==", "aGVsbG8gdGhlcmUgZ2VuZXJhbA=="];
+/// let results = perform_batch_cracking(&inputs, Config::default()).unwrap();
+/// assert_eq!(results[0].result.as_ref().unwrap().text[0], "hello there general");
+/// assert!(results[1].deduplicated);
+/// ```
+///
+/// # Errors
+/// Returns `BatchError::TooLarge` if there are more than `MAX_BATCH_SIZE` inputs.
+pub fn perform_batch_cracking<T: AsRef<str>>(
+    inputs: &[T],
+    config: Config,
+) -> Result<Vec<BatchItem>, BatchError> {
+    check_batch_size(inputs.len())?;
+
+    let mut config = config;
+    config.human_checker_on = false;
+    config.top_results = false;
+    config.all_results = false;
+    prepare_cracking(config);
+
+    crack_prepared_batch(inputs)
+}
+
+/// Cracks every input like [`perform_batch_cracking`], with the config already set up by
+/// `prepare_cracking`, as services such as `ciphey serve` do once for every request
+///
+/// # Errors
+/// Returns `BatchError::TooLarge` if there are more than `MAX_BATCH_SIZE` inputs.
+pub fn crack_prepared_batch<T: AsRef<str>>(inputs: &[T]) -> Result<Vec<BatchItem>, BatchError> {
+    check_batch_size(inputs.len())?;
+
+    let mut known = KnownResults::default();
+    let items = inputs
+        .iter()
+        .map(|input| {
+            let input = input.as_ref();
+            let start = Instant::now();
+            if let Some(result) = known.get(input) {
+                log::debug!("Batch input {} was already solved in this batch", input);
+                return BatchItem {
+                    input: input.to_string(),
+                    result,
+                    outcome: RunOutcome::Cached,
+                    duration: start.elapsed(),
+                    deduplicated: true,
+                };
+            }
+
+            let (result, outcome) =
+                crack_prepared_text(input, SystemTime::now(), &CancellationToken::new());
+            known.record(input, &result);
+            BatchItem {
+                input: input.to_string(),
+                result,
+                outcome,
+                duration: start.elapsed(),
+                deduplicated: false,
+            }
+        })
+        .collect();
+    log::info!("{}", crate::storage::memory_cache::stats());
+    Ok(items)
+}
+
+/// Refuses batches of more than `MAX_BATCH_SIZE` inputs
+fn check_batch_size(size: usize) -> Result<(), BatchError> {
+    if size > MAX_BATCH_SIZE {
+        return Err(BatchError::TooLarge {
+            size,
+            max: MAX_BATCH_SIZE,
+        });
+    }
+    Ok(())
+}
+
+/// The results already found in this batch, keyed by the text they decode
+#[derive(Default)]
+struct KnownResults {
+    /// Maps an input, or a text part way down a decoding path, to its result
+    results: HashMap<String, Option<DecoderResult>>,
+}
+
+impl KnownResults {
+    /// Looks up the result for the text, the outer Option is None if it hasn't been seen
+    fn get(&self, text: &str) -> Option<Option<DecoderResult>> {
+        self.results.get(text).cloned()
+    }
+
+    /// Remembers the result for the input, and for every text on its decoding path
+    /// Each text on the path decodes to the same plaintext using the rest of the path.
+    fn record(&mut self, input: &str, result: &Option<DecoderResult>) {
+        self.results.insert(input.to_string(), result.clone());
+        let Some(result) = result else {
+            return;
+        };
+        for (i, step) in result.path.iter().enumerate() {
+            self.results
+                .entry(step.encrypted_text.clone())
+                .or_insert_with(|| {
+                    Some(DecoderResult {
+                        text: result.text.clone(),
+                        path: result.path[i..].to_vec(),
+                        bytes: None,
+                    })
+                });
+        }
+    }
+}
+
+#[cfg(test)]
+#[serial_test::parallel]
+mod tests {
+    use super::*;
+    use crate::decoders::crack_results::CrackResult;
+    use crate::decoders::interface::Decoder;
+    use crate::storage::database::DB_PATH;
+
+    /// Uses the same shared in-memory database as the storage tests,
+    /// so whichever test sets the global path first the database tests still work
+    fn set_memory_db_path() {
+        let path = std::path::PathBuf::from(String::from("file::memory:?cache=shared"));
+        let _ = DB_PATH.set(Some(path));
+    }
+
+    #[test]
+    fn too_many_inputs_are_rejected() {
+        let inputs = vec![""; MAX_BATCH_SIZE + 1];
+        let result = perform_batch_cracking(&inputs, Config::default());
+        assert_eq!(
+            result.unwrap_err(),
+            BatchError::TooLarge {
+                size: MAX_BATCH_SIZE + 1,
+                max: MAX_BATCH_SIZE
+            }
+        );
+    }
+
+    #[test]
+    fn results_come_back_in_input_order() {
+        set_memory_db_path();
+
+        let inputs = [
+            "aGVsbG8gdGhlcmUgZ2VuZXJhbA==",
+            "",
+            "aGVsbG8gdGhlcmUgZ2VuZXJhbA==",
+        ];
+        let results = perform_batch_cracking(&inputs, Config::default()).unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].input, inputs[0]);
+        assert!(!results[0].deduplicated);
+        assert!(results[1].result.is_none());
+        assert!(results[2].deduplicated);
+        assert_eq!(results[2].outcome, RunOutcome::Cached);
+        assert_eq!(
+            results[2].result.as_ref().unwrap().text[0],
+            "hello there general"
+        );
+    }
+
+    #[test]
+    fn texts_on_a_decoding_path_are_known() {
+        let decoder = Decoder::default();
+        let outer = CrackResult::new(&decoder, "outer".to_string());
+        let inner = CrackResult::new(&decoder, "inner".to_string());
+        let result = DecoderResult {
+            text: vec!["plaintext".to_string()],
+            path: vec![outer, inner],
+            bytes: None,
+        };
+
+        let mut known = KnownResults::default();
+        known.record("outer", &Some(result));
+        let inner_result = known.get("inner").unwrap().unwrap();
+        assert_eq!(inner_result.text[0], "plaintext");
+        assert_eq!(inner_result.path.len(), 1);
+        assert!(known.get("unseen").is_none());
+    }
+}
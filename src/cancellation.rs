@@ -0,0 +1,76 @@
+//! Stopping a search early, before its timeout
+//! A search only ever ends by finding the plaintext or running out of time, so an
+//! application embedding ciphey hands it a [`CancellationToken`] to stop it from outside,
+//! such as when its user closes the window the search was started from.
+//!
+//! ```
+//! use ciphey::config::Config;
+//! use ciphey::{perform_cracking_with_cancel, CancellationToken};
+//! use std::time::{Duration, Instant};
+//! # let _test_db = ciphey::TestDatabase::default();
+//! # ciphey::set_test_db_path();
+//!
+//! let mut config = Config::default();
+//! config.timeout = 60;
+//! let token = CancellationToken::new();
+//! let search_token = token.clone();
+//! let started = Instant::now();
+//! let search = std::thread::spawn(move || {
+//!     perform_cracking_with_cancel("qZ3xW9vK2pL8mN4bR7tY", config, &search_token)
+//! });
+//! std::thread::sleep(Duration::from_millis(200));
+//! token.cancel();
+//! let _result = search.join().unwrap();
+//! assert!(started.elapsed() < Duration::from_secs(60));
+//! // When nothing was identified the decodes most like plaintext so far are still kept
+//! let _best_so_far = ciphey::best_candidates();
+//! ```
+//! ```
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Stops a search when cancelled, shared by cloning it
+/// Every clone cancels the same searches, and a token stays cancelled once it is.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    /// Set once the token is cancelled
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// A token which hasn't been cancelled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stops every search using this token, as soon as they next check it
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether the token has been cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// The flag behind the token, for things which can only set a flag such as signal handlers
+    pub fn flag(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.cancelled)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clones_share_cancellation() {
+        let token = CancellationToken::new();
+        let clone = token.clone();
+        assert!(!clone.is_cancelled());
+        token.cancel();
+        assert!(clone.is_cancelled());
+        assert!(token.flag().load(Ordering::Relaxed));
+    }
+}
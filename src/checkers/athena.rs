@@ -1,18 +1,26 @@
 /// Athena checker runs all other checkers and returns immediately when a plaintext is found.
 /// This is the standard checker that exits early when a plaintext is found.
 /// For a version that continues checking and collects all plaintexts, see WaitAthena.
+use crate::events::{self, SearchEvent};
 use crate::{checkers::checker_result::CheckResult, cli_pretty_printing, config::get_config};
 use gibberish_or_not::Sensitivity;
 use lemmeknow::Identifier;
 use log::trace;
 
 use super::{
-    checker_type::{Check, Checker},
+    checker_type::{check_bytes, check_if_enabled, Check, Checker},
+    checksum::ChecksumChecker,
+    confirmed, custom, denylist,
     english::EnglishChecker,
+    ensemble,
+    entropy::EntropyChecker,
     human_checker,
+    language::LanguageChecker,
     lemmeknow_checker::LemmeKnow,
+    magic_bytes,
     password::PasswordChecker,
     regex_checker::RegexChecker,
+    structured_data::StructuredDataChecker,
     wordlist::WordlistChecker,
 };
 
@@ -30,7 +38,7 @@ impl Check for Checker<Athena> {
             expected_runtime: 0.01,
             popularity: 1.0,
             lemmeknow_config: Identifier::default(),
-            sensitivity: Sensitivity::Medium, // Default to Medium sensitivity
+            sensitivity: get_config().sensitivity.into(),
             enhanced_detector: None,
             _phantom: std::marker::PhantomData,
         }
@@ -40,110 +48,59 @@ impl Check for Checker<Athena> {
         trace!("Athena checker running on text: {}", text);
         let config = get_config();
 
-        // If regex is specified, only run the regex checker
-        if config.regex.is_some() {
+        // Degenerate and denylisted plaintexts are never accepted,
+        // so don't bother the checkers (or the human) with them
+        if denylist::is_denied(text) {
+            trace!("Athena rejected denylisted text: {:?}", text);
+            return CheckResult::new(self);
+        }
+
+        // Plaintexts the user confirmed before are accepted without checking or asking again
+        if confirmed::was_confirmed(text) {
+            trace!("Athena accepted a confirmed plaintext: {:?}", text);
+            let mut check_res = CheckResult::new(self);
+            check_res.is_identified = true;
+            check_res.text = text.to_string();
+            check_res.description = String::from("a plaintext you confirmed before");
+            check_res.confidence = 1.0;
+            return check_res;
+        }
+
+        // In Ciphey if the user uses the regex checker all the other checkers turn off
+        // This is because they are looking for one specific bit of information so will not want the other checkers
+        let verdict = if !config.regex_patterns().is_empty() {
             trace!("running regex");
             let regex_checker = Checker::<RegexChecker>::new().with_sensitivity(self.sensitivity);
-            let regex_result = regex_checker.check(text);
-            if regex_result.is_identified {
-                let mut check_res = CheckResult::new(&regex_checker);
-                trace!("DEBUG: Athena - About to run human checker for regex result");
-                let human_result = human_checker::human_checker(&regex_result);
-                trace!(
-                    "Human checker called from regex checker with result: {}",
-                    human_result
-                );
-                check_res.is_identified = human_result;
-                check_res.text = regex_result.text;
-                check_res.description = regex_result.description;
-                cli_pretty_printing::success(&format!(
-                    "DEBUG: Athena regex checker - human_result: {}, check_res.is_identified: {}",
-                    human_result, check_res.is_identified
-                ));
-                return check_res;
-            }
+            let regex_result = check_if_enabled(&regex_checker, text);
+            regex_result.is_identified.then_some(regex_result)
         } else {
-            // Run wordlist checker first if a wordlist is provided
-            if config.wordlist.is_some() {
-                trace!("running wordlist checker");
-                let wordlist_checker =
-                    Checker::<WordlistChecker>::new().with_sensitivity(self.sensitivity);
-                let wordlist_result = wordlist_checker.check(text);
-                if wordlist_result.is_identified {
-                    let mut check_res = CheckResult::new(&wordlist_checker);
-                    let human_result = human_checker::human_checker(&wordlist_result);
-                    trace!(
-                        "Human checker called from wordlist checker with result: {}",
-                        human_result
-                    );
-                    check_res.is_identified = human_result;
-                    check_res.text = wordlist_result.text;
-                    check_res.description = wordlist_result.description;
-                    cli_pretty_printing::success(&format!(
-                        "DEBUG: Athena wordlist checker - human_result: {}, check_res.is_identified: {}",
-                        human_result, check_res.is_identified
-                    ));
-                    return check_res;
-                }
-            }
-
-            // In Ciphey if the user uses the regex checker all the other checkers turn off
-            // This is because they are looking for one specific bit of information so will not want the other checkers
-            // TODO: wrap all checkers in oncecell so we only create them once!
-            let lemmeknow = Checker::<LemmeKnow>::new().with_sensitivity(self.sensitivity);
-            let lemmeknow_result = lemmeknow.check(text);
-            //println!("Text is {}", text);
-            if lemmeknow_result.is_identified {
-                let mut check_res = CheckResult::new(&lemmeknow);
-                let human_result = human_checker::human_checker(&lemmeknow_result);
-                trace!(
-                    "Human checker called from lemmeknow checker with result: {}",
-                    human_result
-                );
-                check_res.is_identified = human_result;
-                check_res.text = lemmeknow_result.text;
-                check_res.description = lemmeknow_result.description;
-                cli_pretty_printing::success(&format!("DEBUG: Athena lemmeknow checker - human_result: {}, check_res.is_identified: {}", human_result, check_res.is_identified));
-                return check_res;
-            }
-
-            let password = Checker::<PasswordChecker>::new().with_sensitivity(self.sensitivity);
-            let password_result = password.check(text);
-            if password_result.is_identified {
-                let mut check_res = CheckResult::new(&password);
-                let human_result = human_checker::human_checker(&password_result);
-                trace!(
-                    "Human checker called from password checker with result: {}",
-                    human_result
-                );
-                check_res.is_identified = human_result;
-                check_res.text = password_result.text;
-                check_res.description = password_result.description;
-                cli_pretty_printing::success(&format!("DEBUG: Athena password checker - human_result: {}, check_res.is_identified: {}", human_result, check_res.is_identified));
-                return check_res;
-            }
-
-            let english = Checker::<EnglishChecker>::new().with_sensitivity(self.sensitivity);
-            let english_result = english.check(text);
-            if english_result.is_identified {
-                let mut check_res = CheckResult::new(&english);
-                let human_result = human_checker::human_checker(&english_result);
-                trace!(
-                    "Human checker called from english checker with result: {}",
-                    human_result
-                );
-                check_res.is_identified = human_result;
-                check_res.text = english_result.text;
-                check_res.description = english_result.description;
-                cli_pretty_printing::success(&format!(
-                    "DEBUG: Athena english checker - human_result: {}, check_res.is_identified: {}",
-                    human_result, check_res.is_identified
-                ));
-                return check_res;
-            }
+            // Every checker gets a say, so agreeing checkers add up to a more confident verdict
+            ensemble::rank_verdicts(sub_checker_results(self.sensitivity, text))
+        };
+        if let Some(mut check_res) =
+            verdict.filter(|verdict| ensemble::meets_min_confidence(verdict, config.min_confidence))
+        {
+            check_res.text = text.to_string();
+            events::publish(|| SearchEvent::checker_succeeded(&check_res));
+            let human_result = human_checker::human_checker(&check_res);
+            trace!(
+                "Human checker called from {} with result: {}",
+                check_res.checker_name,
+                human_result
+            );
+            check_res.is_identified = human_result;
+            cli_pretty_printing::success(&format!(
+                "DEBUG: Athena {} - human_result: {}, check_res.is_identified: {}",
+                check_res.checker_name, human_result, check_res.is_identified
+            ));
+            return check_res;
         }
 
-        CheckResult::new(self)
+        // Nothing identified the text, but its scores still let the search rank it
+        let entropy = Checker::<EntropyChecker>::new().with_sensitivity(self.sensitivity);
+        let mut unidentified = CheckResult::new(self);
+        unidentified.scores = check_if_enabled(&entropy, text).scores;
+        unidentified
     }
 
     fn with_sensitivity(mut self, sensitivity: Sensitivity) -> Self {
@@ -155,3 +112,45 @@ impl Check for Checker<Athena> {
         self.sensitivity
     }
 }
+
+/// Runs every checker Athena combines into its verdict, in the order their results are
+/// ranked in when they are equally confident
+/// The wordlist checker only runs when a wordlist was given, and checkers registered with
+/// [`super::register_custom_checker`] run last.
+pub fn sub_checker_results(sensitivity: Sensitivity, text: &str) -> Vec<CheckResult> {
+    let mut results = Vec::new();
+    if get_config().wordlist.is_some() {
+        trace!("running wordlist checker");
+        let wordlist = Checker::<WordlistChecker>::new().with_sensitivity(sensitivity);
+        results.push(check_if_enabled(&wordlist, text));
+    }
+    // TODO: wrap all checkers in oncecell so we only create them once!
+    let lemmeknow = Checker::<LemmeKnow>::new().with_sensitivity(sensitivity);
+    results.push(check_if_enabled(&lemmeknow, text));
+    let checksum = Checker::<ChecksumChecker>::new().with_sensitivity(sensitivity);
+    results.push(check_if_enabled(&checksum, text));
+    let structured_data = Checker::<StructuredDataChecker>::new().with_sensitivity(sensitivity);
+    results.push(check_if_enabled(&structured_data, text));
+    let password = Checker::<PasswordChecker>::new().with_sensitivity(sensitivity);
+    results.push(check_if_enabled(&password, text));
+    let language = Checker::<LanguageChecker>::new().with_sensitivity(sensitivity);
+    results.push(check_if_enabled(&language, text));
+    let english = Checker::<EnglishChecker>::new().with_sensitivity(sensitivity);
+    results.push(check_if_enabled(&english, text));
+    results.extend(custom::custom_checker_results(text));
+    results
+}
+
+impl Checker<Athena> {
+    /// Checks a decoded value which may not be text
+    /// Bytes which are a file, such as a ZIP archive, are the plaintext. The human checker
+    /// isn't asked about them as printing a file to the terminal shows nothing useful.
+    /// Anything else is checked as text if it is UTF-8, see [`check_bytes`].
+    pub fn check_bytes(&self, bytes: &[u8]) -> CheckResult {
+        if let Some(file_result) = magic_bytes::finished_file(bytes) {
+            trace!("Athena found a file: {}", file_result.description);
+            return file_result;
+        }
+        check_bytes(self, bytes)
+    }
+}
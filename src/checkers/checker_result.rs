@@ -1,6 +1,9 @@
 use super::checker_type::Checker;
+use super::entropy::TextScores;
+use gibberish_or_not::Sensitivity;
 
 /// The checkerResult struct is used to store the results of a checker.
+#[derive(Clone)]
 pub struct CheckResult {
     /// If our checkers return success, we change this bool to True
     pub is_identified: bool,
@@ -16,6 +19,14 @@ pub struct CheckResult {
     pub checker_description: &'static str,
     /// Link to more info about checker
     pub link: &'static str,
+    /// How confident the checker is that the text is plaintext, from 0.0 to 1.0
+    /// Used to rank checkers against each other when more than one identifies the text
+    pub confidence: f32,
+    /// The gibberish detection sensitivity the checker ran with
+    pub sensitivity: Sensitivity,
+    /// How much like plaintext the text is, filled in by the Entropy Checker
+    /// The search ranks decodes no checker identified by these.
+    pub scores: Option<TextScores>,
 }
 
 /// To save time we have a default
@@ -33,6 +44,9 @@ impl CheckResult {
             checker_description: checker_used.description,
             description: "".to_string(),
             link: checker_used.link,
+            confidence: 0.0,
+            sensitivity: checker_used.sensitivity,
+            scores: None,
         }
     }
 }
@@ -1,6 +1,7 @@
 /// Checker_type is a type used to define checkers
 /// This means that we can standardise the way we check for plaintext
 use crate::checkers::checker_result::CheckResult;
+use crate::config::get_config;
 use gibberish_or_not::Sensitivity;
 use lemmeknow::Identifier;
 
@@ -46,6 +47,12 @@ pub trait CheckInfo {
     fn get_name(&self) -> &str;
     /// Returns the checker description
     fn get_description(&self) -> &str;
+    /// Returns the link to read more about the checker
+    fn get_link(&self) -> &str;
+    /// Returns the checker tags
+    fn get_tags(&self) -> &[&str];
+    /// Returns the checker popularity
+    fn get_popularity(&self) -> f32;
 }
 
 impl<Type> CheckInfo for Checker<Type> {
@@ -57,6 +64,18 @@ impl<Type> CheckInfo for Checker<Type> {
     fn get_description(&self) -> &str {
         self.description
     }
+    /// Returns the link to read more about the checker
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Returns the checker tags
+    fn get_tags(&self) -> &[&str] {
+        &self.tags
+    }
+    /// Returns the checker popularity
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
 }
 
 /// Every checker must implement this trait
@@ -77,6 +96,38 @@ pub trait Check {
     fn get_sensitivity(&self) -> Sensitivity;
 }
 
+/// Runs the checker on the text unless the user has toggled it off
+/// A checker that is toggled off never identifies anything
+pub fn check_if_enabled<Type>(checker: &Checker<Type>, text: &str) -> CheckResult
+where
+    Checker<Type>: Check,
+{
+    if get_config().is_checker_enabled(checker.name) {
+        checker.check(text)
+    } else {
+        log::trace!("Skipping {} as it is toggled off", checker.name);
+        CheckResult::new(checker)
+    }
+}
+
+/// Runs the checker on a decoded value which may not be text
+/// UTF-8 is checked as text. Anything else is never identified, and the result's text is
+/// a lossy rendering of the bytes so it can still be shown.
+pub fn check_bytes<Type>(checker: &Checker<Type>, bytes: &[u8]) -> CheckResult
+where
+    Checker<Type>: Check,
+{
+    match std::str::from_utf8(bytes) {
+        Ok(text) => checker.check(text),
+        Err(_) => {
+            let mut result = CheckResult::new(checker);
+            result.text = String::from_utf8_lossy(bytes).to_string();
+            result.description = "Binary data, not text".to_string();
+            result
+        }
+    }
+}
+
 /// Optional trait for checkers that use sensitivity for gibberish detection
 /// Not all checkers need to implement this trait
 /// This is a future improvement - not currently used
@@ -0,0 +1,291 @@
+use crate::checkers::checker_result::CheckResult;
+use gibberish_or_not::Sensitivity;
+use lemmeknow::Identifier;
+
+use crate::checkers::checker_type::{Check, Checker};
+
+/// Checks if the input is a number whose check digits are right: a credit card or IMEI
+/// passing the Luhn check, an ISBN-10 or ISBN-13, or an IBAN.
+/// Regexes can only say a text is shaped like one of these, the check digits say it is one.
+pub struct ChecksumChecker;
+
+/// Implementation of the Check trait for ChecksumChecker
+impl Check for Checker<ChecksumChecker> {
+    fn new() -> Self {
+        Checker {
+            name: "Checksum Checker",
+            description:
+                "Checks if the input is a credit card, IMEI, ISBN or IBAN with valid check digits",
+            link: "https://en.wikipedia.org/wiki/Check_digit",
+            tags: vec!["checksum", "luhn", "isbn", "iban"],
+            expected_runtime: 0.01,
+            popularity: 1.0,
+            lemmeknow_config: Identifier::default(),
+            sensitivity: Sensitivity::Medium,
+            enhanced_detector: None,
+            _phantom: std::marker::PhantomData,
+        }
+    }
+
+    fn check(&self, text: &str) -> CheckResult {
+        let matched = identify(text.trim());
+        CheckResult {
+            is_identified: matched.is_some(),
+            text: text.to_string(),
+            checker_name: self.name,
+            checker_description: self.description,
+            description: matched
+                .as_ref()
+                .map(|(description, _)| description.clone())
+                .unwrap_or_default(),
+            link: self.link,
+            confidence: matched.map_or(0.0, |(_, confidence)| confidence),
+            sensitivity: self.sensitivity,
+            scores: None,
+        }
+    }
+
+    fn with_sensitivity(mut self, sensitivity: Sensitivity) -> Self {
+        self.sensitivity = sensitivity;
+        self
+    }
+
+    fn get_sensitivity(&self) -> Sensitivity {
+        self.sensitivity
+    }
+}
+
+/// What the text is and how sure we are, None if it isn't a number with valid check digits
+/// One check digit in ten passes the Luhn check by chance, so those are the least certain.
+fn identify(text: &str) -> Option<(String, f32)> {
+    if let Some(country) = iban_country(text) {
+        return Some((format!("IBAN for {country}"), 0.9));
+    }
+    let digits = digits_of(text)?;
+    if is_isbn_13(&digits) {
+        return Some(("ISBN-13".to_string(), 0.8));
+    }
+    if is_isbn_10(&digits) {
+        return Some(("ISBN-10".to_string(), 0.5));
+    }
+    if !passes_luhn(&digits) {
+        return None;
+    }
+    match (card_issuer(&digits), digits.len()) {
+        (Some(issuer), 13..=19) => Some((format!("{issuer} credit card number"), 0.6)),
+        (None, 15) => Some(("IMEI".to_string(), 0.4)),
+        _ => None,
+    }
+}
+
+/// The digits of a number written with optional spaces or dashes between groups, with an
+/// ISBN-10's check digit of X as 10, None if anything else is in it
+fn digits_of(text: &str) -> Option<Vec<u32>> {
+    if text.is_empty() || text.starts_with(['-', ' ']) || text.ends_with(['-', ' ']) {
+        return None;
+    }
+    let compact: Vec<char> = text.chars().filter(|c| !matches!(c, ' ' | '-')).collect();
+    compact
+        .iter()
+        .enumerate()
+        .map(|(i, &c)| match c {
+            'X' | 'x' if i == compact.len() - 1 && compact.len() == 10 => Some(10),
+            _ => c.to_digit(10),
+        })
+        .collect()
+}
+
+/// Whether the digits pass the Luhn check, which doubles every second digit from the right
+fn passes_luhn(digits: &[u32]) -> bool {
+    if digits.contains(&10) {
+        return false;
+    }
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &digit)| match (i % 2, digit * 2) {
+            (1, doubled) if doubled > 9 => doubled - 9,
+            (1, doubled) => doubled,
+            _ => digit,
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+/// The card network a number's prefix and length belong to
+fn card_issuer(digits: &[u32]) -> Option<&'static str> {
+    let prefix = |length: usize| {
+        digits
+            .iter()
+            .take(length)
+            .fold(0, |number, digit| number * 10 + digit)
+    };
+    match digits.len() {
+        15 if [34, 37].contains(&prefix(2)) => Some("American Express"),
+        13 | 16 | 19 if digits[0] == 4 => Some("Visa"),
+        16 if (51..=55).contains(&prefix(2)) || (2221..=2720).contains(&prefix(4)) => {
+            Some("Mastercard")
+        }
+        16..=19 if prefix(4) == 6011 || prefix(2) == 65 => Some("Discover"),
+        _ => None,
+    }
+}
+
+/// Whether the digits are an ISBN-13, which starts 978 or 979 and weights digits 1 and 3
+fn is_isbn_13(digits: &[u32]) -> bool {
+    digits.len() == 13
+        && digits[..2] == [9, 7]
+        && [8, 9].contains(&digits[2])
+        && !digits.contains(&10)
+        && digits
+            .iter()
+            .enumerate()
+            .map(|(i, digit)| if i % 2 == 0 { *digit } else { digit * 3 })
+            .sum::<u32>()
+            .is_multiple_of(10)
+}
+
+/// Whether the digits are an ISBN-10, whose digits weighted 10 down to 1 sum to a multiple
+/// of 11
+fn is_isbn_10(digits: &[u32]) -> bool {
+    digits.len() == 10
+        && !digits[..9].contains(&10)
+        && digits
+            .iter()
+            .zip((1..=10).rev())
+            .map(|(digit, weight)| digit * weight)
+            .sum::<u32>()
+            .is_multiple_of(11)
+}
+
+/// The country of an IBAN, None if the text isn't one with valid check digits
+/// An IBAN is a country code, two check digits and up to 30 letters and digits, often
+/// written in groups of four. Moving the first four characters to the end and reading
+/// letters as 10 to 35 gives a number which is 1 modulo 97.
+fn iban_country(text: &str) -> Option<&'static str> {
+    let compact: String = text.chars().filter(|c| *c != ' ').collect();
+    if !(15..=34).contains(&compact.len())
+        || !compact.chars().all(|c| c.is_ascii_alphanumeric())
+        || !compact[..2].chars().all(|c| c.is_ascii_uppercase())
+        || !compact[2..4].chars().all(|c| c.is_ascii_digit())
+    {
+        return None;
+    }
+    let country = IBAN_COUNTRIES
+        .iter()
+        .find(|(code, length, _)| *code == &compact[..2] && *length == compact.len())?
+        .2;
+    let remainder =
+        compact[4..]
+            .chars()
+            .chain(compact[..4].chars())
+            .try_fold(0u32, |remainder, c| {
+                let value = c.to_digit(36)?;
+                let shift = if value > 9 { 100 } else { 10 };
+                Some((remainder * shift + value) % 97)
+            })?;
+    (remainder == 1).then_some(country)
+}
+
+/// Countries using IBANs, with the length of theirs
+const IBAN_COUNTRIES: [(&str, usize, &str); 36] = [
+    ("AD", 24, "Andorra"),
+    ("AT", 20, "Austria"),
+    ("BE", 16, "Belgium"),
+    ("BG", 22, "Bulgaria"),
+    ("BR", 29, "Brazil"),
+    ("CH", 21, "Switzerland"),
+    ("CY", 28, "Cyprus"),
+    ("CZ", 24, "Czechia"),
+    ("DE", 22, "Germany"),
+    ("DK", 18, "Denmark"),
+    ("EE", 20, "Estonia"),
+    ("ES", 24, "Spain"),
+    ("FI", 18, "Finland"),
+    ("FR", 27, "France"),
+    ("GB", 22, "the United Kingdom"),
+    ("GR", 27, "Greece"),
+    ("HR", 21, "Croatia"),
+    ("HU", 28, "Hungary"),
+    ("IE", 22, "Ireland"),
+    ("IS", 26, "Iceland"),
+    ("IT", 27, "Italy"),
+    ("LI", 21, "Liechtenstein"),
+    ("LT", 20, "Lithuania"),
+    ("LU", 20, "Luxembourg"),
+    ("LV", 21, "Latvia"),
+    ("MC", 27, "Monaco"),
+    ("MT", 31, "Malta"),
+    ("NL", 18, "the Netherlands"),
+    ("NO", 15, "Norway"),
+    ("PL", 28, "Poland"),
+    ("PT", 25, "Portugal"),
+    ("RO", 24, "Romania"),
+    ("SA", 24, "Saudi Arabia"),
+    ("SE", 24, "Sweden"),
+    ("SI", 19, "Slovenia"),
+    ("SK", 24, "Slovakia"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn description(text: &str) -> Option<String> {
+        let result = Checker::<ChecksumChecker>::new().check(text);
+        result.is_identified.then_some(result.description)
+    }
+
+    #[test]
+    fn test_credit_cards_pass_luhn() {
+        assert_eq!(
+            description("4111 1111 1111 1111").unwrap(),
+            "Visa credit card number"
+        );
+        assert_eq!(
+            description("5555-5555-5555-4444").unwrap(),
+            "Mastercard credit card number"
+        );
+        assert_eq!(
+            description("378282246310005").unwrap(),
+            "American Express credit card number"
+        );
+        assert!(description("4111 1111 1111 1112").is_none());
+    }
+
+    #[test]
+    fn test_imei() {
+        assert_eq!(description("490154203237518").unwrap(), "IMEI");
+        assert!(description("490154203237519").is_none());
+    }
+
+    #[test]
+    fn test_isbn() {
+        assert_eq!(description("978-0-306-40615-7").unwrap(), "ISBN-13");
+        assert_eq!(description("0-306-40615-2").unwrap(), "ISBN-10");
+        assert_eq!(description("080442957X").unwrap(), "ISBN-10");
+        assert!(description("978-0-306-40615-8").is_none());
+    }
+
+    #[test]
+    fn test_iban() {
+        assert_eq!(
+            description("GB82 WEST 1234 5698 7654 32").unwrap(),
+            "IBAN for the United Kingdom"
+        );
+        assert_eq!(
+            description("DE89370400440532013000").unwrap(),
+            "IBAN for Germany"
+        );
+        assert!(description("GB82 WEST 1234 5698 7654 33").is_none());
+    }
+
+    #[test]
+    fn test_other_text_is_not_identified() {
+        assert!(description("hello world").is_none());
+        assert!(description("12345").is_none());
+        assert!(description("").is_none());
+        assert!(description("-4111111111111111").is_none());
+    }
+}
@@ -0,0 +1,45 @@
+//! Plaintexts the user confirmed with the human checker
+//! The human checker only asks when it isn't sure, so a plaintext the user said yes to once
+//! is accepted straight away whenever it is decoded again, in this run or a later one,
+//! without running the checkers or asking again. This mirrors how rejected plaintexts are
+//! remembered by the denylist.
+
+use dashmap::DashSet;
+use once_cell::sync::Lazy;
+
+use crate::storage::backend::storage;
+
+/// Plaintexts confirmed with the human checker, in earlier runs and this one
+/// Read from storage the first time a text is checked, once it has been set up.
+static CONFIRMED: Lazy<DashSet<String>> =
+    Lazy::new(|| match storage().read_confirmed_plaintexts() {
+        Ok(plaintexts) => plaintexts.into_iter().collect(),
+        Err(e) => {
+            log::debug!("Could not read the confirmed plaintexts: {}", e);
+            DashSet::new()
+        }
+    });
+
+/// Returns true if the user confirmed this plaintext with the human checker
+pub fn was_confirmed(text: &str) -> bool {
+    CONFIRMED.contains(text)
+}
+
+/// Remembers that the user confirmed this plaintext, so it is accepted for the rest of the run
+/// The human checker also saves it in the database for later runs.
+pub fn remember_confirmation(text: &str) {
+    CONFIRMED.insert(text.to_string());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn confirmed_plaintexts_are_remembered() {
+        let text = "the plaintext a human said was right";
+        assert!(!was_confirmed(text));
+        remember_confirmation(text);
+        assert!(was_confirmed(text));
+    }
+}
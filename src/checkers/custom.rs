@@ -0,0 +1,185 @@
+//! Checkers registered at runtime by users of the library
+//! The checkers ciphey ships with are a closed set, so anything else a user knows about
+//! their plaintext, such as a format only they use or a service which can confirm it, is
+//! told to ciphey by registering a checker here. Athena runs registered checkers alongside
+//! its own and combines their verdicts with the rest.
+//!
+//! ```
+//! use ciphey::checkers::checker_result::CheckResult;
+//! use ciphey::checkers::checker_type::Check;
+//! use ciphey::checkers::register_custom_checker;
+//! use gibberish_or_not::Sensitivity;
+//!
+//! /// Recognises the ticket numbers of an internal bug tracker, such as `TICKET-1234`
+//! struct TicketChecker {
+//!     sensitivity: Sensitivity,
+//! }
+//!
+//! impl Check for TicketChecker {
+//!     fn new() -> Self {
+//!         TicketChecker {
+//!             sensitivity: Sensitivity::Medium,
+//!         }
+//!     }
+//!
+//!     fn check(&self, text: &str) -> CheckResult {
+//!         let is_ticket = text
+//!             .strip_prefix("TICKET-")
+//!             .is_some_and(|number| !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()));
+//!         CheckResult {
+//!             is_identified: is_ticket,
+//!             text: text.to_string(),
+//!             description: "Ticket number".to_string(),
+//!             checker_name: "Ticket Checker",
+//!             checker_description: "Checks if the text is a ticket number",
+//!             link: "",
+//!             confidence: if is_ticket { 0.9 } else { 0.0 },
+//!             sensitivity: self.sensitivity,
+//!             scores: None,
+//!         }
+//!     }
+//!
+//!     fn with_sensitivity(mut self, sensitivity: Sensitivity) -> Self {
+//!         self.sensitivity = sensitivity;
+//!         self
+//!     }
+//!
+//!     fn get_sensitivity(&self) -> Sensitivity {
+//!         self.sensitivity
+//!     }
+//! }
+//!
+//! register_custom_checker(Box::new(TicketChecker::new()));
+//! ```
+
+use crate::checkers::checker_result::CheckResult;
+use crate::checkers::checker_type::Check;
+use crate::config::get_config;
+use log::trace;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::{Mutex, PoisonError, RwLock};
+
+/// A checker registered with [`register_custom_checker`]
+pub type CustomChecker = Box<dyn Check + Send + Sync>;
+
+/// The registered checkers, in the order they were registered
+static CUSTOM_CHECKERS: Lazy<RwLock<Vec<CustomChecker>>> = Lazy::new(|| RwLock::new(Vec::new()));
+
+/// The names and descriptions registered checkers have put on their results
+/// Results read back from the cache are matched to their checker by these.
+static CUSTOM_CHECKER_NAMES: Lazy<Mutex<HashMap<&'static str, &'static str>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Adds a checker for Athena to run on every decoded text, after its own checkers
+///
+/// The checker is run with the sensitivity it was created with, and it can be toggled off
+/// like any other checker by the name it puts on its results. It shouldn't register or
+/// clear checkers from inside `check`, as the registered checkers are locked while they run.
+pub fn register_custom_checker(checker: CustomChecker) {
+    CUSTOM_CHECKERS
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .push(checker);
+}
+
+/// Removes every registered checker
+pub fn clear_custom_checkers() {
+    CUSTOM_CHECKERS
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clear();
+}
+
+/// Runs every registered checker that isn't toggled off on the text
+pub fn custom_checker_results(text: &str) -> Vec<CheckResult> {
+    let checkers = CUSTOM_CHECKERS
+        .read()
+        .unwrap_or_else(PoisonError::into_inner);
+    if checkers.is_empty() {
+        return Vec::new();
+    }
+    let config = get_config();
+    let mut names = CUSTOM_CHECKER_NAMES
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    checkers
+        .iter()
+        .map(|checker| checker.check(text))
+        .filter(|result| {
+            names.insert(result.checker_name, result.checker_description);
+            let enabled = config.is_checker_enabled(result.checker_name);
+            if !enabled {
+                trace!("Skipping {} as it is toggled off", result.checker_name);
+            }
+            enabled
+        })
+        .collect()
+}
+
+/// The name and description of a registered checker which has given a result under this
+/// name, None if none has
+pub fn custom_checker_info(name: &str) -> Option<(&'static str, &'static str)> {
+    CUSTOM_CHECKER_NAMES
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_key_value(name)
+        .map(|(name, description)| (*name, *description))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checkers::athena::Athena;
+    use crate::checkers::checker_type::Checker;
+    use gibberish_or_not::Sensitivity;
+
+    /// Accepts text starting with a marker no other test decodes to
+    struct MarkerChecker;
+
+    impl Check for MarkerChecker {
+        fn new() -> Self {
+            MarkerChecker
+        }
+
+        fn check(&self, text: &str) -> CheckResult {
+            CheckResult {
+                is_identified: text.starts_with("Q7ZX-"),
+                text: text.to_string(),
+                description: "Custom marker".to_string(),
+                checker_name: "Marker Checker",
+                checker_description: "Checks for the custom marker",
+                link: "",
+                confidence: 0.9,
+                sensitivity: Sensitivity::Medium,
+                scores: None,
+            }
+        }
+
+        fn with_sensitivity(self, _sensitivity: Sensitivity) -> Self {
+            self
+        }
+
+        fn get_sensitivity(&self) -> Sensitivity {
+            Sensitivity::Medium
+        }
+    }
+
+    #[test]
+    fn registered_checkers_are_run_by_athena() {
+        let athena = Checker::<Athena>::new();
+        assert!(!athena.check("Q7ZX-0042-VKJW").is_identified);
+
+        register_custom_checker(Box::new(MarkerChecker::new()));
+        let result = athena.check("Q7ZX-0042-VKJW");
+        assert!(result.is_identified);
+        assert_eq!(result.checker_name, "Marker Checker");
+        assert_eq!(
+            custom_checker_info("Marker Checker"),
+            Some(("Marker Checker", "Checks for the custom marker"))
+        );
+
+        clear_custom_checkers();
+        assert!(!athena.check("Q7ZX-0042-VKJW").is_identified);
+    }
+}
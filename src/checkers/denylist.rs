@@ -0,0 +1,120 @@
+//! Plaintexts that should never be accepted, no matter what a checker says.
+//! Several decoders can "succeed" with degenerate output such as an empty string,
+//! a single letter or one character repeated over and over, and some checkers will
+//! happily identify those. We reject them centrally instead of in every decoder.
+//! Users can extend the list with `plaintext_denylist` in their config.
+//! Plaintexts the user rejected with the human checker are never accepted again either,
+//! so they aren't asked about the same false positive on every run.
+
+use dashmap::DashSet;
+use once_cell::sync::Lazy;
+
+use crate::config::get_config;
+use crate::storage::backend::storage;
+
+/// Plaintexts rejected with the human checker, in earlier runs and this one
+/// Read from storage the first time a text is checked, once it has been set up.
+static REJECTED: Lazy<DashSet<String>> = Lazy::new(|| match storage().read_rejected_plaintexts() {
+    Ok(plaintexts) => plaintexts.into_iter().collect(),
+    Err(e) => {
+        log::debug!("Could not read the rejected plaintexts: {}", e);
+        DashSet::new()
+    }
+});
+
+/// Returns true if the text is degenerate, on the configured denylist or was rejected
+///
+/// Degenerate text is empty, a single character, or one character repeated
+/// (like `aaaa`). Whitespace is ignored. Configured entries are matched
+/// case-insensitively against the trimmed text. Rejected plaintexts only count
+/// without `--ignore-rejections`.
+pub fn is_denied(text: &str) -> bool {
+    is_degenerate(text)
+        || is_configured(text, &get_config().plaintext_denylist)
+        || was_rejected(text)
+}
+
+/// Returns true if the user rejected this plaintext with the human checker
+pub fn was_rejected(text: &str) -> bool {
+    !get_config().ignore_rejections && REJECTED.contains(text)
+}
+
+/// Remembers that the user rejected this plaintext, so it is denied for the rest of the run
+/// The human checker also saves it in the database for later runs.
+pub fn remember_rejection(text: &str) {
+    REJECTED.insert(text.to_string());
+}
+
+/// Returns true if the plaintext should be rejected as the output of decoding `input`
+///
+/// On top of [`is_denied`], this rejects plaintext that is just the input again.
+pub fn is_denied_for_input(plaintext: &str, input: &str) -> bool {
+    is_denied(plaintext) || plaintext.trim() == input.trim()
+}
+
+/// Empty, a single character, or one character repeated, ignoring whitespace
+fn is_degenerate(text: &str) -> bool {
+    let mut chars = text.chars().filter(|c| !c.is_whitespace());
+    match chars.next() {
+        None => true,
+        Some(first) => chars.all(|c| c == first),
+    }
+}
+
+/// Checks the text against the user's denylist
+fn is_configured(text: &str, denylist: &[String]) -> bool {
+    let text = text.trim();
+    denylist
+        .iter()
+        .any(|denied| denied.trim().eq_ignore_ascii_case(text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_and_whitespace_are_denied() {
+        assert!(is_denied(""));
+        assert!(is_denied("   \n\t"));
+    }
+
+    #[test]
+    fn single_characters_are_denied() {
+        assert!(is_denied("a"));
+        assert!(is_denied(" Z "));
+    }
+
+    #[test]
+    fn repeated_characters_are_denied() {
+        assert!(is_denied("aaaaaaaa"));
+        assert!(is_denied("0000 0000"));
+    }
+
+    #[test]
+    fn real_plaintext_is_allowed() {
+        assert!(!is_denied("hello world"));
+        assert!(!is_denied("ab"));
+    }
+
+    #[test]
+    fn input_echo_is_denied() {
+        assert!(is_denied_for_input("hello world", " hello world "));
+        assert!(!is_denied_for_input("hello world", "aGVsbG8gd29ybGQ="));
+    }
+
+    #[test]
+    fn rejected_plaintexts_are_denied() {
+        let text = "the plaintext a human said was wrong";
+        assert!(!is_denied(text));
+        remember_rejection(text);
+        assert!(is_denied(text));
+    }
+
+    #[test]
+    fn configured_entries_match_case_insensitively() {
+        let denylist = vec!["Lorem Ipsum".to_string()];
+        assert!(is_configured(" lorem ipsum ", &denylist));
+        assert!(!is_configured("lorem ipsum dolor", &denylist));
+    }
+}
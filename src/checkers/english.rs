@@ -11,8 +11,6 @@ pub struct EnglishChecker;
 /// given an input, check every item in the array and return true if any of them match
 impl Check for Checker<EnglishChecker> {
     fn new() -> Self {
-        let config = get_config();
-
         Checker {
             name: "English Checker",
             description: "Uses gibberish detection to check if text is meaningful English",
@@ -49,6 +47,13 @@ impl Check for Checker<EnglishChecker> {
             checker_description: self.description,
             description: "Words".to_string(),
             link: self.link,
+            confidence: 0.0,
+            sensitivity: if is_enhanced {
+                Sensitivity::High
+            } else {
+                self.sensitivity
+            },
+            scores: None,
         };
 
         // Handle edge case of very short strings after normalization
@@ -57,6 +62,10 @@ impl Check for Checker<EnglishChecker> {
             result.is_identified = false;
         }
 
+        if result.is_identified {
+            result.confidence = english_confidence(&text);
+        }
+
         result
     }
 
@@ -70,6 +79,19 @@ impl Check for Checker<EnglishChecker> {
     }
 }
 
+/// Grades how English the text is by the strictest sensitivity it still passes
+/// Text that passes Low sensitivity, the strictest, is far more likely to be English than text
+/// which only passes High, the most lenient
+fn english_confidence(text: &str) -> f32 {
+    if !is_gibberish(text, Sensitivity::Low) {
+        0.9
+    } else if !is_gibberish(text, Sensitivity::Medium) {
+        0.7
+    } else {
+        0.5
+    }
+}
+
 /// Strings look funny, they might have commas, be uppercase etc
 /// This normalises the string so English checker can work on it
 /// In particular it:
@@ -188,4 +210,16 @@ mod tests {
         let high_checker = Checker::<EnglishChecker>::new().with_sensitivity(Sensitivity::High);
         assert!(high_checker.check(text).is_identified);
     }
+
+    #[test]
+    fn test_weak_english_is_less_confident_than_strong_english() {
+        let checker = Checker::<EnglishChecker>::new().with_sensitivity(Sensitivity::High);
+        let strong = checker.check("The quick brown fox jumps over the lazy dog");
+        // Only one real word, so it passes High and Medium sensitivity but not Low
+        let weak = checker.check("Rcl maocr otmwi lit dnoen oehc 13 iron seah.");
+        assert!(strong.is_identified && weak.is_identified);
+        assert!(weak.confidence < strong.confidence);
+        assert!((strong.confidence - 0.9).abs() < f32::EPSILON);
+        assert!((weak.confidence - 0.7).abs() < f32::EPSILON);
+    }
 }
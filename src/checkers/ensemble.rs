@@ -0,0 +1,133 @@
+//! Combines the results of several checkers into a single ranked verdict.
+//! Athena used to trust whichever checker happened to run first. When more than one
+//! checker identifies the same text we now rank them by confidence instead, and treat
+//! each agreeing checker as independent evidence that the text is plaintext.
+
+use crate::checkers::checker_result::CheckResult;
+use log::trace;
+
+/// Ranks the identified results by confidence and merges them into one verdict
+///
+/// The most confident checker leads the verdict and gives it its name, description and link.
+/// The confidence of the verdict is the chance that at least one checker is right,
+/// `1 - (1 - a)(1 - b)...`, so agreeing checkers always raise the confidence.
+///
+/// The text is taken from the first result passed in, as some checkers such as the
+/// English checker normalise the text they return.
+///
+/// Returns `None` if no checker identified the text.
+pub fn rank_verdicts(results: Vec<CheckResult>) -> Option<CheckResult> {
+    let text = results.first()?.text.clone();
+    let mut identified: Vec<CheckResult> = results
+        .into_iter()
+        .filter(|res| res.is_identified)
+        .collect();
+    if identified.is_empty() {
+        return None;
+    }
+
+    identified.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+    let confidence = 1.0
+        - identified
+            .iter()
+            .map(|res| 1.0 - res.confidence.clamp(0.0, 1.0))
+            .product::<f32>();
+
+    let supporting: Vec<String> = identified[1..]
+        .iter()
+        .map(|res| format!("{} ({})", res.checker_name, res.description))
+        .collect();
+
+    let mut verdict = identified.swap_remove(0);
+    if !supporting.is_empty() {
+        verdict.description = format!(
+            "{}, also identified by {}",
+            verdict.description,
+            supporting.join(", ")
+        );
+    }
+    verdict.text = text;
+    verdict.confidence = confidence;
+    Some(verdict)
+}
+
+/// Whether a verdict is confident enough to be the plaintext, given `--min-confidence`
+/// Its default of 0 accepts any text a checker identified.
+pub fn meets_min_confidence(verdict: &CheckResult, min_confidence: f32) -> bool {
+    if verdict.confidence < min_confidence {
+        trace!(
+            "Rejecting {} verdict with confidence {:.2}, below the minimum of {:.2}",
+            verdict.checker_name,
+            verdict.confidence,
+            min_confidence
+        );
+        return false;
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(checker_name: &'static str, is_identified: bool, confidence: f32) -> CheckResult {
+        CheckResult {
+            is_identified,
+            text: "Hello World".to_string(),
+            description: format!("{} description", checker_name),
+            checker_name,
+            checker_description: "",
+            link: "",
+            confidence,
+            sensitivity: gibberish_or_not::Sensitivity::Medium,
+            scores: None,
+        }
+    }
+
+    #[test]
+    fn nothing_identified_returns_none() {
+        let verdict = rank_verdicts(vec![result("a", false, 0.0), result("b", false, 0.0)]);
+        assert!(verdict.is_none());
+    }
+
+    #[test]
+    fn single_identification_is_passed_through() {
+        let verdict = rank_verdicts(vec![result("a", false, 0.0), result("b", true, 0.7)]).unwrap();
+        assert_eq!(verdict.checker_name, "b");
+        assert_eq!(verdict.description, "b description");
+        assert!((verdict.confidence - 0.7).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn most_confident_checker_leads() {
+        let verdict = rank_verdicts(vec![result("a", true, 0.3), result("b", true, 0.9)]).unwrap();
+        assert_eq!(verdict.checker_name, "b");
+        assert_eq!(
+            verdict.description,
+            "b description, also identified by a (a description)"
+        );
+    }
+
+    #[test]
+    fn agreeing_checkers_raise_confidence() {
+        let verdict = rank_verdicts(vec![result("a", true, 0.5), result("b", true, 0.5)]).unwrap();
+        assert!((verdict.confidence - 0.75).abs() < 1e-6);
+    }
+
+    #[test]
+    fn verdicts_below_the_minimum_confidence_are_rejected() {
+        let verdict = rank_verdicts(vec![result("a", true, 0.5), result("b", true, 0.5)]).unwrap();
+        assert!(meets_min_confidence(&verdict, 0.0));
+        assert!(meets_min_confidence(&verdict, 0.75));
+        assert!(!meets_min_confidence(&verdict, 0.8));
+    }
+
+    #[test]
+    fn text_comes_from_first_result() {
+        let mut normalised = result("english", true, 0.9);
+        normalised.text = "hello world".to_string();
+        let verdict = rank_verdicts(vec![result("lemmeknow", true, 0.4), normalised]).unwrap();
+        assert_eq!(verdict.checker_name, "english");
+        assert_eq!(verdict.text, "Hello World");
+    }
+}
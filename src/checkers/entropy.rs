@@ -0,0 +1,199 @@
+use crate::checkers::checker_result::CheckResult;
+use crate::decoders::frequency_analysis::{chi_squared, letter_counts, letter_indices};
+use gibberish_or_not::Sensitivity;
+use lemmeknow::Identifier;
+
+use crate::checkers::checker_type::{Check, Checker};
+
+/// Entropies between these, in bits per byte, are usual for prose
+const PROSE_ENTROPY: std::ops::RangeInclusive<f32> = 2.5..=4.5;
+
+/// Text with an entropy this high looks like encoded or random data, not prose
+const DATA_ENTROPY: f32 = 6.5;
+
+/// How much of the likelihood depends on the letters looking like English, the rest
+/// depends on the entropy looking like prose
+const ENGLISH_WEIGHT: f32 = 0.7;
+
+/// Scores how much like plaintext a text is without deciding whether it is
+/// Shannon entropy tells prose from encoded or random data, the printable ratio tells text
+/// from binary, and chi-squared against English tells English from shifted or encoded
+/// letters. The scores are put on the CheckResult so the search can rank decodes no
+/// checker identified, and show the most likely one when it gives up.
+pub struct EntropyChecker;
+
+/// How much like plaintext a text's bytes are
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextScores {
+    /// The Shannon entropy of the bytes in bits per byte, from 0 to 8
+    pub entropy: f32,
+    /// The fraction of the bytes which are printable ASCII or whitespace, from 0 to 1
+    pub printable_ratio: f32,
+    /// Chi-squared of the letter frequencies against English, lower is more English-like
+    /// Infinite when there are no letters.
+    pub chi_squared: f32,
+    /// The fraction of the bytes which are ASCII letters, from 0 to 1
+    pub letter_ratio: f32,
+    /// How many bytes were scored
+    pub length: usize,
+}
+
+impl TextScores {
+    /// Scores the bytes of a text, or a decoded value which isn't text
+    pub fn of(bytes: &[u8]) -> Self {
+        if bytes.is_empty() {
+            return TextScores {
+                entropy: 0.0,
+                printable_ratio: 0.0,
+                chi_squared: f32::INFINITY,
+                letter_ratio: 0.0,
+                length: 0,
+            };
+        }
+        let mut counts = [0usize; 256];
+        for &byte in bytes {
+            counts[byte as usize] += 1;
+        }
+        let total = bytes.len() as f64;
+        let entropy: f64 = counts
+            .iter()
+            .filter(|&&count| count > 0)
+            .map(|&count| {
+                let probability = count as f64 / total;
+                -probability * probability.log2()
+            })
+            .sum();
+        let printable = bytes
+            .iter()
+            .filter(|byte| byte.is_ascii_graphic() || matches!(byte, b' ' | b'\t' | b'\n' | b'\r'))
+            .count();
+        let letters = letter_indices(&String::from_utf8_lossy(bytes));
+        TextScores {
+            entropy: entropy as f32,
+            printable_ratio: (printable as f64 / total) as f32,
+            chi_squared: if letters.is_empty() {
+                f32::INFINITY
+            } else {
+                chi_squared(&letter_counts(&letters)) as f32
+            },
+            letter_ratio: (letters.len() as f64 / total) as f32,
+            length: bytes.len(),
+        }
+    }
+
+    /// How likely the text is to be plaintext going by these scores, from 0 to 1
+    /// English prose scores around 0.7, Caesar-shifted English and hex around 0.3,
+    /// Base64 around 0.2 and random bytes close to 0.
+    pub fn plaintext_likelihood(&self) -> f32 {
+        let entropy_score = if self.entropy > *PROSE_ENTROPY.end() {
+            ((DATA_ENTROPY - self.entropy) / (DATA_ENTROPY - PROSE_ENTROPY.end())).max(0.0)
+        } else {
+            (self.entropy / PROSE_ENTROPY.start()).min(1.0)
+        };
+        let english_score = if self.chi_squared.is_finite() && self.letter_ratio > 0.0 {
+            // Chi-squared grows with the number of letters, so compare it per letter
+            let letters = self.letter_ratio * self.length as f32;
+            (-self.chi_squared / letters.max(1.0) / 2.0).exp()
+        } else {
+            0.0
+        };
+        self.printable_ratio
+            * ((1.0 - ENGLISH_WEIGHT) * entropy_score
+                + ENGLISH_WEIGHT * english_score * self.letter_ratio)
+    }
+}
+
+impl Check for Checker<EntropyChecker> {
+    fn new() -> Self {
+        Checker {
+            name: "Entropy Checker",
+            description: "Scores how much like plaintext text is by its entropy, printable characters and letter frequencies",
+            link: "https://en.wikipedia.org/wiki/Entropy_(information_theory)",
+            tags: vec!["entropy", "statistics", "chi-squared"],
+            expected_runtime: 0.01,
+            popularity: 1.0,
+            lemmeknow_config: Identifier::default(),
+            sensitivity: Sensitivity::Medium,
+            enhanced_detector: None,
+            _phantom: std::marker::PhantomData,
+        }
+    }
+
+    fn check(&self, text: &str) -> CheckResult {
+        let scores = TextScores::of(text.as_bytes());
+        let mut result = CheckResult::new(self);
+        // The scores only rank candidates, they are never enough to call text plaintext
+        result.text = text.to_string();
+        result.description = format!(
+            "Entropy of {:.2} bits per byte, {:.0}% printable, chi-squared of {:.1} against English",
+            scores.entropy,
+            scores.printable_ratio * 100.0,
+            scores.chi_squared
+        );
+        result.confidence = scores.plaintext_likelihood();
+        result.scores = Some(scores);
+        result
+    }
+
+    fn with_sensitivity(mut self, sensitivity: Sensitivity) -> Self {
+        self.sensitivity = sensitivity;
+        self
+    }
+
+    fn get_sensitivity(&self) -> Sensitivity {
+        self.sensitivity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn likelihood(text: &[u8]) -> f32 {
+        TextScores::of(text).plaintext_likelihood()
+    }
+
+    #[test]
+    fn english_is_more_likely_than_encodings_of_it() {
+        let english = likelihood(
+            b"The quick brown fox jumps over the lazy dog while the farmers watch the harvest",
+        );
+        let rot13 = likelihood(
+            b"Gur dhvpx oebja sbk whzcf bire gur ynml qbt juvyr gur snezref jngpu gur uneirfg",
+        );
+        let base64 = likelihood(b"VGhlIHF1aWNrIGJyb3duIGZveCBqdW1wcyBvdmVyIHRoZSBsYXp5IGRvZw==");
+        let hex = likelihood(b"54686520717569636b2062726f776e20666f78206a756d7073");
+        assert!(english > 0.6, "{english}");
+        assert!(
+            english > rot13 && rot13 > base64,
+            "{english} {rot13} {base64}"
+        );
+        assert!(english > hex, "{english} {hex}");
+    }
+
+    #[test]
+    fn random_bytes_are_unlikely() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        let scores = TextScores::of(&bytes);
+        assert!((scores.entropy - 8.0).abs() < 0.001);
+        assert!(scores.plaintext_likelihood() < 0.2);
+    }
+
+    #[test]
+    fn checker_scores_without_identifying() {
+        let result = Checker::<EntropyChecker>::new().check("hello world");
+        assert!(!result.is_identified);
+        assert!(result.scores.is_some());
+        assert_eq!(
+            result.confidence,
+            result.scores.unwrap().plaintext_likelihood()
+        );
+    }
+
+    #[test]
+    fn empty_text_scores_nothing() {
+        let scores = TextScores::of(b"");
+        assert_eq!(scores.length, 0);
+        assert_eq!(scores.plaintext_likelihood(), 0.0);
+    }
+}
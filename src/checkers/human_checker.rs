@@ -1,59 +1,369 @@
 use crate::checkers::checker_result::CheckResult;
-use crate::cli_pretty_printing::human_checker_check;
-use crate::config::get_config;
-use crate::storage::database;
-use crate::{cli_pretty_printing, timer};
+use crate::checkers::{confirmed, denylist, webhook};
+use crate::cli_pretty_printing::{
+    human_checker_check, human_checker_timed_out, warning_webhook_failed,
+};
+use crate::config::{get_config, HumanCheckerDefault};
+use crate::events::{self, SearchEvent};
+use crate::storage::backend::storage;
+use crate::{cli_pretty_printing, CancellationToken, DecoderResult};
+use crossbeam::channel::{unbounded, Receiver as LineReceiver, RecvTimeoutError, Sender};
 use dashmap::DashSet;
-use std::sync::OnceLock;
-use text_io::read;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::Receiver;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
 
+/// Prompts the user has already answered, so we never ask about the same plaintext twice
 static SEEN_PROMPTS: OnceLock<DashSet<String>> = OnceLock::new();
 
+/// Where the answers come from when they aren't read from stdin, such as the terminal UI
+static INLINE_ANSWERS: Mutex<Option<Receiver<bool>>> = Mutex::new(None);
+
+/// The questions of the running search which haven't been handed back to it
+static QUESTIONS: Mutex<Vec<Question>> = Mutex::new(Vec::new());
+
+/// How many questions there are, so searches which asked none never lock them
+static QUESTION_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// Where questions are sent to be asked one at a time, by the text they are about
+static ASKER: OnceLock<Sender<String>> = OnceLock::new();
+
+/// The lines typed on stdin, read on their own thread so a question can stop waiting
+static TERMINAL_LINES: OnceLock<LineReceiver<String>> = OnceLock::new();
+
+/// A question for the user and what came of it
+struct Question {
+    /// What the checkers found
+    check: CheckResult,
+    /// The decode of the text, once the search has made it
+    decode: Option<DecoderResult>,
+    /// Whether the text is the plaintext, None until the question is answered
+    accepted: Option<bool>,
+}
+
+/// How a question ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Answer {
+    /// The user said yes or no
+    Given(bool),
+    /// Nobody answered within `human_checker_timeout`
+    TimedOut,
+    /// Nothing can answer any more, such as stdin being closed
+    Closed,
+}
+
+/// Takes the human checker's answers from `answers` instead of asking on the terminal
+/// Each question is published as a `HumanCheckerPrompt` event and the next answer received
+/// is its answer, so whatever shows the events can ask the user itself. The human checker
+/// then runs in `api_mode` too, which otherwise turns it off. Once `answers` has no sender
+/// every question is rejected without remembering the rejection, like a closed stdin.
+pub fn answer_prompts_with(answers: Receiver<bool>) {
+    *INLINE_ANSWERS
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner) = Some(answers);
+}
+
+/// Gets the set of prompts already shown to the user
 fn get_seen_prompts() -> &'static DashSet<String> {
     SEEN_PROMPTS.get_or_init(DashSet::new)
 }
 
+/// Locks the questions, recovering them if a thread panicked while holding them
+fn lock_questions() -> std::sync::MutexGuard<'static, Vec<Question>> {
+    QUESTIONS
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
 /// The Human Checker asks humans if the expected plaintext is real plaintext
 /// We can use all the automated checkers in the world, but sometimes they get false positives
 /// Humans have the last say.
+///
+/// The question is queued and asked on another thread, so the search carries on with
+/// other decodes while it waits. Until the user answers the text isn't the plaintext;
+/// once they say yes it is handed to the search by [`take_accepted`].
 /// TODO: Add a way to specify a list of checkers to use in the library. This checker is not library friendly!
 // compile this if we are not running tests
 pub fn human_checker(input: &CheckResult) -> bool {
-    timer::pause();
     // wait instead of get so it waits for config being set
     let config = get_config();
+    let answers_inline = INLINE_ANSWERS
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .is_some();
     // We still call human checker, just if config is false we return True
-    if !config.human_checker_on || config.api_mode {
-        timer::resume();
+    // A webhook doesn't need anyone at a terminal, so it asks in api_mode too
+    let asks_webhook = !config.human_checker_webhook.is_empty();
+    if !config.human_checker_on || (config.api_mode && !answers_inline && !asks_webhook) {
         return true;
     }
 
-    // Check if we've already prompted for this text
+    // Check if we've already prompted for this text, its answer comes with that question
     let prompt_key = format!("{}{}", input.description, input.text);
     if !get_seen_prompts().insert(prompt_key) {
-        return true; // Return true to allow the search to continue
-    }
-    human_checker_check(&input.description, &input.text);
-
-    let reply: String = read!("{}\n");
-    cli_pretty_printing::success(&format!("DEBUG: Human checker received reply: '{}'", reply));
-    let result = reply.to_ascii_lowercase().starts_with('y');
-    timer::resume();
-
-    cli_pretty_printing::success(&format!("DEBUG: Human checker returning: {}", result));
-
-    if !result {
-        let fd_result = database::insert_human_rejection(uuid::Uuid::new_v4(), &input.text, input);
-        match fd_result {
-            Ok(_) => (),
-            Err(e) => {
-                cli_pretty_printing::warning(&format!(
-                    "DEBUG: Failed to write human checker rejection due to error: {}",
-                    e
-                ));
+        return false;
+    }
+    let mut check = input.clone();
+    check.is_identified = true;
+    let text = check.text.clone();
+    {
+        let mut questions = lock_questions();
+        questions.push(Question {
+            check,
+            decode: None,
+            accepted: None,
+        });
+        QUESTION_COUNT.store(questions.len(), Ordering::Relaxed);
+    }
+    if asker().send(text).is_err() {
+        log::warn!("The human checker can't ask any more questions");
+    }
+    false
+}
+
+/// Where questions are sent to be asked, starting the thread asking them the first time
+fn asker() -> &'static Sender<String> {
+    ASKER.get_or_init(|| {
+        let (sender, receiver) = unbounded::<String>();
+        std::thread::spawn(move || {
+            for text in receiver {
+                ask(&text);
             }
+        });
+        sender
+    })
+}
+
+/// Asks the question about `text`, unless the search it was asked in has ended or has
+/// already been answered with a plaintext
+fn ask(text: &str) {
+    let check = {
+        let questions = lock_questions();
+        if questions
+            .iter()
+            .any(|question| question.accepted == Some(true))
+        {
+            return;
         }
-        return false;
+        questions
+            .iter()
+            .find(|question| question.check.text == text)
+            .map(|question| question.check.clone())
+    };
+    let Some(check) = check else {
+        return;
+    };
+    let config = get_config();
+    let timeout = (config.human_checker_timeout > 0)
+        .then(|| Duration::from_secs(config.human_checker_timeout.into()));
+    let mut waited = config.human_checker_timeout;
+    events::publish(|| SearchEvent::HumanCheckerPrompt {
+        description: check.description.clone(),
+        text: events::shorten(&check.text),
+    });
+    let answer = {
+        let inline_answers = INLINE_ANSWERS
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        match inline_answers.as_ref() {
+            Some(answers) => receive_inline(answers, timeout),
+            None if !config.human_checker_webhook.is_empty() => {
+                drop(inline_answers);
+                waited = config.human_checker_webhook_timeout;
+                ask_through_webhook(&check)
+            }
+            None => ask_on_the_terminal(&check, timeout),
+        }
+    };
+    let accepted = match answer {
+        Answer::Given(accepted) => accepted,
+        Answer::TimedOut => {
+            let assumed = config.human_checker_default == HumanCheckerDefault::Yes;
+            human_checker_timed_out(waited, assumed);
+            assumed
+        }
+        Answer::Closed => false,
+    };
+    cli_pretty_printing::success(&format!("DEBUG: Human checker returning: {}", accepted));
+    events::publish(|| SearchEvent::HumanCheckerAnswered {
+        text: events::shorten(&check.text),
+        accepted,
+    });
+
+    // Only answers the user gave are remembered, not ones assumed or forced by a closed stdin
+    if answer == Answer::Given(false) {
+        denylist::remember_rejection(&check.text);
+        if let Err(e) = storage().insert_rejection(&check.text, &check) {
+            cli_pretty_printing::warning(&format!(
+                "DEBUG: Failed to write human checker rejection due to error: {}",
+                e
+            ));
+        }
+    }
+    if answer == Answer::Given(true) {
+        confirmed::remember_confirmation(&check.text);
+        if let Err(e) = storage().insert_confirmation(&check.text, &check) {
+            cli_pretty_printing::warning(&format!(
+                "DEBUG: Failed to write human checker confirmation due to error: {}",
+                e
+            ));
+        }
+    }
+    let mut questions = lock_questions();
+    if accepted {
+        if let Some(question) = questions.iter_mut().find(|q| q.check.text == text) {
+            question.accepted = Some(true);
+        }
+    } else {
+        questions.retain(|question| question.check.text != text);
+    }
+    QUESTION_COUNT.store(questions.len(), Ordering::Relaxed);
+}
+
+/// Waits for the next answer from [`answer_prompts_with`]
+fn receive_inline(answers: &Receiver<bool>, timeout: Option<Duration>) -> Answer {
+    let answer = match timeout {
+        Some(timeout) => answers.recv_timeout(timeout).map_err(|e| match e {
+            std::sync::mpsc::RecvTimeoutError::Timeout => Answer::TimedOut,
+            std::sync::mpsc::RecvTimeoutError::Disconnected => Answer::Closed,
+        }),
+        None => answers.recv().map_err(|_| Answer::Closed),
+    };
+    answer.map_or_else(|answer| answer, Answer::Given)
+}
+
+/// Asks through the webhook and waits for its answer
+/// A question which couldn't be asked is rejected without remembering the rejection.
+fn ask_through_webhook(check: &CheckResult) -> Answer {
+    match webhook::ask(check) {
+        Ok(Some(accepted)) => Answer::Given(accepted),
+        Ok(None) => Answer::TimedOut,
+        Err(e) => {
+            warning_webhook_failed(&e);
+            Answer::Closed
+        }
+    }
+}
+
+/// Asks the user on the terminal and waits for the line they type
+/// A closed standard input isn't an answer, so it rejects without remembering the rejection.
+fn ask_on_the_terminal(check: &CheckResult, timeout: Option<Duration>) -> Answer {
+    human_checker_check(&check.description, &check.text);
+    let lines = TERMINAL_LINES.get_or_init(|| {
+        let (sender, receiver) = unbounded();
+        std::thread::spawn(move || {
+            let mut reply = String::new();
+            while std::io::stdin()
+                .read_line(&mut reply)
+                .is_ok_and(|read| read > 0)
+            {
+                if sender.send(std::mem::take(&mut reply)).is_err() {
+                    return;
+                }
+            }
+        });
+        receiver
+    });
+    let reply = match timeout {
+        Some(timeout) => lines.recv_timeout(timeout).map_err(|e| match e {
+            RecvTimeoutError::Timeout => Answer::TimedOut,
+            RecvTimeoutError::Disconnected => Answer::Closed,
+        }),
+        None => lines.recv().map_err(|_| Answer::Closed),
+    };
+    match reply {
+        Ok(reply) => {
+            let reply = reply.trim_end_matches(['\n', '\r']);
+            cli_pretty_printing::success(&format!(
+                "DEBUG: Human checker received reply: '{}'",
+                reply
+            ));
+            Answer::Given(reply.to_ascii_lowercase().starts_with('y'))
+        }
+        Err(answer) => answer,
+    }
+}
+
+/// Gives a question the decode it is about, as searchers call with each new decode
+/// The checkers only see the text, so this is how an accepted text gets the decoders which
+/// reached it.
+pub fn attach_decode(decode: &DecoderResult) {
+    if QUESTION_COUNT.load(Ordering::Relaxed) == 0 {
+        return;
+    }
+    let mut questions = lock_questions();
+    let question = questions
+        .iter_mut()
+        .find(|question| question.decode.is_none() && decode.text.contains(&question.check.text));
+    if let Some(question) = question {
+        question.decode = Some(DecoderResult {
+            text: vec![question.check.text.clone()],
+            path: decode.path.clone(),
+            bytes: None,
+        });
+    }
+}
+
+/// The plaintext of a question the user said yes to, once the search has made its decode
+pub fn take_accepted() -> Option<DecoderResult> {
+    if QUESTION_COUNT.load(Ordering::Relaxed) == 0 {
+        return None;
+    }
+    let mut questions = lock_questions();
+    let index = questions
+        .iter()
+        .position(|question| question.accepted == Some(true) && question.decode.is_some())?;
+    let question = questions.remove(index);
+    QUESTION_COUNT.store(questions.len(), Ordering::Relaxed);
+    let mut decode = question.decode?;
+    if let Some(step) = decode.path.last_mut() {
+        step.update_checker(&question.check);
+    }
+    Some(decode)
+}
+
+/// Waits until every question asked has been answered, giving the first plaintext the user
+/// said yes to
+/// Cancelling `cancel` stops waiting.
+pub fn wait_for_answers(cancel: &CancellationToken) -> Option<DecoderResult> {
+    loop {
+        if let Some(accepted) = take_accepted() {
+            return Some(accepted);
+        }
+        let waiting = QUESTION_COUNT.load(Ordering::Relaxed) > 0
+            && lock_questions()
+                .iter()
+                .any(|question| question.accepted.is_none());
+        if !waiting || cancel.is_cancelled() {
+            return None;
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+}
+
+/// Forgets every question, as a new search starts or the last was cancelled
+/// A question being asked is still answered, but nothing comes of a yes.
+pub fn forget_questions() {
+    let mut questions = lock_questions();
+    questions.clear();
+    QUESTION_COUNT.store(0, Ordering::Relaxed);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc::channel;
+
+    #[test]
+    fn inline_answers_time_out_or_close() {
+        let (sender, answers) = channel();
+        sender.send(true).unwrap();
+        let timeout = Some(Duration::from_millis(10));
+        assert_eq!(receive_inline(&answers, timeout), Answer::Given(true));
+        assert_eq!(receive_inline(&answers, timeout), Answer::TimedOut);
+        drop(sender);
+        assert_eq!(receive_inline(&answers, timeout), Answer::Closed);
+        assert_eq!(receive_inline(&answers, None), Answer::Closed);
     }
-    true
 }
@@ -0,0 +1,202 @@
+use crate::checkers::checker_result::CheckResult;
+use crate::config::get_config;
+use crate::storage::ngrams::languages::{self, LanguageProfile, LANGUAGES};
+use gibberish_or_not::Sensitivity;
+use lemmeknow::Identifier;
+use log::trace;
+
+use crate::checkers::checker_type::{Check, Checker};
+
+/// Text with fewer trigrams than this is too short to tell languages apart
+const MIN_TRIGRAMS: usize = 8;
+
+/// The best language has to cover this many times as much of the text as the next best,
+/// as languages sharing an alphabet share many trigrams
+const MIN_MARGIN: f64 = 1.2;
+
+/// Checks for plaintext in the language chosen with `--language` or the `language` setting,
+/// or in any of them when it is `auto`. Spanish, French, German, Russian and Arabic are
+/// recognised by how much of the text's trigrams are in each language's profile.
+/// English is left to the English checker.
+pub struct LanguageChecker;
+
+impl Check for Checker<LanguageChecker> {
+    fn new() -> Self {
+        Checker {
+            name: "Language Checker",
+            description: "Uses trigram profiles to check if text is Spanish, French, German, Russian or Arabic",
+            link: "https://en.wikipedia.org/wiki/Language_identification",
+            tags: vec!["language", "nlp", "trigram"],
+            expected_runtime: 0.01,
+            popularity: 1.0,
+            lemmeknow_config: Identifier::default(),
+            sensitivity: Sensitivity::Medium,
+            enhanced_detector: None,
+            _phantom: std::marker::PhantomData,
+        }
+    }
+
+    fn check(&self, text: &str) -> CheckResult {
+        let wanted = wanted_languages(&get_config().language);
+        self.check_for(text, &wanted)
+    }
+
+    fn with_sensitivity(mut self, sensitivity: Sensitivity) -> Self {
+        self.sensitivity = sensitivity;
+        self
+    }
+
+    fn get_sensitivity(&self) -> Sensitivity {
+        self.sensitivity
+    }
+}
+
+impl Checker<LanguageChecker> {
+    /// Checks whether the text is in one of the wanted languages
+    fn check_for(&self, text: &str, wanted: &[&LanguageProfile]) -> CheckResult {
+        let mut result = CheckResult::new(self);
+        if wanted.is_empty() {
+            return result;
+        }
+        let Some((language, coverage)) = detect(text, min_coverage(self.sensitivity)) else {
+            return result;
+        };
+        if !wanted.iter().any(|wanted| wanted.code == language.code) {
+            trace!("The language checker found {} text", language.name);
+            return result;
+        }
+        trace!(
+            "The language checker found {} text covering {:.2} of its profile",
+            language.name,
+            coverage
+        );
+        result.is_identified = true;
+        result.text = text.to_string();
+        result.description = format!("{} text", language.name);
+        result.confidence = coverage.min(0.9) as f32;
+        result
+    }
+}
+
+/// The languages the `language` setting asks for, none for English as the English checker
+/// covers it, and every other language for `auto`
+pub fn wanted_languages(setting: &str) -> Vec<&'static LanguageProfile> {
+    if setting.eq_ignore_ascii_case("auto") {
+        return LANGUAGES
+            .iter()
+            .filter(|language| language.code != "en")
+            .collect();
+    }
+    languages::find_language(setting)
+        .filter(|language| language.code != "en")
+        .into_iter()
+        .collect()
+}
+
+/// Whether the `language` setting is `auto` or a language with a profile
+pub fn is_known_language(setting: &str) -> bool {
+    setting.eq_ignore_ascii_case("auto") || languages::find_language(setting).is_some()
+}
+
+/// The fraction of the text's trigrams which have to be in a profile: a stricter
+/// sensitivity wants more of them, like the English checker's gibberish detection
+fn min_coverage(sensitivity: Sensitivity) -> f64 {
+    match sensitivity {
+        Sensitivity::Low => 0.25,
+        Sensitivity::Medium => 0.35,
+        Sensitivity::High => 0.45,
+    }
+}
+
+/// The language the text is most likely in and how much of it the profile covers, None if
+/// it is too short, covered too little by every profile, or too close between two of them
+fn detect(text: &str, min_coverage: f64) -> Option<(&'static LanguageProfile, f64)> {
+    let trigrams = languages::trigrams(text);
+    if trigrams.len() < MIN_TRIGRAMS {
+        return None;
+    }
+    let mut coverages: Vec<(&'static LanguageProfile, f64)> = LANGUAGES
+        .iter()
+        .map(|language| (language, language.coverage(&trigrams)))
+        .collect();
+    coverages.sort_by(|a, b| b.1.total_cmp(&a.1));
+    let (best, coverage) = coverages[0];
+    let runner_up = coverages.get(1).map_or(0.0, |(_, coverage)| *coverage);
+    (coverage >= min_coverage && coverage >= runner_up * MIN_MARGIN).then_some((best, coverage))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// What the checker makes of the text when any language is wanted
+    fn detected(text: &str) -> Option<String> {
+        let result = Checker::<LanguageChecker>::new().check_for(text, &wanted_languages("auto"));
+        result.is_identified.then_some(result.description)
+    }
+
+    #[test]
+    fn languages_are_recognised() {
+        let texts = [
+            (
+                "El perro de mi vecino ladra todas las noches y no puedo dormir bien.",
+                "Spanish text",
+            ),
+            (
+                "Le chat de ma voisine dort toute la journée sur le canapé.",
+                "French text",
+            ),
+            (
+                "Der Hund meines Nachbarn bellt jede Nacht, und ich kann nicht schlafen.",
+                "German text",
+            ),
+            (
+                "Собака моего соседа лает каждую ночь, и я не могу нормально спать.",
+                "Russian text",
+            ),
+            (
+                "كلب جاري ينبح كل ليلة ولا أستطيع النوم جيدا منذ أسابيع.",
+                "Arabic text",
+            ),
+        ];
+        for (text, description) in texts {
+            assert_eq!(detected(text).as_deref(), Some(description), "{text}");
+        }
+    }
+
+    #[test]
+    fn english_is_left_to_the_english_checker() {
+        assert!(detected("The dog next door barks every night and I cannot sleep.").is_none());
+    }
+
+    #[test]
+    fn ciphertext_is_not_a_language() {
+        assert!(detected("Wkh grj qhaw grru edunv hyhub qljkw dqg L fdqqrw vohhs.").is_none());
+        assert!(detected("VGhlIGRvZyBuZXh0IGRvb3IgYmFya3MgZXZlcnkgbmlnaHQ=").is_none());
+        assert!(detected("").is_none());
+    }
+
+    #[test]
+    fn only_the_chosen_language_is_accepted() {
+        let checker = Checker::<LanguageChecker>::new();
+        let spanish = "El perro de mi vecino ladra todas las noches y no puedo dormir bien.";
+        assert!(
+            checker
+                .check_for(spanish, &wanted_languages("es"))
+                .is_identified
+        );
+        assert!(
+            !checker
+                .check_for(spanish, &wanted_languages("german"))
+                .is_identified
+        );
+        assert!(wanted_languages("en").is_empty());
+    }
+
+    #[test]
+    fn language_settings_are_validated() {
+        assert!(is_known_language("auto"));
+        assert!(is_known_language("FR"));
+        assert!(!is_known_language("xx"));
+    }
+}
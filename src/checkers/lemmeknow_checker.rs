@@ -28,9 +28,12 @@ impl Check for Checker<LemmeKnow> {
         let lemmeknow_result = self.lemmeknow_config.identify(text);
         let mut is_identified = false;
         let mut description = "".to_string();
+        let mut confidence = 0.0;
         if !lemmeknow_result.is_empty() {
             is_identified = true;
-            description = format_data_result(&lemmeknow_result[0].data)
+            description = format_data_result(&lemmeknow_result[0].data);
+            // Rarer patterns are less likely to match by accident
+            confidence = lemmeknow_result[0].data.rarity.clamp(0.0, 1.0);
         }
 
         CheckResult {
@@ -41,6 +44,9 @@ impl Check for Checker<LemmeKnow> {
             // Returns a vector of matches
             description,
             link: self.link,
+            confidence,
+            sensitivity: self.sensitivity,
+            scores: None,
         }
     }
 
@@ -0,0 +1,230 @@
+use crate::checkers::checker_result::CheckResult;
+use crate::config::get_config;
+use gibberish_or_not::Sensitivity;
+use lemmeknow::Identifier;
+use log::trace;
+use std::path::{Path, PathBuf};
+
+use crate::checkers::checker_type::{Check, Checker};
+
+/// Checks if decoded bytes are a file, such as a PNG image or a ZIP archive, by the
+/// signature at its start. Decoding that ends in a file is as much a success as decoding
+/// that ends in text, the file just can't be printed.
+pub struct MagicBytesChecker;
+
+/// A kind of file and the bytes it starts with
+pub struct FileSignature {
+    /// What the file is, such as "ZIP archive"
+    pub name: &'static str,
+    /// The usual file extension, without the dot
+    pub extension: &'static str,
+    /// The bytes the file has, and the offset of each from its start
+    parts: &'static [(usize, &'static [u8])],
+    /// The decoder which unpacks files like this, if ciphey has one
+    /// The search carries on into those rather than stopping at the file.
+    pub unpacked_by: Option<&'static str>,
+}
+
+impl FileSignature {
+    /// Whether the bytes start with this signature
+    fn matches(&self, bytes: &[u8]) -> bool {
+        self.parts.iter().all(|(offset, magic)| {
+            bytes
+                .get(*offset..offset + magic.len())
+                .is_some_and(|found| found == *magic)
+        })
+    }
+}
+
+/// The signatures recognised, longer ones first so they win over shorter ones they share
+/// bytes with
+static SIGNATURES: [FileSignature; 21] = [
+    signature("SQLite database", "sqlite", &[(0, b"SQLite format 3\0")]),
+    signature("PNG image", "png", &[(0, b"\x89PNG\r\n\x1a\n")]),
+    signature("7-Zip archive", "7z", &[(0, b"7z\xbc\xaf\x27\x1c")]),
+    FileSignature {
+        name: "xz archive",
+        extension: "xz",
+        parts: &[(0, b"\xfd7zXZ\0")],
+        unpacked_by: Some("LZMA"),
+    },
+    signature("RAR archive", "rar", &[(0, b"Rar!\x1a\x07")]),
+    signature("GIF image", "gif", &[(0, b"GIF87a")]),
+    signature("GIF image", "gif", &[(0, b"GIF89a")]),
+    FileSignature {
+        name: "bzip2 archive",
+        extension: "bz2",
+        parts: &[(0, b"BZh"), (4, b"1AY&SY")],
+        unpacked_by: Some("Bzip2"),
+    },
+    signature("WAV audio", "wav", &[(0, b"RIFF"), (8, b"WAVE")]),
+    signature("WebP image", "webp", &[(0, b"RIFF"), (8, b"WEBP")]),
+    signature("tar archive", "tar", &[(257, b"ustar")]),
+    signature("PDF document", "pdf", &[(0, b"%PDF-")]),
+    signature("ZIP archive", "zip", &[(0, b"PK\x03\x04")]),
+    signature("ELF executable", "elf", &[(0, b"\x7fELF")]),
+    signature("Java class file", "class", &[(0, b"\xca\xfe\xba\xbe")]),
+    signature("WebAssembly module", "wasm", &[(0, b"\0asm")]),
+    signature("Ogg media", "ogg", &[(0, b"OggS")]),
+    signature("TIFF image", "tiff", &[(0, b"II*\0")]),
+    signature("JPEG image", "jpg", &[(0, b"\xff\xd8\xff")]),
+    signature("MP3 audio", "mp3", &[(0, b"ID3")]),
+    FileSignature {
+        name: "gzip archive",
+        extension: "gz",
+        parts: &[(0, b"\x1f\x8b\x08")],
+        unpacked_by: Some("Gzip"),
+    },
+];
+
+/// A signature of a file ciphey has no decoder for
+const fn signature(
+    name: &'static str,
+    extension: &'static str,
+    parts: &'static [(usize, &'static [u8])],
+) -> FileSignature {
+    FileSignature {
+        name,
+        extension,
+        parts,
+        unpacked_by: None,
+    }
+}
+
+/// The kind of file the bytes are, None if they don't start with a known signature
+pub fn identify(bytes: &[u8]) -> Option<&'static FileSignature> {
+    SIGNATURES.iter().find(|signature| signature.matches(bytes))
+}
+
+/// Where to write a decoded file given `--save-binary`'s path, which is used as it is
+/// unless it is a directory, where `decoded.<extension>` is written
+pub fn output_path(path: &str, signature: &FileSignature) -> PathBuf {
+    let path = Path::new(path);
+    if path.is_dir() {
+        path.join(format!("decoded.{}", signature.extension))
+    } else {
+        path.to_path_buf()
+    }
+}
+
+impl Check for Checker<MagicBytesChecker> {
+    fn new() -> Self {
+        Checker {
+            name: "Magic Bytes Checker",
+            description: "Recognises files such as images, archives, documents and executables by their signature",
+            link: "https://en.wikipedia.org/wiki/List_of_file_signatures",
+            tags: vec!["magic", "file", "binary"],
+            expected_runtime: 0.01,
+            popularity: 1.0,
+            lemmeknow_config: Identifier::default(),
+            sensitivity: Sensitivity::Medium,
+            enhanced_detector: None,
+            _phantom: std::marker::PhantomData,
+        }
+    }
+
+    fn check(&self, text: &str) -> CheckResult {
+        self.check_bytes(text.as_bytes())
+    }
+
+    fn with_sensitivity(mut self, sensitivity: Sensitivity) -> Self {
+        self.sensitivity = sensitivity;
+        self
+    }
+
+    fn get_sensitivity(&self) -> Sensitivity {
+        self.sensitivity
+    }
+}
+
+impl Checker<MagicBytesChecker> {
+    /// Checks whether the bytes are a known kind of file
+    /// The result's text is a lossy rendering of the bytes so it can still be shown.
+    pub fn check_bytes(&self, bytes: &[u8]) -> CheckResult {
+        let mut result = CheckResult::new(self);
+        if let Some(signature) = identify(bytes) {
+            result.is_identified = true;
+            result.text = String::from_utf8_lossy(bytes).to_string();
+            result.description = format!("Decoded to a {}", signature.name);
+            result.confidence = 0.9;
+        }
+        result
+    }
+}
+
+/// The result for bytes which are a file the search should stop at, None if they aren't one
+/// Files ciphey has an enabled decoder for, such as gzip, are left to that decoder to unpack.
+pub fn finished_file(bytes: &[u8]) -> Option<CheckResult> {
+    let checker = Checker::<MagicBytesChecker>::new();
+    let config = get_config();
+    if !config.is_checker_enabled(checker.name) {
+        return None;
+    }
+    let signature = identify(bytes)?;
+    if signature
+        .unpacked_by
+        .is_some_and(crate::filtration_system::is_decoder_running)
+    {
+        trace!(
+            "Leaving the {} to the {} decoder",
+            signature.name,
+            signature.unpacked_by.unwrap_or_default()
+        );
+        return None;
+    }
+    Some(checker.check_bytes(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn files_are_recognised_by_signature() {
+        let name = |bytes: &[u8]| identify(bytes).map(|signature| signature.name);
+        assert_eq!(name(b"PK\x03\x04\x14\0\0\0\x08\0"), Some("ZIP archive"));
+        assert_eq!(name(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"), Some("PNG image"));
+        assert_eq!(name(b"%PDF-1.7\n%\xe2\xe3\xcf\xd3"), Some("PDF document"));
+        assert_eq!(name(b"\x7fELF\x02\x01\x01"), Some("ELF executable"));
+        assert_eq!(name(b"\x1f\x8b\x08\0\0\0\0\0"), Some("gzip archive"));
+        assert_eq!(name(b"BZh91AY&SY\x00"), Some("bzip2 archive"));
+    }
+
+    #[test]
+    fn signatures_have_to_be_complete() {
+        assert!(identify(b"PK\x03").is_none());
+        assert!(identify(b"BZhello world").is_none());
+        assert!(identify(b"RIFF\0\0\0\0AVI ").is_none());
+        assert!(identify(b"").is_none());
+    }
+
+    #[test]
+    fn tar_is_recognised_at_its_offset() {
+        let mut tar = vec![0u8; 512];
+        tar[257..262].copy_from_slice(b"ustar");
+        assert_eq!(identify(&tar).unwrap().extension, "tar");
+    }
+
+    #[test]
+    fn files_are_saved_in_directories_by_extension() {
+        let zip = identify(b"PK\x03\x04").unwrap();
+        let directory = std::env::temp_dir();
+        assert_eq!(
+            output_path(directory.to_str().unwrap(), zip),
+            directory.join("decoded.zip")
+        );
+        assert_eq!(output_path("out.bin", zip), PathBuf::from("out.bin"));
+    }
+
+    #[test]
+    fn checker_describes_the_file() {
+        let result = Checker::<MagicBytesChecker>::new().check_bytes(b"PK\x03\x04\x14\0\xff");
+        assert!(result.is_identified);
+        assert_eq!(result.description, "Decoded to a ZIP archive");
+        assert!(
+            !Checker::<MagicBytesChecker>::new()
+                .check("hello world")
+                .is_identified
+        );
+    }
+}
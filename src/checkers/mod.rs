@@ -1,15 +1,22 @@
 use self::{
     athena::Athena,
     checker_result::CheckResult,
-    checker_type::{Check, CheckInfo, Checker},
+    checker_type::{check_bytes, Check, CheckInfo, Checker},
+    checksum::ChecksumChecker,
     english::EnglishChecker,
+    entropy::EntropyChecker,
+    language::LanguageChecker,
     lemmeknow_checker::LemmeKnow,
+    magic_bytes::MagicBytesChecker,
     password::PasswordChecker,
     regex_checker::RegexChecker,
+    structured_data::StructuredDataChecker,
     wait_athena::WaitAthena,
     wordlist::WordlistChecker,
 };
 
+pub use self::custom::{clear_custom_checkers, register_custom_checker};
+
 use gibberish_or_not::Sensitivity;
 use once_cell::sync::Lazy;
 use std::collections::HashMap;
@@ -20,20 +27,40 @@ pub mod athena;
 pub mod checker_result;
 /// This is the base checker that all other checkers inherit from.
 pub mod checker_type;
+/// The Checksum Checker checks the check digits of credit cards, IMEIs, ISBNs and IBANs
+pub mod checksum;
+/// Remembers plaintexts the user confirmed, so they are accepted without asking again
+pub mod confirmed;
+/// Checkers registered at runtime by users of the library
+pub mod custom;
 /// The default checker we use which simply calls all other checkers in order.
 pub mod default_checker;
+/// Rejects degenerate plaintexts and ones on the user's denylist
+pub mod denylist;
 /// The English Checker is a checker that checks if the input is English
 pub mod english;
+/// Combines the results of several checkers into a single ranked verdict
+pub mod ensemble;
+/// The Entropy Checker scores how much like plaintext text is, to rank what no checker identified
+pub mod entropy;
 /// The Human Checker asks humans if the expected plaintext is real plaintext
 pub mod human_checker;
+/// The Language Checker checks if the input is Spanish, French, German, Russian or Arabic
+pub mod language;
 /// The LemmeKnow Checker checks if the text matches a known Regex pattern.
 pub mod lemmeknow_checker;
+/// The Magic Bytes Checker checks if decoded bytes are a file, such as a PNG image or ZIP archive
+pub mod magic_bytes;
 /// The Password checker checks if the text matches a known common password
 pub mod password;
 /// The Regex checker checks to see if the intended text matches the plaintext
 pub mod regex_checker;
+/// The Structured Data Checker checks if the text is a well-formed JSON, XML or YAML document
+pub mod structured_data;
 /// The WaitAthena Checker is a variant of Athena that collects all plaintexts found during the search
 pub mod wait_athena;
+/// Asks the human checker's questions through a webhook such as Slack's or Discord's
+pub mod webhook;
 /// The Wordlist checker checks if the text exactly matches any word in a user-provided wordlist
 pub mod wordlist;
 
@@ -53,6 +80,16 @@ pub enum CheckerTypes {
     CheckPassword(Checker<PasswordChecker>),
     /// Wrapper for Wordlist Checker
     CheckWordlist(Checker<WordlistChecker>),
+    /// Wrapper for Checksum Checker
+    CheckChecksum(Checker<ChecksumChecker>),
+    /// Wrapper for Language Checker
+    CheckLanguage(Checker<LanguageChecker>),
+    /// Wrapper for Entropy Checker
+    CheckEntropy(Checker<EntropyChecker>),
+    /// Wrapper for Magic Bytes Checker
+    CheckMagicBytes(Checker<MagicBytesChecker>),
+    /// Wrapper for Structured Data Checker
+    CheckStructuredData(Checker<StructuredDataChecker>),
 }
 
 impl CheckerTypes {
@@ -66,6 +103,33 @@ impl CheckerTypes {
             CheckerTypes::CheckRegex(regex_checker) => regex_checker.check(text),
             CheckerTypes::CheckPassword(password_checker) => password_checker.check(text),
             CheckerTypes::CheckWordlist(wordlist_checker) => wordlist_checker.check(text),
+            CheckerTypes::CheckChecksum(checksum_checker) => checksum_checker.check(text),
+            CheckerTypes::CheckLanguage(language_checker) => language_checker.check(text),
+            CheckerTypes::CheckEntropy(entropy_checker) => entropy_checker.check(text),
+            CheckerTypes::CheckMagicBytes(magic_bytes_checker) => magic_bytes_checker.check(text),
+            CheckerTypes::CheckStructuredData(structured_data_checker) => {
+                structured_data_checker.check(text)
+            }
+        }
+    }
+
+    /// Calls the appropriate checker on a decoded value which may not be text
+    /// See [`checker_type::check_bytes`] for how bytes which aren't UTF-8 are treated.
+    /// Athena, WaitAthena and the Magic Bytes Checker also recognise files in the bytes.
+    pub fn check_bytes(&self, bytes: &[u8]) -> CheckResult {
+        match self {
+            CheckerTypes::CheckLemmeKnow(checker) => check_bytes(checker, bytes),
+            CheckerTypes::CheckEnglish(checker) => check_bytes(checker, bytes),
+            CheckerTypes::CheckAthena(checker) => checker.check_bytes(bytes),
+            CheckerTypes::CheckWaitAthena(checker) => checker.check_bytes(bytes),
+            CheckerTypes::CheckRegex(checker) => check_bytes(checker, bytes),
+            CheckerTypes::CheckPassword(checker) => check_bytes(checker, bytes),
+            CheckerTypes::CheckWordlist(checker) => check_bytes(checker, bytes),
+            CheckerTypes::CheckChecksum(checker) => check_bytes(checker, bytes),
+            CheckerTypes::CheckLanguage(checker) => check_bytes(checker, bytes),
+            CheckerTypes::CheckEntropy(checker) => check_bytes(checker, bytes),
+            CheckerTypes::CheckMagicBytes(checker) => checker.check_bytes(bytes),
+            CheckerTypes::CheckStructuredData(checker) => check_bytes(checker, bytes),
         }
     }
 
@@ -107,6 +171,31 @@ impl CheckerTypes {
                 new_checker.sensitivity = sensitivity;
                 CheckerTypes::CheckWordlist(new_checker)
             }
+            CheckerTypes::CheckChecksum(_checker) => {
+                let mut new_checker = Checker::<ChecksumChecker>::new();
+                new_checker.sensitivity = sensitivity;
+                CheckerTypes::CheckChecksum(new_checker)
+            }
+            CheckerTypes::CheckLanguage(_checker) => {
+                let mut new_checker = Checker::<LanguageChecker>::new();
+                new_checker.sensitivity = sensitivity;
+                CheckerTypes::CheckLanguage(new_checker)
+            }
+            CheckerTypes::CheckEntropy(_checker) => {
+                let mut new_checker = Checker::<EntropyChecker>::new();
+                new_checker.sensitivity = sensitivity;
+                CheckerTypes::CheckEntropy(new_checker)
+            }
+            CheckerTypes::CheckMagicBytes(_checker) => {
+                let mut new_checker = Checker::<MagicBytesChecker>::new();
+                new_checker.sensitivity = sensitivity;
+                CheckerTypes::CheckMagicBytes(new_checker)
+            }
+            CheckerTypes::CheckStructuredData(_checker) => {
+                let mut new_checker = Checker::<StructuredDataChecker>::new();
+                new_checker.sensitivity = sensitivity;
+                CheckerTypes::CheckStructuredData(new_checker)
+            }
         }
     }
 
@@ -120,6 +209,11 @@ impl CheckerTypes {
             CheckerTypes::CheckRegex(checker) => checker.get_sensitivity(),
             CheckerTypes::CheckPassword(checker) => checker.get_sensitivity(),
             CheckerTypes::CheckWordlist(checker) => checker.get_sensitivity(),
+            CheckerTypes::CheckChecksum(checker) => checker.get_sensitivity(),
+            CheckerTypes::CheckLanguage(checker) => checker.get_sensitivity(),
+            CheckerTypes::CheckEntropy(checker) => checker.get_sensitivity(),
+            CheckerTypes::CheckMagicBytes(checker) => checker.get_sensitivity(),
+            CheckerTypes::CheckStructuredData(checker) => checker.get_sensitivity(),
         }
     }
 }
@@ -149,10 +243,22 @@ impl CheckerBox {
 pub static CHECKER_MAP: Lazy<HashMap<&str, CheckerBox>> = Lazy::new(|| {
     HashMap::from([
         ("Athena Checker", CheckerBox::new(Checker::<Athena>::new())),
+        (
+            "Checksum Checker",
+            CheckerBox::new(Checker::<ChecksumChecker>::new()),
+        ),
         (
             "English Checker",
             CheckerBox::new(Checker::<EnglishChecker>::new()),
         ),
+        (
+            "Entropy Checker",
+            CheckerBox::new(Checker::<EntropyChecker>::new()),
+        ),
+        (
+            "Language Checker",
+            CheckerBox::new(Checker::<LanguageChecker>::new()),
+        ),
         (
             "Template checker",
             CheckerBox::new(Checker::<default_checker::DefaultChecker>::new()),
@@ -161,6 +267,10 @@ pub static CHECKER_MAP: Lazy<HashMap<&str, CheckerBox>> = Lazy::new(|| {
             "LemmeKnow Checker",
             CheckerBox::new(Checker::<LemmeKnow>::new()),
         ),
+        (
+            "Magic Bytes Checker",
+            CheckerBox::new(Checker::<MagicBytesChecker>::new()),
+        ),
         (
             "Password Checker",
             CheckerBox::new(Checker::<PasswordChecker>::new()),
@@ -169,6 +279,10 @@ pub static CHECKER_MAP: Lazy<HashMap<&str, CheckerBox>> = Lazy::new(|| {
             "Regex Checker",
             CheckerBox::new(Checker::<RegexChecker>::new()),
         ),
+        (
+            "Structured Data Checker",
+            CheckerBox::new(Checker::<StructuredDataChecker>::new()),
+        ),
         (
             "WaitAthena Checker",
             CheckerBox::new(Checker::<WaitAthena>::new()),
@@ -200,4 +314,32 @@ mod tests {
         let athena = CheckerTypes::CheckAthena(Checker::<Athena>::new());
         assert!(athena.check("exuberant").is_identified);
     }
+
+    #[test]
+    fn bytes_which_arent_text_are_not_identified() {
+        let athena = CheckerTypes::CheckAthena(Checker::<Athena>::new());
+        assert!(!athena.check_bytes(&[0x1f, 0x8b, 0x08, 0xff]).is_identified);
+        assert!(athena.check_bytes(b"exuberant").is_identified);
+    }
+
+    #[test]
+    fn athena_combines_agreeing_checkers() {
+        let athena = Checker::<Athena>::new();
+        let result = athena.check("sunshine");
+        assert!(result.is_identified);
+        assert_eq!(result.checker_name, "English Checker");
+        assert!(result
+            .description
+            .contains("also identified by Password Checker"));
+        // 1 - (1 - 0.9)(1 - 0.8), more confident than either checker alone
+        assert!((result.confidence - 0.98).abs() < 1e-6);
+    }
+
+    #[test]
+    fn bytes_which_are_a_file_are_identified() {
+        let athena = CheckerTypes::CheckAthena(Checker::<Athena>::new());
+        let result = athena.check_bytes(b"PK\x03\x04\x14\x00\x00\x00\x08\x00\xff\xfe");
+        assert!(result.is_identified);
+        assert_eq!(result.checker_name, "Magic Bytes Checker");
+    }
 }
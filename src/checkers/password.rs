@@ -25,13 +25,17 @@ impl Check for Checker<PasswordChecker> {
     }
 
     fn check(&self, text: &str) -> CheckResult {
+        let is_identified = is_password(text);
         CheckResult {
-            is_identified: is_password(text),
+            is_identified,
             text: text.to_string(),
             checker_name: self.name,
             checker_description: self.description,
             description: "Common Password".to_string(),
             link: self.link,
+            confidence: if is_identified { 0.8 } else { 0.0 },
+            sensitivity: self.sensitivity,
+            scores: None,
         }
     }
 
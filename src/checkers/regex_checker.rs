@@ -3,13 +3,35 @@ use lemmeknow::Identifier;
 
 use super::checker_type::{Check, Checker};
 use crate::{checkers::checker_result::CheckResult, config::get_config};
-use log::trace;
+use log::{trace, warn};
+use once_cell::sync::Lazy;
 use regex::Regex;
 
 /// The Regex Checker checks if the text matches a known Regex pattern.
 /// This is the struct for it.
 pub struct RegexChecker;
 
+/// The patterns from `regex`, `regexes` and `--regex`, compiled once
+/// The CLI rejects patterns which don't compile, any others are skipped with a warning.
+static PATTERNS: Lazy<Vec<Regex>> = Lazy::new(|| {
+    get_config()
+        .regex_patterns()
+        .into_iter()
+        .filter_map(|pattern| match Regex::new(pattern) {
+            Ok(re) => Some(re),
+            Err(e) => {
+                warn!("Skipping regex {:?} which doesn't compile: {}", pattern, e);
+                None
+            }
+        })
+        .collect()
+});
+
+/// The first of the patterns the text matches
+fn first_match<'a>(patterns: &'a [Regex], text: &str) -> Option<&'a Regex> {
+    patterns.iter().find(|re| re.is_match(text))
+}
+
 impl Check for Checker<RegexChecker> {
     fn new() -> Self {
         Checker {
@@ -28,25 +50,23 @@ impl Check for Checker<RegexChecker> {
 
     fn check(&self, text: &str) -> CheckResult {
         trace!("Checking {} with regex", text);
-        // TODO put this into a lazy static so we don't generate it everytime
-        let config = get_config();
-        let regex_to_parse = config.regex.clone();
-        let re = Regex::new(&regex_to_parse.unwrap()).unwrap();
-
-        let regex_check_result = re.is_match(text);
-        let mut plaintext_found = false;
-        let printed_name = format!("Regex matched: {re}");
-        if regex_check_result {
-            plaintext_found = true;
-        }
+        let matched = first_match(&PATTERNS, text);
+        let printed_name = match matched {
+            Some(re) => format!("Regex matched: {re}"),
+            None => String::new(),
+        };
 
         CheckResult {
-            is_identified: plaintext_found,
+            is_identified: matched.is_some(),
             text: text.to_string(),
             checker_name: self.name,
             checker_description: self.description,
             description: printed_name,
             link: self.link,
+            // The user told us exactly what the plaintext looks like
+            confidence: if matched.is_some() { 1.0 } else { 0.0 },
+            sensitivity: self.sensitivity,
+            scores: None,
         }
     }
 
@@ -59,3 +79,25 @@ impl Check for Checker<RegexChecker> {
         self.sensitivity
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::first_match;
+    use regex::Regex;
+
+    #[test]
+    fn any_pattern_can_match() {
+        let patterns = [
+            Regex::new(r"flag\{.*\}").unwrap(),
+            Regex::new(r"CTF\{.*\}").unwrap(),
+        ];
+        assert_eq!(
+            first_match(&patterns, "the answer is CTF{hello}")
+                .unwrap()
+                .as_str(),
+            r"CTF\{.*\}"
+        );
+        assert!(first_match(&patterns, "flag{hello}").is_some());
+        assert!(first_match(&patterns, "hello").is_none());
+    }
+}
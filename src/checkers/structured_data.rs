@@ -0,0 +1,568 @@
+use crate::checkers::checker_result::CheckResult;
+use gibberish_or_not::Sensitivity;
+use lemmeknow::Identifier;
+use quick_xml::events::Event;
+use quick_xml::{Reader, Writer};
+
+use crate::checkers::checker_type::{Check, Checker};
+
+/// Documents with fewer values than this, such as `{}` or `[1]`, are too small to be
+/// worth stopping at, as short garbage decodes into them by chance
+const MIN_VALUES: usize = 2;
+
+/// How many spaces each level of the pretty-printed output is indented by
+const INDENT: usize = 2;
+
+/// Checks if the input is a well-formed JSON, XML or YAML document with some content in it.
+/// Leaked configs and exfiltrated secrets are often structured data rather than English,
+/// so the English checker alone would let them go by.
+pub struct StructuredDataChecker;
+
+/// The formats of structured data recognised
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataFormat {
+    /// JavaScript Object Notation, an object or an array at the top
+    Json,
+    /// Extensible Markup Language, with a single root element
+    Xml,
+    /// YAML block mappings and sequences, as written in config files
+    Yaml,
+}
+
+impl DataFormat {
+    /// The name of the format as it is usually written
+    pub fn name(self) -> &'static str {
+        match self {
+            DataFormat::Json => "JSON",
+            DataFormat::Xml => "XML",
+            DataFormat::Yaml => "YAML",
+        }
+    }
+}
+
+impl Check for Checker<StructuredDataChecker> {
+    fn new() -> Self {
+        Checker {
+            name: "Structured Data Checker",
+            description: "Checks if the input is a well-formed JSON, XML or YAML document",
+            link: "https://en.wikipedia.org/wiki/Serialization#Serialization_formats",
+            tags: vec!["json", "xml", "yaml", "structured"],
+            expected_runtime: 0.01,
+            popularity: 1.0,
+            lemmeknow_config: Identifier::default(),
+            sensitivity: Sensitivity::Medium,
+            enhanced_detector: None,
+            _phantom: std::marker::PhantomData,
+        }
+    }
+
+    fn check(&self, text: &str) -> CheckResult {
+        let mut result = CheckResult::new(self);
+        if let Some((format, pretty)) = parse(text) {
+            result.is_identified = true;
+            result.text = pretty;
+            result.description = format!("{} document", format.name());
+            result.confidence = 0.8;
+        }
+        result
+    }
+
+    fn with_sensitivity(mut self, sensitivity: Sensitivity) -> Self {
+        self.sensitivity = sensitivity;
+        self
+    }
+
+    fn get_sensitivity(&self) -> Sensitivity {
+        self.sensitivity
+    }
+}
+
+/// The format of the text and a pretty-printed copy of it, None if it isn't a well-formed
+/// document with at least [`MIN_VALUES`] values
+/// The format is picked by the first character, so each text is only parsed once.
+pub fn parse(text: &str) -> Option<(DataFormat, String)> {
+    let trimmed = text.trim();
+    match trimmed.chars().next()? {
+        '{' | '[' => parse_json(trimmed).map(|pretty| (DataFormat::Json, pretty)),
+        '<' => parse_xml(trimmed).map(|pretty| (DataFormat::Xml, pretty)),
+        _ => parse_yaml(text).map(|pretty| (DataFormat::Yaml, pretty)),
+    }
+}
+
+/// Pretty-prints a JSON object or array, keeping its keys in the order they were written
+fn parse_json(text: &str) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(text).ok()?;
+    if json_values(&value) < MIN_VALUES {
+        return None;
+    }
+    Some(reindent_json(text))
+}
+
+/// How many scalars are in a JSON value
+fn json_values(value: &serde_json::Value) -> usize {
+    match value {
+        serde_json::Value::Array(items) => items.iter().map(json_values).sum(),
+        serde_json::Value::Object(entries) => entries.values().map(json_values).sum(),
+        _ => 1,
+    }
+}
+
+/// Lays out JSON which is known to be valid with one value on each line
+/// serde_json sorts the keys of objects, so the text is re-indented instead.
+fn reindent_json(text: &str) -> String {
+    let mut pretty = String::with_capacity(text.len() * 2);
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut chars = text.chars().peekable();
+    let newline = |pretty: &mut String, depth: usize| {
+        pretty.push('\n');
+        pretty.push_str(&" ".repeat(depth * INDENT));
+    };
+    while let Some(c) = chars.next() {
+        if in_string {
+            pretty.push(c);
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => {
+                in_string = true;
+                pretty.push(c);
+            }
+            '{' | '[' => {
+                pretty.push(c);
+                while chars.peek().is_some_and(|next| next.is_whitespace()) {
+                    chars.next();
+                }
+                if chars.peek().is_some_and(|next| matches!(next, '}' | ']')) {
+                    // Empty objects and arrays stay on one line
+                    pretty.extend(chars.next());
+                } else {
+                    depth += 1;
+                    newline(&mut pretty, depth);
+                }
+            }
+            '}' | ']' => {
+                depth -= 1;
+                newline(&mut pretty, depth);
+                pretty.push(c);
+            }
+            ',' => {
+                pretty.push(c);
+                newline(&mut pretty, depth);
+            }
+            ':' => pretty.push_str(": "),
+            _ if c.is_whitespace() => {}
+            _ => pretty.push(c),
+        }
+    }
+    pretty
+}
+
+/// Pretty-prints an XML document with a single root element which every tag in is closed
+fn parse_xml(text: &str) -> Option<String> {
+    let mut reader = Reader::from_str(text);
+    reader.config_mut().trim_text(true);
+    let mut writer = Writer::new_with_indent(Vec::new(), b' ', INDENT);
+    let mut depth = 0usize;
+    let mut roots = 0;
+    let mut values = 0;
+    loop {
+        let event = reader.read_event().ok()?;
+        match &event {
+            Event::Start(element) | Event::Empty(element) => {
+                if depth == 0 {
+                    roots += 1;
+                }
+                for attribute in element.attributes() {
+                    attribute.ok()?;
+                    values += 1;
+                }
+                if matches!(event, Event::Start(_)) {
+                    depth += 1;
+                }
+            }
+            Event::End(_) => depth = depth.checked_sub(1)?,
+            Event::Text(text) => {
+                // Text outside the root element isn't allowed
+                if depth == 0 {
+                    return None;
+                }
+                text.unescape().ok()?;
+                values += 1;
+            }
+            Event::CData(_) if depth == 0 => return None,
+            Event::CData(_) => values += 1,
+            Event::Eof => break,
+            _ => {}
+        }
+        writer.write_event(event).ok()?;
+    }
+    if depth != 0 || roots != 1 || values < MIN_VALUES {
+        return None;
+    }
+    String::from_utf8(writer.into_inner()).ok()
+}
+
+/// A YAML node, keeping mappings in the order they were written
+#[derive(Debug, PartialEq)]
+enum YamlNode {
+    /// A single value, as it was written
+    Scalar(String),
+    /// A `|` or `>` block scalar's indicator and lines, indented as they were under it
+    Block(&'static str, String),
+    /// Items written on lines starting with `- `
+    Sequence(Vec<YamlNode>),
+    /// Keys and their values written as `key: value`
+    Mapping(Vec<(String, YamlNode)>),
+}
+
+impl YamlNode {
+    /// How many scalars are in the node
+    fn values(&self) -> usize {
+        match self {
+            YamlNode::Scalar(_) | YamlNode::Block(..) => 1,
+            YamlNode::Sequence(items) => items.iter().map(YamlNode::values).sum(),
+            YamlNode::Mapping(entries) => entries.iter().map(|(_, value)| value.values()).sum(),
+        }
+    }
+
+    /// Writes the node indented by `indent` spaces, with nested nodes indented further
+    fn write(&self, indent: usize, out: &mut String) {
+        let pad = " ".repeat(indent);
+        match self {
+            YamlNode::Scalar(value) => {
+                out.push_str(&pad);
+                out.push_str(value);
+                out.push('\n');
+            }
+            YamlNode::Block(indicator, block) => {
+                out.push_str(&pad);
+                out.push_str(indicator);
+                out.push('\n');
+                YamlNode::write_block(block, indent + INDENT, out);
+            }
+            YamlNode::Sequence(items) => {
+                for item in items {
+                    let mut nested = String::new();
+                    item.write(indent + INDENT, &mut nested);
+                    // The item's first line starts with the dash instead of its indent
+                    out.push_str(&pad);
+                    out.push_str("- ");
+                    out.push_str(&nested[indent + INDENT..]);
+                }
+            }
+            YamlNode::Mapping(entries) => {
+                for (key, value) in entries {
+                    out.push_str(&pad);
+                    out.push_str(key);
+                    out.push(':');
+                    match value {
+                        YamlNode::Block(indicator, block) => {
+                            out.push(' ');
+                            out.push_str(indicator);
+                            out.push('\n');
+                            YamlNode::write_block(block, indent + INDENT, out);
+                        }
+                        YamlNode::Scalar(scalar) => {
+                            out.push(' ');
+                            out.push_str(scalar);
+                            out.push('\n');
+                        }
+                        _ => {
+                            out.push('\n');
+                            value.write(indent + INDENT, out);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl YamlNode {
+    /// Writes the lines of a block scalar indented by `indent` spaces
+    fn write_block(block: &str, indent: usize, out: &mut String) {
+        for line in block.lines() {
+            out.push_str(&" ".repeat(indent));
+            out.push_str(line);
+            out.push('\n');
+        }
+    }
+}
+
+/// A line of YAML with its indent measured and comments taken off
+struct YamlLine<'a> {
+    /// How many spaces the line starts with
+    indent: usize,
+    /// The rest of the line
+    content: &'a str,
+}
+
+/// Pretty-prints YAML made of block mappings and sequences, the way config files are written
+/// Stricter than YAML itself: keys can't have spaces in, so prose with a colon in it isn't
+/// taken for a mapping, and flow collections, anchors and tags are left alone.
+fn parse_yaml(text: &str) -> Option<String> {
+    let mut lines = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let content = line.trim_start_matches(' ');
+        if content.starts_with('\t') {
+            return None;
+        }
+        if content.trim().is_empty() || content.starts_with('#') {
+            continue;
+        }
+        if i == 0 && content.trim_end() == "---" {
+            continue;
+        }
+        lines.push(YamlLine {
+            indent: line.len() - content.len(),
+            content: content.trim_end(),
+        });
+    }
+    let mut position = 0;
+    let root = parse_yaml_block(&lines, &mut position, lines.first()?.indent)?;
+    if position != lines.len() || root.values() < MIN_VALUES {
+        return None;
+    }
+    let mut pretty = String::new();
+    root.write(0, &mut pretty);
+    Some(pretty.trim_end().to_string())
+}
+
+/// Parses the mapping or sequence whose lines start at `indent`
+fn parse_yaml_block(lines: &[YamlLine], position: &mut usize, indent: usize) -> Option<YamlNode> {
+    let first = lines.get(*position)?;
+    if first.indent != indent {
+        return None;
+    }
+    if is_sequence_item(first.content) {
+        let mut items = Vec::new();
+        while let Some(line) = lines.get(*position) {
+            if line.indent > indent {
+                return None;
+            }
+            // A sequence under a key can be at the key's indent, so ends at the next key
+            if line.indent < indent || !is_sequence_item(line.content) {
+                break;
+            }
+            let item = line.content[1..].trim_start();
+            let item_indent = indent + line.content.len() - item.len();
+            *position += 1;
+            items.push(if item.is_empty() {
+                match lines.get(*position) {
+                    Some(line) if line.indent > indent => {
+                        parse_yaml_block(lines, position, line.indent)?
+                    }
+                    _ => YamlNode::Scalar("null".to_string()),
+                }
+            } else if split_key(item).is_some() {
+                // A mapping starting on the dash's line, carrying on at the key's indent
+                parse_yaml_mapping(lines, position, item_indent, Some(item))?
+            } else {
+                YamlNode::Scalar(parse_scalar(item)?)
+            });
+        }
+        return Some(YamlNode::Sequence(items));
+    }
+    parse_yaml_mapping(lines, position, indent, None)
+}
+
+/// Parses a mapping whose keys start at `indent`, the first of which may already have been
+/// read off a sequence item's line
+fn parse_yaml_mapping(
+    lines: &[YamlLine],
+    position: &mut usize,
+    indent: usize,
+    mut first: Option<&str>,
+) -> Option<YamlNode> {
+    let mut entries = Vec::new();
+    loop {
+        let content = match first.take() {
+            Some(content) => content,
+            None => match lines.get(*position) {
+                Some(line) if line.indent < indent => break,
+                Some(line) if line.indent == indent => {
+                    *position += 1;
+                    line.content
+                }
+                Some(_) => return None,
+                None => break,
+            },
+        };
+        let (key, value) = split_key(content)?;
+        let value = match value {
+            "" => parse_yaml_nested(lines, position, indent)?,
+            "|" | ">" | "|-" | ">-" => YamlNode::Block(
+                block_indicator(value),
+                parse_block_scalar(lines, position, indent)?,
+            ),
+            _ => YamlNode::Scalar(parse_scalar(value)?),
+        };
+        entries.push((key.to_string(), value));
+    }
+    Some(YamlNode::Mapping(entries))
+}
+
+/// Parses the value of a key or dash with nothing after it: a block indented further, a
+/// sequence at the same indent under a key, or nothing at all
+fn parse_yaml_nested(lines: &[YamlLine], position: &mut usize, indent: usize) -> Option<YamlNode> {
+    match lines.get(*position) {
+        Some(line) if line.indent > indent => parse_yaml_block(lines, position, line.indent),
+        Some(line) if line.indent == indent && is_sequence_item(line.content) => {
+            parse_yaml_block(lines, position, indent)
+        }
+        _ => Some(YamlNode::Scalar("null".to_string())),
+    }
+}
+
+/// Collects the lines of a `|` or `>` block scalar, which are indented further than its key
+fn parse_block_scalar(lines: &[YamlLine], position: &mut usize, indent: usize) -> Option<String> {
+    let block_indent = lines
+        .get(*position)
+        .filter(|line| line.indent > indent)?
+        .indent;
+    let mut block = Vec::new();
+    while let Some(line) = lines.get(*position).filter(|line| line.indent > indent) {
+        block.push(format!(
+            "{}{}",
+            " ".repeat(line.indent.saturating_sub(block_indent)),
+            line.content
+        ));
+        *position += 1;
+    }
+    Some(block.join("\n"))
+}
+
+/// The indicator of a block scalar, kept so folded and literal blocks stay as they were
+fn block_indicator(value: &str) -> &'static str {
+    match value {
+        "|" => "|",
+        ">" => ">",
+        "|-" => "|-",
+        _ => ">-",
+    }
+}
+
+/// Whether the line is an item of a block sequence
+fn is_sequence_item(content: &str) -> bool {
+    content == "-" || content.starts_with("- ")
+}
+
+/// The key and the value of a `key: value` line, None if it isn't one
+fn split_key(content: &str) -> Option<(&str, &str)> {
+    let (key, value) = match content.strip_suffix(':') {
+        Some(key) if !key.contains(": ") => (key, ""),
+        _ => content.split_once(": ")?,
+    };
+    let quoted = key.len() >= 2
+        && ((key.starts_with('"') && key.ends_with('"'))
+            || (key.starts_with('\'') && key.ends_with('\'')));
+    let plain = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | '/' | '$'))
+        && !key.starts_with('-');
+    (quoted || plain).then(|| (key, value.trim()))
+}
+
+/// A scalar with any comment after it taken off, None if it is something this parser
+/// doesn't read or isn't valid YAML, such as an unclosed quote
+fn parse_scalar(value: &str) -> Option<String> {
+    if let Some(quote @ ('"' | '\'')) = value.chars().next() {
+        let close = value[1..].find(quote)? + 1;
+        let rest = value[close + 1..].trim();
+        return (rest.is_empty() || rest.starts_with('#')).then(|| value[..=close].to_string());
+    }
+    let value = value.split(" #").next().unwrap_or_default().trim_end();
+    let flow_or_anchor = value.starts_with(['[', '{', '&', '*', '!', '|', '>', '@', '`', '%']);
+    (!value.is_empty() && !flow_or_anchor && !value.contains(": ")).then(|| value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format_of(text: &str) -> Option<DataFormat> {
+        parse(text).map(|(format, _)| format)
+    }
+
+    #[test]
+    fn json_is_pretty_printed_in_order() {
+        let (format, pretty) =
+            parse(r#"{"user":"admin","roles":["read","write"],"meta":{},"key":"a,b:c"}"#).unwrap();
+        assert_eq!(format, DataFormat::Json);
+        assert_eq!(
+            pretty,
+            "{\n  \"user\": \"admin\",\n  \"roles\": [\n    \"read\",\n    \"write\"\n  ],\n  \"meta\": {},\n  \"key\": \"a,b:c\"\n}"
+        );
+    }
+
+    #[test]
+    fn trivial_or_broken_json_is_not_identified() {
+        assert!(format_of("{}").is_none());
+        assert!(format_of("[1]").is_none());
+        assert!(format_of(r#"{"a": 1,}"#).is_none());
+        assert!(format_of("[1, 2").is_none());
+        assert!(format_of("12345").is_none());
+    }
+
+    #[test]
+    fn xml_is_pretty_printed() {
+        let (format, pretty) =
+            parse(r#"<config><user name="admin"/><password>hunter2</password></config>"#).unwrap();
+        assert_eq!(format, DataFormat::Xml);
+        assert_eq!(
+            pretty,
+            "<config>\n  <user name=\"admin\"/>\n  <password>hunter2</password>\n</config>"
+        );
+    }
+
+    #[test]
+    fn malformed_xml_is_not_identified() {
+        assert!(format_of("<a><b>text</a></b>").is_none());
+        assert!(format_of("<a>one</a><b>two</b>").is_none());
+        assert!(format_of("<a x=\"1\">unclosed").is_none());
+        assert!(format_of("<b>hi</b>").is_none());
+        assert!(format_of("< not xml at all >").is_none());
+    }
+
+    #[test]
+    fn yaml_is_pretty_printed() {
+        let text = "---\ndatabase:\n    host: db.internal # primary\n    port: 5432\nusers:\n- name: admin\n  password: \"s3cret\"\n- guest\ncert: |\n    line one\n    line two\n";
+        let (format, pretty) = parse(text).unwrap();
+        assert_eq!(format, DataFormat::Yaml);
+        assert_eq!(
+            pretty,
+            "database:\n  host: db.internal\n  port: 5432\nusers:\n  - name: admin\n    password: \"s3cret\"\n  - guest\ncert: |\n  line one\n  line two"
+        );
+    }
+
+    #[test]
+    fn prose_is_not_yaml() {
+        assert!(format_of("Hello world, this is plain text").is_none());
+        assert!(format_of("Dear John: I hope you are well.\nBest wishes: Jane").is_none());
+        assert!(format_of("key: value").is_none());
+        assert!(format_of("a: b\n  c: d").is_none());
+        assert!(format_of("a: 'unclosed\nb: c").is_none());
+    }
+
+    #[test]
+    fn checker_reports_the_format() {
+        let result =
+            Checker::<StructuredDataChecker>::new().check("api_key: abc123\nregion: eu-west-1");
+        assert!(result.is_identified);
+        assert_eq!(result.description, "YAML document");
+        assert!(
+            !Checker::<StructuredDataChecker>::new()
+                .check("hello world")
+                .is_identified
+        );
+    }
+}
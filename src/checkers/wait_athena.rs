@@ -10,12 +10,12 @@ use log::trace;
 use crate::storage::wait_athena_storage;
 
 use super::{
-    checker_type::{Check, Checker},
-    english::EnglishChecker,
-    lemmeknow_checker::LemmeKnow,
-    password::PasswordChecker,
+    athena,
+    checker_type::{check_bytes, check_if_enabled, Check, Checker},
+    denylist, ensemble,
+    entropy::EntropyChecker,
+    magic_bytes,
     regex_checker::RegexChecker,
-    wordlist::WordlistChecker,
 };
 
 /// WaitAthena checker runs all other checkers and stores results for later display
@@ -33,7 +33,7 @@ impl Check for Checker<WaitAthena> {
             expected_runtime: 1.0,
             popularity: 1.0,
             lemmeknow_config: Identifier::default(),
-            sensitivity: Sensitivity::Medium, // Default to Medium sensitivity
+            sensitivity: get_config().sensitivity.into(),
             enhanced_detector: None,
             _phantom: std::marker::PhantomData,
         }
@@ -42,119 +42,45 @@ impl Check for Checker<WaitAthena> {
     fn check(&self, text: &str) -> CheckResult {
         let config = get_config();
 
+        // Degenerate and denylisted plaintexts are never accepted
+        if denylist::is_denied(text) {
+            trace!("WaitAthena rejected denylisted text: {:?}", text);
+            return CheckResult::new(self);
+        }
+
         // If regex is specified, only run the regex checker
         // operates exactly the same as athena
-        if config.regex.is_some() {
+        let verdict = if !config.regex_patterns().is_empty() {
             trace!("running regex");
             let regex_checker = Checker::<RegexChecker>::new().with_sensitivity(self.sensitivity);
-            let regex_result = regex_checker.check(text);
-            if regex_result.is_identified {
-                let mut check_res = CheckResult::new(&regex_checker);
-                check_res.is_identified = true; // No human checker involvement
-                check_res.text = regex_result.text;
-                check_res.description = regex_result.description;
-
-                // Store the result instead of returning immediately
-                wait_athena_storage::add_plaintext_result(
-                    check_res.text.clone(),
-                    check_res.description.clone(),
-                    regex_checker.name.to_string(),
-                    "RegexChecker".to_string(),
-                );
-
-                // Continue checking by returning the result
-                return check_res;
-            }
+            let regex_result = check_if_enabled(&regex_checker, text);
+            regex_result.is_identified.then_some(regex_result)
         } else {
-            // Run wordlist checker first if a wordlist is provided
-            if config.wordlist.is_some() {
-                trace!("running wordlist checker");
-                let wordlist_checker =
-                    Checker::<WordlistChecker>::new().with_sensitivity(self.sensitivity);
-                let wordlist_result = wordlist_checker.check(text);
-                if wordlist_result.is_identified {
-                    let mut check_res = CheckResult::new(&wordlist_checker);
-                    check_res.is_identified = true; // No human checker involvement
-                    check_res.text = wordlist_result.text;
-                    check_res.description = wordlist_result.description;
-
-                    // Store the result instead of returning immediately
-                    wait_athena_storage::add_plaintext_result(
-                        check_res.text.clone(),
-                        check_res.description.clone(),
-                        wordlist_checker.name.to_string(),
-                        "WordlistChecker".to_string(),
-                    );
-
-                    // Continue checking by returning the result
-                    return check_res;
-                }
-            }
-
-            // In Ciphey if the user uses the regex checker all the other checkers turn off
-            // This is because they are looking for one specific bit of information so will not want the other checkers
-            let lemmeknow = Checker::<LemmeKnow>::new().with_sensitivity(self.sensitivity);
-            let lemmeknow_result = lemmeknow.check(text);
-            if lemmeknow_result.is_identified {
-                let mut check_res = CheckResult::new(&lemmeknow);
-                check_res.is_identified = true; // No human checker involvement
-                check_res.text = lemmeknow_result.text;
-                check_res.description = lemmeknow_result.description;
-
-                // Store the result instead of returning immediately
-                wait_athena_storage::add_plaintext_result(
-                    check_res.text.clone(),
-                    check_res.description.clone(),
-                    lemmeknow.name.to_string(),
-                    "LemmeKnow".to_string(),
-                );
-
-                // Continue checking by returning the result
-                return check_res;
-            }
-
-            let password = Checker::<PasswordChecker>::new().with_sensitivity(self.sensitivity);
-            let password_result = password.check(text);
-            if password_result.is_identified {
-                let mut check_res = CheckResult::new(&password);
-                check_res.is_identified = true; // No human checker involvement
-                check_res.text = password_result.text;
-                check_res.description = password_result.description;
-
-                // Store the result instead of returning immediately
-                wait_athena_storage::add_plaintext_result(
-                    check_res.text.clone(),
-                    check_res.description.clone(),
-                    password.name.to_string(),
-                    "PasswordChecker".to_string(),
-                );
-
-                // Continue checking by returning the result
-                return check_res;
-            }
-
-            let english = Checker::<EnglishChecker>::new().with_sensitivity(self.sensitivity);
-            let english_result = english.check(text);
-            if english_result.is_identified {
-                let mut check_res = CheckResult::new(&english);
-                check_res.is_identified = true; // No human checker involvement
-                check_res.text = english_result.text;
-                check_res.description = english_result.description;
-
-                // Store the result instead of returning immediately
-                wait_athena_storage::add_plaintext_result(
-                    check_res.text.clone(),
-                    check_res.description.clone(),
-                    english.name.to_string(),
-                    "EnglishChecker".to_string(),
-                );
-
-                // Continue checking by returning the result
-                return check_res;
-            }
+            ensemble::rank_verdicts(athena::sub_checker_results(self.sensitivity, text))
+        };
+        if let Some(mut check_res) =
+            verdict.filter(|verdict| ensemble::meets_min_confidence(verdict, config.min_confidence))
+        {
+            check_res.text = text.to_string();
+            check_res.is_identified = true; // No human checker involvement
+
+            // Store the result instead of returning immediately
+            wait_athena_storage::add_plaintext_result(
+                check_res.text.clone(),
+                check_res.description.clone(),
+                check_res.checker_name.to_string(),
+                check_res.checker_name.replace(' ', ""),
+            );
+
+            // Continue checking by returning the result
+            return check_res;
         }
 
-        CheckResult::new(self)
+        // Nothing identified the text, but its scores still let the search rank it
+        let entropy = Checker::<EntropyChecker>::new().with_sensitivity(self.sensitivity);
+        let mut unidentified = CheckResult::new(self);
+        unidentified.scores = check_if_enabled(&entropy, text).scores;
+        unidentified
     }
 
     fn with_sensitivity(mut self, sensitivity: Sensitivity) -> Self {
@@ -167,6 +93,23 @@ impl Check for Checker<WaitAthena> {
     }
 }
 
+impl Checker<WaitAthena> {
+    /// Checks a decoded value which may not be text, storing files such as ZIP archives
+    /// Anything else is checked as text if it is UTF-8, see [`check_bytes`].
+    pub fn check_bytes(&self, bytes: &[u8]) -> CheckResult {
+        if let Some(file_result) = magic_bytes::finished_file(bytes) {
+            wait_athena_storage::add_plaintext_result(
+                file_result.text.clone(),
+                file_result.description.clone(),
+                file_result.checker_name.to_string(),
+                "MagicBytesChecker".to_string(),
+            );
+            return file_result;
+        }
+        check_bytes(self, bytes)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
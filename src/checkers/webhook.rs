@@ -0,0 +1,371 @@
+//! Asks the human checker's questions through a webhook, for servers nobody sits at
+//! Each question is POSTed to `human_checker_webhook`. A generic endpoint may answer in
+//! its response; Slack and Discord only say they got the message, so the question carries
+//! approve and reject links to `human_checker_webhook_answer_url`, where ciphey listens
+//! for whoever follows one.
+//!
+//! | Request                   | What it does                                  |
+//! |---------------------------|-----------------------------------------------|
+//! | `GET /answers/<id>/approve` | Says the text of question `id` is the plaintext |
+//! | `GET /answers/<id>/reject`  | Says it isn't                                 |
+
+use std::collections::HashMap;
+use std::io::BufReader;
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::{channel, Sender};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+use crate::checkers::checker_result::CheckResult;
+use crate::config::{get_config, WebhookFormat};
+use crate::events::shorten;
+use crate::server::{read_request, write_response, HttpError, Request, Response};
+
+/// How long whoever follows a link may take to send their request
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The questions waiting for someone to follow one of their links, by their id
+static WAITING: Mutex<Option<HashMap<String, Sender<bool>>>> = Mutex::new(None);
+
+/// Whether ciphey is listening for answers, or why it couldn't
+static LISTENING: OnceLock<Result<(), String>> = OnceLock::new();
+
+/// Locks the questions waiting for an answer, recovering them if a thread panicked
+fn lock_waiting() -> std::sync::MutexGuard<'static, Option<HashMap<String, Sender<bool>>>> {
+    WAITING
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+/// Asks whether the text the checkers found is the plaintext through the webhook
+/// Gives the answer, or None if nobody answered within `human_checker_webhook_timeout`.
+///
+/// # Errors
+/// Returns why the question couldn't be asked, such as the webhook being unreachable.
+pub fn ask(check: &CheckResult) -> Result<Option<bool>, String> {
+    let config = get_config();
+    let id = uuid::Uuid::new_v4().to_string();
+    let answer_url = config
+        .human_checker_webhook_answer_url
+        .trim_end_matches('/');
+    let answers = if answer_url.is_empty() {
+        None
+    } else {
+        LISTENING.get_or_init(|| listen(answer_url)).clone()?;
+        let (sender, receiver) = channel();
+        lock_waiting()
+            .get_or_insert_with(HashMap::new)
+            .insert(id.clone(), sender);
+        Some(receiver)
+    };
+    let links = answers.is_some().then(|| Links::new(answer_url, &id));
+    let timeout = (config.human_checker_webhook_timeout > 0)
+        .then(|| Duration::from_secs(config.human_checker_webhook_timeout.into()));
+
+    let message = payload(
+        config.human_checker_webhook_format,
+        check,
+        &id,
+        links.as_ref(),
+    );
+    let answer = match post(&config.human_checker_webhook, &message, timeout) {
+        Ok(Some(body)) => match (verdict(&body), &answers) {
+            (Some(accepted), _) => Ok(Some(accepted)),
+            (None, Some(answers)) => Ok(match timeout {
+                Some(timeout) => answers.recv_timeout(timeout).ok(),
+                None => answers.recv().ok(),
+            }),
+            (None, None) => Err(String::from(
+                "The webhook's response neither approved nor rejected the text, and there's \
+                 no human_checker_webhook_answer_url to answer at",
+            )),
+        },
+        Ok(None) => Ok(None),
+        Err(e) => Err(e),
+    };
+    if let Some(waiting) = lock_waiting().as_mut() {
+        waiting.remove(&id);
+    }
+    answer
+}
+
+/// Where a question's answers are sent
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Links {
+    /// Following this says the text is the plaintext
+    approve: String,
+    /// Following this says it isn't
+    reject: String,
+}
+
+impl Links {
+    /// The links for question `id`, under `answer_url`
+    fn new(answer_url: &str, id: &str) -> Self {
+        Links {
+            approve: format!("{}/answers/{}/approve", answer_url, id),
+            reject: format!("{}/answers/{}/reject", answer_url, id),
+        }
+    }
+}
+
+/// The body POSTed to a webhook of `format` asking about `check`
+fn payload(format: WebhookFormat, check: &CheckResult, id: &str, links: Option<&Links>) -> Value {
+    let question = format!(
+        "ciphey thinks this is {}, is it the plaintext?",
+        check.description
+    );
+    match format {
+        WebhookFormat::Generic => json!({
+            "id": id,
+            "text": check.text,
+            "description": check.description,
+            "checker": check.checker_name,
+            "confidence": check.confidence,
+            "approve_url": links.map(|links| &links.approve),
+            "reject_url": links.map(|links| &links.reject),
+        }),
+        WebhookFormat::Slack => {
+            let answers = links.map_or_else(String::new, |links| {
+                format!("\n<{}|Approve> or <{}|Reject>", links.approve, links.reject)
+            });
+            json!({
+                "text": format!("{}\n```{}```{}", question, shorten(&check.text), answers),
+                // Slack fetching the links to preview them would answer the question
+                "unfurl_links": false,
+                "unfurl_media": false,
+            })
+        }
+        WebhookFormat::Discord => {
+            // Links in angle brackets aren't fetched for a preview
+            let answers = links.map_or_else(String::new, |links| {
+                format!(
+                    "\n[Approve](<{}>) or [Reject](<{}>)",
+                    links.approve, links.reject
+                )
+            });
+            json!({
+                "content": format!("{}\n```{}```{}", question, shorten(&check.text), answers),
+            })
+        }
+    }
+}
+
+/// POSTs `message` to the webhook, giving its response's body, or None if it didn't
+/// respond within `timeout`
+fn post(url: &str, message: &Value, timeout: Option<Duration>) -> Result<Option<String>, String> {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(timeout)
+        .build()
+        .map_err(|e| format!("Could not set up the webhook's client: {}", e))?;
+    let response = match client.post(url).json(message).send() {
+        Ok(response) => response,
+        Err(e) if e.is_timeout() => return Ok(None),
+        Err(e) => return Err(format!("Could not reach the webhook: {}", e)),
+    };
+    let status = response.status();
+    if !status.is_success() {
+        return Err(format!("The webhook answered {}", status));
+    }
+    match response.text() {
+        Ok(body) => Ok(Some(body)),
+        Err(e) if e.is_timeout() => Ok(None),
+        Err(e) => Err(format!("Could not read the webhook's response: {}", e)),
+    }
+}
+
+/// Whether a webhook's response approves or rejects the text, None if it does neither
+/// `{"accepted": true}`, `{"answer": "approve"}` and a body of just `yes` all approve.
+fn verdict(body: &str) -> Option<bool> {
+    let answer = match serde_json::from_str::<Value>(body.trim()) {
+        Ok(Value::Object(fields)) => {
+            if let Some(Value::Bool(accepted)) = fields.get("accepted") {
+                return Some(*accepted);
+            }
+            fields.get("answer")?.as_str()?.to_string()
+        }
+        Ok(Value::Bool(accepted)) => return Some(accepted),
+        Ok(Value::String(answer)) => answer,
+        _ => body.to_string(),
+    };
+    match answer.trim().to_ascii_lowercase().as_str() {
+        "approve" | "approved" | "accept" | "accepted" | "yes" | "y" | "true" => Some(true),
+        "reject" | "rejected" | "deny" | "denied" | "no" | "n" | "false" => Some(false),
+        _ => None,
+    }
+}
+
+/// Starts listening for answers on the port of `answer_url`, on every address
+fn listen(answer_url: &str) -> Result<(), String> {
+    let (scheme, rest) = answer_url
+        .split_once("://")
+        .ok_or_else(|| format!("{} isn't a URL", answer_url))?;
+    let authority = rest.split('/').next().unwrap_or(rest);
+    let port = match authority.rsplit_once(':') {
+        Some((_, port)) if !port.ends_with(']') => port
+            .parse::<u16>()
+            .map_err(|_| format!("The port of {} isn't a number", answer_url))?,
+        _ if scheme == "https" => 443,
+        _ => 80,
+    };
+    let listener = TcpListener::bind(("0.0.0.0", port))
+        .map_err(|e| format!("Can't listen for answers on port {}: {}", port, e))?;
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            std::thread::spawn(move || handle_connection(stream));
+        }
+    });
+    Ok(())
+}
+
+/// Reads the request someone following a link made, answers it and closes it
+fn handle_connection(stream: TcpStream) {
+    let _ = stream.set_read_timeout(Some(READ_TIMEOUT));
+    let Ok(mut writer) = stream.try_clone() else {
+        return;
+    };
+    let response = match read_request(&mut BufReader::new(stream), 0) {
+        Ok(request) => route(&request),
+        Err(HttpError::Bad { status, reason }) => Response::error(status, &reason),
+        Err(HttpError::Io(_)) => return,
+    };
+    if let Err(e) = write_response(&mut writer, &response) {
+        log::debug!("Could not answer a request: {}", e);
+    }
+}
+
+/// Hands the answer in a followed link to the question waiting for it
+fn route(request: &Request) -> Response {
+    let parts: Vec<&str> = request.path.trim_matches('/').split('/').collect();
+    let (id, accepted) = match parts.as_slice() {
+        ["answers", id, "approve"] => (*id, true),
+        ["answers", id, "reject"] => (*id, false),
+        _ => return Response::error(404, &format!("There is no {}", request.path)),
+    };
+    if request.method != "GET" && request.method != "POST" {
+        let mut response = Response::error(405, "Answers are sent with GET or POST");
+        response.headers.push(("Allow", String::from("GET, POST")));
+        return response;
+    }
+    let waiting = lock_waiting()
+        .as_mut()
+        .and_then(|waiting| waiting.remove(id));
+    match waiting.map(|question| question.send(accepted)) {
+        Some(Ok(())) => Response::json(200, &json!({ "answered": true, "accepted": accepted })),
+        _ => Response::error(
+            404,
+            "No question is waiting for that answer, it may have been answered or timed out",
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checkers::checker_type::{Check, Checker};
+    use crate::checkers::english::EnglishChecker;
+
+    /// A result as the English Checker gives it
+    fn english(text: &str) -> CheckResult {
+        let mut check = CheckResult::new(&Checker::<EnglishChecker>::new());
+        check.text = text.to_string();
+        check.description = String::from("English");
+        check
+    }
+
+    #[test]
+    fn responses_approve_reject_or_leave_it_open() {
+        assert_eq!(verdict("{\"accepted\": true}"), Some(true));
+        assert_eq!(verdict("{\"answer\": \"Reject\"}"), Some(false));
+        assert_eq!(verdict("yes\n"), Some(true));
+        assert_eq!(verdict("\"no\""), Some(false));
+        assert_eq!(verdict("ok"), None);
+        assert_eq!(verdict(""), None);
+        assert_eq!(verdict("{\"answer\": 3}"), None);
+    }
+
+    #[test]
+    fn questions_are_shaped_for_their_webhook() {
+        let check = english("hello there");
+        let links = Links::new("http://ciphey.example.com:8181", "abc");
+        let generic = payload(WebhookFormat::Generic, &check, "abc", Some(&links));
+        assert_eq!(generic["text"], "hello there");
+        assert_eq!(
+            generic["approve_url"],
+            "http://ciphey.example.com:8181/answers/abc/approve"
+        );
+        assert_eq!(
+            payload(WebhookFormat::Generic, &check, "abc", None)["reject_url"],
+            Value::Null
+        );
+
+        let slack = payload(WebhookFormat::Slack, &check, "abc", Some(&links));
+        let message = slack["text"].as_str().unwrap();
+        assert!(message.contains("```hello there```"));
+        assert!(message.contains("|Reject>"));
+        assert_eq!(slack["unfurl_links"], false);
+
+        let discord = payload(WebhookFormat::Discord, &check, "abc", Some(&links));
+        assert!(discord["content"]
+            .as_str()
+            .unwrap()
+            .contains("[Approve](<http://ciphey.example.com:8181/answers/abc/approve>)"));
+    }
+
+    #[test]
+    fn following_a_link_answers_its_question() {
+        let (sender, receiver) = channel();
+        lock_waiting()
+            .get_or_insert_with(HashMap::new)
+            .insert(String::from("followed"), sender);
+        let request = |method: &str, path: &str| Request {
+            method: method.to_string(),
+            path: path.to_string(),
+            headers: Vec::new(),
+            body: Vec::new(),
+        };
+        assert_eq!(
+            route(&request("GET", "/answers/followed/maybe")).status,
+            404
+        );
+        assert_eq!(
+            route(&request("DELETE", "/answers/followed/reject")).status,
+            405
+        );
+        assert_eq!(
+            route(&request("GET", "/answers/followed/reject")).status,
+            200
+        );
+        assert_eq!(receiver.try_recv(), Ok(false));
+        // Each question is only answered once
+        assert_eq!(
+            route(&request("GET", "/answers/followed/approve")).status,
+            404
+        );
+    }
+
+    #[test]
+    fn a_webhook_can_answer_in_its_response() {
+        let webhook = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hook", webhook.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let (stream, _) = webhook.accept().unwrap();
+            let mut writer = stream.try_clone().unwrap();
+            let request = read_request(&mut BufReader::new(stream), 1024 * 1024).unwrap();
+            let response = Response::json(200, &json!({ "accepted": true }));
+            write_response(&mut writer, &response).unwrap();
+            request
+        });
+        let message = payload(WebhookFormat::Generic, &english("hi"), "abc", None);
+        let body = post(&url, &message, Some(Duration::from_secs(10))).unwrap();
+        assert_eq!(body.as_deref().and_then(verdict), Some(true));
+        let request = server.join().unwrap();
+        assert_eq!(
+            (request.method.as_str(), request.path.as_str()),
+            ("POST", "/hook")
+        );
+        let sent: Value = serde_json::from_slice(&request.body).unwrap();
+        assert_eq!(sent["text"], "hi");
+    }
+}
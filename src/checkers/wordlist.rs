@@ -4,12 +4,94 @@ use crate::config::get_config;
 use gibberish_or_not::Sensitivity;
 use lemmeknow::Identifier;
 use log::trace;
-#[cfg(test)]
+use once_cell::sync::Lazy;
 use std::collections::HashSet;
 
 /// WordlistChecker checks if the input text exactly matches any word in a user-provided wordlist
+/// With `wordlist_contains` or `--wordlist-contains` it also accepts text containing an entry.
 pub struct WordlistChecker;
 
+/// Entries shorter than this aren't looked for inside texts, as they are inside almost all of them
+const MIN_CONTAINED_LENGTH: usize = 4;
+
+/// How the text matched the wordlist
+enum WordlistMatch<'a> {
+    /// The text is an entry
+    Exact,
+    /// The text contains this entry
+    Contains(&'a str),
+}
+
+/// The lengths of the configured wordlist's entries to look for inside texts, built once
+static CONTAINED_LENGTHS: Lazy<Vec<usize>> = Lazy::new(|| {
+    get_config()
+        .wordlist
+        .as_ref()
+        .map(contained_lengths)
+        .unwrap_or_default()
+});
+
+/// The byte lengths of the entries long enough to be looked for inside texts, longest first
+/// Looking up every slice of the text with one of these lengths finds contained entries
+/// without building anything bigger than the wordlist.
+fn contained_lengths(wordlist: &HashSet<String>) -> Vec<usize> {
+    let mut lengths: Vec<usize> = wordlist
+        .iter()
+        .filter(|entry| entry.chars().count() >= MIN_CONTAINED_LENGTH)
+        .map(String::len)
+        .collect();
+    lengths.sort_unstable_by(|a, b| b.cmp(a));
+    lengths.dedup();
+    lengths
+}
+
+/// How the text matches the wordlist, looking inside it for entries of these lengths
+fn find_match<'a>(
+    text: &'a str,
+    wordlist: &HashSet<String>,
+    lengths: &[usize],
+) -> Option<WordlistMatch<'a>> {
+    if wordlist.contains(text) {
+        return Some(WordlistMatch::Exact);
+    }
+    text.char_indices().find_map(|(start, _)| {
+        lengths
+            .iter()
+            .filter_map(|length| text.get(start..start + length))
+            .find(|slice| wordlist.contains(*slice))
+            .map(WordlistMatch::Contains)
+    })
+}
+
+impl Checker<WordlistChecker> {
+    /// The result of checking the text, given how it matched the wordlist
+    fn result_for(&self, text: &str, matched: Option<WordlistMatch>) -> CheckResult {
+        let mut result = CheckResult::new(self);
+        match matched {
+            Some(WordlistMatch::Exact) => {
+                trace!("Found exact match in wordlist for: {}", text);
+                result.is_identified = true;
+                result.confidence = 1.0;
+                result.description = "Text matches an entry in the provided wordlist".to_string();
+            }
+            Some(WordlistMatch::Contains(entry)) => {
+                trace!("Found wordlist entry {:?} in: {}", entry, text);
+                result.is_identified = true;
+                // A longer text containing an entry is a little less certain than an exact match
+                result.confidence = 0.9;
+                result.description =
+                    format!("Text contains {entry:?}, an entry in the provided wordlist");
+            }
+            None => {
+                trace!("No match found in wordlist for: {}", text);
+                return result;
+            }
+        }
+        result.text = text.to_string();
+        result
+    }
+}
+
 impl Check for Checker<WordlistChecker> {
     fn new() -> Self {
         Checker {
@@ -33,26 +115,15 @@ impl Check for Checker<WordlistChecker> {
         // Only run this checker if a wordlist is provided
         if let Some(wordlist) = &config.wordlist {
             trace!("Running wordlist checker with {} entries", wordlist.len());
-
-            // Perform exact matching against the wordlist
-            let is_match = wordlist.contains(text);
-
-            if is_match {
-                trace!("Found exact match in wordlist for: {}", text);
-                let mut result = CheckResult::new(self);
-                result.is_identified = true;
-                result.text = text.to_string();
-                result.description =
-                    "text which matches an entry in the provided wordlist".to_string();
-                return result;
-            }
-
-            trace!("No match found in wordlist for: {}", text);
-        } else {
-            trace!("Wordlist checker skipped - no wordlist provided");
+            let lengths: &[usize] = if config.wordlist_contains {
+                &CONTAINED_LENGTHS
+            } else {
+                &[]
+            };
+            return self.result_for(text, find_match(text, wordlist, lengths));
         }
 
-        // No match found or no wordlist provided
+        trace!("Wordlist checker skipped - no wordlist provided");
         CheckResult::new(self)
     }
 
@@ -75,23 +146,13 @@ impl Checker<WordlistChecker> {
     /// Check with a directly provided wordlist (for testing)
     fn check_with_wordlist(&self, text: &str, wordlist: &HashSet<String>) -> CheckResult {
         trace!("Running wordlist checker with {} entries", wordlist.len());
+        self.result_for(text, find_match(text, wordlist, &[]))
+    }
 
-        // Perform exact matching against the wordlist
-        let is_match = wordlist.contains(text);
-
-        if is_match {
-            trace!("Found exact match in wordlist for: {}", text);
-            let mut result = CheckResult::new(self);
-            result.is_identified = true;
-            result.text = text.to_string();
-            result.description = "Text matches an entry in the provided wordlist".to_string();
-            return result;
-        }
-
-        trace!("No match found in wordlist for: {}", text);
-
-        // No match found
-        CheckResult::new(self)
+    /// Check with a directly provided wordlist, also looking for entries inside the text
+    fn check_containing(&self, text: &str, wordlist: &HashSet<String>) -> CheckResult {
+        let lengths = contained_lengths(wordlist);
+        self.result_for(text, find_match(text, wordlist, &lengths))
     }
 }
 
@@ -147,4 +208,33 @@ mod tests {
         );
         assert!(!result.is_identified);
     }
+
+    #[test]
+    fn test_wordlist_contains() {
+        let wordlist: HashSet<String> = ["flag{found_it}", "hunter2", "abc"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        let checker = Checker::<WordlistChecker>::new();
+
+        let result = checker.check_containing("the flag is flag{found_it}!", &wordlist);
+        assert!(result.is_identified);
+        assert!(result.description.contains("flag{found_it}"));
+
+        // Exact matching doesn't look inside the text
+        assert!(
+            !checker
+                .check_with_wordlist("my password is hunter2", &wordlist)
+                .is_identified
+        );
+        assert!(
+            checker
+                .check_containing("my password is hunter2", &wordlist)
+                .is_identified
+        );
+
+        // Entries this short would be inside almost anything
+        assert!(!checker.check_containing("abcdef", &wordlist).is_identified);
+        assert!(checker.check_containing("abc", &wordlist).is_identified);
+    }
 }
@@ -0,0 +1,280 @@
+//! Cracks every input in a file with `ciphey batch`, such as strings pulled out of a log dump
+//! The inputs are one on each line, or JSON Lines objects with an `input` field and an
+//! optional `id` which is copied to the result. They are cracked in one process with
+//! [`crate::batch::perform_batch_cracking`], so the cache, the search threads and what
+//! earlier inputs decoded to are shared between them, and the results are written as JSON
+//! Lines or CSV in the same order.
+
+use std::fs;
+use std::io::Read;
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::batch::{perform_batch_cracking, BatchItem};
+use crate::cli::json_output::JsonResult;
+use crate::cli_pretty_printing::decoded_path;
+use crate::config::get_config_file_into_struct;
+use crate::storage::transfer::quote_csv_field;
+
+/// How the inputs of a batch are written
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum BatchInputFormat {
+    /// One input on each line, blank lines are skipped
+    #[default]
+    Lines,
+    /// One JSON object on each line, such as `{"id": 7, "input": "aGk="}`
+    Jsonl,
+}
+
+/// How the results of a batch are written
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum BatchOutputFormat {
+    /// One JSON object on each line, as `--output-format json` prints for a single input
+    #[default]
+    Jsonl,
+    /// Comma-separated values with a header
+    Csv,
+}
+
+/// One input of a batch read from JSON Lines
+#[derive(Debug, Deserialize)]
+struct JsonlInput {
+    /// Anything identifying the input, copied to its result
+    #[serde(default)]
+    id: Option<serde_json::Value>,
+    /// The text to crack
+    input: String,
+}
+
+/// An input of a batch and what identifies it
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchInput {
+    /// What identifies the input, copied from JSON Lines input
+    pub id: Option<serde_json::Value>,
+    /// The text to crack
+    pub input: String,
+}
+
+/// One line of JSON Lines output
+#[derive(Debug, Serialize)]
+struct BatchJsonLine<'a> {
+    /// What identifies the input, if it was given one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<&'a serde_json::Value>,
+    /// Everything found cracking the input
+    #[serde(flatten)]
+    result: JsonResult,
+    /// Whether it was answered by an earlier input of the batch instead of searched for
+    deduplicated: bool,
+}
+
+/// The columns of CSV output
+const CSV_COLUMNS: [&str; 9] = [
+    "id",
+    "input",
+    "outcome",
+    "plaintext",
+    "decoders",
+    "checker",
+    "confidence",
+    "duration_ms",
+    "deduplicated",
+];
+
+/// Reads the inputs of a batch
+///
+/// # Errors
+/// Returns an error message naming the line if JSON Lines input isn't an object with an
+/// `input` field.
+pub fn parse_batch_input(
+    contents: &str,
+    format: BatchInputFormat,
+) -> Result<Vec<BatchInput>, String> {
+    let lines = contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+    match format {
+        BatchInputFormat::Lines => Ok(lines
+            .map(|(_, line)| BatchInput {
+                id: None,
+                input: line.to_string(),
+            })
+            .collect()),
+        BatchInputFormat::Jsonl => lines
+            .map(|(number, line)| {
+                let parsed: JsonlInput = serde_json::from_str(line)
+                    .map_err(|e| format!("Line {} isn't a batch input: {}", number + 1, e))?;
+                Ok(BatchInput {
+                    id: parsed.id,
+                    input: parsed.input,
+                })
+            })
+            .collect(),
+    }
+}
+
+/// Writes the results of a batch, one for each input in the same order
+pub fn render_batch_results(
+    inputs: &[BatchInput],
+    items: &[BatchItem],
+    format: BatchOutputFormat,
+) -> String {
+    let rows = inputs.iter().zip(items);
+    match format {
+        BatchOutputFormat::Jsonl => rows
+            .map(|(input, item)| {
+                let line = BatchJsonLine {
+                    id: input.id.as_ref(),
+                    result: JsonResult::new(
+                        &item.input,
+                        item.result.as_ref(),
+                        item.outcome,
+                        item.duration,
+                        &[],
+                    ),
+                    deduplicated: item.deduplicated,
+                };
+                serde_json::to_string(&line).unwrap_or_else(|_| String::from("{}")) + "\n"
+            })
+            .collect(),
+        BatchOutputFormat::Csv => {
+            let mut csv = CSV_COLUMNS.join(",");
+            csv.push('\n');
+            for (input, item) in rows {
+                let accepted = item.result.as_ref().and_then(|result| result.path.last());
+                let fields = [
+                    input.id.as_ref().map_or_else(String::new, |id| match id {
+                        serde_json::Value::String(id) => id.clone(),
+                        id => id.to_string(),
+                    }),
+                    item.input.clone(),
+                    item.outcome.to_string(),
+                    item.result
+                        .as_ref()
+                        .and_then(|result| result.text.first().cloned())
+                        .unwrap_or_default(),
+                    item.result.as_ref().map(decoded_path).unwrap_or_default(),
+                    accepted
+                        .map(|step| step.checker_name.to_string())
+                        .unwrap_or_default(),
+                    accepted
+                        .map(|step| step.confidence.to_string())
+                        .unwrap_or_default(),
+                    item.duration.as_millis().to_string(),
+                    item.deduplicated.to_string(),
+                ];
+                let fields: Vec<String> =
+                    fields.iter().map(|field| quote_csv_field(field)).collect();
+                csv.push_str(&fields.join(","));
+                csv.push('\n');
+            }
+            csv
+        }
+    }
+}
+
+/// Cracks every input in `file`, or stdin if it is `-`, writing the results to `output`
+/// or stdout
+///
+/// # Errors
+/// Returns an error message if the inputs can't be read or parsed, there are too many of
+/// them, or the results can't be written.
+pub fn run_batch(
+    file: &str,
+    input_format: BatchInputFormat,
+    output_format: BatchOutputFormat,
+    output: Option<&str>,
+    timeout: Option<u32>,
+) -> Result<(), String> {
+    let contents = if file == "-" {
+        let mut contents = String::new();
+        std::io::stdin()
+            .read_to_string(&mut contents)
+            .map_err(|e| format!("Can't read stdin: {}", e))?;
+        contents
+    } else {
+        fs::read_to_string(file).map_err(|e| format!("Can't read '{}': {}", file, e))?
+    };
+    let inputs = parse_batch_input(&contents, input_format)?;
+
+    let mut config = get_config_file_into_struct();
+    if let Some(timeout) = timeout {
+        config.timeout = timeout;
+    }
+    // Only the results are written to stdout
+    config.api_mode = true;
+    let texts: Vec<&str> = inputs.iter().map(|input| input.input.as_str()).collect();
+    let items = perform_batch_cracking(&texts, config).map_err(|e| e.to_string())?;
+
+    let rendered = render_batch_results(&inputs, &items, output_format);
+    match output {
+        Some(path) => {
+            fs::write(path, rendered).map_err(|e| format!("Can't write '{}': {}", path, e))
+        }
+        None => {
+            print!("{}", rendered);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::history::RunOutcome;
+    use crate::DecoderResult;
+    use std::time::Duration;
+
+    /// A batch item which was cracked to "hi"
+    fn item(input: &str) -> BatchItem {
+        BatchItem {
+            input: input.to_string(),
+            result: Some(DecoderResult::from("hi")),
+            outcome: RunOutcome::Solved,
+            duration: Duration::from_millis(12),
+            deduplicated: false,
+        }
+    }
+
+    #[test]
+    fn lines_are_inputs_and_blank_lines_are_skipped() {
+        let inputs = parse_batch_input("aGk=\n\n  \nbXk=\r\n", BatchInputFormat::Lines).unwrap();
+        let texts: Vec<&str> = inputs.iter().map(|input| input.input.as_str()).collect();
+        assert_eq!(texts, ["aGk=", "bXk="]);
+    }
+
+    #[test]
+    fn jsonl_inputs_keep_their_ids() {
+        let inputs = parse_batch_input(
+            "{\"id\": 7, \"input\": \"aGk=\"}\n{\"input\": \"bXk=\"}",
+            BatchInputFormat::Jsonl,
+        )
+        .unwrap();
+        assert_eq!(inputs[0].id, Some(serde_json::json!(7)));
+        assert_eq!(inputs[1].input, "bXk=");
+        let error = parse_batch_input("{}\nnot json", BatchInputFormat::Jsonl).unwrap_err();
+        assert!(error.starts_with("Line 1"));
+    }
+
+    #[test]
+    fn results_are_written_as_jsonl_or_csv() {
+        let inputs = vec![BatchInput {
+            id: Some(serde_json::json!("log-1")),
+            input: "a,b".to_string(),
+        }];
+        let items = vec![item("a,b")];
+
+        let jsonl = render_batch_results(&inputs, &items, BatchOutputFormat::Jsonl);
+        let line: serde_json::Value = serde_json::from_str(jsonl.trim_end()).unwrap();
+        assert_eq!(line["id"], "log-1");
+        assert_eq!(line["plaintext"], "hi");
+        assert_eq!(line["deduplicated"], false);
+
+        let csv = render_batch_results(&inputs, &items, BatchOutputFormat::Csv);
+        let rows: Vec<&str> = csv.lines().collect();
+        assert_eq!(rows[0], CSV_COLUMNS.join(","));
+        assert!(rows[1].starts_with("log-1,\"a,b\",solved,hi,"));
+    }
+}
@@ -0,0 +1,92 @@
+//! Looks after the cache with `ciphey cache clear`, `prune`, `export` and `import`
+//! Inserts already keep the cache within the limits in the config, so pruning is only
+//! needed after lowering them. Clearing forgets every cached result, such as after
+//! upgrading ciphey to one which decodes more. Exports share a warmed cache with others.
+
+use std::fs;
+use std::path::Path;
+
+use crate::cli::db::open_configured_database;
+use crate::config::get_existing_config;
+use crate::storage::database::{self, CacheLimits, PrunedRows};
+use crate::storage::transfer::{self, ExportFormat, ExportTable};
+
+/// Describes how many rows were removed
+fn describe(pruned: &PrunedRows) -> String {
+    format!(
+        "Removed {} cached results and {} cached decodes",
+        pruned.results, pruned.edges
+    )
+}
+
+/// Removes every cached result and decode
+///
+/// # Errors
+/// Returns an error message if the database can't be opened or written to.
+pub fn run_clear() -> Result<String, String> {
+    open_configured_database()?;
+    let pruned =
+        database::clear_cache().map_err(|e| format!("Could not clear the cache: {}", e))?;
+    Ok(describe(&pruned))
+}
+
+/// Removes the cached results and decodes which are older or more than the config allows
+///
+/// # Errors
+/// Returns an error message if the database can't be opened or written to.
+pub fn run_prune() -> Result<String, String> {
+    open_configured_database()?;
+    let config = get_existing_config().unwrap_or_default();
+    let pruned = database::prune_cache(&CacheLimits::from_config(&config))
+        .map_err(|e| format!("Could not prune the cache: {}", e))?;
+    Ok(describe(&pruned))
+}
+
+/// Reads the cache and decoder statistics from the database as an export
+///
+/// # Errors
+/// Returns an error message if the database can't be opened or read.
+pub fn render_export(format: ExportFormat, table: ExportTable) -> Result<String, String> {
+    open_configured_database()?;
+    let export = transfer::read_export().map_err(|e| format!("Could not read the cache: {}", e))?;
+    Ok(transfer::render(&export, format, table))
+}
+
+/// Writes the cache and decoder statistics to a file
+///
+/// # Errors
+/// Returns an error message if the database can't be read or the file can't be written.
+pub fn run_export(format: ExportFormat, table: ExportTable, path: &str) -> Result<String, String> {
+    let export = render_export(format, table)?;
+    fs::write(path, export).map_err(|e| format!("Could not write {}: {}", path, e))?;
+    Ok(format!("Exported the cache to {}", path))
+}
+
+/// Adds the cached results and decoder statistics in an export to the database
+/// Without a format, files ending in `.csv` are read as CSV and anything else as JSON.
+///
+/// # Errors
+/// Returns an error message if the file can't be read, is from a newer ciphey, or the
+/// database can't be written to.
+pub fn run_import(path: &str, format: Option<ExportFormat>) -> Result<String, String> {
+    let format = format.unwrap_or_else(|| {
+        let is_csv = Path::new(path)
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("csv"));
+        if is_csv {
+            ExportFormat::Csv
+        } else {
+            ExportFormat::Json
+        }
+    });
+    let contents =
+        fs::read_to_string(path).map_err(|e| format!("Could not read {}: {}", path, e))?;
+    let export = transfer::parse(&contents, format)?;
+    open_configured_database()?;
+    let imported =
+        transfer::import(&export).map_err(|e| format!("Could not import {}: {}", path, e))?;
+    Ok(format!(
+        "Imported {} cached results and the statistics of {} decoders",
+        imported.results, imported.decoders
+    ))
+}
@@ -0,0 +1,64 @@
+//! Looks after the database with `ciphey db doctor`
+//! The doctor checks the database ciphey would use, repairs it if it is damaged
+//! and says what it found, so storage problems don't have to be debugged by hand.
+//! Other subcommands open the same database as decoding does with [`open_configured_database`].
+
+use crate::storage::{database, doctor, stats};
+
+/// Runs the doctor on the configured database and prints its report
+///
+/// # Errors
+/// Returns an error message if the database is damaged and couldn't be replaced.
+pub fn run_doctor() -> Result<(), String> {
+    if database::database_disabled() {
+        return Err(String::from(
+            "The database is turned off with --no-database, so there is nothing to check",
+        ));
+    }
+    let path = database::chosen_database_path()
+        .or_else(|| crate::config::get_configured_database_path().map(std::path::PathBuf::from))
+        .unwrap_or_else(database::get_database_path);
+    println!("Checking the database at {}", path.display());
+    let report = doctor::diagnose_and_repair(&path)?;
+    println!("{}", report);
+    if report.healthy {
+        Ok(())
+    } else {
+        Err(String::from("The database still can't be used"))
+    }
+}
+
+/// Opens the database decoding would use, refusing the in-memory fallback as nothing read
+/// from or written to it would be the user's
+///
+/// # Errors
+/// Returns an error message if the database is turned off, can't be opened or is unusable.
+pub fn open_configured_database() -> Result<(), String> {
+    if database::database_disabled() {
+        return Err(String::from(
+            "The database is turned off with --no-database, so there is nothing to use",
+        ));
+    }
+    if let Some(path) = crate::config::get_configured_database_path() {
+        database::use_database_path(path.into());
+    }
+    database::setup_database().map_err(|e| format!("Could not open the database: {}", e))?;
+    if let Some(reason) = database::fallback_reason() {
+        return Err(format!(
+            "{}. Run `ciphey db doctor` to diagnose and repair it.",
+            reason
+        ));
+    }
+    Ok(())
+}
+
+/// Prints what the database has recorded about earlier searches
+///
+/// # Errors
+/// Returns an error message if the database can't be opened or read.
+pub fn run_stats() -> Result<(), String> {
+    open_configured_database()?;
+    let report = stats::gather().map_err(|e| format!("Could not read the statistics: {}", e))?;
+    println!("{}", report);
+    Ok(())
+}
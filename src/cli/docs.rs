@@ -0,0 +1,175 @@
+//! Generates user-facing documentation straight from the decoder registry
+//! Run with `ciphey docs decoders --format markdown`
+//! Because the documentation is rendered from the registry it can never drift out of sync
+//! with the decoders ciphey actually runs.
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::filtration_system::get_registered_decoders;
+
+/// The formats the documentation can be rendered in
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum DocsFormat {
+    /// A Markdown document with a section per decoder
+    Markdown,
+    /// A JSON array with an object per decoder
+    Json,
+}
+
+/// An example input for a decoder and the plaintext it decodes to
+#[derive(Debug, Serialize)]
+pub struct DecoderExample {
+    /// The encoded input
+    pub input: String,
+    /// What the input decodes to
+    pub output: String,
+}
+
+/// Everything we document about a single decoder
+#[derive(Debug, Serialize)]
+pub struct DecoderDoc {
+    /// The name of the decoder
+    pub name: String,
+    /// The description of the decoder
+    pub description: String,
+    /// A link to read more about the encoding
+    pub link: String,
+    /// The tags the decoder is filtered by
+    pub tags: Vec<String>,
+    /// An example of the decoder in action, if the decoder provides one
+    pub example: Option<DecoderExample>,
+    /// The conditions an input has to meet before the decoder will decode it
+    pub guards: Vec<String>,
+}
+
+/// Collects the documentation for every decoder in the registry, in registry order
+pub fn get_decoder_docs() -> Vec<DecoderDoc> {
+    get_registered_decoders()
+        .components
+        .iter()
+        .map(|decoder| DecoderDoc {
+            name: decoder.get_name().to_string(),
+            description: decoder.get_description().to_string(),
+            link: decoder.get_link().to_string(),
+            tags: decoder
+                .get_tags()
+                .iter()
+                .map(|tag| tag.to_string())
+                .collect(),
+            example: decoder.get_example().map(|(input, output)| DecoderExample {
+                input: input.to_string(),
+                output: output.to_string(),
+            }),
+            guards: decoder
+                .get_guards()
+                .iter()
+                .map(|guard| guard.to_string())
+                .collect(),
+        })
+        .collect()
+}
+
+/// Renders the documentation for every decoder in the requested format
+///
+/// # Panics
+/// Panics if the documentation cannot be serialised to JSON, which cannot happen
+/// as it only contains strings.
+pub fn render_decoder_docs(format: DocsFormat) -> String {
+    let docs = get_decoder_docs();
+    match format {
+        DocsFormat::Markdown => render_markdown(&docs),
+        DocsFormat::Json => {
+            serde_json::to_string_pretty(&docs).expect("Decoder docs should serialise to JSON")
+        }
+    }
+}
+
+/// Renders the decoder documentation as a Markdown document
+fn render_markdown(docs: &[DecoderDoc]) -> String {
+    let mut out = String::new();
+    out.push_str("# Decoders\n\n");
+    out.push_str(
+        "This document is generated by `ciphey docs decoders --format markdown`. Do not edit it by hand.\n\n",
+    );
+
+    for doc in docs {
+        out.push_str(&format!("## {}\n\n", doc.name));
+        out.push_str(&format!("{}\n\n", doc.description));
+        out.push_str(&format!("- **Link:** <{}>\n", doc.link));
+        let tags: Vec<String> = doc.tags.iter().map(|tag| format!("`{}`", tag)).collect();
+        out.push_str(&format!("- **Tags:** {}\n\n", tags.join(", ")));
+
+        out.push_str("### Example\n\n");
+        match &doc.example {
+            Some(example) => {
+                out.push_str(&format!("Input:\n\n```text\n{}\n```\n\n", example.input));
+                out.push_str(&format!("Output:\n\n```text\n{}\n```\n\n", example.output));
+            }
+            None => out.push_str("No example available.\n\n"),
+        }
+
+        out.push_str("### Guard conditions\n\n");
+        if doc.guards.is_empty() {
+            out.push_str("None, the decoder attempts every input.\n\n");
+        } else {
+            for guard in &doc.guards {
+                out.push_str(&format!("- {}\n", guard));
+            }
+            out.push('\n');
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checkers::{
+        athena::Athena,
+        checker_type::{Check, Checker},
+        CheckerTypes,
+    };
+
+    #[test]
+    fn markdown_has_a_section_for_every_decoder() {
+        let markdown = render_decoder_docs(DocsFormat::Markdown);
+        for decoder in get_registered_decoders().components.iter() {
+            assert!(markdown.contains(&format!("## {}\n", decoder.get_name())));
+        }
+    }
+
+    #[test]
+    fn json_round_trips_every_decoder() {
+        let json = render_decoder_docs(DocsFormat::Json);
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            parsed.as_array().unwrap().len(),
+            get_registered_decoders().components.len()
+        );
+    }
+
+    #[test]
+    fn every_documented_example_decodes() {
+        let checker = CheckerTypes::CheckAthena(Checker::<Athena>::new());
+        for decoder in get_registered_decoders().components.iter() {
+            if let Some((input, output)) = decoder.get_example() {
+                let result = decoder.crack(input, &checker);
+                // Searches in other tests share the timer, and one about to time out cuts
+                // the slower crackers short
+                if result.timed_out {
+                    continue;
+                }
+                let decoded = result.unencrypted_text.unwrap_or_default();
+                assert!(
+                    decoded.iter().any(|text| text == output),
+                    "The documented example for {} decoded to {:?} instead of {:?}",
+                    decoder.get_name(),
+                    decoded,
+                    output
+                );
+            }
+        }
+    }
+}
@@ -41,7 +41,7 @@ pub struct ColorScheme {
 ///
 /// # Returns
 /// * `String` - The input text formatted in white color
-fn print_statement<T: Display>(text: T) -> String {
+pub(super) fn print_statement<T: Display>(text: T) -> String {
     text.to_string().white().to_string()
 }
 
@@ -52,7 +52,7 @@ fn print_statement<T: Display>(text: T) -> String {
 ///
 /// # Returns
 /// * `String` - The input text formatted in red color
-fn print_warning<T: Display>(text: T) -> String {
+pub(super) fn print_warning<T: Display>(text: T) -> String {
     text.to_string().red().to_string()
 }
 
@@ -63,7 +63,7 @@ fn print_warning<T: Display>(text: T) -> String {
 ///
 /// # Returns
 /// * `String` - The input text formatted in yellow color
-fn print_question<T: Display>(text: T) -> String {
+pub(super) fn print_question<T: Display>(text: T) -> String {
     text.to_string().yellow().to_string()
 }
 
@@ -74,7 +74,7 @@ fn print_question<T: Display>(text: T) -> String {
 ///
 /// # Returns
 /// * `String` - The input text formatted in green color
-fn print_success<T: Display>(text: T) -> String {
+pub(super) fn print_success<T: Display>(text: T) -> String {
     text.to_string().green().to_string()
 }
 
@@ -167,6 +167,9 @@ fn get_default_scheme() -> ColorScheme {
 ///
 /// # Returns
 /// * `HashMap<String, String>` - A mapping of role names to their RGB color values
+///
+/// # Panics
+/// Panics if stdout cannot be flushed while prompting the user.
 pub fn run_first_time_setup() -> HashMap<String, String> {
     println!(
         "\n{}",
@@ -437,7 +440,7 @@ pub fn run_first_time_setup() -> HashMap<String, String> {
 ///
 /// # Returns
 /// * `bool` - true for yes, false for no
-fn ask_yes_no_question(question: &str, default_yes: bool) -> bool {
+pub(super) fn ask_yes_no_question(question: &str, default_yes: bool) -> bool {
     // Only print the question if it's not empty (for formatted sequences)
     if !question.is_empty() {
         println!("\n{}", print_question(question));
@@ -0,0 +1,153 @@
+//! Shows the runs ciphey has recorded with `ciphey history`, and one of them with
+//! `ciphey replay`
+//! Replaying shows what the run found, how it got there and which decoders it spent its
+//! time on. With `--rerun` the run's input is cracked again with the config it ran with,
+//! to check whether ciphey still gets the same answer.
+
+use crate::cli::db::open_configured_database;
+use crate::cli_pretty_printing::decoded_path;
+use crate::config::Config;
+use crate::storage::database::{self, RunRow};
+use crate::storage::history;
+
+/// How many characters of inputs and plaintexts `ciphey history` shows
+const SHOWN_CHARS: usize = 40;
+
+/// A text on one line, escaping line breaks and cutting it short if it is long
+fn shorten(text: &str) -> String {
+    let escaped: String = text.escape_debug().collect();
+    if escaped.chars().count() > SHOWN_CHARS {
+        let cut: String = escaped.chars().take(SHOWN_CHARS - 1).collect();
+        format!("{}…", cut)
+    } else {
+        escaped
+    }
+}
+
+/// Describes the most recent runs, newest first, one on each line
+pub fn render_history(runs: &[RunRow]) -> String {
+    if runs.is_empty() {
+        return String::from(
+            "No runs have been recorded yet. Every text ciphey cracks is recorded here.",
+        );
+    }
+    runs.iter()
+        .map(|run| {
+            let plaintext = run
+                .plaintext
+                .as_deref()
+                .map_or_else(String::new, |plaintext| {
+                    format!(" → {}", shorten(plaintext))
+                });
+            format!(
+                "#{:<5} {}  {:<9} {:>7} ms  {}{}",
+                run.id,
+                run.timestamp,
+                run.outcome,
+                run.duration_ms,
+                shorten(&run.input),
+                plaintext
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Describes everything recorded about a run
+pub fn render_run(run: &RunRow) -> String {
+    let mut lines = vec![
+        format!(
+            "Run #{}, finished {} by ciphey {}",
+            run.id, run.timestamp, run.version
+        ),
+        format!("Input: {}", run.input),
+        format!("Outcome: {} in {} ms", run.outcome, run.duration_ms),
+    ];
+    if let Some(result) = history::recorded_result(run) {
+        lines.push(format!("Plaintext: {}", result.text[0]));
+        if !result.path.is_empty() {
+            lines.push(format!("Decoders used: {}", decoded_path(&result)));
+        }
+    }
+    let timings = history::decoder_timings(run);
+    if !timings.is_empty() {
+        lines.push(String::from("Decoders run:"));
+        for (decoder, timing) in timings {
+            lines.push(format!(
+                "  {:<24} {:>6} times {:>10.2} ms",
+                decoder,
+                timing.attempts,
+                timing.runtime_micros as f64 / 1000.0
+            ));
+        }
+    }
+    lines.join("\n")
+}
+
+/// Prints the most recent runs
+///
+/// # Errors
+/// Returns an error message if the database can't be opened or read.
+pub fn run_history(limit: usize) -> Result<(), String> {
+    open_configured_database()?;
+    let runs =
+        database::read_runs(limit).map_err(|e| format!("Could not read the history: {}", e))?;
+    println!("{}", render_history(&runs));
+    Ok(())
+}
+
+/// Prints what a run found, and with `rerun` cracks its input again with its config
+///
+/// # Errors
+/// Returns an error message if the database can't be read, there is no such run, or its
+/// config can't be used again.
+pub fn run_replay(id: i64, rerun: bool) -> Result<(), String> {
+    open_configured_database()?;
+    let run = database::read_run(id)
+        .map_err(|e| format!("Could not read the history: {}", e))?
+        .ok_or_else(|| format!("There is no run #{}. See `ciphey history` for the runs", id))?;
+    println!("{}", render_run(&run));
+    if !rerun {
+        return Ok(());
+    }
+
+    let mut config = Config::from_run_snapshot(&run.config)?;
+    config.skip_cache = true;
+    // Printed plainly, as colouring it would read the global config before the run's is set
+    println!("\nRunning #{} again", run.id);
+    match crate::perform_cracking(&run.input, config) {
+        Some(result) => {
+            let same = run.plaintext.as_ref() == result.text.first();
+            crate::cli_pretty_printing::program_exiting_successful_decoding(result);
+            if !same {
+                println!(
+                    "{}",
+                    crate::cli_pretty_printing::warning(&format!(
+                        "This isn't what run #{} found",
+                        run.id
+                    ))
+                );
+            }
+        }
+        None => crate::cli_pretty_printing::failed_to_decode(),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn long_inputs_are_shortened_onto_one_line() {
+        assert_eq!(shorten("hi\nthere"), "hi\\nthere");
+        let shortened = shorten(&"a".repeat(100));
+        assert_eq!(shortened.chars().count(), SHOWN_CHARS);
+        assert!(shortened.ends_with('…'));
+    }
+
+    #[test]
+    fn empty_histories_say_so() {
+        assert!(render_history(&[]).starts_with("No runs"));
+    }
+}
@@ -0,0 +1,89 @@
+//! Says what a text probably is with `ciphey identify`, without cracking it
+//! Each guess names the decoder for it, so it can be decoded by hand or given to
+//! `--only-decoders` to crack it with just that decoder.
+
+use clap::ValueEnum;
+
+use crate::identify::Identification;
+
+/// How the guesses are written
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum IdentifyFormat {
+    /// The guesses one on each line with how confident each is, for reading
+    #[default]
+    Text,
+    /// One JSON document with the entropy and every guess
+    Json,
+}
+
+/// Writes what the probes made of the text in the requested format
+///
+/// # Panics
+/// Panics if the identification cannot be serialised to JSON, which cannot happen as it
+/// only holds strings and numbers.
+pub fn render_identification(identification: &Identification, format: IdentifyFormat) -> String {
+    match format {
+        IdentifyFormat::Json => serde_json::to_string_pretty(identification)
+            .expect("The identification should serialise to JSON"),
+        IdentifyFormat::Text => render_text(identification),
+    }
+}
+
+/// Writes the guesses for reading, the most likely first
+fn render_text(identification: &Identification) -> String {
+    let mut lines = vec![format!(
+        "Entropy {:.2} bits per byte, {:.0}% printable",
+        identification.entropy,
+        identification.printable_ratio * 100.0
+    )];
+    if identification.guesses.is_empty() {
+        lines.push(String::from(
+            "Nothing ciphey knows of fits this text. Cracking it may still find something.",
+        ));
+        return lines.join("\n");
+    }
+    let width = identification
+        .guesses
+        .iter()
+        .map(|guess| guess.name.chars().count())
+        .max()
+        .unwrap_or(0);
+    lines.push(String::new());
+    for guess in &identification.guesses {
+        let decoder = guess
+            .decoder
+            .map_or_else(String::new, |decoder| format!(" [{}]", decoder));
+        lines.push(format!(
+            "{:>3.0}%  {:<width$}  {}{}",
+            guess.confidence * 100.0,
+            guess.name,
+            guess.reason,
+            decoder,
+            width = width
+        ));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::identify::identify;
+    use gibberish_or_not::Sensitivity;
+
+    #[test]
+    fn text_lists_the_guesses_most_likely_first_with_their_decoder() {
+        let identification = identify("aGVsbG8gd29ybGQ=", Sensitivity::Medium);
+        let text = render_identification(&identification, IdentifyFormat::Text);
+        let first = text.lines().nth(2).unwrap();
+        assert!(first.contains("Base64"), "{}", text);
+        assert!(first.ends_with("[Base64]"), "{}", text);
+
+        let json: serde_json::Value = serde_json::from_str(&render_identification(
+            &identification,
+            IdentifyFormat::Json,
+        ))
+        .unwrap();
+        assert_eq!(json["guesses"][0]["decoder"], "Base64");
+    }
+}
@@ -0,0 +1,166 @@
+//! Setup wizard for `ciphey init`
+//!
+//! Creates the config file, picks where the database lives, offers the optional
+//! wordlist and enhanced detection model, then runs a selftest to check ciphey can decode.
+//! `ciphey init --yes` skips every question and uses the defaults, downloading nothing.
+
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use super::first_run::{
+    ask_yes_no_question, print_question, print_statement, print_success, print_warning,
+};
+use crate::config::{config_from_setup_answers, get_config_file_path, save_config_to_file, Config};
+use crate::storage::database;
+
+/// The plaintext the selftest expects to get back
+const SELFTEST_PLAINTEXT: &str = "hello world this is the ciphey selftest";
+
+/// `SELFTEST_PLAINTEXT` in base64, which every install should be able to decode
+const SELFTEST_CIPHERTEXT: &str = "aGVsbG8gd29ybGQgdGhpcyBpcyB0aGUgY2lwaGV5IHNlbGZ0ZXN0";
+
+/// Runs the setup wizard, asking nothing when `yes` is set
+///
+/// Any existing config file is backed up to `config.toml.bak` before being replaced.
+///
+/// # Errors
+/// Returns an error message if the user keeps their existing config,
+/// the old config can't be backed up, or the selftest fails.
+pub fn run_init(yes: bool) -> Result<(), String> {
+    let config_path = get_config_file_path();
+
+    if config_path.exists() {
+        if !yes
+            && !ask_yes_no_question(
+                &format!(
+                    "There is already a config file at {}. Do you want to replace it?",
+                    config_path.display()
+                ),
+                false,
+            )
+        {
+            return Err("Kept the existing config file".to_string());
+        }
+        let backup_path = config_path.with_extension("toml.bak");
+        std::fs::copy(&config_path, &backup_path)
+            .map_err(|e| format!("Could not back up the existing config file: {}", e))?;
+        println!(
+            "{}",
+            print_statement(format!(
+                "Backed up the old config file to {}",
+                backup_path.display()
+            ))
+        );
+    }
+
+    let answers: HashMap<String, String> = if yes {
+        HashMap::new()
+    } else {
+        let mut answers = super::run_first_time_setup();
+        if let Some(path) = ask_database_path() {
+            answers.insert("database_path".to_string(), path);
+        }
+        answers
+    };
+
+    let config = config_from_setup_answers(&answers);
+    save_config_to_file(&config, &config_path);
+    println!(
+        "{}",
+        print_success(format!("Saved your config to {}", config_path.display()))
+    );
+
+    run_selftest(config)?;
+    println!(
+        "{}",
+        print_success("Selftest passed, ciphey is ready to go!")
+    );
+    Ok(())
+}
+
+/// Asks where the database should go, None keeps the default location
+fn ask_database_path() -> Option<String> {
+    let default_path = database::get_database_path();
+    println!(
+        "\n{}",
+        print_statement("Where should ciphey keep its database of cached results and settings?")
+    );
+    print!(
+        "{}",
+        print_question(format!(
+            "Path (leave empty for {}): ",
+            default_path.display()
+        ))
+    );
+    io::stdout().flush().ok()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input).ok()?;
+    parse_database_answer(&input)
+}
+
+/// Turns the answer to the database question into a path, an empty answer means the default
+fn parse_database_answer(input: &str) -> Option<String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return None;
+    }
+    let path = match input.strip_prefix("~/") {
+        Some(rest) => dirs::home_dir()
+            .map(|home| home.join(rest))
+            .unwrap_or_else(|| PathBuf::from(input)),
+        None => PathBuf::from(input),
+    };
+    Some(path.display().to_string())
+}
+
+/// Decodes a known base64 string with the new config to check everything works
+fn run_selftest(mut config: Config) -> Result<(), String> {
+    println!("{}", print_statement("Running the selftest..."));
+    config.human_checker_on = false;
+    config.top_results = false;
+    config.api_mode = true;
+
+    match crate::perform_cracking(SELFTEST_CIPHERTEXT, config) {
+        Some(result) if result.text.iter().any(|text| text == SELFTEST_PLAINTEXT) => Ok(()),
+        Some(result) => {
+            println!(
+                "{}",
+                print_warning(format!("The selftest decoded to {:?}", result.text))
+            );
+            Err("The selftest decoded the wrong plaintext".to_string())
+        }
+        None => Err("The selftest could not decode its base64 string".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::{engine::general_purpose, Engine as _};
+
+    #[test]
+    fn selftest_ciphertext_is_the_plaintext_in_base64() {
+        assert_eq!(
+            general_purpose::STANDARD.encode(SELFTEST_PLAINTEXT),
+            SELFTEST_CIPHERTEXT
+        );
+    }
+
+    #[test]
+    fn empty_database_answer_keeps_the_default() {
+        assert_eq!(parse_database_answer("  \n"), None);
+        assert_eq!(
+            parse_database_answer("/tmp/ciphey.sqlite\n"),
+            Some("/tmp/ciphey.sqlite".to_string())
+        );
+    }
+
+    #[test]
+    fn database_answer_expands_the_home_directory() {
+        let path = parse_database_answer("~/ciphey.sqlite").unwrap();
+        assert!(!path.starts_with('~'));
+        assert!(path.ends_with("ciphey.sqlite"));
+    }
+}
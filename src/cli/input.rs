@@ -0,0 +1,134 @@
+//! Reads the input to crack from `--text`, `--file` or stdin
+//! Files and stdin are read as bytes, so binary files such as a gzipped blob can be cracked
+//! as they are rather than having to be pasted as text. Input longer than
+//! `max_input_bytes` is refused instead of being read into memory.
+
+use std::fs::File;
+use std::io::Read;
+
+/// The most bytes of input read when the config doesn't say otherwise, 16 MiB
+pub const DEFAULT_MAX_INPUT_BYTES: usize = 16 * 1024 * 1024;
+
+/// Where the input to crack comes from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    /// Text given on the command line with `--text`
+    Text(String),
+    /// A file given with `--file`
+    File(String),
+    /// Whatever is piped into ciphey, or given with `--file -`
+    Stdin,
+}
+
+/// How input read from a file or stdin is trimmed before it is cracked
+/// Only input which is UTF-8 text is trimmed, binary input is always cracked as it is.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum InputTrim {
+    /// Keep the input exactly as it is
+    None,
+    /// Remove the line breaks at the end, which editors and `echo` add
+    #[default]
+    Newline,
+    /// Remove whitespace at both ends
+    Whitespace,
+}
+
+/// Reads at most `max_bytes` from `reader`, 0 meaning there is no limit
+///
+/// # Errors
+/// Returns an error message if it can't be read or is longer than `max_bytes`.
+fn read_limited(mut reader: impl Read, max_bytes: usize, what: &str) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    let result = if max_bytes == 0 {
+        reader.read_to_end(&mut bytes)
+    } else {
+        reader.take(max_bytes as u64 + 1).read_to_end(&mut bytes)
+    };
+    result.map_err(|e| format!("Can't read {}: {}", what, e))?;
+    if max_bytes != 0 && bytes.len() > max_bytes {
+        return Err(format!(
+            "{} is longer than {} bytes. Raise the limit with --max-input-bytes, or 0 for none",
+            what, max_bytes
+        ));
+    }
+    Ok(bytes)
+}
+
+/// Reads the input from where it comes from, trimming input from a file or stdin
+///
+/// # Errors
+/// Returns an error message if the input can't be read or is longer than `max_bytes`.
+pub fn read_input(
+    source: &InputSource,
+    trim: InputTrim,
+    max_bytes: usize,
+) -> Result<Vec<u8>, String> {
+    match source {
+        InputSource::Text(text) => read_limited(text.as_bytes(), max_bytes, "The text"),
+        InputSource::File(path) => {
+            let file =
+                File::open(path).map_err(|e| format!("Can't open the file '{}': {}", path, e))?;
+            let bytes = read_limited(file, max_bytes, &format!("The file '{}'", path))?;
+            Ok(trim_input(bytes, trim))
+        }
+        InputSource::Stdin => {
+            let bytes = read_limited(std::io::stdin().lock(), max_bytes, "stdin")?;
+            Ok(trim_input(bytes, trim))
+        }
+    }
+}
+
+/// Trims input the way `trim` says, if it is UTF-8 text
+pub fn trim_input(bytes: Vec<u8>, trim: InputTrim) -> Vec<u8> {
+    let Ok(text) = std::str::from_utf8(&bytes) else {
+        return bytes;
+    };
+    let trimmed = match trim {
+        InputTrim::None => text,
+        InputTrim::Newline => text.trim_end_matches(['\n', '\r']),
+        InputTrim::Whitespace => text.trim(),
+    };
+    if trimmed.len() == bytes.len() {
+        bytes
+    } else {
+        trimmed.as_bytes().to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_input_is_trimmed_as_asked() {
+        let input = b"  aGk=\r\n".to_vec();
+        assert_eq!(trim_input(input.clone(), InputTrim::None), input);
+        assert_eq!(trim_input(input.clone(), InputTrim::Newline), b"  aGk=");
+        assert_eq!(trim_input(input, InputTrim::Whitespace), b"aGk=");
+    }
+
+    #[test]
+    fn binary_input_is_never_trimmed() {
+        let gzip = vec![0x1f, 0x8b, 0x08, 0xff, b'\n'];
+        assert_eq!(trim_input(gzip.clone(), InputTrim::Whitespace), gzip);
+    }
+
+    #[test]
+    fn input_over_the_limit_is_refused() {
+        assert_eq!(read_limited(&b"abcd"[..], 4, "The text").unwrap(), b"abcd");
+        assert!(read_limited(&b"abcde"[..], 4, "The text")
+            .unwrap_err()
+            .contains("longer than 4 bytes"));
+        assert_eq!(read_limited(&b"abcde"[..], 0, "The text").unwrap().len(), 5);
+    }
+
+    #[test]
+    fn binary_files_are_read_as_they_are() {
+        let path = std::env::temp_dir().join("ciphey_binary_input_test.bin");
+        let bytes = vec![0x00, 0x9f, 0x92, 0x96, b'\n'];
+        std::fs::write(&path, &bytes).unwrap();
+        let source = InputSource::File(path.to_string_lossy().into_owned());
+        assert_eq!(read_input(&source, InputTrim::Newline, 0).unwrap(), bytes);
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,187 @@
+//! Prints the result of cracking a text as JSON, for `--output-format json`
+//! Scripts get the input, how the run ended, the plaintext, every decode on the way to it
+//! with its key and how long it took, and the checker which confirmed the plaintext, all in
+//! one object on stdout instead of having to read the pretty printer's output.
+
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::storage::history::RunOutcome;
+use crate::{CrackResult, DecoderResult};
+
+/// One decode on the way to the plaintext
+#[derive(Debug, Serialize)]
+pub struct JsonStep {
+    /// The decoder which ran
+    pub decoder: &'static str,
+    /// The key the decoder recovered or was given, if it uses one
+    pub key: Option<String>,
+    /// The text the decoder decoded
+    pub input: String,
+    /// What the decoder decoded it to
+    pub output: Option<String>,
+    /// How long the decode took in milliseconds
+    /// None when it wasn't decoded in this run, such as when the result came from the cache.
+    pub runtime_ms: Option<f64>,
+}
+
+impl From<&CrackResult> for JsonStep {
+    fn from(step: &CrackResult) -> Self {
+        JsonStep {
+            decoder: step.decoder,
+            key: step.key.clone(),
+            input: step.encrypted_text.clone(),
+            output: step
+                .unencrypted_text
+                .as_ref()
+                .and_then(|texts| texts.first().cloned()),
+            runtime_ms: step.runtime.map(|runtime| runtime.as_secs_f64() * 1000.0),
+        }
+    }
+}
+
+/// A decode which looked most like plaintext when nothing was identified
+#[derive(Debug, Serialize)]
+pub struct JsonCandidate {
+    /// The decoded text
+    pub text: String,
+    /// How much like plaintext it is, from 0.0 to 1.0
+    pub score: f32,
+    /// The decodes which reached it, in order
+    pub path: Vec<JsonStep>,
+}
+
+/// Everything `--output-format json` prints about a run
+#[derive(Debug, Serialize)]
+pub struct JsonResult {
+    /// The text ciphey was asked to crack
+    pub input: String,
+    /// How the run ended: solved, cached, plaintext, unsolved or cancelled
+    pub outcome: &'static str,
+    /// The plaintext, None if none was found
+    pub plaintext: Option<String>,
+    /// The checker which confirmed the plaintext
+    pub checker: Option<&'static str>,
+    /// How confident the checker was that it is the plaintext, from 0.0 to 1.0
+    pub confidence: Option<f32>,
+    /// The decodes from the input to the plaintext, in order
+    pub path: Vec<JsonStep>,
+    /// How long the whole run took in milliseconds
+    pub duration_ms: f64,
+    /// The decodes most like plaintext, when no plaintext was found
+    pub candidates: Vec<JsonCandidate>,
+}
+
+impl JsonResult {
+    /// Describes a run which found `result`, or nothing along with the best `candidates`
+    pub fn new(
+        input: &str,
+        result: Option<&DecoderResult>,
+        outcome: RunOutcome,
+        duration: Duration,
+        candidates: &[(DecoderResult, f32)],
+    ) -> Self {
+        let accepted = result.and_then(|result| result.path.last());
+        JsonResult {
+            input: input.to_string(),
+            outcome: outcome.as_str(),
+            plaintext: result.and_then(|result| result.text.first().cloned()),
+            checker: accepted
+                .map(|step| step.checker_name)
+                .filter(|checker| !checker.is_empty()),
+            confidence: accepted.map(|step| step.confidence),
+            path: result
+                .map(|result| result.path.iter().map(JsonStep::from).collect())
+                .unwrap_or_default(),
+            duration_ms: duration.as_secs_f64() * 1000.0,
+            candidates: candidates
+                .iter()
+                .filter_map(|(candidate, score)| {
+                    Some(JsonCandidate {
+                        text: candidate.text.first()?.clone(),
+                        score: *score,
+                        path: candidate.path.iter().map(JsonStep::from).collect(),
+                    })
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Prints the result of a run as one line of JSON on stdout
+/// The outcome is read from [`crate::last_run_outcome`], so call it straight after cracking.
+pub fn print_json_result(input: &str, result: Option<&DecoderResult>, duration: Duration) {
+    let outcome = crate::last_run_outcome().unwrap_or(RunOutcome::Unsolved);
+    let candidates = if result.is_none() {
+        crate::best_candidates()
+    } else {
+        Vec::new()
+    };
+    let json = JsonResult::new(input, result, outcome, duration, &candidates);
+    match serde_json::to_string(&json) {
+        Ok(json) => println!("{}", json),
+        Err(e) => {
+            eprintln!("Could not write the result as JSON: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoders::base64_decoder::Base64Decoder;
+    use crate::decoders::interface::{Crack, Decoder};
+
+    /// A result which decoded "aGk=" to "hi" with Base64
+    fn base64_result() -> DecoderResult {
+        let mut step = CrackResult::new(&Decoder::<Base64Decoder>::new(), "aGk=".to_string());
+        step.unencrypted_text = Some(vec!["hi".to_string()]);
+        step.checker_name = "English Checker";
+        step.confidence = 0.9;
+        step.runtime = Some(Duration::from_micros(1500));
+        DecoderResult {
+            text: vec!["hi".to_string()],
+            path: vec![step],
+            bytes: None,
+        }
+    }
+
+    #[test]
+    fn solved_runs_describe_every_step() {
+        let result = base64_result();
+        let json = JsonResult::new(
+            "aGk=",
+            Some(&result),
+            RunOutcome::Solved,
+            Duration::from_millis(20),
+            &[],
+        );
+        let value = serde_json::to_value(&json).unwrap();
+        assert_eq!(value["outcome"], "solved");
+        assert_eq!(value["plaintext"], "hi");
+        assert_eq!(value["checker"], "English Checker");
+        assert_eq!(value["path"][0]["decoder"], "Base64");
+        assert_eq!(value["path"][0]["output"], "hi");
+        assert_eq!(value["path"][0]["runtime_ms"], 1.5);
+        assert_eq!(value["duration_ms"], 20.0);
+    }
+
+    #[test]
+    fn unsolved_runs_list_their_candidates() {
+        let candidate = base64_result();
+        let json = JsonResult::new(
+            "aGk=",
+            None,
+            RunOutcome::Unsolved,
+            Duration::ZERO,
+            &[(candidate, 0.4)],
+        );
+        assert!(json.plaintext.is_none());
+        assert!(json.checker.is_none());
+        assert!(json.path.is_empty());
+        assert_eq!(json.candidates[0].text, "hi");
+        assert_eq!(json.candidates[0].path[0].decoder, "Base64");
+    }
+}
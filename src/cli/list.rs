@@ -0,0 +1,202 @@
+//! Lists the decoders and checkers ciphey has with `ciphey list-decoders` and
+//! `ciphey list-checkers`
+//! The names and tags listed are what `--only-decoders`, `--exclude-decoders` and
+//! `ciphey toggle` take, and whether each one runs is worked out from the config file and
+//! the saved toggles the same way a search works it out.
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::checkers::CHECKER_MAP;
+use crate::config::Config;
+use crate::filtration_system::{enabled_decoders, get_registered_decoders};
+
+/// How many characters of a description the table shows
+const SHOWN_DESCRIPTION_CHARS: usize = 60;
+
+/// The checker in the map which only shows how checkers are written
+const TEMPLATE_CHECKER: &str = "Template checker";
+
+/// The formats the list can be written in
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ListFormat {
+    /// A table with a row for each one
+    Table,
+    /// A JSON array with an object for each one
+    Json,
+}
+
+/// What the list shows about a decoder or checker
+#[derive(Debug, Serialize, PartialEq)]
+pub struct ListEntry {
+    /// Its name
+    pub name: String,
+    /// What it does
+    pub description: String,
+    /// The tags it can be picked by
+    pub tags: Vec<String>,
+    /// How common what it decodes or checks for is, from 0.0 to 1.0
+    pub popularity: f32,
+    /// A link to read more about it
+    pub link: String,
+    /// Whether searches run it with this config
+    pub enabled: bool,
+}
+
+/// Every registered decoder, in the order they are registered, plugins included once loaded
+pub fn list_decoders(config: &Config) -> Vec<ListEntry> {
+    let enabled = enabled_decoders(config);
+    get_registered_decoders()
+        .components
+        .iter()
+        .map(|decoder| ListEntry {
+            name: decoder.get_name().to_string(),
+            description: decoder.get_description().to_string(),
+            tags: decoder
+                .get_tags()
+                .iter()
+                .map(|tag| tag.to_string())
+                .collect(),
+            popularity: decoder.get_popularity(),
+            link: decoder.get_link().to_string(),
+            enabled: enabled
+                .components
+                .iter()
+                .any(|running| running.get_name() == decoder.get_name()),
+        })
+        .collect()
+}
+
+/// Every checker, by name
+pub fn list_checkers(config: &Config) -> Vec<ListEntry> {
+    let mut names: Vec<&&str> = CHECKER_MAP
+        .keys()
+        .filter(|name| **name != TEMPLATE_CHECKER)
+        .collect();
+    names.sort();
+    names
+        .into_iter()
+        .map(|name| {
+            let checker = CHECKER_MAP[*name].get::<()>();
+            ListEntry {
+                name: checker.get_name().to_string(),
+                description: checker.get_description().to_string(),
+                tags: checker
+                    .get_tags()
+                    .iter()
+                    .map(|tag| tag.to_string())
+                    .collect(),
+                popularity: checker.get_popularity(),
+                link: checker.get_link().to_string(),
+                enabled: config.is_checker_enabled(name),
+            }
+        })
+        .collect()
+}
+
+/// A description on one line, cut short if it is long
+fn shorten(description: &str) -> String {
+    let line = description.split_whitespace().collect::<Vec<_>>().join(" ");
+    if line.chars().count() > SHOWN_DESCRIPTION_CHARS {
+        let cut: String = line.chars().take(SHOWN_DESCRIPTION_CHARS - 1).collect();
+        format!("{}…", cut)
+    } else {
+        line
+    }
+}
+
+/// Writes the list in the requested format
+///
+/// # Panics
+/// Panics if the list cannot be serialised to JSON, which cannot happen as it only holds
+/// strings, numbers and booleans.
+pub fn render_list(entries: &[ListEntry], format: ListFormat) -> String {
+    match format {
+        ListFormat::Json => {
+            serde_json::to_string_pretty(entries).expect("The list should serialise to JSON")
+        }
+        ListFormat::Table => render_table(entries),
+    }
+}
+
+/// Writes the list as a table, with the columns as wide as their widest value
+fn render_table(entries: &[ListEntry]) -> String {
+    let rows: Vec<[String; 6]> = entries
+        .iter()
+        .map(|entry| {
+            [
+                entry.name.clone(),
+                String::from(if entry.enabled { "yes" } else { "no" }),
+                format!("{:.2}", entry.popularity),
+                entry.tags.join(","),
+                shorten(&entry.description),
+                entry.link.clone(),
+            ]
+        })
+        .collect();
+    let header = ["NAME", "ON", "POPULARITY", "TAGS", "DESCRIPTION", "LINK"].map(String::from);
+    let widths: Vec<usize> = (0..header.len())
+        .map(|column| {
+            std::iter::once(&header)
+                .chain(&rows)
+                .map(|row| row[column].chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    std::iter::once(&header)
+        .chain(&rows)
+        .map(|row| {
+            let cells: Vec<String> = row
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+                .collect();
+            cells.join("  ").trim_end().to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decoders_left_out_are_listed_as_off() {
+        let config = Config {
+            exclude_decoders: vec![String::from("base64")],
+            ..Config::default()
+        };
+        let decoders = list_decoders(&config);
+        assert_eq!(decoders.len(), get_registered_decoders().components.len());
+        let base64 = decoders
+            .iter()
+            .find(|entry| entry.name == "Base64")
+            .unwrap();
+        assert!(!base64.enabled);
+        assert!(base64.tags.contains(&String::from("base")));
+        assert!(decoders.iter().any(|entry| entry.enabled));
+
+        let checkers = list_checkers(&config);
+        assert!(checkers.iter().any(|entry| entry.name == "English Checker"));
+        assert!(!checkers.iter().any(|entry| entry.name == TEMPLATE_CHECKER));
+    }
+
+    #[test]
+    fn table_lines_up_and_json_round_trips() {
+        let entries = list_checkers(&Config::default());
+        let table = render_list(&entries, ListFormat::Table);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), entries.len() + 1);
+        let on_column = lines[0].find("ON").unwrap();
+        assert!(lines[1..]
+            .iter()
+            .all(|line| line.chars().nth(on_column) != Some(' ')));
+
+        let json: serde_json::Value =
+            serde_json::from_str(&render_list(&entries, ListFormat::Json)).unwrap();
+        assert_eq!(json.as_array().unwrap().len(), entries.len());
+        assert_eq!(json[0]["name"], entries[0].name.as_str());
+    }
+}
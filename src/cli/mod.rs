@@ -1,16 +1,65 @@
 // First-run configuration module
 mod first_run;
 pub use first_run::run_first_time_setup;
+// Setup wizard for `ciphey init`
+pub mod init;
+// Cracking a file of inputs with `ciphey batch`
+pub mod batch;
+// Cache maintenance for `ciphey cache clear`, `prune`, `export` and `import`
+pub mod cache;
+// Database maintenance for `ciphey db doctor` and `ciphey stats`
+pub mod db;
+// Documentation generator for `ciphey docs`
+pub mod docs;
+// Run history for `ciphey history` and `ciphey replay`
+pub mod history;
+// Guessing what a text is with `ciphey identify`
+pub mod identify;
+// Listing the decoders and checkers with `ciphey list-decoders` and `list-checkers`
+pub mod list;
+// Scanning directories and ZIP archives with `ciphey scan`
+pub mod scan;
+// Reading the input from `--text`, `--file` or stdin
+pub mod input;
+// Machine-readable results for `--output-format json`
+pub mod json_output;
+// Saved decoder and checker toggles for `ciphey toggle`
+pub mod toggle;
+// Showing a search live with `ciphey --tui`
+pub mod tui;
+// Cracking lines as they arrive with `ciphey watch`
+pub mod watch;
 
-use std::{fs::File, io::Read};
+use std::sync::Arc;
+use std::{
+    fs::File,
+    io::{IsTerminal, Read},
+};
 
+use crate::checkers::language::is_known_language;
 use crate::cli_pretty_printing;
 use crate::cli_pretty_printing::panic_failure_both_input_and_fail_provided;
-use crate::config::{get_config_file_into_struct, load_wordlist, Config};
+use crate::config::{
+    get_config_file_into_struct, get_database_path_from_env, load_keyfile, load_wordlist,
+    set_config_file_path, Config, GibberishSensitivity, HumanCheckerDefault, OutputFormat,
+    SearchStrategy,
+};
+use crate::storage::database;
+use crate::storage::transfer::{ExportFormat, ExportTable};
+use crate::CancellationToken;
+use batch::{BatchInputFormat, BatchOutputFormat};
 /// This doc string acts as a help message when the uses run '--help' in CLI mode
 /// as do all doc strings on fields
-use clap::Parser;
+use clap::{Parser, Subcommand};
+use docs::{render_decoder_docs, DocsFormat};
+use identify::IdentifyFormat;
+use input::{InputSource, InputTrim};
+use list::ListFormat;
 use log::trace;
+use regex::Regex;
+use scan::ScanFormat;
+use toggle::{ToggleKind, ToggleState};
+use watch::WatchFormat;
 
 /// The struct for Clap CLI arguments
 #[derive(Parser)]
@@ -27,6 +76,13 @@ pub struct Opts {
     /// Turn off human checker, perfect for APIs where you don't want input from humans
     #[arg(short, long)]
     disable_human_checker: bool,
+    /// How many seconds to wait for an answer to each question of the human checker, 0 to
+    /// wait as long as it takes. The search carries on while a question waits
+    #[arg(long, value_name = "SECONDS")]
+    human_checker_timeout: Option<u32>,
+    /// What a question nobody answered in time counts as. Defaults to no
+    #[arg(long, value_enum, value_name = "ANSWER")]
+    human_checker_default: Option<HumanCheckerDefault>,
 
     /// Set timeout, if it is not decrypted after this time, it will return an error.
     /// Default is 5 seconds.
@@ -38,15 +94,37 @@ pub struct Opts {
     /// Default is false
     #[arg(short, long)]
     api_mode: Option<bool>,
-    /// Opens a file for decoding
-    /// Use instead of `--text`
+    /// How to print the result: text for people, or json for scripts, which prints one JSON
+    /// object with the plaintext, the decoders used, their keys and timings, and the checker
+    /// which confirmed it. Json also turns off the human checker
+    #[arg(long, value_enum, value_name = "FORMAT")]
+    output_format: Option<OutputFormat>,
+    /// Show the search live: the tree of decodes tried, how many are waiting, the time
+    /// left and the best candidates so far. The human checker's questions are answered
+    /// with y or n in the same screen, and q stops the search
+    #[arg(long, conflicts_with = "output_format")]
+    tui: bool,
+    /// Opens a file for decoding, or reads stdin with `-`
+    /// Use instead of `--text`. Binary files are decoded as they are, and input piped into
+    /// ciphey is read without `--file -` when there is no `--text`
     #[arg(short, long)]
     file: Option<String>,
+    /// How to trim input read from a file or stdin: newline removes the line breaks at the
+    /// end, whitespace removes whitespace at both ends and none keeps it exactly as it is.
+    /// Binary input is never trimmed
+    #[arg(long, value_enum, value_name = "MODE", default_value_t = InputTrim::Newline)]
+    trim: InputTrim,
+    /// The most bytes of input to read, 0 for no limit. Defaults to 16 MiB
+    /// Takes precedence over `max_input_bytes` in the config file
+    #[arg(long, value_name = "BYTES")]
+    max_input_bytes: Option<usize>,
     /// If you have a crib (you know a piece of information in the plaintext)
     /// Or you want to create a custom regex to check against, you can use the Regex checker below.
     /// This turns off other checkers (English, LemmeKnow)
+    /// Can be given more than once, text matching any of them is the plaintext.
+    /// Takes precedence over `regex` and `regexes` in the config file
     #[arg(short, long)]
-    regex: Option<String>,
+    regex: Vec<String>,
     /// Path to a wordlist file containing newline-separated words
     /// The checker will match input against these words exactly
     /// Takes precedence over config file if both specify a wordlist
@@ -55,21 +133,352 @@ pub struct Opts {
         help = "Path to a wordlist file with newline-separated words for exact matching"
     )]
     wordlist: Option<String>,
+    /// Also accept text which contains a wordlist entry of at least four characters
+    /// Useful when the plaintext is a sentence around a known flag or password
+    #[arg(long)]
+    wordlist_contains: bool,
+    /// The language the plaintext is in besides English: es, fr, de, ru or ar, or auto to
+    /// recognise any of them. Takes precedence over `language` in the config file
+    #[arg(long)]
+    language: Option<String>,
+    /// Where to write the plaintext when it decodes to a file such as a ZIP archive or PNG
+    /// A directory gets `decoded.<extension>` written in it
+    #[arg(long, value_name = "PATH")]
+    save_binary: Option<String>,
+    /// How confident the checkers have to be, from 0 to 1, to accept a plaintext
+    /// Higher means fewer false positives but more missed plaintexts. Defaults to 0
+    #[arg(long, value_name = "CONFIDENCE")]
+    min_confidence: Option<f32>,
     /// Show all potential plaintexts found instead of exiting after the first one
     /// Automatically disables the human checker
     #[arg(long)]
     top_results: bool,
+    /// Keep searching until the timeout and show every distinct plaintext found, best first
+    /// Automatically disables the human checker
+    #[arg(long)]
+    all_results: bool,
+    /// How many of the decodes most like plaintext to show if nothing is identified
+    /// Defaults to 1, and 0 shows none
+    #[arg(long, value_name = "N")]
+    show_candidates: Option<usize>,
+    /// How to search for the plaintext: astar, breadth-first, depth-first or beam
+    /// Takes precedence over `search_strategy` in the config file. Defaults to astar
+    #[arg(long, value_enum, value_name = "STRATEGY")]
+    search_strategy: Option<SearchStrategy>,
+    /// How many decodes of each depth beam search decodes further. Defaults to 30
+    #[arg(long, value_name = "N")]
+    beam_width: Option<usize>,
+    /// The most decoders a decoding chain can have, such as 3 to never decode deeper than
+    /// base64(base64(base64(...))). Takes precedence over `max_depth` in the config file
+    #[arg(long, value_name = "N")]
+    max_depth: Option<usize>,
+    /// How many threads to decode on. Defaults to one on every core
+    #[arg(long, value_name = "N")]
+    threads: Option<usize>,
+    /// Only run these decoders, by name or tag such as `base64,hex`. A tag picks every
+    /// decoder with it, so `base` picks every base encoding. Replaces `only_decoders` in the
+    /// config file
+    #[arg(long, value_delimiter = ',', value_name = "DECODERS")]
+    only_decoders: Vec<String>,
+    /// Never run these decoders, by name or tag such as `brainfuck`. Replaces
+    /// `exclude_decoders` in the config file
+    #[arg(long, value_delimiter = ',', value_name = "DECODERS")]
+    exclude_decoders: Vec<String>,
+    /// Start from a named bundle of settings, such as `ctf`, `forensics`, `web-tokens` or
+    /// `classical-ciphers`, or one from `[profiles]` in the config file. Other flags still
+    /// take precedence over it
+    #[arg(long, value_name = "NAME")]
+    profile: Option<String>,
+    /// How readily the checkers take a decode to be words rather than gibberish. Defaults
+    /// to medium
+    #[arg(long, value_enum, value_name = "LEVEL")]
+    sensitivity: Option<GibberishSensitivity>,
+    /// Carries on the search of this text from where the last one ran out of time,
+    /// instead of starting over. Only the astar search strategy saves where it got to
+    #[arg(long)]
+    resume: bool,
+    /// Accept plaintexts which were rejected with the human checker in earlier runs,
+    /// instead of skipping them as wrong
+    #[arg(long)]
+    ignore_rejections: bool,
     /// Enables enhanced plaintext detection with BERT model.
     #[arg(long)]
     enable_enhanced_detection: bool,
+    /// A custom 64 character Base64 alphabet to try after the standard and URL-safe ones
+    /// Takes precedence over `base64_alphabet` in the config file
+    #[arg(long)]
+    base64_alphabet: Option<String>,
+    /// A key to decrypt AES, ChaCha20-Poly1305 or Fernet with, as text, hex or Base64
+    /// Can be given more than once, and is never saved to the config file
+    #[arg(long)]
+    key: Vec<String>,
+    /// A file of keys to decrypt with, one on each line, or a single raw binary key
+    #[arg(long)]
+    keyfile: Option<String>,
+    /// Where the database holding the cache and saved toggles is, instead of
+    /// `~/.ciphey/database.sqlite`. Takes precedence over `database_path` in the config file
+    /// and the CIPHEY_DATABASE_PATH environment variable
+    #[arg(
+        long,
+        global = true,
+        value_name = "PATH",
+        conflicts_with = "no_database"
+    )]
+    database_path: Option<String>,
+    /// Don't read or write the database, keeping the cache in memory for this run only
+    /// Useful with a read-only home directory or in a container
+    #[arg(long, global = true)]
+    no_database: bool,
+    /// The config file to read instead of `~/.config/ciphey/config.toml` or
+    /// `~/.ciphey/config.toml`. Flags given on the command line still take precedence over it
+    #[arg(long, global = true, value_name = "PATH")]
+    config: Option<std::path::PathBuf>,
+    /// Subcommands which run instead of decoding, such as `ciphey docs decoders`
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+/// The subcommands ciphey supports alongside decoding
+#[derive(Subcommand)]
+pub enum Command {
+    /// Crack every input in a file, one on each line or as JSON Lines, in one go
+    /// The cache and search threads are shared between inputs, and the results are written
+    /// as JSON Lines or CSV in the same order
+    Batch {
+        /// The file of inputs, or - for stdin
+        file: String,
+        /// How the inputs are written
+        #[arg(long, value_enum, default_value_t = BatchInputFormat::Lines)]
+        input_format: BatchInputFormat,
+        /// How to write the results
+        #[arg(long, value_enum, default_value_t = BatchOutputFormat::Jsonl)]
+        format: BatchOutputFormat,
+        /// The file to write the results to, standard output if not given
+        #[arg(short, long)]
+        output: Option<String>,
+        /// How many seconds to search each input for. Defaults to the config's timeout
+        #[arg(short, long)]
+        cracking_timeout: Option<u32>,
+    },
+    /// Clear, prune, export or import the results ciphey has cached
+    Cache {
+        /// What to do to the cache
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+    /// Look after the database ciphey caches results in
+    Db {
+        /// What to do to the database
+        #[command(subcommand)]
+        action: DbAction,
+    },
+    /// Generate documentation from the registry
+    Docs {
+        /// What to generate documentation for
+        #[command(subcommand)]
+        target: DocsTarget,
+    },
+    /// Guess what a text probably is from its alphabet, padding, entropy and lemmeknow's
+    /// patterns, without cracking it. Lists each guess with how well the text fits it and
+    /// the decoder for it
+    Identify {
+        /// The text to identify, or - for stdin
+        text: String,
+        /// How to write the guesses
+        #[arg(long, value_enum, default_value_t = IdentifyFormat::Text)]
+        format: IdentifyFormat,
+    },
+    /// List every decoder with its description, tags, popularity and link, and whether it
+    /// runs. The names and tags are what `--only-decoders` and `--exclude-decoders` take
+    ListDecoders {
+        /// How to write the list
+        #[arg(long, value_enum, default_value_t = ListFormat::Table)]
+        format: ListFormat,
+    },
+    /// List every checker with its description, tags, popularity and link, and whether it
+    /// runs
+    ListCheckers {
+        /// How to write the list
+        #[arg(long, value_enum, default_value_t = ListFormat::Table)]
+        format: ListFormat,
+    },
+    /// Show what earlier searches recorded: how each decoder fared, the most common first
+    /// decoders, how often the cache answered and what the human checker rejected
+    Stats,
+    /// List the most recent runs: when they finished, how they ended, how long they took,
+    /// their input and the plaintext they found
+    History {
+        /// How many runs to list
+        #[arg(short = 'n', long, default_value_t = 20)]
+        limit: usize,
+    },
+    /// Show everything recorded about a run from `ciphey history`, or crack its input again
+    Replay {
+        /// The number of the run, as listed by `ciphey history`
+        run_id: i64,
+        /// Crack the input again with the config the run used, instead of only showing what
+        /// it found
+        #[arg(long)]
+        rerun: bool,
+    },
+    /// Scan every file in a directory or ZIP archive for encoded strings, such as runs of
+    /// Base64 or hex, and crack them, reporting what each file held
+    Scan {
+        /// The directory or ZIP archive to scan, or a single file
+        path: String,
+        /// How to write the report
+        #[arg(long, value_enum, default_value_t = ScanFormat::Text)]
+        format: ScanFormat,
+        /// The file to write the report to, standard output if not given
+        #[arg(short, long)]
+        output: Option<String>,
+        /// How many seconds to search each string for. Defaults to the config's timeout
+        #[arg(short, long)]
+        cracking_timeout: Option<u32>,
+        /// A regex to find strings with instead of `scan_extractors` in the config, as
+        /// NAME=REGEX. Can be given more than once
+        #[arg(long, value_name = "NAME=REGEX")]
+        extractor: Vec<String>,
+    },
+    /// Follow a file, a FIFO or stdin and crack each new line as it is written, printing
+    /// each result as soon as it is found
+    Watch {
+        /// The file or FIFO to watch, or - for stdin
+        path: String,
+        /// How to write the results
+        #[arg(long, value_enum, default_value_t = WatchFormat::Text)]
+        format: WatchFormat,
+        /// How many seconds to search each line for. Defaults to the config's timeout
+        #[arg(short, long)]
+        cracking_timeout: Option<u32>,
+        /// Crack the lines already in the file too, instead of only new ones
+        #[arg(long)]
+        from_start: bool,
+        /// How many lines can wait to be cracked before ciphey stops reading more
+        #[arg(long, default_value_t = crate::watch::DEFAULT_QUEUE_SIZE)]
+        queue_size: usize,
+        /// How many milliseconds to wait before checking a file for new lines again
+        #[arg(long, default_value_t = crate::watch::DEFAULT_POLL_MS)]
+        poll_ms: u64,
+    },
+    /// Answer JSON-RPC requests on stdin, or on a TCP address, streaming the progress of
+    /// each search as notifications until it can be cancelled or finishes
+    Rpc {
+        /// Listen for connections on this address, such as 127.0.0.1:9090, instead of
+        /// reading stdin
+        #[arg(long, value_name = "HOST:PORT")]
+        listen: Option<String>,
+        /// The most seconds any text is searched for. Defaults to the config's timeout
+        #[arg(short, long)]
+        cracking_timeout: Option<u32>,
+    },
+    /// Answer HTTP requests to crack texts, list the decoders and show statistics, for web
+    /// UIs and other programs which would otherwise run ciphey for every text
+    Serve {
+        /// The port to listen on
+        #[arg(short, long, default_value_t = crate::server::DEFAULT_PORT)]
+        port: u16,
+        /// The address to listen on. Use 0.0.0.0 to accept connections from other machines
+        #[arg(long, default_value = "127.0.0.1")]
+        host: String,
+        /// The most seconds any text is searched for. Defaults to the config's timeout
+        #[arg(short, long)]
+        cracking_timeout: Option<u32>,
+    },
+    /// Set ciphey up: create the config file, choose a database location,
+    /// download optional extras and run a selftest
+    Init {
+        /// Don't ask anything, use the defaults and download nothing
+        #[arg(long)]
+        yes: bool,
+    },
+    /// Turn a decoder or checker on or off for every future run
+    /// e.g. `ciphey toggle decoder brainfuck off`
+    Toggle {
+        /// Whether to toggle a decoder or a checker
+        #[arg(value_enum)]
+        kind: ToggleKind,
+        /// The name of the decoder or checker
+        name: String,
+        /// Turn it on or off
+        #[arg(value_enum)]
+        state: ToggleState,
+    },
+}
+
+/// The things `ciphey cache` can do
+#[derive(Subcommand)]
+pub enum CacheAction {
+    /// Forget every cached result and decode
+    Clear,
+    /// Forget the cached results and decodes older or more than `cache_max_age_days`,
+    /// `cache_max_entries` and `edge_cache_max_entries` in the config file allow
+    Prune,
+    /// Write the cached results and decoder statistics out, to share them or analyse them
+    Export {
+        /// JSON holds every table, CSV holds the one chosen with --table
+        #[arg(long, value_enum, default_value = "json")]
+        format: ExportFormat,
+        /// Which table a CSV export holds
+        #[arg(long, value_enum, default_value = "cache")]
+        table: ExportTable,
+        /// The file to write to, standard output if not given
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+    /// Add the cached results and decoder statistics from an export to the database
+    Import {
+        /// The file written by `ciphey cache export`
+        file: String,
+        /// The format of the file, guessed from its extension if not given
+        #[arg(long, value_enum)]
+        format: Option<ExportFormat>,
+    },
+}
+
+/// The things `ciphey db` can do
+#[derive(Subcommand)]
+pub enum DbAction {
+    /// Check the database for damage with SQLite's integrity check, rebuild its
+    /// indexes and recover every readable row into a fresh database if needed
+    Doctor,
+}
+
+/// The things `ciphey docs` can document
+#[derive(Subcommand)]
+pub enum DocsTarget {
+    /// Document every decoder: its name, description, link, tags, example and guard conditions
+    Decoders {
+        /// The format to render the documentation in
+        #[arg(long, value_enum, default_value_t = DocsFormat::Markdown)]
+        format: DocsFormat,
+    },
+}
+
+/// Cancels the token when the user presses Ctrl-C, so the search stops and shows what it
+/// found so far instead of the program being killed
+/// Pressing Ctrl-C again before the program exits kills it straight away.
+pub fn cancel_on_ctrl_c(token: &CancellationToken) {
+    let flag = token.flag();
+    // The shutdown is registered first, so it only fires once the flag is already set
+    let registered = signal_hook::flag::register_conditional_shutdown(
+        signal_hook::consts::SIGINT,
+        130,
+        Arc::clone(&flag),
+    )
+    .and_then(|_| signal_hook::flag::register(signal_hook::consts::SIGINT, flag));
+    if let Err(e) = registered {
+        log::warn!("Could not listen for Ctrl-C: {}", e);
+    }
 }
 
 /// Parse CLI Arguments turns a Clap Opts struct, seen above
 /// Into a library Struct for use within the program
 /// The library struct can be found in the [config](../config) folder.
+/// The input is given as bytes, as files and stdin don't have to be UTF-8 text.
 /// # Panics
 /// This function can panic when it gets both a file and text input at the same time.
-pub fn parse_cli_args() -> (String, Config) {
+pub fn parse_cli_args() -> (Vec<u8>, Config) {
     let mut opts: Opts = Opts::parse();
     let min_log_level = match opts.verbose {
         0 => "Warn",
@@ -81,27 +490,288 @@ pub fn parse_cli_args() -> (String, Config) {
         env_logger::Env::default().filter_or(env_logger::DEFAULT_FILTER_ENV, min_log_level),
     );
 
+    if let Some(path) = opts.config.take() {
+        set_config_file_path(path);
+    }
+
+    // Subcommands run on their own and never perform any decoding
+    if let Some(command) = opts.command.take() {
+        if opts.no_database {
+            database::disable_database();
+        } else if let Some(path) = &opts.database_path {
+            database::use_database_path(path.into());
+        }
+        run_command(command);
+        std::process::exit(0);
+    }
+
     // If both the file and text are proivded, panic because we're not sure which one to use
     if opts.file.is_some() && opts.text.is_some() {
         panic_failure_both_input_and_fail_provided();
     }
 
-    let input_text: String = if opts.file.is_some() {
-        read_and_parse_file(opts.file.unwrap())
-    } else {
-        opts.text
-            .expect("Error. No input was provided. Please use ciphey --help")
+    let source = match (opts.file.take(), opts.text.take()) {
+        (Some(path), _) if path == "-" => InputSource::Stdin,
+        (Some(path), _) => InputSource::File(path),
+        (None, Some(text)) => InputSource::Text(text),
+        // Input piped into ciphey is read without having to ask for it
+        (None, None) if !std::io::stdin().is_terminal() => InputSource::Stdin,
+        (None, None) => panic!("Error. No input was provided. Please use ciphey --help"),
     };
+    let trim = opts.trim;
+    let piped = source == InputSource::Stdin;
 
-    // Fixes bug where opts.text and opts.file are partially borrowed
-    opts.text = None;
-    opts.file = None;
+    let config = cli_args_into_config_struct(opts);
+    let input = match input::read_input(&source, trim, config.max_input_bytes) {
+        Ok(input) => input,
+        Err(e) => {
+            // Critical error - there is nothing to decode
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+    if piped && input.is_empty() {
+        eprintln!("Error. No input was provided. Please use ciphey --help");
+        std::process::exit(1);
+    }
 
     trace!("Program was called with CLI 😉");
     trace!("Parsed the arguments");
-    trace!("The inputted text is {}", &input_text);
+    trace!("The inputted text is {}", String::from_utf8_lossy(&input));
 
-    cli_args_into_config_struct(opts, input_text)
+    (input, config)
+}
+
+/// Runs a subcommand such as `ciphey docs decoders`
+fn run_command(command: Command) {
+    match command {
+        Command::Batch {
+            file,
+            input_format,
+            format,
+            output,
+            cracking_timeout,
+        } => {
+            if let Err(e) = batch::run_batch(
+                &file,
+                input_format,
+                format,
+                output.as_deref(),
+                cracking_timeout,
+            ) {
+                eprintln!("{}", cli_pretty_printing::warning(&e));
+                std::process::exit(1);
+            }
+        }
+        Command::Cache {
+            action:
+                CacheAction::Export {
+                    format,
+                    table,
+                    output: None,
+                },
+        } => match cache::render_export(format, table) {
+            Ok(export) => print!("{}", export),
+            Err(e) => {
+                eprintln!("{}", cli_pretty_printing::warning(&e));
+                std::process::exit(1);
+            }
+        },
+        Command::Cache { action } => {
+            let result = match action {
+                CacheAction::Clear => cache::run_clear(),
+                CacheAction::Prune => cache::run_prune(),
+                CacheAction::Export {
+                    format,
+                    table,
+                    output: Some(path),
+                } => cache::run_export(format, table, &path),
+                CacheAction::Export { output: None, .. } => {
+                    unreachable!("Exports to standard output are handled above")
+                }
+                CacheAction::Import { file, format } => cache::run_import(&file, format),
+            };
+            match result {
+                Ok(report) => println!("{}", cli_pretty_printing::success(&report)),
+                Err(e) => {
+                    eprintln!("{}", cli_pretty_printing::warning(&e));
+                    std::process::exit(1);
+                }
+            }
+        }
+        Command::Db {
+            action: DbAction::Doctor,
+        } => {
+            if let Err(e) = db::run_doctor() {
+                eprintln!("{}", cli_pretty_printing::warning(&e));
+                std::process::exit(1);
+            }
+        }
+        Command::Docs {
+            target: DocsTarget::Decoders { format },
+        } => println!("{}", render_decoder_docs(format)),
+        Command::Identify { text, format } => {
+            let config = get_config_file_into_struct();
+            let text = if text == "-" {
+                match input::read_input(
+                    &InputSource::Stdin,
+                    InputTrim::Newline,
+                    config.max_input_bytes,
+                ) {
+                    Ok(input) => String::from_utf8_lossy(&input).into_owned(),
+                    Err(e) => {
+                        eprintln!("{}", cli_pretty_printing::warning(&e));
+                        std::process::exit(1);
+                    }
+                }
+            } else {
+                text
+            };
+            let identification = crate::identify::identify(&text, config.sensitivity.into());
+            println!(
+                "{}",
+                identify::render_identification(&identification, format)
+            );
+        }
+        Command::ListDecoders { format } => {
+            // Loads the plugins and saved toggles, so the list shows what a search would run
+            crate::server::prepare_service(get_config_file_into_struct());
+            let decoders = list::list_decoders(crate::config::get_config());
+            println!("{}", list::render_list(&decoders, format));
+        }
+        Command::ListCheckers { format } => {
+            crate::server::prepare_service(get_config_file_into_struct());
+            let checkers = list::list_checkers(crate::config::get_config());
+            println!("{}", list::render_list(&checkers, format));
+        }
+        Command::Stats => {
+            if let Err(e) = db::run_stats() {
+                eprintln!("{}", cli_pretty_printing::warning(&e));
+                std::process::exit(1);
+            }
+        }
+        Command::History { limit } => {
+            if let Err(e) = history::run_history(limit) {
+                eprintln!("{}", cli_pretty_printing::warning(&e));
+                std::process::exit(1);
+            }
+        }
+        Command::Replay { run_id, rerun } => {
+            if let Err(e) = history::run_replay(run_id, rerun) {
+                eprintln!("{}", cli_pretty_printing::warning(&e));
+                std::process::exit(1);
+            }
+        }
+        Command::Scan {
+            path,
+            format,
+            output,
+            cracking_timeout,
+            extractor,
+        } => {
+            if let Err(e) = scan::run_scan(
+                &path,
+                format,
+                output.as_deref(),
+                cracking_timeout,
+                &extractor,
+            ) {
+                eprintln!("{}", cli_pretty_printing::warning(&e));
+                std::process::exit(1);
+            }
+        }
+        Command::Watch {
+            path,
+            format,
+            cracking_timeout,
+            from_start,
+            queue_size,
+            poll_ms,
+        } => {
+            let options = crate::watch::WatchOptions {
+                from_start,
+                poll_interval: std::time::Duration::from_millis(poll_ms),
+                queue_size,
+            };
+            if let Err(e) = watch::run_watch(&path, format, cracking_timeout, options) {
+                eprintln!("{}", cli_pretty_printing::warning(&e));
+                std::process::exit(1);
+            }
+        }
+        Command::Rpc {
+            listen,
+            cracking_timeout,
+        } => {
+            let mut config = get_config_file_into_struct();
+            if let Some(timeout) = cracking_timeout {
+                config.timeout = timeout;
+            }
+            crate::server::prepare_service(config);
+            match listen {
+                Some(address) => match std::net::TcpListener::bind(&address) {
+                    Ok(listener) => {
+                        eprintln!("Listening for JSON-RPC on {}", address);
+                        crate::rpc::serve_rpc_on(&listener);
+                    }
+                    Err(e) => {
+                        let e = format!("Can't listen on {}: {}", address, e);
+                        eprintln!("{}", cli_pretty_printing::warning(&e));
+                        std::process::exit(1);
+                    }
+                },
+                None => {
+                    let writer: crate::rpc::RpcWriter =
+                        Arc::new(std::sync::Mutex::new(Box::new(std::io::stdout())));
+                    crate::rpc::serve_rpc(std::io::stdin().lock(), writer);
+                }
+            }
+        }
+        Command::Serve {
+            port,
+            host,
+            cracking_timeout,
+        } => {
+            let mut config = get_config_file_into_struct();
+            if let Some(timeout) = cracking_timeout {
+                config.timeout = timeout;
+            }
+            let options = crate::server::ServeOptions { host, port };
+            match crate::server::bind(&options) {
+                Ok(listener) => {
+                    eprintln!("Listening on http://{}:{}", options.host, options.port);
+                    crate::server::serve(&listener, config);
+                }
+                Err(e) => {
+                    eprintln!("{}", cli_pretty_printing::warning(&e));
+                    std::process::exit(1);
+                }
+            }
+        }
+        Command::Init { yes } => {
+            if let Err(e) = init::run_init(yes) {
+                eprintln!("{}", cli_pretty_printing::warning(&e));
+                std::process::exit(1);
+            }
+        }
+        Command::Toggle { kind, name, state } => match toggle::toggle(kind, &name, state) {
+            Ok(resolved) => println!(
+                "{}",
+                cli_pretty_printing::success(&format!(
+                    "{} is now {}. Settings in your config file still take precedence.",
+                    resolved,
+                    if state == ToggleState::On {
+                        "on"
+                    } else {
+                        "off"
+                    }
+                ))
+            ),
+            Err(e) => {
+                eprintln!("{}", cli_pretty_printing::warning(&e));
+                std::process::exit(1);
+            }
+        },
+    }
 }
 
 /// When the CLI is called with `-f` to open a file
@@ -127,13 +797,30 @@ pub fn read_and_parse_file(file_path: String) -> String {
 }
 
 /// Turns our CLI arguments into a config stuct
-fn cli_args_into_config_struct(opts: Opts, text: String) -> (String, Config) {
+fn cli_args_into_config_struct(opts: Opts) -> Config {
     // Get configuration from file first
     let mut config = get_config_file_into_struct();
+    // A profile goes on top of the file, and flags on top of the profile
+    if let Some(profile) = opts.profile.as_deref() {
+        if let Err(e) = config.apply_profile(profile) {
+            eprintln!("{}", cli_pretty_printing::warning(&e));
+            std::process::exit(1);
+        }
+    }
 
     // Update config with CLI arguments when they're explicitly set
-    config.verbose = opts.verbose;
-    config.human_checker_on = !opts.disable_human_checker;
+    if opts.verbose > 0 {
+        config.verbose = opts.verbose;
+    }
+    if opts.disable_human_checker {
+        config.human_checker_on = false;
+    }
+    if let Some(timeout) = opts.human_checker_timeout {
+        config.human_checker_timeout = timeout;
+    }
+    if let Some(answer) = opts.human_checker_default {
+        config.human_checker_default = answer;
+    }
 
     if let Some(timeout) = opts.cracking_timeout {
         config.timeout = timeout;
@@ -143,8 +830,31 @@ fn cli_args_into_config_struct(opts: Opts, text: String) -> (String, Config) {
         config.api_mode = api_mode;
     }
 
-    if let Some(regex) = opts.regex {
-        config.regex = Some(regex);
+    if let Some(max_input_bytes) = opts.max_input_bytes {
+        config.max_input_bytes = max_input_bytes;
+    }
+
+    if let Some(output_format) = opts.output_format {
+        config.output_format = output_format;
+    }
+    if opts.tui {
+        config.tui = true;
+    }
+    // Nothing but the JSON may be printed to stdout, so ciphey runs as it does for the API
+    if config.output_format == OutputFormat::Json {
+        config.api_mode = true;
+    }
+
+    if !opts.regex.is_empty() {
+        for pattern in &opts.regex {
+            // Critical error - a pattern which can't match would make every check fail
+            if let Err(e) = Regex::new(pattern) {
+                eprintln!("Can't use the regex '{}': {}", pattern, e);
+                std::process::exit(1);
+            }
+        }
+        config.regex = None;
+        config.regexes = opts.regex;
     }
 
     // Handle wordlist if provided via CLI (takes precedence over config file)
@@ -164,11 +874,105 @@ fn cli_args_into_config_struct(opts: Opts, text: String) -> (String, Config) {
         }
     }
 
+    if opts.wordlist_contains {
+        config.wordlist_contains = true;
+    }
+
+    if let Some(language) = opts.language {
+        if !is_known_language(&language) {
+            eprintln!(
+                "Unknown language '{}', expected auto, en, es, fr, de, ru or ar",
+                language
+            );
+            std::process::exit(1);
+        }
+        config.language = language;
+    }
+
+    if let Some(min_confidence) = opts.min_confidence {
+        if !(0.0..=1.0).contains(&min_confidence) {
+            eprintln!(
+                "--min-confidence must be between 0 and 1, got {}",
+                min_confidence
+            );
+            std::process::exit(1);
+        }
+        config.min_confidence = min_confidence;
+    }
+
+    if let Some(path) = opts.save_binary {
+        config.binary_output_path = Some(path);
+    }
+
+    if let Some(alphabet) = opts.base64_alphabet {
+        config.base64_alphabet = Some(alphabet);
+    }
+
+    config.decryption_keys = opts.key;
+    if let Some(keyfile) = opts.keyfile {
+        match load_keyfile(&keyfile) {
+            Ok(keys) => config.decryption_keys.extend(keys),
+            Err(e) => {
+                // Critical error - the user expects these keys to be tried
+                eprintln!("Can't load keyfile at '{}': {}", keyfile, e);
+                std::process::exit(1);
+            }
+        }
+    }
+
     // Set top_results mode if the flag is present
-    config.top_results = opts.top_results;
+    config.top_results |= opts.top_results;
+
+    config.all_results |= opts.all_results;
+    if let Some(show_candidates) = opts.show_candidates {
+        config.show_candidates = show_candidates;
+    }
+
+    if let Some(search_strategy) = opts.search_strategy {
+        config.search_strategy = search_strategy;
+    }
+    if let Some(beam_width) = opts.beam_width {
+        if beam_width == 0 {
+            eprintln!("--beam-width must be at least 1");
+            std::process::exit(1);
+        }
+        config.beam_width = beam_width;
+    }
+
+    if let Some(max_depth) = opts.max_depth {
+        if max_depth == 0 {
+            eprintln!("--max-depth must be at least 1");
+            std::process::exit(1);
+        }
+        config.max_depth = Some(max_depth);
+    }
+
+    if let Some(threads) = opts.threads {
+        config.threads = threads;
+    }
+
+    if let Some(sensitivity) = opts.sensitivity {
+        config.sensitivity = sensitivity;
+    }
+
+    if !opts.only_decoders.is_empty() {
+        config.only_decoders = opts.only_decoders;
+    }
+    if !opts.exclude_decoders.is_empty() {
+        config.exclude_decoders = opts.exclude_decoders;
+    }
+
+    config.resume = opts.resume;
+    config.ignore_rejections = opts.ignore_rejections;
+
+    // The database path on the command line beats the environment, which beats the config file
+    if let Some(path) = opts.database_path.or_else(get_database_path_from_env) {
+        config.database_path = Some(path);
+    }
+    config.no_database = opts.no_database;
 
-    // If top_results is enabled, automatically disable the human checker
-    if config.top_results {
+    // If top_results or all_results is enabled, automatically disable the human checker
+    if config.top_results || config.all_results {
         config.human_checker_on = false;
     }
 
@@ -183,5 +987,5 @@ fn cli_args_into_config_struct(opts: Opts, text: String) -> (String, Config) {
         );
     }
 
-    (text, config)
+    config
 }
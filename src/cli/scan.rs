@@ -0,0 +1,271 @@
+//! Scans a directory or ZIP archive for encoded strings with `ciphey scan`
+//! Every file is searched with the `scan_extractors` in the config, or the ones given with
+//! `--extractor`, and what each cracked candidate decoded to is reported file by file, as
+//! text for reading or JSON for other tools.
+
+use std::fs;
+use std::path::Path;
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::cli::json_output::JsonResult;
+use crate::cli_pretty_printing::decoded_path;
+use crate::config::get_config_file_into_struct;
+use crate::scan::{perform_scan, FileReport, ScanExtractor, ScanReport};
+
+/// How the report of a scan is written
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum ScanFormat {
+    /// The files with candidates and what they decoded to, for reading
+    #[default]
+    Text,
+    /// One JSON document with every file scanned
+    Json,
+}
+
+/// A cracked candidate in a JSON report
+#[derive(Debug, Serialize)]
+struct JsonFinding {
+    /// The name of the extractor which found it
+    extractor: String,
+    /// How many bytes into the file it was first found
+    offset: usize,
+    /// Everything found cracking it
+    #[serde(flatten)]
+    result: JsonResult,
+}
+
+/// A file in a JSON report
+#[derive(Debug, Serialize)]
+struct JsonFile<'a> {
+    /// The file, with `!` and the entry name for entries of ZIP archives
+    path: &'a str,
+    /// How many distinct candidates were found in it
+    candidates: usize,
+    /// The candidates which were cracked
+    findings: Vec<JsonFinding>,
+    /// Why the file couldn't be scanned, if it couldn't
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<&'a str>,
+}
+
+/// The whole of a JSON report
+#[derive(Debug, Serialize)]
+struct JsonScanReport<'a> {
+    /// Every file scanned, in the order they were walked
+    files: Vec<JsonFile<'a>>,
+    /// How many distinct candidates weren't cracked because there were too many
+    skipped: usize,
+}
+
+/// Reads an extractor given as `NAME=REGEX`
+///
+/// # Errors
+/// Returns an error message if there is no `=` or either side of it is empty.
+pub fn parse_extractor(extractor: &str) -> Result<ScanExtractor, String> {
+    match extractor.split_once('=') {
+        Some((name, pattern)) if !name.is_empty() && !pattern.is_empty() => Ok(ScanExtractor {
+            name: name.to_string(),
+            pattern: pattern.to_string(),
+        }),
+        _ => Err(format!(
+            "Can't use the extractor '{}', expected NAME=REGEX such as jwt=eyJ[\\w.-]+",
+            extractor
+        )),
+    }
+}
+
+/// Describes a file's findings, None if it had no candidates and no error
+fn render_file(file: &FileReport) -> Option<String> {
+    if let Some(error) = &file.error {
+        return Some(format!("{}: {}", file.path, error));
+    }
+    if file.candidates == 0 {
+        return None;
+    }
+    let mut lines = vec![format!(
+        "{}: {} candidates, {} decoded",
+        file.path,
+        file.candidates,
+        file.findings.len()
+    )];
+    for finding in &file.findings {
+        lines.push(format!(
+            "  at byte {} ({}): {} → {} via {}",
+            finding.candidate.offset,
+            finding.candidate.extractor,
+            finding.candidate.text,
+            finding.result.text.first().map_or("", String::as_str),
+            decoded_path(&finding.result)
+        ));
+    }
+    Some(lines.join("\n"))
+}
+
+/// Writes the report of a scan
+pub fn render_scan_report(report: &ScanReport, format: ScanFormat) -> String {
+    match format {
+        ScanFormat::Text => {
+            let mut sections: Vec<String> = report.files.iter().filter_map(render_file).collect();
+            let candidates: usize = report.files.iter().map(|file| file.candidates).sum();
+            let decoded: usize = report.files.iter().map(|file| file.findings.len()).sum();
+            sections.push(format!(
+                "Scanned {} files, found {} candidates and decoded {}",
+                report.files.len(),
+                candidates,
+                decoded
+            ));
+            if report.skipped > 0 {
+                sections.push(format!(
+                    "{} more distinct candidates weren't cracked, scan fewer files at once",
+                    report.skipped
+                ));
+            }
+            sections.join("\n") + "\n"
+        }
+        ScanFormat::Json => {
+            let files: Vec<JsonFile> = report
+                .files
+                .iter()
+                .map(|file| JsonFile {
+                    path: &file.path,
+                    candidates: file.candidates,
+                    findings: file
+                        .findings
+                        .iter()
+                        .map(|finding| JsonFinding {
+                            extractor: finding.candidate.extractor.clone(),
+                            offset: finding.candidate.offset,
+                            result: JsonResult::new(
+                                &finding.candidate.text,
+                                Some(&finding.result),
+                                finding.outcome,
+                                finding.duration,
+                                &[],
+                            ),
+                        })
+                        .collect(),
+                    error: file.error.as_deref(),
+                })
+                .collect();
+            serde_json::to_string_pretty(&JsonScanReport {
+                files,
+                skipped: report.skipped,
+            })
+            .unwrap_or_else(|_| String::from("{}"))
+                + "\n"
+        }
+    }
+}
+
+/// Scans `path`, writing the report to `output` or stdout
+///
+/// # Errors
+/// Returns an error message if an extractor can't be used, the path can't be read or the
+/// report can't be written.
+pub fn run_scan(
+    path: &str,
+    format: ScanFormat,
+    output: Option<&str>,
+    timeout: Option<u32>,
+    extractors: &[String],
+) -> Result<(), String> {
+    let mut config = get_config_file_into_struct();
+    if !extractors.is_empty() {
+        config.scan_extractors = extractors
+            .iter()
+            .map(|extractor| parse_extractor(extractor))
+            .collect::<Result<_, _>>()?;
+    }
+    if let Some(timeout) = timeout {
+        config.timeout = timeout;
+    }
+    // Only the report is written to stdout
+    config.api_mode = true;
+    let report = perform_scan(Path::new(path), config).map_err(|e| e.to_string())?;
+
+    let rendered = render_scan_report(&report, format);
+    match output {
+        Some(path) => {
+            fs::write(path, rendered).map_err(|e| format!("Can't write '{}': {}", path, e))
+        }
+        None => {
+            print!("{}", rendered);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::{Candidate, Finding};
+    use crate::storage::history::RunOutcome;
+    use crate::DecoderResult;
+
+    /// A report of one file with a decoded candidate, one with none and one unreadable
+    fn report() -> ScanReport {
+        ScanReport {
+            files: vec![
+                FileReport {
+                    path: String::from("logs/app.log"),
+                    candidates: 2,
+                    findings: vec![Finding {
+                        candidate: Candidate {
+                            text: String::from("aGVsbG8gdGhlcmUgZnJpZW5k"),
+                            extractor: String::from("base64"),
+                            offset: 15,
+                        },
+                        result: DecoderResult::from("hello there friend"),
+                        outcome: RunOutcome::Solved,
+                        duration: std::time::Duration::from_millis(3),
+                    }],
+                    error: None,
+                },
+                FileReport {
+                    path: String::from("logs/empty.log"),
+                    ..FileReport::default()
+                },
+                FileReport {
+                    path: String::from("logs/huge.log"),
+                    error: Some(String::from("It is too long")),
+                    ..FileReport::default()
+                },
+            ],
+            skipped: 0,
+        }
+    }
+
+    #[test]
+    fn extractors_are_given_as_name_and_regex() {
+        let extractor = parse_extractor("jwt=eyJ[\\w.-]+=").unwrap();
+        assert_eq!(extractor.name, "jwt");
+        assert_eq!(extractor.pattern, "eyJ[\\w.-]+=");
+        assert!(parse_extractor("no-regex").is_err());
+        assert!(parse_extractor("=regex").is_err());
+    }
+
+    #[test]
+    fn text_reports_skip_files_without_candidates() {
+        let text = render_scan_report(&report(), ScanFormat::Text);
+        assert!(text.contains("logs/app.log: 2 candidates, 1 decoded"));
+        assert!(text.contains("at byte 15 (base64): aGVsbG8gdGhlcmUgZnJpZW5k → hello there"));
+        assert!(!text.contains("empty.log"));
+        assert!(text.contains("logs/huge.log: It is too long"));
+        assert!(text.ends_with("Scanned 3 files, found 2 candidates and decoded 1\n"));
+    }
+
+    #[test]
+    fn json_reports_list_every_file() {
+        let json: serde_json::Value =
+            serde_json::from_str(&render_scan_report(&report(), ScanFormat::Json)).unwrap();
+        assert_eq!(json["files"].as_array().unwrap().len(), 3);
+        assert_eq!(json["files"][0]["findings"][0]["extractor"], "base64");
+        assert_eq!(
+            json["files"][0]["findings"][0]["plaintext"],
+            "hello there friend"
+        );
+        assert_eq!(json["files"][2]["error"], "It is too long");
+    }
+}
@@ -0,0 +1,128 @@
+//! Turns decoders and checkers on or off with `ciphey toggle decoder brainfuck off`
+//! The choice is saved in the database so it sticks between runs without editing
+//! the config file. Anything set in the config file still takes precedence.
+
+use clap::ValueEnum;
+
+use crate::checkers::CHECKER_MAP;
+use crate::cli::db::open_configured_database;
+use crate::filtration_system::{get_registered_decoders, normalise_name as normalise};
+use crate::storage::database;
+
+/// What can be toggled
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ToggleKind {
+    /// A decoder, such as `brainfuck` or `base64`
+    Decoder,
+    /// A checker which Athena runs, such as `english` or `password`
+    Checker,
+}
+
+impl ToggleKind {
+    /// The name this kind is stored under in the settings table
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ToggleKind::Decoder => "decoder",
+            ToggleKind::Checker => "checker",
+        }
+    }
+}
+
+/// Whether to turn the decoder or checker on or off
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ToggleState {
+    /// Run it as normal
+    On,
+    /// Never run it
+    Off,
+}
+
+/// The checkers Athena runs which can be toggled
+/// Athena and WaitAthena themselves can't be, as nothing would be checked
+const TOGGLEABLE_CHECKERS: [&str; 10] = [
+    "Checksum Checker",
+    "English Checker",
+    "Entropy Checker",
+    "Language Checker",
+    "LemmeKnow Checker",
+    "Magic Bytes Checker",
+    "Password Checker",
+    "Regex Checker",
+    "Structured Data Checker",
+    "Wordlist Checker",
+];
+
+/// Finds the registry name for what the user typed
+///
+/// Matching ignores case, spaces and punctuation, so `base58-bitcoin` finds `Base58 Bitcoin`.
+/// Checkers can be given without the `Checker` suffix, so `english` finds `English Checker`.
+pub fn resolve_name(kind: ToggleKind, name: &str) -> Option<String> {
+    let wanted = normalise(name);
+    match kind {
+        ToggleKind::Decoder => get_registered_decoders()
+            .components
+            .iter()
+            .map(|decoder| decoder.get_name().to_string())
+            .find(|registered| normalise(registered) == wanted),
+        ToggleKind::Checker => TOGGLEABLE_CHECKERS
+            .iter()
+            .filter(|checker| CHECKER_MAP.contains_key(*checker))
+            .find(|checker| {
+                let registered = normalise(checker);
+                registered == wanted || registered.strip_suffix("checker") == Some(wanted.as_str())
+            })
+            .map(|checker| checker.to_string()),
+    }
+}
+
+/// Saves the enable state for a decoder or checker in the database
+///
+/// Returns the registry name of what was toggled
+///
+/// # Errors
+/// Returns an error message if the name doesn't match anything that can be toggled
+/// or if the database can't be opened or written to.
+pub fn toggle(kind: ToggleKind, name: &str, state: ToggleState) -> Result<String, String> {
+    let resolved = resolve_name(kind, name)
+        .ok_or_else(|| format!("There is no {} called {}", kind.as_str(), name))?;
+    // Saving to the in-memory fallback would be forgotten as soon as we exit
+    open_configured_database()?;
+    database::upsert_setting(kind.as_str(), &resolved, state == ToggleState::On)
+        .map_err(|e| format!("Could not save the setting: {}", e))?;
+    Ok(resolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decoder_names_ignore_case_and_punctuation() {
+        assert_eq!(
+            resolve_name(ToggleKind::Decoder, "brainfuck"),
+            Some("Brainfuck".to_string())
+        );
+        assert_eq!(
+            resolve_name(ToggleKind::Decoder, "base58-bitcoin"),
+            Some("Base58 Bitcoin".to_string())
+        );
+    }
+
+    #[test]
+    fn checker_names_can_drop_the_suffix() {
+        assert_eq!(
+            resolve_name(ToggleKind::Checker, "english"),
+            Some("English Checker".to_string())
+        );
+        assert_eq!(
+            resolve_name(ToggleKind::Checker, "LemmeKnow Checker"),
+            Some("LemmeKnow Checker".to_string())
+        );
+    }
+
+    #[test]
+    fn unknown_names_are_not_resolved() {
+        assert!(resolve_name(ToggleKind::Decoder, "not a decoder").is_none());
+        assert!(resolve_name(ToggleKind::Checker, "athena").is_none());
+    }
+}
@@ -0,0 +1,602 @@
+//! Shows a search live in the terminal with `ciphey --tui`
+//! The screen is drawn from the search's events: the tree of decodes which looked like
+//! plaintext, how deep the search is and how many decodes are waiting, the time left and
+//! the best candidates so far. The human checker asks in the same screen instead of on
+//! stdin, and is answered with y or n. Once the search ends the screen stays
+//! until a key is pressed, and the result is printed as usual.
+
+use std::io::Stdout;
+use std::sync::mpsc::{channel, Sender};
+use std::time::{Duration, Instant};
+
+use ratatui::backend::CrosstermBackend;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::crossterm::{execute, terminal};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Paragraph};
+use ratatui::{Frame, Terminal, TerminalOptions, Viewport};
+
+use crate::checkers::human_checker::answer_prompts_with;
+use crate::config::Config;
+use crate::events::{self, SearchEvent, Subscription};
+use crate::{perform_cracking_bytes_with_cancel, CancellationToken, DecoderResult};
+
+/// How many of the best candidates are shown
+const SHOWN_CANDIDATES: usize = 5;
+
+/// How many children of a decode in the tree are shown, the rest are counted
+const SHOWN_CHILDREN: usize = 4;
+
+/// How long to wait for a key before drawing the screen again
+const REDRAW_INTERVAL: Duration = Duration::from_millis(100);
+
+/// A decode in the tree, reached by the decoders on the path to it
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DecodeTree {
+    /// The decoder which made this decode
+    pub decoder: &'static str,
+    /// The best score of this decode or any decoded from it
+    pub best: f32,
+    /// Whether a plaintext was found here
+    pub solved: bool,
+    /// The decodes made from this one
+    pub children: Vec<DecodeTree>,
+}
+
+impl DecodeTree {
+    /// Adds the decode reached by `path`, scoring `score`
+    pub fn insert(&mut self, path: &[&'static str], score: f32) {
+        self.best = self.best.max(score);
+        let Some((decoder, rest)) = path.split_first() else {
+            return;
+        };
+        let index = match self.children.iter().position(|c| c.decoder == *decoder) {
+            Some(index) => index,
+            None => {
+                self.children.push(DecodeTree {
+                    decoder,
+                    ..DecodeTree::default()
+                });
+                self.children.len() - 1
+            }
+        };
+        self.children[index].insert(rest, score);
+        // The most promising branches come first
+        self.children.sort_by(|a, b| {
+            b.best
+                .partial_cmp(&a.best)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+    }
+
+    /// Marks the decode reached by `path` as the plaintext
+    pub fn solve(&mut self, path: &[&'static str]) {
+        self.insert(path, 1.0);
+        let mut node = self;
+        for decoder in path {
+            match node.children.iter_mut().find(|c| c.decoder == *decoder) {
+                Some(child) => node = child,
+                None => return,
+            }
+        }
+        node.solved = true;
+    }
+
+    /// Draws the decodes under this one, one on each line with box drawing for the branches
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        self.draw_children("", &mut lines);
+        lines
+    }
+
+    /// Draws the children of this decode, each line starting with `prefix`
+    fn draw_children(&self, prefix: &str, lines: &mut Vec<String>) {
+        let hidden = self.children.len().saturating_sub(SHOWN_CHILDREN);
+        let shown = &self.children[..self.children.len() - hidden];
+        for (i, child) in shown.iter().enumerate() {
+            let last = i + 1 == shown.len() && hidden == 0;
+            let (branch, indent) = if last {
+                ("└─ ", "   ")
+            } else {
+                ("├─ ", "│  ")
+            };
+            let solved = if child.solved { "  ✓ plaintext" } else { "" };
+            lines.push(format!(
+                "{prefix}{branch}{}  {:.2}{solved}",
+                child.decoder, child.best
+            ));
+            child.draw_children(&format!("{prefix}{indent}"), lines);
+        }
+        if hidden > 0 {
+            lines.push(format!("{prefix}└─ … {} more", hidden));
+        }
+    }
+}
+
+/// A candidate as the screen shows it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate {
+    /// The decoded text
+    pub text: String,
+    /// How much like plaintext it is, from 0.0 to 1.0
+    pub score: f32,
+    /// The decoders which reached it, in order
+    pub path: Vec<&'static str>,
+}
+
+/// What the screen shows, built up from the search's events
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TuiState {
+    /// The input being searched
+    pub input: String,
+    /// How many seconds the search may take
+    pub timeout: u32,
+    /// How many decoders deep the search is
+    pub depth: u32,
+    /// How many decodes are waiting to be decoded further
+    pub frontier: usize,
+    /// How many decodes have been decoded further
+    pub expanded: usize,
+    /// How many times a decoder ran
+    pub decoders_run: usize,
+    /// The decodes which looked most like plaintext, and the plaintext if one was found
+    pub tree: DecodeTree,
+    /// The best candidates, best first
+    pub candidates: Vec<Candidate>,
+    /// What the human checker is asking about: what it thinks the text is, and the text
+    pub prompt: Option<(String, String)>,
+    /// The plaintext, once one was found or the cache had it
+    pub plaintext: Option<String>,
+    /// How the run ended, None while it runs
+    pub outcome: Option<&'static str>,
+}
+
+impl TuiState {
+    /// The screen for a search of `input` which hasn't started yet
+    pub fn new(input: &str) -> Self {
+        TuiState {
+            input: events::shorten(input),
+            ..TuiState::default()
+        }
+    }
+
+    /// Updates the screen with something which happened in the search
+    pub fn apply(&mut self, event: SearchEvent) {
+        match event {
+            SearchEvent::Started { input, timeout } => {
+                self.input = input;
+                self.timeout = timeout;
+            }
+            SearchEvent::CacheHit { plaintext } => self.plaintext = Some(plaintext),
+            SearchEvent::DecoderAttempted { .. } => self.decoders_run += 1,
+            SearchEvent::NodesExpanded {
+                depth,
+                nodes,
+                queued,
+            } => {
+                self.depth = depth;
+                self.frontier = queued;
+                self.expanded += nodes;
+            }
+            SearchEvent::CandidateFound { text, score, path } => {
+                self.tree.insert(&path, score);
+                self.candidates.retain(|candidate| candidate.text != text);
+                self.candidates.push(Candidate { text, score, path });
+                self.candidates.sort_by(|a, b| {
+                    b.score
+                        .partial_cmp(&a.score)
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                });
+                self.candidates.truncate(SHOWN_CANDIDATES);
+            }
+            SearchEvent::HumanCheckerPrompt { description, text } => {
+                self.prompt = Some((description, text));
+            }
+            SearchEvent::HumanCheckerAnswered { .. } => self.prompt = None,
+            SearchEvent::PlaintextFound { text, path, .. } => {
+                self.tree.solve(&path);
+                self.plaintext = Some(text);
+            }
+            SearchEvent::Finished { outcome } => self.outcome = Some(outcome),
+            SearchEvent::CheckerSucceeded { .. }
+            | SearchEvent::TimedOut
+            | SearchEvent::Cancelled => {}
+        }
+    }
+}
+
+/// Formats a duration as seconds with one decimal place
+fn seconds(duration: Duration) -> String {
+    format!("{:.1}s", duration.as_secs_f64())
+}
+
+/// Draws the screen from the state of the search
+/// `remaining` is how long the search has left, None once it has ended.
+pub fn draw(frame: &mut Frame, state: &TuiState, elapsed: Duration, remaining: Option<Duration>) {
+    let time = match remaining {
+        Some(remaining) => format!("{} elapsed, {} left", seconds(elapsed), seconds(remaining)),
+        None => format!("{} elapsed", seconds(elapsed)),
+    };
+    let header = Paragraph::new(vec![
+        Line::from(vec![
+            Span::from("ciphey").bold(),
+            Span::from(format!("  {}", state.input.replace(['\n', '\r'], " "))),
+        ]),
+        Line::from(format!(
+            "{} · depth {} · frontier {} · expanded {} · decoders run {}",
+            time, state.depth, state.frontier, state.expanded, state.decoders_run
+        )),
+    ]);
+
+    let mut candidates: Vec<Line> = state
+        .candidates
+        .iter()
+        .map(|candidate| {
+            Line::from(format!(
+                "{:.2}  {}  ({})",
+                candidate.score,
+                candidate.text.replace(['\n', '\r'], " "),
+                candidate.path.join(" → ")
+            ))
+        })
+        .collect();
+    if candidates.is_empty() {
+        candidates.push(Line::from("none yet").italic());
+    }
+
+    let prompt = state.prompt.as_ref().map(|(description, text)| {
+        Paragraph::new(vec![
+            Line::from(format!(
+                "{}: {}",
+                description,
+                text.replace(['\n', '\r'], " ")
+            )),
+            Line::from("y accept · n reject").dim(),
+        ])
+        .block(Block::bordered().title("Is this the plaintext?").yellow())
+    });
+
+    let footer = match (state.outcome, &state.plaintext) {
+        (Some(outcome), Some(plaintext)) => Line::from(format!(
+            "Finished ({}): {} · press any key",
+            outcome, plaintext
+        ))
+        .green(),
+        (Some(outcome), None) => {
+            Line::from(format!("Finished ({}) · press any key", outcome)).yellow()
+        }
+        (None, _) => Line::from("q stop the search").dim(),
+    };
+
+    // The tree gets whatever room the other parts leave
+    let [header_area, tree_area, candidates_area, prompt_area, footer_area] = Layout::vertical([
+        Constraint::Length(2),
+        Constraint::Min(3),
+        Constraint::Length(u16::try_from(candidates.len() + 2).unwrap_or(u16::MAX)),
+        Constraint::Length(if prompt.is_some() { 4 } else { 0 }),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+
+    let mut tree = vec![Line::from("input")];
+    let branches = state.tree.lines();
+    if branches.is_empty() {
+        tree.push(Line::from("nothing promising yet").italic());
+    }
+    tree.extend(branches.into_iter().map(|branch| {
+        let style = if branch.ends_with("✓ plaintext") {
+            Style::new().green()
+        } else {
+            Style::new()
+        };
+        Line::styled(branch, style)
+    }));
+    // and is cut short to fit inside its borders
+    let room = usize::from(tree_area.height.saturating_sub(2));
+    if tree.len() > room {
+        tree.truncate(room.saturating_sub(1));
+        tree.push(Line::from("…"));
+    }
+
+    frame.render_widget(header, header_area);
+    frame.render_widget(
+        Paragraph::new(tree).block(Block::bordered().title("Decode tree")),
+        tree_area,
+    );
+    frame.render_widget(
+        Paragraph::new(candidates).block(Block::bordered().title("Best candidates")),
+        candidates_area,
+    );
+    if let Some(prompt) = prompt {
+        frame.render_widget(prompt, prompt_area);
+    }
+    frame.render_widget(Paragraph::new(footer), footer_area);
+}
+
+/// The terminal drawn on, put back however the screen is left
+struct Screen(Terminal<CrosstermBackend<Stdout>>);
+
+impl Screen {
+    /// Switches to a blank screen which reads keys as they are pressed
+    fn enter() -> std::io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(std::io::stdout(), terminal::EnterAlternateScreen)?;
+        // Some terminals, such as a bare pseudo-terminal, don't know their size
+        let viewport = match terminal::size()? {
+            (0, _) | (_, 0) => Viewport::Fixed(Rect::new(0, 0, 80, 24)),
+            _ => Viewport::Fullscreen,
+        };
+        let backend = CrosstermBackend::new(std::io::stdout());
+        Ok(Screen(Terminal::with_options(
+            backend,
+            TerminalOptions { viewport },
+        )?))
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        let _ = self.0.show_cursor();
+        let _ = terminal::disable_raw_mode();
+        let _ = execute!(std::io::stdout(), terminal::LeaveAlternateScreen);
+    }
+}
+
+/// Whether a key asks for the search to stop
+fn is_stop(key: &KeyEvent) -> bool {
+    matches!(key.code, KeyCode::Char('q') | KeyCode::Esc)
+        || (key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL))
+}
+
+/// Shows the search until it ends and a key is pressed
+fn show_search(
+    screen: &mut Screen,
+    state: &mut TuiState,
+    events: &Subscription,
+    answers: Sender<bool>,
+    cancel: &CancellationToken,
+    search_ended: impl Fn() -> bool,
+) -> std::io::Result<()> {
+    let started = Instant::now();
+    let mut answers = Some(answers);
+    let mut ended = None;
+    loop {
+        while let Ok(event) = events.try_recv() {
+            state.apply(event);
+        }
+        if ended.is_none() && search_ended() {
+            ended = Some(started.elapsed());
+            // Whatever was published just before the search ended is still waiting
+            while let Ok(event) = events.try_recv() {
+                state.apply(event);
+            }
+            state.outcome.get_or_insert("cancelled");
+        }
+        let remaining = match ended {
+            Some(_) => None,
+            None => crate::timer::remaining_time(),
+        };
+        let elapsed = ended.unwrap_or_else(|| started.elapsed());
+        screen
+            .0
+            .draw(|frame| draw(frame, state, elapsed, remaining))?;
+
+        if !event::poll(REDRAW_INTERVAL)? {
+            continue;
+        }
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        if ended.is_some() {
+            return Ok(());
+        }
+        if is_stop(&key) {
+            cancel.cancel();
+            // A question waiting for an answer gives up once nothing can answer it
+            answers = None;
+            state.prompt = None;
+            continue;
+        }
+        let answer = match key.code {
+            KeyCode::Char('y' | 'Y') => true,
+            KeyCode::Char('n' | 'N') => false,
+            _ => continue,
+        };
+        if let (Some(_), Some(sender)) = (state.prompt.take(), &answers) {
+            let _ = sender.send(answer);
+        }
+    }
+}
+
+/// Cracks `input` while showing the search live, then prints the result
+///
+/// # Errors
+/// Returns an error message if the terminal can't be drawn on.
+pub fn run_tui(input: &[u8], config: Config) -> Result<Option<DecoderResult>, String> {
+    let mut config = config;
+    // Nothing but the screen may be printed while it shows, so ciphey runs as it does for
+    // the API, with the human checker asking through the screen
+    config.api_mode = true;
+    let (answers, answer_receiver) = channel();
+    answer_prompts_with(answer_receiver);
+
+    let text = String::from_utf8_lossy(input).into_owned();
+    let mut state = TuiState::new(&text);
+    let events = events::subscribe();
+    let cancel = CancellationToken::new();
+    let mut screen = Screen::enter().map_err(|e| format!("Can't draw on the terminal: {}", e))?;
+    let (result, shown) = std::thread::scope(|scope| {
+        let search = scope.spawn(|| perform_cracking_bytes_with_cancel(input, config, &cancel));
+        let shown = show_search(&mut screen, &mut state, &events, answers, &cancel, || {
+            search.is_finished()
+        });
+        // The search stops if the screen can't be drawn, so the error isn't waited on
+        if shown.is_err() {
+            cancel.cancel();
+        }
+        let result = search
+            .join()
+            .unwrap_or_else(|panic| std::panic::resume_unwind(panic));
+        (result, shown)
+    });
+    drop(screen);
+    shown.map_err(|e| format!("Can't draw on the terminal: {}", e))?;
+
+    match &result {
+        Some(result) => println!(
+            "{}\nvia {}",
+            result.text.first().map_or("", String::as_str),
+            crate::cli_pretty_printing::decoded_path(result)
+        ),
+        None => println!(
+            "Not cracked ({})",
+            state.outcome.unwrap_or(if cancel.is_cancelled() {
+                "cancelled"
+            } else {
+                "unsolved"
+            })
+        ),
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+
+    /// A state which has seen `events`
+    fn state_after(events: Vec<SearchEvent>) -> TuiState {
+        let mut state = TuiState::new("aGk=");
+        for event in events {
+            state.apply(event);
+        }
+        state
+    }
+
+    /// The lines of a `width` by `height` screen drawn from `state`, without trailing spaces
+    fn screen(
+        state: &TuiState,
+        elapsed: Duration,
+        remaining: Option<Duration>,
+        width: u16,
+        height: u16,
+    ) -> Vec<String> {
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        terminal
+            .draw(|frame| draw(frame, state, elapsed, remaining))
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..height)
+            .map(|y| {
+                let line: String = (0..width).map(|x| buffer[(x, y)].symbol()).collect();
+                line.trim_end().to_string()
+            })
+            .collect()
+    }
+
+    /// A candidate event
+    fn candidate(text: &str, score: f32, path: &[&'static str]) -> SearchEvent {
+        SearchEvent::CandidateFound {
+            text: text.to_string(),
+            score,
+            path: path.to_vec(),
+        }
+    }
+
+    #[test]
+    fn events_build_up_the_progress_and_candidates() {
+        let state = state_after(vec![
+            SearchEvent::Started {
+                input: String::from("aGk="),
+                timeout: 5,
+            },
+            SearchEvent::NodesExpanded {
+                depth: 1,
+                nodes: 3,
+                queued: 12,
+            },
+            SearchEvent::NodesExpanded {
+                depth: 2,
+                nodes: 4,
+                queued: 20,
+            },
+            candidate("first", 0.3, &["Base64"]),
+            candidate("second", 0.6, &["Base64", "Hexadecimal"]),
+            candidate("first", 0.4, &["Base64"]),
+        ]);
+        assert_eq!((state.depth, state.frontier, state.expanded), (2, 20, 7));
+        assert_eq!(state.timeout, 5);
+        let texts: Vec<&str> = state.candidates.iter().map(|c| c.text.as_str()).collect();
+        assert_eq!(texts, ["second", "first"]);
+    }
+
+    #[test]
+    fn the_tree_puts_the_best_branches_first_and_marks_the_plaintext() {
+        let mut tree = DecodeTree::default();
+        tree.insert(&["Hexadecimal"], 0.2);
+        tree.insert(&["Base64"], 0.5);
+        tree.insert(&["Base64", "Caesar Cipher"], 0.7);
+        tree.solve(&["Base64", "Caesar Cipher"]);
+        assert_eq!(
+            tree.lines(),
+            [
+                "├─ Base64  1.00",
+                "│  └─ Caesar Cipher  1.00  ✓ plaintext",
+                "└─ Hexadecimal  0.20",
+            ]
+        );
+    }
+
+    #[test]
+    fn the_screen_fits_its_size_and_shows_questions() {
+        let mut events: Vec<SearchEvent> = (0..30)
+            .map(|i| candidate("text", 0.1, &["Base64", ["A", "B", "C"][i % 3], "Rot13"]))
+            .collect();
+        events.push(SearchEvent::HumanCheckerPrompt {
+            description: String::from("English"),
+            text: String::from("hello there"),
+        });
+        let state = state_after(events);
+        let lines = screen(
+            &state,
+            Duration::from_secs(2),
+            Some(Duration::from_secs(3)),
+            40,
+            14,
+        );
+        assert_eq!(lines.len(), 14);
+        assert!(lines[1].starts_with("2.0s elapsed, 3.0s left"));
+        assert!(lines.iter().any(|line| line.contains("Decode tree")));
+        assert!(lines.iter().any(|line| line.starts_with("│…")));
+        assert!(lines
+            .iter()
+            .any(|line| line == "│English: hello there                  │"));
+        assert_eq!(lines.last().unwrap(), "q stop the search");
+    }
+
+    #[test]
+    fn the_screen_says_how_the_search_ended() {
+        let state = state_after(vec![
+            SearchEvent::PlaintextFound {
+                text: String::from("hi"),
+                checker: "English Checker",
+                path: vec!["Base64"],
+            },
+            SearchEvent::Finished { outcome: "solved" },
+        ]);
+        let lines = screen(&state, Duration::from_secs(1), None, 80, 20);
+        assert!(lines
+            .iter()
+            .any(|line| line.starts_with("│└─ Base64  1.00  ✓ plaintext")));
+        assert_eq!(
+            lines.last().unwrap(),
+            "Finished (solved): hi · press any key"
+        );
+    }
+}
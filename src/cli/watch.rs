@@ -0,0 +1,125 @@
+//! Cracks each new line of a file, a FIFO or stdin with `ciphey watch`
+//! Results are printed as soon as each line is cracked, as text for reading or one JSON
+//! object per line for other tools, and stdout is flushed after every one so they can be
+//! piped on straight away.
+
+use std::io::Write;
+use std::path::PathBuf;
+
+use clap::ValueEnum;
+
+use crate::batch::BatchItem;
+use crate::cli::json_output::JsonResult;
+use crate::cli_pretty_printing::decoded_path;
+use crate::config::get_config_file_into_struct;
+use crate::watch::{perform_watch, spawn_line_reader, WatchOptions, WatchSource};
+use crate::CancellationToken;
+
+/// How the results of watching are written
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum WatchFormat {
+    /// One line for each input with what it decoded to, for reading
+    #[default]
+    Text,
+    /// One JSON object for each input, as `--output-format json` prints
+    Jsonl,
+}
+
+/// Writes the result of cracking one line, without a line break
+pub fn render_watch_item(item: &BatchItem, format: WatchFormat) -> String {
+    match format {
+        WatchFormat::Text => match &item.result {
+            Some(result) => format!(
+                "{} → {} via {}",
+                item.input,
+                result.text.first().map_or("", String::as_str),
+                decoded_path(result)
+            ),
+            None => format!("{} → not cracked ({})", item.input, item.outcome),
+        },
+        WatchFormat::Jsonl => {
+            let json = JsonResult::new(
+                &item.input,
+                item.result.as_ref(),
+                item.outcome,
+                item.duration,
+                &[],
+            );
+            serde_json::to_string(&json).unwrap_or_else(|_| String::from("{}"))
+        }
+    }
+}
+
+/// Watches `path`, or stdin if it is `-`, printing the result of each line as it is cracked
+///
+/// # Errors
+/// Returns an error message if the path can't be watched or stdout can't be written to.
+pub fn run_watch(
+    path: &str,
+    format: WatchFormat,
+    timeout: Option<u32>,
+    options: WatchOptions,
+) -> Result<(), String> {
+    let source = if path == "-" {
+        WatchSource::Stdin
+    } else {
+        WatchSource::Path(PathBuf::from(path))
+    };
+    let mut config = get_config_file_into_struct();
+    if let Some(timeout) = timeout {
+        config.timeout = timeout;
+    }
+    // Only the results are written to stdout
+    config.api_mode = true;
+
+    let stop = CancellationToken::new();
+    let (lines, _reader) = spawn_line_reader(source, options, &stop).map_err(|e| e.to_string())?;
+    let mut failed = None;
+    perform_watch(&lines, config, &stop, |item| {
+        let mut stdout = std::io::stdout().lock();
+        if let Err(e) =
+            writeln!(stdout, "{}", render_watch_item(&item, format)).and_then(|()| stdout.flush())
+        {
+            // Whatever was reading the results has gone, so stop reading lines too
+            failed = Some(format!("Can't write to stdout: {}", e));
+            stop.cancel();
+        }
+    });
+    failed.map_or(Ok(()), Err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::history::RunOutcome;
+    use crate::DecoderResult;
+    use std::time::Duration;
+
+    /// A watched line and what it was cracked to
+    fn item(result: Option<&str>, outcome: RunOutcome) -> BatchItem {
+        BatchItem {
+            input: String::from("aGk="),
+            result: result.map(DecoderResult::from),
+            outcome,
+            duration: Duration::from_millis(4),
+            deduplicated: false,
+        }
+    }
+
+    #[test]
+    fn text_results_show_the_plaintext_or_how_the_run_ended() {
+        let solved = render_watch_item(&item(Some("hi"), RunOutcome::Solved), WatchFormat::Text);
+        assert!(solved.starts_with("aGk= → hi via"));
+        let unsolved = render_watch_item(&item(None, RunOutcome::Unsolved), WatchFormat::Text);
+        assert_eq!(unsolved, "aGk= → not cracked (unsolved)");
+    }
+
+    #[test]
+    fn jsonl_results_are_one_object_per_line() {
+        let line = render_watch_item(&item(Some("hi"), RunOutcome::Cached), WatchFormat::Jsonl);
+        assert!(!line.contains('\n'));
+        let json: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(json["plaintext"], "hi");
+        assert_eq!(json["outcome"], "cached");
+    }
+}
@@ -28,6 +28,8 @@
 
 #[cfg(test)]
 mod tests;
+use crate::checkers::magic_bytes::{self, FileSignature};
+use crate::checkers::structured_data;
 use crate::storage;
 use crate::storage::wait_athena_storage::PlaintextResult;
 use crate::DecoderResult;
@@ -256,14 +258,12 @@ pub fn program_exiting_successful_decoding(result: DecoderResult) {
     if config.top_results {
         return;
     }
+    if config.all_results {
+        display_all_results(&crate::all_results());
+        return;
+    }
+    let decoded_path = decoded_path(&result);
     let plaintext = result.text;
-    // calculate path
-    let decoded_path = result
-        .path
-        .iter()
-        .map(|c| c.decoder)
-        .collect::<Vec<_>>()
-        .join(" → ");
 
     let decoded_path_coloured = statement(&decoded_path, Some("informational"));
     let decoded_path_string = if !decoded_path.contains('→') {
@@ -272,6 +272,14 @@ pub fn program_exiting_successful_decoding(result: DecoderResult) {
     } else {
         format!("the decoders used are {decoded_path_coloured}")
     };
+    if let Some(signature) = result.bytes.as_deref().and_then(magic_bytes::identify) {
+        decoded_file(
+            result.bytes.as_deref().unwrap_or_default(),
+            signature,
+            &decoded_path_string,
+        );
+        return;
+    }
     /// If 30% of the characters are invisible characters, then prompt the
     /// user to save the resulting plaintext into a file
     const INVIS_CHARS_DETECTION_PERCENTAGE: f64 = 0.3;
@@ -320,13 +328,56 @@ pub fn program_exiting_successful_decoding(result: DecoderResult) {
             return;
         }
     }
+    // Structured data is shown laid out rather than as it was decoded, often on one line
+    let shown = match result.path.last() {
+        Some(last) if last.checker_name == "Structured Data Checker" => {
+            structured_data::parse(&plaintext[0]).map(|(_, pretty)| pretty)
+        }
+        _ => None,
+    };
     println!(
         "The plaintext is:\n{}\n{}",
-        success(&plaintext[0]),
+        success(shown.as_deref().unwrap_or(&plaintext[0])),
         decoded_path_string
     );
 }
 
+/// Reports plaintext which is a file rather than text, writing it out if
+/// `--save-binary` was given instead of printing the bytes
+fn decoded_file(bytes: &[u8], signature: &FileSignature, decoded_path_string: &str) {
+    let config = crate::config::get_config();
+    println!(
+        "The plaintext is a {}\n{}",
+        success(signature.name),
+        decoded_path_string
+    );
+    let Some(path) = &config.binary_output_path else {
+        println!(
+            "Use {} to write the {} to a file.",
+            statement("--save-binary <PATH>", None),
+            signature.name
+        );
+        return;
+    };
+    let file_path = magic_bytes::output_path(path, signature);
+    match write(&file_path, bytes) {
+        Ok(()) => println!(
+            "Wrote the {} to {}",
+            signature.name,
+            statement(&file_path.display().to_string(), None)
+        ),
+        Err(e) => eprintln!(
+            "{}",
+            warning(&format!(
+                "Could not write the {} to {}: {}",
+                signature.name,
+                file_path.display(),
+                e
+            ))
+        ),
+    }
+}
+
 /// Prints the number of decoding attempts performed.
 ///
 /// # Arguments
@@ -368,6 +419,59 @@ pub fn human_checker_check(description: &str, text: &str) {
     );
 }
 
+/// Tells the user the human checker's question wasn't answered within `seconds`, and
+/// what it was counted as
+pub fn human_checker_timed_out(seconds: u32, accepted: bool) {
+    let config = crate::config::get_config();
+    if config.api_mode {
+        return;
+    }
+    let assumed = if accepted { "yes" } else { "no" };
+    println!(
+        "{}",
+        warning(&format!(
+            "No answer after {} seconds, so ciphey took it as {}.",
+            seconds, assumed
+        ))
+    );
+}
+
+/// Warns that `only_decoders` or `exclude_decoders` has a name no decoder is called or
+/// tagged with
+pub fn warning_unknown_decoder(name: &str) {
+    let config = crate::config::get_config();
+    if config.api_mode {
+        return;
+    }
+    eprintln!(
+        "{}",
+        warning(&format!(
+            "There is no decoder called or tagged '{}'. `ciphey docs decoders` lists them.",
+            name
+        ))
+    );
+}
+
+/// Warns that the human checker couldn't ask its question through the webhook
+/// Services run in `api_mode` have nobody watching their output, so it is logged as well.
+pub fn warning_webhook_failed(reason: &str) {
+    log::warn!(
+        "The human checker couldn't ask through the webhook: {}",
+        reason
+    );
+    let config = crate::config::get_config();
+    if config.api_mode {
+        return;
+    }
+    eprintln!(
+        "{}",
+        warning(&format!(
+            "The human checker couldn't ask through the webhook: {}. The text was taken as no.",
+            reason
+        ))
+    );
+}
+
 /// Prints a failure message when decoding was unsuccessful.
 ///
 /// This function provides user guidance by suggesting Discord support
@@ -387,6 +491,141 @@ pub fn failed_to_decode() {
     );
 }
 
+/// Tells the user the search was stopped before anything was identified, such as with Ctrl-C
+///
+/// # Note
+/// This message is suppressed in API mode.
+pub fn search_cancelled() {
+    let config = crate::config::get_config();
+    if config.api_mode {
+        return;
+    }
+
+    println!(
+        "{}",
+        warning("⛔️ The search was stopped before ciphey decoded the text.")
+    );
+}
+
+/// Tells the user the search saved where it got to, and how to carry on from there.
+pub fn search_can_be_resumed() {
+    let config = crate::config::get_config();
+    if config.api_mode {
+        return;
+    }
+
+    println!(
+        "{}",
+        statement(
+            "💾 Ciphey saved where the search got to. Run it again with --resume and a longer --cracking-timeout to carry on.",
+            None
+        )
+    );
+}
+
+/// Shows the user the decode which looked most like plaintext when nothing was identified.
+///
+/// # Arguments
+/// * `candidate` - The decode, with the decoders which produced it
+/// * `likelihood` - How likely it is to be plaintext, from 0 to 1
+///
+/// # Note
+/// This message is suppressed in API mode.
+pub fn most_likely_candidate(candidate: &DecoderResult, likelihood: f32) {
+    let config = crate::config::get_config();
+    if config.api_mode {
+        return;
+    }
+    let decoded_path = candidate
+        .path
+        .iter()
+        .map(|c| c.decoder)
+        .collect::<Vec<_>>()
+        .join(" → ");
+    println!(
+        "{}",
+        statement(
+            &format!(
+                "The most likely candidate ({:.0}% like plaintext) came from {}:\n{}",
+                likelihood * 100.0,
+                decoded_path,
+                candidate.text.first().map_or("", String::as_str)
+            ),
+            None
+        )
+    );
+}
+
+/// Shows the user the decodes which looked most like plaintext when nothing was identified,
+/// as many as `show_candidates` asks for.
+///
+/// # Arguments
+/// * `candidates` - The decodes, most likely first, and how likely each is to be plaintext
+///
+/// # Note
+/// This message is suppressed in API mode.
+pub fn best_candidates(candidates: &[(DecoderResult, f32)]) {
+    let config = crate::config::get_config();
+    if config.api_mode {
+        return;
+    }
+    let shown = &candidates[..candidates.len().min(config.show_candidates)];
+    if let [(candidate, likelihood)] = shown {
+        most_likely_candidate(candidate, *likelihood);
+        return;
+    }
+    if shown.is_empty() {
+        return;
+    }
+    println!(
+        "{}",
+        statement(
+            &format!("The {} most likely candidates were:", shown.len()),
+            None
+        )
+    );
+    for (i, (candidate, likelihood)) in shown.iter().enumerate() {
+        println!(
+            "{}",
+            statement(
+                &format!(
+                    "#{} ({:.0}% like plaintext) from {}:\n{}",
+                    i + 1,
+                    likelihood * 100.0,
+                    decoded_path(candidate),
+                    candidate.text.first().map_or("", String::as_str)
+                ),
+                None
+            )
+        );
+    }
+}
+
+/// Tells the user the input looks like a hash, which can only be looked up and not decoded.
+///
+/// # Arguments
+/// * `algorithms` - The algorithms the hash could be from, most likely first
+///
+/// # Note
+/// This message is suppressed in API mode.
+pub fn input_looks_like_a_hash(algorithms: &[&str]) {
+    let config = crate::config::get_config();
+    if config.api_mode {
+        return;
+    }
+
+    println!(
+        "{}",
+        statement(
+            &format!(
+                "Your input looks like a {} hash. Hashes can't be decoded, only looked up, and it isn't the hash of a common key or a word of your wordlist. Try a bigger wordlist with --wordlist.",
+                algorithms.join(" or ")
+            ),
+            None
+        )
+    );
+}
+
 /// Updates the user on decoding progress with a countdown timer.
 ///
 /// # Arguments
@@ -400,7 +639,7 @@ pub fn countdown_until_program_ends(seconds_spent_running: u32, duration: u32) {
     if config.api_mode {
         return;
     }
-    if seconds_spent_running % 5 == 0 && seconds_spent_running != 0 {
+    if seconds_spent_running.is_multiple_of(5) && seconds_spent_running != 0 {
         let time_left = duration - seconds_spent_running;
         if time_left == 0 {
             return;
@@ -472,7 +711,120 @@ pub fn warning_unknown_config_key(key: &str) {
     );
 }
 
+/// Warns that a plugin in the plugins directory couldn't be loaded and won't be searched.
+///
+/// # Arguments
+/// * `path` - The plugin's file
+/// * `reason` - Why it couldn't be loaded
+///
+/// # Note
+/// This warning is suppressed in API mode.
+pub fn warning_plugin_not_loaded(path: &std::path::Path, reason: &str) {
+    let config = crate::config::get_config();
+    if config.api_mode {
+        return;
+    }
+    eprintln!(
+        "{}",
+        warning(&format!(
+            "Could not load the plugin {}: {}",
+            path.display(),
+            reason
+        ))
+    );
+}
+
+/// Warns that the database file can't be used and results won't be saved.
+///
+/// # Arguments
+/// * `reason` - Why the database file couldn't be used
+///
+/// # Note
+/// This warning is suppressed in API mode.
+pub fn warning_database_unavailable(reason: &str) {
+    let config = crate::config::get_config();
+    if config.api_mode {
+        return;
+    }
+    eprintln!(
+        "{}",
+        warning(&format!(
+            "{}. Using an in-memory database for this run, so nothing will be cached between runs. Run `ciphey db doctor` to diagnose and repair it.",
+            reason
+        ))
+    );
+}
+
+/// Warns that the storage backend in the config can't be used, so the SQLite database is
+/// used instead.
+///
+/// # Arguments
+/// * `reason` - Why the backend couldn't be used
+///
+/// # Note
+/// This warning is suppressed in API mode.
+pub fn warning_storage_unavailable(reason: &str) {
+    let config = crate::config::get_config();
+    if config.api_mode {
+        return;
+    }
+    eprintln!(
+        "{}",
+        warning(&format!(
+            "Could not use the {:?} storage backend: {}. Using the SQLite database instead.",
+            config.storage_backend, reason
+        ))
+    );
+}
+
+/// The decoders used to reach a result joined by arrows, showing the key for decoders which
+/// recovered one
+pub fn decoded_path(result: &DecoderResult) -> String {
+    result
+        .path
+        .iter()
+        .map(|c| match &c.key {
+            Some(key) => format!("{} (key: {})", c.decoder, key),
+            None => c.decoder.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(" → ")
+}
+
+/// Displays every plaintext an all_results search found, best first, with how confident
+/// the checker was and the decoders used to reach it
+pub fn display_all_results(results: &[DecoderResult]) {
+    let config = crate::config::get_config();
+    if config.api_mode {
+        return;
+    }
+    println!(
+        "{}",
+        success(&format!(
+            "\nFound {} possible plaintext{}, best first:",
+            results.len(),
+            if results.len() == 1 { "" } else { "s" }
+        ))
+    );
+    for (i, result) in results.iter().enumerate() {
+        let confidence = result.path.last().map_or(0.0, |step| step.confidence);
+        println!(
+            "\n{} {}",
+            statement(&format!("#{}", i + 1), Some("informational")),
+            result.text.first().map_or("", String::as_str)
+        );
+        println!(
+            "   confidence {:.2}, {}",
+            confidence,
+            statement(&decoded_path(result), Some("informational"))
+        );
+    }
+}
+
 /// Display all plaintext results collected by WaitAthena
+///
+/// # Panics
+/// Panics if the user's answer cannot be read from stdin.
 pub fn display_top_results(results: &[PlaintextResult]) {
     let config = crate::config::get_config();
     if config.api_mode {
@@ -1,4 +1,5 @@
 /// import general checker
+use gibberish_or_not::Sensitivity;
 use lemmeknow::Identifier;
 use memmap2::Mmap;
 use once_cell::sync::OnceCell;
@@ -7,7 +8,14 @@ use std::collections::{HashMap, HashSet};
 use std::fs::{self, File};
 use std::io::{self, BufRead, BufReader};
 use std::io::{Read, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::storage::backend::StorageBackend;
+use crate::storage::database::SettingRow;
+
+/// Named bundles of settings chosen with `--profile`
+pub mod profiles;
 
 /// Library input is the default API input
 /// The CLI turns its arguments into a LibraryInput struct
@@ -46,29 +54,397 @@ pub struct Config {
     /// Should the human checker be on?
     /// This asks yes/no for plaintext. Turn off for API
     pub human_checker_on: bool,
+    /// How many seconds the human checker waits for an answer to each question, 0 to wait
+    /// as long as it takes. The search carries on while a question waits.
+    pub human_checker_timeout: u32,
+    /// What a question nobody answered within `human_checker_timeout` counts as
+    pub human_checker_default: HumanCheckerDefault,
+    /// The URL the human checker POSTs its questions to instead of asking on the terminal,
+    /// for servers nobody sits at. Empty asks on the terminal.
+    pub human_checker_webhook: String,
+    /// What the webhook is, so questions are sent in a way it can show, see [`WebhookFormat`]
+    pub human_checker_webhook_format: WebhookFormat,
+    /// How many seconds a question sent to the webhook waits for an answer, 0 to wait as
+    /// long as it takes. One nobody answered counts as `human_checker_default`.
+    pub human_checker_webhook_timeout: u32,
+    /// Where the approve and reject links sent with each question point, such as
+    /// `http://ciphey.example.com:8181`. ciphey listens for answers on this URL's port.
+    /// Empty only takes the answer from the webhook's response.
+    pub human_checker_webhook_answer_url: String,
     /// The timeout threshold before ciphey quits
     /// This is in seconds
     pub timeout: u32,
+    /// How readily the checkers take a decode to be words rather than gibberish
+    pub sensitivity: GibberishSensitivity,
     /// Whether to collect all plaintexts until timeout expires
     /// instead of exiting after finding the first valid plaintext
     pub top_results: bool,
+    /// Whether to keep searching after the first plaintext until the timeout expires, and
+    /// return every distinct plaintext found ranked by confidence then path length
+    pub all_results: bool,
+    /// How many of the decodes most like plaintext to show when no checker identifies the
+    /// plaintext, ranked by the Entropy Checker's scores. 0 shows none.
+    pub show_candidates: usize,
+    /// How the decodes of the input are searched for the plaintext, see [`SearchStrategy`]
+    pub search_strategy: SearchStrategy,
+    /// How many decodes of each depth beam search decodes further
+    pub beam_width: usize,
+    /// How many threads the search decodes on, 0 for one on every core
+    pub threads: usize,
+    /// Whether the A* search carries on from the checkpoint saved when the last search of
+    /// the same input ran out of time. Set with `--resume`, never from the config file.
+    #[serde(skip)]
+    pub resume: bool,
+    /// Whether plaintexts rejected with the human checker in earlier runs can be accepted
+    /// again. Set with `--ignore-rejections`, never from the config file.
+    #[serde(skip)]
+    pub ignore_rejections: bool,
+    /// Searches even when the cache holds a result for the text, such as when
+    /// `ciphey replay --rerun` repeats a run. Never read from the config file
+    #[serde(skip)]
+    pub skip_cache: bool,
     /// Is the program being run in API mode?
     /// This is used to determine if we should print to stdout
     /// Or return the values
     pub api_mode: bool,
+    /// How the CLI prints the result, see [`OutputFormat`]
+    pub output_format: OutputFormat,
+    /// Show the search live in the terminal, with its decode tree, best candidates and the
+    /// human checker's questions, instead of only printing the result at the end
+    pub tui: bool,
+    /// The most bytes of input the CLI reads from `--text`, `--file` or stdin, 0 for no
+    /// limit. Longer input is refused rather than read into memory.
+    pub max_input_bytes: usize,
     /// Regex enables the user to search for a specific regex or crib
     pub regex: Option<String>,
+    /// More patterns for the Regex checker alongside `regex`, such as `flag\{.*\}`.
+    /// Text matching any of them is the plaintext.
+    pub regexes: Vec<String>,
     /// Path to the wordlist file. Will be overridden by CLI argument if provided.
     pub wordlist_path: Option<String>,
     /// Wordlist data structure (loaded from file). CLI takes precedence if both config and CLI specify a wordlist.
     #[serde(skip)]
     pub wordlist: Option<HashSet<String>>,
+    /// Whether text containing a wordlist entry of at least four characters counts as the
+    /// plaintext, not only text which is an entry. Also set with `--wordlist-contains`.
+    pub wordlist_contains: bool,
+    /// The language plaintext is expected in, as a code such as `es` or a name such as
+    /// `Spanish`, or `auto` to accept any supported language. English is always checked.
+    pub language: String,
+    /// Where to write the plaintext when it decodes to a file such as a ZIP archive, as a
+    /// file path or a directory to write `decoded.<extension>` in. Also set with `--save-binary`.
+    pub binary_output_path: Option<String>,
+    /// How confident the checkers have to be, from 0 to 1, before text is taken as the
+    /// plaintext. Raising it trades missed plaintexts for fewer false positives.
+    pub min_confidence: f32,
     /// Colourscheme hashmap
     pub colourscheme: HashMap<String, String>,
     /// Enables enhanced plaintext detection using a BERT model.
     pub enhanced_detection: bool,
     /// Path to the enhanced detection model. If None, will use the default path.
     pub model_path: Option<String>,
+    /// Path to the database holding the cache and saved toggles.
+    /// If None, `~/.ciphey/database.sqlite` is used. The `CIPHEY_DATABASE_PATH` environment
+    /// variable takes precedence over it.
+    pub database_path: Option<String>,
+    /// Keeps the cache, statistics and toggles in memory for this run only, so nothing is
+    /// read from or written to the database file. Set with `--no-database`, never from the
+    /// config file.
+    #[serde(skip)]
+    pub no_database: bool,
+    /// Whether every run is recorded in the history shown by `ciphey history`
+    pub run_history: bool,
+    /// The most runs the history keeps, 0 for no limit
+    /// Once it is full the oldest runs are forgotten first.
+    pub history_max_runs: usize,
+    /// Where searches keep the cache, rejections, statistics and checkpoints: `sqlite` for
+    /// the database at `database_path`, `memory` for this run only, or `redis` for a server
+    /// shared across machines, which needs ciphey built with the `redis` feature.
+    pub storage_backend: StorageBackend,
+    /// URL of the server the `redis` backend uses, such as `redis://:password@host:6379/0`
+    pub storage_url: Option<String>,
+    /// Directory of WebAssembly plugin decoders to load.
+    /// If None, `~/.ciphey/plugins` is used.
+    pub plugins_dir: Option<String>,
+    /// Plaintexts that should never be accepted, on top of the built-in degenerate ones
+    /// (empty text, single characters and one character repeated).
+    /// Matched case-insensitively against the whole plaintext.
+    pub plaintext_denylist: Vec<String>,
+    /// Decoders to turn on or off, keyed by decoder name, e.g. `Brainfuck = false`
+    /// Anything missing falls back to what was saved with `ciphey toggle`, then to enabled.
+    pub decoder_toggles: HashMap<String, bool>,
+    /// Checkers to turn on or off, keyed by checker name, e.g. `"Password Checker" = false`
+    /// Anything missing falls back to what was saved with `ciphey toggle`, then to enabled.
+    pub checker_toggles: HashMap<String, bool>,
+    /// The only decoders to run, by name or tag such as `["base64", "hex"]`. A tag picks
+    /// every decoder with it, so `base` picks every base encoding. Empty runs every decoder.
+    pub only_decoders: Vec<String>,
+    /// Decoders never to run, by name or tag, even if `only_decoders` picks them
+    pub exclude_decoders: Vec<String>,
+    /// Named bundles of settings chosen with `--profile`, see [`profiles::Profile`]
+    /// These replace the built-in profiles of the same name.
+    pub profiles: HashMap<String, profiles::Profile>,
+    /// Rules forbidding decoders in parts of a decoding chain, see [`ChainRule`]
+    pub chain_rules: Vec<ChainRule>,
+    /// The regexes `ciphey scan` pulls candidate encoded strings out of files with, see
+    /// [`crate::scan::ScanExtractor`]. Defaults to runs of hex and of Base64.
+    pub scan_extractors: Vec<crate::scan::ScanExtractor>,
+    /// The most decoders a decoding chain can have, None for no limit
+    /// Depth first search stops following a chain at 10 decoders without it.
+    pub max_depth: Option<usize>,
+    /// The most times each decoder can be used in one decoding chain, keyed by decoder name,
+    /// e.g. `caesar = 2`. Decoders missing from it can be used any number of times.
+    pub decoder_depth_limits: HashMap<String, usize>,
+    /// Characters which separate the numbers of A1Z26 text, on top of whitespace
+    pub a1z26_delimiters: String,
+    /// How many results to keep in memory in front of the SQLite cache, 0 turns it off
+    pub memory_cache_entries: usize,
+    /// The most results the SQLite cache keeps, 0 for no limit
+    /// Once it is full the results used longest ago are forgotten first.
+    pub cache_max_entries: usize,
+    /// The most decodes the SQLite cache keeps of the texts met during searches, 0 for no
+    /// limit. Once it is full the decodes cached longest ago are forgotten first.
+    pub edge_cache_max_entries: usize,
+    /// How many days cached results and decodes are kept without being used, 0 keeps them
+    /// forever
+    pub cache_max_age_days: u32,
+    /// How many keys the substitution solver scores before giving up on a text
+    /// Higher solves more ciphertexts, but every text it runs on takes longer.
+    pub substitution_iterations: u32,
+    /// How many key squares the Playfair cracker scores before giving up on a text
+    /// Playfair needs far more than substitution, as a square can be wrong in many more ways.
+    pub playfair_iterations: u32,
+    /// How many of the 60 rotor orders of an M3 Enigma the Enigma cracker tries
+    /// Every order is tried at all 17,576 rotor positions, so each one adds a lot of work.
+    pub enigma_rotor_combinations: u32,
+    /// A custom 64 character alphabet the Base64 decoder tries after the standard and
+    /// URL-safe ones, from the character for 0 to the one for 63. `=` is still padding.
+    pub base64_alphabet: Option<String>,
+    /// The keyboard layouts the keyboard shift decoder moves keys on, out of `qwerty`,
+    /// `azerty` and `dvorak`. Each one adds four more candidates to every text.
+    pub keyboard_layouts: Vec<String>,
+    /// How many decryptions the brute force cipher decoder tries on a text, counting each
+    /// key once for every cipher. Keys come from a short built-in list and the wordlist.
+    pub brute_force_attempts: u32,
+    /// Keys the keyed decryption decoder tries with AES, ChaCha20-Poly1305 and Fernet, as
+    /// text, hex or Base64. Set with `--key` and `--keyfile`, never from the config file,
+    /// so keys aren't left on disk.
+    #[serde(skip)]
+    pub decryption_keys: Vec<String>,
+}
+
+/// How the decodes of the input are searched for the plaintext
+/// In the config file it is written as `search_strategy = "beam"`.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum SearchStrategy {
+    /// Decodes the heuristic ranks most promising first, whatever their depth
+    #[default]
+    #[serde(rename = "astar")]
+    #[value(name = "astar")]
+    AStar,
+    /// Every decode of one depth before any of the next, best for wide but shallow inputs
+    #[serde(alias = "bfs")]
+    #[value(alias = "bfs")]
+    BreadthFirst,
+    /// Each chain of decoders as deep as it goes before the next, best for deep inputs
+    #[serde(alias = "dfs")]
+    #[value(alias = "dfs")]
+    DepthFirst,
+    /// One depth at a time, only decoding the `beam_width` most promising decodes of each
+    /// further, for inputs both deep and wide
+    Beam,
+}
+
+/// How the CLI prints the result of cracking a text
+/// In the config file it is written as `output_format = "json"`.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    /// Explains the plaintext and the decoders used to reach it for a person to read
+    #[default]
+    Text,
+    /// One JSON object on stdout with the plaintext, every decode on the way to it and
+    /// how long each took, for scripts. Nothing else is printed to stdout.
+    Json,
+}
+
+/// What a question of the human checker counts as when nobody answers it in time
+/// In the config file it is written as `human_checker_default = "yes"`.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum HumanCheckerDefault {
+    /// The text is the plaintext
+    Yes,
+    /// The text isn't the plaintext, though it isn't remembered as rejected
+    #[default]
+    No,
+}
+
+/// How readily the checkers take a decode to be words rather than gibberish
+/// In the config file it is written as `sensitivity = "high"`.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum GibberishSensitivity {
+    /// Only text which is clearly words, for fewer false positives
+    Low,
+    /// A balance between missing plaintexts and false positives
+    #[default]
+    Medium,
+    /// Anything which looks a little like words, for short or unusual plaintexts
+    High,
+}
+
+impl From<GibberishSensitivity> for Sensitivity {
+    fn from(sensitivity: GibberishSensitivity) -> Self {
+        match sensitivity {
+            GibberishSensitivity::Low => Sensitivity::Low,
+            GibberishSensitivity::Medium => Sensitivity::Medium,
+            GibberishSensitivity::High => Sensitivity::High,
+        }
+    }
+}
+
+/// What the human checker's webhook is, which decides what its questions look like
+/// In the config file it is written as `human_checker_webhook_format = "slack"`.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookFormat {
+    /// Any HTTP endpoint, sent the question as JSON. It may answer in its response with
+    /// `{"accepted": true}`, or by following the approve or reject link.
+    #[default]
+    Generic,
+    /// A Slack incoming webhook, sent a message with approve and reject links
+    Slack,
+    /// A Discord webhook, sent a message with approve and reject links
+    Discord,
+}
+
+/// A rule forbidding a decoder from running in some part of a decoding chain
+/// Every condition that is set has to match for the decoder to be skipped.
+/// Depth is how many decoders have already run, so the input itself is at depth 0.
+/// ```toml
+/// # Never run Base65536 on the output of Binary
+/// [[chain_rules]]
+/// decoder = "Base65536"
+/// after = "Binary"
+///
+/// # Never run Brainfuck before depth 2
+/// [[chain_rules]]
+/// decoder = "Brainfuck"
+/// max_depth = 1
+/// ```
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct ChainRule {
+    /// The decoder this rule forbids
+    pub decoder: String,
+    /// Only forbid the decoder straight after this one
+    pub after: Option<String>,
+    /// Only forbid the decoder at this depth or deeper
+    pub min_depth: Option<usize>,
+    /// Only forbid the decoder at this depth or shallower
+    pub max_depth: Option<usize>,
+}
+
+impl ChainRule {
+    /// Returns true if this rule forbids running `decoder` at `depth`,
+    /// straight after `previous` (None for the input itself)
+    /// Decoder names are matched ignoring case.
+    pub fn forbids(&self, decoder: &str, previous: Option<&str>, depth: usize) -> bool {
+        self.decoder.eq_ignore_ascii_case(decoder)
+            && self.after.as_ref().is_none_or(|after| {
+                previous.is_some_and(|previous| after.eq_ignore_ascii_case(previous))
+            })
+            && self.min_depth.is_none_or(|min| depth >= min)
+            && self.max_depth.is_none_or(|max| depth <= max)
+    }
+}
+
+impl Config {
+    /// Reads a config saved as JSON with a run in the history, so the run can be repeated
+    /// The wordlist is loaded again from `wordlist_path`. Keys given with `--key` are never
+    /// saved, so runs which needed them have to be given them again.
+    ///
+    /// # Errors
+    /// Returns an error message if the JSON isn't a config or the wordlist can't be loaded.
+    pub fn from_run_snapshot(json: &str) -> Result<Config, String> {
+        let mut config: Config = serde_json::from_str(json)
+            .map_err(|e| format!("The run's config can't be read: {}", e))?;
+        update_identifier_in_config(&mut config);
+        if let Some(wordlist_path) = &config.wordlist_path {
+            let wordlist = load_wordlist(wordlist_path)
+                .map_err(|e| format!("Can't load wordlist at '{}': {}", wordlist_path, e))?;
+            config.wordlist = Some(wordlist);
+        }
+        Ok(config)
+    }
+
+    /// Returns whether the decoder with this name should be run
+    /// Decoders are enabled unless they have been toggled off
+    pub fn is_decoder_enabled(&self, name: &str) -> bool {
+        toggle_state(&self.decoder_toggles, name).unwrap_or(true)
+    }
+
+    /// Returns whether the checker with this name should be run
+    /// Checkers are enabled unless they have been toggled off
+    pub fn is_checker_enabled(&self, name: &str) -> bool {
+        toggle_state(&self.checker_toggles, name).unwrap_or(true)
+    }
+
+    /// Returns every pattern the Regex checker looks for, `regex` first
+    /// The other checkers are turned off when there are any.
+    pub fn regex_patterns(&self) -> Vec<&str> {
+        self.regex
+            .iter()
+            .chain(&self.regexes)
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Returns whether the chain rules allow `decoder` at `depth`, straight after `previous`
+    pub fn is_chain_allowed(&self, decoder: &str, previous: Option<&str>, depth: usize) -> bool {
+        !self
+            .chain_rules
+            .iter()
+            .any(|rule| rule.forbids(decoder, previous, depth))
+    }
+
+    /// Returns whether the depth limits allow `decoder` at `depth`, when it has already
+    /// been used `uses` times in the chain
+    /// Decoder names are matched ignoring case.
+    pub fn is_depth_allowed(&self, decoder: &str, depth: usize, uses: usize) -> bool {
+        self.max_depth.is_none_or(|max| depth < max)
+            && self
+                .decoder_depth_limits
+                .iter()
+                .find(|(limited, _)| limited.eq_ignore_ascii_case(decoder))
+                .is_none_or(|(_, limit)| uses < *limit)
+    }
+
+    /// Merges the enable state saved in the database into the config
+    /// The config file takes precedence, so saved settings only fill in
+    /// decoders and checkers the config does not mention.
+    pub fn merge_stored_toggles(&mut self, settings: &[SettingRow]) {
+        for setting in settings {
+            let toggles = match setting.kind.as_str() {
+                "decoder" => &mut self.decoder_toggles,
+                "checker" => &mut self.checker_toggles,
+                _ => continue,
+            };
+            if toggle_state(toggles, &setting.name).is_none() {
+                toggles.insert(setting.name.clone(), setting.enabled);
+            }
+        }
+    }
+}
+
+/// Looks up a toggle by name, ignoring case so config files don't have to match exactly
+fn toggle_state(toggles: &HashMap<String, bool>, name: &str) -> Option<bool> {
+    toggles
+        .iter()
+        .find(|(toggled, _)| toggled.eq_ignore_ascii_case(name))
+        .map(|(_, enabled)| *enabled)
 }
 
 /// Cell for storing global Config
@@ -123,15 +499,67 @@ impl Default for Config {
             lemmeknow_exclude_tags: vec![],
             lemmeknow_boundaryless: false,
             human_checker_on: false,
+            human_checker_timeout: 0,
+            human_checker_default: HumanCheckerDefault::No,
+            human_checker_webhook: String::new(),
+            human_checker_webhook_format: WebhookFormat::Generic,
+            human_checker_webhook_timeout: 300,
+            human_checker_webhook_answer_url: String::new(),
             timeout: 5,
+            sensitivity: GibberishSensitivity::Medium,
             top_results: false,
+            all_results: false,
+            show_candidates: 1,
+            search_strategy: SearchStrategy::AStar,
+            beam_width: 30,
+            threads: 0,
+            resume: false,
+            ignore_rejections: false,
+            skip_cache: false,
             api_mode: false,
+            output_format: OutputFormat::Text,
+            tui: false,
+            max_input_bytes: crate::cli::input::DEFAULT_MAX_INPUT_BYTES,
             regex: None,
+            regexes: Vec::new(),
             wordlist_path: None,
             wordlist: None,
+            wordlist_contains: false,
+            language: "en".to_string(),
+            binary_output_path: None,
+            min_confidence: 0.0,
             enhanced_detection: false,
             model_path: None,
+            database_path: None,
+            no_database: false,
+            run_history: true,
+            history_max_runs: crate::storage::history::DEFAULT_HISTORY_MAX_RUNS,
+            storage_backend: StorageBackend::Sqlite,
+            storage_url: None,
+            plugins_dir: None,
             colourscheme: HashMap::new(),
+            plaintext_denylist: vec![],
+            decoder_toggles: HashMap::new(),
+            checker_toggles: HashMap::new(),
+            only_decoders: Vec::new(),
+            exclude_decoders: Vec::new(),
+            profiles: HashMap::new(),
+            chain_rules: vec![],
+            scan_extractors: crate::scan::default_extractors(),
+            max_depth: None,
+            decoder_depth_limits: HashMap::new(),
+            a1z26_delimiters: String::from(",;:-"),
+            memory_cache_entries: crate::storage::memory_cache::DEFAULT_CAPACITY,
+            cache_max_entries: crate::storage::database::DEFAULT_CACHE_MAX_ENTRIES,
+            edge_cache_max_entries: crate::storage::database::DEFAULT_EDGE_CACHE_MAX_ENTRIES,
+            cache_max_age_days: crate::storage::database::DEFAULT_CACHE_MAX_AGE_DAYS,
+            substitution_iterations: 30_000,
+            playfair_iterations: 1_000_000,
+            enigma_rotor_combinations: 60,
+            base64_alphabet: None,
+            keyboard_layouts: vec![String::from("qwerty")],
+            brute_force_attempts: 20_000,
+            decryption_keys: Vec::new(),
         };
 
         // Set default colors
@@ -155,7 +583,18 @@ impl Default for Config {
     }
 }
 
+/// The config file given with `--config`, read instead of the usual ones
+static CONFIG_FILE_PATH: OnceLock<PathBuf> = OnceLock::new();
+
+/// Reads the config file at `path` instead of the usual ones, as `--config` does
+/// Only the first path set is used.
+pub fn set_config_file_path(path: PathBuf) {
+    CONFIG_FILE_PATH.set(path).ok();
+}
+
 /// Get the path to the ciphey config file
+/// This is the one given with `--config`, else `~/.config/ciphey/config.toml` if it exists,
+/// else `~/.ciphey/config.toml`.
 ///
 /// # Panics
 ///
@@ -163,7 +602,15 @@ impl Default for Config {
 /// - The home directory cannot be found
 /// - The ciphey directory cannot be created
 pub fn get_config_file_path() -> std::path::PathBuf {
-    let mut path = dirs::home_dir().expect("Could not find home directory");
+    if let Some(path) = CONFIG_FILE_PATH.get() {
+        return path.clone();
+    }
+    let home = dirs::home_dir().expect("Could not find home directory");
+    let xdg_path = home.join(".config").join("ciphey").join("config.toml");
+    if xdg_path.exists() {
+        return xdg_path;
+    }
+    let mut path = home;
     path.push(".ciphey");
     fs::create_dir_all(&path).expect("Could not create ciphey directory");
     path.push("config.toml");
@@ -177,6 +624,10 @@ pub fn get_config_file_path() -> std::path::PathBuf {
 /// This function will panic if:
 /// - The config cannot be serialized to TOML
 /// - The config file path cannot be determined (see `get_config_file_path`)
+///
+/// # Errors
+///
+/// Returns an error if the config file cannot be created or written to.
 pub fn create_default_config_file() -> std::io::Result<()> {
     let config = Config::default();
     let toml_string = toml::to_string_pretty(&config).expect("Could not serialize config");
@@ -195,6 +646,34 @@ fn read_config_file() -> std::io::Result<String> {
     Ok(contents)
 }
 
+/// Environment variable which sets the database path, taking precedence over `database_path`
+/// in the config file. Useful in containers and for giving each user of a machine their own.
+pub const DATABASE_PATH_ENV: &str = "CIPHEY_DATABASE_PATH";
+
+/// The database path set with [`DATABASE_PATH_ENV`], None if it isn't set or is empty
+pub fn get_database_path_from_env() -> Option<String> {
+    std::env::var(DATABASE_PATH_ENV)
+        .ok()
+        .filter(|path| !path.is_empty())
+}
+
+/// The database path set in the environment or else the config file, without running the
+/// setup wizard if there is no config file yet. Subcommands use this to find the same
+/// database as decoding does.
+pub fn get_configured_database_path() -> Option<String> {
+    get_database_path_from_env().or_else(|| get_existing_config()?.database_path)
+}
+
+/// The config file, without running the setup wizard if there is no config file yet
+/// Subcommands use this to run with the same settings as decoding does.
+pub fn get_existing_config() -> Option<Config> {
+    if !get_config_file_path().exists() {
+        return None;
+    }
+    let contents = read_config_file().ok()?;
+    toml::from_str::<Config>(&contents).ok()
+}
+
 /// Parse a TOML string into a Config struct, handling unknown keys
 fn parse_toml_with_unknown_keys(contents: &str) -> Config {
     // First parse into a generic Value to check for unknown keys
@@ -207,18 +686,65 @@ fn parse_toml_with_unknown_keys(contents: &str) -> Config {
             "lemmeknow_min_rarity",
             "enhanced_detection",
             "model_path",
+            "database_path",
+            "run_history",
+            "history_max_runs",
+            "storage_backend",
+            "storage_url",
+            "plugins_dir",
             "lemmeknow_max_rarity",
             "lemmeknow_tags",
             "lemmeknow_exclude_tags",
             "lemmeknow_boundaryless",
             "human_checker_on",
+            "human_checker_timeout",
+            "human_checker_default",
+            "human_checker_webhook",
+            "human_checker_webhook_format",
+            "human_checker_webhook_timeout",
+            "human_checker_webhook_answer_url",
             "timeout",
+            "sensitivity",
             "top_results",
+            "all_results",
+            "show_candidates",
+            "search_strategy",
+            "beam_width",
+            "threads",
             "api_mode",
+            "output_format",
+            "tui",
+            "max_input_bytes",
             "regex",
+            "regexes",
             "wordlist_path",
+            "wordlist_contains",
+            "language",
+            "binary_output_path",
+            "min_confidence",
             "question",
             "colourscheme",
+            "plaintext_denylist",
+            "decoder_toggles",
+            "checker_toggles",
+            "only_decoders",
+            "exclude_decoders",
+            "profiles",
+            "chain_rules",
+            "scan_extractors",
+            "max_depth",
+            "decoder_depth_limits",
+            "a1z26_delimiters",
+            "memory_cache_entries",
+            "cache_max_entries",
+            "edge_cache_max_entries",
+            "cache_max_age_days",
+            "substitution_iterations",
+            "playfair_iterations",
+            "enigma_rotor_combinations",
+            "base64_alphabet",
+            "keyboard_layouts",
+            "brute_force_attempts",
         ];
         for key in table.keys() {
             if !known_keys.contains(&key.as_str()) {
@@ -250,6 +776,9 @@ fn parse_toml_with_unknown_keys(contents: &str) -> Config {
 /// * The file cannot be memory-mapped
 /// * The file contains invalid UTF-8 characters
 ///
+/// # Panics
+/// Panics if a memory-mapped wordlist file contains invalid UTF-8.
+///
 /// # Safety
 /// This implementation uses unsafe code in two places:
 /// 1. Memory mapping (unsafe { Mmap::map(&file) }):
@@ -274,12 +803,10 @@ pub fn load_wordlist<P: AsRef<Path>>(path: P) -> io::Result<HashSet<String>> {
         let reader = BufReader::new(file);
         let mut wordlist = HashSet::new();
 
-        for line in reader.lines() {
-            if let Ok(word) = line {
-                let trimmed = word.trim().to_string();
-                if !trimmed.is_empty() {
-                    wordlist.insert(trimmed);
-                }
+        for word in reader.lines().map_while(Result::ok) {
+            let trimmed = word.trim().to_string();
+            if !trimmed.is_empty() {
+                wordlist.insert(trimmed);
             }
         }
 
@@ -308,45 +835,43 @@ pub fn load_wordlist<P: AsRef<Path>>(path: P) -> io::Result<HashSet<String>> {
     }
 }
 
+/// Loads the keys in a keyfile for the keyed decryption decoder
+/// A text file holds one key on each line. Any other file is a single raw key, which is
+/// returned as hex so it reads back as the same bytes.
+///
+/// # Errors
+///
+/// Returns an error if the keyfile can't be read.
+pub fn load_keyfile<P: AsRef<Path>>(path: P) -> io::Result<Vec<String>> {
+    let contents = fs::read(path)?;
+    match String::from_utf8(contents) {
+        Ok(text) => Ok(text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(String::from)
+            .collect()),
+        Err(error) => Ok(vec![error
+            .as_bytes()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()]),
+    }
+}
+
 /// Get configuration from file or create default if it doesn't exist
 pub fn get_config_file_into_struct() -> Config {
     let path = get_config_file_path();
 
+    if !path.exists() && CONFIG_FILE_PATH.get().is_some() {
+        // Critical error - the user asked for this file, so don't quietly use another config
+        eprintln!("There is no config file at '{}'", path.display());
+        std::process::exit(1);
+    }
     if !path.exists() {
         // First run - get user preferences
         let first_run_config = crate::cli::run_first_time_setup();
-        let mut config = Config::default();
-
-        // Extract color scheme values
-        config.colourscheme = first_run_config
-            .iter()
-            .filter(|(k, _)| !k.starts_with("wordlist") && *k != "timeout")
-            .map(|(k, v)| (k.clone(), v.clone()))
-            .collect();
-
-        // Set timeout if present
-        if let Some(timeout) = first_run_config.get("timeout") {
-            config.timeout = timeout.parse().unwrap_or(5);
-        }
-
-        // Extract wordlist path if present
-        if let Some(wordlist_path) = first_run_config.get("wordlist_path") {
-            config.wordlist_path = Some(wordlist_path.clone());
-
-            // Load the wordlist
-            match load_wordlist(wordlist_path) {
-                Ok(wordlist) => {
-                    config.wordlist = Some(wordlist);
-                }
-                Err(e) => {
-                    eprintln!(
-                        "Warning: Could not load wordlist at '{}': {}",
-                        wordlist_path, e
-                    );
-                    // Don't exit - just continue without the wordlist
-                }
-            }
-        }
+        let config = config_from_setup_answers(&first_run_config);
 
         // Save the config to file
         save_config_to_file(&config, &path);
@@ -383,10 +908,297 @@ pub fn get_config_file_into_struct() -> Config {
     }
 }
 
+/// Builds a config from the answers given to the setup wizard
+/// Anything that isn't a known setting is treated as a colour, and a wordlist
+/// which can't be loaded is warned about rather than stopping setup.
+pub fn config_from_setup_answers(answers: &HashMap<String, String>) -> Config {
+    let settings = [
+        "timeout",
+        "top_results",
+        "wordlist_path",
+        "enhanced_detection",
+        "model_path",
+        "database_path",
+    ];
+    let mut config = Config {
+        colourscheme: answers
+            .iter()
+            .filter(|(k, _)| !settings.contains(&k.as_str()))
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect(),
+        ..Default::default()
+    };
+    // No colours were picked, so keep the default ones
+    if config.colourscheme.is_empty() {
+        config.colourscheme = Config::default().colourscheme;
+    }
+
+    // Set timeout if present
+    if let Some(timeout) = answers.get("timeout") {
+        config.timeout = timeout.parse().unwrap_or(5);
+    }
+    if let Some(top_results) = answers.get("top_results") {
+        config.top_results = top_results == "true";
+    }
+    if let Some(enhanced_detection) = answers.get("enhanced_detection") {
+        config.enhanced_detection = enhanced_detection == "true";
+    }
+    config.model_path = answers.get("model_path").cloned();
+    config.database_path = answers.get("database_path").cloned();
+
+    // Extract wordlist path if present
+    if let Some(wordlist_path) = answers.get("wordlist_path") {
+        config.wordlist_path = Some(wordlist_path.clone());
+
+        // Load the wordlist
+        match load_wordlist(wordlist_path) {
+            Ok(wordlist) => {
+                config.wordlist = Some(wordlist);
+            }
+            Err(e) => {
+                eprintln!(
+                    "Warning: Could not load wordlist at '{}': {}",
+                    wordlist_path, e
+                );
+                // Don't exit - just continue without the wordlist
+            }
+        }
+    }
+
+    config
+}
+
 /// Save a Config struct to a file
-fn save_config_to_file(config: &Config, path: &std::path::Path) {
+///
+/// # Panics
+/// Panics if the config can't be serialized or the file can't be written.
+pub fn save_config_to_file(config: &Config, path: &std::path::Path) {
     let toml_string = toml::to_string_pretty(config).expect("Could not serialize config");
     let mut file = File::create(path).expect("Could not create config file");
     file.write_all(toml_string.as_bytes())
         .expect("Could not write to config file");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setting(kind: &str, name: &str, enabled: bool) -> SettingRow {
+        SettingRow {
+            kind: kind.to_string(),
+            name: name.to_string(),
+            enabled,
+            timestamp: String::new(),
+        }
+    }
+
+    #[test]
+    fn everything_is_enabled_by_default() {
+        let config = Config::default();
+        assert!(config.is_decoder_enabled("Brainfuck"));
+        assert!(config.is_checker_enabled("English Checker"));
+    }
+
+    #[test]
+    fn stored_toggles_are_merged() {
+        let mut config = Config::default();
+        config.merge_stored_toggles(&[
+            setting("decoder", "Brainfuck", false),
+            setting("checker", "Password Checker", false),
+        ]);
+        assert!(!config.is_decoder_enabled("Brainfuck"));
+        assert!(!config.is_checker_enabled("Password Checker"));
+        assert!(config.is_decoder_enabled("Base64"));
+    }
+
+    #[test]
+    fn setup_answers_are_split_into_settings_and_colours() {
+        let answers: HashMap<String, String> = [
+            ("informational", "1,2,3"),
+            ("timeout", "9"),
+            ("top_results", "true"),
+            ("database_path", "/tmp/ciphey.sqlite"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        let config = config_from_setup_answers(&answers);
+        assert_eq!(config.timeout, 9);
+        assert!(config.top_results);
+        assert_eq!(config.database_path.as_deref(), Some("/tmp/ciphey.sqlite"));
+        assert_eq!(config.colourscheme.len(), 1);
+        assert_eq!(config.colourscheme["informational"], "1,2,3");
+    }
+
+    #[test]
+    fn no_setup_answers_gives_the_defaults() {
+        let config = config_from_setup_answers(&HashMap::new());
+        assert_eq!(config.timeout, Config::default().timeout);
+        assert_eq!(config.colourscheme, Config::default().colourscheme);
+        assert_eq!(config.database_path, None);
+    }
+
+    #[test]
+    fn chain_rules_need_every_condition_to_match() {
+        let rule = ChainRule {
+            decoder: "Base65536".to_string(),
+            after: Some("Binary".to_string()),
+            ..Default::default()
+        };
+        assert!(rule.forbids("base65536", Some("Binary"), 3));
+        assert!(!rule.forbids("Base65536", Some("Base64"), 3));
+        assert!(!rule.forbids("Base65536", None, 0));
+        assert!(!rule.forbids("Base64", Some("Binary"), 3));
+    }
+
+    #[test]
+    fn chain_rules_can_limit_depth() {
+        let mut config = Config::default();
+        config.chain_rules.push(ChainRule {
+            decoder: "Brainfuck".to_string(),
+            max_depth: Some(1),
+            ..Default::default()
+        });
+        assert!(!config.is_chain_allowed("Brainfuck", None, 0));
+        assert!(!config.is_chain_allowed("Brainfuck", Some("Base64"), 1));
+        assert!(config.is_chain_allowed("Brainfuck", Some("Base64"), 2));
+        assert!(config.is_chain_allowed("Base64", None, 0));
+    }
+
+    #[test]
+    fn depth_limits_cap_chains_and_decoder_uses() {
+        let mut config = Config::default();
+        assert!(config.is_depth_allowed("Caesar Cipher", 50, 50));
+
+        config.max_depth = Some(3);
+        config
+            .decoder_depth_limits
+            .insert("caesar cipher".to_string(), 2);
+        assert!(config.is_depth_allowed("Caesar Cipher", 2, 1));
+        assert!(!config.is_depth_allowed("Caesar Cipher", 2, 2));
+        assert!(!config.is_depth_allowed("Base64", 3, 0));
+        assert!(config.is_depth_allowed("Base64", 2, 2));
+    }
+
+    #[test]
+    fn depth_limits_are_read_from_toml() {
+        let config = parse_toml_with_unknown_keys(
+            r#"
+            max_depth = 4
+            [decoder_depth_limits]
+            Base64 = 3
+            "#,
+        );
+        assert_eq!(config.max_depth, Some(4));
+        assert_eq!(config.decoder_depth_limits.get("Base64"), Some(&3));
+    }
+
+    #[test]
+    fn chain_rules_are_read_from_toml() {
+        let config = parse_toml_with_unknown_keys(
+            r#"
+            [[chain_rules]]
+            decoder = "Brainfuck"
+            min_depth = 3
+            "#,
+        );
+        assert_eq!(
+            config.chain_rules,
+            vec![ChainRule {
+                decoder: "Brainfuck".to_string(),
+                min_depth: Some(3),
+                ..Default::default()
+            }]
+        );
+        assert!(toml::to_string_pretty(&config).is_ok());
+    }
+
+    #[test]
+    fn run_snapshots_are_read_back() {
+        let config = Config {
+            timeout: 17,
+            lemmeknow_min_rarity: 0.5,
+            ..Default::default()
+        };
+        let snapshot = serde_json::to_string(&config).unwrap();
+        let restored = Config::from_run_snapshot(&snapshot).unwrap();
+        assert_eq!(restored.timeout, 17);
+        assert_eq!(restored.lemmeknow_config.min_rarity, 0.5);
+        assert!(Config::from_run_snapshot("not json").is_err());
+    }
+
+    #[test]
+    fn storage_backend_is_read_from_toml() {
+        let config = parse_toml_with_unknown_keys(
+            r#"
+            storage_backend = "redis"
+            storage_url = "redis://localhost:6379"
+            "#,
+        );
+        assert_eq!(config.storage_backend, StorageBackend::Redis);
+        assert_eq!(
+            config.storage_url.as_deref(),
+            Some("redis://localhost:6379")
+        );
+        assert_eq!(Config::default().storage_backend, StorageBackend::Sqlite);
+    }
+
+    #[test]
+    fn scan_extractors_are_read_from_toml() {
+        let config = parse_toml_with_unknown_keys(
+            r#"
+            [[scan_extractors]]
+            name = "flag"
+            pattern = "flag\\{[^}]*\\}"
+            "#,
+        );
+        assert_eq!(config.scan_extractors.len(), 1);
+        assert_eq!(config.scan_extractors[0].name, "flag");
+        assert_eq!(config.scan_extractors[0].pattern, "flag\\{[^}]*\\}");
+        assert_eq!(Config::default().scan_extractors.len(), 2);
+    }
+
+    #[test]
+    fn output_format_is_read_from_toml() {
+        let config = parse_toml_with_unknown_keys(r#"output_format = "json""#);
+        assert_eq!(config.output_format, OutputFormat::Json);
+        assert_eq!(Config::default().output_format, OutputFormat::Text);
+    }
+
+    #[test]
+    fn sensitivity_is_read_from_toml() {
+        let config = parse_toml_with_unknown_keys(r#"sensitivity = "high""#);
+        assert_eq!(config.sensitivity, GibberishSensitivity::High);
+        assert!(matches!(
+            Sensitivity::from(Config::default().sensitivity),
+            Sensitivity::Medium
+        ));
+    }
+
+    #[test]
+    fn search_strategy_is_read_from_toml() {
+        let config = parse_toml_with_unknown_keys(
+            r#"
+            search_strategy = "beam"
+            beam_width = 3
+            "#,
+        );
+        assert_eq!(config.search_strategy, SearchStrategy::Beam);
+        assert_eq!(config.beam_width, 3);
+        assert!(toml::to_string_pretty(&config)
+            .unwrap()
+            .contains(r#"search_strategy = "beam""#));
+
+        let config = parse_toml_with_unknown_keys(r#"search_strategy = "bfs""#);
+        assert_eq!(config.search_strategy, SearchStrategy::BreadthFirst);
+        assert_eq!(Config::default().search_strategy, SearchStrategy::AStar);
+    }
+
+    #[test]
+    fn config_file_toggles_take_precedence() {
+        let mut config = Config::default();
+        config.decoder_toggles.insert("brainfuck".to_string(), true);
+        config.merge_stored_toggles(&[setting("decoder", "Brainfuck", false)]);
+        assert!(config.is_decoder_enabled("Brainfuck"));
+    }
+}
@@ -0,0 +1,209 @@
+//! Named bundles of settings for common kinds of input, chosen with `--profile`
+//! A profile only sets what it mentions, on top of the config file, and flags given on the
+//! command line still take precedence over it. Profiles defined in the config file under
+//! `[profiles.<name>]` replace a built-in of the same name.
+//!
+//! ```toml
+//! [profiles.pico]
+//! description = "picoCTF flags"
+//! timeout = 20
+//! regexes = ["picoCTF\\{[^}]*\\}"]
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use super::{Config, GibberishSensitivity};
+
+/// Settings a profile changes, None for the ones it leaves alone
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Profile {
+    /// What the profile is for, shown when listing the profiles
+    pub description: String,
+    /// How many seconds to search for
+    pub timeout: Option<u32>,
+    /// How readily the checkers take a decode to be words
+    pub sensitivity: Option<GibberishSensitivity>,
+    /// The only decoders to run, by name or tag
+    pub only_decoders: Option<Vec<String>>,
+    /// Decoders never to run, by name or tag
+    pub exclude_decoders: Option<Vec<String>>,
+    /// Patterns the plaintext must match, which turn the other checkers off
+    pub regexes: Option<Vec<String>>,
+}
+
+/// Turns a list of names into the strings a profile holds
+fn strings(names: &[&str]) -> Option<Vec<String>> {
+    Some(names.iter().map(|name| name.to_string()).collect())
+}
+
+/// The profiles ciphey ships with, by name
+pub fn builtin_profiles() -> Vec<(&'static str, Profile)> {
+    vec![
+        (
+            "ctf",
+            Profile {
+                description: String::from("Capture the flag: only accept text like flag{...}"),
+                timeout: Some(15),
+                regexes: strings(&[r"[A-Za-z0-9_]{2,16}\{[^}]+\}"]),
+                ..Default::default()
+            },
+        ),
+        (
+            "forensics",
+            Profile {
+                description: String::from(
+                    "Blobs from disk images and captures: base encodings, hex dumps, \
+                     compression and mail encodings",
+                ),
+                timeout: Some(30),
+                sensitivity: Some(GibberishSensitivity::Low),
+                only_decoders: strings(&["base", "hex", "compression", "email", "usenet"]),
+                ..Default::default()
+            },
+        ),
+        (
+            "web-tokens",
+            Profile {
+                description: String::from(
+                    "Cookies, tokens and URLs: JWTs, URL and Base64 encoding, escapes and \
+                     compression",
+                ),
+                timeout: Some(5),
+                only_decoders: strings(&["web", "base64", "hex", "escape", "compression"]),
+                ..Default::default()
+            },
+        ),
+        (
+            "classical-ciphers",
+            Profile {
+                description: String::from(
+                    "Pen and paper ciphers: substitution, transposition and the classics",
+                ),
+                timeout: Some(20),
+                sensitivity: Some(GibberishSensitivity::High),
+                only_decoders: strings(&[
+                    "classic",
+                    "classical",
+                    "substitution",
+                    "transposition",
+                    "reciprocal",
+                ]),
+                ..Default::default()
+            },
+        ),
+    ]
+}
+
+/// Every profile which can be chosen, the config file's first and then the built-in ones
+/// it doesn't replace
+pub fn available_profiles(config: &Config) -> Vec<(String, Profile)> {
+    let mut profiles: Vec<(String, Profile)> = config
+        .profiles
+        .iter()
+        .map(|(name, profile)| (name.clone(), profile.clone()))
+        .collect();
+    profiles.sort_by(|(a, _), (b, _)| a.cmp(b));
+    for (name, profile) in builtin_profiles() {
+        if !profiles
+            .iter()
+            .any(|(defined, _)| defined.eq_ignore_ascii_case(name))
+        {
+            profiles.push((name.to_string(), profile));
+        }
+    }
+    profiles
+}
+
+impl Config {
+    /// Changes the settings the profile called `name` mentions, ignoring case
+    ///
+    /// # Errors
+    /// Returns an error message if there is no such profile or one of its regexes is invalid.
+    pub fn apply_profile(&mut self, name: &str) -> Result<(), String> {
+        let profiles = available_profiles(self);
+        let Some(profile) = profiles
+            .iter()
+            .find(|(defined, _)| defined.eq_ignore_ascii_case(name))
+            .map(|(_, profile)| profile.clone())
+        else {
+            let names: Vec<&str> = profiles.iter().map(|(name, _)| name.as_str()).collect();
+            return Err(format!(
+                "There is no profile called {}, expected one of {}",
+                name,
+                names.join(", ")
+            ));
+        };
+        if let Some(regexes) = profile.regexes {
+            if let Some(e) = regexes
+                .iter()
+                .find_map(|pattern| regex::Regex::new(pattern).err())
+            {
+                return Err(format!("The {} profile has a bad regex: {}", name, e));
+            }
+            self.regex = None;
+            self.regexes = regexes;
+        }
+        if let Some(timeout) = profile.timeout {
+            self.timeout = timeout;
+        }
+        if let Some(sensitivity) = profile.sensitivity {
+            self.sensitivity = sensitivity;
+        }
+        if let Some(only_decoders) = profile.only_decoders {
+            self.only_decoders = only_decoders;
+        }
+        if let Some(exclude_decoders) = profile.exclude_decoders {
+            self.exclude_decoders = exclude_decoders;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filtration_system::get_registered_decoders;
+
+    #[test]
+    fn builtin_profiles_pick_real_decoders_and_valid_regexes() {
+        let decoders = get_registered_decoders();
+        for (name, profile) in builtin_profiles() {
+            let mut config = Config::default();
+            config.apply_profile(name).unwrap();
+            for wanted in profile.only_decoders.iter().flatten() {
+                assert!(
+                    !decoders.matching(wanted).is_empty(),
+                    "{} picks no decoder with {}",
+                    name,
+                    wanted
+                );
+            }
+        }
+        let mut config = Config::default();
+        config.apply_profile("CTF").unwrap();
+        assert_eq!(config.timeout, 15);
+        assert!(regex::Regex::new(config.regex_patterns()[0])
+            .unwrap()
+            .is_match("picoCTF{s0me_fl4g}"));
+    }
+
+    #[test]
+    fn config_file_profiles_replace_builtins_and_leave_the_rest() {
+        let mut config: Config = toml::from_str(
+            r#"
+            timeout = 7
+            [profiles.ctf]
+            sensitivity = "low"
+            "#,
+        )
+        .unwrap();
+        config.apply_profile("ctf").unwrap();
+        assert_eq!(config.sensitivity, GibberishSensitivity::Low);
+        assert_eq!(config.timeout, 7);
+        assert!(config.regexes.is_empty());
+
+        let error = config.apply_profile("nope").unwrap_err();
+        assert!(error.contains("ctf, forensics, web-tokens, classical-ciphers"));
+    }
+}
@@ -0,0 +1,269 @@
+//! Generates layered test cases: random English plaintext run through a random chain of encoders
+//! Used by the integration tests, as fuzzing seeds and by the whole program benchmark,
+//! so multi-layer chains get covered without hand-writing every string.
+//! Generators are seeded, so the same seed always gives the same cases.
+
+use base64::{engine::general_purpose, Engine as _};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Fewest encoders a generated case goes through
+pub const MIN_LAYERS: usize = 1;
+
+/// Most encoders a generated case goes through
+pub const MAX_LAYERS: usize = 5;
+
+/// Common words which the English checker recognises, plaintexts are built from these
+const WORDS: [&str; 40] = [
+    "the", "quick", "brown", "fox", "jumps", "over", "lazy", "dog", "hello", "world", "this", "is",
+    "a", "secret", "message", "about", "people", "who", "like", "to", "write", "code", "every",
+    "day", "and", "night", "when", "they", "should", "sleep", "until", "morning", "comes",
+    "around", "again", "with", "coffee", "for", "everyone", "here",
+];
+
+/// Fewest words in a generated plaintext
+const MIN_WORDS: usize = 4;
+
+/// Most words in a generated plaintext
+const MAX_WORDS: usize = 10;
+
+/// An encoding with a matching decoder, which the corpus can layer
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Encoder {
+    /// Standard padded base64
+    Base64,
+    /// Standard padded base32
+    Base32,
+    /// Base58 with the Bitcoin alphabet
+    Base58Bitcoin,
+    /// Lowercase hexadecimal
+    Hexadecimal,
+    /// Space-separated 8 bit binary
+    Binary,
+    /// The text backwards
+    Reverse,
+    /// Caesar with a shift of 13
+    Rot13,
+}
+
+impl Encoder {
+    /// Every encoder the corpus can pick from
+    pub const ALL: [Encoder; 7] = [
+        Encoder::Base64,
+        Encoder::Base32,
+        Encoder::Base58Bitcoin,
+        Encoder::Hexadecimal,
+        Encoder::Binary,
+        Encoder::Reverse,
+        Encoder::Rot13,
+    ];
+
+    /// The name of the decoder which undoes this encoder
+    pub fn decoder_name(&self) -> &'static str {
+        match self {
+            Encoder::Base64 => "Base64",
+            Encoder::Base32 => "Base32",
+            Encoder::Base58Bitcoin => "Base58 Bitcoin",
+            Encoder::Hexadecimal => "Hexadecimal",
+            Encoder::Binary => "Binary",
+            Encoder::Reverse => "Reverse",
+            Encoder::Rot13 => "caesar",
+        }
+    }
+
+    /// Encodes the text
+    pub fn encode(&self, text: &str) -> String {
+        match self {
+            Encoder::Base64 => general_purpose::STANDARD.encode(text),
+            Encoder::Base32 => data_encoding::BASE32.encode(text.as_bytes()),
+            Encoder::Base58Bitcoin => bs58::encode(text)
+                .with_alphabet(bs58::Alphabet::BITCOIN)
+                .into_string(),
+            Encoder::Hexadecimal => text.bytes().map(|b| format!("{:02x}", b)).collect(),
+            Encoder::Binary => text
+                .bytes()
+                .map(|b| format!("{:08b}", b))
+                .collect::<Vec<_>>()
+                .join(" "),
+            Encoder::Reverse => text.chars().rev().collect(),
+            Encoder::Rot13 => text
+                .chars()
+                .map(|c| {
+                    if c.is_ascii_alphabetic() {
+                        let first = if c.is_ascii_lowercase() { b'a' } else { b'A' };
+                        (first + (c as u8 - first + 13) % 26) as char
+                    } else {
+                        c
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    /// Self-inverse encoders undo themselves when applied twice in a row
+    fn is_self_inverse(&self) -> bool {
+        matches!(self, Encoder::Reverse | Encoder::Rot13)
+    }
+}
+
+/// A generated test case
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CorpusCase {
+    /// The plaintext ciphey should find
+    pub plaintext: String,
+    /// The plaintext after every encoder has been applied
+    pub ciphertext: String,
+    /// The encoders in the order they were applied, so the last one is undone first
+    pub encoders: Vec<Encoder>,
+}
+
+/// Generates corpus cases from a seed
+/// ```
+/// use ciphey::corpus::CorpusGenerator;
+///
+/// let mut generator = CorpusGenerator::new(42).with_layers(2, 3);
+/// let case = generator.generate();
+/// assert!((2..=3).contains(&case.encoders.len()));
+/// assert_eq!(CorpusGenerator::new(42).with_layers(2, 3).generate(), case);
+/// ```
+pub struct CorpusGenerator {
+    /// Where the randomness comes from
+    rng: StdRng,
+    /// Fewest encoders per case
+    min_layers: usize,
+    /// Most encoders per case
+    max_layers: usize,
+    /// The encoders to pick from
+    encoders: Vec<Encoder>,
+}
+
+impl CorpusGenerator {
+    /// Creates a generator using between `MIN_LAYERS` and `MAX_LAYERS` of every encoder
+    pub fn new(seed: u64) -> Self {
+        CorpusGenerator {
+            rng: StdRng::seed_from_u64(seed),
+            min_layers: MIN_LAYERS,
+            max_layers: MAX_LAYERS,
+            encoders: Encoder::ALL.to_vec(),
+        }
+    }
+
+    /// Sets how many encoders each case goes through, clamped to `MIN_LAYERS..=MAX_LAYERS`
+    pub fn with_layers(mut self, min: usize, max: usize) -> Self {
+        self.min_layers = min.clamp(MIN_LAYERS, MAX_LAYERS);
+        self.max_layers = max.clamp(self.min_layers, MAX_LAYERS);
+        self
+    }
+
+    /// Only picks from these encoders, an empty list keeps the current ones
+    pub fn with_encoders(mut self, encoders: &[Encoder]) -> Self {
+        if !encoders.is_empty() {
+            self.encoders = encoders.to_vec();
+        }
+        self
+    }
+
+    /// Generates the next case
+    pub fn generate(&mut self) -> CorpusCase {
+        let plaintext = self.plaintext();
+        let layers = self.rng.random_range(self.min_layers..=self.max_layers);
+
+        let mut encoders: Vec<Encoder> = Vec::with_capacity(layers);
+        let mut ciphertext = plaintext.clone();
+        while encoders.len() < layers {
+            let encoder = self.encoders[self.rng.random_range(0..self.encoders.len())];
+            // Reversing twice gives back the input, which isn't a layer at all
+            if encoder.is_self_inverse() && encoders.last() == Some(&encoder) {
+                if self.encoders.len() == 1 {
+                    break;
+                }
+                continue;
+            }
+            ciphertext = encoder.encode(&ciphertext);
+            encoders.push(encoder);
+        }
+
+        CorpusCase {
+            plaintext,
+            ciphertext,
+            encoders,
+        }
+    }
+
+    /// Generates `count` cases
+    pub fn cases(&mut self, count: usize) -> Vec<CorpusCase> {
+        (0..count).map(|_| self.generate()).collect()
+    }
+
+    /// Picks a random sentence of common words
+    fn plaintext(&mut self) -> String {
+        let count = self.rng.random_range(MIN_WORDS..=MAX_WORDS);
+        (0..count)
+            .map(|_| WORDS[self.rng.random_range(0..WORDS.len())])
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checkers::{
+        athena::Athena,
+        checker_type::{Check, Checker},
+        CheckerTypes,
+    };
+    use crate::filtration_system::get_registered_decoders;
+
+    #[test]
+    fn same_seed_gives_same_cases() {
+        let first = CorpusGenerator::new(7).cases(10);
+        assert_eq!(first, CorpusGenerator::new(7).cases(10));
+        assert_ne!(first, CorpusGenerator::new(8).cases(10));
+    }
+
+    #[test]
+    fn layers_stay_in_range() {
+        let mut generator = CorpusGenerator::new(1).with_layers(2, 4);
+        for case in generator.cases(50) {
+            assert!((2..=4).contains(&case.encoders.len()), "{:?}", case);
+        }
+    }
+
+    #[test]
+    fn self_inverse_encoders_are_not_repeated() {
+        let mut generator = CorpusGenerator::new(3)
+            .with_layers(MAX_LAYERS, MAX_LAYERS)
+            .with_encoders(&[Encoder::Reverse, Encoder::Base64]);
+        for case in generator.cases(20) {
+            assert!(case
+                .encoders
+                .windows(2)
+                .all(|pair| pair[0] != Encoder::Reverse || pair[1] != Encoder::Reverse));
+        }
+    }
+
+    #[test]
+    fn every_encoder_is_undone_by_its_decoder() {
+        let checker = CheckerTypes::CheckAthena(Checker::<Athena>::new());
+        let decoders = get_registered_decoders();
+        let plaintext = "hello world this is a secret message";
+        for encoder in Encoder::ALL {
+            let decoder = decoders
+                .components
+                .iter()
+                .find(|decoder| decoder.get_name() == encoder.decoder_name())
+                .expect("Every encoder should have a registered decoder");
+            let decoded = decoder
+                .crack(&encoder.encode(plaintext), &checker)
+                .unencrypted_text
+                .unwrap_or_default();
+            assert!(
+                decoded.iter().any(|text| text == plaintext),
+                "{} did not undo {:?}",
+                encoder.decoder_name(),
+                encoder
+            );
+        }
+    }
+}
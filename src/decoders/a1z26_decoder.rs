@@ -1,12 +1,13 @@
 use crate::checkers::CheckerTypes;
+use crate::config::get_config;
 use crate::decoders::interface::check_string_success;
 
+use super::alphabet::position_to_letter;
 use super::crack_results::CrackResult;
 use super::interface::Crack;
 use super::interface::Decoder;
 
 use log::{debug, info, trace};
-use regex::Regex;
 
 /// A1Z26 Decoder
 pub struct A1Z26Decoder;
@@ -32,7 +33,8 @@ impl Crack for Decoder<A1Z26Decoder> {
     /// be a string of capital letters with no spaces or punctuation.
     ///
     /// This implementation accepts a list of decimal numbers separated by any combination of
-    /// delimiters including `,` `;` `:` '-' and whitespace. For successful decoding, the input
+    /// whitespace and the delimiters in the `a1z26_delimiters` config option, which are
+    /// `,` `;` `:` and `-` by default. For successful decoding, the input
     /// must contain at least one numeric digit, and every number must be in the range 1 to 26. The
     /// input is allowed to start and end with delimiters.
     ///
@@ -43,7 +45,7 @@ impl Crack for Decoder<A1Z26Decoder> {
     fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
         trace!("Trying A1Z26 with text {:?}", text);
 
-        let decoded_text = decode_a1z26(text);
+        let decoded_text = decode_a1z26(text, &get_config().a1z26_delimiters);
         trace!("Decoded text for A1Z26: {:?}", decoded_text);
 
         let mut results = CrackResult::new(self, text.to_string());
@@ -89,42 +91,46 @@ impl Crack for Decoder<A1Z26Decoder> {
     fn get_link(&self) -> &str {
         self.link
     }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some(("8 5 12 12 15", "HELLO"))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input must contain at least one digit",
+            "Input may only contain digits, whitespace and the configured delimiters (`,` `;` `:` `-` by default)",
+            "Every number must be between 1 and 26",
+        ]
+    }
 }
 
 /// This function does the actual decoding
+/// Numbers are split on whitespace and any of the characters in `delimiters`
 /// It returns an Option<string> if it was successful
 /// Else the Option returns nothing and the error is logged in Trace
-fn decode_a1z26(ctext: &str) -> Option<String> {
-    let re_has_a_digit = Regex::new(r"[0-9]").expect("Regex should be valid");
-    if !re_has_a_digit.is_match(ctext) {
+fn decode_a1z26(ctext: &str, delimiters: &str) -> Option<String> {
+    if !ctext.chars().any(|c| c.is_ascii_digit()) {
         return None;
     }
 
-    let re_all_valid_chars = Regex::new(r"\A([0-9,;:\-\s])*\z").expect("Regex should be valid");
-    if !re_all_valid_chars.is_match(ctext) {
-        return None;
-    }
-
-    let re_delimiters = Regex::new(r"[,;:\-\s]+").expect("Regex should be valid");
-    let letters: Option<Vec<char>> = re_delimiters
-        .split(ctext)
+    ctext
+        .split(|c: char| c.is_whitespace() || (!c.is_ascii_digit() && delimiters.contains(c)))
         .filter(|x| !x.is_empty())
         .map(decode_one_char_a1z26)
-        .collect();
-    let decoded_text: Option<String> = letters.map(|x| x.into_iter().collect());
-
-    decoded_text
+        .collect()
 }
 
 /// Decode a single numeric string (decimal digits only) to a single character
 fn decode_one_char_a1z26(num_text: &str) -> Option<char> {
-    let num: u8 = num_text.parse().ok()?;
-    if (1..=26).contains(&num) {
-        let letter = (b'A' + num - 1) as char;
-        Some(letter)
-    } else {
-        None
+    if !num_text.chars().all(|c| c.is_ascii_digit()) {
+        return None;
     }
+    position_to_letter(num_text.parse().ok()?)
 }
 
 #[cfg(test)]
@@ -266,6 +272,18 @@ mod tests {
         assert_eq!(result.unencrypted_text.unwrap()[0], "I");
     }
 
+    #[test]
+    fn test_custom_delimiters() {
+        assert_eq!(decode_a1z26("8.5.12.12.15", "."), Some("HELLO".to_string()));
+        assert_eq!(decode_a1z26("8.5.12.12.15", ",;:-"), None);
+        assert_eq!(decode_a1z26("8 5 12 12 15", ""), Some("HELLO".to_string()));
+    }
+
+    #[test]
+    fn test_digits_are_never_delimiters() {
+        assert_eq!(decode_a1z26("8 5 12", "1"), Some("HEL".to_string()));
+    }
+
     #[test]
     fn test_delimited_ctext() {
         let decoder = Decoder::<A1Z26Decoder>::new();
@@ -0,0 +1,213 @@
+//! Decode an affine cipher string
+//! The affine cipher encrypts the letter numbered x as `(a * x + b) mod 26`,
+//! where `a` has to share no factors with 26 so the cipher can be undone.
+//! We brute-force every valid (a, b) pair, rank them by how English-like they are
+//! and only run the checker on the best few.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::check_string_success;
+
+use super::alphabet::{letter_index, map_letters, mod_inverse, ALPHABET_LENGTH};
+use super::crack_results::CrackResult;
+use super::frequency_analysis::english_score;
+use super::interface::Crack;
+use super::interface::Decoder;
+
+use log::{info, trace};
+
+/// How many of the most English-like keys are given to the checker
+const CANDIDATES_TO_CHECK: usize = 5;
+
+/// The fewest letters we will try to decode, frequency analysis is meaningless below this
+const MIN_LETTERS: usize = 6;
+
+/// The affine decoder, call:
+/// `let affine_decoder = Decoder::<AffineDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = affine_decoder.crack(input)` to decode an affine string
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::affine_decoder::AffineDecoder;
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_affine = Decoder::<AffineDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_affine.crack("Zrc kewsg npaov hat beqfu ajcp zrc lidy xam", &checker);
+/// assert_eq!(result.unencrypted_text.unwrap()[0], "The quick brown fox jumps over the lazy dog");
+/// assert_eq!(result.key.unwrap(), "a=5, b=8");
+/// ```
+pub struct AffineDecoder;
+
+impl Crack for Decoder<AffineDecoder> {
+    fn new() -> Decoder<AffineDecoder> {
+        Decoder {
+            name: "Affine",
+            description: "The affine cipher maps each letter to a number, multiplies it by one key, adds another and maps it back to a letter. There are only 312 possible keys, so every one is tried and the most English-like are checked.",
+            link: "https://en.wikipedia.org/wiki/Affine_cipher",
+            tags: vec!["affine", "substitution", "decryption", "classic"],
+            popularity: 0.5,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying affine with text {:?}", text);
+        let mut results = CrackResult::new(self, text.to_string());
+
+        if text.chars().filter_map(letter_index).count() < MIN_LETTERS {
+            info!("Failed to decode affine because the text has too few letters");
+            return results;
+        }
+
+        let mut candidates: Vec<(u8, u8, String, f64)> = keys()
+            .map(|(a, b)| {
+                let decoded_text = affine(text, a, b);
+                let score = english_score(&decoded_text);
+                (a, b, decoded_text, score)
+            })
+            .filter(|(_, _, decoded_text, _)| check_string_success(decoded_text, text))
+            .collect();
+        candidates.sort_by(|x, y| x.3.total_cmp(&y.3));
+        candidates.truncate(CANDIDATES_TO_CHECK);
+
+        if candidates.is_empty() {
+            return results;
+        }
+
+        for (index, (a, b, decoded_text, _)) in candidates.iter().enumerate() {
+            let checker_result = checker.check(decoded_text);
+            if checker_result.is_identified {
+                trace!("Found a match with affine key a={}, b={}", a, b);
+                results.key = Some(format!("a={}, b={}", a, b));
+                results.update_checker(&checker_result);
+                candidates.swap(0, index);
+                break;
+            }
+        }
+
+        results.unencrypted_text = Some(candidates.into_iter().map(|c| c.2).collect());
+        results
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some((
+            "Zrc kewsg npaov hat beqfu ajcp zrc lidy xam",
+            "The quick brown fox jumps over the lazy dog",
+        ))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input must contain at least 6 letters",
+            "Skips a = 1, which is a Caesar shift, and the Atbash key (a = 25, b = 25)",
+            "Only the 5 most English-like keys are checked",
+        ]
+    }
+}
+
+/// Every key worth trying: each `a` with an inverse and every `b`
+/// Caesar shifts and Atbash already have their own decoders, so they are left out.
+fn keys() -> impl Iterator<Item = (u8, u8)> {
+    (2..ALPHABET_LENGTH)
+        .filter(|&a| mod_inverse(a).is_some())
+        .flat_map(|a| (0..ALPHABET_LENGTH).map(move |b| (a, b)))
+        .filter(|&key| key != (25, 25))
+}
+
+/// Undoes the affine cipher with a key of `a` and `b`
+/// # Panics
+/// Panics if `a` has no inverse modulo 26, `keys` never gives one of those
+fn affine(text: &str, a: u8, b: u8) -> String {
+    let inverse = mod_inverse(a).expect("a should share no factors with 26") as u16;
+    map_letters(text, |y| {
+        let shifted = y as u16 + ALPHABET_LENGTH as u16 - b as u16;
+        ((inverse * shifted) % ALPHABET_LENGTH as u16) as u8
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checkers::{
+        athena::Athena,
+        checker_type::{Check, Checker},
+        CheckerTypes,
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn affine_undoes_encryption() {
+        assert_eq!(affine("rclla oaplx", 5, 8), "hello world");
+        assert_eq!(affine("RCLLA", 5, 8), "HELLO");
+    }
+
+    #[test]
+    fn only_valid_keys_are_tried() {
+        let keys: Vec<(u8, u8)> = keys().collect();
+        // 12 values of a have inverses, minus a = 1, times 26 values of b, minus Atbash
+        assert_eq!(keys.len(), 11 * 26 - 1);
+        assert!(keys
+            .iter()
+            .all(|&(a, _)| a != 1 && mod_inverse(a).is_some()));
+    }
+
+    #[test]
+    fn successful_decoding() {
+        let decoder = Decoder::<AffineDecoder>::new();
+        let result = decoder.crack(
+            "Zrc kewsg npaov hat beqfu ajcp zrc lidy xam",
+            &get_athena_checker(),
+        );
+        assert!(result.success);
+        assert_eq!(
+            result.unencrypted_text.unwrap()[0],
+            "The quick brown fox jumps over the lazy dog"
+        );
+        assert_eq!(result.key.as_deref(), Some("a=5, b=8"));
+    }
+
+    #[test]
+    fn short_text_is_not_decoded() {
+        let decoder = Decoder::<AffineDecoder>::new();
+        let result = decoder.crack("rclla", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+
+    #[test]
+    fn affine_handle_panic_if_emoji() {
+        let decoder = Decoder::<AffineDecoder>::new();
+        let result = decoder.crack("😂", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+}
@@ -0,0 +1,78 @@
+//! Letter arithmetic shared by the classical cipher decoders
+//! Letters are numbered from 0 (A) to 25 (Z). Mapping text keeps the case of every letter
+//! and leaves anything that isn't an ASCII letter untouched, so decoders only have to say
+//! what happens to a letter's number.
+
+/// How many letters are in the alphabet
+pub const ALPHABET_LENGTH: u8 = 26;
+
+/// The number of an ASCII letter, from 0 (A) to 25 (Z), ignoring case
+pub fn letter_index(c: char) -> Option<u8> {
+    c.is_ascii_alphabetic()
+        .then(|| c.to_ascii_uppercase() as u8 - b'A')
+}
+
+/// The letter with this number, wrapping numbers past Z back round to A
+pub fn index_to_letter(index: u8, uppercase: bool) -> char {
+    let first = if uppercase { b'A' } else { b'a' };
+    (first + index % ALPHABET_LENGTH) as char
+}
+
+/// Replaces every letter with the letter numbered `map(index)`, keeping its case
+pub fn map_letters(text: &str, map: impl Fn(u8) -> u8) -> String {
+    text.chars()
+        .map(|c| match letter_index(c) {
+            Some(index) => index_to_letter(map(index), c.is_ascii_uppercase()),
+            None => c,
+        })
+        .collect()
+}
+
+/// The multiplicative inverse of `a` modulo 26, None if `a` shares a factor with 26
+pub fn mod_inverse(a: u8) -> Option<u8> {
+    (1..ALPHABET_LENGTH).find(|&x| (a as u16 * x as u16) % ALPHABET_LENGTH as u16 == 1)
+}
+
+/// The number of the letter at this position in the alphabet, counting A as 1
+/// None for numbers outside 1 to 26
+pub fn position_to_letter(position: u8) -> Option<char> {
+    (1..=ALPHABET_LENGTH)
+        .contains(&position)
+        .then(|| index_to_letter(position - 1, true))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn letters_are_numbered_from_zero() {
+        assert_eq!(letter_index('a'), Some(0));
+        assert_eq!(letter_index('Z'), Some(25));
+        assert_eq!(letter_index('!'), None);
+        assert_eq!(letter_index('é'), None);
+    }
+
+    #[test]
+    fn mapping_keeps_case_and_other_characters() {
+        assert_eq!(map_letters("Hello, World!", |i| i + 1), "Ifmmp, Xpsme!");
+        assert_eq!(map_letters("Zz", |i| i + 1), "Aa");
+    }
+
+    #[test]
+    fn only_coprime_numbers_have_inverses() {
+        assert_eq!(mod_inverse(1), Some(1));
+        assert_eq!(mod_inverse(3), Some(9));
+        assert_eq!(mod_inverse(25), Some(25));
+        assert_eq!(mod_inverse(2), None);
+        assert_eq!(mod_inverse(13), None);
+    }
+
+    #[test]
+    fn positions_count_from_one() {
+        assert_eq!(position_to_letter(1), Some('A'));
+        assert_eq!(position_to_letter(26), Some('Z'));
+        assert_eq!(position_to_letter(0), None);
+        assert_eq!(position_to_letter(27), None);
+    }
+}
@@ -0,0 +1,324 @@
+//! Decodes an Ascii85 (Base85) string
+//! Tries the standard, Adobe (`<~ ~>` wrapped), btoa and ZeroMQ Z85 variants
+//! and returns the variant the checker is most confident in.
+//! Call ascii85_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
+use crate::checkers::checker_result::CheckResult;
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::check_string_success;
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::Decoder;
+
+use log::{debug, trace};
+
+/// The Ascii85 decoder, call:
+/// `let ascii85_decoder = Decoder::<Ascii85Decoder>::new()` to create a new instance
+/// And then call:
+/// `result = ascii85_decoder.crack(input)` to decode an Ascii85 string
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::ascii85_decoder::{Ascii85Decoder};
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_ascii85 = Decoder::<Ascii85Decoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_ascii85.crack("<~BOu!rD]j7BEbo7~>", &checker).unencrypted_text;
+/// assert!(result.is_some());
+/// assert_eq!(result.unwrap()[0], "hello world");
+/// ```
+pub struct Ascii85Decoder;
+
+/// The Ascii85 variants we try, in the order we try them
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Variant {
+    /// Adobe Ascii85, wrapped in `<~` and `~>`
+    Adobe,
+    /// btoa, which adds `y` as a shorthand for four spaces
+    Btoa,
+    /// Standard Ascii85 with `z` as a shorthand for four zero bytes
+    Standard,
+    /// ZeroMQ's Z85, which uses a different alphabet that is safe in source code
+    Z85,
+}
+
+impl Crack for Decoder<Ascii85Decoder> {
+    fn new() -> Decoder<Ascii85Decoder> {
+        Decoder {
+            name: "Ascii85",
+            description: "Ascii85, also called Base85, is a form of binary-to-text encoding that uses five ASCII characters to represent four bytes of binary data. This decoder handles the standard, Adobe, btoa and Z85 variants.",
+            link: "https://en.wikipedia.org/wiki/Ascii85",
+            tags: vec!["ascii85", "base85", "decoder", "base"],
+            popularity: 0.7,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// This function does the actual decoding
+    /// Every variant that decodes is run through the checker, and the identified
+    /// plaintext with the highest confidence wins.
+    /// If nothing is identified, every decoded candidate is returned.
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying Ascii85 with text {:?}", text);
+        let mut results = CrackResult::new(self, text.to_string());
+
+        let mut candidates: Vec<String> = Vec::new();
+        let mut best: Option<(String, CheckResult)> = None;
+
+        for variant in variants_to_try(text) {
+            let decoded_text = match decode_variant(text, variant) {
+                Some(decoded_text) => decoded_text,
+                None => {
+                    trace!("Failed to decode Ascii85 variant {:?}", variant);
+                    continue;
+                }
+            };
+            if !check_string_success(&decoded_text, text) || candidates.contains(&decoded_text) {
+                continue;
+            }
+
+            let checker_result = checker.check(&decoded_text);
+            if checker_result.is_identified {
+                trace!(
+                    "Ascii85 variant {:?} identified with confidence {}",
+                    variant,
+                    checker_result.confidence
+                );
+                let is_better = match &best {
+                    Some((_, best_result)) => checker_result.confidence > best_result.confidence,
+                    None => true,
+                };
+                if is_better {
+                    best = Some((decoded_text.clone(), checker_result));
+                }
+            }
+            candidates.push(decoded_text);
+        }
+
+        if let Some((decoded_text, checker_result)) = best {
+            results.unencrypted_text = Some(vec![decoded_text]);
+            results.update_checker(&checker_result);
+            return results;
+        }
+
+        if candidates.is_empty() {
+            debug!("Failed to decode Ascii85 because no variant decoded the text");
+            return results;
+        }
+
+        results.unencrypted_text = Some(candidates);
+        results
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some(("<~BOu!rD]j7BEbo7~>", "hello world"))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input wrapped in `<~` and `~>` is decoded as Adobe Ascii85",
+            "Input containing `y` or an `xbtoa Begin` header is also decoded as btoa",
+            "Standard and Z85 are always tried",
+            "Decoded bytes must be valid UTF-8",
+        ]
+    }
+}
+
+/// Works out which variants are worth trying for this text
+/// btoa only differs from standard Ascii85 by its `y` shorthand and header,
+/// so we only try it when one of those is present
+fn variants_to_try(text: &str) -> Vec<Variant> {
+    let trimmed = text.trim();
+    let mut variants = Vec::new();
+    if trimmed.starts_with("<~") && trimmed.ends_with("~>") {
+        variants.push(Variant::Adobe);
+    }
+    if trimmed.contains('y') || trimmed.starts_with("xbtoa Begin") {
+        variants.push(Variant::Btoa);
+    }
+    variants.push(Variant::Standard);
+    variants.push(Variant::Z85);
+    variants
+}
+
+/// Decodes the text with a single variant, returning None if it is not valid
+fn decode_variant(text: &str, variant: Variant) -> Option<String> {
+    let bytes = match variant {
+        Variant::Adobe => {
+            let inner = text.trim().strip_prefix("<~")?.strip_suffix("~>")?;
+            decode_ascii85(inner, false)?
+        }
+        Variant::Btoa => decode_ascii85(strip_btoa_header(text), true)?,
+        Variant::Standard => decode_ascii85(text, false)?,
+        Variant::Z85 => z85::decode(text.trim().as_bytes()).ok()?,
+    };
+    String::from_utf8(bytes).ok()
+}
+
+/// btoa output is wrapped in `xbtoa Begin` and `xbtoa End ...` lines
+/// Strips them if present so only the encoded data is left
+fn strip_btoa_header(text: &str) -> &str {
+    let text = text.trim();
+    let text = match text.strip_prefix("xbtoa Begin") {
+        Some(rest) => rest,
+        None => return text,
+    };
+    match text.find("xbtoa End") {
+        Some(end) => &text[..end],
+        None => text,
+    }
+}
+
+/// Decodes standard Ascii85, ignoring whitespace
+/// `z` is four zero bytes, and when `allow_y` is set `y` is four spaces (btoa)
+fn decode_ascii85(text: &str, allow_y: bool) -> Option<Vec<u8>> {
+    let mut out = Vec::new();
+    let mut group: Vec<u8> = Vec::with_capacity(5);
+
+    for c in text.chars().filter(|c| !c.is_whitespace()) {
+        match c {
+            'z' if group.is_empty() => out.extend_from_slice(&[0, 0, 0, 0]),
+            'y' if allow_y && group.is_empty() => out.extend_from_slice(b"    "),
+            '!'..='u' => {
+                group.push(c as u8 - b'!');
+                if group.len() == 5 {
+                    out.extend_from_slice(&decode_group(&group)?);
+                    group.clear();
+                }
+            }
+            _ => return None,
+        }
+    }
+
+    // A final partial group is padded with the highest digit and the padding bytes thrown away
+    // A single leftover character can't encode anything
+    match group.len() {
+        0 => {}
+        1 => return None,
+        len => {
+            group.resize(5, 84);
+            out.extend_from_slice(&decode_group(&group)?[..len - 1]);
+        }
+    }
+
+    if out.is_empty() {
+        return None;
+    }
+    Some(out)
+}
+
+/// Decodes five base-85 digits into four bytes, None if the value overflows 32 bits
+fn decode_group(group: &[u8]) -> Option<[u8; 4]> {
+    let value = group.iter().try_fold(0u32, |acc, &digit| {
+        acc.checked_mul(85)?.checked_add(u32::from(digit))
+    })?;
+    Some(value.to_be_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_ascii85, Ascii85Decoder};
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn ascii85_decodes_standard_successfully() {
+        let decoder = Decoder::<Ascii85Decoder>::new();
+        let result = decoder.crack("BOu!rD]j7BEbo7", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+    }
+
+    #[test]
+    fn ascii85_decodes_adobe_successfully() {
+        let decoder = Decoder::<Ascii85Decoder>::new();
+        let result = decoder.crack("<~BOu!rD]j7BEbo7~>", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+    }
+
+    #[test]
+    fn ascii85_decodes_btoa_successfully() {
+        let decoder = Decoder::<Ascii85Decoder>::new();
+        let result = decoder.crack("yBOu!rD]g/F+EqaECh+Y2+<U", &get_athena_checker());
+        assert_eq!(
+            result.unencrypted_text.unwrap()[0],
+            "    hello    world    "
+        );
+    }
+
+    #[test]
+    fn ascii85_decodes_z85_successfully() {
+        let decoder = Decoder::<Ascii85Decoder>::new();
+        let result = decoder.crack("nm=QNzY&b1A+]nf", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "Hello World!");
+    }
+
+    #[test]
+    fn ascii85_decodes_long_standard_successfully() {
+        let decoder = Decoder::<Ascii85Decoder>::new();
+        let result = decoder.crack(
+            ";fHDaDKm:BAftQ!@:O'qEHP]1FF#J\\C3='\"AKYi8+Eh[I/c",
+            &get_athena_checker(),
+        );
+        assert_eq!(
+            result.unencrypted_text.unwrap()[0],
+            "Sphinx of black quartz, judge my vow."
+        );
+    }
+
+    #[test]
+    fn ascii85_handle_panic_if_empty_string() {
+        let decoder = Decoder::<Ascii85Decoder>::new();
+        let result = decoder.crack("", &get_athena_checker()).unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn ascii85_handle_panic_if_emoji() {
+        let decoder = Decoder::<Ascii85Decoder>::new();
+        let result = decoder.crack("😂", &get_athena_checker()).unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn ascii85_rejects_overflowing_group() {
+        // "uuuuu" is larger than 2^32 - 1
+        assert!(decode_ascii85("uuuuu", false).is_none());
+    }
+}
@@ -1,6 +1,7 @@
 use crate::checkers::CheckerTypes;
 use crate::decoders::interface::check_string_success;
 
+use super::alphabet::{map_letters, ALPHABET_LENGTH};
 use super::crack_results::CrackResult;
 use super::interface::Crack;
 use super::interface::Decoder;
@@ -65,17 +66,23 @@ impl Crack for Decoder<AtbashDecoder> {
     fn get_link(&self) -> &str {
         self.link
     }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some(("svool dliow", "hello world"))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &["Decoded text must differ from the input"]
+    }
 }
 
 /// Maps atbash to the alphabet
 fn atbash_to_alphabet(text: &str) -> String {
-    text.chars()
-        .map(|char| match char {
-            letter @ 'a'..='z' => (b'a' + b'z' - letter as u8) as char,
-            letter @ 'A'..='Z' => (b'A' + b'Z' - letter as u8) as char,
-            other => other,
-        })
-        .collect()
+    map_letters(text, |index| ALPHABET_LENGTH - 1 - index)
 }
 
 #[cfg(test)]
@@ -0,0 +1,227 @@
+//! Decodes a base122 string
+//! Base122 packs data 7 bits at a time into single byte UTF-8 characters. The six 7 bit
+//! values which cause trouble in HTML and JavaScript strings (null, newline, carriage return,
+//! `"`, `&` and `\`) are instead folded into a two byte character together with the 7 bits
+//! after them, so the text is mostly ASCII with a few characters between U+0080 and U+07FF.
+//! Call base122_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::check_string_success;
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::Decoder;
+use super::radix::bytes_to_text;
+
+use log::{debug, info, trace};
+
+/// The 7 bit values that can't be written as themselves, in the order two byte characters number them
+const ILLEGALS: [u8; 6] = [0, 10, 13, 34, 38, 92];
+
+/// The number a two byte character uses when it only holds the last 7 bits of the data
+const SHORTENED: usize = 0b111;
+
+/// The Base122 decoder, call:
+/// `let base122_decoder = Decoder::<Base122Decoder>::new()` to create a new instance
+/// And then call:
+/// `result = base122_decoder.crack(input)` to decode a base122 string
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::base122_decoder::{Base122Decoder};
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_base122 = Decoder::<Base122Decoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_base122.crack("4\u{19}-Fc<@w7\u{5cd}F ", &checker).unencrypted_text;
+/// assert!(result.is_some());
+/// assert_eq!(result.unwrap()[0], "hello world");
+/// ```
+pub struct Base122Decoder;
+
+impl Crack for Decoder<Base122Decoder> {
+    fn new() -> Decoder<Base122Decoder> {
+        Decoder {
+            name: "Base122",
+            description: "Base122 stores 7 bits of data in every single byte UTF-8 character, using two byte characters for the few values that would break HTML or JavaScript strings. It was made to embed binary data in web pages with less overhead than Base64.",
+            link: "https://github.com/kevinAlbs/Base122",
+            tags: vec!["base122", "decoder", "base", "unicode"],
+            popularity: 0.1,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying Base122 with text {:?}", text);
+        let mut results = CrackResult::new(self, text.to_string());
+
+        let Some(decoded_text) = decode_base122(text).and_then(bytes_to_text) else {
+            debug!("Failed to decode base122 because it isn't base122 or isn't readable text");
+            return results;
+        };
+        if !check_string_success(&decoded_text, text) {
+            info!(
+                "Failed to decode base122 because check_string_success returned false on string {}",
+                decoded_text
+            );
+            return results;
+        }
+
+        let checker_result = checker.check(&decoded_text);
+        results.unencrypted_text = Some(vec![decoded_text]);
+
+        results.update_checker(&checker_result);
+
+        results
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some(("4\u{19}-Fc<@w7\u{5cd}F ", "hello world"))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input must contain a two byte character, as base122 of more than a few bytes does",
+            "Input may only contain characters below U+0800, and none of null, newline, carriage return, `\"`, `&` or `\\`",
+            "Decoded bytes must be valid UTF-8 without control characters",
+        ]
+    }
+}
+
+/// Decodes base122 into bytes, None if the text isn't valid base122
+/// Plain ASCII text is rejected, as any of it would decode into noise.
+fn decode_base122(text: &str) -> Option<Vec<u8>> {
+    if text.is_ascii() {
+        return None;
+    }
+    let mut bytes = Vec::new();
+    let mut current: u32 = 0;
+    let mut bit_count = 0;
+    let mut push = |seven: u32| {
+        current = (current << 7) | seven;
+        bit_count += 7;
+        if bit_count >= 8 {
+            bit_count -= 8;
+            bytes.push((current >> bit_count) as u8);
+            current &= (1 << bit_count) - 1;
+        }
+    };
+
+    let characters: Vec<u32> = text.chars().map(u32::from).collect();
+    for (index, &c) in characters.iter().enumerate() {
+        if c < 0x80 {
+            if ILLEGALS.contains(&(c as u8)) {
+                return None;
+            }
+            push(c);
+        } else {
+            // A two byte character is 110iii1x 10xxxxxx, the number of an illegal value
+            // and then the 7 bits after it. The 1 keeps the character from being overlong.
+            if c >= 0x800 || c & 0x80 == 0 {
+                return None;
+            }
+            let illegal = ((c >> 8) & 0b111) as usize;
+            if illegal == SHORTENED {
+                if index != characters.len() - 1 {
+                    return None;
+                }
+            } else {
+                push(u32::from(*ILLEGALS.get(illegal)?));
+            }
+            push(c & 0x7F);
+        }
+    }
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_base122, Base122Decoder};
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn successful_decoding() {
+        let base122_decoder = Decoder::<Base122Decoder>::new();
+        let result = base122_decoder.crack(
+            ")\u{58d}\u{6}K9p 7YD\u{6}\u{13}1Bc5H\u{e}\u{17}+\u{5}dt=\u{b}\u{4}\u{6}SUHg2H\u{2d7}I\u{1}lo;K@",
+            &get_athena_checker(),
+        );
+        assert_eq!(
+            result.unencrypted_text.unwrap()[0],
+            "Sphinx of black quartz, judge my vow."
+        );
+    }
+
+    #[test]
+    fn shortened_characters_hold_the_last_bits() {
+        // The last bit of "b" is a 0, which padded to 7 bits can't be written alone
+        assert_eq!(decode_base122("1\u{780}"), Some(b"b".to_vec()));
+    }
+
+    #[test]
+    fn shortened_characters_must_be_last() {
+        assert_eq!(decode_base122("1\u{780}1"), None);
+    }
+
+    #[test]
+    fn illegal_characters_are_rejected() {
+        assert_eq!(decode_base122("4\"\u{5cd}"), None);
+    }
+
+    #[test]
+    fn ascii_text_is_not_decoded() {
+        let base122_decoder = Decoder::<Base122Decoder>::new();
+        let result = base122_decoder
+            .crack("hello world", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn base122_handle_panic_if_emoji() {
+        let base122_decoder = Decoder::<Base122Decoder>::new();
+        let result = base122_decoder
+            .crack("😂", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+}
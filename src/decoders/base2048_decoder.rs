@@ -0,0 +1,189 @@
+//! Decodes a base2048 string
+//! Base2048 writes every 11 bits as one of 2048 characters chosen to display on most
+//! platforms, none of them right-to-left or punctuation, so that a tweet holds as many
+//! bytes as possible. When the last character would hold 3 bits or fewer it is instead
+//! one of 8 Tibetan marks, so the end can't be mistaken for a longer one.
+//! Call base2048_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::check_string_success;
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::Decoder;
+use super::radix::bytes_to_text;
+
+use log::{debug, info, trace};
+
+/// The Base2048 decoder, call:
+/// `let base2048_decoder = Decoder::<Base2048Decoder>::new()` to create a new instance
+/// And then call:
+/// `result = base2048_decoder.crack(input)` to decode a base2048 string
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::base2048_decoder::{Base2048Decoder};
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_base2048 = Decoder::<Base2048Decoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_base2048.crack("ӜțƘ໐û౩ൻট", &checker).unencrypted_text;
+/// assert!(result.is_some());
+/// assert_eq!(result.unwrap()[0], "hello world");
+/// ```
+pub struct Base2048Decoder;
+
+impl Crack for Decoder<Base2048Decoder> {
+    fn new() -> Decoder<Base2048Decoder> {
+        Decoder {
+            name: "Base2048",
+            description: "Base2048 writes every 11 bits of data as one of 2048 Unicode characters from European, Indic and Southeast Asian scripts, so that a tweet counted in characters holds 385 bytes. The last character may be one of 8 Tibetan marks holding the final few bits.",
+            link: "https://github.com/LLFourn/rust-base2048",
+            tags: vec!["base2048", "decoder", "base", "unicode"],
+            popularity: 0.1,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying Base2048 with text {:?}", text);
+        let mut results = CrackResult::new(self, text.to_string());
+
+        let Some(decoded_text) = decode_base2048(text).and_then(bytes_to_text) else {
+            debug!("Failed to decode base2048 because it isn't base2048 or isn't readable text");
+            return results;
+        };
+        if !check_string_success(&decoded_text, text) {
+            info!(
+                "Failed to decode base2048 because check_string_success returned false on string {}",
+                decoded_text
+            );
+            return results;
+        }
+
+        let checker_result = checker.check(&decoded_text);
+        results.unencrypted_text = Some(vec![decoded_text]);
+
+        results.update_checker(&checker_result);
+
+        results
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some(("ӜțƘ໐û౩ൻট", "hello world"))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input may only contain Base2048 characters, so ASCII text is never decoded",
+            "Only the last character may be one of the 8 tail characters",
+            "The decoded bytes must encode back to exactly the input",
+            "Decoded bytes must be valid UTF-8 without control characters",
+        ]
+    }
+}
+
+/// Decodes base2048 into bytes, None if the text isn't valid base2048
+/// Every character is checked against the table first, as the base2048 crate panics on
+/// characters far outside it, and text whose last character holds bits which don't
+/// decode to anything is rejected by encoding the bytes again.
+fn decode_base2048(text: &str) -> Option<Vec<u8>> {
+    if text.is_empty()
+        || !text
+            .chars()
+            .all(|c| base2048::ENC_TABLE.contains(&c) || base2048::TAIL.contains(&c))
+    {
+        return None;
+    }
+    let bytes = base2048::decode(text)?;
+    (base2048::encode(&bytes) == text).then_some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_base2048, Base2048Decoder};
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn successful_decoding() {
+        let base2048_decoder = Decoder::<Base2048Decoder>::new();
+        let result = base2048_decoder.crack("ЬणƒອङɽੲĢӼĘຕΖźఈർঽȡÙફ൰Ռ৩వǠՍցӇ", &get_athena_checker());
+        assert_eq!(
+            result.unencrypted_text.unwrap()[0],
+            "Sphinx of black quartz, judge my vow."
+        );
+    }
+
+    #[test]
+    fn tail_characters_hold_the_last_bits() {
+        assert_eq!(
+            decode_base2048("ԵտћΖыɘ༖ĢկଜѷΖχ৩ਨඖԔǙϐຕႤɔकԈԄडї࿋൦༎"),
+            Some(b"some utf8 bytes to encode more compactly".to_vec())
+        );
+        assert_eq!(decode_base2048("༎Ե"), None);
+    }
+
+    #[test]
+    fn only_the_shortest_ending_is_accepted() {
+        assert_eq!(decode_base2048("ġ"), Some(b"a".to_vec()));
+        // Ҥ holds more than the 8 bits a lone last character can
+        assert_eq!(decode_base2048("Ҥ"), None);
+    }
+
+    #[test]
+    fn ascii_text_is_not_decoded() {
+        let base2048_decoder = Decoder::<Base2048Decoder>::new();
+        let result = base2048_decoder
+            .crack("hello world", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn base2048_handle_panic_if_emoji() {
+        let base2048_decoder = Decoder::<Base2048Decoder>::new();
+        let result = base2048_decoder
+            .crack("😂", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+}
@@ -0,0 +1,229 @@
+//! Decodes a base32768 string
+//! Base32768 writes every 15 bits as one of 32768 characters taken from the Basic
+//! Multilingual Plane in blocks of 32, which makes it the densest encoding for systems
+//! that count UTF-16 code units, such as Twitter once did. The last few bits can instead
+//! be written with one of 128 characters which each hold 7 bits.
+//! Call base32768_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::check_string_success;
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::Decoder;
+use super::radix::bytes_to_text;
+
+use log::{debug, info, trace};
+
+/// The first and last characters of every block of characters holding 15 bits, in order
+const FULL_BLOCKS: &str = "ҠҿԀԟڀڿݠޟ߀ߟကဟႠႿᄀᅟᆀᆟᇠሿበቿዠዿጠጿᎠᏟᐠᙟᚠᛟកសᠠᡟᣀᣟᦀᦟ᧠᧿ᨠᨿᯀᯟᰀᰟᴀᴟ⇠⇿⋀⋟⍀⏟␀␟─❟➀➿⠀⥿⦠⦿⨠⩟⪀⪿⫠⭟ⰀⰟⲀⳟⴀⴟⵀⵟ⺠⻟㇀㇟㐀䶟䷀龿ꀀꑿ꒠꒿ꔀꗿꙀꙟꚠꛟ꜀ꝟꞀꞟꡀꡟ";
+
+/// The first and last characters of the blocks of characters holding the last 7 bits
+const SHORT_BLOCKS: &str = "ƀƟɀʟ";
+
+/// How many bits a character from the full blocks holds
+const FULL_BITS: u32 = 15;
+
+/// How many bits a character from the short blocks holds
+const SHORT_BITS: u32 = 7;
+
+/// The Base32768 decoder, call:
+/// `let base32768_decoder = Decoder::<Base32768Decoder>::new()` to create a new instance
+/// And then call:
+/// `result = base32768_decoder.crack(input)` to decode a base32768 string
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::base32768_decoder::{Base32768Decoder};
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_base32768 = Decoder::<Base32768Decoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_base32768.crack("媒腻㐤┖ꈳ埳", &checker).unencrypted_text;
+/// assert!(result.is_some());
+/// assert_eq!(result.unwrap()[0], "hello world");
+/// ```
+pub struct Base32768Decoder;
+
+impl Crack for Decoder<Base32768Decoder> {
+    fn new() -> Decoder<Base32768Decoder> {
+        Decoder {
+            name: "Base32768",
+            description: "Base32768 writes every 15 bits of data as one of 32768 Unicode characters from the Basic Multilingual Plane, the densest encoding for text measured in UTF-16. It is a sibling of Base65536 by the same author.",
+            link: "https://github.com/qntm/base32768",
+            tags: vec!["base32768", "decoder", "base", "unicode"],
+            popularity: 0.1,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying Base32768 with text {:?}", text);
+        let mut results = CrackResult::new(self, text.to_string());
+
+        let Some(decoded_text) = decode_base32768(text).and_then(bytes_to_text) else {
+            debug!("Failed to decode base32768 because it isn't base32768 or isn't readable text");
+            return results;
+        };
+        if !check_string_success(&decoded_text, text) {
+            info!(
+                "Failed to decode base32768 because check_string_success returned false on string {}",
+                decoded_text
+            );
+            return results;
+        }
+
+        let checker_result = checker.check(&decoded_text);
+        results.unencrypted_text = Some(vec![decoded_text]);
+
+        results.update_checker(&checker_result);
+
+        results
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some(("媒腻㐤┖ꈳ埳", "hello world"))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input may only contain Base32768 characters, so ASCII text is never decoded",
+            "Only the last character may hold 7 bits, and the padding bits must all be 1",
+            "Decoded bytes must be valid UTF-8 without control characters",
+        ]
+    }
+}
+
+/// The number a character stands for in the repertoire whose blocks are given, if it is in one
+fn lookup(c: char, blocks: &str) -> Option<u32> {
+    let bounds: Vec<u32> = blocks.chars().map(u32::from).collect();
+    let mut offset = 0;
+    for block in bounds.chunks(2) {
+        let (first, last) = (block[0], block[1]);
+        if (first..=last).contains(&u32::from(c)) {
+            return Some(offset + u32::from(c) - first);
+        }
+        offset += last - first + 1;
+    }
+    None
+}
+
+/// Decodes base32768 into bytes, None if the text isn't valid base32768
+/// The bits that don't fill the last byte are padding, which is always ones.
+fn decode_base32768(text: &str) -> Option<Vec<u8>> {
+    let characters: Vec<char> = text.chars().collect();
+    let mut bytes = Vec::new();
+    let mut bits: u32 = 0;
+    let mut bit_count = 0;
+    for (index, &c) in characters.iter().enumerate() {
+        let (value, width) = match lookup(c, FULL_BLOCKS) {
+            Some(value) => (value, FULL_BITS),
+            None if index == characters.len() - 1 => (lookup(c, SHORT_BLOCKS)?, SHORT_BITS),
+            None => return None,
+        };
+        bits = (bits << width) | value;
+        bit_count += width;
+        while bit_count >= 8 {
+            bit_count -= 8;
+            bytes.push((bits >> bit_count) as u8);
+            bits &= (1 << bit_count) - 1;
+        }
+    }
+    if bits != (1 << bit_count) - 1 {
+        return None;
+    }
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_base32768, lookup, Base32768Decoder, FULL_BLOCKS, SHORT_BLOCKS};
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn successful_decoding() {
+        let base32768_decoder = Decoder::<Base32768Decoder>::new();
+        let result = base32768_decoder.crack(
+            "倘䁚启ቦꇑᆉ缢觋㙸莸咮渂蝣倵漮讀崜湽瑎饏",
+            &get_athena_checker(),
+        );
+        assert_eq!(
+            result.unencrypted_text.unwrap()[0],
+            "Sphinx of black quartz, judge my vow."
+        );
+    }
+
+    #[test]
+    fn repertoires_are_complete() {
+        assert_eq!(lookup('ꡟ', FULL_BLOCKS), Some(32767));
+        assert_eq!(lookup('ʟ', SHORT_BLOCKS), Some(127));
+    }
+
+    #[test]
+    fn short_characters_hold_the_last_bits() {
+        assert_eq!(decode_base32768("坟"), Some(b"a".to_vec()));
+        assert_eq!(decode_base32768("圑ɟ"), Some(b"ab".to_vec()));
+    }
+
+    #[test]
+    fn short_characters_must_be_last() {
+        assert_eq!(decode_base32768("ɟ圑"), None);
+    }
+
+    #[test]
+    fn ascii_text_is_not_decoded() {
+        let base32768_decoder = Decoder::<Base32768Decoder>::new();
+        let result = base32768_decoder
+            .crack("hello world", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn base32768_handle_panic_if_emoji() {
+        let base32768_decoder = Decoder::<Base32768Decoder>::new();
+        let result = base32768_decoder
+            .crack("😂", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+}
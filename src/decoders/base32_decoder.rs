@@ -92,6 +92,21 @@ impl Crack for Decoder<Base32Decoder> {
     fn get_link(&self) -> &str {
         self.link
     }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some(("NBSWY3DPEB3W64TMMQ======", "hello world"))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input must be valid Base32 once `=` padding is stripped",
+            "Decoded text must differ from the input",
+        ]
+    }
 }
 
 /// helper function
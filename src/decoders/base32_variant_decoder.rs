@@ -0,0 +1,245 @@
+//! Decodes base32 written with one of the alphabets other than the standard one
+//! The standard RFC 4648 alphabet is left to the Base32 decoder. This decoder tries
+//! base32hex (RFC 4648 section 7, which keeps the sort order of the data), Crockford's
+//! base32 (no I, L, O or U, read without caring for case or hyphens) and z-base-32
+//! (with the easiest characters to tell apart given to the most common values).
+//! Call base32_variant_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::check_string_success;
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::Decoder;
+use super::radix::bytes_to_text;
+
+use data_encoding::{Encoding, Specification};
+use log::{debug, info, trace};
+use once_cell::sync::Lazy;
+
+/// The variants we try, by name, in the order we try them
+static VARIANTS: Lazy<Vec<(&str, Encoding)>> = Lazy::new(|| {
+    vec![
+        (
+            "base32hex",
+            encoding(
+                "0123456789ABCDEFGHIJKLMNOPQRSTUV",
+                ("abcdefghijklmnopqrstuv", "ABCDEFGHIJKLMNOPQRSTUV"),
+                "",
+            ),
+        ),
+        (
+            "Crockford",
+            encoding(
+                "0123456789ABCDEFGHJKMNPQRSTVWXYZ",
+                // I and L are read as 1 and O as 0, as Crockford's spec asks
+                (
+                    "abcdefghjkmnpqrstvwxyzIiLlOo",
+                    "ABCDEFGHJKMNPQRSTVWXYZ111100",
+                ),
+                "-",
+            ),
+        ),
+        (
+            "z-base-32",
+            encoding(
+                "ybndrfg8ejkmcpqxot1uwisza345h769",
+                ("YBNDRFGEJKMCPQXOTUWISZAH", "ybndrfgejkmcpqxotuwiszah"),
+                "",
+            ),
+        ),
+    ]
+});
+
+/// Builds an unpadded base32 encoding with these symbols from 0 to 31
+/// `translate` maps other characters onto symbols, and `ignore` are characters to skip.
+fn encoding(symbols: &str, translate: (&str, &str), ignore: &str) -> Encoding {
+    let mut specification = Specification::new();
+    specification.symbols.push_str(symbols);
+    specification.translate.from.push_str(translate.0);
+    specification.translate.to.push_str(translate.1);
+    specification.ignore.push_str(ignore);
+    specification
+        .encoding()
+        .expect("base32 variant specifications are valid")
+}
+
+/// The Base32 variant decoder, call:
+/// `let base32_variant_decoder = Decoder::<Base32VariantDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = base32_variant_decoder.crack(input)` to decode a base32 string
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::base32_variant_decoder::{Base32VariantDecoder};
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_base32_variant = Decoder::<Base32VariantDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_base32_variant.crack("pb1sa5dxrb5s6hucco", &checker);
+/// assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+/// assert!(result.description.contains("z-base-32"));
+/// ```
+pub struct Base32VariantDecoder;
+
+impl Crack for Decoder<Base32VariantDecoder> {
+    fn new() -> Decoder<Base32VariantDecoder> {
+        Decoder {
+            name: "Base32 Variants",
+            description: "Base32 written with an alphabet other than the standard one: base32hex from RFC 4648, which keeps the sort order of the data, Crockford's base32, which leaves out letters that are easy to misread, or z-base-32, which is made to be easy for people to read and type.",
+            link: "https://en.wikipedia.org/wiki/Base32#Alternative_versions",
+            tags: vec!["base32", "decoder", "base"],
+            popularity: 0.6,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// Decodes the text with every variant whose alphabet it is written in.
+    /// The text the checker identifies is moved to the front and its variant is
+    /// recorded in the description.
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying Base32 variants with text {:?}", text);
+        let mut results = CrackResult::new(self, text.to_string());
+
+        let stripped = text.trim().trim_end_matches('=');
+        let mut candidates: Vec<(&str, String)> = VARIANTS
+            .iter()
+            .filter_map(|(name, encoding)| {
+                let decoded = encoding.decode(stripped.as_bytes()).ok()?;
+                Some((*name, bytes_to_text(decoded)?))
+            })
+            .filter(|(_, decoded)| check_string_success(decoded, text))
+            .collect();
+        if candidates.is_empty() {
+            info!("Failed to decode base32 with any of the variant alphabets");
+            return results;
+        }
+
+        let mut checker_result = None;
+        for index in 0..candidates.len() {
+            let result = checker.check(&candidates[index].1);
+            if result.is_identified {
+                candidates.swap(0, index);
+                checker_result = Some(result);
+                break;
+            }
+        }
+
+        let variant = candidates[0].0;
+        debug!("Base32 decoded with the {} alphabet", variant);
+        results.description = format!(
+            "{} Decoded with the {} alphabet.",
+            self.description, variant
+        );
+        results.unencrypted_text =
+            Some(candidates.into_iter().map(|(_, decoded)| decoded).collect());
+        if let Some(checker_result) = checker_result {
+            results.update_checker(&checker_result);
+        }
+
+        results
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some(("D1JPRV3F41VPYWKCCG", "hello world"))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input must be valid base32hex, Crockford base32 or z-base-32 once `=` padding is stripped",
+            "Unused bits at the end must be zero",
+            "Decoded bytes must be valid UTF-8 without control characters",
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Base32VariantDecoder;
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn base32hex_decodes_successfully() {
+        let decoder = Decoder::<Base32VariantDecoder>::new();
+        let result = decoder.crack("D1IMOR3F41RMUSJCCG======", &get_athena_checker());
+        assert!(result.success);
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+        assert!(result.description.contains("base32hex"));
+    }
+
+    #[test]
+    fn crockford_decodes_successfully() {
+        let decoder = Decoder::<Base32VariantDecoder>::new();
+        let result = decoder.crack("D1JPRV3F41VPYWKCCG", &get_athena_checker());
+        assert!(result.success);
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+        assert!(result.description.contains("Crockford"));
+    }
+
+    #[test]
+    fn crockford_ignores_case_hyphens_and_misread_letters() {
+        // l is read as 1, and hyphens are only there to make the text easier to read
+        let decoder = Decoder::<Base32VariantDecoder>::new();
+        let result = decoder.crack("d1jprv3f4l-vpywkccg", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+    }
+
+    #[test]
+    fn z_base_32_decodes_successfully() {
+        let decoder = Decoder::<Base32VariantDecoder>::new();
+        let result = decoder.crack("pb1sa5dxrb5s6hucco", &get_athena_checker());
+        assert!(result.success);
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+        assert!(result.description.contains("z-base-32"));
+    }
+
+    #[test]
+    fn base32_variants_handle_panic_if_empty_string() {
+        let decoder = Decoder::<Base32VariantDecoder>::new();
+        let result = decoder.crack("", &get_athena_checker()).unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn base32_variants_handle_panic_if_emoji() {
+        let decoder = Decoder::<Base32VariantDecoder>::new();
+        let result = decoder.crack("😂", &get_athena_checker()).unencrypted_text;
+        assert!(result.is_none());
+    }
+}
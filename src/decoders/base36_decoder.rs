@@ -0,0 +1,202 @@
+//! Decodes a base36 string
+//! Base36 writes data as one big number with the digits and one case of letters, so it
+//! survives being read aloud or typed without caring for case. It is often used for short
+//! IDs and tracking numbers, so besides the bytes the number itself is given as a candidate too.
+//! Call base36_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::check_string_success;
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::Decoder;
+use super::radix::big_number_readings;
+
+use log::{debug, info, trace};
+
+/// The digits of base36, from 0 to 35
+const ALPHABET: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+/// The Base36 decoder, call:
+/// `let base36_decoder = Decoder::<Base36Decoder>::new()` to create a new instance
+/// And then call:
+/// `result = base36_decoder.crack(input)` to decode a base36 string
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::base36_decoder::{Base36Decoder};
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_base36 = Decoder::<Base36Decoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_base36.crack("fuvrsivvnfrbjwajo", &checker).unencrypted_text;
+/// assert!(result.is_some());
+/// assert_eq!(result.unwrap()[0], "hello world");
+/// ```
+pub struct Base36Decoder;
+
+impl Crack for Decoder<Base36Decoder> {
+    fn new() -> Decoder<Base36Decoder> {
+        Decoder {
+            name: "Base36",
+            description: "Base36 writes binary data as one big number in radix 36, using the digits and the letters of one case. It is used for short IDs that have to be typed or read out, such as tracking numbers. Short inputs mixing digits and letters are also read as the number they write.",
+            link: "https://en.wikipedia.org/wiki/Base36",
+            tags: vec!["base36", "decoder", "base"],
+            popularity: 0.4,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying Base36 with text {:?}", text);
+        let mut results = CrackResult::new(self, text.to_string());
+
+        // Base36 ignores case, but text mixing both cases is more likely something else
+        if text.bytes().any(|c| c.is_ascii_lowercase())
+            && text.bytes().any(|c| c.is_ascii_uppercase())
+        {
+            debug!("Failed to decode base36 because it mixes capital and small letters");
+            return results;
+        }
+        let decoded: Vec<String> = big_number_readings(&text.to_ascii_lowercase(), ALPHABET)
+            .into_iter()
+            .filter(|decoded| check_string_success(decoded, text))
+            .collect();
+        if decoded.is_empty() {
+            info!("Failed to decode base36 because it isn't a base36 number or decodes to nothing readable");
+            return results;
+        }
+
+        for candidate in &decoded {
+            let checker_result = checker.check(candidate);
+            if checker_result.is_identified {
+                results.update_checker(&checker_result);
+                break;
+            }
+        }
+        debug!("Base36 decoded {} candidates", decoded.len());
+        results.unencrypted_text = Some(decoded);
+
+        results
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some(("fuvrsivvnfrbjwajo", "hello world"))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input may only contain ASCII digits and letters of one case",
+            "Decoded bytes must be readable text, or the input must mix digits and letters and fit in 64 bits",
+            "Decoded text must differ from the input",
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Base36Decoder;
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn successful_decoding() {
+        let base36_decoder = Decoder::<Base36Decoder>::new();
+        let result = base36_decoder.crack("fuvrsivvnfrbjwajo", &get_athena_checker());
+        assert!(result.success);
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+    }
+
+    #[test]
+    fn ids_are_read_as_numbers() {
+        let base36_decoder = Decoder::<Base36Decoder>::new();
+        let result = base36_decoder
+            .crack("KF12OI", &get_athena_checker())
+            .unencrypted_text
+            .unwrap();
+        assert!(result.contains(&"1234567890".to_string()));
+    }
+
+    #[test]
+    fn words_are_not_read_as_numbers() {
+        let base36_decoder = Decoder::<Base36Decoder>::new();
+        let result = base36_decoder
+            .crack("hello", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn base36_rejects_mixed_case() {
+        let base36_decoder = Decoder::<Base36Decoder>::new();
+        let result = base36_decoder
+            .crack("fuvrsivvnFRBJWAJO", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn base36_rejects_characters_outside_alphabet() {
+        let base36_decoder = Decoder::<Base36Decoder>::new();
+        let result = base36_decoder
+            .crack("fuvrsivvnfrbjwajo+", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn base36_handle_panic_if_empty_string() {
+        let base36_decoder = Decoder::<Base36Decoder>::new();
+        let result = base36_decoder
+            .crack("", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn base36_handle_panic_if_emoji() {
+        let base36_decoder = Decoder::<Base36Decoder>::new();
+        let result = base36_decoder
+            .crack("😂", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+}
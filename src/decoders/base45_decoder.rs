@@ -0,0 +1,225 @@
+//! Decodes a base45 string, as specified in RFC 9285
+//! Base45 packs every two bytes into three characters from the 45 which a QR code can
+//! store most compactly in its alphanumeric mode. EU Digital COVID Certificates are
+//! written this way after an `HC1:` prefix, and hold zlib compressed CBOR, so
+//! [`decode_base45`] gives the raw bytes for decoders which work on binary data.
+//! Call base45_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::check_string_success;
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::Decoder;
+
+use log::{debug, info, trace};
+
+/// The characters of base45, from 0 to 44
+const ALPHABET: &[u8; 45] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ $%*+-./:";
+
+/// The prefix EU Digital COVID Certificates put before their base45 payload
+const CERTIFICATE_PREFIX: &str = "HC1:";
+
+/// The Base45 decoder, call:
+/// `let base45_decoder = Decoder::<Base45Decoder>::new()` to create a new instance
+/// And then call:
+/// `result = base45_decoder.crack(input)` to decode a base45 string
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::base45_decoder::{Base45Decoder};
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_base45 = Decoder::<Base45Decoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_base45.crack("+8D VD82EK4F.KEA2", &checker).unencrypted_text;
+/// assert!(result.is_some());
+/// assert_eq!(result.unwrap()[0], "hello world");
+/// ```
+pub struct Base45Decoder;
+
+impl Crack for Decoder<Base45Decoder> {
+    fn new() -> Decoder<Base45Decoder> {
+        Decoder {
+            name: "Base45",
+            description: "Base45 (RFC 9285) writes every two bytes as three characters from the 45 that QR codes store most compactly: the digits, the capital letters, space and $%*+-./:. It is used for the QR codes of EU Digital COVID Certificates, which start with HC1:.",
+            link: "https://www.rfc-editor.org/rfc/rfc9285",
+            tags: vec!["base45", "decoder", "base", "qr"],
+            popularity: 0.3,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying Base45 with text {:?}", text);
+        let mut results = CrackResult::new(self, text.to_string());
+
+        let Some(decoded_text) =
+            decode_base45(text).and_then(|bytes| String::from_utf8(bytes).ok())
+        else {
+            debug!("Failed to decode base45 because it isn't base45 or isn't valid UTF-8");
+            return results;
+        };
+        if !check_string_success(&decoded_text, text) {
+            info!(
+                "Failed to decode base45 because check_string_success returned false on string {}",
+                decoded_text
+            );
+            return results;
+        }
+
+        let checker_result = checker.check(&decoded_text);
+        results.unencrypted_text = Some(vec![decoded_text]);
+
+        results.update_checker(&checker_result);
+
+        results
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some(("+8D VD82EK4F.KEA2", "hello world"))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input may only use the base45 alphabet, after an optional `HC1:` prefix",
+            "Input length must not leave a single character over, and every group must fit its bytes",
+            "Decoded bytes must be valid UTF-8",
+        ]
+    }
+}
+
+/// Decodes base45 into bytes, None if the text isn't valid base45
+/// Every three characters are a number `c + d * 45 + e * 45²` which is two bytes,
+/// and two characters left over at the end are one byte.
+pub fn decode_base45(text: &str) -> Option<Vec<u8>> {
+    let text = text.strip_prefix(CERTIFICATE_PREFIX).unwrap_or(text);
+    if text.is_empty() || text.len() % 3 == 1 {
+        return None;
+    }
+    let values: Vec<u32> = text
+        .bytes()
+        .map(|c| ALPHABET.iter().position(|&a| a == c).map(|v| v as u32))
+        .collect::<Option<_>>()?;
+
+    let mut bytes = Vec::with_capacity(values.len() / 3 * 2 + 1);
+    for group in values.chunks(3) {
+        let number = group
+            .iter()
+            .rev()
+            .fold(0, |number, &value| number * 45 + value);
+        if group.len() == 3 {
+            let pair = u16::try_from(number).ok()?;
+            bytes.extend(pair.to_be_bytes());
+        } else {
+            bytes.push(u8::try_from(number).ok()?);
+        }
+    }
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_base45, Base45Decoder};
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn successful_decoding() {
+        let base45_decoder = Decoder::<Base45Decoder>::new();
+        let result = base45_decoder.crack("+8D VD82EK4F.KEA2", &get_athena_checker());
+        assert!(result.success);
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+    }
+
+    #[test]
+    fn rfc_9285_examples() {
+        assert_eq!(decode_base45("BB8"), Some(b"AB".to_vec()));
+        assert_eq!(decode_base45("%69 VD92EX0"), Some(b"Hello!!".to_vec()));
+        assert_eq!(decode_base45("UJCLQE7W581"), Some(b"base-45".to_vec()));
+        assert_eq!(decode_base45("QED8WEX0"), Some(b"ietf!".to_vec()));
+    }
+
+    #[test]
+    fn certificate_prefix_is_stripped() {
+        assert_eq!(decode_base45("HC1:BB8"), Some(b"AB".to_vec()));
+    }
+
+    #[test]
+    fn groups_too_big_for_their_bytes_are_rejected() {
+        // ::: is 44 + 44 * 45 + 44 * 45², more than two bytes can hold
+        assert_eq!(decode_base45(":::"), None);
+        // GGW is the RFC's example of an invalid group
+        assert_eq!(decode_base45("GGW"), None);
+    }
+
+    #[test]
+    fn a_single_leftover_character_is_rejected() {
+        assert_eq!(decode_base45("BB8B"), None);
+    }
+
+    #[test]
+    fn base45_rejects_small_letters() {
+        let base45_decoder = Decoder::<Base45Decoder>::new();
+        let result = base45_decoder
+            .crack("hello world", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn base45_handle_panic_if_empty_string() {
+        let base45_decoder = Decoder::<Base45Decoder>::new();
+        let result = base45_decoder
+            .crack("", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn base45_handle_panic_if_emoji() {
+        let base45_decoder = Decoder::<Base45Decoder>::new();
+        let result = base45_decoder
+            .crack("😂", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+}
@@ -36,7 +36,7 @@ impl Crack for Decoder<Base58BitcoinDecoder> {
     fn new() -> Decoder<Base58BitcoinDecoder> {
         Decoder {
             name: "Base58 Bitcoin",
-            description: "Base58 is a group of binary-to-text encoding schemes that represent binary data (more specifically, a sequence of 8-bit bytes) in an ASCII string format by translating the data into a radix-32 representation.",
+            description: "Base58 is a group of binary-to-text encoding schemes that represent binary data (more specifically, a sequence of 8-bit bytes) in an ASCII string format by translating the data into a radix-58 representation. The Bitcoin alphabet leaves out 0, O, I and l so it cannot be misread, which is why it is used for Bitcoin addresses and IPFS hashes.",
             link: "https://en.wikipedia.org/wiki/Base58",
             tags: vec!["base58_bitcoin", "base58", "bitcoin", "cryptocurrency", "decoder", "base"],
             popularity: 0.8,
@@ -89,6 +89,21 @@ impl Crack for Decoder<Base58BitcoinDecoder> {
     fn get_link(&self) -> &str {
         self.link
     }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some(("StV1DL6CwTryKyV", "hello world"))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input may only use the Bitcoin Base58 alphabet",
+            "Decoded text must differ from the input",
+        ]
+    }
 }
 
 /// helper function
@@ -175,6 +190,26 @@ mod tests {
         assert!(result.is_none());
     }
 
+    #[test]
+    fn base58_bitcoin_rejects_characters_outside_alphabet() {
+        // 0, O, I and l are not part of the Bitcoin alphabet
+        let base58_bitcoin_decoder = Decoder::<Base58BitcoinDecoder>::new();
+        let result = base58_bitcoin_decoder
+            .crack("0OIl", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn base58_bitcoin_decodes_leading_ones_as_zero_bytes() {
+        // Each leading 1 is a leading zero byte, as seen at the start of Bitcoin addresses
+        let base58_bitcoin_decoder = Decoder::<Base58BitcoinDecoder>::new();
+        let result = base58_bitcoin_decoder
+            .crack("11StV1DL6CwTryKyV", &get_athena_checker())
+            .unencrypted_text;
+        assert_eq!(result.unwrap()[0], "\0\0hello world");
+    }
+
     #[test]
     fn base58_bitcoin_handle_panic_if_emoji() {
         let base58_bitcoin_decoder = Decoder::<Base58BitcoinDecoder>::new();
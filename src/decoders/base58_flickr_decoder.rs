@@ -36,7 +36,7 @@ impl Crack for Decoder<Base58FlickrDecoder> {
     fn new() -> Decoder<Base58FlickrDecoder> {
         Decoder {
             name: "Base58 Flickr",
-            description: "Base58 is a group of binary-to-text encoding schemes that represent binary data (more specifically, a sequence of 8-bit bytes) in an ASCII string format by translating the data into a radix-32 representation.",
+            description: "Base58 is a group of binary-to-text encoding schemes that represent binary data (more specifically, a sequence of 8-bit bytes) in an ASCII string format by translating the data into a radix-58 representation.",
             link: "https://en.wikipedia.org/wiki/Base58",
             tags: vec!["base58_flickr", "base58", "flickr", "decoder", "base"],
             popularity: 0.4,
@@ -89,6 +89,21 @@ impl Crack for Decoder<Base58FlickrDecoder> {
     fn get_link(&self) -> &str {
         self.link
     }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some(("rTu1dk6cWsRYjYu", "hello world"))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input may only use the Flickr Base58 alphabet",
+            "Decoded text must differ from the input",
+        ]
+    }
 }
 
 /// helper function
@@ -36,7 +36,7 @@ impl Crack for Decoder<Base58MoneroDecoder> {
     fn new() -> Decoder<Base58MoneroDecoder> {
         Decoder {
             name: "Base58 Monero",
-            description: "Base58 is a group of binary-to-text encoding schemes that represent binary data (more specifically, a sequence of 8-bit bytes) in an ASCII string format by translating the data into a radix-32 representation.",
+            description: "Base58 is a group of binary-to-text encoding schemes that represent binary data (more specifically, a sequence of 8-bit bytes) in an ASCII string format by translating the data into a radix-58 representation.",
             link: "https://en.wikipedia.org/wiki/Base58",
             tags: vec!["base58_monero", "base58", "monero", "cryptocurrency", "decoder", "base"],
             popularity: 0.4,
@@ -89,6 +89,21 @@ impl Crack for Decoder<Base58MoneroDecoder> {
     fn get_link(&self) -> &str {
         self.link
     }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some(("StV1DL6CwTryKyV", "hello world"))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input may only use the Monero Base58 alphabet",
+            "Decoded text must differ from the input",
+        ]
+    }
 }
 
 /// helper function
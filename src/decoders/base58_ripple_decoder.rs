@@ -36,7 +36,7 @@ impl Crack for Decoder<Base58RippleDecoder> {
     fn new() -> Decoder<Base58RippleDecoder> {
         Decoder {
             name: "Base58 Ripple",
-            description: "Base58 is a group of binary-to-text encoding schemes that represent binary data (more specifically, a sequence of 8-bit bytes) in an ASCII string format by translating the data into a radix-32 representation.",
+            description: "Base58 is a group of binary-to-text encoding schemes that represent binary data (more specifically, a sequence of 8-bit bytes) in an ASCII string format by translating the data into a radix-58 representation.",
             link: "https://en.wikipedia.org/wiki/Base58",
             tags: vec!["base58_ripple", "base58", "ripple", "cryptocurrency", "decoder", "base"],
             popularity: 0.8,
@@ -89,6 +89,21 @@ impl Crack for Decoder<Base58RippleDecoder> {
     fn get_link(&self) -> &str {
         self.link
     }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some(("StVrDLaUATiyKyV", "hello world"))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input may only use the Ripple Base58 alphabet",
+            "Decoded text must differ from the input",
+        ]
+    }
 }
 
 /// helper function
@@ -0,0 +1,186 @@
+//! Decodes a base62 string
+//! Base62 writes data as one big number with the digits, capital letters and small
+//! letters, which keeps it safe in URLs. It is how URL shorteners and many sites write
+//! their IDs, so besides the bytes the number itself is given as a candidate too.
+//! Call base62_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::check_string_success;
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::Decoder;
+use super::radix::big_number_readings;
+
+use log::{debug, info, trace};
+
+/// The digits of base62, from 0 to 61
+const ALPHABET: &[u8; 62] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+/// The Base62 decoder, call:
+/// `let base62_decoder = Decoder::<Base62Decoder>::new()` to create a new instance
+/// And then call:
+/// `result = base62_decoder.crack(input)` to decode a base62 string
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::base62_decoder::{Base62Decoder};
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_base62 = Decoder::<Base62Decoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_base62.crack("AAwf93rvy4aWQVw", &checker).unencrypted_text;
+/// assert!(result.is_some());
+/// assert_eq!(result.unwrap()[0], "hello world");
+/// ```
+pub struct Base62Decoder;
+
+impl Crack for Decoder<Base62Decoder> {
+    fn new() -> Decoder<Base62Decoder> {
+        Decoder {
+            name: "Base62",
+            description: "Base62 writes binary data as one big number in radix 62, using the digits, the capital letters and the small letters. Having no symbols makes it safe in URLs, so it is used by URL shorteners and for IDs. Short inputs mixing digits and letters are also read as the number they write.",
+            link: "https://en.wikipedia.org/wiki/Base62",
+            tags: vec!["base62", "decoder", "base"],
+            popularity: 0.5,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying Base62 with text {:?}", text);
+        let mut results = CrackResult::new(self, text.to_string());
+
+        let decoded: Vec<String> = big_number_readings(text, ALPHABET)
+            .into_iter()
+            .filter(|decoded| check_string_success(decoded, text))
+            .collect();
+        if decoded.is_empty() {
+            info!("Failed to decode base62 because it isn't a base62 number or decodes to nothing readable");
+            return results;
+        }
+
+        for candidate in &decoded {
+            let checker_result = checker.check(candidate);
+            if checker_result.is_identified {
+                results.update_checker(&checker_result);
+                break;
+            }
+        }
+        debug!("Base62 decoded {} candidates", decoded.len());
+        results.unencrypted_text = Some(decoded);
+
+        results
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some(("AAwf93rvy4aWQVw", "hello world"))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input may only contain ASCII digits and letters",
+            "Decoded bytes must be readable text, or the input must mix digits and letters and fit in 64 bits",
+            "Decoded text must differ from the input",
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Base62Decoder;
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn successful_decoding() {
+        let base62_decoder = Decoder::<Base62Decoder>::new();
+        let result = base62_decoder.crack("AAwf93rvy4aWQVw", &get_athena_checker());
+        assert!(result.success);
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+    }
+
+    #[test]
+    fn ids_are_read_as_numbers() {
+        let base62_decoder = Decoder::<Base62Decoder>::new();
+        let result = base62_decoder
+            .crack("1LY7VK", &get_athena_checker())
+            .unencrypted_text
+            .unwrap();
+        assert!(result.contains(&"1234567890".to_string()));
+    }
+
+    #[test]
+    fn words_are_not_read_as_numbers() {
+        let base62_decoder = Decoder::<Base62Decoder>::new();
+        let result = base62_decoder
+            .crack("hello", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn base62_rejects_characters_outside_alphabet() {
+        let base62_decoder = Decoder::<Base62Decoder>::new();
+        let result = base62_decoder
+            .crack("AAwf93rvy4aWQVw+", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn base62_handle_panic_if_empty_string() {
+        let base62_decoder = Decoder::<Base62Decoder>::new();
+        let result = base62_decoder
+            .crack("", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn base62_handle_panic_if_emoji() {
+        let base62_decoder = Decoder::<Base62Decoder>::new();
+        let result = base62_decoder
+            .crack("😂", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+}
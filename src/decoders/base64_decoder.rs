@@ -4,14 +4,16 @@
 //! `result.is_some()` to see if it returned okay.
 
 use crate::checkers::CheckerTypes;
-use crate::decoders::interface::check_string_success;
-use base64::{engine::general_purpose, Engine as _};
+use crate::config::get_config;
+use base64::alphabet::Alphabet;
+use base64::engine::{general_purpose, DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig};
+use base64::Engine as _;
 
 use super::crack_results::CrackResult;
 use super::interface::Crack;
 use super::interface::Decoder;
 
-use log::{debug, info, trace};
+use log::{debug, info, trace, warn};
 
 /// The Base64 decoder, call:
 /// `let base64_decoder = Decoder::<Base64Decoder>::new()` to create a new instance
@@ -45,42 +47,92 @@ impl Crack for Decoder<Base64Decoder> {
         }
     }
 
-    /// This function does the actual decoding
-    /// It returns an Option<string> if it was successful
-    /// Else the Option returns nothing and the error is logged in Trace
+    /// Decodes the text with the alphabet its characters belong to, padded or not, and with
+    /// the custom alphabet from the config if there is one.
+    /// The variant that worked is recorded in the description.
     fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
         trace!("Trying Base64 with text {:?}", text);
 
         let mut results = CrackResult::new(self, text.to_string());
 
-        // Determine which decoder to use based on the characters present
-        let uses_standard_chars = text.contains('+') || text.contains('=') || text.contains('/');
-
-        let decoded_text = if uses_standard_chars {
-            debug!("Using standard Base64 decoder");
-            decode_base64_no_error_handling(text)
+        let mut candidates: Vec<(String, Vec<u8>)> = Vec::new();
+        // Determine which alphabet to use based on the characters present
+        let uses_standard_chars = text.contains(['+', '/']);
+        let uses_url_safe_chars = text.contains(['-', '_']);
+        let padding = if text.ends_with('=') || text.len().is_multiple_of(4) {
+            ""
         } else {
-            debug!("Using URL-safe Base64 decoder");
-            decode_base64_url_no_error_handling(text)
+            " without padding"
         };
+        if uses_standard_chars && uses_url_safe_chars {
+            debug!("Base64 text mixes the standard and URL-safe alphabets");
+        } else if uses_url_safe_chars {
+            debug!("Using URL-safe Base64 decoder");
+            if let Some(decoded) = decode_base64_url_no_error_handling(text) {
+                candidates.push((format!("URL-safe Base64{}", padding), decoded));
+            }
+        } else {
+            debug!("Using standard Base64 decoder");
+            if let Some(decoded) = decode_base64_no_error_handling(text) {
+                candidates.push((format!("standard Base64{}", padding), decoded));
+            }
+        }
+        if let Some(alphabet) = &get_config().base64_alphabet {
+            if let Some(decoded) = decode_base64_custom_no_error_handling(text, alphabet) {
+                if !candidates.iter().any(|(_, existing)| *existing == decoded) {
+                    candidates.push((
+                        format!("Base64 with the custom alphabet {}", alphabet),
+                        decoded,
+                    ));
+                }
+            }
+        }
 
-        if decoded_text.is_none() {
-            debug!("Base64 decode failed");
+        candidates.retain(|(_, decoded)| !decoded.is_empty() && decoded != text.as_bytes());
+        if candidates.is_empty() {
+            info!("Failed to decode base64 with any alphabet");
             return results;
         }
 
-        let decoded_text = decoded_text.unwrap();
-        if !check_string_success(&decoded_text, text) {
-            info!(
-                "Failed to decode base64 because check_string_success returned false on string {}",
-                decoded_text
-            );
+        // Text is what the checker and most decoders want, so bytes which aren't text are
+        // only passed on when no alphabet gave text
+        let (texts, binaries): (Vec<_>, Vec<_>) = candidates
+            .into_iter()
+            .map(|(variant, decoded)| match String::from_utf8(decoded) {
+                Ok(decoded_text) => Ok((variant, decoded_text)),
+                Err(e) => Err((variant, e.into_bytes())),
+            })
+            .partition(Result::is_ok);
+        let mut candidates: Vec<(String, String)> = texts.into_iter().flatten().collect();
+        if candidates.is_empty() {
+            let binaries: Vec<(String, Vec<u8>)> =
+                binaries.into_iter().filter_map(Result::err).collect();
+            debug!("Decoded {} to bytes which aren't text", binaries[0].0);
+            results.description = format!("{} Decoded as {}.", self.description, binaries[0].0);
+            let checker_result = checker.check_bytes(&binaries[0].1);
+            results.unencrypted_bytes =
+                Some(binaries.into_iter().map(|(_, decoded)| decoded).collect());
+            results.update_checker(&checker_result);
             return results;
         }
 
-        let checker_result = checker.check(&decoded_text);
-        results.unencrypted_text = Some(vec![decoded_text]);
-        results.update_checker(&checker_result);
+        let mut checker_result = None;
+        for index in 0..candidates.len() {
+            let result = checker.check(&candidates[index].1);
+            if result.is_identified {
+                candidates.swap(0, index);
+                checker_result = Some(result);
+                break;
+            }
+        }
+
+        debug!("Decoded {}", candidates[0].0);
+        results.description = format!("{} Decoded as {}.", self.description, candidates[0].0);
+        results.unencrypted_text =
+            Some(candidates.into_iter().map(|(_, decoded)| decoded).collect());
+        if let Some(checker_result) = checker_result {
+            results.update_checker(&checker_result);
+        }
 
         results
     }
@@ -105,10 +157,23 @@ impl Crack for Decoder<Base64Decoder> {
     fn get_link(&self) -> &str {
         self.link
     }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some(("aGVsbG8gd29ybGQ=", "hello world"))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input must be valid Base64 once `=` padding is stripped",
+            "Input with `-` or `_` is decoded with the URL-safe alphabet, other input with the standard one",
+            "The custom alphabet set with `base64_alphabet` is tried as well",
+            "Decoded bytes which aren't valid UTF-8 are passed on as bytes, for decoders such as gzip",
+        ]
+    }
 }
 
 /// helper function for standard base64
-fn decode_base64_no_error_handling(text: &str) -> Option<String> {
+fn decode_base64_no_error_handling(text: &str) -> Option<Vec<u8>> {
     // Strip all padding
     let text = text.replace('=', "");
     // Runs the code to decode base64
@@ -116,11 +181,10 @@ fn decode_base64_no_error_handling(text: &str) -> Option<String> {
     general_purpose::STANDARD_NO_PAD
         .decode(text.as_bytes())
         .ok()
-        .map(|inner| String::from_utf8(inner).ok())?
 }
 
 /// helper function for url-safe base64
-fn decode_base64_url_no_error_handling(text: &str) -> Option<String> {
+fn decode_base64_url_no_error_handling(text: &str) -> Option<Vec<u8>> {
     // Strip all padding
     let text = text.replace('=', "");
 
@@ -128,12 +192,28 @@ fn decode_base64_url_no_error_handling(text: &str) -> Option<String> {
     general_purpose::URL_SAFE_NO_PAD
         .decode(text.as_bytes())
         .ok()
-        .map(|inner| String::from_utf8(inner).ok())?
+}
+
+/// helper function for base64 with a custom alphabet
+/// None if the alphabet isn't 64 different printable ASCII characters or the text doesn't decode
+fn decode_base64_custom_no_error_handling(text: &str, alphabet: &str) -> Option<Vec<u8>> {
+    let alphabet = match Alphabet::new(alphabet) {
+        Ok(alphabet) => alphabet,
+        Err(e) => {
+            warn!("Ignoring the custom Base64 alphabet: {}", e);
+            return None;
+        }
+    };
+    let engine = GeneralPurpose::new(
+        &alphabet,
+        GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::RequireNone),
+    );
+    engine.decode(text.trim_end_matches('=').as_bytes()).ok()
 }
 
 #[cfg(test)]
 mod tests {
-    use super::Base64Decoder;
+    use super::{decode_base64_custom_no_error_handling, Base64Decoder};
     use crate::{
         checkers::{
             athena::Athena,
@@ -160,6 +240,17 @@ mod tests {
         assert_eq!(decoded_str[0], "hello world");
     }
 
+    #[test]
+    fn binary_output_is_passed_on_as_bytes() {
+        let base64_decoder = Decoder::<Base64Decoder>::new();
+        let result = base64_decoder.crack("H4sIAAAAAAACA8tIzcnJ", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+        assert_eq!(
+            result.unencrypted_bytes.unwrap()[0],
+            vec![0x1f, 0x8b, 0x08, 0, 0, 0, 0, 0, 0x02, 0x03, 0xcb, 0x48, 0xcd, 0xc9, 0xc9]
+        );
+    }
+
     #[test]
     fn successful_url_safe_decoding() {
         let base64_decoder = Decoder::<Base64Decoder>::new();
@@ -183,6 +274,52 @@ mod tests {
         }
     }
 
+    #[test]
+    fn padded_url_safe_decoding() {
+        // Padding used to send text to the standard alphabet, which can't read - or _
+        let base64_decoder = Decoder::<Base64Decoder>::new();
+        let result = base64_decoder.crack("PD8-Pz4=", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "<?>?>");
+        assert!(result.description.contains("URL-safe Base64."));
+    }
+
+    #[test]
+    fn missing_padding_is_reported() {
+        let base64_decoder = Decoder::<Base64Decoder>::new();
+        let result = base64_decoder.crack("aGVsbG8gd29ybGQ", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+        assert!(result
+            .description
+            .contains("standard Base64 without padding"));
+    }
+
+    #[test]
+    fn mixed_alphabets_are_rejected() {
+        let base64_decoder = Decoder::<Base64Decoder>::new();
+        let result = base64_decoder
+            .crack("PD8-Pz4/", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn custom_alphabet_decoding() {
+        // The standard alphabet with the capital and small letters swapped
+        let alphabet = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789+/";
+        assert_eq!(
+            decode_base64_custom_no_error_handling("AgvSBg8GD29YBgq=", alphabet),
+            Some(b"hello world".to_vec())
+        );
+    }
+
+    #[test]
+    fn invalid_custom_alphabets_are_ignored() {
+        assert_eq!(
+            decode_base64_custom_no_error_handling("aGVsbG8gd29ybGQ=", "abc"),
+            None
+        );
+    }
+
     #[test]
     fn base64_decode_empty_string() {
         let base64_decoder = Decoder::<Base64Decoder>::new();
@@ -0,0 +1,234 @@
+//! Decodes a base92 string
+//! Base92 uses the 91 printable ASCII characters other than space, `"` and `` ` ``, and
+//! writes every 13 bits as two of them, with a last lone character holding up to 6 bits.
+//! A single `~` stands for no data at all.
+//! Call base92_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::check_string_success;
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::Decoder;
+use super::radix::bytes_to_text;
+
+use log::{debug, info, trace};
+
+/// How many values one base92 character holds, `~` is left for the empty string
+const RADIX: u32 = 91;
+
+/// The Base92 decoder, call:
+/// `let base92_decoder = Decoder::<Base92Decoder>::new()` to create a new instance
+/// And then call:
+/// `result = base92_decoder.crack(input)` to decode a base92 string
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::base92_decoder::{Base92Decoder};
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_base92 = Decoder::<Base92Decoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_base92.crack("Fc_$aOTdKnsM*k", &checker).unencrypted_text;
+/// assert!(result.is_some());
+/// assert_eq!(result.unwrap()[0], "hello world");
+/// ```
+pub struct Base92Decoder;
+
+impl Crack for Decoder<Base92Decoder> {
+    fn new() -> Decoder<Base92Decoder> {
+        Decoder {
+            name: "Base92",
+            description: "Base92 writes every 13 bits of data as two of the 91 printable ASCII characters other than space, double quote and backtick, making it a little denser than Base91. A lone ~ is the empty string.",
+            link: "https://github.com/thenoviceoof/base92",
+            tags: vec!["base92", "decoder", "base"],
+            popularity: 0.2,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying Base92 with text {:?}", text);
+        let mut results = CrackResult::new(self, text.to_string());
+
+        // Base92 of anything but the shortest data has symbols in it,
+        // so plain words and numbers aren't decoded into noise
+        if !text.bytes().any(|c| c.is_ascii_punctuation()) {
+            debug!("Failed to decode base92 because the text has no symbols");
+            return results;
+        }
+        let Some(decoded_text) = decode_base92(text).and_then(bytes_to_text) else {
+            debug!("Failed to decode base92 because it isn't base92 or isn't readable text");
+            return results;
+        };
+        if !check_string_success(&decoded_text, text) {
+            info!(
+                "Failed to decode base92 because check_string_success returned false on string {}",
+                decoded_text
+            );
+            return results;
+        }
+
+        let checker_result = checker.check(&decoded_text);
+        results.unencrypted_text = Some(vec![decoded_text]);
+
+        results.update_checker(&checker_result);
+
+        results
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some(("Fc_$aOTdKnsM*k", "hello world"))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input may only contain printable ASCII other than space, `\"` and `` ` ``",
+            "Input must contain at least one symbol",
+            "Decoded bytes must be valid UTF-8 without control characters",
+        ]
+    }
+}
+
+/// The value of one base92 character, None for characters outside the alphabet
+fn value(c: u8) -> Option<u32> {
+    match c {
+        b'!' => Some(0),
+        b'#'..=b'_' => Some(u32::from(c - b'#') + 1),
+        b'a'..=b'}' => Some(u32::from(c - b'a') + 62),
+        _ => None,
+    }
+}
+
+/// Decodes base92 into bytes, None if the text isn't valid base92
+/// Bits left over at the end which don't make a whole byte are padding.
+fn decode_base92(text: &str) -> Option<Vec<u8>> {
+    if text == "~" {
+        return Some(Vec::new());
+    }
+    let mut bytes = Vec::new();
+    let mut bits: u32 = 0;
+    let mut bit_count = 0;
+    for pair in text.as_bytes().chunks(2) {
+        let (number, width) = match *pair {
+            [first, second] => (value(first)? * RADIX + value(second)?, 13),
+            [last] => (value(last)?, 6),
+            _ => unreachable!("chunks of two are never empty"),
+        };
+        if number >= 1 << width {
+            return None;
+        }
+        bits = (bits << width) | number;
+        bit_count += width;
+        while bit_count >= 8 {
+            bit_count -= 8;
+            bytes.push((bits >> bit_count) as u8);
+            bits &= (1 << bit_count) - 1;
+        }
+    }
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_base92, Base92Decoder};
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn successful_decoding() {
+        let base92_decoder = Decoder::<Base92Decoder>::new();
+        let result = base92_decoder.crack(
+            "?A&W\\;7-$Jjn*]DnDzE2hUa//lxw$p?HE=v5OYe>$XqEjU",
+            &get_athena_checker(),
+        );
+        assert_eq!(
+            result.unencrypted_text.unwrap()[0],
+            "Sphinx of black quartz, judge my vow."
+        );
+    }
+
+    #[test]
+    fn lone_characters_hold_the_last_bits() {
+        // "ab" is 16 bits, one pair and then 3 bits which fit in a lone character
+        assert_eq!(decode_base92("D82"), Some(b"ab".to_vec()));
+        // "a" is 8 bits, too many for a lone character, so it's padded out to a pair
+        assert_eq!(decode_base92("D,"), Some(b"a".to_vec()));
+    }
+
+    #[test]
+    fn tilde_is_empty() {
+        assert_eq!(decode_base92("~"), Some(Vec::new()));
+    }
+
+    #[test]
+    fn pairs_past_13_bits_are_rejected() {
+        // }} is 90 * 91 + 90, more than 13 bits can hold
+        assert_eq!(decode_base92("}}"), None);
+    }
+
+    #[test]
+    fn ordinary_words_are_not_decoded() {
+        let base92_decoder = Decoder::<Base92Decoder>::new();
+        let result = base92_decoder
+            .crack("hello", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn base92_rejects_spaces() {
+        let base92_decoder = Decoder::<Base92Decoder>::new();
+        let result = base92_decoder
+            .crack("Fc_$a OTdKnsM*k", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn base92_handle_panic_if_emoji() {
+        let base92_decoder = Decoder::<Base92Decoder>::new();
+        let result = base92_decoder
+            .crack("😂", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+}
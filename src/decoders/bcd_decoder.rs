@@ -0,0 +1,231 @@
+//! Decode binary-coded decimal
+//! Binary-coded decimal writes a number one decimal digit at a time, each digit as its own
+//! four bits, so only the patterns 0000 to 1001 ever appear. Packed BCD puts two digits in
+//! each byte, and unpacked BCD one digit in the low half of each byte. The digits are
+//! returned as they are, and also read as the three digit character codes of text, as
+//! decimal byte strings are written.
+//! Call bcd_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::check_string_success;
+use crate::decoders::radix::{bytes_to_text, parse_bytes, MIN_BYTES};
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::Decoder;
+
+use log::{debug, trace};
+
+/// The BCD decoder, call:
+/// `let bcd_decoder = Decoder::<BcdDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = bcd_decoder.crack(input)` to decode binary-coded decimal
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::bcd_decoder::{BcdDecoder};
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_bcd = Decoder::<BcdDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// // The digits 104 101 121 are the character codes of "hey"
+/// let result = decode_bcd.crack("0001 0000 0100 0001 0000 0001 0001 0010 0001", &checker).unencrypted_text.unwrap();
+/// assert!(result.contains(&"hey".to_string()));
+/// assert!(result.contains(&"104101121".to_string()));
+/// ```
+pub struct BcdDecoder;
+
+impl Crack for Decoder<BcdDecoder> {
+    fn new() -> Decoder<BcdDecoder> {
+        Decoder {
+            name: "BCD",
+            description: "Binary-coded decimal writes each decimal digit of a number as four bits, as used by calculators, clocks and other electronics which show numbers. Packed BCD holds two digits in a byte and unpacked BCD one.",
+            link: "https://en.wikipedia.org/wiki/Binary-coded_decimal",
+            tags: vec!["bcd", "binary", "decoder"],
+            popularity: 0.3,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying BCD with text {:?}", text);
+        let mut results = CrackResult::new(self, text.to_string());
+
+        let Some(digits) = bcd_to_digits(text) else {
+            debug!(
+                "Failed to decode BCD because the input isn't groups of 4 or 8 bits holding digits"
+            );
+            return results;
+        };
+
+        let mut candidates = Vec::new();
+        // The digits as character codes, then the digits themselves for other decoders
+        if let Some(decoded_text) = parse_bytes(&digits, 10, 3).and_then(bytes_to_text) {
+            candidates.push(decoded_text);
+        }
+        candidates.push(digits);
+        candidates.retain(|candidate| check_string_success(candidate, text));
+
+        for (i, candidate) in candidates.iter().enumerate() {
+            let checker_result = checker.check(candidate);
+            if checker_result.is_identified {
+                let candidate = candidates.remove(i);
+                candidates.insert(0, candidate);
+                results.update_checker(&checker_result);
+                break;
+            }
+        }
+        if candidates.is_empty() {
+            return results;
+        }
+        results.unencrypted_text = Some(candidates);
+
+        results
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some(("0001 0000 0100 0001 0000 0001 0001 0010 0001", "hey"))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input must be groups of 4 or 8 bits, or one run of bits a multiple of 4 long",
+            "Every group of 4 bits must be a digit from 0000 to 1001",
+            "Must hold at least 3 digits",
+        ]
+    }
+}
+
+/// Reads binary-coded decimal as its digits, None if it isn't BCD
+/// Bytes whose upper halves are all zero are unpacked BCD, other bytes are packed.
+fn bcd_to_digits(text: &str) -> Option<String> {
+    let groups: Vec<&str> = text.split_whitespace().collect();
+    if groups.is_empty()
+        || !groups
+            .iter()
+            .all(|g| g.bytes().all(|b| b == b'0' || b == b'1'))
+    {
+        return None;
+    }
+    let bits = groups.concat();
+    let nibbles: Vec<u8> = bits
+        .as_bytes()
+        .chunks(4)
+        .map(|nibble| {
+            (nibble.len() == 4)
+                .then(|| nibble.iter().fold(0, |value, bit| value * 2 + (bit - b'0')))
+        })
+        .collect::<Option<Vec<u8>>>()?;
+    if nibbles.iter().any(|&nibble| nibble > 9) {
+        return None;
+    }
+
+    let width = groups[0].len();
+    let nibbles: Vec<u8> = match width {
+        _ if groups.len() == 1 => nibbles,
+        4 if groups.iter().all(|g| g.len() == 4) => nibbles,
+        8 if groups.iter().all(|g| g.len() == 8) => {
+            let unpacked = nibbles.chunks(2).all(|byte| byte[0] == 0);
+            if unpacked {
+                nibbles.chunks(2).map(|byte| byte[1]).collect()
+            } else {
+                nibbles
+            }
+        }
+        _ => return None,
+    };
+    if nibbles.len() < MIN_BYTES {
+        return None;
+    }
+    Some(
+        nibbles
+            .iter()
+            .map(|&digit| char::from(b'0' + digit))
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bcd_to_digits, BcdDecoder};
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn bcd_decodes_to_text() {
+        let decoder = Decoder::<BcdDecoder>::new();
+        let result = decoder.crack(
+            "0001 0000 0100 0001 0000 0001 0001 0000 1000 0001 0000 1000 0001 0001 0001 0000 0011 0010 0001 0001 1001 0001 0001 0001 0001 0001 0100 0001 0000 1000 0001 0000 0000",
+            &get_athena_checker(),
+        );
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+    }
+
+    #[test]
+    fn packed_and_unpacked_bytes() {
+        assert_eq!(bcd_to_digits("00100000 00100100").unwrap(), "2024");
+        assert_eq!(bcd_to_digits("00000010 00000000 00000010").unwrap(), "202");
+        assert_eq!(bcd_to_digits("0010000000100100").unwrap(), "2024");
+    }
+
+    #[test]
+    fn nibbles_above_nine_are_rejected() {
+        assert!(bcd_to_digits("0001 1010 0100").is_none());
+    }
+
+    #[test]
+    fn mixed_group_sizes_are_rejected() {
+        assert!(bcd_to_digits("0001 00000100 0001").is_none());
+    }
+
+    #[test]
+    fn bcd_rejects_empty_string() {
+        let decoder = Decoder::<BcdDecoder>::new();
+        let result = decoder.crack("", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+
+    #[test]
+    fn bcd_rejects_emoji() {
+        let decoder = Decoder::<BcdDecoder>::new();
+        let result = decoder.crack("😂", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+}
@@ -70,6 +70,22 @@ impl Crack for Decoder<BinaryDecoder> {
     fn get_link(&self) -> &str {
         self.link
     }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some(("0110100001100101011011000110110001101111001000000111011101101111011100100110110001100100", "hello world"))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Every character other than `0` and `1` is ignored",
+            "Tries every byte length from 1 to 24 bits",
+            "Decoded text must differ from the input",
+        ]
+    }
 }
 
 /// Decodes binary to string
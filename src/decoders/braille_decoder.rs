@@ -1,12 +1,69 @@
+//! Decode Braille, written as Unicode Braille patterns or as the numbers of the raised dots
+//! The text is read as Grade 1 (uncontracted) Braille, so besides the letters it follows the
+//! capital sign, the capital word sign, the number sign which turns a to j into digits, and
+//! the common punctuation cells. Cells can also be given in dot-number notation, such as
+//! `125 15 123` or `125-15-123`, with words split by `/` or a wider gap.
+//! Call braille_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
 use super::crack_results::CrackResult;
+use super::interface::check_string_success;
 use super::interface::Crack;
 use super::interface::Decoder;
 use crate::checkers::CheckerTypes;
 
-use log::trace;
-use std::collections::HashMap;
+use log::{debug, info, trace};
+
+/// The blank cell, which is the space between words
+const BLANK: char = '\u{2800}';
+
+/// The cells for a to z, in order
+const LETTERS: [char; 26] = [
+    '⠁', '⠃', '⠉', '⠙', '⠑', '⠋', '⠛', '⠓', '⠊', '⠚', '⠅', '⠇', '⠍', '⠝', '⠕', '⠏', '⠟', '⠗', '⠎',
+    '⠞', '⠥', '⠧', '⠺', '⠭', '⠽', '⠵',
+];
+
+/// The capital sign, dot 6, which makes the next letter a capital or, doubled, the whole word
+const CAPITAL: char = '⠠';
+
+/// The number sign, dots 3456, which makes a to j the digits 1 to 0 until the next space
+const NUMBER: char = '⠼';
+
+/// The letter sign, dots 56, which ends a number so the next cell is a letter again
+const LETTER: char = '⠰';
+
+/// Punctuation cells and what they stand for
+const PUNCTUATION: [(char, char); 8] = [
+    ('⠂', ','),
+    ('⠆', ';'),
+    ('⠒', ':'),
+    ('⠲', '.'),
+    ('⠖', '!'),
+    ('⠦', '?'),
+    ('⠄', '\''),
+    ('⠤', '-'),
+];
 
-/// Braille Decoder
+/// The Braille decoder, call:
+/// `let braille_decoder = Decoder::<BrailleDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = braille_decoder.crack(input)` to decode Braille
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::braille_decoder::{BrailleDecoder};
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_braille = Decoder::<BrailleDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_braille.crack("⠠⠓⠑⠇⠇⠕⠀⠺⠕⠗⠇⠙⠖", &checker);
+/// assert_eq!(result.unencrypted_text.unwrap()[0], "Hello world!");
+/// // Cells can also be written as the numbers of their raised dots
+/// let result = decode_braille.crack("125 15 123 123 135 / 2456 135 1235 123 145", &checker);
+/// assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+/// ```
 pub struct BrailleDecoder;
 
 impl Crack for Decoder<BrailleDecoder> {
@@ -25,21 +82,22 @@ impl Crack for Decoder<BrailleDecoder> {
         trace!("Trying braille with text {:?}", text);
         let mut results = CrackResult::new(self, text.to_string());
 
-        if text.is_empty() {
+        let Some(decoded_text) = braille_to_text(text) else {
+            debug!("Failed to decode braille because the text isn't Braille cells or dot numbers");
             return results;
-        }
-
-        let decoded_text = braille_to_text(text);
-
-        let checker_result = checker.check(&decoded_text);
-        if checker_result.is_identified {
-            trace!("Found a match with braille");
-            results.unencrypted_text = Some(vec![decoded_text]);
-            results.update_checker(&checker_result);
+        };
+        if !check_string_success(&decoded_text, text) {
+            info!(
+                "Failed to decode braille because check_string_success returned false on string {}",
+                decoded_text
+            );
             return results;
         }
 
+        let checker_result = checker.check(&decoded_text);
         results.unencrypted_text = Some(vec![decoded_text]);
+        results.update_checker(&checker_result);
+
         results
     }
 
@@ -60,50 +118,130 @@ impl Crack for Decoder<BrailleDecoder> {
     fn get_link(&self) -> &str {
         self.link
     }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some(("⠓⠑⠇⠇⠕⠀⠺⠕⠗⠇⠙", "hello world"))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "At least half the characters besides whitespace must be Unicode Braille patterns, or the input must be dot numbers from 1 to 6",
+            "Characters without a Braille mapping are left unchanged",
+        ]
+    }
 }
 
-/// Converts Braille Unicode characters to their corresponding Latin alphabet characters
-///
-/// This function maps each Braille character to its corresponding Latin letter
-/// and returns the decoded text as a String.
-fn braille_to_text(text: &str) -> String {
-    let mut mapping = HashMap::new();
-    mapping.insert('⠁', 'a');
-    mapping.insert('⠃', 'b');
-    mapping.insert('⠉', 'c');
-    mapping.insert('⠙', 'd');
-    mapping.insert('⠑', 'e');
-    mapping.insert('⠋', 'f');
-    mapping.insert('⠛', 'g');
-    mapping.insert('⠓', 'h');
-    mapping.insert('⠊', 'i');
-    mapping.insert('⠚', 'j');
-    mapping.insert('⠅', 'k');
-    mapping.insert('⠇', 'l');
-    mapping.insert('⠍', 'm');
-    mapping.insert('⠝', 'n');
-    mapping.insert('⠕', 'o');
-    mapping.insert('⠏', 'p');
-    mapping.insert('⠟', 'q');
-    mapping.insert('⠗', 'r');
-    mapping.insert('⠎', 's');
-    mapping.insert('⠞', 't');
-    mapping.insert('⠥', 'u');
-    mapping.insert('⠧', 'v');
-    mapping.insert('⠺', 'w');
-    mapping.insert('⠭', 'x');
-    mapping.insert('⠽', 'y');
-    mapping.insert('⠵', 'z');
-    mapping.insert('⠀', ' ');
-
-    text.chars()
-        .map(|c| *mapping.get(&c).unwrap_or(&c))
-        .collect::<String>()
+/// Converts Braille, as Unicode patterns or dot numbers, to text
+/// None if the text is neither.
+fn braille_to_text(text: &str) -> Option<String> {
+    let braille = text.chars().filter(|c| is_braille(*c)).count();
+    let visible = text.chars().filter(|c| !c.is_whitespace()).count();
+    if braille > 0 && braille * 2 >= visible {
+        return Some(read_cells(text));
+    }
+    Some(read_cells(&dot_numbers_to_cells(text)?))
+}
+
+/// Whether the character is in the Unicode Braille patterns block
+fn is_braille(c: char) -> bool {
+    ('\u{2800}'..='\u{28FF}').contains(&c)
+}
+
+/// Turns dot-number notation into Braille patterns, None if it isn't dot numbers
+/// Each cell is its raised dots in order, split by a space or `-`. Words are split
+/// by `/` or by more than one space.
+fn dot_numbers_to_cells(text: &str) -> Option<String> {
+    if !text.contains(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    let mut cells = String::new();
+    let mut cell_count = 0;
+    for (i, word) in text
+        .split('/')
+        .flat_map(|part| part.split("  "))
+        .filter(|word| !word.trim().is_empty())
+        .enumerate()
+    {
+        if i > 0 {
+            cells.push(BLANK);
+        }
+        for group in word.split(|c: char| c == '-' || c.is_whitespace()) {
+            if group.is_empty() {
+                continue;
+            }
+            let mut dots = 0u32;
+            let mut last = 0;
+            for dot in group.chars() {
+                let dot = dot.to_digit(10).filter(|dot| (1..=6).contains(dot))?;
+                // The dots of a cell are written in order, each one once
+                if dot <= last {
+                    return None;
+                }
+                last = dot;
+                dots |= 1 << (dot - 1);
+            }
+            cells.push(char::from_u32(u32::from(BLANK) + dots)?);
+            cell_count += 1;
+        }
+    }
+    (cell_count >= 2).then_some(cells)
+}
+
+/// Reads Braille patterns as Grade 1 Braille
+/// Characters which aren't Braille, and cells with no meaning here, are left unchanged.
+fn read_cells(text: &str) -> String {
+    let mut decoded = String::new();
+    // 0 for lowercase, 1 for a capital letter next and 2 for a capital word
+    let mut capitals = 0;
+    let mut number = false;
+    let mut previous = None;
+    for c in text.chars() {
+        if c == BLANK || c.is_whitespace() {
+            decoded.push(if c == BLANK { ' ' } else { c });
+            capitals = 0;
+            number = false;
+        } else if c == CAPITAL {
+            capitals = if previous == Some(CAPITAL) { 2 } else { 1 };
+        } else if c == NUMBER {
+            number = true;
+        } else if c == LETTER {
+            number = false;
+        } else if let Some(index) = LETTERS.iter().position(|&letter| letter == c) {
+            if number && index < 10 {
+                decoded.push(char::from(b'0' + ((index as u8 + 1) % 10)));
+            } else {
+                number = false;
+                let letter = char::from(b'a' + index as u8);
+                decoded.push(if capitals > 0 {
+                    letter.to_ascii_uppercase()
+                } else {
+                    letter
+                });
+                if capitals == 1 {
+                    capitals = 0;
+                }
+            }
+        } else if let Some((_, mark)) = PUNCTUATION.iter().find(|(cell, _)| *cell == c) {
+            // Commas and full stops can sit inside numbers, anything else ends them
+            if !matches!(mark, ',' | '.') {
+                number = false;
+            }
+            decoded.push(*mark);
+        } else {
+            decoded.push(c);
+        }
+        previous = Some(c);
+    }
+    decoded
 }
 
 #[cfg(test)]
 mod tests {
-    use super::BrailleDecoder;
+    use super::{dot_numbers_to_cells, read_cells, BrailleDecoder};
     use crate::{
         checkers::{
             athena::Athena,
@@ -149,12 +287,12 @@ mod tests {
 
     #[test]
     fn test_braille_handles_invalid_chars() {
+        // Text without Braille isn't decoded, rather than given back unchanged
         let braille_decoder = Decoder::<BrailleDecoder>::new();
         let result = braille_decoder
             .crack("123ABC", &get_athena_checker())
             .unencrypted_text;
-        assert!(result.is_some());
-        assert_eq!(result.unwrap()[0], "123ABC");
+        assert!(result.is_none());
     }
 
     #[test]
@@ -166,4 +304,39 @@ mod tests {
         assert!(result.is_some());
         assert_eq!(result.unwrap()[0], "hello123");
     }
+
+    #[test]
+    fn capitals_numbers_and_punctuation() {
+        assert_eq!(read_cells("⠠⠓⠊⠖"), "Hi!");
+        assert_eq!(read_cells("⠠⠠⠝⠁⠎⠁⠀⠊⠎⠀⠉⠕⠕⠇"), "NASA is cool");
+        assert_eq!(read_cells("⠼⠁⠃⠉⠀⠁⠃⠉"), "123 abc");
+        assert_eq!(read_cells("⠼⠃⠰⠁"), "2a");
+        assert_eq!(read_cells("⠼⠉⠲⠁⠙"), "3.14");
+    }
+
+    #[test]
+    fn dot_numbers_are_decoded() {
+        let braille_decoder = Decoder::<BrailleDecoder>::new();
+        let result = braille_decoder.crack(
+            "125-15-123-123-135  2456-135-1235-123-145",
+            &get_athena_checker(),
+        );
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+    }
+
+    #[test]
+    fn dot_numbers_must_be_in_order() {
+        assert!(dot_numbers_to_cells("21 15").is_none());
+        assert!(dot_numbers_to_cells("17 15").is_none());
+        assert!(dot_numbers_to_cells("125").is_none());
+    }
+
+    #[test]
+    fn braille_handles_panic_if_emoji() {
+        let braille_decoder = Decoder::<BrailleDecoder>::new();
+        let result = braille_decoder
+            .crack("😂", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
 }
@@ -93,6 +93,22 @@ impl Crack for Decoder<BrainfuckInterpreter> {
     fn get_link(&self) -> &str {
         self.link
     }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some((">++++++++[<+++++++++>-]<.>++++[<+++++++>-]<+.+++++++..+++.>>++++++[<+++++++>-]<++.------------.>++++++[<+++++++++>-]<+.<.+++.------.--------.>>>++++[<++++++++>-]<+.", "Hello, World!"))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input must not contain `,` as programs that read input are not supported",
+            "Input must end with `.` and contain at least 5 `.` instructions",
+            "Input must contain at least 20 of the instructions `+` `-` `<` `>` `[` `]`",
+        ]
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,360 @@
+//! Tries likely keys against RC4, DES and repeating-key XOR
+//! Each key is tried with every cipher: RC4 with the key as it is, DES in ECB mode and in
+//! CBC mode with a zero IV with the key padded to 8 bytes with zeros, and XOR repeating the
+//! key. Keys longer than 8 bytes are only tried with RC4 and XOR. The first decryption the
+//! checkers accept is returned, otherwise the most English-like printable decryptions.
+//! Call brute_force_cipher_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
+use crate::checkers::CheckerTypes;
+use crate::config::get_config;
+use crate::decoders::compression::payloads;
+use crate::decoders::crack_results::CrackResult;
+use crate::decoders::frequency_analysis::english_byte_fitness;
+use crate::decoders::interface::{check_string_success, Crack, Decoder};
+use crate::decoders::radix::bytes_to_text;
+
+use super::des::Des;
+use super::rc4::rc4;
+use super::{keys, strip_padding};
+
+use log::{debug, trace};
+use std::time::Duration;
+
+/// The shortest ciphertext we try, shorter ones decrypt to text with too many keys
+const MIN_LENGTH: usize = 4;
+
+/// The longest ciphertext we try, as every key decrypts all of it
+const MAX_LENGTH: usize = 1024;
+
+/// How many printable decryptions we return when none are identified
+const TOP_CANDIDATES: usize = 3;
+
+/// Stop trying keys when less than this is left before the search times out
+const MIN_TIME_TO_TRY: Duration = Duration::from_millis(250);
+
+/// The Brute force cipher decoder, call:
+/// `let brute_force_cipher_decoder = Decoder::<BruteForceCipherDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = brute_force_cipher_decoder.crack(input)` to try likely keys on the ciphertext
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::brute_force::brute_force_cipher_decoder::{BruteForceCipherDecoder};
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_brute_force = Decoder::<BruteForceCipherDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// // "attack at dawn" encrypted with RC4 and the key "secret"
+/// let result = decode_brute_force.crack("8c42a67de1cff6c746ebdfbd8488", &checker);
+/// assert_eq!(result.unencrypted_text.unwrap()[0], "attack at dawn");
+/// assert_eq!(result.key.unwrap(), "secret");
+/// assert!(result.description.contains("RC4"));
+/// ```
+pub struct BruteForceCipherDecoder;
+
+impl Crack for Decoder<BruteForceCipherDecoder> {
+    fn new() -> Decoder<BruteForceCipherDecoder> {
+        Decoder {
+            name: "Brute force ciphers",
+            description: "Tries common keys and the words of the wordlist as keys for RC4, DES and repeating-key XOR, which is how short ciphertexts with guessable keys are broken.",
+            link: "https://en.wikipedia.org/wiki/Brute-force_attack",
+            tags: vec!["brute force", "decryption", "rc4", "des", "xor"],
+            popularity: 0.1,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// This function does the actual decoding
+    /// It reads the ciphertext out of the text as hex, Base64 or raw bytes, and tries
+    /// every key on every reading until the checkers accept a decryption.
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying brute force ciphers with text {:?}", text);
+        brute_force(self, text, payloads(text), checker)
+    }
+    /// Tries the keys on bytes from the decoder before, which couldn't be passed on as text
+    fn crack_bytes(&self, bytes: &[u8], checker: &CheckerTypes) -> Option<CrackResult> {
+        trace!("Trying brute force ciphers with {} bytes", bytes.len());
+        let text = String::from_utf8_lossy(bytes);
+        Some(brute_force(
+            self,
+            &text,
+            vec![("raw bytes", bytes.to_vec())],
+            checker,
+        ))
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some(("8c42a67de1cff6c746ebdfbd8488", "attack at dawn"))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input must be bytes passed on by the decoder before, hex, Base64, or characters below U+0100 including at least one that isn't printable ASCII",
+            "Ciphertext must be between 4 and 1024 bytes",
+            "At most `brute_force_attempts` decryptions are tried, fewer when the search is about to time out",
+            "Decryptions must be valid UTF-8 without control characters",
+        ]
+    }
+}
+
+/// A decrypted candidate, with what it took to get it
+struct Decryption {
+    /// The key which decrypted it
+    key: &'static str,
+    /// The cipher the key was used with
+    cipher: &'static str,
+    /// How the ciphertext was read out of the input
+    reading: &'static str,
+    /// The plaintext
+    plaintext: String,
+}
+
+/// Tries every key on the payloads, `text` being what they were read from
+fn brute_force(
+    decoder: &Decoder<BruteForceCipherDecoder>,
+    text: &str,
+    payloads: Vec<(&'static str, Vec<u8>)>,
+    checker: &CheckerTypes,
+) -> CrackResult {
+    let mut results = CrackResult::new(decoder, text.to_string());
+    let payloads: Vec<_> = payloads
+        .into_iter()
+        .filter(|(_, bytes)| (MIN_LENGTH..=MAX_LENGTH).contains(&bytes.len()))
+        .collect();
+    if payloads.is_empty() {
+        debug!("Brute force ciphers skipped text which isn't a short enough ciphertext");
+        return results;
+    }
+
+    let budget = get_config().brute_force_attempts;
+    let mut attempts = 0;
+    let mut candidates: Vec<Decryption> = Vec::new();
+    'keys: for key in keys() {
+        if decoder
+            .remaining_time()
+            .is_some_and(|left| left < MIN_TIME_TO_TRY)
+        {
+            debug!(
+                "Brute force ciphers are almost out of time, stopping after {attempts} attempts"
+            );
+            results.timed_out = true;
+            break;
+        }
+        for (reading, ciphertext) in &payloads {
+            for (cipher, plaintext) in decryptions(key.as_bytes(), ciphertext) {
+                if attempts >= budget {
+                    debug!("Brute force ciphers used all {budget} attempts");
+                    break 'keys;
+                }
+                attempts += 1;
+                let Some(plaintext) = bytes_to_text(plaintext) else {
+                    continue;
+                };
+                if !check_string_success(&plaintext, text)
+                    || candidates.iter().any(|seen| seen.plaintext == plaintext)
+                {
+                    continue;
+                }
+                let checker_result = checker.check(&plaintext);
+                let decryption = Decryption {
+                    key,
+                    cipher,
+                    reading,
+                    plaintext,
+                };
+                if checker_result.is_identified {
+                    trace!(
+                        "Brute force ciphers identified plaintext with {cipher} and key {key:?}"
+                    );
+                    describe(&mut results, decoder, &decryption);
+                    results.unencrypted_text = Some(vec![decryption.plaintext]);
+                    results.update_checker(&checker_result);
+                    return results;
+                }
+                candidates.push(decryption);
+            }
+        }
+    }
+
+    // Text that isn't identified is still passed on if it looks like it could be English
+    candidates.retain(|candidate| {
+        candidate
+            .plaintext
+            .chars()
+            .all(|c| c.is_ascii_graphic() || c.is_ascii_whitespace())
+    });
+    candidates.sort_by(|a, b| {
+        english_byte_fitness(b.plaintext.as_bytes())
+            .total_cmp(&english_byte_fitness(a.plaintext.as_bytes()))
+    });
+    candidates.truncate(TOP_CANDIDATES);
+    let Some(best) = candidates.first() else {
+        debug!("Brute force ciphers found no key giving text after {attempts} attempts");
+        return results;
+    };
+    describe(&mut results, decoder, best);
+    results.unencrypted_text = Some(
+        candidates
+            .into_iter()
+            .map(|candidate| candidate.plaintext)
+            .collect(),
+    );
+    results
+}
+
+/// Every decryption of the ciphertext with the key, named by cipher
+fn decryptions(key: &[u8], ciphertext: &[u8]) -> Vec<(&'static str, Vec<u8>)> {
+    let mut decryptions = Vec::new();
+    if let Some(plaintext) = rc4(key, ciphertext) {
+        decryptions.push(("RC4", plaintext));
+    }
+    decryptions.push((
+        "XOR",
+        ciphertext
+            .iter()
+            .zip(key.iter().cycle())
+            .map(|(byte, key)| byte ^ key)
+            .collect(),
+    ));
+    if key.len() <= 8 {
+        let mut padded = [0; 8];
+        padded[..key.len()].copy_from_slice(key);
+        let des = Des::new(padded);
+        if let Some(plaintext) = des.decrypt_ecb(ciphertext) {
+            decryptions.push(("DES-ECB", strip_padding(plaintext)));
+        }
+        if ciphertext.len() > 8 {
+            if let Some(plaintext) = des.decrypt_cbc(ciphertext, 0) {
+                decryptions.push(("DES-CBC", strip_padding(plaintext)));
+            }
+        }
+    }
+    decryptions
+}
+
+/// Records the key, cipher and reading of the decryption in the results
+fn describe(
+    results: &mut CrackResult,
+    decoder: &Decoder<BruteForceCipherDecoder>,
+    decryption: &Decryption,
+) {
+    results.key = Some(decryption.key.to_string());
+    results.description = format!(
+        "{} It was decrypted with {}, reading the ciphertext as {}.",
+        decoder.description, decryption.cipher, decryption.reading
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decryptions, BruteForceCipherDecoder};
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::{
+            brute_force::des::Des,
+            interface::{Crack, Decoder},
+        },
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    /// Encrypts the text with DES-ECB and PKCS#7 padding, as hex
+    fn des_ecb_hex(key: &[u8; 8], text: &[u8]) -> String {
+        let des = Des::new(*key);
+        let padding = 8 - text.len() % 8;
+        let mut padded = text.to_vec();
+        padded.extend(std::iter::repeat_n(padding as u8, padding));
+        padded
+            .chunks(8)
+            .map(|block| des.encrypt_block(u64::from_be_bytes(block.try_into().unwrap())))
+            .map(|block| format!("{block:016x}"))
+            .collect()
+    }
+
+    #[test]
+    fn rc4_key_is_found() {
+        let decoder = Decoder::<BruteForceCipherDecoder>::new();
+        let result = decoder.crack("97905462231ec3c1133dcf", &get_athena_checker());
+        assert!(result.success);
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+        assert_eq!(result.key.unwrap(), "password");
+        assert!(result
+            .description
+            .contains("RC4, reading the ciphertext as hex"));
+    }
+
+    #[test]
+    fn des_key_is_found() {
+        let decoder = Decoder::<BruteForceCipherDecoder>::new();
+        let ciphertext = des_ecb_hex(b"letmein\0", b"the quick brown fox jumps");
+        let result = decoder.crack(&ciphertext, &get_athena_checker());
+        assert_eq!(
+            result.unencrypted_text.unwrap()[0],
+            "the quick brown fox jumps"
+        );
+        assert_eq!(result.key.unwrap(), "letmein");
+        assert!(result.description.contains("DES-ECB"));
+    }
+
+    #[test]
+    fn xor_key_is_found() {
+        let decoder = Decoder::<BruteForceCipherDecoder>::new();
+        let result = decoder.crack("12111713061f530417520115040b", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "attack at dawn");
+        assert_eq!(result.key.unwrap(), "secret");
+    }
+
+    #[test]
+    fn long_keys_skip_des() {
+        let ciphers: Vec<&str> = decryptions(b"much too long", &[0; 16])
+            .into_iter()
+            .map(|(cipher, _)| cipher)
+            .collect();
+        assert_eq!(ciphers, ["RC4", "XOR"]);
+    }
+
+    #[test]
+    fn brute_force_rejects_empty_string() {
+        let decoder = Decoder::<BruteForceCipherDecoder>::new();
+        let result = decoder.crack("", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+
+    #[test]
+    fn brute_force_rejects_emoji() {
+        let decoder = Decoder::<BruteForceCipherDecoder>::new();
+        let result = decoder.crack("😂", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+}
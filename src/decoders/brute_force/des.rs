@@ -0,0 +1,99 @@
+//! The DES block cipher
+//! DES encrypts 64 bit blocks with a 56 bit key, carried in 8 bytes whose lowest bits are
+//! parity and ignored. It is long broken, but still turns up in old systems and puzzles.
+//! The cipher and the CBC mode come from the RustCrypto `des` and `cbc` crates, and blocks
+//! are numbers here, big endian.
+
+use ::des::cipher::block_padding::NoPadding;
+use ::des::cipher::KeyInit;
+use ::des::cipher::{BlockCipherDecrypt, BlockCipherEncrypt, BlockModeDecrypt, InnerIvInit};
+
+/// DES with the subkeys of one key worked out
+pub struct Des {
+    /// The cipher keyed with the 8 bytes
+    cipher: ::des::Des,
+}
+
+impl Des {
+    /// Works out the round keys for the 8 byte key
+    pub fn new(key: [u8; 8]) -> Self {
+        Des {
+            cipher: ::des::Des::new(&key.into()),
+        }
+    }
+
+    /// Encrypts one block
+    pub fn encrypt_block(&self, block: u64) -> u64 {
+        let mut block = block.to_be_bytes().into();
+        self.cipher.encrypt_block(&mut block);
+        u64::from_be_bytes(block.into())
+    }
+
+    /// Decrypts one block
+    pub fn decrypt_block(&self, block: u64) -> u64 {
+        let mut block = block.to_be_bytes().into();
+        self.cipher.decrypt_block(&mut block);
+        u64::from_be_bytes(block.into())
+    }
+
+    /// Decrypts the data block by block, None if it isn't a whole number of blocks
+    pub fn decrypt_ecb(&self, data: &[u8]) -> Option<Vec<u8>> {
+        if data.is_empty() || !data.len().is_multiple_of(8) {
+            return None;
+        }
+        let mut plaintext = Vec::with_capacity(data.len());
+        for chunk in data.chunks(8) {
+            let block = u64::from_be_bytes(chunk.try_into().ok()?);
+            plaintext.extend(self.decrypt_block(block).to_be_bytes());
+        }
+        Some(plaintext)
+    }
+
+    /// Decrypts data chained from the IV, None if it isn't a whole number of blocks
+    pub fn decrypt_cbc(&self, data: &[u8], iv: u64) -> Option<Vec<u8>> {
+        if data.is_empty() || !data.len().is_multiple_of(8) {
+            return None;
+        }
+        let mut plaintext = data.to_vec();
+        cbc::Decryptor::inner_iv_init(self.cipher.clone(), &iv.to_be_bytes().into())
+            .decrypt_padded::<NoPadding>(&mut plaintext)
+            .ok()?;
+        Some(plaintext)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Des;
+
+    #[test]
+    fn known_block() {
+        // The worked example most DES walkthroughs use
+        let des = Des::new(0x1334_5779_9BBC_DFF1u64.to_be_bytes());
+        assert_eq!(
+            des.encrypt_block(0x0123_4567_89AB_CDEF),
+            0x85E8_1354_0F0A_B405
+        );
+        assert_eq!(
+            des.decrypt_block(0x85E8_1354_0F0A_B405),
+            0x0123_4567_89AB_CDEF
+        );
+    }
+
+    #[test]
+    fn chained_blocks_decrypt() {
+        let des = Des::new(*b"secret\0\0");
+        let iv = 0x0102_0304_0506_0708;
+        let first = des.encrypt_block(u64::from_be_bytes(*b"attack a") ^ iv);
+        let second = des.encrypt_block(u64::from_be_bytes(*b"t dawn\x02\x02") ^ first);
+        let ciphertext: Vec<u8> = [first, second]
+            .iter()
+            .flat_map(|b| b.to_be_bytes())
+            .collect();
+        assert_eq!(
+            des.decrypt_cbc(&ciphertext, iv).unwrap(),
+            b"attack at dawn\x02\x02"
+        );
+        assert!(des.decrypt_ecb(&ciphertext[1..]).is_none());
+    }
+}
@@ -0,0 +1,97 @@
+//! Decryptors which try a list of likely keys
+//! Modern ciphers can't be broken by looking at the ciphertext, but puzzles and weak
+//! systems often use a guessable key such as `secret`. These decoders try a short list of
+//! common keys and then the words of the wordlist set with `wordlist_path` or `--wordlist`,
+//! and keep what decrypts to text the checkers accept. The ciphertext is read the way the
+//! compression decoders read theirs, from hex, Base64 or raw bytes. The work is bounded by
+//! `brute_force_attempts` and by the time left before the search times out.
+
+/// The brute_force_cipher_decoder module tries keys against RC4, DES and XOR
+pub mod brute_force_cipher_decoder;
+/// The des module decrypts the DES block cipher
+pub mod des;
+/// The rc4 module decrypts the RC4 stream cipher
+pub mod rc4;
+
+use crate::config::get_config;
+
+/// Keys tried before the wordlist's, as they are the ones people reach for first
+pub const COMMON_KEYS: [&str; 24] = [
+    "key",
+    "secret",
+    "password",
+    "pass",
+    "admin",
+    "root",
+    "toor",
+    "test",
+    "flag",
+    "ctf",
+    "letmein",
+    "qwerty",
+    "abc123",
+    "123456",
+    "12345678",
+    "passw0rd",
+    "changeme",
+    "default",
+    "hello",
+    "crypto",
+    "cipher",
+    "ciphey",
+    "ares",
+    "secretkey",
+];
+
+/// The keys to try in order, the common keys then any in the configured wordlist
+pub fn keys() -> impl Iterator<Item = &'static str> {
+    let wordlist = get_config()
+        .wordlist
+        .iter()
+        .flatten()
+        .map(String::as_str)
+        .filter(|key| !COMMON_KEYS.contains(key));
+    COMMON_KEYS.into_iter().chain(wordlist)
+}
+
+/// Removes PKCS#7 padding, or the zero bytes some tools pad with, from decrypted blocks
+/// Plaintexts which don't end in either are returned unchanged.
+pub fn strip_padding(mut plaintext: Vec<u8>) -> Vec<u8> {
+    if let Some(&last) = plaintext.last() {
+        let size = usize::from(last);
+        let padded = (1..=16).contains(&size)
+            && size <= plaintext.len()
+            && plaintext[plaintext.len() - size..]
+                .iter()
+                .all(|&b| b == last);
+        if padded {
+            plaintext.truncate(plaintext.len() - size);
+            return plaintext;
+        }
+    }
+    while plaintext.last() == Some(&0) {
+        plaintext.pop();
+    }
+    plaintext
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{keys, strip_padding, COMMON_KEYS};
+
+    #[test]
+    fn common_keys_come_first() {
+        let keys: Vec<&str> = keys().take(COMMON_KEYS.len()).collect();
+        assert_eq!(keys, COMMON_KEYS);
+    }
+
+    #[test]
+    fn padding_is_stripped() {
+        assert_eq!(
+            strip_padding(b"attack at dawn\x02\x02".to_vec()),
+            b"attack at dawn"
+        );
+        assert_eq!(strip_padding(b"hello\0\0\0".to_vec()), b"hello");
+        assert_eq!(strip_padding(b"hello\x03\x02".to_vec()), b"hello\x03\x02");
+    }
+}
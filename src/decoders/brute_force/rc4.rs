@@ -0,0 +1,31 @@
+//! The RC4 stream cipher
+//! RC4 mixes the key into a permutation of the 256 byte values and then draws a keystream
+//! from it, which is XORed with the data. Encrypting and decrypting are the same thing.
+//! The cipher comes from the RustCrypto `rc4` crate.
+
+use ::rc4::{KeyInit, Rc4, StreamCipher};
+
+/// Encrypts or decrypts the data with the key, None unless the key is 1 to 256 bytes long
+pub fn rc4(key: &[u8], data: &[u8]) -> Option<Vec<u8>> {
+    let mut cipher = Rc4::new_from_slice(key).ok()?;
+    let mut output = data.to_vec();
+    cipher.apply_keystream(&mut output);
+    Some(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::rc4;
+
+    #[test]
+    fn known_keystream() {
+        // The example from Wikipedia's RC4 article
+        let ciphertext = rc4(b"Key", b"Plaintext").unwrap();
+        assert_eq!(
+            ciphertext,
+            [0xbb, 0xf3, 0x16, 0xe8, 0xd9, 0x40, 0xaf, 0x0a, 0xd3]
+        );
+        assert_eq!(rc4(b"Key", &ciphertext).unwrap(), b"Plaintext");
+        assert!(rc4(b"", b"Plaintext").is_none());
+    }
+}
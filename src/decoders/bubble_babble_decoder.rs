@@ -0,0 +1,228 @@
+//! Decode Bubble Babble
+//! Bubble Babble writes bytes as pronounceable groups of five letters joined by dashes,
+//! between an `x` at each end, which is how `ssh-keygen -B` shows key fingerprints. Each
+//! group alternates vowels and consonants and holds two bytes, and the vowels are mixed
+//! with a running checksum, so only real Bubble Babble decodes.
+//! Bytes which aren't text, such as the hashes of fingerprints, are returned as hex.
+//! Call bubble_babble_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::check_string_success;
+use crate::decoders::radix::bytes_to_text;
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::Decoder;
+
+use log::{debug, info, trace};
+
+/// The vowels, which hold the top and bottom two bits of the first byte of each group
+const VOWELS: &[u8; 6] = b"aeiouy";
+
+/// The consonants, which hold four bits each. The seventeenth, `x`, only marks the end of
+/// an even number of bytes.
+const CONSONANTS: &[u8; 17] = b"bcdfghklmnprstvzx";
+
+/// The Bubble Babble decoder, call:
+/// `let bubble_babble_decoder = Decoder::<BubbleBabbleDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = bubble_babble_decoder.crack(input)` to decode Bubble Babble
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::bubble_babble_decoder::{BubbleBabbleDecoder};
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_bubble_babble = Decoder::<BubbleBabbleDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_bubble_babble.crack("xipak-herek-serid-batak-zusuk-sunex", &checker).unencrypted_text;
+/// assert!(result.is_some());
+/// assert_eq!(result.unwrap()[0], "hello world");
+/// ```
+pub struct BubbleBabbleDecoder;
+
+impl Crack for Decoder<BubbleBabbleDecoder> {
+    fn new() -> Decoder<BubbleBabbleDecoder> {
+        Decoder {
+            name: "Bubble Babble",
+            description: "Bubble Babble encodes bytes as pronounceable five letter groups such as xipak-hevix, with a checksum mixed into the vowels. OpenSSH uses it to show key fingerprints in a form that can be read out loud.",
+            link: "https://web.mit.edu/kenta/www/one/bubblebabble/spec/jrtrjwzi/draft-huima-01.txt",
+            tags: vec!["bubble babble", "fingerprint", "decoder"],
+            popularity: 0.2,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying Bubble Babble with text {:?}", text);
+        let mut results = CrackResult::new(self, text.to_string());
+
+        let bytes = match decode_bubble_babble(text.trim()) {
+            Some(bytes) if !bytes.is_empty() => bytes,
+            _ => {
+                debug!(
+                    "Failed to decode Bubble Babble because the input isn't valid Bubble Babble"
+                );
+                return results;
+            }
+        };
+        let decoded_text = bytes_to_text(bytes.clone()).unwrap_or_else(|| encode_hex(&bytes));
+
+        if !check_string_success(&decoded_text, text) {
+            info!(
+                "Failed to decode Bubble Babble because check_string_success returned false on string {}",
+                decoded_text
+            );
+            return results;
+        }
+
+        let checker_result = checker.check(&decoded_text);
+        results.unencrypted_text = Some(vec![decoded_text]);
+
+        results.update_checker(&checker_result);
+
+        results
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some(("xipak-hevix", "hey"))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input must start and end with `x` and be groups of vowels and consonants joined by `-`",
+            "The checksum in the vowels must match",
+            "Bytes which aren't text are returned as hex",
+        ]
+    }
+}
+
+/// Decodes Bubble Babble into its bytes, None if it isn't valid
+fn decode_bubble_babble(text: &str) -> Option<Vec<u8>> {
+    let inner = text.strip_prefix('x')?.strip_suffix('x')?.as_bytes();
+    if inner.len() % 6 != 3 {
+        return None;
+    }
+    let vowel = |c: u8| VOWELS.iter().position(|&v| v == c);
+    let consonant = |c: u8| CONSONANTS.iter().position(|&v| v == c);
+
+    let mut bytes = Vec::with_capacity(inner.len() / 3);
+    let mut seed = 1;
+    for group in inner.chunks(6) {
+        let (a, b, c) = (vowel(group[0])?, consonant(group[1])?, vowel(group[2])?);
+        if group.len() == 3 && b == 16 {
+            // The end of an even number of bytes only holds the checksum
+            if a != seed % 6 || c != seed / 6 {
+                return None;
+            }
+            break;
+        }
+        let high = (a + 6 - seed % 6) % 6;
+        let low = (c + 6 - seed / 6) % 6;
+        if high > 3 || low > 3 || b > 15 {
+            return None;
+        }
+        let first = (high << 6 | b << 2 | low) as u8;
+        bytes.push(first);
+        if group.len() == 3 {
+            break;
+        }
+        let (d, e) = (consonant(group[3])?, consonant(group[5])?);
+        if group[4] != b'-' || d > 15 || e > 15 {
+            return None;
+        }
+        let second = (d << 4 | e) as u8;
+        bytes.push(second);
+        seed = (seed * 5 + usize::from(first) * 7 + usize::from(second)) % 36;
+    }
+    Some(bytes)
+}
+
+/// Encodes bytes as lowercase hex
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_bubble_babble, BubbleBabbleDecoder};
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn specification_examples_decode() {
+        assert_eq!(decode_bubble_babble("xexax").unwrap(), b"");
+        assert_eq!(
+            decode_bubble_babble("xesef-disof-gytuf-katof-movif-baxux").unwrap(),
+            b"1234567890"
+        );
+        assert_eq!(
+            decode_bubble_babble("xigak-nyryk-humil-bosek-sonax").unwrap(),
+            b"Pineapple"
+        );
+    }
+
+    #[test]
+    fn bytes_which_are_not_text_are_hex() {
+        let decoder = Decoder::<BubbleBabbleDecoder>::new();
+        let result = decoder.crack("xolim-lymuf-gukob-gaket-tuxix", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "9e876134d90499dd");
+    }
+
+    #[test]
+    fn bad_checksum_is_rejected() {
+        assert!(decode_bubble_babble("xigak-nyryk-humil-bosek-sonux").is_none());
+        assert!(decode_bubble_babble("xexex").is_none());
+    }
+
+    #[test]
+    fn bubble_babble_rejects_empty_string() {
+        let decoder = Decoder::<BubbleBabbleDecoder>::new();
+        let result = decoder.crack("", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+
+    #[test]
+    fn bubble_babble_rejects_emoji() {
+        let decoder = Decoder::<BubbleBabbleDecoder>::new();
+        let result = decoder.crack("😂", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+}
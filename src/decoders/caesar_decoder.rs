@@ -8,6 +8,7 @@ use crate::checkers::CheckerTypes;
 use crate::decoders::interface::check_string_success;
 use gibberish_or_not::Sensitivity;
 
+use super::alphabet::map_letters;
 use super::crack_results::CrackResult;
 use super::interface::Crack;
 use super::interface::Decoder;
@@ -30,8 +31,7 @@ use log::{info, trace};
 ///
 /// let result = decode_caesar.crack("uryyb guvf vf ybat grkg", &checker).unencrypted_text;
 /// assert!(result.is_some());
-/// // If it succeeds, the 0th element is the plaintext else it'll contain 25 elements
-/// // of unsuccessfully decoded text
+/// // It always contains all 25 shifts, and if it succeeds the 0th element is the plaintext
 /// assert_eq!(result.unwrap()[0], "hello this is long text");
 /// ```
 pub struct CaesarDecoder;
@@ -74,7 +74,11 @@ impl Crack for Decoder<CaesarDecoder> {
             // If checkers return true, exit early with the correct result
             if checker_result.is_identified {
                 trace!("Found a match with caesar shift {}", shift);
-                results.unencrypted_text = Some(vec![borrowed_decoded_text.to_string()]);
+                // Return every shift so other checkers can still weigh in, with the match first
+                decoded_strings.extend(((shift + 1)..=25).map(|rest| caesar(text, rest)));
+                decoded_strings.rotate_left(shift as usize - 1);
+                results.unencrypted_text = Some(decoded_strings);
+                results.key = Some(shift.to_string());
                 results.update_checker(&checker_result);
                 return results;
             }
@@ -98,22 +102,26 @@ impl Crack for Decoder<CaesarDecoder> {
     fn get_link(&self) -> &str {
         self.link
     }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some(("uryyb guvf vf ybat grkg", "hello this is long text"))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Tries every shift from 1 to 25",
+            "Decoded text must differ from the input",
+        ]
+    }
 }
 
 /// Caesar cipher to rotate cipher text by shift and return an owned String.
 fn caesar(cipher: &str, shift: u8) -> String {
-    cipher
-        .chars()
-        .map(|c| {
-            if c.is_ascii_alphabetic() {
-                let first = if c.is_ascii_lowercase() { b'a' } else { b'A' };
-                // modulo the distance to keep character range
-                (first + (c as u8 + shift - first) % 26) as char
-            } else {
-                c
-            }
-        })
-        .collect()
+    map_letters(cipher, |index| index + shift)
 }
 
 #[cfg(test)]
@@ -158,6 +166,18 @@ mod tests {
         assert_eq!(result.unencrypted_text.unwrap()[0], "attack");
     }
 
+    #[test]
+    fn successful_decoding_returns_every_shift() {
+        let caesar_decoder = Decoder::<CaesarDecoder>::new();
+        let result = caesar_decoder.crack("fyyfhp", &get_athena_checker());
+        assert_eq!(result.key.as_deref(), Some("21"));
+        let decoded = result.unencrypted_text.unwrap();
+        assert_eq!(decoded.len(), 25);
+        assert_eq!(decoded[0], "attack");
+        assert_eq!(decoded[1], caesar("fyyfhp", 22));
+        assert_eq!(decoded[24], caesar("fyyfhp", 20));
+    }
+
     #[test]
     fn successful_decoding_one_step_forward() {
         let caesar_decoder = Decoder::<CaesarDecoder>::new();
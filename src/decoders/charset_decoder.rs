@@ -0,0 +1,497 @@
+//! Decodes text written in a character set other than UTF-8
+//! Windows tools often write UTF-16, mainframes write EBCDIC, and old DOS and Windows
+//! programs write their own code pages. A byte order mark says which Unicode form is
+//! used. Without one, UTF-16 and UTF-32 are recognised by where the zero bytes fall,
+//! which is how ASCII looks in them. The code pages are tried when the bytes read as
+//! mostly plain text in them. Several readings can fit, so each one is passed on.
+//! Bytes which aren't valid UTF-8 reach this decoder through `crack_bytes`. Text has its
+//! characters taken as bytes when they are all below U+0100 and one is a control
+//! character, as the zero bytes of UTF-16 are.
+//! Call charset_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::check_string_success;
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::Decoder;
+use super::radix::{bytes_to_text, chars_as_bytes};
+
+use log::{debug, trace};
+
+/// What the EBCDIC code page 037 bytes from 0x40 to 0xFF stand for.
+/// The bytes below are control characters, of which only the whitespace is read.
+const EBCDIC_037: &str = concat!(
+    " \u{a0}âäàáãåçñ¢.<(+|",
+    "&éêëèíîïìß!$*);¬",
+    "-/ÂÄÀÁÃÅÇÑ¦,%_>?",
+    "øÉÊËÈÍÎÏÌ`:#@'=\"",
+    "Øabcdefghi«»ðýþ±",
+    "°jklmnopqrªºæ¸Æ¤",
+    "µ~stuvwxyz¡¿ÐÝÞ®",
+    "^£¥·©§¶¼½¾[]¯¨´×",
+    "{ABCDEFGHI\u{ad}ôöòóõ",
+    "}JKLMNOPQR¹ûüùúÿ",
+    "\\÷STUVWXYZ²ÔÖÒÓÕ",
+    "0123456789³ÛÜÙÚ\u{9f}",
+);
+
+/// What the DOS code page 437 bytes from 0x80 to 0xFF stand for
+const CP437_HIGH: &str = concat!(
+    "ÇüéâäàåçêëèïîìÄÅ",
+    "ÉæÆôöòûùÿÖÜ¢£¥₧ƒ",
+    "áíóúñÑªº¿⌐¬½¼¡«»",
+    "░▒▓│┤╡╢╖╕╣║╗╝╜╛┐",
+    "└┴┬├─┼╞╟╚╔╩╦╠═╬╧",
+    "╨╤╥╙╘╒╓╫╪┘┌█▄▌▐▀",
+    "αßΓπΣσµτΦΘΩδ∞φε∩",
+    "≡±≥≤⌠⌡÷≈°∙·√ⁿ²■\u{a0}",
+);
+
+/// What the Windows-1252 bytes from 0x80 to 0x9F stand for, `\0` where nothing is assigned.
+/// The bytes above are the same as in Latin-1.
+const WINDOWS_1252_HIGH: &str = concat!("€\0‚ƒ„…†‡ˆ‰Š‹Œ\0Ž\0", "\0‘’“”•–—˜™š›œ\0žŸ",);
+
+/// The Charset decoder, call:
+/// `let charset_decoder = Decoder::<CharsetDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = charset_decoder.crack(input)` to decode text from another character set
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::charset_decoder::{CharsetDecoder};
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_charset = Decoder::<CharsetDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_charset.crack("h\0e\0l\0l\0o\0 \0w\0o\0r\0l\0d\0", &checker);
+/// assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+/// assert!(result.description.contains("UTF-16LE"));
+/// ```
+pub struct CharsetDecoder;
+
+impl Crack for Decoder<CharsetDecoder> {
+    fn new() -> Decoder<CharsetDecoder> {
+        Decoder {
+            name: "Charset",
+            description: "Text can be written in character sets other than UTF-8, such as the UTF-16 Windows tools write, UTF-32, the EBCDIC of IBM mainframes, and the Windows-1252 and DOS 437 code pages. A byte order mark gives the Unicode form away, otherwise it shows in where the zero bytes fall.",
+            link: "https://en.wikipedia.org/wiki/Character_encoding",
+            tags: vec!["charset", "decoder", "unicode"],
+            popularity: 0.3,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// This function does the actual decoding
+    /// It reads the characters as bytes when they look like bytes which aren't UTF-8,
+    /// and tries every character set which fits them.
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying charsets with text {:?}", text);
+        if let Some(rest) = text.strip_prefix('\u{feff}') {
+            return decode(
+                self,
+                text,
+                vec![("UTF-8 with a byte order mark", rest)],
+                checker,
+            );
+        }
+        let Some(bytes) = text_as_bytes(text) else {
+            debug!("Failed to decode charsets because the text doesn't look like bytes");
+            return CrackResult::new(self, text.to_string());
+        };
+        let candidates = charset_candidates(&bytes);
+        let candidates = candidates
+            .iter()
+            .map(|(charset, decoded)| (*charset, decoded.as_str()))
+            .collect();
+        decode(self, text, candidates, checker)
+    }
+    /// Decodes bytes from the decoder before, which couldn't be passed on as UTF-8 text
+    fn crack_bytes(&self, bytes: &[u8], checker: &CheckerTypes) -> Option<CrackResult> {
+        trace!("Trying charsets with {} bytes", bytes.len());
+        let text = String::from_utf8_lossy(bytes);
+        let candidates = charset_candidates(bytes);
+        let candidates = candidates
+            .iter()
+            .map(|(charset, decoded)| (*charset, decoded.as_str()))
+            .collect();
+        Some(decode(self, &text, candidates, checker))
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some(("h\0e\0l\0l\0o\0 \0w\0o\0r\0l\0d\0", "hello world"))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input must be bytes which aren't UTF-8, text starting with a byte order mark, or characters below U+0100 including a control character",
+            "UTF-16 and UTF-32 need a byte order mark, or zero bytes in the high half of at least half the characters",
+            "EBCDIC must read as at least three quarters letters, digits and spaces",
+            "Windows-1252 and code page 437 are only tried when at most a quarter of the bytes are above 0x7F",
+            "Decoded text must not contain control characters other than whitespace",
+        ]
+    }
+}
+
+/// Checks the candidates and returns them, the first one the checker identifies put first
+fn decode(
+    decoder: &Decoder<CharsetDecoder>,
+    text: &str,
+    candidates: Vec<(&str, &str)>,
+    checker: &CheckerTypes,
+) -> CrackResult {
+    let mut results = CrackResult::new(decoder, text.to_string());
+
+    let mut candidates: Vec<(&str, &str)> = candidates
+        .into_iter()
+        .filter(|(_, decoded)| check_string_success(decoded, text))
+        .collect();
+    if candidates.is_empty() {
+        debug!("Failed to decode charsets because no character set fits the bytes");
+        return results;
+    }
+
+    let mut checker_result = None;
+    for index in 0..candidates.len() {
+        let result = checker.check(candidates[index].1);
+        if result.is_identified {
+            candidates.swap(0, index);
+            checker_result = Some(result);
+            break;
+        }
+    }
+
+    results.description = format!("{} Read as {}.", decoder.description, candidates[0].0);
+    results.unencrypted_text = Some(
+        candidates
+            .into_iter()
+            .map(|(_, decoded)| decoded.to_string())
+            .collect(),
+    );
+    if let Some(checker_result) = checker_result {
+        results.update_checker(&checker_result);
+    }
+
+    results
+}
+
+/// Takes each character as the byte with its value, when the text looks like bytes.
+/// That is every character being below U+0100, with at least one control character
+/// other than whitespace, like the zero bytes in UTF-16. Text without one is already readable.
+fn text_as_bytes(text: &str) -> Option<Vec<u8>> {
+    if !text.chars().any(|c| c.is_control() && !c.is_whitespace()) {
+        return None;
+    }
+    chars_as_bytes(text)
+}
+
+/// Every character set the bytes can be read as, with the name of each.
+/// A byte order mark settles which Unicode form it is, so nothing else is tried then.
+pub fn charset_candidates(bytes: &[u8]) -> Vec<(&'static str, String)> {
+    if let Some((charset, decoded)) = with_byte_order_mark(bytes) {
+        return readable(decoded)
+            .map(|decoded| vec![(charset, decoded)])
+            .unwrap_or_default();
+    }
+
+    let mut candidates = Vec::new();
+    let unicode: [(&str, Option<String>); 4] = [
+        (
+            "UTF-32LE",
+            (looks_like(bytes, 4, 3) && at_least_half_zero(bytes, 2, 4))
+                .then(|| utf32(bytes, u32::from_le_bytes))
+                .flatten(),
+        ),
+        (
+            "UTF-32BE",
+            (looks_like(bytes, 4, 0) && at_least_half_zero(bytes, 1, 4))
+                .then(|| utf32(bytes, u32::from_be_bytes))
+                .flatten(),
+        ),
+        (
+            "UTF-16LE",
+            at_least_half_zero(bytes, 1, 2)
+                .then(|| utf16(bytes, u16::from_le_bytes))
+                .flatten(),
+        ),
+        (
+            "UTF-16BE",
+            at_least_half_zero(bytes, 0, 2)
+                .then(|| utf16(bytes, u16::from_be_bytes))
+                .flatten(),
+        ),
+    ];
+    for (charset, decoded) in unicode {
+        if let Some(decoded) = decoded.and_then(readable) {
+            candidates.push((charset, decoded));
+        }
+    }
+
+    if let Some(decoded) = ebcdic(bytes).and_then(readable) {
+        let plain = decoded
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric() || *c == ' ')
+            .count();
+        if plain * 4 >= decoded.chars().count() * 3 {
+            candidates.push(("EBCDIC (code page 037)", decoded));
+        }
+    }
+
+    let high = bytes.iter().filter(|&&byte| byte > 0x7F).count();
+    if high > 0 && high * 4 <= bytes.len() {
+        if let Some(decoded) = windows_1252(bytes).and_then(readable) {
+            candidates.push(("Windows-1252", decoded));
+        }
+        if let Some(decoded) = readable(cp437(bytes)) {
+            candidates.push(("code page 437", decoded));
+        }
+    }
+    candidates
+}
+
+/// Decodes bytes which start with a byte order mark, with the name of the form it marks
+fn with_byte_order_mark(bytes: &[u8]) -> Option<(&'static str, String)> {
+    if let Some(rest) = bytes.strip_prefix(&[0xFF, 0xFE, 0x00, 0x00]) {
+        return Some(("UTF-32LE", utf32(rest, u32::from_le_bytes)?));
+    }
+    if let Some(rest) = bytes.strip_prefix(&[0x00, 0x00, 0xFE, 0xFF]) {
+        return Some(("UTF-32BE", utf32(rest, u32::from_be_bytes)?));
+    }
+    if let Some(rest) = bytes.strip_prefix(&[0xFF, 0xFE]) {
+        return Some(("UTF-16LE", utf16(rest, u16::from_le_bytes)?));
+    }
+    if let Some(rest) = bytes.strip_prefix(&[0xFE, 0xFF]) {
+        return Some(("UTF-16BE", utf16(rest, u16::from_be_bytes)?));
+    }
+    if let Some(rest) = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]) {
+        return Some((
+            "UTF-8 with a byte order mark",
+            String::from_utf8(rest.to_vec()).ok()?,
+        ));
+    }
+    None
+}
+
+/// Whether the bytes split into units of `width` bytes, each with a zero byte at `offset`
+fn looks_like(bytes: &[u8], width: usize, offset: usize) -> bool {
+    !bytes.is_empty()
+        && bytes.len().is_multiple_of(width)
+        && bytes.chunks(width).all(|unit| unit[offset] == 0)
+}
+
+/// Whether at least half of the units of `width` bytes have a zero byte at `offset`
+fn at_least_half_zero(bytes: &[u8], offset: usize, width: usize) -> bool {
+    if bytes.len() < width * 2 || !bytes.len().is_multiple_of(width) {
+        return false;
+    }
+    let zeros = bytes.chunks(width).filter(|unit| unit[offset] == 0).count();
+    zeros * 2 >= bytes.len() / width
+}
+
+/// Decodes UTF-16, None if the length is odd or a surrogate is unpaired
+fn utf16(bytes: &[u8], unit: fn([u8; 2]) -> u16) -> Option<String> {
+    if !bytes.len().is_multiple_of(2) {
+        return None;
+    }
+    let units = bytes.chunks(2).map(|pair| unit([pair[0], pair[1]]));
+    char::decode_utf16(units)
+        .collect::<Result<String, _>>()
+        .ok()
+}
+
+/// Decodes UTF-32, None if the length isn't a multiple of four or a value isn't a character
+fn utf32(bytes: &[u8], unit: fn([u8; 4]) -> u32) -> Option<String> {
+    if !bytes.len().is_multiple_of(4) {
+        return None;
+    }
+    bytes
+        .chunks(4)
+        .map(|quad| char::from_u32(unit([quad[0], quad[1], quad[2], quad[3]])))
+        .collect()
+}
+
+/// Decodes EBCDIC code page 037, None if a control character other than whitespace is used
+fn ebcdic(bytes: &[u8]) -> Option<String> {
+    let table: Vec<char> = EBCDIC_037.chars().collect();
+    bytes
+        .iter()
+        .map(|&byte| match byte {
+            0x05 => Some('\t'),
+            0x0D => Some('\r'),
+            0x15 | 0x25 => Some('\n'),
+            0x40..=0xFF => Some(table[usize::from(byte - 0x40)]),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Decodes Windows-1252, None if a byte with nothing assigned to it is used
+fn windows_1252(bytes: &[u8]) -> Option<String> {
+    let table: Vec<char> = WINDOWS_1252_HIGH.chars().collect();
+    bytes
+        .iter()
+        .map(|&byte| match byte {
+            0x80..=0x9F => Some(table[usize::from(byte - 0x80)]).filter(|&c| c != '\0'),
+            _ => Some(char::from(byte)),
+        })
+        .collect()
+}
+
+/// Decodes DOS code page 437, where every byte stands for a character
+fn cp437(bytes: &[u8]) -> String {
+    let table: Vec<char> = CP437_HIGH.chars().collect();
+    bytes
+        .iter()
+        .map(|&byte| match byte {
+            0x80..=0xFF => table[usize::from(byte - 0x80)],
+            _ => char::from(byte),
+        })
+        .collect()
+}
+
+/// The text, None if it is empty or has control characters other than whitespace
+fn readable(text: String) -> Option<String> {
+    if text.trim().is_empty() {
+        return None;
+    }
+    bytes_to_text(text.into_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{charset_candidates, CharsetDecoder, CP437_HIGH, EBCDIC_037, WINDOWS_1252_HIGH};
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn tables_cover_every_byte() {
+        assert_eq!(EBCDIC_037.chars().count(), 0xC0);
+        assert_eq!(CP437_HIGH.chars().count(), 0x80);
+        assert_eq!(WINDOWS_1252_HIGH.chars().count(), 0x20);
+    }
+
+    #[test]
+    fn successful_decoding() {
+        let charset_decoder = Decoder::<CharsetDecoder>::new();
+        let result =
+            charset_decoder.crack("h\0e\0l\0l\0o\0 \0w\0o\0r\0l\0d\0", &get_athena_checker());
+        assert!(result.success);
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+    }
+
+    #[test]
+    fn byte_order_marks_pick_the_form() {
+        assert_eq!(
+            charset_candidates(b"\xfe\xff\0h\0i"),
+            vec![("UTF-16BE", "hi".to_string())]
+        );
+        assert_eq!(
+            charset_candidates(b"\xff\xfe\0\0h\0\0\0i\0\0\0"),
+            vec![("UTF-32LE", "hi".to_string())]
+        );
+    }
+
+    #[test]
+    fn utf32_without_a_byte_order_mark() {
+        let bytes = b"\0\0\0h\0\0\0e\0\0\0y";
+        assert_eq!(
+            charset_candidates(bytes),
+            vec![("UTF-32BE", "hey".to_string())]
+        );
+    }
+
+    #[test]
+    fn utf16_bytes_are_decoded() {
+        let charset_decoder = Decoder::<CharsetDecoder>::new();
+        let bytes = b"\xff\xfeh\0e\0l\0l\0o\0 \0w\0o\0r\0l\0d\0";
+        let result = charset_decoder
+            .crack_bytes(bytes, &get_athena_checker())
+            .unwrap();
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+        assert!(result.description.contains("UTF-16LE"));
+    }
+
+    #[test]
+    fn ebcdic_is_decoded() {
+        let bytes = [
+            0x88, 0x85, 0x93, 0x93, 0x96, 0x40, 0xA6, 0x96, 0x99, 0x93, 0x84,
+        ];
+        assert!(charset_candidates(&bytes)
+            .contains(&("EBCDIC (code page 037)", "hello world".to_string())));
+    }
+
+    #[test]
+    fn code_pages_are_decoded() {
+        let found = charset_candidates(b"caf\x82 con leche");
+        assert!(found.contains(&("code page 437", "café con leche".to_string())));
+        let found = charset_candidates(b"it\x92s here");
+        assert!(found.contains(&("Windows-1252", "it’s here".to_string())));
+    }
+
+    #[test]
+    fn mostly_high_bytes_are_not_read_as_code_pages() {
+        assert!(charset_candidates(&[0xCB, 0x48, 0xCD, 0xC9, 0xC9, 0x57]).is_empty());
+    }
+
+    #[test]
+    fn readable_text_is_not_decoded() {
+        let charset_decoder = Decoder::<CharsetDecoder>::new();
+        let result = charset_decoder
+            .crack("café con leche", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn charset_handle_panic_if_empty_string() {
+        let charset_decoder = Decoder::<CharsetDecoder>::new();
+        let result = charset_decoder
+            .crack("", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn charset_handle_panic_if_emoji() {
+        let charset_decoder = Decoder::<CharsetDecoder>::new();
+        let result = charset_decoder
+            .crack("😂", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+}
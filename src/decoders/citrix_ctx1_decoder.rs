@@ -83,11 +83,30 @@ impl Crack for Decoder<CitrixCTX1Decoder> {
     fn get_link(&self) -> &str {
         self.link
     }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some((
+            "MNGIKIANMEGBKIANMHGCOHECJADFPPFKINCIOBEEIFCA",
+            "hello world",
+        ))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input length must be a multiple of 4",
+            "Every character must be at or above `A` in ASCII",
+            "Decoded bytes must be valid UTF-8",
+        ]
+    }
 }
 
 /// Decodes Citrix CTX1
 fn decode_citrix_ctx1(text: &str) -> Result<String, Error> {
-    if text.len() % 4 != 0 {
+    if !text.len().is_multiple_of(4) {
         return Err(Error::InvalidLength);
     }
 
@@ -0,0 +1,501 @@
+//! ADFGVX and ADFGX cipher cracker
+//! Both ciphers replace every letter with the row and column of its cell in a keyed square,
+//! written with the letters ADFGVX (6x6, letters and digits) or ADFGX (5x5, I and J sharing),
+//! then put the result through a columnar transposition.
+//! The transposition is undone first. Once the columns are in the right order every pair
+//! of symbols stands for one letter, so the pairs have the lopsided frequencies of English
+//! letters, while pairs across the wrong columns are closer to random. The column order is
+//! built up one column at a time, keeping the partial orders whose complete pairs have the
+//! highest Index of Coincidence. What is left is a substitution of pairs for letters, which
+//! is solved by simulated annealing on quadgram scores within the `substitution_iterations`
+//! budget in the config, or the time the search has left.
+
+use crate::checkers::CheckerTypes;
+use crate::config::get_config;
+use crate::decoders::alphabet::{index_to_letter, ALPHABET_LENGTH};
+use crate::decoders::columnar_transposition_decoder::{rotations, untranspose};
+use crate::decoders::crack_results::CrackResult;
+use crate::decoders::frequency_analysis::index_of_coincidence;
+use crate::decoders::interface::{check_string_success, Crack, Decoder};
+use crate::decoders::substitution_solver::{anneal, symbol_frequency_key, MIN_TIME_TO_ANNEAL};
+
+use super::fitness;
+
+use log::{debug, info, trace};
+
+/// The symbols of the 6x6 square, the first naming row or column 0
+const ADFGVX: &[u8; 6] = b"ADFGVX";
+
+/// The symbols of the 5x5 square
+const ADFGX: &[u8; 5] = b"ADFGX";
+
+/// The shortest transposition key tried
+const MIN_KEY_LENGTH: usize = 2;
+
+/// The longest transposition key tried
+const MAX_KEY_LENGTH: usize = 10;
+
+/// How many partial column orders are kept at each step
+const BEAM_WIDTH: usize = 128;
+
+/// How many column orders are kept for each key length once the beam finishes
+const KEYS_PER_LENGTH: usize = 2;
+
+/// How many of the best column orders over every key length get a substitution solve
+const KEYS_TO_SOLVE: usize = 3;
+
+/// How many times the square is annealed, each search starting from the last one's best key
+const SEARCHES: usize = 2;
+
+/// How many candidate plaintexts we return
+const TOP_CANDIDATES: usize = 3;
+
+/// The fewest letters, so pairs of symbols, we will try to crack.
+/// The substitution can't be solved reliably with less.
+const MIN_LETTERS: usize = 60;
+
+/// The ADFGVX decoder, call:
+/// `let adfgvx_decoder = Decoder::<AdfgvxDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = adfgvx_decoder.crack(input)` to crack an ADFGVX or ADFGX cipher
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::classical::adfgvx_decoder::AdfgvxDecoder;
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let crack_adfgvx = Decoder::<AdfgvxDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = crack_adfgvx.crack(
+///     "AXGGD XFFAG GFAXV XFXFG AVAFD AAFAV ADFVA FAFVX GGADF VGAGG GAXAD FFFAD GAAFD FDDGA GVXXD VDFGF DAFFD VXGFD DVDAF VFGFV AFFAA DFDFG FFAAX GAGAX XDVDD AFVGF AAVAF FGVAG VDFXG DFGAG VVGFV FGDVA FGGDD GGFFX FGFAF DFAFV AFAFD XGGAV VXGAG DVFAX GFFXF FFFAD XAAVF ADDVD VDVDX FAVFF GDAXX FFFFX DADDV ADDDA GADVD VVVGV FGDVD AVGXA VFVFX FFDFA GDFGG AXFDV VFGDA FFDGA XXAFV DGVFX FGAXD FAXVG VDFGF VGAAG DXAFX FVD",
+///     &checker,
+/// );
+/// assert_eq!(
+///     result.unencrypted_text.unwrap()[0],
+///     "SENDMOREAMMUNITIONTOTHEEASTERNTRENCHESBEFORENIGHTFALLTHEENEMYHASBEENMOVINGGUNSINTOTHEWOODSBEHINDTHEVILLAGEANDWEEXPECTANATTACKATFIRSTLIGHTTOMORROWMORNINGIFTHEWEATHERHOLDS"
+/// );
+/// ```
+pub struct AdfgvxDecoder;
+
+impl Crack for Decoder<AdfgvxDecoder> {
+    fn new() -> Decoder<AdfgvxDecoder> {
+        Decoder {
+            name: "ADFGVX",
+            description: "A fractionating cipher which writes every letter as the row and column of its cell in a keyed square, using the letters ADFGVX (or ADFGX for a 5x5 square), then scrambles the result with a columnar transposition. The column order is found with the Index of Coincidence of the pairs it makes, and the square with simulated annealing on English quadgram scores. Digits and spacing in the plaintext can't be recovered, so the output is capital letters only.",
+            link: "https://en.wikipedia.org/wiki/ADFGVX_cipher",
+            tags: vec!["adfgvx", "classical", "cipher", "fractionation", "transposition"],
+            popularity: 0.2,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying ADFGVX with text {:?}", text);
+        let mut results = CrackResult::new(self, text.to_string());
+
+        let Some((symbols, side)) = parse_symbols(text) else {
+            debug!("Failed to crack ADFGVX because the text isn't only ADFGVX letters");
+            return results;
+        };
+        if !symbols.len().is_multiple_of(2) || symbols.len() / 2 < MIN_LETTERS {
+            info!("Failed to crack ADFGVX because the text doesn't have an even number of symbols, or too few");
+            return results;
+        }
+
+        // Every column needs at least two symbols for the order to mean anything
+        let max_key_length = MAX_KEY_LENGTH.min(symbols.len() / 2);
+        let mut keys: Vec<(Vec<usize>, f64)> = (MIN_KEY_LENGTH..=max_key_length)
+            .flat_map(|key_length| best_keys(&symbols, key_length, side))
+            .collect();
+        keys.sort_by(|a, b| b.1.total_cmp(&a.1));
+        keys.truncate(KEYS_TO_SOLVE);
+
+        // A decryption which starts a pair into the first row makes the same pairs as the
+        // real one, so it solves to the same square. Every rotation of the key is decrypted
+        // with that square and quadgrams, or the checker, pick between them.
+        let iterations = get_config().substitution_iterations;
+        let out_of_time = || {
+            self.remaining_time()
+                .is_some_and(|left| left < MIN_TIME_TO_ANNEAL)
+        };
+        let mut candidates: Vec<(Vec<usize>, Vec<u8>, f64)> = keys
+            .into_iter()
+            .flat_map(|(key, _)| {
+                let (square, timed_out) = solve_square(
+                    &pairs(&untranspose(&symbols, &key), side),
+                    side,
+                    iterations,
+                    &out_of_time,
+                );
+                results.timed_out |= timed_out;
+                rotations(&key)
+                    .into_iter()
+                    .map(|rotated| {
+                        let letters = decrypt(&symbols, &rotated, side, &square);
+                        let score = fitness(&letters);
+                        (rotated, letters, score)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        candidates.sort_by(|a, b| b.2.total_cmp(&a.2));
+        candidates.truncate(TOP_CANDIDATES);
+
+        let mut candidates: Vec<(Vec<usize>, String)> = candidates
+            .into_iter()
+            .map(|(key, letters, _)| {
+                let plaintext: String = letters
+                    .into_iter()
+                    .map(|letter| index_to_letter(letter, true))
+                    .collect();
+                (key, plaintext)
+            })
+            .filter(|(_, plaintext)| check_string_success(plaintext, text))
+            .collect();
+        if candidates.is_empty() {
+            info!("Failed ADFGVX decoding validation");
+            return results;
+        }
+
+        let mut checker_result = None;
+        for index in 0..candidates.len() {
+            let result = checker.check(&candidates[index].1);
+            if result.is_identified {
+                candidates.swap(0, index);
+                checker_result = Some(result);
+                break;
+            }
+        }
+
+        let key = format_key(&candidates[0].0);
+        debug!("ADFGVX recovered column order {}", key);
+        results.description = format!("{} Recovered column order: {}", self.description, key);
+        results.key = Some(key);
+        results.unencrypted_text = Some(
+            candidates
+                .into_iter()
+                .map(|(_, plaintext)| plaintext)
+                .collect(),
+        );
+        if let Some(checker_result) = checker_result {
+            results.update_checker(&checker_result);
+        }
+
+        results
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some((
+            "AXGGD XFFAG GFAXV XFXFG AVAFD AAFAV ADFVA FAFVX GGADF VGAGG GAXAD FFFAD GAAFD FDDGA GVXXD VDFGF DAFFD VXGFD DVDAF VFGFV AFFAA DFDFG FFAAX GAGAX XDVDD AFVGF AAVAF FGVAG VDFXG DFGAG VVGFV FGDVA FGGDD GGFFX FGFAF DFAFV AFAFD XGGAV VXGAG DVFAX GFFXF FFFAD XAAVF ADDVD VDVDX FAVFF GDAXX FFFFX DADDV ADDDA GADVD VVVGV FGDVD AVGXA VFVFX FFDFA GDFGG AXFDV VFGDA FFDGA XXAFV DGVFX FGAXD FAXVG VDFGF VGAAG DXAFX FVD",
+            "SENDMOREAMMUNITIONTOTHEEASTERNTRENCHESBEFORENIGHTFALLTHEENEMYHASBEENMOVINGGUNSINTOTHEWOODSBEHINDTHEVILLAGEANDWEEXPECTANATTACKATFIRSTLIGHTTOMORROWMORNINGIFTHEWEATHERHOLDS",
+        ))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input must only contain the letters ADFGVX and whitespace",
+            "Input must have an even number of letters, and at least 120",
+            "Tries transposition keys from 2 to 10 columns long",
+            "Solves the square for the 3 column orders with the most English-like pairs",
+        ]
+    }
+}
+
+/// The number of every symbol of the text and the side of the square they come from,
+/// None if the text has anything besides ADFGVX letters and whitespace
+/// ADFGX is used unless there is a V.
+fn parse_symbols(text: &str) -> Option<(Vec<u8>, u8)> {
+    let letters: Vec<u8> = text
+        .bytes()
+        .filter(|b| !b.is_ascii_whitespace())
+        .map(|b| b.to_ascii_uppercase())
+        .collect();
+    let alphabet: &[u8] = if letters.contains(&b'V') {
+        ADFGVX
+    } else {
+        ADFGX
+    };
+    let symbols: Option<Vec<u8>> = letters
+        .iter()
+        .map(|letter| {
+            alphabet
+                .iter()
+                .position(|symbol| symbol == letter)
+                .map(|index| index as u8)
+        })
+        .collect();
+    Some((symbols?, alphabet.len() as u8))
+}
+
+/// Joins every two symbols into the number of the cell they name, rows first
+fn pairs(symbols: &[u8], side: u8) -> Vec<u8> {
+    symbols
+        .chunks_exact(2)
+        .map(|pair| pair[0] * side + pair[1])
+        .collect()
+}
+
+/// Finds the column orders whose pairs look most like English letters for this key length
+///
+/// `key[column]` is the position the column was read out in, as for columnar
+/// transposition. The key is built one column at a time, and every partial key is scored
+/// on the pairs which both fall in the columns chosen so far.
+fn best_keys(symbols: &[u8], key_length: usize, side: u8) -> Vec<(Vec<usize>, f64)> {
+    let mut beam: Vec<Vec<usize>> = vec![vec![]];
+    for column in 0..key_length {
+        let mut extended: Vec<(Vec<usize>, f64)> = beam
+            .iter()
+            .flat_map(|partial| {
+                (0..key_length)
+                    .filter(|position| !partial.contains(position))
+                    .map(|position| {
+                        let mut key = partial.clone();
+                        key.push(position);
+                        let score = pair_score(symbols, &key, key_length, column + 1, side);
+                        (key, score)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        extended.sort_by(|a, b| b.1.total_cmp(&a.1));
+        extended.truncate(BEAM_WIDTH);
+        beam = extended.into_iter().map(|(key, _)| key).collect();
+    }
+    beam.into_iter()
+        .take(KEYS_PER_LENGTH)
+        .map(|key| {
+            let key = if key_length.is_multiple_of(2) {
+                order_column_pairs(symbols, &key, side)
+            } else {
+                key
+            };
+            let score = pair_score(symbols, &key, key_length, key_length, side);
+            (key, score)
+        })
+        .collect()
+}
+
+/// The Index of Coincidence of the pairs whose symbols are both in the first `columns` columns
+/// The columns still to be chosen are filled in in their natural order, which only
+/// affects where the chosen columns start when the last row is incomplete.
+fn pair_score(
+    symbols: &[u8],
+    partial: &[usize],
+    key_length: usize,
+    columns: usize,
+    side: u8,
+) -> f64 {
+    let mut key = partial.to_vec();
+    key.extend((0..key_length).filter(|position| !partial.contains(position)));
+    let untransposed = untranspose(symbols, &key);
+    let mut counts = vec![0; usize::from(side * side)];
+    for (index, pair) in untransposed.chunks_exact(2).enumerate() {
+        let first_column = (2 * index) % key_length;
+        let second_column = (2 * index + 1) % key_length;
+        if first_column < columns && second_column < columns {
+            counts[usize::from(pair[0] * side + pair[1])] += 1;
+        }
+    }
+    index_of_coincidence(&counts)
+}
+
+/// Puts the pairs of columns in the order whose letters repeat the most bigrams
+/// With an even number of columns every pair of symbols sits in the same two neighbouring
+/// columns, so the pairs can't tell which order those two-column blocks go in. Repeated
+/// bigrams can, and the substitution of pairs for letters doesn't change how often they repeat.
+fn order_column_pairs(symbols: &[u8], key: &[usize], side: u8) -> Vec<usize> {
+    let blocks: Vec<&[usize]> = key.chunks(2).collect();
+    let cells = usize::from(side * side);
+    let bigram_score = |key: &[usize]| {
+        let letters = pairs(&untranspose(symbols, key), side);
+        let mut counts = vec![0; cells * cells];
+        for bigram in letters.windows(2) {
+            counts[usize::from(bigram[0]) * cells + usize::from(bigram[1])] += 1;
+        }
+        index_of_coincidence(&counts)
+    };
+    permutations(blocks.len())
+        .into_iter()
+        .map(|order| {
+            order
+                .into_iter()
+                .flat_map(|block| blocks[block].iter().copied())
+                .collect::<Vec<usize>>()
+        })
+        .max_by(|a, b| bigram_score(a).total_cmp(&bigram_score(b)))
+        .unwrap_or_else(|| key.to_vec())
+}
+
+/// Every order of the numbers from 0 to `n`
+fn permutations(n: usize) -> Vec<Vec<usize>> {
+    if n == 0 {
+        return vec![vec![]];
+    }
+    permutations(n - 1)
+        .into_iter()
+        .flat_map(|shorter| {
+            (0..n).map(move |position| {
+                let mut order = shorter.clone();
+                order.insert(position, n - 1);
+                order
+            })
+        })
+        .collect()
+}
+
+/// Finds the letter each cell of the square stands for by annealing, and whether
+/// `out_of_time` cut the search short
+/// Only the cells which turn up take part, so that fewer swaps are wasted on cells no
+/// pair uses. Cells which never turn up are read as A.
+fn solve_square(
+    pairs: &[u8],
+    side: u8,
+    iterations: u32,
+    out_of_time: &impl Fn() -> bool,
+) -> (Vec<u8>, bool) {
+    let mut used: Vec<u8> = pairs.to_vec();
+    used.sort_unstable();
+    used.dedup();
+    let symbols: Vec<u8> = pairs
+        .iter()
+        .map(|pair| used.binary_search(pair).expect("every pair is used") as u8)
+        .collect();
+    // Spare symbols which never turn up hold the letters the used ones don't have yet
+    let mut counts = vec![0; used.len().max(usize::from(ALPHABET_LENGTH))];
+    for &symbol in &symbols {
+        counts[usize::from(symbol)] += 1;
+    }
+    // The first search can settle on a key with a few pairs of letters swapped round,
+    // starting again from it usually shakes them loose
+    let mut key = symbol_frequency_key(&counts);
+    let mut timed_out = false;
+    for _ in 0..SEARCHES {
+        (key, timed_out) = anneal(&symbols, key, iterations, out_of_time);
+        if timed_out {
+            break;
+        }
+    }
+
+    let mut square = vec![0; usize::from(side * side)];
+    for (symbol, &cell) in used.iter().enumerate() {
+        square[usize::from(cell)] = key[symbol];
+    }
+    (square, timed_out)
+}
+
+/// Undoes the transposition with the key and looks every pair up in the solved square
+fn decrypt(symbols: &[u8], key: &[usize], side: u8, square: &[u8]) -> Vec<u8> {
+    pairs(&untranspose(symbols, key), side)
+        .into_iter()
+        .map(|pair| square[usize::from(pair)])
+        .collect()
+}
+
+/// Writes the key the way it is usually given, the read out position of each column from 1
+fn format_key(key: &[usize]) -> String {
+    key.iter()
+        .map(|position| (position + 1).to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checkers::{
+        athena::Athena,
+        checker_type::{Check, Checker},
+        CheckerTypes,
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn successful_decoding() {
+        let decoder = Decoder::<AdfgvxDecoder>::new();
+        let (input, expected) = decoder.get_example().unwrap();
+        let result = decoder.crack(input, &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], expected);
+        // The key PRIVACY
+        assert_eq!(result.key.as_deref(), Some("4 5 3 6 1 2 7"));
+    }
+
+    #[test]
+    fn adfgx_is_cracked_too() {
+        let decoder = Decoder::<AdfgvxDecoder>::new();
+        let result = decoder.crack(
+            "XFFAD DXGFD FXFGF DAXFD XFDGF FXFGD DGGFF DAFGF DGFGG GFXGG FDDFF XDGXX FXAGD GFFGG GFXDD GFFGD FAGDG DGGXX AGXXG DGDXX DAFFF DGFDX ADGFD FGDFD XXDFX AXGFA GAFDX XGXAA FXXAG GXFFG FGGAF GXFAF FDFFG FAGFD XFFGF DXXAG ADGDX FGGDF ADXFA XXGFX GFGFG FGFDG DXGDF GFXXD AXDXF AXGXD GDFXA XAGGD XGXAD GDGFD XXDFA XAGXF AGXGF DXAFD ADFGF FADFG AADGA XXAGF GXAAF FGAGX XXFXD XXDGF FAGAD FGFDA XXGFF FXGAA AAGDG FXDFD AXXXG AXA",
+            &get_athena_checker(),
+        );
+        assert_eq!(
+            result.unencrypted_text.unwrap()[0],
+            "THECONVOYWILLLEAVETHENORTHERNDEPOTBEFOREDAWNANDTRAVELALONGTHECOASTROADUNTILITREACHESTHEBRIDGEHOLDTHEBRIDGEUNTILTHESECONDCOMPANYARRIVESANDDONOTLETANYONECROSSWITHOUTTHEPASSWORD"
+        );
+        // The key CARGO
+        assert_eq!(result.key.as_deref(), Some("2 1 5 3 4"));
+    }
+
+    #[test]
+    fn v_picks_the_bigger_square() {
+        assert_eq!(parse_symbols("ad fg x"), Some((vec![0, 1, 2, 3, 4], 5)));
+        assert_eq!(parse_symbols("ADFGVX"), Some((vec![0, 1, 2, 3, 4, 5], 6)));
+        assert_eq!(parse_symbols("ADFGXQ"), None);
+    }
+
+    #[test]
+    fn pairs_are_rows_then_columns() {
+        assert_eq!(pairs(&[0, 1, 5, 5], 6), vec![1, 35]);
+    }
+
+    #[test]
+    fn every_permutation_is_listed_once() {
+        let mut orders = permutations(4);
+        assert_eq!(orders.len(), 24);
+        orders.sort();
+        orders.dedup();
+        assert_eq!(orders.len(), 24);
+    }
+
+    #[test]
+    fn short_text_is_not_decoded() {
+        let decoder = Decoder::<AdfgvxDecoder>::new();
+        let result = decoder.crack("ADFGV XADFG VXADF", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+
+    #[test]
+    fn adfgvx_handles_panic_if_emoji() {
+        let decoder = Decoder::<AdfgvxDecoder>::new();
+        let result = decoder.crack("😂", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+}
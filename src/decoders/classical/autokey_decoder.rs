@@ -0,0 +1,317 @@
+//! Autokey cipher decoder with automated primer detection
+//! Autokey is Vigenère where the key is a short primer followed by the plaintext itself,
+//! so the key never repeats and Kasiski examination finds nothing. It does mean that once a
+//! letter of the primer is known, every letter which it keyed can be decrypted, and those
+//! letters key the next ones. So each primer letter decides a whole chain of the plaintext
+//! on its own, and can be found by frequency analysis of that chain.
+//! The primer found for each length is then polished with quadgram scores.
+//! Uses Medium sensitivity for gibberish detection as the default.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::alphabet::{index_to_letter, ALPHABET_LENGTH};
+use crate::decoders::crack_results::CrackResult;
+use crate::decoders::frequency_analysis::{chi_squared, letter_counts, letter_indices};
+use crate::decoders::interface::{check_string_success, Crack, Decoder};
+
+use super::{fitness, restore_layout};
+
+use gibberish_or_not::Sensitivity;
+use log::{debug, info, trace};
+
+/// The longest primer we try
+const MAX_PRIMER_LENGTH: usize = 12;
+
+/// Each chain of letters keyed by one primer letter needs this many letters to analyse
+const MIN_CHAIN_LENGTH: usize = 4;
+
+/// How many candidate plaintexts we return
+const TOP_CANDIDATES: usize = 3;
+
+/// The autokey decoder, call:
+/// `let autokey_decoder = Decoder::<AutokeyDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = autokey_decoder.crack(input)` to decode an autokey cipher
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::classical::autokey_decoder::AutokeyDecoder;
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_autokey = Decoder::<AutokeyDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_autokey.crack(
+///     "Jbi qhdcnt gxikim qcczq sf Wueoymf, ki jh aejwsz xo svix dm hti jkogm zxjgg or fhsc sob jozx ayrjg ere cwikk hjkmm plnha. Fitht c jprb okccisx ulg wcfh hmj ppr zoyjk jej bx wltpejwahxap kgtq zsgu htpx vn qxid.",
+///     &checker,
+/// );
+/// assert_eq!(
+///     result.unencrypted_text.unwrap()[0],
+///     "The museum closes early on Sundays, so we agreed to meet at the front steps by noon and walk along the river after lunch. Bring a coat because the wind off the water can be surprisingly cold this time of year."
+/// );
+/// ```
+pub struct AutokeyDecoder;
+
+impl Crack for Decoder<AutokeyDecoder> {
+    fn new() -> Decoder<AutokeyDecoder> {
+        Decoder {
+            name: "Autokey",
+            description: "A Vigenère cipher whose key is a short primer followed by the plaintext itself, so the key never repeats. Each primer letter is found by frequency analysis of the letters it decides, then the primer is refined with English quadgram scores. Uses Medium sensitivity for gibberish detection.",
+            link: "https://en.wikipedia.org/wiki/Autokey_cipher",
+            tags: vec!["substitution", "classical", "cipher", "decryption"],
+            popularity: 0.3,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// Recovers a primer for every length and returns the most English-like plaintexts, best first.
+    /// The plaintext the checker identifies is moved to the front and its primer is
+    /// recorded in the description.
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying autokey with text {:?}", text);
+        let mut results = CrackResult::new(self, text.to_string());
+
+        let letters = letter_indices(text);
+        let max_primer_length = MAX_PRIMER_LENGTH.min(letters.len() / MIN_CHAIN_LENGTH);
+        if max_primer_length == 0 {
+            debug!("Too few letters for autokey");
+            return results;
+        }
+
+        let mut candidates: Vec<(Vec<u8>, f64)> = Vec::new();
+        for primer_length in 1..=max_primer_length {
+            let primer = refine_primer(&letters, find_primer(&letters, primer_length));
+            // A primer made of a shorter primer repeated was usually found already
+            if candidates.iter().any(|(candidate, _)| *candidate == primer) {
+                continue;
+            }
+            let score = fitness(&decrypt(&letters, &primer));
+            candidates.push((primer, score));
+        }
+        candidates.sort_by(|a, b| b.1.total_cmp(&a.1));
+        candidates.truncate(TOP_CANDIDATES);
+
+        let mut candidates: Vec<(String, String)> = candidates
+            .into_iter()
+            .map(|(primer, _)| {
+                let plaintext = restore_layout(text, &decrypt(&letters, &primer));
+                (format_primer(&primer), plaintext)
+            })
+            .filter(|(_, plaintext)| check_string_success(plaintext, text))
+            .collect();
+        if candidates.is_empty() {
+            info!("Failed autokey decoding validation");
+            return results;
+        }
+
+        let checker_with_sensitivity = checker.with_sensitivity(Sensitivity::Medium);
+        let mut checker_result = None;
+        for index in 0..candidates.len() {
+            let result = checker_with_sensitivity.check(&candidates[index].1);
+            if result.is_identified {
+                candidates.swap(0, index);
+                checker_result = Some(result);
+                break;
+            }
+        }
+
+        let primer = candidates[0].0.clone();
+        debug!("Autokey recovered primer {}", primer);
+        results.description = format!("{} Recovered primer: {}", self.description, primer);
+        results.key = Some(primer);
+        results.unencrypted_text = Some(
+            candidates
+                .into_iter()
+                .map(|(_, plaintext)| plaintext)
+                .collect(),
+        );
+        if let Some(checker_result) = checker_result {
+            results.update_checker(&checker_result);
+        }
+
+        results
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some((
+            "Jbi qhdcnt gxikim qcczq sf Wueoymf, ki jh aejwsz xo svix dm hti jkogm zxjgg or fhsc sob jozx ayrjg ere cwikk hjkmm plnha. Fitht c jprb okccisx ulg wcfh hmj ppr zoyjk jej bx wltpejwahxap kgtq zsgu htpx vn qxid.",
+            "The museum closes early on Sundays, so we agreed to meet at the front steps by noon and walk along the river after lunch. Bring a coat because the wind off the water can be surprisingly cold this time of year.",
+        ))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input must contain at least 4 ASCII letters",
+            "Tries every primer length from 1 to 12, with at least 4 letters per primer letter",
+            "Decoded text must differ from the input",
+        ]
+    }
+}
+
+/// Decrypts the letters with the primer, each plaintext letter keying the one a primer length later
+fn decrypt(letters: &[u8], primer: &[u8]) -> Vec<u8> {
+    let mut plaintext: Vec<u8> = Vec::with_capacity(letters.len());
+    for (i, &letter) in letters.iter().enumerate() {
+        let key = if i < primer.len() {
+            primer[i]
+        } else {
+            plaintext[i - primer.len()]
+        };
+        plaintext.push((letter + ALPHABET_LENGTH - key) % ALPHABET_LENGTH);
+    }
+    plaintext
+}
+
+/// Decrypts only the letters which the primer letter at `offset` decides
+/// These are every `primer_length`th letter from `offset`, each keyed by the one before it.
+fn decrypt_chain(
+    letters: &[u8],
+    offset: usize,
+    primer_length: usize,
+    primer_letter: u8,
+) -> Vec<u8> {
+    let mut key = primer_letter;
+    letters
+        .iter()
+        .skip(offset)
+        .step_by(primer_length)
+        .map(|&letter| {
+            key = (letter + ALPHABET_LENGTH - key) % ALPHABET_LENGTH;
+            key
+        })
+        .collect()
+}
+
+/// Finds each primer letter by picking the one whose chain has the most English letter frequencies
+fn find_primer(letters: &[u8], primer_length: usize) -> Vec<u8> {
+    (0..primer_length)
+        .map(|offset| {
+            (0..ALPHABET_LENGTH)
+                .min_by(|&a, &b| {
+                    let chain_score = |primer_letter| {
+                        chi_squared(&letter_counts(&decrypt_chain(
+                            letters,
+                            offset,
+                            primer_length,
+                            primer_letter,
+                        )))
+                    };
+                    chain_score(a).total_cmp(&chain_score(b))
+                })
+                .unwrap_or(0)
+        })
+        .collect()
+}
+
+/// Improves the primer one letter at a time by quadgram score of the whole plaintext
+/// Short chains can fool frequency analysis, but a wrong primer letter garbles every
+/// letter of its chain, which quadgrams notice. Stops once no single letter helps.
+fn refine_primer(letters: &[u8], mut primer: Vec<u8>) -> Vec<u8> {
+    let mut best_score = fitness(&decrypt(letters, &primer));
+    let mut improved = true;
+    while improved {
+        improved = false;
+        for position in 0..primer.len() {
+            let original = primer[position];
+            for letter in 0..ALPHABET_LENGTH {
+                primer[position] = letter;
+                let score = fitness(&decrypt(letters, &primer));
+                if score > best_score {
+                    best_score = score;
+                    improved = true;
+                } else {
+                    primer[position] = original;
+                }
+            }
+        }
+    }
+    primer
+}
+
+/// Writes the primer in capital letters
+fn format_primer(primer: &[u8]) -> String {
+    primer
+        .iter()
+        .map(|&letter| index_to_letter(letter, true))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checkers::{
+        athena::Athena,
+        checker_type::{Check, Checker},
+        CheckerTypes,
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn successful_decoding() {
+        let decoder = Decoder::<AutokeyDecoder>::new();
+        let (input, expected) = decoder.get_example().unwrap();
+        let result = decoder.crack(input, &get_athena_checker());
+        assert!(result.success);
+        assert_eq!(result.unencrypted_text.unwrap()[0], expected);
+        assert_eq!(result.key.as_deref(), Some("QUEENLY"));
+        assert!(result.description.ends_with("Recovered primer: QUEENLY"));
+    }
+
+    #[test]
+    fn plaintext_keys_the_letters_after_the_primer() {
+        // "attackatdawn" with the primer QUEENLY, from Wikipedia
+        let ciphertext = letter_indices("QNXEPVYTWTWP");
+        let primer = letter_indices("QUEENLY");
+        assert_eq!(
+            format_primer(&decrypt(&ciphertext, &primer)),
+            "ATTACKATDAWN"
+        );
+    }
+
+    #[test]
+    fn chains_match_the_full_decryption() {
+        let ciphertext = letter_indices("QNXEPVYTWTWP");
+        let plaintext = decrypt(&ciphertext, &letter_indices("QUEENLY"));
+        let chain = decrypt_chain(&ciphertext, 1, 7, b'U' - b'A');
+        assert_eq!(chain, vec![plaintext[1], plaintext[8]]);
+    }
+
+    #[test]
+    fn short_text_is_not_decoded() {
+        let decoder = Decoder::<AutokeyDecoder>::new();
+        let result = decoder.crack("abc", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+
+    #[test]
+    fn autokey_handles_panic_if_emoji() {
+        let decoder = Decoder::<AutokeyDecoder>::new();
+        let result = decoder.crack("😂", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+}
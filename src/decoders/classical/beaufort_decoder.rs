@@ -0,0 +1,263 @@
+//! Beaufort cipher decoder with automated key detection
+//! Beaufort subtracts the plaintext from the key instead of adding the key to the plaintext,
+//! so every column of the ciphertext is English reversed and shifted. Key lengths are found
+//! with Kasiski examination and the Index of Coincidence just like Vigenère, and each
+//! key letter by frequency analysis of its column.
+//! Uses Medium sensitivity for gibberish detection as the default.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::alphabet::ALPHABET_LENGTH;
+use crate::decoders::crack_results::CrackResult;
+use crate::decoders::frequency_analysis::{
+    column, estimate_key_lengths, letter_indices, ranked_shifts, shortest_period,
+};
+use crate::decoders::interface::{check_string_success, Crack, Decoder};
+
+use super::fitness;
+
+use gibberish_or_not::Sensitivity;
+use log::{debug, info, trace};
+
+/// The longest key we try, longer keys need far more ciphertext to break
+const MAX_KEY_LENGTH: usize = 20;
+
+/// How many of the most likely key lengths we recover a key for
+const KEY_LENGTHS_TO_TRY: usize = 5;
+
+/// How many candidate plaintexts we return
+const TOP_CANDIDATES: usize = 3;
+
+/// The Beaufort decoder, call:
+/// `let beaufort_decoder = Decoder::<BeaufortDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = beaufort_decoder.crack(input)` to decode a Beaufort cipher
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::classical::beaufort_decoder::BeaufortDecoder;
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_beaufort = Decoder::<BeaufortDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_beaufort.crack(
+///     "Wsyqq fmwpzbl hgy gohww oskewk vzw zonrw wkmy bdjdjj knbasvy, hbo hgy pcnpc oc jawvv gxnak zqgmhjz eavn uvd yxsjj pvawjj. Ukjugbtuai rondo enpeka sa koggp gcwj ricydz, rnk vd iawjjdl nalv tj ytjq gq eaby lhjpj jak dfjpp srjbye vkw rvtdn jzov.",
+///     &checker,
+/// );
+/// assert_eq!(
+///     result.unencrypted_text.unwrap()[0],
+///     "Every morning the baker opened his small shop before sunrise, and the smell of fresh bread drifted down the quiet street. Neighbours would wander in still half asleep, and he greeted each of them by name while the ovens warmed the whole room."
+/// );
+/// ```
+pub struct BeaufortDecoder;
+
+impl Crack for Decoder<BeaufortDecoder> {
+    fn new() -> Decoder<BeaufortDecoder> {
+        Decoder {
+            name: "Beaufort",
+            description: "A polyalphabetic cipher like Vigenère, except each letter is replaced by the key letter minus the plaintext letter, which makes encrypting and decrypting the same operation. The key length is found with Kasiski examination and the Index of Coincidence, then each key letter with frequency analysis. Uses Medium sensitivity for gibberish detection.",
+            link: "https://en.wikipedia.org/wiki/Beaufort_cipher",
+            tags: vec!["substitution", "classical", "cipher", "decryption"],
+            popularity: 0.3,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// Recovers a key for each of the most likely key lengths and returns the most
+    /// English-like plaintexts, best first.
+    /// The plaintext the checker identifies is moved to the front and its key is
+    /// recorded in the description.
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying Beaufort with text {:?}", text);
+        let mut results = CrackResult::new(self, text.to_string());
+
+        let letters = letter_indices(text);
+        if letters.is_empty() {
+            debug!("No letters found in Beaufort input");
+            return results;
+        }
+
+        // Different key lengths often recover the same key repeated,
+        // so only keep one candidate per distinct key
+        let mut candidates: Vec<(String, String)> = Vec::new();
+        for key_length in estimate_key_lengths(&letters, MAX_KEY_LENGTH, KEY_LENGTHS_TO_TRY) {
+            let key = shortest_period(&find_key(&letters, key_length));
+            if candidates
+                .iter()
+                .any(|(candidate_key, _)| *candidate_key == key)
+            {
+                continue;
+            }
+            let plaintext = decrypt(text, &key);
+            if check_string_success(&plaintext, text) {
+                candidates.push((key, plaintext));
+            }
+        }
+
+        if candidates.is_empty() {
+            info!("Failed Beaufort decoding validation");
+            return results;
+        }
+
+        // Longer keys fit the letter frequencies of each column more closely even when they
+        // are wrong, so candidates are ranked by quadgrams, which a wrong key letter garbles
+        let fitness_of = |plaintext: &str| fitness(&letter_indices(plaintext));
+        candidates.sort_by(|a, b| fitness_of(&b.1).total_cmp(&fitness_of(&a.1)));
+        candidates.truncate(TOP_CANDIDATES);
+
+        let checker_with_sensitivity = checker.with_sensitivity(Sensitivity::Medium);
+        let mut checker_result = None;
+        for index in 0..candidates.len() {
+            let result = checker_with_sensitivity.check(&candidates[index].1);
+            if result.is_identified {
+                candidates.swap(0, index);
+                checker_result = Some(result);
+                break;
+            }
+        }
+
+        let key = candidates[0].0.clone();
+        debug!("Beaufort recovered key {}", key);
+        results.description = format!("{} Recovered key: {}", self.description, key);
+        results.key = Some(key);
+        results.unencrypted_text = Some(
+            candidates
+                .into_iter()
+                .map(|(_, plaintext)| plaintext)
+                .collect(),
+        );
+        if let Some(checker_result) = checker_result {
+            results.update_checker(&checker_result);
+        }
+
+        results
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some((
+            "Wsyqq fmwpzbl hgy gohww oskewk vzw zonrw wkmy bdjdjj knbasvy, hbo hgy pcnpc oc jawvv gxnak zqgmhjz eavn uvd yxsjj pvawjj. Ukjugbtuai rondo enpeka sa koggp gcwj ricydz, rnk vd iawjjdl nalv tj ytjq gq eaby lhjpj jak dfjpp srjbye vkw rvtdn jzov.",
+            "Every morning the baker opened his small shop before sunrise, and the smell of fresh bread drifted down the quiet street. Neighbours would wander in still half asleep, and he greeted each of them by name while the ovens warmed the whole room.",
+        ))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input must contain at least one ASCII letter",
+            "Tries the five most likely key lengths from 1 to 20",
+            "Decoded text must differ from the input",
+        ]
+    }
+}
+
+/// Finds the key letter for each column with frequency analysis
+/// Negating a column turns `key - plaintext` into `plaintext - key`, an ordinary Caesar
+/// shift by minus the key letter, so the best Caesar shift gives the key letter.
+fn find_key(letters: &[u8], key_length: usize) -> String {
+    (0..key_length)
+        .map(|i| {
+            let negated: Vec<u8> = column(letters, i, key_length)
+                .into_iter()
+                .map(|letter| (ALPHABET_LENGTH - letter) % ALPHABET_LENGTH)
+                .collect();
+            let shift = ranked_shifts(&negated)[0].0;
+            (b'A' + (ALPHABET_LENGTH - shift) % ALPHABET_LENGTH) as char
+        })
+        .collect()
+}
+
+/// Decrypts the text with the key, which is the same as encrypting it
+/// Only letters are decrypted and advance the key, so the original formatting is kept
+fn decrypt(text: &str, key: &str) -> String {
+    let key_bytes: Vec<u8> = key.bytes().collect();
+    let mut result = String::with_capacity(text.len());
+    let mut key_idx = 0;
+
+    for c in text.chars() {
+        if c.is_ascii_alphabetic() {
+            let key_letter = key_bytes[key_idx % key_bytes.len()] - b'A';
+            let base = if c.is_ascii_uppercase() { b'A' } else { b'a' };
+            let pos = (c as u8) - base;
+            result.push((base + (key_letter + ALPHABET_LENGTH - pos) % ALPHABET_LENGTH) as char);
+            key_idx += 1;
+        } else {
+            result.push(c);
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checkers::{
+        athena::Athena,
+        checker_type::{Check, Checker},
+        CheckerTypes,
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn successful_decoding() {
+        let decoder = Decoder::<BeaufortDecoder>::new();
+        let (input, expected) = decoder.get_example().unwrap();
+        let result = decoder.crack(input, &get_athena_checker());
+        assert!(result.success);
+        assert_eq!(result.unencrypted_text.unwrap()[0], expected);
+        assert_eq!(result.key.as_deref(), Some("ANCHOR"));
+    }
+
+    #[test]
+    fn decrypting_twice_gives_the_text_back() {
+        let text = "Defend the east wall!";
+        assert_eq!(decrypt(&decrypt(text, "FORT"), "FORT"), text);
+    }
+
+    #[test]
+    fn key_letter_minus_plaintext() {
+        // Key letter D (3) minus plaintext letter A (0) is D
+        assert_eq!(decrypt("a", "D"), "d");
+        // Key letter A (0) minus plaintext letter B (1) wraps round to Z
+        assert_eq!(decrypt("B", "A"), "Z");
+    }
+
+    #[test]
+    fn non_alphabetic_input_is_not_decoded() {
+        let decoder = Decoder::<BeaufortDecoder>::new();
+        let result = decoder.crack("12345!@#$%", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+
+    #[test]
+    fn beaufort_handles_panic_if_emoji() {
+        let decoder = Decoder::<BeaufortDecoder>::new();
+        let result = decoder.crack("😂", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+}
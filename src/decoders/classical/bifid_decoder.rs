@@ -0,0 +1,274 @@
+//! Bifid cipher decoder with automated period detection
+//! Bifid looks every letter up in a Polybius square, writes the rows of a block of letters
+//! on one line and their columns on the next, then reads the line of numbers back out in
+//! pairs as new letters. The block length is the period. This decoder uses the usual
+//! square, the alphabet in rows of five without J, so only the period has to be found:
+//! every period up to 20 is tried, as well as the whole text as one block, and the
+//! decryptions are ranked by quadgram score.
+//! Uses Medium sensitivity for gibberish detection as the default.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::crack_results::CrackResult;
+use crate::decoders::frequency_analysis::letter_indices;
+use crate::decoders::interface::{check_string_success, Crack, Decoder};
+
+use super::{fitness, polybius_cell, polybius_letter, restore_layout};
+
+use gibberish_or_not::Sensitivity;
+use log::{debug, info, trace};
+
+/// The longest period we try besides the whole text
+const MAX_PERIOD: usize = 20;
+
+/// The fewest letters we will try to decode, quadgram scores are meaningless below this
+const MIN_LETTERS: usize = 8;
+
+/// The number of J, which the usual Polybius square leaves out
+const J: u8 = 9;
+
+/// How many candidate plaintexts we return
+const TOP_CANDIDATES: usize = 3;
+
+/// The Bifid decoder, call:
+/// `let bifid_decoder = Decoder::<BifidDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = bifid_decoder.crack(input)` to decode a Bifid cipher
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::classical::bifid_decoder::BifidDecoder;
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_bifid = Decoder::<BifidDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_bifid.crack(
+///     "Rds tporo nlbiay hp dtoe ifg nsxqc lqhq dy ofb qsvdwnncc, tlo ee cgueomr dhi pivra qwl ricao plivr cly pqd sldos wrbbbt. Kasdsmk hivdf fysyt fcrnz ym gftohw iolwix rglrc qyp qhfm dpzr cmoaasg.",
+///     &checker,
+/// );
+/// assert_eq!(
+///     result.unencrypted_text.unwrap()[0],
+///     "The storm rolled in over the hills late in the afternoon, and by evening the river had risen above the old stone bridge. Farmers moved their sheep to higher ground while the rain kept falling."
+/// );
+/// ```
+pub struct BifidDecoder;
+
+impl Crack for Decoder<BifidDecoder> {
+    fn new() -> Decoder<BifidDecoder> {
+        Decoder {
+            name: "Bifid",
+            description: "A fractionating cipher which writes down the Polybius square rows and columns of a block of letters and reads them back out in pairs as new letters. This decoder uses the usual 5x5 square without J and tries every period up to 20, ranking the decryptions by English quadgram scores. Uses Medium sensitivity for gibberish detection.",
+            link: "https://en.wikipedia.org/wiki/Bifid_cipher",
+            tags: vec!["bifid", "classical", "cipher", "fractionation", "decryption"],
+            popularity: 0.3,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// Decrypts the text with every period and returns the most English-like plaintexts, best first.
+    /// The plaintext the checker identifies is moved to the front and its period is
+    /// recorded in the description.
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying Bifid with text {:?}", text);
+        let mut results = CrackResult::new(self, text.to_string());
+
+        let letters = letter_indices(text);
+        if letters.len() < MIN_LETTERS || letters.contains(&J) {
+            debug!("Failed to decode Bifid because the text has a J or too few letters");
+            return results;
+        }
+
+        // A period of one leaves the text as it is, and periods past the length of the
+        // text are all the same as the whole text
+        let mut periods: Vec<usize> = (2..=MAX_PERIOD.min(letters.len())).collect();
+        if letters.len() > MAX_PERIOD {
+            periods.push(letters.len());
+        }
+        let mut candidates: Vec<(usize, Vec<u8>, f64)> = periods
+            .into_iter()
+            .map(|period| {
+                let plaintext = decrypt(&letters, period);
+                let score = fitness(&plaintext);
+                (period, plaintext, score)
+            })
+            .collect();
+        candidates.sort_by(|a, b| b.2.total_cmp(&a.2));
+        candidates.truncate(TOP_CANDIDATES);
+
+        let mut candidates: Vec<(usize, String)> = candidates
+            .into_iter()
+            .map(|(period, plaintext, _)| (period, restore_layout(text, &plaintext)))
+            .filter(|(_, plaintext)| check_string_success(plaintext, text))
+            .collect();
+        if candidates.is_empty() {
+            info!("Failed Bifid decoding validation");
+            return results;
+        }
+
+        let checker_with_sensitivity = checker.with_sensitivity(Sensitivity::Medium);
+        let mut checker_result = None;
+        for index in 0..candidates.len() {
+            let result = checker_with_sensitivity.check(&candidates[index].1);
+            if result.is_identified {
+                candidates.swap(0, index);
+                checker_result = Some(result);
+                break;
+            }
+        }
+
+        let period = candidates[0].0;
+        debug!("Bifid recovered period {}", period);
+        results.description = format!("{} Recovered period: {}", self.description, period);
+        results.key = Some(period.to_string());
+        results.unencrypted_text = Some(
+            candidates
+                .into_iter()
+                .map(|(_, plaintext)| plaintext)
+                .collect(),
+        );
+        if let Some(checker_result) = checker_result {
+            results.update_checker(&checker_result);
+        }
+
+        results
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some((
+            "Rds tporo nlbiay hp dtoe ifg nsxqc lqhq dy ofb qsvdwnncc, tlo ee cgueomr dhi pivra qwl ricao plivr cly pqd sldos wrbbbt. Kasdsmk hivdf fysyt fcrnz ym gftohw iolwix rglrc qyp qhfm dpzr cmoaasg.",
+            "The storm rolled in over the hills late in the afternoon, and by evening the river had risen above the old stone bridge. Farmers moved their sheep to higher ground while the rain kept falling.",
+        ))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input must contain at least 8 ASCII letters and no J",
+            "Tries every period from 2 to 20 and the whole text as one block",
+            "Decoded text must differ from the input",
+        ]
+    }
+}
+
+/// Decrypts the letters a block of `period` letters at a time
+/// The cells of a ciphertext block, read in order, are the rows of the plaintext block
+/// followed by its columns.
+fn decrypt(letters: &[u8], period: usize) -> Vec<u8> {
+    letters
+        .chunks(period)
+        .flat_map(|block| {
+            let coordinates: Vec<u8> = block
+                .iter()
+                .flat_map(|&letter| {
+                    let (row, column) = polybius_cell(letter);
+                    [row, column]
+                })
+                .collect();
+            let (rows, columns) = coordinates.split_at(block.len());
+            rows.iter()
+                .zip(columns)
+                .map(|(&row, &column)| polybius_letter(row, column))
+                .collect::<Vec<u8>>()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checkers::{
+        athena::Athena,
+        checker_type::{Check, Checker},
+        CheckerTypes,
+    };
+    use crate::decoders::alphabet::index_to_letter;
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    /// Encrypts the letters a block of `period` letters at a time
+    fn encrypt(letters: &[u8], period: usize) -> Vec<u8> {
+        letters
+            .chunks(period)
+            .flat_map(|block| {
+                let cells: Vec<(u8, u8)> =
+                    block.iter().map(|&letter| polybius_cell(letter)).collect();
+                let coordinates: Vec<u8> = cells
+                    .iter()
+                    .map(|cell| cell.0)
+                    .chain(cells.iter().map(|cell| cell.1))
+                    .collect();
+                coordinates
+                    .chunks(2)
+                    .map(|pair| polybius_letter(pair[0], pair[1]))
+                    .collect::<Vec<u8>>()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn successful_decoding() {
+        let decoder = Decoder::<BifidDecoder>::new();
+        let (input, expected) = decoder.get_example().unwrap();
+        let result = decoder.crack(input, &get_athena_checker());
+        assert!(result.success);
+        assert_eq!(result.unencrypted_text.unwrap()[0], expected);
+        assert_eq!(result.key.as_deref(), Some("7"));
+    }
+
+    #[test]
+    fn decrypt_undoes_encrypt() {
+        let plaintext = letter_indices("fleeatoncewearediscovered");
+        for period in [2, 5, 7, plaintext.len()] {
+            assert_eq!(decrypt(&encrypt(&plaintext, period), period), plaintext);
+        }
+    }
+
+    #[test]
+    fn whole_text_block() {
+        // "FLEEATONCE" as one block with the usual square
+        let decrypted = decrypt(&letter_indices("HADNAAZDSP"), 10);
+        let decrypted: String = decrypted
+            .into_iter()
+            .map(|letter| index_to_letter(letter, true))
+            .collect();
+        assert_eq!(decrypted, "FLEEATONCE");
+    }
+
+    #[test]
+    fn text_with_j_is_not_decoded() {
+        let decoder = Decoder::<BifidDecoder>::new();
+        let result = decoder.crack("Jolly jumping jackdaws", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+
+    #[test]
+    fn bifid_handles_panic_if_emoji() {
+        let decoder = Decoder::<BifidDecoder>::new();
+        let result = decoder.crack("😂", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+}
@@ -0,0 +1,579 @@
+//! Enigma machine cracker for the three-rotor M3 with rotors I to V and reflector B
+//! The plugboard only swaps letters on the way in and out, so with the rotors in the right
+//! place even an unplugged decryption has more repeated letters than random text. So every
+//! rotor order is tried at every start position with the rings at A, and the settings whose
+//! decryption has the highest Index of Coincidence are kept. The ring settings of the right
+//! and middle rotors then decide when the rotors to their left turn over, and they are
+//! found the same way. Last, plugboard pairs are added one at a time by quadgram score.
+//! How many rotor orders are tried is the `enigma_rotor_combinations` setting in the config,
+//! and the search stops early with the best settings so far when the search is out of time.
+
+use crate::checkers::CheckerTypes;
+use crate::config::get_config;
+use crate::decoders::alphabet::{index_to_letter, ALPHABET_LENGTH};
+use crate::decoders::crack_results::CrackResult;
+use crate::decoders::frequency_analysis::{index_of_coincidence, letter_counts, letter_indices};
+use crate::decoders::interface::{check_string_success, Crack, Decoder};
+
+use super::fitness;
+
+use log::{debug, info, trace};
+use std::time::Duration;
+
+/// The wiring of rotors I to V, the letter each contact is wired to
+const ROTOR_WIRINGS: [&[u8; 26]; 5] = [
+    b"EKMFLGDQVZNTOWYHXUSPAIBRCJ",
+    b"AJDKSIRUXBLHWTMCQGZNPYFVOE",
+    b"BDFHJLCPRTXVZNYEIWGAKMUSQO",
+    b"ESOVPZJAYQUIRHXLNFTGKDCMWB",
+    b"VZBRGITYUPSDNHLXAWMJQOFECK",
+];
+
+/// The position of rotors I to V at which they turn the rotor to their left as they step
+const ROTOR_NOTCHES: [u8; 5] = [16, 4, 21, 9, 25];
+
+/// The names of rotors I to V
+const ROTOR_NAMES: [&str; 5] = ["I", "II", "III", "IV", "V"];
+
+/// The wiring of reflector B
+const REFLECTOR_B: &[u8; 26] = b"YRUHQSLDPXNGOKMIEBFZCWVJAT";
+
+/// The most plugboard pairs an M3 was used with
+const MAX_PLUGS: usize = 10;
+
+/// How many of the best rotor settings go on to the ring and plugboard searches
+const SETTINGS_TO_REFINE: usize = 5;
+
+/// The fewest letters we will try to crack, the Index of Coincidence is too noisy below this
+const MIN_LETTERS: usize = 100;
+
+/// Enigma output has the flat letter frequencies of random text, around 0.038
+const MAX_INDEX_OF_COINCIDENCE: f64 = 0.045;
+
+/// An unplugged decryption this close to English letter frequencies is taken as
+/// the right rotor order, and the orders after it aren't tried
+const GOOD_INDEX_OF_COINCIDENCE: f64 = 0.058;
+
+/// With less time than this left in the search, no more rotor orders or settings are tried
+const MIN_TIME_TO_TRY: Duration = Duration::from_millis(250);
+
+/// One rotor order of a three-rotor machine, indices into rotors I to V from left to right
+type RotorOrder = [usize; 3];
+
+/// The settings of an Enigma machine
+#[derive(Clone, Debug, PartialEq)]
+struct Machine {
+    /// The rotors from left to right, as indices into rotors I to V
+    rotors: RotorOrder,
+    /// The ring setting of each rotor, from 0 (A)
+    rings: [u8; 3],
+    /// The start position of each rotor, the letter in its window from 0 (A)
+    positions: [u8; 3],
+    /// The letter each letter is swapped with on the plugboard, itself if it isn't plugged
+    plugboard: [u8; 26],
+}
+
+impl Machine {
+    /// A machine with the rotors in this order, the rings at A and nothing plugged
+    fn new(rotors: RotorOrder, positions: [u8; 3]) -> Machine {
+        Machine {
+            rotors,
+            rings: [0; 3],
+            positions,
+            plugboard: std::array::from_fn(|letter| letter as u8),
+        }
+    }
+
+    /// Encrypts the letters, which is the same as decrypting them
+    fn encrypt(&self, letters: &[u8]) -> Vec<u8> {
+        self.encrypt_with(&Wiring::new(self.rotors), letters)
+            .collect()
+    }
+
+    /// Encrypts the letters with the wiring of this machine's rotors worked out beforehand
+    fn encrypt_with<'a>(
+        &'a self,
+        wiring: &'a Wiring,
+        letters: &'a [u8],
+    ) -> impl Iterator<Item = u8> + 'a {
+        let notches = self.rotors.map(|rotor| ROTOR_NOTCHES[rotor]);
+        let mut positions = self.positions;
+
+        letters.iter().map(move |&letter| {
+            // The middle rotor steps itself and the left rotor when it reaches its
+            // notch, so it moves on two letters in a row: the double step
+            let middle_turns = positions[1] == notches[1];
+            if middle_turns {
+                positions[0] = (positions[0] + 1) % ALPHABET_LENGTH;
+            }
+            if middle_turns || positions[2] == notches[2] {
+                positions[1] = (positions[1] + 1) % ALPHABET_LENGTH;
+            }
+            positions[2] = (positions[2] + 1) % ALPHABET_LENGTH;
+
+            let left = offset(positions[0], self.rings[0]);
+            let middle = offset(positions[1], self.rings[1]);
+            let right = offset(positions[2], self.rings[2]);
+
+            let mut c = self.plugboard[letter as usize] as usize;
+            c = wiring.forward[2][right][c] as usize;
+            c = wiring.forward[1][middle][c] as usize;
+            c = wiring.forward[0][left][c] as usize;
+            c = (REFLECTOR_B[c] - b'A') as usize;
+            c = wiring.backward[0][left][c] as usize;
+            c = wiring.backward[1][middle][c] as usize;
+            c = wiring.backward[2][right][c] as usize;
+            self.plugboard[c]
+        })
+    }
+
+    /// The letter counts of the decryption, without keeping the decryption
+    fn counts_with(&self, wiring: &Wiring, letters: &[u8]) -> [usize; 26] {
+        let mut counts = [0; 26];
+        for letter in self.encrypt_with(wiring, letters) {
+            counts[letter as usize] += 1;
+        }
+        counts
+    }
+
+    /// Writes the settings the way an Enigma key sheet would
+    fn describe(&self) -> String {
+        let letters = |values: [u8; 3]| -> String {
+            values
+                .iter()
+                .map(|&value| index_to_letter(value, true))
+                .collect()
+        };
+        let rotors: Vec<&str> = self
+            .rotors
+            .iter()
+            .map(|&rotor| ROTOR_NAMES[rotor])
+            .collect();
+        let plugs: Vec<String> = (0..ALPHABET_LENGTH)
+            .filter(|&letter| self.plugboard[letter as usize] > letter)
+            .map(|letter| {
+                [letter, self.plugboard[letter as usize]]
+                    .iter()
+                    .map(|&l| index_to_letter(l, true))
+                    .collect()
+            })
+            .collect();
+        format!(
+            "reflector B, rotors {}, rings {}, start {}, plugboard {}",
+            rotors.join(" "),
+            letters(self.rings),
+            letters(self.positions),
+            if plugs.is_empty() {
+                "empty".to_string()
+            } else {
+                plugs.join(" ")
+            }
+        )
+    }
+}
+
+/// Where each letter comes out of each rotor of one rotor order, at every offset of the
+/// rotor's wiring from its rest position, worked out once so the search only looks them up
+struct Wiring {
+    /// The contact a letter comes out of going from right to left, `forward[rotor][offset][letter]`
+    forward: [[[u8; 26]; 26]; 3],
+    /// The contact a letter comes out of on the way back from the reflector
+    backward: [[[u8; 26]; 26]; 3],
+}
+
+impl Wiring {
+    /// Works out the wiring of the rotors from left to right
+    fn new(rotors: RotorOrder) -> Wiring {
+        let mut wiring = Wiring {
+            forward: [[[0; 26]; 26]; 3],
+            backward: [[[0; 26]; 26]; 3],
+        };
+        for (slot, &rotor) in rotors.iter().enumerate() {
+            for offset in 0..ALPHABET_LENGTH {
+                for letter in 0..ALPHABET_LENGTH {
+                    let contact =
+                        ROTOR_WIRINGS[rotor][((letter + offset) % ALPHABET_LENGTH) as usize] - b'A';
+                    let out = (contact + ALPHABET_LENGTH - offset) % ALPHABET_LENGTH;
+                    wiring.forward[slot][offset as usize][letter as usize] = out;
+                    wiring.backward[slot][offset as usize][out as usize] = letter;
+                }
+            }
+        }
+        wiring
+    }
+}
+
+/// How far a rotor's wiring is turned, from its position and ring setting
+fn offset(position: u8, ring: u8) -> usize {
+    ((position + ALPHABET_LENGTH - ring) % ALPHABET_LENGTH) as usize
+}
+
+/// The Enigma decoder, call:
+/// `let enigma_decoder = Decoder::<EnigmaDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = enigma_decoder.crack(input)` to crack an Enigma message
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::classical::enigma_decoder::EnigmaDecoder;
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let crack_enigma = Decoder::<EnigmaDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// // Ordinary English doesn't look like Enigma output, so it isn't cracked
+/// let result = crack_enigma.crack("The weather today is clear and calm along the whole coast, with light winds from the west expected to continue through the night and into tomorrow morning.", &checker);
+/// assert!(result.unencrypted_text.is_none());
+/// ```
+pub struct EnigmaDecoder;
+
+impl Crack for Decoder<EnigmaDecoder> {
+    fn new() -> Decoder<EnigmaDecoder> {
+        Decoder {
+            name: "Enigma",
+            description: "The rotor cipher machine used by the German military. This cracks the three-rotor M3 with rotors I to V and reflector B: the rotor order and start positions are found with the Index of Coincidence, then the ring settings, then the plugboard pairs with English quadgram scores. The output is capital letters only.",
+            link: "https://en.wikipedia.org/wiki/Cryptanalysis_of_the_Enigma",
+            tags: vec!["enigma", "classical", "cipher", "machine", "decryption"],
+            popularity: 0.1,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying Enigma with text {:?}", text);
+        let mut results = CrackResult::new(self, text.to_string());
+
+        if !text
+            .chars()
+            .all(|c| c.is_ascii_alphabetic() || c.is_whitespace())
+        {
+            debug!("Failed to crack Enigma because the text isn't only letters");
+            return results;
+        }
+        let letters = letter_indices(text);
+        if letters.len() < MIN_LETTERS {
+            info!("Failed to crack Enigma because the text has too few letters");
+            return results;
+        }
+        if index_of_coincidence(&letter_counts(&letters)) > MAX_INDEX_OF_COINCIDENCE {
+            info!("Failed to crack Enigma because the letter frequencies aren't flat enough");
+            return results;
+        }
+
+        let orders = rotor_orders();
+        let combinations = get_config().enigma_rotor_combinations as usize;
+        let out_of_time = || {
+            self.remaining_time()
+                .is_some_and(|left| left < MIN_TIME_TO_TRY)
+        };
+        let (machine, timed_out) = crack_letters(
+            &letters,
+            &orders[..combinations.min(orders.len())],
+            out_of_time,
+        );
+        results.timed_out = timed_out;
+        let Some(machine) = machine else {
+            info!("Failed to crack Enigma because there was no time to try a rotor order");
+            return results;
+        };
+        let decoded_text: String = machine
+            .encrypt(&letters)
+            .into_iter()
+            .map(|letter| index_to_letter(letter, true))
+            .collect();
+        if !check_string_success(&decoded_text, text) {
+            info!("Failed to crack Enigma because the best settings changed nothing");
+            return results;
+        }
+
+        let settings = machine.describe();
+        debug!("Enigma recovered settings {}", settings);
+        let checker_result = checker.check(&decoded_text);
+        results.description = format!("{} Recovered settings: {}", self.description, settings);
+        results.key = Some(settings);
+        results.unencrypted_text = Some(vec![decoded_text]);
+        results.update_checker(&checker_result);
+        results
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some((
+            "OUEMZ NMZSV MXSJU IQDQA BBCMP FFSUJ POWDQ YLYXR MBGNT QKRII KLDHP VWFOS ZVKMV YGIIT DHVBB KIXFN DGVIP MARZS XBNPW BMGLY NVMRA NFHVG HFHWF YQLJL VJSFU YRXTG EBRGH XMRVW BGKMN NOYLJ XFCCJ YOLTK FPGCL DVWSD JCEGH ZQFUM ZG",
+            "WEATHERREPORTFORTHEMORNINGHEAVYFOGALONGTHENORTHERNCOASTWITHVISIBILITYBELOWONEMILEWINDFROMTHEEASTATFIFTEENKNOTSRISINGTOGALEFORCEBYEVENINGALLSHIPSARETOREMAININHARBOURUNTILFURTHERORDERS",
+        ))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input must only contain ASCII letters and whitespace, at least 100 letters",
+            "Letter frequencies must look random (Index of Coincidence of at most 0.045)",
+            "Tries `enigma_rotor_combinations` rotor orders from the config at every start position, fewer if the search is almost out of time",
+        ]
+    }
+}
+
+/// Every order of three different rotors out of I to V, starting with I II III
+fn rotor_orders() -> Vec<RotorOrder> {
+    let mut orders = Vec::new();
+    for left in 0..5 {
+        for middle in 0..5 {
+            for right in 0..5 {
+                if left != middle && middle != right && left != right {
+                    orders.push([left, middle, right]);
+                }
+            }
+        }
+    }
+    orders
+}
+
+/// Finds the settings of the machine which most likely encrypted the letters, and whether
+/// it ran out of time
+/// Rotor orders are tried until `out_of_time` is true, and the best settings so far are
+/// refined, best first, until it is true again. None if no rotor order was tried.
+fn crack_letters(
+    letters: &[u8],
+    orders: &[RotorOrder],
+    out_of_time: impl Fn() -> bool,
+) -> (Option<Machine>, bool) {
+    // Rotor orders and start positions, with the rings at A and nothing plugged
+    let mut settings: Vec<(Machine, f64)> = Vec::new();
+    let mut timed_out = false;
+    for &order in orders {
+        if out_of_time() {
+            debug!("Enigma is almost out of time, refining the rotor orders tried so far");
+            timed_out = true;
+            break;
+        }
+        let best = best_positions(letters, order);
+        let good_enough = best[0].1 >= GOOD_INDEX_OF_COINCIDENCE;
+        settings.extend(best);
+        settings.sort_by(|a, b| b.1.total_cmp(&a.1));
+        settings.truncate(SETTINGS_TO_REFINE);
+        if good_enough {
+            debug!(
+                "Enigma rotor order {:?} looks right, not trying the others",
+                order
+            );
+            break;
+        }
+    }
+
+    let mut refined: Vec<(Machine, f64)> = Vec::new();
+    for (machine, _) in settings {
+        // The best setting is always refined, so there is something to return
+        if !refined.is_empty() && out_of_time() {
+            debug!("Enigma is almost out of time, keeping the settings refined so far");
+            timed_out = true;
+            break;
+        }
+        let wiring = Wiring::new(machine.rotors);
+        let ioc = |machine: &Machine| index_of_coincidence(&machine.counts_with(&wiring, letters));
+        let quadgrams = |machine: &Machine| {
+            fitness(&machine.encrypt_with(&wiring, letters).collect::<Vec<u8>>())
+        };
+        // The rings are searched again once the plugboard is known, as the Index of
+        // Coincidence can't always tell neighbouring ring settings apart
+        let machine = find_rings(
+            find_plugs(find_rings(machine, &ioc), &quadgrams),
+            &quadgrams,
+        );
+        let score = quadgrams(&machine);
+        refined.push((machine, score));
+    }
+
+    let best = refined
+        .into_iter()
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(machine, _)| machine);
+    (best, timed_out)
+}
+
+/// The best start positions for this rotor order by the Index of Coincidence of the
+/// decryption, best first
+fn best_positions(letters: &[u8], order: RotorOrder) -> Vec<(Machine, f64)> {
+    let wiring = Wiring::new(order);
+    let mut best: Vec<(Machine, f64)> = Vec::with_capacity(SETTINGS_TO_REFINE + 1);
+    for left in 0..ALPHABET_LENGTH {
+        for middle in 0..ALPHABET_LENGTH {
+            for right in 0..ALPHABET_LENGTH {
+                let machine = Machine::new(order, [left, middle, right]);
+                let score = index_of_coincidence(&machine.counts_with(&wiring, letters));
+                if best.len() < SETTINGS_TO_REFINE || score > best[best.len() - 1].1 {
+                    best.push((machine, score));
+                    best.sort_by(|a, b| b.1.total_cmp(&a.1));
+                    best.truncate(SETTINGS_TO_REFINE);
+                }
+            }
+        }
+    }
+    best
+}
+
+/// Finds the ring settings of the right and then the middle rotor
+/// Turning a ring and the rotor's start position together leaves the wiring where it was,
+/// and only changes when the rotor turns over the one to its left.
+fn find_rings(mut machine: Machine, score: &impl Fn(&Machine) -> f64) -> Machine {
+    for rotor in [2, 1] {
+        let start = machine.clone();
+        let mut best_score = score(&machine);
+        for ring in 0..ALPHABET_LENGTH {
+            let mut candidate = start.clone();
+            candidate.rings[rotor] = (start.rings[rotor] + ring) % ALPHABET_LENGTH;
+            candidate.positions[rotor] = (start.positions[rotor] + ring) % ALPHABET_LENGTH;
+            let candidate_score = score(&candidate);
+            if candidate_score > best_score {
+                best_score = candidate_score;
+                machine = candidate;
+            }
+        }
+    }
+    machine
+}
+
+/// Adds the plugboard pair which improves the score the most, until none does
+fn find_plugs(mut machine: Machine, score: &impl Fn(&Machine) -> f64) -> Machine {
+    let mut best_score = score(&machine);
+    for _ in 0..MAX_PLUGS {
+        let unplugged: Vec<u8> = (0..ALPHABET_LENGTH)
+            .filter(|&letter| machine.plugboard[letter as usize] == letter)
+            .collect();
+        let mut best_plug = None;
+        for (i, &a) in unplugged.iter().enumerate() {
+            for &b in &unplugged[i + 1..] {
+                let mut candidate = machine.clone();
+                candidate.plugboard[a as usize] = b;
+                candidate.plugboard[b as usize] = a;
+                let candidate_score = score(&candidate);
+                if candidate_score > best_score {
+                    best_score = candidate_score;
+                    best_plug = Some(candidate);
+                }
+            }
+        }
+        match best_plug {
+            Some(candidate) => machine = candidate,
+            None => break,
+        }
+    }
+    machine
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checkers::{
+        athena::Athena,
+        checker_type::{Check, Checker},
+        CheckerTypes,
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn successful_decoding() {
+        let decoder = Decoder::<EnigmaDecoder>::new();
+        let (input, expected) = decoder.get_example().unwrap();
+        let result = decoder.crack(input, &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], expected);
+        assert_eq!(
+            result.key.as_deref(),
+            Some("reflector B, rotors I II III, rings AAC, start KFM, plugboard ER QW")
+        );
+    }
+
+    #[test]
+    fn known_test_vector() {
+        // Rotors I II III with the rings and start positions at A turn AAAAA into BDZGO
+        let machine = Machine::new([0, 1, 2], [0; 3]);
+        assert_eq!(
+            machine.encrypt(&letter_indices("AAAAA")),
+            letter_indices("BDZGO")
+        );
+    }
+
+    #[test]
+    fn double_step_and_plugboard() {
+        // Rotors II IV V, rings BUL, start position ADU, which double steps the middle rotor
+        let mut machine = Machine::new([1, 3, 4], [0, 3, 20]);
+        machine.rings = [1, 20, 11];
+        for (a, b) in [(0, 1), (2, 3)] {
+            machine.plugboard[a] = b as u8;
+            machine.plugboard[b] = a as u8;
+        }
+        let plaintext = letter_indices("theenemyismovingsouthalongtheriver");
+        let ciphertext = machine.encrypt(&plaintext);
+        assert_eq!(
+            ciphertext,
+            letter_indices("YEZXESRQHHNDMZOHPIGJAXVFANKNTYVSCZ")
+        );
+        assert_eq!(machine.encrypt(&ciphertext), plaintext);
+    }
+
+    #[test]
+    fn there_are_sixty_rotor_orders() {
+        let orders = rotor_orders();
+        assert_eq!(orders.len(), 60);
+        assert_eq!(orders[0], [0, 1, 2]);
+    }
+
+    #[test]
+    fn running_out_of_time_keeps_the_settings_so_far() {
+        let decoder = Decoder::<EnigmaDecoder>::new();
+        let (input, _) = decoder.get_example().unwrap();
+        let letters = letter_indices(input);
+        let orders = rotor_orders();
+        assert_eq!(crack_letters(&letters, &orders, || true), (None, true));
+
+        // Time runs out after the first rotor order, which is the right one
+        let checks = std::cell::Cell::new(0);
+        let (machine, timed_out) = crack_letters(&letters, &orders, || {
+            checks.set(checks.get() + 1);
+            checks.get() > 1
+        });
+        assert!(timed_out);
+        assert_eq!(machine.unwrap().rotors, [0, 1, 2]);
+    }
+
+    #[test]
+    fn short_text_is_not_cracked() {
+        let decoder = Decoder::<EnigmaDecoder>::new();
+        let result = decoder.crack("OUEMZ NMZSV MXSJU IQDQA", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+
+    #[test]
+    fn enigma_handles_panic_if_emoji() {
+        let decoder = Decoder::<EnigmaDecoder>::new();
+        let result = decoder.crack("😂", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+}
@@ -0,0 +1,99 @@
+//! Classical ciphers whose keys have to be searched for
+//! Each cracker works on the letters of the text as numbers from 0 (A) to 25 (Z) and
+//! ranks candidate keys with the English quadgram model, the same fitness the
+//! transposition and substitution crackers use. The decoded letters are then put back
+//! where the ciphertext's letters were, so spacing, punctuation and case survive.
+
+/// The adfgvx_decoder module cracks the ADFGVX and ADFGX ciphers
+pub mod adfgvx_decoder;
+/// The autokey_decoder module cracks the autokey cipher
+pub mod autokey_decoder;
+/// The beaufort_decoder module cracks the Beaufort cipher
+pub mod beaufort_decoder;
+/// The bifid_decoder module cracks the Bifid cipher
+pub mod bifid_decoder;
+/// The enigma_decoder module cracks the Enigma M3 machine
+pub mod enigma_decoder;
+/// The playfair_decoder module cracks the Playfair cipher
+pub mod playfair_decoder;
+/// The polybius_decoder module decodes Polybius square coordinates
+pub mod polybius_decoder;
+/// The tap_code_decoder module decodes tap code
+pub mod tap_code_decoder;
+
+use crate::storage::ngrams::ENGLISH_QUADGRAMS;
+
+use super::alphabet::index_to_letter;
+
+/// The width and height of the usual Polybius square
+pub const POLYBIUS_SIDE: u8 = 5;
+
+/// The number of J, which shares a cell with I in the usual Polybius square
+const J: u8 = 9;
+
+/// How English-like the letters are, higher is better
+/// This is the total quadgram log probability, so only compare letters of the same length.
+pub fn fitness(letters: &[u8]) -> f64 {
+    ENGLISH_QUADGRAMS.score_letters(letters)
+}
+
+/// Replaces the letters of the text, in order, with the decoded letters
+/// Each decoded letter takes the case of the letter it replaces and everything which
+/// isn't an ASCII letter is kept. Letters past the end of `decoded` are left as they were.
+pub fn restore_layout(text: &str, decoded: &[u8]) -> String {
+    let mut decoded = decoded.iter();
+    text.chars()
+        .map(|c| {
+            if !c.is_ascii_alphabetic() {
+                return c;
+            }
+            decoded
+                .next()
+                .map_or(c, |&letter| index_to_letter(letter, c.is_ascii_uppercase()))
+        })
+        .collect()
+}
+
+/// The cell of the letter in the usual Polybius square, as row and column from 0
+/// The square is the alphabet written in rows of five without J, so J is read as I.
+pub fn polybius_cell(letter: u8) -> (u8, u8) {
+    let index = if letter < J { letter } else { letter - 1 };
+    (index / POLYBIUS_SIDE, index % POLYBIUS_SIDE)
+}
+
+/// The letter in the cell of the usual Polybius square, the reverse of [`polybius_cell`]
+pub fn polybius_letter(row: u8, column: u8) -> u8 {
+    let index = row * POLYBIUS_SIDE + column;
+    if index < J {
+        index
+    } else {
+        index + 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layout_and_case_are_kept() {
+        assert_eq!(restore_layout("Ab, c-D!", &[7, 4, 11, 15]), "He, l-P!");
+    }
+
+    #[test]
+    fn polybius_cells_skip_j() {
+        assert_eq!(polybius_cell(0), (0, 0));
+        assert_eq!(polybius_cell(8), (1, 3));
+        assert_eq!(polybius_cell(9), (1, 3));
+        assert_eq!(polybius_cell(25), (4, 4));
+        assert!((0..26).all(|letter| letter == J
+            || polybius_letter(polybius_cell(letter).0, polybius_cell(letter).1) == letter));
+    }
+
+    #[test]
+    fn english_is_fitter_than_noise() {
+        let english = crate::decoders::frequency_analysis::letter_indices("attackatdawn");
+        let noise = crate::decoders::frequency_analysis::letter_indices("qzxjvkqzxjvk");
+        assert!(fitness(&english) > fitness(&noise));
+    }
+}
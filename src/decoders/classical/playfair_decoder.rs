@@ -0,0 +1,471 @@
+//! Playfair cipher cracker
+//! Playfair encrypts pairs of letters with a 5x5 square of the alphabet, with I and J
+//! sharing a cell. There are far too many squares to try, so the square is found by
+//! simulated annealing: small changes to the square are kept if the quadgram score of the
+//! decryption goes up, and early on sometimes even if it goes down. The search stops once
+//! the `playfair_iterations` budget in the config is used up, or with the best square so
+//! far when the search is almost out of time.
+//! Playfair can't encrypt a pair of the same letter, which rules most texts out cheaply.
+
+use crate::checkers::CheckerTypes;
+use crate::config::get_config;
+use crate::decoders::alphabet::index_to_letter;
+use crate::decoders::crack_results::CrackResult;
+use crate::decoders::frequency_analysis::letter_indices;
+use crate::decoders::interface::{check_string_success, Crack, Decoder};
+
+use super::{fitness, restore_layout};
+
+use log::{debug, info, trace};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::time::Duration;
+
+/// The fewest letters we will try to crack, shorter texts have many squares which score well
+const MIN_LETTERS: usize = 80;
+
+/// The number of J, which Playfair squares leave out
+const J: u8 = 9;
+
+/// The width and height of the square
+const SIDE: usize = 5;
+
+/// How much worse, in log probability of the whole text, a change can score and still
+/// often be kept at the start of the search, for every letter of the text.
+/// Longer texts score further apart, so the temperature grows with them.
+const TEMPERATURE_PER_LETTER: f64 = 0.02;
+
+/// The seed for the random changes, so the same ciphertext is always cracked the same way
+const SEED: u64 = 1;
+
+/// How many squares are scored between checks of how long the search has left
+const STEPS_PER_TIME_CHECK: u32 = 1_000;
+
+/// With less time than this left in the search, the best square so far is returned
+const MIN_TIME_TO_SOLVE: Duration = Duration::from_millis(250);
+
+/// A key square, read left to right and top to bottom
+type Square = [u8; SIDE * SIDE];
+
+/// The Playfair decoder, call:
+/// `let playfair_decoder = Decoder::<PlayfairDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = playfair_decoder.crack(input)` to crack a Playfair cipher
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::classical::playfair_decoder::PlayfairDecoder;
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let crack_playfair = Decoder::<PlayfairDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = crack_playfair.crack(
+///     "Mvm kvskcksfaa qkl mvp kmnr lcf boadmvm rp kkatl sefyl, drmndgai fanwey dmkrb ray zrskm phm r phdzagfyq. Rahrhb gr qcf prny iulfdlkc pdlc pr mklzma ciphmk pdi mzlmca mbsaq ciina, yzl pdl eossrga xnl bnagkckrq pdrs pdl cmvrqbso qbxq xmvtc tlnb hvgm wmskp lcf gmh rk pcf libxna.",
+///     &checker,
+/// );
+/// assert_eq!(
+///     result.unencrypted_text.unwrap()[0],
+///     "Our expedition set out from the harbour at first light, carrying enough bread and water for a fortnight. Nobody in the town expected them to return before the autumn rains began, but the captain was confident that the mountain pass would stay open until the end of the season."
+/// );
+/// ```
+pub struct PlayfairDecoder;
+
+impl Crack for Decoder<PlayfairDecoder> {
+    fn new() -> Decoder<PlayfairDecoder> {
+        Decoder {
+            name: "Playfair",
+            description: "A digraph cipher which encrypts pairs of letters using a 5x5 key square, with I and J sharing a cell. Double letters in a pair are split with an X before encrypting and the X is kept when decrypting, as is any spacing or punctuation in the ciphertext. The square is recovered by simulated annealing on English quadgram scores.",
+            link: "https://en.wikipedia.org/wiki/Playfair_cipher",
+            tags: vec!["classical", "cipher", "digraph", "decryption"],
+            popularity: 0.3,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying Playfair with text {:?}", text);
+        let mut results = CrackResult::new(self, text.to_string());
+
+        let letters = letter_indices(text);
+        if letters.len() < MIN_LETTERS || !letters.len().is_multiple_of(2) {
+            info!("Failed to crack Playfair because the text doesn't have an even number of letters, or too few");
+            return results;
+        }
+        if letters.contains(&J) || letters.chunks(2).any(|pair| pair[0] == pair[1]) {
+            info!("Failed to crack Playfair because the text has a J or a pair of the same letter");
+            return results;
+        }
+
+        let out_of_time = || {
+            self.remaining_time()
+                .is_some_and(|left| left < MIN_TIME_TO_SOLVE)
+        };
+        let (square, timed_out) = solve(&letters, get_config().playfair_iterations, out_of_time);
+        results.timed_out = timed_out;
+        let decoded_text = restore_layout(text, &decrypt(&letters, &square));
+        if !check_string_success(&decoded_text, text) {
+            info!("Failed to crack Playfair because the best square changed nothing");
+            return results;
+        }
+
+        let checker_result = checker.check(&decoded_text);
+        results.unencrypted_text = Some(vec![decoded_text]);
+        if checker_result.is_identified {
+            let key = format_square(&square);
+            trace!("Cracked Playfair with square {}", key);
+            results.description = format!("{} Recovered key square: {}", self.description, key);
+            results.key = Some(key);
+            results.update_checker(&checker_result);
+        }
+        results
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some((
+            "Mvm kvskcksfaa qkl mvp kmnr lcf boadmvm rp kkatl sefyl, drmndgai fanwey dmkrb ray zrskm phm r phdzagfyq. Rahrhb gr qcf prny iulfdlkc pdlc pr mklzma ciphmk pdi mzlmca mbsaq ciina, yzl pdl eossrga xnl bnagkckrq pdrs pdl cmvrqbso qbxq xmvtc tlnb hvgm wmskp lcf gmh rk pcf libxna.",
+            "Our expedition set out from the harbour at first light, carrying enough bread and water for a fortnight. Nobody in the town expected them to return before the autumn rains began, but the captain was confident that the mountain pass would stay open until the end of the season.",
+        ))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input must contain an even number of letters, at least 80",
+            "Input must not contain a J",
+            "No pair of letters can be the same letter twice",
+            "Scores at most `playfair_iterations` key squares from the config, fewer if the search is almost out of time",
+        ]
+    }
+}
+
+/// Anneals from the alphabet in order, then polishes the best square seen within the budget,
+/// returning it and whether `out_of_time` cut the search short
+/// Most changes swap two letters of the square. Swapping or flipping rows and columns keeps
+/// most pairs decrypting the same way, which helps once the square is nearly right.
+fn solve(letters: &[u8], iterations: u32, out_of_time: impl Fn() -> bool) -> (Square, bool) {
+    let mut rng = StdRng::seed_from_u64(SEED);
+    let start_temperature = TEMPERATURE_PER_LETTER * letters.len() as f64;
+
+    let mut square: Square = std::array::from_fn(|i| if i < J as usize { i } else { i + 1 } as u8);
+    let mut score = fitness(&decrypt(letters, &square));
+    let mut best_square = square;
+    let mut best_score = score;
+
+    for step in 0..iterations {
+        if step % STEPS_PER_TIME_CHECK == 0 && out_of_time() {
+            debug!("Playfair is almost out of time, returning the best square after {step} steps");
+            return (best_square, true);
+        }
+        let temperature = start_temperature * (1.0 - f64::from(step) / f64::from(iterations));
+        let mut changed = square;
+        let (a, b) = (rng.random_range(0..SIDE), rng.random_range(0..SIDE));
+        match rng.random_range(0..50) {
+            0 => swap_rows(&mut changed, a, b),
+            1 => swap_columns(&mut changed, a, b),
+            2 => (0..SIDE / 2).for_each(|row| swap_rows(&mut changed, row, SIDE - 1 - row)),
+            3 => (0..SIDE / 2)
+                .for_each(|column| swap_columns(&mut changed, column, SIDE - 1 - column)),
+            4 => changed.reverse(),
+            _ => {
+                let (a, b) = (
+                    rng.random_range(0..changed.len()),
+                    rng.random_range(0..changed.len()),
+                );
+                changed.swap(a, b)
+            }
+        }
+        let changed_score = fitness(&decrypt(letters, &changed));
+        if changed_score > score
+            || rng.random::<f64>() < ((changed_score - score) / temperature).exp()
+        {
+            square = changed;
+            score = changed_score;
+            if score > best_score {
+                best_square = square;
+                best_score = score;
+            }
+        }
+    }
+    polish(letters, best_square, best_score, out_of_time)
+}
+
+/// Tries every swap of two letters and every move of a row or column to another place,
+/// keeping any that score better, until none do or `out_of_time` is true
+/// Annealing often ends with the rows or columns in the wrong order, which takes
+/// several swaps in a row to put right.
+fn polish(
+    letters: &[u8],
+    mut square: Square,
+    mut score: f64,
+    out_of_time: impl Fn() -> bool,
+) -> (Square, bool) {
+    let mut improved = true;
+    while improved {
+        if out_of_time() {
+            debug!("Playfair is almost out of time, returning the square polished so far");
+            return (square, true);
+        }
+        improved = false;
+        let mut changes: Vec<Square> = Vec::new();
+        for a in 0..square.len() {
+            for b in a + 1..square.len() {
+                let mut changed = square;
+                changed.swap(a, b);
+                changes.push(changed);
+            }
+        }
+        for from in 0..SIDE {
+            for to in 0..SIDE {
+                let mut changed = square;
+                move_row(&mut changed, from, to);
+                changes.push(changed);
+                let mut changed = square;
+                move_column(&mut changed, from, to);
+                changes.push(changed);
+            }
+        }
+        for changed in changes {
+            let changed_score = fitness(&decrypt(letters, &changed));
+            if changed_score > score {
+                square = changed;
+                score = changed_score;
+                improved = true;
+            }
+        }
+    }
+    (square, false)
+}
+
+/// Swaps two rows of the square
+fn swap_rows(square: &mut Square, a: usize, b: usize) {
+    for column in 0..SIDE {
+        square.swap(a * SIDE + column, b * SIDE + column);
+    }
+}
+
+/// Swaps two columns of the square
+fn swap_columns(square: &mut Square, a: usize, b: usize) {
+    for row in 0..SIDE {
+        square.swap(row * SIDE + a, row * SIDE + b);
+    }
+}
+
+/// Moves a row of the square to another position, shifting the rows in between along
+fn move_row(square: &mut Square, from: usize, to: usize) {
+    if from < to {
+        (from..to).for_each(|row| swap_rows(square, row, row + 1));
+    } else {
+        (to..from)
+            .rev()
+            .for_each(|row| swap_rows(square, row, row + 1));
+    }
+}
+
+/// Moves a column of the square to another position, shifting the columns in between along
+fn move_column(square: &mut Square, from: usize, to: usize) {
+    if from < to {
+        (from..to).for_each(|column| swap_columns(square, column, column + 1));
+    } else {
+        (to..from)
+            .rev()
+            .for_each(|column| swap_columns(square, column, column + 1));
+    }
+}
+
+/// Decrypts the letters, which must be an even number, in pairs with the square
+/// Letters in the same row are replaced by the letters to their left, letters in the same
+/// column by the letters above them, and any other pair by the letters in the same rows
+/// but the other letter's column.
+fn decrypt(letters: &[u8], square: &Square) -> Vec<u8> {
+    let mut positions = [(0, 0); 26];
+    for (i, &letter) in square.iter().enumerate() {
+        positions[letter as usize] = (i / SIDE, i % SIDE);
+    }
+    let at = |row: usize, column: usize| square[row * SIDE + column];
+
+    let mut decrypted = Vec::with_capacity(letters.len());
+    for pair in letters.chunks_exact(2) {
+        let (row_a, column_a) = positions[pair[0] as usize];
+        let (row_b, column_b) = positions[pair[1] as usize];
+        let (first, second) = if row_a == row_b {
+            (
+                at(row_a, (column_a + SIDE - 1) % SIDE),
+                at(row_b, (column_b + SIDE - 1) % SIDE),
+            )
+        } else if column_a == column_b {
+            (
+                at((row_a + SIDE - 1) % SIDE, column_a),
+                at((row_b + SIDE - 1) % SIDE, column_b),
+            )
+        } else {
+            (at(row_a, column_b), at(row_b, column_a))
+        };
+        decrypted.push(first);
+        decrypted.push(second);
+    }
+    decrypted
+}
+
+/// Writes the square one row at a time, rows separated by spaces
+fn format_square(square: &Square) -> String {
+    square
+        .chunks(SIDE)
+        .map(|row| {
+            row.iter()
+                .map(|&letter| index_to_letter(letter, true))
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checkers::{
+        athena::Athena,
+        checker_type::{Check, Checker},
+        CheckerTypes,
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    /// The square for the keyword PLAYFAIR EXAMPLE, from Wikipedia
+    fn example_square() -> Square {
+        let order = letter_indices("PLAYFIREXMBCDGHKNOQSTUVWZ");
+        std::array::from_fn(|i| order[i])
+    }
+
+    /// Shifts the rows and columns of a written square round until A is in the top left
+    /// Shifting every row or column round gives an equivalent square, and this picks one
+    /// of them so squares can be compared.
+    fn with_a_first(key: &str) -> String {
+        let rows: Vec<&str> = key.split(' ').collect();
+        let row = rows.iter().position(|row| row.contains('A')).unwrap();
+        let column = rows[row].find('A').unwrap();
+        (0..SIDE)
+            .map(|i| {
+                let chars: Vec<char> = rows[(row + i) % SIDE].chars().collect();
+                (0..SIDE)
+                    .map(|j| chars[(column + j) % SIDE])
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    #[test]
+    fn decrypts_the_wikipedia_example() {
+        let ciphertext = letter_indices("BMODZBXDNABEKUDMUIXMMOUVIF");
+        assert_eq!(
+            restore_layout(
+                "BMODZBXDNABEKUDMUIXMMOUVIF",
+                &decrypt(&ciphertext, &example_square())
+            ),
+            "HIDETHEGOLDINTHETREXESTUMP"
+        );
+    }
+
+    #[test]
+    fn moving_a_row_shifts_the_rows_between() {
+        let mut square = example_square();
+        move_row(&mut square, 4, 2);
+        assert_eq!(format_square(&square), "PLAYF IREXM TUVWZ BCDGH KNOQS");
+        move_row(&mut square, 2, 4);
+        assert_eq!(square, example_square());
+    }
+
+    #[test]
+    fn squares_are_written_in_rows() {
+        assert_eq!(
+            format_square(&example_square()),
+            "PLAYF IREXM BCDGH KNOQS TUVWZ"
+        );
+    }
+
+    #[test]
+    fn shifted_squares_are_compared_with_a_first() {
+        assert_eq!(
+            with_a_first("PQSTL VWXZU ONARM HYBDC FGIKE"),
+            with_a_first("TLPQS ZUVWX RMONA DCHYB KEFGI")
+        );
+        assert_eq!(
+            with_a_first("PLAYF IREXM BCDGH KNOQS TUVWZ"),
+            "AYFPL EXMIR DGHBC OQSKN VWZTU"
+        );
+    }
+
+    #[test]
+    fn successful_decoding() {
+        let decoder = Decoder::<PlayfairDecoder>::new();
+        let (input, expected) = decoder.get_example().unwrap();
+        let result = decoder.crack(input, &get_athena_checker());
+        assert!(result.success);
+        // The square for the keyword MONARCHY, which may come back with its rows and
+        // columns shifted round
+        assert_eq!(
+            with_a_first(&result.key.unwrap()),
+            with_a_first("MONAR CHYBD EFGIK LPQST UVWXZ")
+        );
+        assert_eq!(result.unencrypted_text.unwrap()[0], expected);
+    }
+
+    #[test]
+    fn running_out_of_time_returns_the_best_square_so_far() {
+        let decoder = Decoder::<PlayfairDecoder>::new();
+        let (input, _) = decoder.get_example().unwrap();
+        let letters = letter_indices(input);
+        let (square, timed_out) = solve(&letters, 1_000_000, || true);
+        assert!(timed_out);
+        assert_eq!(format_square(&square), "ABCDE FGHIK LMNOP QRSTU VWXYZ");
+    }
+
+    #[test]
+    fn pairs_of_the_same_letter_are_not_playfair() {
+        let decoder = Decoder::<PlayfairDecoder>::new();
+        let result = decoder.crack(&"AABC".repeat(30), &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+
+    #[test]
+    fn odd_letter_counts_are_not_playfair() {
+        let decoder = Decoder::<PlayfairDecoder>::new();
+        let result = decoder.crack(&"ABC".repeat(31), &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+
+    #[test]
+    fn playfair_handles_panic_if_emoji() {
+        let decoder = Decoder::<PlayfairDecoder>::new();
+        let result = decoder.crack("😂", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+}
@@ -0,0 +1,250 @@
+//! Polybius square decoder
+//! Every letter is written as the row and column of its cell in a square of the alphabet,
+//! so the text is pairs of digits from 1 to 5. The usual square leaves out J, which is read
+//! as I. A 6x6 square, which holds the digits 0 to 9 after the letters, uses 6 as well.
+//! Letters are usually split by one space and words by more than one, but each word
+//! can also be written as one run of digits.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::alphabet::index_to_letter;
+use crate::decoders::crack_results::CrackResult;
+use crate::decoders::interface::{check_string_success, Crack, Decoder};
+
+use super::{polybius_letter, POLYBIUS_SIDE};
+
+use log::{debug, info, trace};
+
+/// The cells of the 6x6 square, read left to right and top to bottom
+const SQUARE_6X6: &[u8; 36] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+/// The Polybius square decoder, call:
+/// `let polybius_decoder = Decoder::<PolybiusDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = polybius_decoder.crack(input)` to decode Polybius square coordinates
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::classical::polybius_decoder::PolybiusDecoder;
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_polybius = Decoder::<PolybiusDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_polybius.crack(
+///     "32 15 15 44  32 15  12 54  44 23 15  34 31 14  34 11 25  44 42 15 15  11 44  33 34 34 33",
+///     &checker,
+/// );
+/// assert_eq!(
+///     result.unencrypted_text.unwrap()[0],
+///     "MEET ME BY THE OLD OAK TREE AT NOON"
+/// );
+/// ```
+pub struct PolybiusDecoder;
+
+impl Crack for Decoder<PolybiusDecoder> {
+    fn new() -> Decoder<PolybiusDecoder> {
+        Decoder {
+            name: "Polybius Square",
+            description: "The Polybius square writes each letter as the row and column of its cell in a 5x5 square of the alphabet, with I and J sharing a cell. Text using the digit 6 is read with a 6x6 square which holds the digits after the letters. The output is in capital letters, and I stands for J too.",
+            link: "https://en.wikipedia.org/wiki/Polybius_square",
+            tags: vec!["polybius", "classical", "cipher", "digits"],
+            popularity: 0.4,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying Polybius square with text {:?}", text);
+        let mut results = CrackResult::new(self, text.to_string());
+
+        let Some(decoded_text) = decode(text) else {
+            debug!("Failed to decode Polybius square coordinates");
+            return results;
+        };
+        if !check_string_success(&decoded_text, text) {
+            info!(
+                "Failed to decode Polybius square because check_string_success returned false on string {}",
+                decoded_text
+            );
+            return results;
+        }
+
+        let checker_result = checker.check(&decoded_text);
+        results.unencrypted_text = Some(vec![decoded_text]);
+        results.update_checker(&checker_result);
+        results
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some((
+            "32 15 15 44  32 15  12 54  44 23 15  34 31 14  34 11 25  44 42 15 15  11 44  33 34 34 33",
+            "MEET ME BY THE OLD OAK TREE AT NOON",
+        ))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input must only contain the digits 1 to 6, spaces and line breaks",
+            "Every letter must be a pair of digits",
+        ]
+    }
+}
+
+/// Decodes the text line by line, None if it isn't Polybius square coordinates
+fn decode(text: &str) -> Option<String> {
+    if !text
+        .chars()
+        .all(|c| matches!(c, '1'..='6' | ' ' | '\n' | '\r'))
+        || !text.contains(|c: char| c.is_ascii_digit())
+    {
+        return None;
+    }
+    let side = if text.contains('6') { 6 } else { POLYBIUS_SIDE };
+    let lines: Option<Vec<String>> = text.lines().map(|line| decode_line(line, side)).collect();
+    Some(lines?.join("\n"))
+}
+
+/// Decodes one line of coordinates
+/// If every group of digits is one pair they are letters and longer gaps split words,
+/// otherwise every group is a word.
+fn decode_line(line: &str, side: u8) -> Option<String> {
+    let groups: Vec<&str> = line.trim().split(' ').collect();
+    if groups
+        .iter()
+        .all(|group| group.len() == 2 || group.is_empty())
+    {
+        let mut decoded = String::new();
+        for group in groups {
+            if group.is_empty() {
+                if !decoded.ends_with(' ') {
+                    decoded.push(' ');
+                }
+            } else {
+                decoded.push(decode_pair(group.as_bytes(), side)?);
+            }
+        }
+        return Some(decoded);
+    }
+
+    let words: Option<Vec<String>> = line
+        .split_whitespace()
+        .map(|word| {
+            if !word.len().is_multiple_of(2) {
+                return None;
+            }
+            word.as_bytes()
+                .chunks(2)
+                .map(|pair| decode_pair(pair, side))
+                .collect()
+        })
+        .collect();
+    Some(words?.join(" "))
+}
+
+/// The character in the cell the pair of digits points to, rows first
+fn decode_pair(pair: &[u8], side: u8) -> Option<char> {
+    let row = pair[0].checked_sub(b'1')?;
+    let column = pair[1].checked_sub(b'1')?;
+    if row >= side || column >= side {
+        return None;
+    }
+    if side == POLYBIUS_SIDE {
+        Some(index_to_letter(polybius_letter(row, column), true))
+    } else {
+        Some(SQUARE_6X6[(row * side + column) as usize] as char)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checkers::{
+        athena::Athena,
+        checker_type::{Check, Checker},
+        CheckerTypes,
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn successful_decoding() {
+        let decoder = Decoder::<PolybiusDecoder>::new();
+        let (input, expected) = decoder.get_example().unwrap();
+        let result = decoder.crack(input, &get_athena_checker());
+        assert!(result.success);
+        assert_eq!(result.unencrypted_text.unwrap()[0], expected);
+    }
+
+    #[test]
+    fn words_can_be_runs_of_digits() {
+        assert_eq!(
+            decode("2315313134 5234423114").as_deref(),
+            Some("HELLO WORLD")
+        );
+    }
+
+    #[test]
+    fn single_spaced_pairs_are_one_word() {
+        assert_eq!(decode("23 15 31 31 34").as_deref(), Some("HELLO"));
+    }
+
+    #[test]
+    fn six_uses_the_bigger_square() {
+        // In the 6x6 square J has its own cell and the digits follow Z
+        assert_eq!(decode("24 65 66").as_deref(), Some("J89"));
+    }
+
+    #[test]
+    fn lines_are_kept() {
+        assert_eq!(decode("23 15\n54 15").as_deref(), Some("HE\nYE"));
+    }
+
+    #[test]
+    fn odd_runs_of_digits_are_not_decoded() {
+        let decoder = Decoder::<PolybiusDecoder>::new();
+        let result = decoder.crack("231 531", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+
+    #[test]
+    fn digits_outside_the_square_are_not_decoded() {
+        let decoder = Decoder::<PolybiusDecoder>::new();
+        let result = decoder.crack("23 17 08", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+
+    #[test]
+    fn polybius_handles_panic_if_emoji() {
+        let decoder = Decoder::<PolybiusDecoder>::new();
+        let result = decoder.crack("😂", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+}
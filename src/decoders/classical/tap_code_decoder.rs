@@ -0,0 +1,260 @@
+//! Tap code decoder
+//! Tap code is how prisoners spelled messages by knocking on the walls between cells.
+//! Each letter is two counts of knocks, the row and then the column of its cell in a 5x5
+//! square of the alphabet without K, which is sent as C. The counts can be written as
+//! digits from 1 to 5 (`23 15`) or as groups of one knock symbol (`.. ... . .....`).
+//! Digit pairs which are their own groups split words with longer gaps, as in the
+//! Polybius square, and `/` splits words in any form.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::crack_results::CrackResult;
+use crate::decoders::interface::{check_string_success, Crack, Decoder};
+
+use super::POLYBIUS_SIDE;
+
+use log::{debug, info, trace};
+
+/// The cells of the tap code square, read left to right and top to bottom
+const TAP_SQUARE: &[u8; 25] = b"ABCDEFGHIJLMNOPQRSTUVWXYZ";
+
+/// The characters a group of knocks can be written with
+const KNOCKS: [char; 6] = ['.', '*', '•', 'x', '|', '#'];
+
+/// The fewest letters the text must have, as a pair of knocks on its own is no message
+const MIN_LETTERS: usize = 2;
+
+/// The Tap code decoder, call:
+/// `let tap_code_decoder = Decoder::<TapCodeDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = tap_code_decoder.crack(input)` to decode tap code
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::classical::tap_code_decoder::TapCodeDecoder;
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_tap_code = Decoder::<TapCodeDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_tap_code.crack("23 15 31 31 34  52 34 42 31 14", &checker);
+/// assert_eq!(result.unencrypted_text.unwrap()[0], "HELLO WORLD");
+/// let result = decode_tap_code.crack(".. ... . ..... ... . ... . ... ....", &checker);
+/// assert_eq!(result.unencrypted_text.unwrap()[0], "HELLO");
+/// ```
+pub struct TapCodeDecoder;
+
+impl Crack for Decoder<TapCodeDecoder> {
+    fn new() -> Decoder<TapCodeDecoder> {
+        Decoder {
+            name: "Tap code",
+            description: "Tap code spells each letter as two counts of knocks, the row and column of the letter in a 5x5 square of the alphabet where C stands for K too. It was used by prisoners of war to talk through cell walls. The output is in capital letters.",
+            link: "https://en.wikipedia.org/wiki/Tap_code",
+            tags: vec!["tap code", "classical", "cipher", "knocks"],
+            popularity: 0.3,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying tap code with text {:?}", text);
+        let mut results = CrackResult::new(self, text.to_string());
+
+        let Some(decoded_text) = decode(text) else {
+            debug!("Failed to decode tap code");
+            return results;
+        };
+        if !check_string_success(&decoded_text, text) {
+            info!(
+                "Failed to decode tap code because check_string_success returned false on string {}",
+                decoded_text
+            );
+            return results;
+        }
+
+        let checker_result = checker.check(&decoded_text);
+        results.unencrypted_text = Some(vec![decoded_text]);
+        results.update_checker(&checker_result);
+        results
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some(("23 15 31 31 34  52 34 42 31 14", "HELLO WORLD"))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input must be digits from 1 to 5, or groups of up to five of one knock symbol such as `.` or `*`",
+            "Each word must hold an even number of counts, at least two letters in all",
+        ]
+    }
+}
+
+/// Decodes tap code in any of its forms, None if it isn't tap code
+fn decode(text: &str) -> Option<String> {
+    let groups: Vec<&str> = text.split_whitespace().filter(|g| *g != "/").collect();
+    let digits = groups.iter().all(|g| g.bytes().all(|b| b.is_ascii_digit()));
+    let knock = groups.first()?.chars().next()?;
+    if !digits && !groups.iter().all(|g| g.chars().all(|c| c == knock)) {
+        return None;
+    }
+    // Digit pairs standing alone are letters, so a longer gap between them is a word break
+    let pairs = digits && groups.iter().all(|g| g.len() == 2);
+    let words: Vec<&str> = text
+        .split('/')
+        .flat_map(|part| {
+            if pairs {
+                part.split("  ").collect()
+            } else {
+                vec![part]
+            }
+        })
+        .filter(|word| !word.trim().is_empty())
+        .collect();
+
+    let mut letters = 0;
+    let mut decoded = Vec::new();
+    for word in words {
+        let word = decode_word(word, digits)?;
+        letters += word.len();
+        decoded.push(word);
+    }
+    (letters >= MIN_LETTERS).then(|| decoded.join(" "))
+}
+
+/// Decodes one word of counts, written as digits or as groups of knocks
+fn decode_word(word: &str, digits: bool) -> Option<String> {
+    let mut counts = Vec::new();
+    for group in word.split_whitespace() {
+        if digits {
+            counts.extend(group.bytes().map(|b| b - b'0'));
+        } else {
+            if !KNOCKS.contains(&group.chars().next()?) {
+                return None;
+            }
+            counts.push(u8::try_from(group.chars().count()).ok()?);
+        }
+    }
+    if !counts.len().is_multiple_of(2) {
+        return None;
+    }
+    counts
+        .chunks(2)
+        .map(|pair| {
+            let row = pair[0].checked_sub(1)?;
+            let column = pair[1].checked_sub(1)?;
+            if row >= POLYBIUS_SIDE || column >= POLYBIUS_SIDE {
+                return None;
+            }
+            Some(char::from(
+                TAP_SQUARE[usize::from(row * POLYBIUS_SIDE + column)],
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checkers::{
+        athena::Athena,
+        checker_type::{Check, Checker},
+        CheckerTypes,
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn successful_decoding() {
+        let tap_code_decoder = Decoder::<TapCodeDecoder>::new();
+        let result =
+            tap_code_decoder.crack("23 15 31 31 34  52 34 42 31 14", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "HELLO WORLD");
+    }
+
+    #[test]
+    fn knocks_can_be_any_one_symbol() {
+        assert_eq!(
+            decode(".. ... . ..... ... . ... . ... ....").unwrap(),
+            "HELLO"
+        );
+        assert_eq!(
+            decode("** *** * ***** *** * *** * *** ****").unwrap(),
+            "HELLO"
+        );
+        assert!(decode(".. *** . ..... ... . ... . ... ....").is_none());
+    }
+
+    #[test]
+    fn slashes_split_words() {
+        assert_eq!(decode("2315313134 / 5234423114").unwrap(), "HELLO WORLD");
+        assert_eq!(decode("... . / ... .").unwrap(), "L L");
+    }
+
+    #[test]
+    fn k_is_sent_as_c() {
+        // KNOCK is spelled CNOCC
+        assert_eq!(decode("13 33 34 13 13").unwrap(), "CNOCC");
+    }
+
+    #[test]
+    fn counts_above_five_are_not_decoded() {
+        assert!(decode("23 16").is_none());
+        assert!(decode("...... .").is_none());
+    }
+
+    #[test]
+    fn odd_counts_are_not_decoded() {
+        assert!(decode("23 15 3").is_none());
+    }
+
+    #[test]
+    fn morse_dashes_are_not_knocks() {
+        assert!(decode("--- --- ---").is_none());
+    }
+
+    #[test]
+    fn tap_code_handle_panic_if_empty_string() {
+        let tap_code_decoder = Decoder::<TapCodeDecoder>::new();
+        let result = tap_code_decoder
+            .crack("", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn tap_code_handle_panic_if_emoji() {
+        let tap_code_decoder = Decoder::<TapCodeDecoder>::new();
+        let result = tap_code_decoder
+            .crack("😂", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+}
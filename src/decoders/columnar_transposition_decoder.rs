@@ -0,0 +1,385 @@
+//! Crack a columnar transposition cipher
+//! The plaintext is written in rows under a key, and the columns are read out in the
+//! order the key gives. We try every key length from 2 to 9. There are far too many
+//! column orders to try them all for the longer keys, so the order is built up one
+//! column at a time, keeping only the most English-like partial orders by quadgram score.
+//! Uses Low sensitivity for gibberish detection.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::check_string_success;
+use crate::storage::ngrams::ENGLISH_QUADGRAMS;
+use gibberish_or_not::Sensitivity;
+
+use super::alphabet::letter_index;
+use super::crack_results::CrackResult;
+use super::frequency_analysis::{index_of_coincidence, letter_counts, letter_indices};
+use super::interface::Crack;
+use super::interface::Decoder;
+
+use log::{info, trace};
+
+/// The shortest key tried
+const MIN_KEY_LENGTH: usize = 2;
+
+/// The longest key tried
+const MAX_KEY_LENGTH: usize = 9;
+
+/// How many partial column orders are kept at each step
+const BEAM_WIDTH: usize = 256;
+
+/// How many column orders are kept for each key length once the beam finishes
+const KEYS_PER_LENGTH: usize = 3;
+
+/// How many of the best column orders are given to the checker
+const CANDIDATES_TO_CHECK: usize = 5;
+
+/// The fewest letters we will try to decode, quadgram scores are meaningless below this
+const MIN_LETTERS: usize = 16;
+
+/// Transposition keeps every letter, so the ciphertext should have English letter frequencies.
+/// English sits around 0.067 and random letters around 0.038.
+const MIN_INDEX_OF_COINCIDENCE: f64 = 0.05;
+
+/// The columnar transposition decoder, call:
+/// `let columnar_decoder = Decoder::<ColumnarTranspositionDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = columnar_decoder.crack(input)` to decode a columnar transposition string
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::columnar_transposition_decoder::ColumnarTranspositionDecoder;
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_columnar = Decoder::<ColumnarTranspositionDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_columnar.crack("edel aseenw vft ireorcd ceo ae", &checker);
+/// assert_eq!(result.unencrypted_text.unwrap()[0], "we are discovered flee at once");
+/// ```
+pub struct ColumnarTranspositionDecoder;
+
+impl Crack for Decoder<ColumnarTranspositionDecoder> {
+    fn new() -> Decoder<ColumnarTranspositionDecoder> {
+        Decoder {
+            name: "Columnar Transposition",
+            description: "Columnar transposition writes the plaintext in rows under a keyword and reads it out column by column, in the alphabetical order of the keyword's letters. It only moves letters around, so the ciphertext keeps English letter frequencies.",
+            link: "https://en.wikipedia.org/wiki/Transposition_cipher#Columnar_transposition",
+            tags: vec!["columnar", "cipher", "classic", "transposition"],
+            popularity: 0.4,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying columnar transposition with text {:?}", text);
+        let mut results = CrackResult::new(self, text.to_string());
+
+        let letters = letter_indices(text);
+        if letters.len() < MIN_LETTERS {
+            info!("Failed to decode columnar transposition because the text has too few letters");
+            return results;
+        }
+        if index_of_coincidence(&letter_counts(&letters)) < MIN_INDEX_OF_COINCIDENCE {
+            info!("Failed to decode columnar transposition because the letter frequencies aren't English");
+            return results;
+        }
+
+        let chars: Vec<char> = text.chars().collect();
+        // Every column needs at least two letters for the order to mean anything
+        let max_key_length = MAX_KEY_LENGTH.min(chars.len() / 2);
+        let mut candidates: Vec<(Vec<usize>, String, f64)> = (MIN_KEY_LENGTH..=max_key_length)
+            .flat_map(|key_length| best_keys(&chars, key_length))
+            .map(|key| {
+                let decoded_text = decode(&chars, &key);
+                let score = ENGLISH_QUADGRAMS.score(&decoded_text);
+                (key, decoded_text, score)
+            })
+            .filter(|(_, decoded_text, _)| check_string_success(decoded_text, text))
+            .collect();
+        candidates.sort_by(|a, b| b.2.total_cmp(&a.2));
+        candidates.dedup_by(|a, b| a.1 == b.1);
+        candidates.truncate(CANDIDATES_TO_CHECK);
+
+        if candidates.is_empty() {
+            return results;
+        }
+
+        // Use the checker with Low sensitivity, like the other transposition ciphers
+        let checker_with_sensitivity = checker.with_sensitivity(Sensitivity::Low);
+        for (index, (key, decoded_text, _)) in candidates.iter().enumerate() {
+            let checker_result = checker_with_sensitivity.check(decoded_text);
+            if checker_result.is_identified {
+                trace!("Found a match with columnar key {:?}", key);
+                results.key = Some(format_key(key));
+                results.update_checker(&checker_result);
+                candidates.swap(0, index);
+                break;
+            }
+        }
+
+        results.unencrypted_text = Some(candidates.into_iter().map(|c| c.1).collect());
+        results
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some((
+            "edel aseenw vft ireorcd ceo ae",
+            "we are discovered flee at once",
+        ))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input must contain at least 16 letters",
+            "Letter frequencies must look like English (Index of Coincidence of at least 0.05)",
+            "Tries key lengths from 2 to 9, keeping the 64 best partial column orders",
+            "Only the 5 most English-like keys are checked",
+        ]
+    }
+}
+
+/// Finds the most English-like column orders for this key length
+///
+/// `key[column]` is the position the column was read out in. The key is built one
+/// plaintext column at a time, and every partial key is scored on the columns chosen so far.
+fn best_keys(chars: &[char], key_length: usize) -> Vec<Vec<usize>> {
+    let mut beam: Vec<Vec<usize>> = vec![vec![]];
+    for column in 0..key_length {
+        let mut extended: Vec<(Vec<usize>, f64)> = beam
+            .iter()
+            .flat_map(|partial| {
+                (0..key_length)
+                    .filter(|position| !partial.contains(position))
+                    .map(|position| {
+                        let mut key = partial.clone();
+                        key.push(position);
+                        let score = partial_score(chars, &key, key_length, column + 1);
+                        (key, score)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        extended.sort_by(|a, b| b.1.total_cmp(&a.1));
+        extended.truncate(BEAM_WIDTH);
+        beam = extended.into_iter().map(|(key, _)| key).collect();
+    }
+    beam.truncate(KEYS_PER_LENGTH);
+    let mut keys: Vec<Vec<usize>> = beam
+        .into_iter()
+        .map(|key| improve_key(chars, key))
+        .flat_map(|key| rotations(&key))
+        .collect();
+    keys.sort();
+    keys.dedup();
+    keys
+}
+
+/// Every way of shifting the columns of the key round
+/// A decoding which starts a few letters into the first row can score as well as the
+/// real one, so every rotation is passed on and the checker picks between them.
+pub fn rotations(key: &[usize]) -> Vec<Vec<usize>> {
+    (0..key.len())
+        .map(|shift| {
+            let mut rotated = key.to_vec();
+            rotated.rotate_left(shift);
+            rotated
+        })
+        .collect()
+}
+
+/// Swaps and moves columns for as long as that makes the decoding more English-like
+/// The beam only scores the columns chosen so far, so it can leave a run of columns
+/// in the wrong place. Moving one column shifts the run along, swapping fixes neighbours.
+fn improve_key(chars: &[char], mut key: Vec<usize>) -> Vec<usize> {
+    let full_score = |key: &[usize]| ENGLISH_QUADGRAMS.score(&decode(chars, key));
+    let mut best_score = full_score(&key);
+    let mut improved = true;
+    while improved {
+        improved = false;
+        for from in 0..key.len() {
+            for to in 0..key.len() {
+                if from == to {
+                    continue;
+                }
+                let mut candidates = vec![key.clone(), key.clone()];
+                candidates[0].swap(from, to);
+                let column = candidates[1].remove(from);
+                candidates[1].insert(to, column);
+                for candidate in candidates {
+                    let score = full_score(&candidate);
+                    if score > best_score {
+                        best_score = score;
+                        key = candidate;
+                        improved = true;
+                    }
+                }
+            }
+        }
+    }
+    key
+}
+
+/// Scores the first `columns` columns of each row, decoding with the partial key
+/// The columns still to be chosen are filled in in their natural order, which only
+/// affects where the chosen columns start when the last row is incomplete.
+fn partial_score(chars: &[char], partial: &[usize], key_length: usize, columns: usize) -> f64 {
+    let mut key = partial.to_vec();
+    key.extend((0..key_length).filter(|position| !partial.contains(position)));
+    let letters: Vec<u8> = decode(chars, &key)
+        .chars()
+        .collect::<Vec<_>>()
+        .chunks(key_length)
+        .flat_map(|row| row.iter().take(columns))
+        .filter_map(|&c| letter_index(c))
+        .collect();
+    ENGLISH_QUADGRAMS.score_letters(&letters)
+}
+
+/// Undoes the transposition, where `key[column]` is the position the column was read out in
+fn decode(chars: &[char], key: &[usize]) -> String {
+    untranspose(chars, key).into_iter().collect()
+}
+
+/// Puts the symbols back in the order they were written in, where `key[column]` is the
+/// position the column was read out in
+/// Works on any symbols, so ciphers which transpose something other than characters can use it.
+pub fn untranspose<T: Copy>(symbols: &[T], key: &[usize]) -> Vec<T> {
+    let key_length = key.len();
+    let rows = symbols.len().div_ceil(key_length);
+    // Columns left of this are one longer when the last row is incomplete
+    let full_columns = match symbols.len() % key_length {
+        0 => key_length,
+        remainder => remainder,
+    };
+    let column_length = |column: usize| {
+        if column < full_columns {
+            rows
+        } else {
+            rows - 1
+        }
+    };
+
+    let mut read_order: Vec<usize> = (0..key_length).collect();
+    read_order.sort_by_key(|&column| key[column]);
+
+    let mut columns: Vec<&[T]> = vec![&[]; key_length];
+    let mut offset = 0;
+    for column in read_order {
+        let length = column_length(column);
+        columns[column] = &symbols[offset..offset + length];
+        offset += length;
+    }
+
+    (0..rows)
+        .flat_map(|row| columns.iter().filter_map(move |column| column.get(row)))
+        .copied()
+        .collect()
+}
+
+/// Writes the key the way it is usually given, the read out position of each column from 1
+fn format_key(key: &[usize]) -> String {
+    key.iter()
+        .map(|position| (position + 1).to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checkers::{
+        athena::Athena,
+        checker_type::{Check, Checker},
+        CheckerTypes,
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    /// Encrypts the text, where `key[column]` is the position the column is read out in
+    fn encode(text: &str, key: &[usize]) -> String {
+        let chars: Vec<char> = text.chars().collect();
+        let mut read_order: Vec<usize> = (0..key.len()).collect();
+        read_order.sort_by_key(|&column| key[column]);
+        read_order
+            .into_iter()
+            .flat_map(|column| chars.iter().skip(column).step_by(key.len()))
+            .collect()
+    }
+
+    #[test]
+    fn decode_undoes_encode() {
+        let key = [2, 0, 3, 1];
+        let plaintext = "we are discovered flee at once";
+        let ciphertext: Vec<char> = encode(plaintext, &key).chars().collect();
+        assert_eq!(decode(&ciphertext, &key), plaintext);
+    }
+
+    #[test]
+    fn keys_are_written_from_one() {
+        assert_eq!(format_key(&[2, 0, 3, 1]), "3 1 4 2");
+    }
+
+    #[test]
+    fn successful_decoding() {
+        let plaintext = "we are discovered flee at once";
+        let ciphertext = encode(plaintext, &[2, 0, 3, 1, 4, 5]);
+        let decoder = Decoder::<ColumnarTranspositionDecoder>::new();
+        let result = decoder.crack(&ciphertext, &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], plaintext);
+        assert_eq!(result.key.as_deref(), Some("3 1 4 2 5 6"));
+    }
+
+    #[test]
+    fn long_keys_are_found() {
+        let plaintext =
+            "the quick brown fox jumps over the lazy dog and then the dog chases the fox back home";
+        let key = [4, 7, 0, 2, 8, 5, 1, 6, 3];
+        let ciphertext = encode(plaintext, &key);
+        let chars: Vec<char> = ciphertext.chars().collect();
+        assert!(best_keys(&chars, key.len())
+            .iter()
+            .any(|found| decode(&chars, found) == plaintext));
+    }
+
+    #[test]
+    fn text_without_english_letter_frequencies_is_skipped() {
+        let decoder = Decoder::<ColumnarTranspositionDecoder>::new();
+        let result = decoder.crack("zqxjkvbwpyfmgzqxjkvbwpyfmg", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+
+    #[test]
+    fn columnar_handles_panic_if_emoji() {
+        let decoder = Decoder::<ColumnarTranspositionDecoder>::new();
+        let result = decoder.crack("😂", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+}
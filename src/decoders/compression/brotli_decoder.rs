@@ -0,0 +1,260 @@
+//! Decompresses brotli streams
+//! Brotli is the compression browsers accept for HTTP and WOFF2 fonts use. Like raw
+//! deflate it has no magic bytes or checksum, so the bytes are accepted when they
+//! decompress to readable text and the stream ends exactly where the bytes do. Bytes
+//! which start like one of the other compressed formats are left to those decoders.
+//! Call brotli_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::crack_results::CrackResult;
+use crate::decoders::interface::{check_string_success, Crack, Decoder};
+use crate::decoders::radix::bytes_to_text;
+
+use super::bzip2_decoder::is_bzip2_header;
+use super::gzip_decoder::MAGIC as GZIP_MAGIC;
+use super::lzma_decoder::XZ_MAGIC;
+use super::zlib_decoder::is_zlib_header;
+use super::{payloads, MAX_OUTPUT};
+
+use brotli_decompressor::{BrotliDecompressStream, BrotliResult, BrotliState, StandardAlloc};
+use log::{debug, trace};
+
+/// The fewest bytes we try to decompress, as shorter streams too often decompress by chance
+const MIN_LENGTH: usize = 4;
+
+/// The Brotli decoder, call:
+/// `let brotli_decoder = Decoder::<BrotliDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = brotli_decoder.crack(input)` to decompress a brotli stream
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::compression::brotli_decoder::{BrotliDecoder};
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_brotli = Decoder::<BrotliDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_brotli.crack("CwWAaGVsbG8gd29ybGQD", &checker);
+/// assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+/// ```
+pub struct BrotliDecoder;
+
+impl Crack for Decoder<BrotliDecoder> {
+    fn new() -> Decoder<BrotliDecoder> {
+        Decoder {
+            name: "Brotli",
+            description: "Brotli is the compression used for HTTP responses sent with Content-Encoding: br and inside WOFF2 fonts. Its streams have no header or checksum, so they are recognised by decompressing exactly to their end.",
+            link: "https://en.wikipedia.org/wiki/Brotli",
+            tags: vec!["brotli", "decoder", "compression"],
+            popularity: 0.2,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// This function does the actual decoding
+    /// It reads the compressed bytes out of the text as hex, Base64 or raw bytes,
+    /// and decompresses the first reading that is a whole brotli stream.
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying brotli with text {:?}", text);
+        decompress(self, text, payloads(text), checker)
+    }
+    /// Decompresses bytes from the decoder before, which couldn't be passed on as text
+    fn crack_bytes(&self, bytes: &[u8], checker: &CheckerTypes) -> Option<CrackResult> {
+        trace!("Trying brotli with {} bytes", bytes.len());
+        let text = String::from_utf8_lossy(bytes);
+        Some(decompress(
+            self,
+            &text,
+            vec![("raw bytes", bytes.to_vec())],
+            checker,
+        ))
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some(("CwWAaGVsbG8gd29ybGQD", "hello world"))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input must be bytes passed on by the decoder before, hex, Base64, or characters below U+0100 including at least one that isn't printable ASCII",
+            "The bytes must be at least 4 long and not start with a gzip, zlib, bzip2 or xz header",
+            "The bytes must be exactly one brotli stream, decompressing to at most 16 MiB",
+            "Decompressed bytes must be valid UTF-8 without control characters",
+        ]
+    }
+}
+
+/// Decompresses the first of the payloads that works, `text` being what they were read from
+fn decompress(
+    decoder: &Decoder<BrotliDecoder>,
+    text: &str,
+    payloads: Vec<(&'static str, Vec<u8>)>,
+    checker: &CheckerTypes,
+) -> CrackResult {
+    let mut results = CrackResult::new(decoder, text.to_string());
+
+    let decompressed = payloads.into_iter().find_map(|(reading, bytes)| {
+        if bytes.len() < MIN_LENGTH
+            || bytes.starts_with(&GZIP_MAGIC)
+            || is_zlib_header(&bytes)
+            || is_bzip2_header(&bytes)
+            || bytes.starts_with(&XZ_MAGIC)
+        {
+            return None;
+        }
+        Some((reading, bytes_to_text(decompress_stream(&bytes)?)?))
+    });
+    let Some((reading, decoded_text)) = decompressed else {
+        debug!(
+            "Failed to decompress brotli because no reading of the text is a brotli stream of text"
+        );
+        return results;
+    };
+    if !check_string_success(&decoded_text, text) {
+        debug!("Failed to decompress brotli because check_string_success returned false");
+        return results;
+    }
+
+    results.description = format!(
+        "{} The compressed data was read as {}.",
+        decoder.description, reading
+    );
+    let checker_result = checker.check(&decoded_text);
+    results.unencrypted_text = Some(vec![decoded_text]);
+
+    results.update_checker(&checker_result);
+
+    results
+}
+
+/// Decompresses the bytes as one brotli stream, None if they aren't exactly one or it is too long
+fn decompress_stream(bytes: &[u8]) -> Option<Vec<u8>> {
+    let mut state = BrotliState::new(
+        StandardAlloc::default(),
+        StandardAlloc::default(),
+        StandardAlloc::default(),
+    );
+    let mut output = Vec::new();
+    let mut buffer = [0u8; 4096];
+    let mut available_in = bytes.len();
+    let mut input_offset = 0;
+    let mut total_out = 0;
+    loop {
+        let mut available_out = buffer.len();
+        let mut output_offset = 0;
+        let result = BrotliDecompressStream(
+            &mut available_in,
+            &mut input_offset,
+            bytes,
+            &mut available_out,
+            &mut output_offset,
+            &mut buffer,
+            &mut total_out,
+            &mut state,
+        );
+        output.extend_from_slice(&buffer[..output_offset]);
+        if output.len() as u64 > MAX_OUTPUT {
+            return None;
+        }
+        match result {
+            BrotliResult::NeedsMoreOutput => continue,
+            // Anything left over means the bytes only started like a brotli stream
+            BrotliResult::ResultSuccess if available_in == 0 => return Some(output),
+            _ => return None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decompress_stream, BrotliDecoder};
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn successful_decoding() {
+        let brotli_decoder = Decoder::<BrotliDecoder>::new();
+        let result = brotli_decoder.crack("CwWAaGVsbG8gd29ybGQD", &get_athena_checker());
+        assert!(result.success);
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+        assert!(result.description.contains("read as Base64"));
+    }
+
+    #[test]
+    fn hex_is_decompressed() {
+        let brotli_decoder = Decoder::<BrotliDecoder>::new();
+        let result = brotli_decoder.crack(
+            "0b158054686520717569636b2062726f776e20666f78206a756d7073206f76657220746865206c617a7920646f6703",
+            &get_athena_checker(),
+        );
+        assert_eq!(
+            result.unencrypted_text.unwrap()[0],
+            "The quick brown fox jumps over the lazy dog"
+        );
+        assert!(result.description.contains("read as hex"));
+    }
+
+    #[test]
+    fn trailing_bytes_are_rejected() {
+        let mut stream = vec![
+            0x0b, 0x05, 0x80, 0x68, 0x65, 0x6c, 0x6c, 0x6f, 0x20, 0x77, 0x6f, 0x72, 0x6c, 0x64,
+            0x03,
+        ];
+        assert_eq!(decompress_stream(&stream), Some(b"hello world".to_vec()));
+        stream.push(0x00);
+        assert_eq!(decompress_stream(&stream), None);
+    }
+
+    #[test]
+    fn ordinary_text_is_not_decompressed() {
+        let brotli_decoder = Decoder::<BrotliDecoder>::new();
+        let result = brotli_decoder
+            .crack("hello world", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn brotli_handle_panic_if_emoji() {
+        let brotli_decoder = Decoder::<BrotliDecoder>::new();
+        let result = brotli_decoder
+            .crack("😂", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+}
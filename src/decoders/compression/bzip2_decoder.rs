@@ -0,0 +1,224 @@
+//! Decompresses bzip2 files
+//! A bzip2 file starts with the letters BZh and a digit from 1 to 9 giving the block size,
+//! then blocks of Burrows-Wheeler transformed data each with a CRC32, and a CRC32 of the
+//! whole file at the end. Files made of several bzip2 streams one after another are
+//! decompressed as one.
+//! Call bzip2_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::crack_results::CrackResult;
+use crate::decoders::interface::{check_string_success, Crack, Decoder};
+use crate::decoders::radix::bytes_to_text;
+
+use super::{payloads, read_all};
+
+use bzip2::read::MultiBzDecoder;
+use log::{debug, trace};
+
+/// The bytes every bzip2 file starts with, before the block size digit
+pub const MAGIC: [u8; 3] = *b"BZh";
+
+/// The Bzip2 decoder, call:
+/// `let bzip2_decoder = Decoder::<Bzip2Decoder>::new()` to create a new instance
+/// And then call:
+/// `result = bzip2_decoder.crack(input)` to decompress a bzip2 file
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::compression::bzip2_decoder::{Bzip2Decoder};
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_bzip2 = Decoder::<Bzip2Decoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_bzip2.crack("QlpoOTFBWSZTWUT3E3gAAAGRgEAABkSQgCAAIgM0hDAhtoFUJ4u5IpwoSCJ7ibwA", &checker);
+/// assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+/// assert!(result.description.contains("Base64"));
+/// ```
+pub struct Bzip2Decoder;
+
+impl Crack for Decoder<Bzip2Decoder> {
+    fn new() -> Decoder<Bzip2Decoder> {
+        Decoder {
+            name: "Bzip2",
+            description: "Bzip2 compresses data with the Burrows-Wheeler transform in blocks of up to 900 kB. Its files start with the letters BZh and a digit for the block size, and it is used for .bz2 and .tar.bz2 files and Wikipedia's dumps.",
+            link: "https://en.wikipedia.org/wiki/Bzip2",
+            tags: vec!["bzip2", "decoder", "compression"],
+            popularity: 0.2,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// This function does the actual decoding
+    /// It reads the compressed bytes out of the text as hex, Base64 or raw bytes,
+    /// and decompresses the first reading that starts like a bzip2 file.
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying bzip2 with text {:?}", text);
+        decompress(self, text, payloads(text), checker)
+    }
+    /// Decompresses bytes from the decoder before, which couldn't be passed on as text
+    fn crack_bytes(&self, bytes: &[u8], checker: &CheckerTypes) -> Option<CrackResult> {
+        trace!("Trying bzip2 with {} bytes", bytes.len());
+        let text = String::from_utf8_lossy(bytes);
+        Some(decompress(
+            self,
+            &text,
+            vec![("raw bytes", bytes.to_vec())],
+            checker,
+        ))
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some((
+            "QlpoOTFBWSZTWUT3E3gAAAGRgEAABkSQgCAAIgM0hDAhtoFUJ4u5IpwoSCJ7ibwA",
+            "hello world",
+        ))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input must be bytes passed on by the decoder before, hex, Base64, or characters below U+0100 including at least one that isn't printable ASCII",
+            "The bytes must start with BZh and a block size digit from 1 to 9",
+            "The data must decompress with matching CRC32s, to at most 16 MiB",
+            "Decompressed bytes must be valid UTF-8 without control characters",
+        ]
+    }
+}
+
+/// Decompresses the first of the payloads that works, `text` being what they were read from
+fn decompress(
+    decoder: &Decoder<Bzip2Decoder>,
+    text: &str,
+    payloads: Vec<(&'static str, Vec<u8>)>,
+    checker: &CheckerTypes,
+) -> CrackResult {
+    let mut results = CrackResult::new(decoder, text.to_string());
+
+    let decompressed = payloads.into_iter().find_map(|(reading, bytes)| {
+        if !is_bzip2_header(&bytes) {
+            return None;
+        }
+        let decoded_text = bytes_to_text(read_all(MultiBzDecoder::new(&bytes[..]))?)?;
+        Some((reading, decoded_text))
+    });
+    let Some((reading, decoded_text)) = decompressed else {
+        debug!("Failed to decompress bzip2 because no reading of the text is a bzip2 file of text");
+        return results;
+    };
+    if !check_string_success(&decoded_text, text) {
+        debug!("Failed to decompress bzip2 because check_string_success returned false");
+        return results;
+    }
+
+    results.description = format!(
+        "{} The compressed data was read as {}.",
+        decoder.description, reading
+    );
+    let checker_result = checker.check(&decoded_text);
+    results.unencrypted_text = Some(vec![decoded_text]);
+
+    results.update_checker(&checker_result);
+
+    results
+}
+
+/// Whether the bytes start with BZh and a block size bzip2 can write
+pub fn is_bzip2_header(bytes: &[u8]) -> bool {
+    bytes.starts_with(&MAGIC)
+        && bytes
+            .get(MAGIC.len())
+            .is_some_and(|b| (b'1'..=b'9').contains(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_bzip2_header, Bzip2Decoder};
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn successful_decoding() {
+        let bzip2_decoder = Decoder::<Bzip2Decoder>::new();
+        let result = bzip2_decoder.crack(
+            "QlpoOTFBWSZTWUT3E3gAAAGRgEAABkSQgCAAIgM0hDAhtoFUJ4u5IpwoSCJ7ibwA",
+            &get_athena_checker(),
+        );
+        assert!(result.success);
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+        assert!(result.description.contains("read as Base64"));
+    }
+
+    #[test]
+    fn hex_is_decompressed() {
+        let bzip2_decoder = Decoder::<Bzip2Decoder>::new();
+        let result = bzip2_decoder.crack(
+            "425a683931415926535944f7137800000191804000064490802000220334843021b68154278bb9229c2848227b89bc00",
+            &get_athena_checker(),
+        );
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+        assert!(result.description.contains("read as hex"));
+    }
+
+    #[test]
+    fn headers_are_checked() {
+        assert!(is_bzip2_header(b"BZh9"));
+        assert!(!is_bzip2_header(b"BZh0"));
+        assert!(!is_bzip2_header(b"BZh"));
+    }
+
+    #[test]
+    fn corrupted_data_is_rejected() {
+        // The last byte of the block's CRC32 is changed
+        let bzip2_decoder = Decoder::<Bzip2Decoder>::new();
+        let result = bzip2_decoder
+            .crack(
+                "425a683931415926535944f7137900000191804000064490802000220334843021b68154278bb9229c2848227b89bc00",
+                &get_athena_checker(),
+            )
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn bzip2_handle_panic_if_emoji() {
+        let bzip2_decoder = Decoder::<Bzip2Decoder>::new();
+        let result = bzip2_decoder
+            .crack("😂", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+}
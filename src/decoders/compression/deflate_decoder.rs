@@ -0,0 +1,219 @@
+//! Inflates raw deflate streams
+//! Raw deflate has no header or checksum, as used inside zip files and by tools which
+//! strip the zlib wrapper, so nothing marks it out. Instead the bytes are accepted when
+//! they inflate to readable text and the stream ends exactly where the bytes do, which
+//! random data almost never does. Bytes with a gzip or zlib header are left to those decoders.
+//! Call deflate_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::crack_results::CrackResult;
+use crate::decoders::interface::{check_string_success, Crack, Decoder};
+use crate::decoders::radix::bytes_to_text;
+
+use super::gzip_decoder::MAGIC as GZIP_MAGIC;
+use super::zlib_decoder::is_zlib_header;
+use super::{payloads, read_all};
+
+use flate2::read::DeflateDecoder as Inflater;
+use log::{debug, trace};
+
+/// The fewest bytes we try to inflate, as shorter streams too often inflate by chance
+const MIN_LENGTH: usize = 4;
+
+/// The Deflate decoder, call:
+/// `let deflate_decoder = Decoder::<DeflateDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = deflate_decoder.crack(input)` to inflate a raw deflate stream
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::compression::deflate_decoder::{DeflateDecoder};
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_deflate = Decoder::<DeflateDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_deflate.crack("y0jNyclXKM8vykkBAA==", &checker);
+/// assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+/// ```
+pub struct DeflateDecoder;
+
+impl Crack for Decoder<DeflateDecoder> {
+    fn new() -> Decoder<DeflateDecoder> {
+        Decoder {
+            name: "Deflate",
+            description: "Deflate is the LZ77 and Huffman coding compression behind zip, gzip, zlib and PNG. Raw deflate streams have no header or checksum, so they are recognised by inflating cleanly to text.",
+            link: "https://en.wikipedia.org/wiki/Deflate",
+            tags: vec!["deflate", "decoder", "compression"],
+            popularity: 0.2,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// This function does the actual decoding
+    /// It reads the compressed bytes out of the text as hex, Base64 or raw bytes,
+    /// and inflates the first reading that is exactly one deflate stream of text.
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying raw deflate with text {:?}", text);
+        decompress(self, text, payloads(text), checker)
+    }
+    /// Decompresses bytes from the decoder before, which couldn't be passed on as text
+    fn crack_bytes(&self, bytes: &[u8], checker: &CheckerTypes) -> Option<CrackResult> {
+        trace!("Trying raw deflate with {} bytes", bytes.len());
+        let text = String::from_utf8_lossy(bytes);
+        Some(decompress(
+            self,
+            &text,
+            vec![("raw bytes", bytes.to_vec())],
+            checker,
+        ))
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some(("y0jNyclXKM8vykkBAA==", "hello world"))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input must be bytes passed on by the decoder before, hex, Base64, or characters below U+0100 including at least one that isn't printable ASCII",
+            "The bytes must be at least 4 long and not start with a gzip or zlib header",
+            "The bytes must be exactly one deflate stream, inflating to at most 16 MiB",
+            "Decompressed bytes must be valid UTF-8 without control characters",
+        ]
+    }
+}
+
+/// Decompresses the first of the payloads that works, `text` being what they were read from
+fn decompress(
+    decoder: &Decoder<DeflateDecoder>,
+    text: &str,
+    payloads: Vec<(&'static str, Vec<u8>)>,
+    checker: &CheckerTypes,
+) -> CrackResult {
+    let mut results = CrackResult::new(decoder, text.to_string());
+
+    let decompressed = payloads.into_iter().find_map(|(reading, bytes)| {
+        if bytes.len() < MIN_LENGTH || bytes.starts_with(&GZIP_MAGIC) || is_zlib_header(&bytes) {
+            return None;
+        }
+        let mut inflater = Inflater::new(&bytes[..]);
+        let inflated = read_all(&mut inflater)?;
+        // Anything left over means the bytes only started like a deflate stream
+        if inflater.total_in() != bytes.len() as u64 {
+            return None;
+        }
+        Some((reading, bytes_to_text(inflated)?))
+    });
+    let Some((reading, decoded_text)) = decompressed else {
+        debug!("Failed to inflate because no reading of the text is a deflate stream of text");
+        return results;
+    };
+    if !check_string_success(&decoded_text, text) {
+        debug!("Failed to inflate because check_string_success returned false");
+        return results;
+    }
+
+    results.description = format!(
+        "{} The compressed data was read as {}.",
+        decoder.description, reading
+    );
+    let checker_result = checker.check(&decoded_text);
+    results.unencrypted_text = Some(vec![decoded_text]);
+
+    results.update_checker(&checker_result);
+
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DeflateDecoder;
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn successful_decoding() {
+        let deflate_decoder = Decoder::<DeflateDecoder>::new();
+        let result = deflate_decoder.crack(
+            "C8lIVSgszUzOVkgqyi/PU0jLr1DIKs0tKFbIL0stUigBSuckVlUqpOSnAwA=",
+            &get_athena_checker(),
+        );
+        assert!(result.success);
+        assert_eq!(
+            result.unencrypted_text.unwrap()[0],
+            "The quick brown fox jumps over the lazy dog"
+        );
+    }
+
+    #[test]
+    fn raw_bytes_are_inflated() {
+        let deflate_decoder = Decoder::<DeflateDecoder>::new();
+        let result = deflate_decoder.crack(
+            "\u{cb}H\u{cd}\u{c9}\u{c9}W(\u{cf}/\u{ca}I\u{1}\u{0}",
+            &get_athena_checker(),
+        );
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+        assert!(result.description.contains("read as raw bytes"));
+    }
+
+    #[test]
+    fn trailing_bytes_are_rejected() {
+        let deflate_decoder = Decoder::<DeflateDecoder>::new();
+        let result = deflate_decoder
+            .crack("cb48cdc9c95728cf2fca49010000", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn zlib_streams_are_left_to_the_zlib_decoder() {
+        let deflate_decoder = Decoder::<DeflateDecoder>::new();
+        let result = deflate_decoder
+            .crack("eJzLSM3JyVcozy/KSQEAGgsEXQ==", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn deflate_handle_panic_if_emoji() {
+        let deflate_decoder = Decoder::<DeflateDecoder>::new();
+        let result = deflate_decoder
+            .crack("😂", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+}
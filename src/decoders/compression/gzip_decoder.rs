@@ -0,0 +1,249 @@
+//! Decompresses gzip files
+//! A gzip file starts with the bytes 1F 8B and the deflate method 08, then a header which
+//! may hold the original file name, then a deflate stream and a CRC32 of the data.
+//! Files made of several gzip members one after another are decompressed as one.
+//! Call gzip_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::crack_results::CrackResult;
+use crate::decoders::interface::{check_string_success, Crack, Decoder};
+use crate::decoders::radix::bytes_to_text;
+
+use super::{payloads, read_all};
+
+use flate2::read::MultiGzDecoder;
+use log::{debug, trace};
+
+/// The bytes every gzip file starts with, the last one being the deflate method
+pub const MAGIC: [u8; 3] = [0x1F, 0x8B, 0x08];
+
+/// The Gzip decoder, call:
+/// `let gzip_decoder = Decoder::<GzipDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = gzip_decoder.crack(input)` to decompress a gzip file
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::compression::gzip_decoder::{GzipDecoder};
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_gzip = Decoder::<GzipDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_gzip.crack("H4sIAAAAAAACA8tIzcnJVyjPL8pJAQCFEUoNCwAAAA==", &checker);
+/// assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+/// assert!(result.description.contains("Base64"));
+/// ```
+pub struct GzipDecoder;
+
+impl Crack for Decoder<GzipDecoder> {
+    fn new() -> Decoder<GzipDecoder> {
+        Decoder {
+            name: "Gzip",
+            description: "Gzip is the deflate compression used by gzip, HTTP and .tar.gz files, with a header that starts with the bytes 1F 8B and may hold the original file name, and a CRC32 of the data at the end.",
+            link: "https://en.wikipedia.org/wiki/Gzip",
+            tags: vec!["gzip", "decoder", "compression"],
+            popularity: 0.4,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// This function does the actual decoding
+    /// It reads the compressed bytes out of the text as hex, Base64 or raw bytes,
+    /// and decompresses the first reading that starts like a gzip file.
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying gzip with text {:?}", text);
+        decompress(self, text, payloads(text), checker)
+    }
+    /// Decompresses bytes from the decoder before, which couldn't be passed on as text
+    fn crack_bytes(&self, bytes: &[u8], checker: &CheckerTypes) -> Option<CrackResult> {
+        trace!("Trying gzip with {} bytes", bytes.len());
+        let text = String::from_utf8_lossy(bytes);
+        Some(decompress(
+            self,
+            &text,
+            vec![("raw bytes", bytes.to_vec())],
+            checker,
+        ))
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some((
+            "H4sIAAAAAAACA8tIzcnJVyjPL8pJAQCFEUoNCwAAAA==",
+            "hello world",
+        ))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input must be bytes passed on by the decoder before, hex, Base64, or characters below U+0100 including at least one that isn't printable ASCII",
+            "The bytes must start with the gzip magic bytes 1F 8B 08",
+            "The data must decompress with a matching CRC32, to at most 16 MiB",
+            "Decompressed bytes must be valid UTF-8 without control characters",
+        ]
+    }
+}
+
+/// Decompresses the first of the payloads that works, `text` being what they were read from
+fn decompress(
+    decoder: &Decoder<GzipDecoder>,
+    text: &str,
+    payloads: Vec<(&'static str, Vec<u8>)>,
+    checker: &CheckerTypes,
+) -> CrackResult {
+    let mut results = CrackResult::new(decoder, text.to_string());
+
+    let decompressed = payloads.into_iter().find_map(|(reading, bytes)| {
+        if !bytes.starts_with(&MAGIC) {
+            return None;
+        }
+        let mut gzip = MultiGzDecoder::new(&bytes[..]);
+        let decoded_text = bytes_to_text(read_all(&mut gzip)?)?;
+        let name = gzip
+            .header()
+            .and_then(|header| header.filename())
+            .map(|name| String::from_utf8_lossy(name).to_string());
+        Some((reading, name, decoded_text))
+    });
+    let Some((reading, name, decoded_text)) = decompressed else {
+        debug!("Failed to decompress gzip because no reading of the text is a gzip file of text");
+        return results;
+    };
+    if !check_string_success(&decoded_text, text) {
+        debug!("Failed to decompress gzip because check_string_success returned false");
+        return results;
+    }
+
+    results.description = format!(
+        "{} The compressed data was read as {}.",
+        decoder.description, reading
+    );
+    if let Some(name) = name {
+        results.description = format!("{} The file was named {}.", results.description, name);
+    }
+    let checker_result = checker.check(&decoded_text);
+    results.unencrypted_text = Some(vec![decoded_text]);
+
+    results.update_checker(&checker_result);
+
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GzipDecoder;
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn successful_decoding() {
+        let gzip_decoder = Decoder::<GzipDecoder>::new();
+        let result = gzip_decoder.crack(
+            "H4sIAAAAAAACA8tIzcnJVyjPL8pJAQCFEUoNCwAAAA==",
+            &get_athena_checker(),
+        );
+        assert!(result.success);
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+        assert!(result.description.contains("read as Base64"));
+    }
+
+    #[test]
+    fn hex_is_decompressed() {
+        let gzip_decoder = Decoder::<GzipDecoder>::new();
+        let result = gzip_decoder.crack(
+            "1f8b0800000000000203cb48cdc9c95728cf2fca49010085114a0d0b000000",
+            &get_athena_checker(),
+        );
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+        assert!(result.description.contains("read as hex"));
+    }
+
+    #[test]
+    fn bytes_are_decompressed() {
+        let gzip_decoder = Decoder::<GzipDecoder>::new();
+        let bytes = [
+            0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x03, 0xcb, 0x48, 0xcd, 0xc9,
+            0xc9, 0x57, 0x28, 0xcf, 0x2f, 0xca, 0x49, 0x01, 0x00, 0x85, 0x11, 0x4a, 0x0d, 0x0b,
+            0x00, 0x00, 0x00,
+        ];
+        let result = gzip_decoder
+            .crack_bytes(&bytes, &get_athena_checker())
+            .unwrap();
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+    }
+
+    #[test]
+    fn file_names_are_reported() {
+        let gzip_decoder = Decoder::<GzipDecoder>::new();
+        let result = gzip_decoder.crack(
+            "H4sICAAAAAAC/2hlbGxvLnR4dADLSM3JyVcozy/KSQEAhRFKDQsAAAA=",
+            &get_athena_checker(),
+        );
+        assert!(result.description.contains("hello.txt"));
+    }
+
+    #[test]
+    fn corrupted_data_is_rejected() {
+        // The last byte of the CRC32 is changed
+        let gzip_decoder = Decoder::<GzipDecoder>::new();
+        let result = gzip_decoder
+            .crack(
+                "1f8b0800000000000203cb48cdc9c95728cf2fca49010085114a0e0b000000",
+                &get_athena_checker(),
+            )
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn gzip_handle_panic_if_empty_string() {
+        let gzip_decoder = Decoder::<GzipDecoder>::new();
+        let result = gzip_decoder
+            .crack("", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn gzip_handle_panic_if_emoji() {
+        let gzip_decoder = Decoder::<GzipDecoder>::new();
+        let result = gzip_decoder
+            .crack("😂", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+}
@@ -0,0 +1,258 @@
+//! Decompresses xz and lzma files
+//! Both hold LZMA compressed data. An xz file starts with the bytes FD 37 7A 58 5A 00 and
+//! keeps its data in blocks with checksums, as xz and 7-Zip write it. The older .lzma
+//! format has no magic bytes, just a 13 byte header of the coder's settings, dictionary
+//! size and uncompressed size, so it is only tried when that header holds the values the
+//! lzma tools write.
+//! Call lzma_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::crack_results::CrackResult;
+use crate::decoders::interface::{check_string_success, Crack, Decoder};
+use crate::decoders::radix::bytes_to_text;
+
+use super::{payloads, BoundedOutput, MAX_OUTPUT};
+
+use log::{debug, trace};
+use lzma_rs::decompress::Options;
+
+/// The bytes every xz file starts with
+pub const XZ_MAGIC: [u8; 6] = [0xFD, 0x37, 0x7A, 0x58, 0x5A, 0x00];
+
+/// The largest settings byte, as the literal context, literal position and position bits
+/// are packed into it as `(pb * 5 + lp) * 9 + lc`
+const MAX_PROPERTIES: u8 = 9 * 5 * 5 - 1;
+
+/// The uncompressed size .lzma files have when it wasn't known and the data ends with a marker
+const UNKNOWN_SIZE: u64 = u64::MAX;
+
+/// The LZMA decoder, call:
+/// `let lzma_decoder = Decoder::<LzmaDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = lzma_decoder.crack(input)` to decompress an xz or lzma file
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::compression::lzma_decoder::{LzmaDecoder};
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_lzma = Decoder::<LzmaDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_lzma.crack("/Td6WFoAAATm1rRGAgAhARYAAAB0L+WjAQAKaGVsbG8gd29ybGQAANpSI+/NfgNTAAEjC8Ib/QkftvN9AQAAAAAEWVo=", &checker);
+/// assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+/// assert!(result.description.contains("xz file"));
+/// ```
+pub struct LzmaDecoder;
+
+impl Crack for Decoder<LzmaDecoder> {
+    fn new() -> Decoder<LzmaDecoder> {
+        Decoder {
+            name: "LZMA",
+            description: "LZMA is the compression used by xz and 7-Zip. Xz files start with the bytes FD 37 7A 58 5A 00 and are used for .xz and .tar.xz files, while the older .lzma files start with a header of the compression settings.",
+            link: "https://en.wikipedia.org/wiki/Lempel%E2%80%93Ziv%E2%80%93Markov_chain_algorithm",
+            tags: vec!["lzma", "xz", "decoder", "compression"],
+            popularity: 0.2,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// This function does the actual decoding
+    /// It reads the compressed bytes out of the text as hex, Base64 or raw bytes,
+    /// and decompresses the first reading that starts like an xz or lzma file.
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying LZMA with text {:?}", text);
+        decompress(self, text, payloads(text), checker)
+    }
+    /// Decompresses bytes from the decoder before, which couldn't be passed on as text
+    fn crack_bytes(&self, bytes: &[u8], checker: &CheckerTypes) -> Option<CrackResult> {
+        trace!("Trying LZMA with {} bytes", bytes.len());
+        let text = String::from_utf8_lossy(bytes);
+        Some(decompress(
+            self,
+            &text,
+            vec![("raw bytes", bytes.to_vec())],
+            checker,
+        ))
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some((
+            "/Td6WFoAAATm1rRGAgAhARYAAAB0L+WjAQAKaGVsbG8gd29ybGQAANpSI+/NfgNTAAEjC8Ib/QkftvN9AQAAAAAEWVo=",
+            "hello world",
+        ))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input must be bytes passed on by the decoder before, hex, Base64, or characters below U+0100 including at least one that isn't printable ASCII",
+            "The bytes must start with the xz magic bytes FD 37 7A 58 5A 00, or with a .lzma header whose dictionary size is one the lzma tools write",
+            "The data must decompress, with matching checksums for xz, to at most 16 MiB",
+            "Decompressed bytes must be valid UTF-8 without control characters",
+        ]
+    }
+}
+
+/// Decompresses the first of the payloads that works, `text` being what they were read from
+fn decompress(
+    decoder: &Decoder<LzmaDecoder>,
+    text: &str,
+    payloads: Vec<(&'static str, Vec<u8>)>,
+    checker: &CheckerTypes,
+) -> CrackResult {
+    let mut results = CrackResult::new(decoder, text.to_string());
+
+    let decompressed = payloads.into_iter().find_map(|(reading, bytes)| {
+        let mut output = BoundedOutput::default();
+        let format = if bytes.starts_with(&XZ_MAGIC) {
+            lzma_rs::xz_decompress(&mut &bytes[..], &mut output).ok()?;
+            "xz file"
+        } else if is_lzma_header(&bytes) {
+            let options = Options {
+                memlimit: Some(MAX_OUTPUT as usize),
+                ..Options::default()
+            };
+            lzma_rs::lzma_decompress_with_options(&mut &bytes[..], &mut output, &options).ok()?;
+            "lzma file"
+        } else {
+            return None;
+        };
+        Some((reading, format, bytes_to_text(output.0)?))
+    });
+    let Some((reading, format, decoded_text)) = decompressed else {
+        debug!("Failed to decompress LZMA because no reading of the text is an xz or lzma file of text");
+        return results;
+    };
+    if !check_string_success(&decoded_text, text) {
+        debug!("Failed to decompress LZMA because check_string_success returned false");
+        return results;
+    }
+
+    results.description = format!(
+        "{} The compressed data was an {} read as {}.",
+        decoder.description, format, reading
+    );
+    let checker_result = checker.check(&decoded_text);
+    results.unencrypted_text = Some(vec![decoded_text]);
+
+    results.update_checker(&checker_result);
+
+    results
+}
+
+/// Whether the bytes start with a .lzma header like the lzma tools write
+/// The settings must be valid, the dictionary size a power of two or halfway between two,
+/// and the uncompressed size either unknown or no more than we would decompress.
+pub fn is_lzma_header(bytes: &[u8]) -> bool {
+    let Some(header) = bytes.get(..13) else {
+        return false;
+    };
+    let dictionary = u32::from_le_bytes([header[1], header[2], header[3], header[4]]);
+    let size = u64::from_le_bytes([
+        header[5], header[6], header[7], header[8], header[9], header[10], header[11], header[12],
+    ]);
+    let power = dictionary.checked_next_power_of_two();
+    let tool_dictionary = dictionary >= 4096
+        && (dictionary.is_power_of_two() || power.is_some_and(|power| power / 4 * 3 == dictionary));
+    header[0] <= MAX_PROPERTIES && tool_dictionary && (size == UNKNOWN_SIZE || size <= MAX_OUTPUT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_lzma_header, LzmaDecoder};
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn successful_decoding() {
+        let lzma_decoder = Decoder::<LzmaDecoder>::new();
+        let result = lzma_decoder.crack(
+            "/Td6WFoAAATm1rRGAgAhARYAAAB0L+WjAQAKaGVsbG8gd29ybGQAANpSI+/NfgNTAAEjC8Ib/QkftvN9AQAAAAAEWVo=",
+            &get_athena_checker(),
+        );
+        assert!(result.success);
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+        assert!(result.description.contains("an xz file read as Base64"));
+    }
+
+    #[test]
+    fn lzma_files_are_decompressed() {
+        let lzma_decoder = Decoder::<LzmaDecoder>::new();
+        let result = lzma_decoder.crack(
+            "5d00008000ffffffffffffffff00341949ee8de917893a336005f7cf64fffb782000",
+            &get_athena_checker(),
+        );
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+        assert!(result.description.contains("an lzma file read as hex"));
+    }
+
+    #[test]
+    fn lzma_headers_are_checked() {
+        let header = |dictionary: u32| {
+            let mut header = vec![0x5D];
+            header.extend(dictionary.to_le_bytes());
+            header.extend(u64::MAX.to_le_bytes());
+            header
+        };
+        assert!(is_lzma_header(&header(1 << 23)));
+        assert!(is_lzma_header(&header(3 << 22)));
+        assert!(!is_lzma_header(&header(12345)));
+        assert!(!is_lzma_header(&header(1 << 23)[..12]));
+    }
+
+    #[test]
+    fn wrong_checksums_are_rejected() {
+        // The first byte of the xz block's CRC64 is changed
+        let lzma_decoder = Decoder::<LzmaDecoder>::new();
+        let result = lzma_decoder
+            .crack(
+                "fd377a585a000004e6d6b4460200210116000000742fe5a301000a68656c6c6f20776f726c640000db5223efcd7e03530001230bc21bfd091fb6f37d010000000004595a",
+                &get_athena_checker(),
+            )
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn lzma_handle_panic_if_emoji() {
+        let lzma_decoder = Decoder::<LzmaDecoder>::new();
+        let result = lzma_decoder
+            .crack("😂", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+}
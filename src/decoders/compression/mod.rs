@@ -0,0 +1,109 @@
+//! Decoders which decompress gzip, zlib, raw deflate, bzip2, brotli and LZMA payloads
+//! Compressed data usually reaches these decoders as bytes passed on by the decoder
+//! before, through `crack_bytes`. Text is read too: each decoder reads the payload from
+//! hex, from Base64, or from text whose characters are all below U+0100 and include a
+//! byte no text would have, taking each character as one byte. Either way the
+//! decompressed text carries on through the search like any other decoder's output.
+
+/// The brotli_decoder module decompresses brotli streams
+pub mod brotli_decoder;
+/// The bzip2_decoder module decompresses bzip2 files
+pub mod bzip2_decoder;
+/// The deflate_decoder module inflates raw deflate streams
+pub mod deflate_decoder;
+/// The gzip_decoder module decompresses gzip files
+pub mod gzip_decoder;
+/// The lzma_decoder module decompresses xz and lzma files
+pub mod lzma_decoder;
+/// The zlib_decoder module decompresses zlib streams
+pub mod zlib_decoder;
+
+use std::io::{self, Read, Write};
+
+use base64::engine::general_purpose::{STANDARD, URL_SAFE};
+use base64::Engine;
+
+use crate::decoders::radix::{chars_as_bytes, decode_hex};
+
+/// The most we decompress, so a small zip bomb can't use up all the memory
+pub const MAX_OUTPUT: u64 = 16 * 1024 * 1024;
+
+/// The ways of reading bytes out of the text, with a name for each to put in descriptions
+/// Readings that don't apply to the text are left out, so the list is often empty.
+pub fn payloads(text: &str) -> Vec<(&'static str, Vec<u8>)> {
+    let mut payloads = Vec::new();
+    let compact: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    if let Some(bytes) = decode_hex(&compact) {
+        payloads.push(("hex", bytes));
+    }
+    if let Ok(bytes) = STANDARD
+        .decode(&compact)
+        .or_else(|_| URL_SAFE.decode(&compact))
+    {
+        payloads.push(("Base64", bytes));
+    }
+    let binary = text
+        .chars()
+        .any(|c| !c.is_ascii() || (c.is_control() && !c.is_ascii_whitespace()));
+    if binary {
+        if let Some(bytes) = chars_as_bytes(text) {
+            payloads.push(("raw bytes", bytes));
+        }
+    }
+    payloads
+}
+
+/// Reads everything a decompressor gives, None if the data is broken or too long
+pub fn read_all(reader: impl Read) -> Option<Vec<u8>> {
+    let mut output = Vec::new();
+    reader.take(MAX_OUTPUT + 1).read_to_end(&mut output).ok()?;
+    if output.len() as u64 > MAX_OUTPUT {
+        return None;
+    }
+    Some(output)
+}
+
+/// Collects what a decompressor writes, failing once it is more than [`MAX_OUTPUT`]
+/// This bounds decompressors which write their output rather than being read from.
+#[derive(Default)]
+pub struct BoundedOutput(pub Vec<u8>);
+
+impl Write for BoundedOutput {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if (self.0.len() + buf.len()) as u64 > MAX_OUTPUT {
+            return Err(io::Error::other("decompressed data is too long"));
+        }
+        self.0.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::payloads;
+
+    #[test]
+    fn hex_and_base64_are_both_read() {
+        // Hex digits are also Base64 characters, so both readings are kept
+        let readings: Vec<&str> = payloads("1f8b0800")
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(readings, vec!["hex", "Base64"]);
+    }
+
+    #[test]
+    fn binary_text_is_read_as_bytes() {
+        let readings = payloads("\u{1f}\u{8b}\u{8}");
+        assert_eq!(readings, vec![("raw bytes", vec![0x1f, 0x8b, 0x08])]);
+    }
+
+    #[test]
+    fn ordinary_text_has_no_payload() {
+        assert!(payloads("hello world").is_empty());
+    }
+}
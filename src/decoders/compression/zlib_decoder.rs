@@ -0,0 +1,229 @@
+//! Decompresses zlib streams
+//! A zlib stream is a deflate stream between a two byte header and an Adler-32 checksum of
+//! the data. It is what PNG chunks, PDF streams and most libraries' compress() produce. The
+//! header's first byte is almost always 78, and the two bytes read as one number are a
+//! multiple of 31, which makes them a cheap check before decompressing.
+//! Call zlib_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::crack_results::CrackResult;
+use crate::decoders::interface::{check_string_success, Crack, Decoder};
+use crate::decoders::radix::bytes_to_text;
+
+use super::{payloads, read_all};
+
+use flate2::read::ZlibDecoder as ZlibReader;
+use log::{debug, trace};
+
+/// The compression method number of deflate, in the low bits of the first header byte
+const DEFLATE: u8 = 8;
+
+/// The largest window size a deflate stream can ask for, as the log of its size minus 8
+const MAX_WINDOW: u8 = 7;
+
+/// The flag for a preset dictionary, which we can't know
+const PRESET_DICTIONARY: u8 = 0x20;
+
+/// The Zlib decoder, call:
+/// `let zlib_decoder = Decoder::<ZlibDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = zlib_decoder.crack(input)` to decompress a zlib stream
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::compression::zlib_decoder::{ZlibDecoder};
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_zlib = Decoder::<ZlibDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_zlib.crack("eJzLSM3JyVcozy/KSQEAGgsEXQ==", &checker);
+/// assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+/// ```
+pub struct ZlibDecoder;
+
+impl Crack for Decoder<ZlibDecoder> {
+    fn new() -> Decoder<ZlibDecoder> {
+        Decoder {
+            name: "Zlib",
+            description: "Zlib wraps a deflate stream in a two byte header, usually starting 78, and an Adler-32 checksum. It is used inside PNG images, PDF files and git objects, and by the compress functions of most languages.",
+            link: "https://en.wikipedia.org/wiki/Zlib",
+            tags: vec!["zlib", "decoder", "compression"],
+            popularity: 0.3,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// This function does the actual decoding
+    /// It reads the compressed bytes out of the text as hex, Base64 or raw bytes,
+    /// and decompresses the first reading that starts with a zlib header.
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying zlib with text {:?}", text);
+        decompress(self, text, payloads(text), checker)
+    }
+    /// Decompresses bytes from the decoder before, which couldn't be passed on as text
+    fn crack_bytes(&self, bytes: &[u8], checker: &CheckerTypes) -> Option<CrackResult> {
+        trace!("Trying zlib with {} bytes", bytes.len());
+        let text = String::from_utf8_lossy(bytes);
+        Some(decompress(
+            self,
+            &text,
+            vec![("raw bytes", bytes.to_vec())],
+            checker,
+        ))
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some(("eJzLSM3JyVcozy/KSQEAGgsEXQ==", "hello world"))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input must be bytes passed on by the decoder before, hex, Base64, or characters below U+0100 including at least one that isn't printable ASCII",
+            "The bytes must start with a zlib header for deflate without a preset dictionary",
+            "The data must decompress with a matching Adler-32, to at most 16 MiB",
+            "Decompressed bytes must be valid UTF-8 without control characters",
+        ]
+    }
+}
+
+/// Decompresses the first of the payloads that works, `text` being what they were read from
+fn decompress(
+    decoder: &Decoder<ZlibDecoder>,
+    text: &str,
+    payloads: Vec<(&'static str, Vec<u8>)>,
+    checker: &CheckerTypes,
+) -> CrackResult {
+    let mut results = CrackResult::new(decoder, text.to_string());
+
+    let decompressed = payloads.into_iter().find_map(|(reading, bytes)| {
+        if !is_zlib_header(&bytes) {
+            return None;
+        }
+        let decoded_text = bytes_to_text(read_all(ZlibReader::new(&bytes[..]))?)?;
+        Some((reading, decoded_text))
+    });
+    let Some((reading, decoded_text)) = decompressed else {
+        debug!("Failed to decompress zlib because no reading of the text is a zlib stream of text");
+        return results;
+    };
+    if !check_string_success(&decoded_text, text) {
+        debug!("Failed to decompress zlib because check_string_success returned false");
+        return results;
+    }
+
+    results.description = format!(
+        "{} The compressed data was read as {}.",
+        decoder.description, reading
+    );
+    let checker_result = checker.check(&decoded_text);
+    results.unencrypted_text = Some(vec![decoded_text]);
+
+    results.update_checker(&checker_result);
+
+    results
+}
+
+/// Whether the bytes start with a zlib header we can decompress
+/// The header must ask for deflate with a window deflate allows, must not need a preset
+/// dictionary, and read as a big endian number must be a multiple of 31.
+pub fn is_zlib_header(bytes: &[u8]) -> bool {
+    let [method, flags, ..] = *bytes else {
+        return false;
+    };
+    method & 0x0F == DEFLATE
+        && method >> 4 <= MAX_WINDOW
+        && flags & PRESET_DICTIONARY == 0
+        && (u16::from(method) << 8 | u16::from(flags)).is_multiple_of(31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_zlib_header, ZlibDecoder};
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn successful_decoding() {
+        let zlib_decoder = Decoder::<ZlibDecoder>::new();
+        let result = zlib_decoder.crack("eJzLSM3JyVcozy/KSQEAGgsEXQ==", &get_athena_checker());
+        assert!(result.success);
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+        assert!(result.description.contains("read as Base64"));
+    }
+
+    #[test]
+    fn hex_is_decompressed() {
+        let zlib_decoder = Decoder::<ZlibDecoder>::new();
+        let result = zlib_decoder.crack(
+            "789ccb48cdc9c95728cf2fca4901001a0b045d",
+            &get_athena_checker(),
+        );
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+        assert!(result.description.contains("read as hex"));
+    }
+
+    #[test]
+    fn headers_are_checked() {
+        // 78 9C is the usual header, 78 9D isn't a multiple of 31 and 78 BB asks for a dictionary
+        assert!(is_zlib_header(&[0x78, 0x9C]));
+        assert!(!is_zlib_header(&[0x78, 0x9D]));
+        assert!(!is_zlib_header(&[0x78, 0xBB]));
+        assert!(!is_zlib_header(&[0x78]));
+    }
+
+    #[test]
+    fn wrong_checksums_are_rejected() {
+        let zlib_decoder = Decoder::<ZlibDecoder>::new();
+        let result = zlib_decoder
+            .crack(
+                "789ccb48cdc9c95728cf2fca4901001a0b045e",
+                &get_athena_checker(),
+            )
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn zlib_handle_panic_if_emoji() {
+        let zlib_decoder = Decoder::<ZlibDecoder>::new();
+        let result = zlib_decoder
+            .crack("😂", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+}
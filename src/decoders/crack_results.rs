@@ -1,9 +1,14 @@
 //! This module contains CrackSuccess and CrackFailure
-use crate::checkers::{checker_result::CheckResult, CheckerTypes, CHECKER_MAP};
+use crate::checkers::{
+    checker_result::CheckResult, custom, denylist, entropy::TextScores, CheckerTypes, CHECKER_MAP,
+};
+use crate::decoders::custom::custom_decoder_info;
 use crate::decoders::{DecoderType, DECODER_MAP};
 
 use super::interface::Decoder;
+use gibberish_or_not::Sensitivity;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 /// Every cracker returns this object which
 /// Either indicates success or failure among other things.
@@ -16,6 +21,11 @@ pub struct CrackResult {
     /// Unencrypted text is what it looks like after.
     /// if decoder failed, this will be None
     pub unencrypted_text: Option<Vec<String>>,
+    /// Decoded values which aren't UTF-8 text, such as compressed data
+    /// Decoders fill this instead of `unencrypted_text` when what they decode isn't text,
+    /// so the bytes reach the next decoder intact rather than mangled by a lossy conversion.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unencrypted_bytes: Option<Vec<Vec<u8>>>,
     /// Decoder is the function we used to decode the text
     pub decoder: &'static str,
     /// Checker which identified the text
@@ -23,11 +33,33 @@ pub struct CrackResult {
     /// Description is a short description of the checker
     pub checker_description: &'static str,
     /// Key is optional as decoders do not use keys.
-    pub key: Option<&'static str>,
+    /// Ciphers which recover a key, such as Vigenère, fill this in.
+    pub key: Option<String>,
     /// Description is a short description of the decoder
-    pub description: &'static str,
+    /// Decoders may add details of the decode, such as the key they recovered.
+    pub description: String,
     /// Link is a link to more info about the decoder
     pub link: &'static str,
+    /// Sensitivity the checker ran with when it identified the text
+    /// Kept out of the JSON, the cache stores it in its own column.
+    #[serde(skip)]
+    pub checker_sensitivity: Option<Sensitivity>,
+    /// How confident the checker was that the text is plaintext, from 0.0 to 1.0
+    #[serde(skip)]
+    pub confidence: f32,
+    /// How much like plaintext the decoded text is, from the Entropy Checker
+    /// Set when no checker identified it, so the search can still rank it.
+    #[serde(skip)]
+    pub scores: Option<TextScores>,
+    /// How long the decoder took to decode the text
+    /// None when the result wasn't decoded in this run, such as when it came from a cache.
+    #[serde(skip)]
+    pub runtime: Option<Duration>,
+    /// Whether the decoder stopped early because the search was running out of time
+    /// What it found may be short of what a longer search would find, so the result isn't
+    /// kept in the edge cache.
+    #[serde(skip)]
+    pub timed_out: bool,
 }
 
 impl CrackResult {
@@ -37,20 +69,37 @@ impl CrackResult {
             success: false,
             encrypted_text: text,
             unencrypted_text: None,
+            unencrypted_bytes: None,
             decoder: decoder_used.name,
             checker_name: "",
             checker_description: "",
             key: None,
-            description: decoder_used.description,
+            description: decoder_used.description.to_string(),
             link: decoder_used.link,
+            checker_sensitivity: None,
+            confidence: 0.0,
+            scores: None,
+            runtime: None,
+            timed_out: false,
         }
     }
 
     /// Updates the checker information
+    /// Plaintexts on the denylist, including the input itself, are never a success
+    /// even if the checker identified them.
     pub fn update_checker(&mut self, checker_result: &CheckResult) {
         self.checker_name = checker_result.checker_name;
         self.checker_description = checker_result.checker_description;
-        self.success = checker_result.is_identified;
+        self.checker_sensitivity = Some(checker_result.sensitivity);
+        self.confidence = checker_result.confidence;
+        self.scores = checker_result.scores;
+        let plaintext = self
+            .unencrypted_text
+            .as_ref()
+            .and_then(|texts| texts.first())
+            .map_or(checker_result.text.as_str(), String::as_str);
+        self.success = checker_result.is_identified
+            && !denylist::is_denied_for_input(plaintext, &self.encrypted_text);
     }
 
     /// Converts CrackResult into JSON
@@ -78,13 +127,16 @@ impl<'de> Deserialize<'de> for CrackResult {
             pub encrypted_text: String,
             /// The resulting unencrypted/decoded text(s) generated in this decode attempt
             pub unencrypted_text: Option<Vec<String>>,
+            /// The resulting decoded values which aren't text, if there were any
+            #[serde(default)]
+            pub unencrypted_bytes: Option<Vec<Vec<u8>>>,
             /// The decoder used to decode the encoded text
             pub decoder: String,
             /// The checker used to validate the success of the decoding attempt
             pub checker_name: String,
             /// Description of the checker
             pub checker_description: String,
-            /// Key intended for use in decryption attempts (currently unused)
+            /// Key recovered by the decoder, if it uses one
             pub key: Option<String>,
             /// Description of the decoder
             pub description: String,
@@ -93,37 +145,60 @@ impl<'de> Deserialize<'de> for CrackResult {
         }
         let temp_cr: TempCrackResult =
             TempCrackResult::deserialize(deserializer).expect("Error deserializing CrackResult");
-        let decoder = DECODER_MAP
-            .get(temp_cr.decoder.as_str())
-            .unwrap_or_else(|| panic!("Error during deserialization of CrackResult: could not find matching decoder for {}", temp_cr.decoder.as_str()))
-            .get::<DecoderType>();
+        // Registered decoders are found by the name they were registered with, results from
+        // one which was never registered in this process keep no decoder rather than panicking
+        let (decoder, link) = match DECODER_MAP.get(temp_cr.decoder.as_str()) {
+            Some(decoder) => {
+                let decoder = decoder.get::<DecoderType>();
+                (decoder.get_name(), decoder.get_link())
+            }
+            None => custom_decoder_info(&temp_cr.decoder).unwrap_or(("", "")),
+        };
         if temp_cr.checker_name.is_empty() {
             return Ok(CrackResult {
                 success: temp_cr.success,
                 encrypted_text: temp_cr.encrypted_text,
                 unencrypted_text: temp_cr.unencrypted_text,
-                decoder: decoder.get_name(),
+                unencrypted_bytes: temp_cr.unencrypted_bytes,
+                decoder,
                 checker_name: "",
                 checker_description: "",
-                key: None,
-                description: decoder.get_description(),
-                link: decoder.get_link(),
+                key: temp_cr.key,
+                description: temp_cr.description,
+                link,
+                checker_sensitivity: None,
+                confidence: 0.0,
+                scores: None,
+                runtime: None,
+                timed_out: false,
             });
         }
-        let checker = CHECKER_MAP
-            .get(temp_cr.checker_name.as_str())
-            .unwrap_or_else(|| panic!("Error during deserialization of CrackResult: could not find matching checker for {}", temp_cr.checker_name.as_str()))
-            .get::<CheckerTypes>();
+        // Checkers registered by library users aren't in the map, and may not be registered
+        // any more, in which case the result is kept without its checker
+        let (checker_name, checker_description) =
+            match CHECKER_MAP.get(temp_cr.checker_name.as_str()) {
+                Some(checker) => {
+                    let checker = checker.get::<CheckerTypes>();
+                    (checker.get_name(), checker.get_description())
+                }
+                None => custom::custom_checker_info(&temp_cr.checker_name).unwrap_or(("", "")),
+            };
         Ok(CrackResult {
             success: temp_cr.success,
             encrypted_text: temp_cr.encrypted_text,
             unencrypted_text: temp_cr.unencrypted_text,
-            decoder: decoder.get_name(),
-            checker_name: checker.get_name(),
-            checker_description: checker.get_description(),
-            key: None,
-            description: decoder.get_description(),
-            link: decoder.get_link(),
+            unencrypted_bytes: temp_cr.unencrypted_bytes,
+            decoder,
+            checker_name,
+            checker_description,
+            key: temp_cr.key,
+            description: temp_cr.description,
+            link,
+            checker_sensitivity: None,
+            confidence: 0.0,
+            scores: None,
+            runtime: None,
+            timed_out: false,
         })
     }
 }
@@ -197,6 +272,9 @@ mod tests {
             checker_description: checker.description,
             description: "".to_string(),
             link: checker.link,
+            confidence: 0.0,
+            sensitivity: checker.sensitivity,
+            scores: None,
         };
 
         let decoder = Decoder::<Base64Decoder>::new();
@@ -245,6 +323,9 @@ mod tests {
             checker_description: checker.description,
             description: "".to_string(),
             link: checker.link,
+            confidence: 0.0,
+            sensitivity: checker.sensitivity,
+            scores: None,
         };
 
         let decoder = Decoder::<CaesarDecoder>::new();
@@ -281,4 +362,27 @@ mod tests {
         assert_eq!(crack_result.description, expected_crack_result.description);
         assert_eq!(crack_result.link, expected_crack_result.link);
     }
+
+    #[test]
+    fn update_checker_rejects_denylisted_plaintext() {
+        let mock_decoder = Decoder::<MockDecoder>::new();
+        let checker = Checker::<EnglishChecker>::new();
+        let mut identified = CheckResult::new(&checker);
+        identified.is_identified = true;
+
+        let mut echo = CrackResult::new(&mock_decoder, String::from("hello world"));
+        echo.unencrypted_text = Some(vec![String::from("hello world")]);
+        echo.update_checker(&identified);
+        assert!(!echo.success);
+
+        let mut repeated = CrackResult::new(&mock_decoder, String::from("YWFhYQ=="));
+        repeated.unencrypted_text = Some(vec![String::from("aaaa")]);
+        repeated.update_checker(&identified);
+        assert!(!repeated.success);
+
+        let mut plaintext = CrackResult::new(&mock_decoder, String::from("aGVsbG8gd29ybGQ="));
+        plaintext.unencrypted_text = Some(vec![String::from("hello world")]);
+        plaintext.update_checker(&identified);
+        assert!(plaintext.success);
+    }
 }
@@ -0,0 +1,132 @@
+//! Decoders registered at runtime by users of the library
+//! The decoders ciphey ships with are a closed set, so an encoding only its user knows
+//! about, such as a proprietary format, is told to ciphey by registering a decoder here.
+//! Registered decoders are searched alongside ciphey's own, their decodes are checked by
+//! the same checkers and cached like any other.
+//!
+//! Decoders tagged `decoder` are tried first at every step of the search, like ciphey's
+//! own encodings. Decoders without the tag are only tried once those have all failed.
+//!
+//! ```
+//! use ciphey::checkers::CheckerTypes;
+//! use ciphey::decoders::crack_results::CrackResult;
+//! use ciphey::decoders::interface::{Crack, Decoder};
+//! use ciphey::decoders::register_decoder;
+//!
+//! /// Undoes an encoding which writes every character twice, such as `hheelllloo`
+//! struct Undoubler {
+//!     decoder: Decoder<()>,
+//! }
+//!
+//! impl Crack for Undoubler {
+//!     fn new() -> Self {
+//!         Undoubler {
+//!             decoder: Decoder {
+//!                 name: "Undoubler",
+//!                 description: "Keeps one of every pair of characters",
+//!                 link: "https://example.com/doubling",
+//!                 tags: vec!["undoubler", "decoder"],
+//!                 popularity: 0.5,
+//!                 phantom: std::marker::PhantomData,
+//!             },
+//!         }
+//!     }
+//!
+//!     fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+//!         let mut result = CrackResult::new(&self.decoder, text.to_string());
+//!         let chars: Vec<char> = text.chars().collect();
+//!         if chars.is_empty() || !chars.len().is_multiple_of(2) || chars.chunks(2).any(|pair| pair[0] != pair[1]) {
+//!             return result;
+//!         }
+//!         let decoded: String = chars.iter().step_by(2).collect();
+//!         let checker_result = checker.check(&decoded);
+//!         result.unencrypted_text = Some(vec![decoded]);
+//!         result.update_checker(&checker_result);
+//!         result
+//!     }
+//!
+//!     fn get_tags(&self) -> &Vec<&str> {
+//!         &self.decoder.tags
+//!     }
+//!
+//!     fn get_name(&self) -> &str {
+//!         self.decoder.name
+//!     }
+//!
+//!     fn get_description(&self) -> &str {
+//!         self.decoder.description
+//!     }
+//!
+//!     fn get_link(&self) -> &str {
+//!         self.decoder.link
+//!     }
+//! }
+//!
+//! register_decoder(Box::new(Undoubler::new()));
+//! ```
+
+use crate::decoders::interface::Crack;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
+
+/// A decoder registered with [`register_decoder`]
+pub type CustomDecoder = Box<dyn Crack + Send + Sync>;
+
+/// A decoder the filtration system can hand out to any number of searches at once
+pub type SharedDecoder = Arc<dyn Crack + Send + Sync>;
+
+/// The registered decoders, in the order they were registered
+static CUSTOM_DECODERS: Lazy<RwLock<Vec<SharedDecoder>>> = Lazy::new(|| RwLock::new(Vec::new()));
+
+/// The names and links of every decoder which has been registered
+/// Results read back from the cache are matched to their decoder by these, and they outlive
+/// the decoder being cleared so those results can still be read.
+static CUSTOM_DECODER_NAMES: Lazy<Mutex<HashMap<&'static str, &'static str>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Adds a decoder for the search to run, after ciphey's own decoders
+///
+/// The decoder can be toggled off like any other by its name. It shouldn't register or clear
+/// decoders from inside `crack`.
+pub fn register_decoder(decoder: CustomDecoder) {
+    let mut names = CUSTOM_DECODER_NAMES
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    if !names.contains_key(decoder.get_name()) {
+        // Registering is rare, so the names are leaked to give them the lifetime results need
+        let name: &'static str = Box::leak(decoder.get_name().to_string().into_boxed_str());
+        let link: &'static str = Box::leak(decoder.get_link().to_string().into_boxed_str());
+        names.insert(name, link);
+    }
+    CUSTOM_DECODERS
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .push(Arc::from(decoder));
+}
+
+/// Removes every registered decoder
+pub fn clear_custom_decoders() {
+    CUSTOM_DECODERS
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clear();
+}
+
+/// Every registered decoder, including ones the user has toggled off
+pub fn custom_decoders() -> Vec<SharedDecoder> {
+    CUSTOM_DECODERS
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
+
+/// The name and link of a decoder which has been registered under this name, None if none
+/// has
+pub fn custom_decoder_info(name: &str) -> Option<(&'static str, &'static str)> {
+    CUSTOM_DECODER_NAMES
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_key_value(name)
+        .map(|(name, link)| (*name, *link))
+}
@@ -0,0 +1,169 @@
+//! Decode a decimal byte string
+//! Performs error handling and returns a string
+//! Call decimal_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::check_string_success;
+use crate::decoders::radix::{bytes_to_text, parse_bytes};
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::Decoder;
+
+use log::{debug, info, trace};
+
+/// The Decimal decoder, call:
+/// `let decimal_decoder = Decoder::<DecimalDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = decimal_decoder.crack(input)` to decode a decimal byte string
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::decimal_decoder::{DecimalDecoder};
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_decimal = Decoder::<DecimalDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_decimal.crack("104 101 108 108 111 32 119 111 114 108 100", &checker).unencrypted_text;
+/// assert!(result.is_some());
+/// assert_eq!(result.unwrap()[0], "hello world");
+/// ```
+pub struct DecimalDecoder;
+
+impl Crack for Decoder<DecimalDecoder> {
+    fn new() -> Decoder<DecimalDecoder> {
+        Decoder {
+            name: "Decimal",
+            description: "Text written as the decimal value of each byte, such as 104 101 108 108 111 for hello. It is one of the most common first layers in CTF challenges.",
+            link: "https://en.wikipedia.org/wiki/ASCII",
+            tags: vec!["decimal", "base", "decoder"],
+            popularity: 0.7,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying decimal with text {:?}", text);
+        let mut results = CrackResult::new(self, text.to_string());
+
+        let decoded_text = match parse_bytes(text, 10, 3).and_then(bytes_to_text) {
+            Some(decoded_text) => decoded_text,
+            None => {
+                debug!("Failed to decode decimal because the input isn't a decimal byte string");
+                return results;
+            }
+        };
+
+        if !check_string_success(&decoded_text, text) {
+            info!(
+                "Failed to decode decimal because check_string_success returned false on string {}",
+                decoded_text
+            );
+            return results;
+        }
+
+        let checker_result = checker.check(&decoded_text);
+        results.unencrypted_text = Some(vec![decoded_text]);
+
+        results.update_checker(&checker_result);
+
+        results
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some(("104 101 108 108 111 32 119 111 114 108 100", "hello world"))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Every number must be at most 255",
+            "Bytes are separated by whitespace, `,`, `:`, `;` or `\\`, or written continuously with 3 digits each",
+            "Must decode to at least 3 bytes of valid UTF-8 without control characters",
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DecimalDecoder;
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn decimal_with_spaces_decodes() {
+        let decoder = Decoder::<DecimalDecoder>::new();
+        let result = decoder.crack(
+            "104 101 108 108 111 32 119 111 114 108 100",
+            &get_athena_checker(),
+        );
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+    }
+
+    #[test]
+    fn decimal_with_commas_decodes() {
+        let decoder = Decoder::<DecimalDecoder>::new();
+        let result = decoder.crack(
+            "104,101,108,108,111,32,119,111,114,108,100",
+            &get_athena_checker(),
+        );
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+    }
+
+    #[test]
+    fn continuous_decimal_decodes() {
+        let decoder = Decoder::<DecimalDecoder>::new();
+        let result = decoder.crack("104101108108111032119111114108100", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+    }
+
+    #[test]
+    fn decimal_rejects_numbers_above_a_byte() {
+        let decoder = Decoder::<DecimalDecoder>::new();
+        let result = decoder.crack("104 101 308 108 111", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+
+    #[test]
+    fn decimal_rejects_empty_string() {
+        let decoder = Decoder::<DecimalDecoder>::new();
+        let result = decoder.crack("", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+}
@@ -0,0 +1,298 @@
+//! Decode text hidden in DNA sequences
+//! Bio-themed puzzles write text with the four bases A, C, G and T (or U for RNA) in one
+//! of two ways. Either each base stands for two bits, so four bases make a byte, or each
+//! run of three bases is a codon and stands for the one letter code of the amino acid it
+//! codes for in the standard genetic code. There is no agreed pairing of bases and bits,
+//! so every one is tried and only the ones giving text are kept, starting with
+//! A=00 C=01 G=10 T=11. Stop codons are read as spaces.
+//! Call dna_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::check_string_success;
+use crate::decoders::radix::bytes_to_text;
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::Decoder;
+
+use log::{debug, trace};
+
+/// The fewest bases worth decoding, three bytes' worth of bits
+const MIN_BASES: usize = 12;
+
+/// The bases in the order their bits are assigned
+const BASES: [char; 4] = ['A', 'C', 'G', 'T'];
+
+/// The amino acid each codon codes for in the standard genetic code, with `*` for the stop
+/// codons. Codons are numbered with T=0 C=1 A=2 G=3 for the first, second and third base.
+const GENETIC_CODE: &[u8; 64] = b"FFLLSSSSYY**CC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG";
+
+/// The DNA decoder, call:
+/// `let dna_decoder = Decoder::<DnaDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = dna_decoder.crack(input)` to decode a DNA sequence
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::dna_decoder::{DnaDecoder};
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_dna = Decoder::<DnaDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// // Two bits for each base, A=00 C=01 G=10 T=11
+/// let result = decode_dna.crack("CGGA CGCC CTGC", &checker).unencrypted_text.unwrap();
+/// assert!(result.contains(&"hey".to_string()));
+///
+/// // Codons read as the amino acids they code for, with the stop codon TAA as a space
+/// let result = decode_dna.crack("CATATTTAAACTCATGAACGTGAA", &checker).unencrypted_text.unwrap();
+/// assert!(result.contains(&"HI THERE".to_string()));
+/// ```
+pub struct DnaDecoder;
+
+impl Crack for Decoder<DnaDecoder> {
+    fn new() -> Decoder<DnaDecoder> {
+        Decoder {
+            name: "DNA",
+            description: "Text written as a DNA or RNA sequence, either with each of the bases A, C, G and T standing for two bits, or with each codon of three bases standing for the one letter code of its amino acid.",
+            link: "https://www.dcode.fr/dna-code",
+            tags: vec!["dna", "decoder"],
+            popularity: 0.3,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying DNA with text {:?}", text);
+        let mut results = CrackResult::new(self, text.to_string());
+
+        let Some(bases) = read_bases(text) else {
+            debug!("Failed to decode DNA because the input isn't a long enough sequence of bases");
+            return results;
+        };
+
+        let mut readings = Vec::new();
+        if let Some(amino_acids) = codons_to_amino_acids(&bases) {
+            readings.push((String::from("standard genetic code"), amino_acids));
+        }
+        for assignment in bit_assignments() {
+            if let Some(decoded_text) = bases_to_bytes(&bases, &assignment).and_then(bytes_to_text)
+            {
+                let key = BASES
+                    .iter()
+                    .zip(assignment)
+                    .map(|(base, bits)| format!("{base}={bits:02b}"))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                readings.push((key, decoded_text));
+            }
+        }
+
+        let mut candidates = Vec::new();
+        let mut keys = Vec::new();
+        for (key, decoded_text) in readings {
+            if !check_string_success(&decoded_text, text) || candidates.contains(&decoded_text) {
+                continue;
+            }
+            let checker_result = checker.check(&decoded_text);
+            if checker_result.is_identified && !results.success {
+                // The match goes first, and every other reading is still returned
+                candidates.insert(0, decoded_text);
+                keys.insert(0, key);
+                results.update_checker(&checker_result);
+            } else {
+                candidates.push(decoded_text);
+                keys.push(key);
+            }
+        }
+        if candidates.is_empty() {
+            debug!("Failed to decode DNA because no reading gave text");
+            return results;
+        }
+        if results.success {
+            results.key = keys.into_iter().next();
+        }
+        results.unencrypted_text = Some(candidates);
+
+        results
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some(("CGGACGCCCTGC", "hey"))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input must only use the bases A, C, G, T and U, all in the same case, and whitespace",
+            "Must be at least 12 bases",
+            "Two bit readings need a multiple of 4 bases and codon readings a multiple of 3",
+        ]
+    }
+}
+
+/// The bases of the sequence in uppercase with U read as T, None if it isn't a sequence
+fn read_bases(text: &str) -> Option<Vec<char>> {
+    let bases: Vec<char> = text.chars().filter(|c| !c.is_whitespace()).collect();
+    let one_case =
+        bases.iter().all(char::is_ascii_uppercase) || bases.iter().all(char::is_ascii_lowercase);
+    if bases.len() < MIN_BASES || !one_case {
+        return None;
+    }
+    bases
+        .into_iter()
+        .map(|c| match c.to_ascii_uppercase() {
+            'U' => Some('T'),
+            base if BASES.contains(&base) => Some(base),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Every way of giving each base its own two bits, in the order of the bases in `BASES`
+fn bit_assignments() -> Vec<[u8; 4]> {
+    (0..=u8::MAX)
+        .map(|n| [n >> 6, (n >> 4) & 3, (n >> 2) & 3, n & 3])
+        .filter(|bits| (0..4).all(|i| !bits[i + 1..].contains(&bits[i])))
+        .collect()
+}
+
+/// Reads every four bases as a byte, the first base giving the highest two bits
+fn bases_to_bytes(bases: &[char], assignment: &[u8; 4]) -> Option<Vec<u8>> {
+    if !bases.len().is_multiple_of(4) {
+        return None;
+    }
+    let bits = |base: &char| assignment[BASES.iter().position(|b| b == base).unwrap_or(0)];
+    Some(
+        bases
+            .chunks(4)
+            .map(|byte| byte.iter().fold(0, |value, base| value << 2 | bits(base)))
+            .collect(),
+    )
+}
+
+/// Reads every codon as its amino acid's letter, with stop codons as spaces
+fn codons_to_amino_acids(bases: &[char]) -> Option<String> {
+    if !bases.len().is_multiple_of(3) {
+        return None;
+    }
+    let number = |base: &char| match base {
+        'T' => 0,
+        'C' => 1,
+        'A' => 2,
+        _ => 3,
+    };
+    let amino_acids: String = bases
+        .chunks(3)
+        .map(|codon| {
+            let index = codon.iter().fold(0, |value, base| value * 4 + number(base));
+            match GENETIC_CODE[index] {
+                b'*' => ' ',
+                amino_acid => char::from(amino_acid),
+            }
+        })
+        .collect();
+    let amino_acids = amino_acids.trim();
+    (!amino_acids.is_empty()).then(|| amino_acids.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bit_assignments, codons_to_amino_acids, read_bases, DnaDecoder};
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn two_bit_bases_decode() {
+        let decoder = Decoder::<DnaDecoder>::new();
+        // "hello world" with A=00 C=01 G=10 T=11
+        let result = decoder.crack(
+            "CGGACGCCCGTACGTACGTTAGAACTCTCGTTCTAGCGTACGCA",
+            &get_athena_checker(),
+        );
+        assert!(result.success);
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+        assert_eq!(result.key.unwrap(), "A=00 C=01 G=10 T=11");
+    }
+
+    #[test]
+    fn other_bit_assignments_are_tried() {
+        let decoder = Decoder::<DnaDecoder>::new();
+        // "hello world" with A=11 C=10 G=01 T=00, the complement of the usual bits
+        let result = decoder.crack(
+            "GCCTGCGGGCATGCATGCAATCTTGAGAGCAAGATCGCATGCGT",
+            &get_athena_checker(),
+        );
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+        assert_eq!(result.key.unwrap(), "A=11 C=10 G=01 T=00");
+    }
+
+    #[test]
+    fn codons_are_read_as_amino_acids() {
+        let bases = read_bases("AUG GAA UAA UGG CAU AUU CGU").unwrap();
+        assert_eq!(codons_to_amino_acids(&bases).unwrap(), "ME WHIR");
+    }
+
+    #[test]
+    fn every_assignment_is_distinct() {
+        let assignments = bit_assignments();
+        assert_eq!(assignments.len(), 24);
+        assert_eq!(assignments[0], [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn dna_rejects_other_letters_and_short_sequences() {
+        assert!(read_bases("CGGACGCCCTGX").is_none());
+        assert!(read_bases("CGGACGCC").is_none());
+        assert!(read_bases("CGGAcgccCTGC").is_none());
+    }
+
+    #[test]
+    fn dna_rejects_empty_string() {
+        let decoder = Decoder::<DnaDecoder>::new();
+        let result = decoder.crack("", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+
+    #[test]
+    fn dna_rejects_emoji() {
+        let decoder = Decoder::<DnaDecoder>::new();
+        let result = decoder.crack("😂", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+}
@@ -0,0 +1,917 @@
+//! Evaluate JSFuck back to what it hides
+//! JSFuck writes any JavaScript with only the six characters `[]()!+`. It builds `false`,
+//! `true` and `undefined` from empty arrays, turns them into strings to pick letters out of,
+//! reads methods such as `toString` and `fontcolor` by name to get the rest, and runs code
+//! by handing a string of it to the `Function` constructor.
+//! Rather than run any of that, this evaluates the small part of JavaScript JSFuck is made
+//! of: arrays, `!`, `+`, indexing and calls to a fixed list of built-in functions. When the
+//! program hands code to `Function` and calls it, that code is the answer, and otherwise it
+//! is the string the expression comes to. Anything outside that list stops the evaluation.
+//! Call jsfuck_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::crack_results::CrackResult;
+use crate::decoders::interface::{check_string_success, Crack, Decoder};
+
+use super::MAX_OUTPUT;
+
+use log::{debug, info, trace};
+
+/// The fewest characters worth evaluating, as `![]+[]` and the like are too short to hide anything
+const MIN_LENGTH: usize = 30;
+
+/// The most characters evaluated, which is a few kilobytes of hidden text
+const MAX_LENGTH: usize = 4 * 1024 * 1024;
+
+/// How deeply brackets may nest before the evaluation gives up
+const MAX_DEPTH: usize = 256;
+
+/// What `Date()` returns, fixed so the same program always decodes the same way
+const DATE: &str = "Thu Jan 01 1970 00:00:00 GMT+0000 (Coordinated Universal Time)";
+
+/// Functions a program can get by running `return` and their name through `Function`
+const GLOBALS: [&str; 9] = [
+    "escape", "unescape", "Date", "String", "Number", "Boolean", "Array", "Function", "RegExp",
+];
+
+/// The JSFuck decoder, call:
+/// `let jsfuck_decoder = Decoder::<JsfuckDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = jsfuck_decoder.crack(input)` to evaluate a JSFuck program
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::esolang::jsfuck_decoder::{JsfuckDecoder};
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let jsfuck_decoder = Decoder::<JsfuckDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = jsfuck_decoder.crack("(([][[]]+[])[!+[]+!+[]]+(!![]+[])[!+[]+!+[]+!+[]]+(![]+[])[+[]]+([![]]+[][[]])[(+!+[]+[])+[+[]]]+(![]+[])[+!+[]]+([][[]]+[])[+!+[]]+(!![]+[])[+[]])", &checker);
+/// assert_eq!(result.unencrypted_text.unwrap()[0], "defiant");
+/// ```
+pub struct JsfuckDecoder;
+
+impl Crack for Decoder<JsfuckDecoder> {
+    fn new() -> Decoder<JsfuckDecoder> {
+        Decoder {
+            name: "JSFuck",
+            description: "JSFuck is a style of JavaScript written with only the six characters []()!+, made by Martin Kleppe. Any script can be written in it, which makes it a favourite for slipping code past filters and hiding it from casual reading.",
+            link: "https://en.wikipedia.org/wiki/JSFuck",
+            tags: vec!["decoder", "esolang", "jsfuck", "javascript"],
+            popularity: 0.3,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying JSFuck with text {:?}", text);
+        let mut results = CrackResult::new(self, text.to_string());
+
+        let Some(decoded_text) = evaluate(text) else {
+            debug!("Failed to decode JSFuck because the text isn't JSFuck which comes to a string");
+            return results;
+        };
+        if decoded_text
+            .chars()
+            .any(|c| c.is_control() && !c.is_whitespace())
+        {
+            debug!("Failed to decode JSFuck because the result has control characters");
+            return results;
+        }
+        if !check_string_success(&decoded_text, text) {
+            info!(
+                "Failed to decode JSFuck because check_string_success returned false on string {}",
+                decoded_text
+            );
+            return results;
+        }
+
+        let checker_result = checker.check(&decoded_text);
+        results.unencrypted_text = Some(vec![decoded_text]);
+
+        results.update_checker(&checker_result);
+
+        results
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some(("(([][[]]+[])[!+[]+!+[]]+(!![]+[])[!+[]+!+[]+!+[]]+(![]+[])[+[]]+([![]]+[][[]])[(+!+[]+[])+[+[]]]+(![]+[])[+!+[]]+([][[]]+[])[+!+[]]+(!![]+[])[+[]])", "defiant"))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input must be at least 30 characters of only `[` `]` `(` `)` `!` `+` and whitespace",
+            "It must parse as one expression, with brackets nested at most 256 deep",
+            "It may only call the built-in functions JSFuck uses, such as toString, fontcolor and Function",
+            "It must come to a string, or hand code to Function and run it, which is then the result",
+        ]
+    }
+}
+
+/// A JavaScript value, as far as JSFuck needs them
+#[derive(Clone, Debug)]
+enum Value {
+    /// `undefined`, from reading past the end of an array
+    Undefined,
+    /// `true` or `false`
+    Boolean(bool),
+    /// A number, which in JavaScript is always a double
+    Number(f64),
+    /// A string
+    Text(String),
+    /// An array
+    Array(Vec<Value>),
+    /// A plain object, which prints as `[object` and its kind `]`
+    Object(&'static str),
+    /// A regular expression, holding its source
+    RegExp(String),
+    /// A built-in function, with the value it was read from when it is a method
+    Function {
+        /// The function's name, which its source text shows
+        name: String,
+        /// The value the method was read from, None for a global function
+        this: Option<Box<Value>>,
+    },
+    /// A function made by the Function constructor, holding its body
+    Code(String),
+}
+
+/// Formats a number the way JavaScript does
+fn number_to_string(number: f64) -> String {
+    if number.is_nan() {
+        return "NaN".to_string();
+    }
+    if number.is_infinite() {
+        return if number > 0.0 {
+            "Infinity"
+        } else {
+            "-Infinity"
+        }
+        .to_string();
+    }
+    if number == 0.0 {
+        return "0".to_string();
+    }
+    if number.abs() >= 1e21 || number.abs() < 1e-6 {
+        // Rust writes 1e21 where JavaScript writes 1e+21
+        let scientific = format!("{number:e}");
+        return match scientific.split_once('e') {
+            Some((mantissa, exponent)) if !exponent.starts_with('-') => {
+                format!("{mantissa}e+{exponent}")
+            }
+            _ => scientific,
+        };
+    }
+    format!("{number}")
+}
+
+/// Writes a whole number in the given base, as `Number.prototype.toString` does
+fn number_to_radix(number: f64, radix: u32) -> Option<String> {
+    if !(2..=36).contains(&radix) || number.fract() != 0.0 || number.abs() > 2f64.powi(53) {
+        return None;
+    }
+    let mut rest = number.abs() as u64;
+    let mut digits = Vec::new();
+    loop {
+        digits.push(char::from_digit((rest % u64::from(radix)) as u32, radix)?);
+        rest /= u64::from(radix);
+        if rest == 0 {
+            break;
+        }
+    }
+    if number < 0.0 {
+        digits.push('-');
+    }
+    Some(digits.iter().rev().collect())
+}
+
+/// What a value becomes when turned into a string
+fn to_text(value: &Value) -> String {
+    match value {
+        Value::Undefined => "undefined".to_string(),
+        Value::Boolean(b) => b.to_string(),
+        Value::Number(n) => number_to_string(*n),
+        Value::Text(text) => text.clone(),
+        Value::Array(items) => items
+            .iter()
+            .map(|item| match item {
+                Value::Undefined => String::new(),
+                item => to_text(item),
+            })
+            .collect::<Vec<_>>()
+            .join(","),
+        Value::Object(kind) => format!("[object {kind}]"),
+        Value::RegExp(source) if source.is_empty() => "/(?:)/".to_string(),
+        Value::RegExp(source) => format!("/{source}/"),
+        Value::Function { name, .. } => format!("function {name}() {{ [native code] }}"),
+        Value::Code(body) => format!("function anonymous(\n) {{\n{body}\n}}"),
+    }
+}
+
+/// What a value becomes when turned into a number
+fn to_number(value: &Value) -> f64 {
+    match value {
+        Value::Undefined => f64::NAN,
+        Value::Boolean(b) => f64::from(u8::from(*b)),
+        Value::Number(n) => *n,
+        Value::Text(text) => {
+            let text = text.trim();
+            match text {
+                "" => 0.0,
+                "Infinity" | "+Infinity" => f64::INFINITY,
+                "-Infinity" => f64::NEG_INFINITY,
+                // Rust would also read words such as inf and nan, which JavaScript doesn't
+                _ if text.chars().all(|c| "0123456789.eE+-".contains(c)) => {
+                    text.parse().unwrap_or(f64::NAN)
+                }
+                _ => f64::NAN,
+            }
+        }
+        value => to_number(&Value::Text(to_text(value))),
+    }
+}
+
+/// Whether a value counts as true
+fn truthy(value: &Value) -> bool {
+    match value {
+        Value::Undefined => false,
+        Value::Boolean(b) => *b,
+        Value::Number(n) => *n != 0.0 && !n.is_nan(),
+        Value::Text(text) => !text.is_empty(),
+        _ => true,
+    }
+}
+
+/// The kind `Object.prototype.toString` gives a value, as in `[object Undefined]`
+fn kind(value: &Value) -> &'static str {
+    match value {
+        Value::Undefined => "Undefined",
+        Value::Boolean(_) => "Boolean",
+        Value::Number(_) => "Number",
+        Value::Text(_) => "String",
+        Value::Array(_) => "Array",
+        Value::Object(kind) => kind,
+        Value::RegExp(_) => "RegExp",
+        Value::Function { .. } | Value::Code(_) => "Function",
+    }
+}
+
+/// JavaScript's `+`, which joins strings and adds anything else
+fn add(a: Value, b: Value) -> Option<Value> {
+    let primitive = |value: Value| match value {
+        Value::Undefined | Value::Boolean(_) | Value::Number(_) | Value::Text(_) => value,
+        value => Value::Text(to_text(&value)),
+    };
+    let (a, b) = (primitive(a), primitive(b));
+    if matches!(a, Value::Text(_)) || matches!(b, Value::Text(_)) {
+        let joined = to_text(&a) + &to_text(&b);
+        if joined.len() > MAX_LENGTH {
+            return None;
+        }
+        return Some(Value::Text(joined));
+    }
+    Some(Value::Number(to_number(&a) + to_number(&b)))
+}
+
+/// JavaScript's `escape`, which writes everything but letters, digits and `@*_+-./` as `%XX`
+fn escape(text: &str) -> String {
+    let mut escaped = String::new();
+    for unit in text.encode_utf16() {
+        match char::from_u32(u32::from(unit)) {
+            Some(c) if c.is_ascii_alphanumeric() || "@*_+-./".contains(c) => escaped.push(c),
+            _ if unit < 0x100 => escaped.push_str(&format!("%{unit:02X}")),
+            _ => escaped.push_str(&format!("%u{unit:04X}")),
+        }
+    }
+    escaped
+}
+
+/// JavaScript's `unescape`, which reads `%XX` and `%uXXXX` back
+fn unescape(text: &str) -> String {
+    let mut units = Vec::new();
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        let code = |digits: &str| u16::from_str_radix(digits, 16).ok();
+        if let Some(unit) = rest
+            .strip_prefix("%u")
+            .and_then(|hex| hex.get(..4))
+            .filter(|hex| hex.bytes().all(|b| b.is_ascii_hexdigit()))
+            .and_then(code)
+        {
+            units.push(unit);
+            rest = &rest[6..];
+        } else if let Some(unit) = rest
+            .strip_prefix('%')
+            .and_then(|hex| hex.get(..2))
+            .filter(|hex| hex.bytes().all(|b| b.is_ascii_hexdigit()))
+            .and_then(code)
+        {
+            units.push(unit);
+            rest = &rest[3..];
+        } else {
+            let mut buffer = [0; 2];
+            units.extend_from_slice(c.encode_utf16(&mut buffer));
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    String::from_utf16_lossy(&units)
+}
+
+/// Turns a value into an index, None if it isn't a whole number that fits
+fn index(value: &Value) -> Option<i64> {
+    let number = to_number(value);
+    if number.is_nan() {
+        return Some(0);
+    }
+    if number.fract() != 0.0 || number.abs() > 2f64.powi(53) {
+        return None;
+    }
+    Some(number as i64)
+}
+
+/// Turns a possibly negative position into one counted from the start, as `slice` does
+fn from_start(position: i64, length: usize) -> usize {
+    if position < 0 {
+        length.saturating_sub(position.unsigned_abs() as usize)
+    } else {
+        (position as usize).min(length)
+    }
+}
+
+/// The methods strings have which JSFuck uses, with the HTML tag of each wrapping method
+const HTML_METHODS: [(&str, &str); 9] = [
+    ("italics", "i"),
+    ("bold", "b"),
+    ("big", "big"),
+    ("blink", "blink"),
+    ("fixed", "tt"),
+    ("small", "small"),
+    ("strike", "strike"),
+    ("sub", "sub"),
+    ("sup", "sup"),
+];
+
+/// The other string methods which can be called
+const STRING_METHODS: [&str; 13] = [
+    "fontcolor",
+    "fontsize",
+    "link",
+    "anchor",
+    "concat",
+    "slice",
+    "at",
+    "charAt",
+    "split",
+    "toString",
+    "toUpperCase",
+    "toLowerCase",
+    "trim",
+];
+
+/// The array methods which can be called
+const ARRAY_METHODS: [&str; 7] = [
+    "concat", "join", "flat", "entries", "at", "slice", "toString",
+];
+
+/// The array methods a program may read but not call, as JSFuck only prints them
+const PRINTED_ARRAY_METHODS: [&str; 6] = ["filter", "fill", "find", "sort", "map", "keys"];
+
+/// Reads a property of a value, None where JavaScript would throw
+fn member(object: Value, key: &Value) -> Option<Value> {
+    let key = to_text(key);
+    let method = |object: Value| Value::Function {
+        name: key.clone(),
+        this: Some(Box::new(object)),
+    };
+    let global = |name: &str| Value::Function {
+        name: name.to_string(),
+        this: None,
+    };
+    let position = key
+        .parse::<usize>()
+        .ok()
+        .filter(|position| position.to_string() == key);
+    let value = match object {
+        Value::Undefined => return None,
+        Value::Text(text) => match key.as_str() {
+            _ if position.is_some() => position
+                .and_then(|position| text.chars().nth(position))
+                .map_or(Value::Undefined, |c| Value::Text(c.to_string())),
+            "length" => Value::Number(text.chars().count() as f64),
+            "constructor" => global("String"),
+            name if STRING_METHODS.contains(&name)
+                || HTML_METHODS.iter().any(|(method, _)| *method == name) =>
+            {
+                method(Value::Text(text))
+            }
+            _ => Value::Undefined,
+        },
+        Value::Array(items) => match key.as_str() {
+            _ if position.is_some() => position
+                .and_then(|position| items.get(position).cloned())
+                .unwrap_or(Value::Undefined),
+            "length" => Value::Number(items.len() as f64),
+            "constructor" => global("Array"),
+            name if ARRAY_METHODS.contains(&name) || PRINTED_ARRAY_METHODS.contains(&name) => {
+                method(Value::Array(items))
+            }
+            _ => Value::Undefined,
+        },
+        Value::Number(n) => match key.as_str() {
+            "constructor" => global("Number"),
+            "toString" => method(Value::Number(n)),
+            _ => Value::Undefined,
+        },
+        Value::Boolean(b) => match key.as_str() {
+            "constructor" => global("Boolean"),
+            "toString" => method(Value::Boolean(b)),
+            _ => Value::Undefined,
+        },
+        Value::Object(kind) => match key.as_str() {
+            "constructor" => global("Object"),
+            "toString" => method(Value::Object(kind)),
+            _ => Value::Undefined,
+        },
+        Value::RegExp(source) => match key.as_str() {
+            "constructor" => global("RegExp"),
+            "source" => Value::Text(source),
+            _ => Value::Undefined,
+        },
+        Value::Function { name, this } => match key.as_str() {
+            "constructor" => global("Function"),
+            "name" => Value::Text(name),
+            "fromCharCode" if name == "String" && this.is_none() => global("fromCharCode"),
+            "call" | "toString" => method(Value::Function { name, this }),
+            _ => Value::Undefined,
+        },
+        Value::Code(body) => match key.as_str() {
+            "constructor" => global("Function"),
+            "name" => Value::Text("anonymous".to_string()),
+            "call" => method(Value::Code(body)),
+            _ => Value::Undefined,
+        },
+    };
+    Some(value)
+}
+
+/// Evaluates JSFuck while it reads it, as it has nothing which runs conditionally
+struct Evaluator<'a> {
+    /// The program with whitespace removed
+    program: &'a [u8],
+    /// The next character to read
+    position: usize,
+    /// How many expressions are open around the one being read
+    depth: usize,
+    /// The body of the last function made with `Function` that was run
+    ran: Option<String>,
+}
+
+impl Evaluator<'_> {
+    /// Reads the given character if it is next
+    fn eat(&mut self, c: u8) -> bool {
+        if self.program.get(self.position) == Some(&c) {
+            self.position += 1;
+            return true;
+        }
+        false
+    }
+
+    /// Reads a whole expression: terms joined by `+`
+    fn expression(&mut self) -> Option<Value> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return None;
+        }
+        let mut value = self.unary()?;
+        while self.eat(b'+') {
+            let right = self.unary()?;
+            value = add(value, right)?;
+        }
+        self.depth -= 1;
+        Some(value)
+    }
+
+    /// Reads a term, which may start with `!` or a `+` turning it into a number
+    fn unary(&mut self) -> Option<Value> {
+        if self.eat(b'!') {
+            let value = self.unary()?;
+            return Some(Value::Boolean(!truthy(&value)));
+        }
+        if self.eat(b'+') {
+            let value = self.unary()?;
+            return Some(Value::Number(to_number(&value)));
+        }
+        self.postfix()
+    }
+
+    /// Reads an array or bracketed expression and the indexing and calls after it
+    fn postfix(&mut self) -> Option<Value> {
+        let mut value = if self.eat(b'[') {
+            let mut items = Vec::new();
+            if !self.eat(b']') {
+                items.push(self.expression()?);
+                if !self.eat(b']') {
+                    return None;
+                }
+            }
+            Value::Array(items)
+        } else if self.eat(b'(') {
+            let value = self.expression()?;
+            if !self.eat(b')') {
+                return None;
+            }
+            value
+        } else {
+            return None;
+        };
+        loop {
+            if self.eat(b'[') {
+                let key = self.expression()?;
+                if !self.eat(b']') {
+                    return None;
+                }
+                value = member(value, &key)?;
+            } else if self.eat(b'(') {
+                let mut arguments = Vec::new();
+                if !self.eat(b')') {
+                    arguments.push(self.expression()?);
+                    if !self.eat(b')') {
+                        return None;
+                    }
+                }
+                value = self.call(value, arguments)?;
+            } else {
+                return Some(value);
+            }
+        }
+    }
+
+    /// Calls a function, None if it isn't one of the built-ins JSFuck uses
+    fn call(&mut self, function: Value, arguments: Vec<Value>) -> Option<Value> {
+        let argument = arguments.first().cloned().unwrap_or(Value::Undefined);
+        let text_argument = || match &argument {
+            Value::Undefined => String::new(),
+            argument => to_text(argument),
+        };
+        let (name, this) = match function {
+            Value::Code(body) => return Some(self.run(body)),
+            Value::Function { name, this } => (name, this.map(|this| *this)),
+            _ => return None,
+        };
+        let value = match (name.as_str(), this) {
+            ("String", None) => Value::Text(text_argument()),
+            ("Number", None) => Value::Number(if arguments.is_empty() {
+                0.0
+            } else {
+                to_number(&argument)
+            }),
+            ("Boolean", None) => Value::Boolean(truthy(&argument)),
+            ("Array", None) => Value::Array(Vec::new()),
+            ("Object", None) => Value::Object("Object"),
+            ("Function", None) => Value::Code(text_argument()),
+            ("RegExp", None) => Value::RegExp(text_argument()),
+            ("Date", None) => Value::Text(DATE.to_string()),
+            ("escape", None) => Value::Text(escape(&to_text(&argument))),
+            ("unescape", None) => Value::Text(unescape(&to_text(&argument))),
+            ("fromCharCode", None) => {
+                let unit = to_number(&argument) as i64 as u16;
+                Value::Text(String::from_utf16_lossy(&[unit]))
+            }
+            // Object.prototype.toString.call(x) names what x is
+            ("call", Some(Value::Function { name, this })) => match (name.as_str(), this) {
+                ("toString", Some(this)) if matches!(*this, Value::Object(_)) => {
+                    Value::Text(format!("[object {}]", kind(&argument)))
+                }
+                (_, None) => {
+                    let function = Value::Function { name, this: None };
+                    self.call(function, arguments.into_iter().skip(1).collect())?
+                }
+                _ => {
+                    let function = Value::Function {
+                        name,
+                        this: Some(Box::new(argument)),
+                    };
+                    self.call(function, arguments.into_iter().skip(1).collect())?
+                }
+            },
+            ("call", Some(Value::Code(body))) => self.run(body),
+            ("toString", Some(Value::Number(n))) => {
+                let radix = match argument {
+                    Value::Undefined => 10,
+                    radix => index(&radix)? as u32,
+                };
+                if radix == 10 {
+                    Value::Text(number_to_string(n))
+                } else {
+                    Value::Text(number_to_radix(n, radix)?)
+                }
+            }
+            ("toString", Some(Value::Object(kind))) => Value::Text(format!("[object {kind}]")),
+            ("toString", Some(this)) => Value::Text(to_text(&this)),
+            (name, Some(Value::Text(text))) => string_method(name, text, &arguments)?,
+            (name, Some(Value::Array(items))) => array_method(name, items, &arguments)?,
+            _ => return None,
+        };
+        Some(value)
+    }
+
+    /// Runs a function made with `Function`, which is the hidden code unless it only
+    /// returns one of the globals JSFuck reaches for, such as `unescape`
+    fn run(&mut self, body: String) -> Value {
+        let returned = body.trim().strip_prefix("return").map(str::trim);
+        match returned {
+            Some(name) if GLOBALS.contains(&name) => Value::Function {
+                name: name.to_string(),
+                this: None,
+            },
+            Some("{}") => Value::Object("Object"),
+            Some(source)
+                if source.len() >= 2 && source.starts_with('/') && source.ends_with('/') =>
+            {
+                Value::RegExp(source[1..source.len() - 1].to_string())
+            }
+            _ => {
+                self.ran = Some(body);
+                Value::Undefined
+            }
+        }
+    }
+}
+
+/// Calls one of the string methods JSFuck uses
+fn string_method(name: &str, text: String, arguments: &[Value]) -> Option<Value> {
+    let argument = arguments.first().cloned().unwrap_or(Value::Undefined);
+    let length = text.chars().count();
+    if let Some((_, tag)) = HTML_METHODS.iter().find(|(method, _)| *method == name) {
+        return Some(Value::Text(format!("<{tag}>{text}</{tag}>")));
+    }
+    let attribute = to_text(&argument).replace('"', "&quot;");
+    let value = match name {
+        "fontcolor" => format!("<font color=\"{attribute}\">{text}</font>"),
+        "fontsize" => format!("<font size=\"{attribute}\">{text}</font>"),
+        "link" => format!("<a href=\"{attribute}\">{text}</a>"),
+        "anchor" => format!("<a name=\"{attribute}\">{text}</a>"),
+        "concat" => {
+            let joined = arguments
+                .iter()
+                .fold(text, |text, argument| text + &to_text(argument));
+            if joined.len() > MAX_LENGTH {
+                return None;
+            }
+            joined
+        }
+        "slice" => {
+            let start = from_start(index(&argument)?, length);
+            let end = match arguments.get(1) {
+                Some(end) => from_start(index(end)?, length),
+                None => length,
+            };
+            text.chars()
+                .skip(start)
+                .take(end.saturating_sub(start))
+                .collect()
+        }
+        "at" | "charAt" => {
+            let position = index(&argument)?;
+            let position = if name == "at" && position < 0 {
+                from_start(position, length)
+            } else {
+                usize::try_from(position).ok()?
+            };
+            match text.chars().nth(position) {
+                Some(c) => c.to_string(),
+                None if name == "at" => return Some(Value::Undefined),
+                None => String::new(),
+            }
+        }
+        "split" => {
+            let parts = match argument {
+                Value::Undefined => vec![Value::Text(text)],
+                separator => {
+                    let separator = to_text(&separator);
+                    if separator.is_empty() {
+                        text.chars().map(|c| Value::Text(c.to_string())).collect()
+                    } else {
+                        text.split(separator.as_str())
+                            .map(|part| Value::Text(part.to_string()))
+                            .collect()
+                    }
+                }
+            };
+            return Some(Value::Array(parts));
+        }
+        "toUpperCase" => text.to_uppercase(),
+        "toLowerCase" => text.to_lowercase(),
+        "trim" => text.trim().to_string(),
+        _ => return None,
+    };
+    Some(Value::Text(value))
+}
+
+/// Calls one of the array methods JSFuck uses
+fn array_method(name: &str, mut items: Vec<Value>, arguments: &[Value]) -> Option<Value> {
+    let argument = arguments.first().cloned().unwrap_or(Value::Undefined);
+    let value = match name {
+        "concat" => {
+            for argument in arguments {
+                match argument {
+                    Value::Array(more) => items.extend(more.iter().cloned()),
+                    argument => items.push(argument.clone()),
+                }
+            }
+            Value::Array(items)
+        }
+        "join" => {
+            let separator = match argument {
+                Value::Undefined => ",".to_string(),
+                separator => to_text(&separator),
+            };
+            let joined: Vec<String> = items
+                .iter()
+                .map(|item| match item {
+                    Value::Undefined => String::new(),
+                    item => to_text(item),
+                })
+                .collect();
+            Value::Text(joined.join(&separator))
+        }
+        "flat" => {
+            let mut flat = Vec::new();
+            for item in items {
+                match item {
+                    Value::Array(inner) => flat.extend(inner),
+                    item => flat.push(item),
+                }
+            }
+            Value::Array(flat)
+        }
+        "entries" => Value::Object("Array Iterator"),
+        "at" => {
+            let position = from_start(index(&argument)?, items.len());
+            items.get(position).cloned().unwrap_or(Value::Undefined)
+        }
+        "slice" => {
+            let length = items.len();
+            let start = from_start(index(&argument)?, length);
+            let end = match arguments.get(1) {
+                Some(end) => from_start(index(end)?, length),
+                None => length,
+            };
+            Value::Array(
+                items
+                    .into_iter()
+                    .skip(start)
+                    .take(end.saturating_sub(start))
+                    .collect(),
+            )
+        }
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// Evaluates JSFuck, None if the text isn't JSFuck or doesn't come to a string or code
+fn evaluate(text: &str) -> Option<String> {
+    let program: Vec<u8> = text.bytes().filter(|b| !b.is_ascii_whitespace()).collect();
+    if program.len() < MIN_LENGTH
+        || program.len() > MAX_LENGTH
+        || !program.iter().all(|b| b"[]()!+".contains(b))
+    {
+        return None;
+    }
+    let mut evaluator = Evaluator {
+        program: &program,
+        position: 0,
+        depth: 0,
+        ran: None,
+    };
+    let value = evaluator.expression()?;
+    if evaluator.position != program.len() {
+        return None;
+    }
+    let decoded = match (evaluator.ran, value) {
+        (Some(code), _) => code,
+        (None, Value::Text(text)) => text,
+        _ => return None,
+    };
+    if decoded.trim().is_empty() || decoded.len() > MAX_OUTPUT {
+        return None;
+    }
+    Some(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{evaluate, number_to_string, JsfuckDecoder};
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    /// "hello world", with the h and w written in base 36
+    const HELLO_WORLD: &str = "((+((+!+[]+[])+[!+[]+!+[]+!+[]+!+[]+!+[]+!+[]+!+[]]))[((!![]+[])[+[]]+(!![]+[][((![]+[])[+[]]+(![]+[])[!+[]+!+[]]+(![]+[])[+!+[]]+(!![]+[])[+[]])])[(+!+[]+[])+[+[]]]+([]+([]+[])[(([]+[][((![]+[])[+[]]+(![]+[])[!+[]+!+[]]+(![]+[])[+!+[]]+(!![]+[])[+[]])])[!+[]+!+[]+!+[]]+(!![]+[][((![]+[])[+[]]+(![]+[])[!+[]+!+[]]+(![]+[])[+!+[]]+(!![]+[])[+[]])])[(+!+[]+[])+[+[]]]+([][[]]+[])[+!+[]]+(![]+[])[!+[]+!+[]+!+[]]+(!![]+[])[+[]]+(!![]+[])[+!+[]]+([][[]]+[])[+[]]+([]+[][((![]+[])[+[]]+(![]+[])[!+[]+!+[]]+(![]+[])[+!+[]]+(!![]+[])[+[]])])[!+[]+!+[]+!+[]]+(!![]+[])[+[]]+(!![]+[][((![]+[])[+[]]+(![]+[])[!+[]+!+[]]+(![]+[])[+!+[]]+(!![]+[])[+[]])])[(+!+[]+[])+[+[]]]+(!![]+[])[+!+[]])])[!+[]+!+[]+!+[]+!+[]+!+[]+!+[]+!+[]+!+[]+!+[]]+(!![]+[])[+[]]+(!![]+[])[+!+[]]+([![]]+[][[]])[(+!+[]+[])+[+[]]]+([][[]]+[])[+!+[]]+([]+([]+[])[(([]+[][((![]+[])[+[]]+(![]+[])[!+[]+!+[]]+(![]+[])[+!+[]]+(!![]+[])[+[]])])[!+[]+!+[]+!+[]]+(!![]+[][((![]+[])[+[]]+(![]+[])[!+[]+!+[]]+(![]+[])[+!+[]]+(!![]+[])[+[]])])[(+!+[]+[])+[+[]]]+([][[]]+[])[+!+[]]+(![]+[])[!+[]+!+[]+!+[]]+(!![]+[])[+[]]+(!![]+[])[+!+[]]+([][[]]+[])[+[]]+([]+[][((![]+[])[+[]]+(![]+[])[!+[]+!+[]]+(![]+[])[+!+[]]+(!![]+[])[+[]])])[!+[]+!+[]+!+[]]+(!![]+[])[+[]]+(!![]+[][((![]+[])[+[]]+(![]+[])[!+[]+!+[]]+(![]+[])[+!+[]]+(!![]+[])[+[]])])[(+!+[]+[])+[+[]]]+(!![]+[])[+!+[]])])[(+!+[]+[])+[!+[]+!+[]+!+[]+!+[]]])](+((!+[]+!+[]+!+[]+[])+[!+[]+!+[]+!+[]+!+[]+!+[]+!+[]]))+(!![]+[])[!+[]+!+[]+!+[]]+(![]+[])[!+[]+!+[]]+(![]+[])[!+[]+!+[]]+(!![]+[][((![]+[])[+[]]+(![]+[])[!+[]+!+[]]+(![]+[])[+!+[]]+(!![]+[])[+[]])])[(+!+[]+[])+[+[]]]+(+[![]]+[][((![]+[])[+[]]+(![]+[])[!+[]+!+[]]+(![]+[])[+!+[]]+(!![]+[])[+[]])])[(+!+[]+[])+[+!+[]]]+(+((!+[]+!+[]+!+[]+[])+[!+[]+!+[]]))[((!![]+[])[+[]]+(!![]+[][((![]+[])[+[]]+(![]+[])[!+[]+!+[]]+(![]+[])[+!+[]]+(!![]+[])[+[]])])[(+!+[]+[])+[+[]]]+([]+([]+[])[(([]+[][((![]+[])[+[]]+(![]+[])[!+[]+!+[]]+(![]+[])[+!+[]]+(!![]+[])[+[]])])[!+[]+!+[]+!+[]]+(!![]+[][((![]+[])[+[]]+(![]+[])[!+[]+!+[]]+(![]+[])[+!+[]]+(!![]+[])[+[]])])[(+!+[]+[])+[+[]]]+([][[]]+[])[+!+[]]+(![]+[])[!+[]+!+[]+!+[]]+(!![]+[])[+[]]+(!![]+[])[+!+[]]+([][[]]+[])[+[]]+([]+[][((![]+[])[+[]]+(![]+[])[!+[]+!+[]]+(![]+[])[+!+[]]+(!![]+[])[+[]])])[!+[]+!+[]+!+[]]+(!![]+[])[+[]]+(!![]+[][((![]+[])[+[]]+(![]+[])[!+[]+!+[]]+(![]+[])[+!+[]]+(!![]+[])[+[]])])[(+!+[]+[])+[+[]]]+(!![]+[])[+!+[]])])[!+[]+!+[]+!+[]+!+[]+!+[]+!+[]+!+[]+!+[]+!+[]]+(!![]+[])[+[]]+(!![]+[])[+!+[]]+([![]]+[][[]])[(+!+[]+[])+[+[]]]+([][[]]+[])[+!+[]]+([]+([]+[])[(([]+[][((![]+[])[+[]]+(![]+[])[!+[]+!+[]]+(![]+[])[+!+[]]+(!![]+[])[+[]])])[!+[]+!+[]+!+[]]+(!![]+[][((![]+[])[+[]]+(![]+[])[!+[]+!+[]]+(![]+[])[+!+[]]+(!![]+[])[+[]])])[(+!+[]+[])+[+[]]]+([][[]]+[])[+!+[]]+(![]+[])[!+[]+!+[]+!+[]]+(!![]+[])[+[]]+(!![]+[])[+!+[]]+([][[]]+[])[+[]]+([]+[][((![]+[])[+[]]+(![]+[])[!+[]+!+[]]+(![]+[])[+!+[]]+(!![]+[])[+[]])])[!+[]+!+[]+!+[]]+(!![]+[])[+[]]+(!![]+[][((![]+[])[+[]]+(![]+[])[!+[]+!+[]]+(![]+[])[+!+[]]+(!![]+[])[+[]])])[(+!+[]+[])+[+[]]]+(!![]+[])[+!+[]])])[(+!+[]+[])+[!+[]+!+[]+!+[]+!+[]]])](+((!+[]+!+[]+!+[]+[])+[!+[]+!+[]+!+[]+!+[]+!+[]+!+[]]))+(!![]+[][((![]+[])[+[]]+(![]+[])[!+[]+!+[]]+(![]+[])[+!+[]]+(!![]+[])[+[]])])[(+!+[]+[])+[+[]]]+(!![]+[])[+!+[]]+(![]+[])[!+[]+!+[]]+([][[]]+[])[!+[]+!+[]])";
+
+    /// alert(1) handed to the Function constructor and called, as JSFuck runs code
+    const ALERT: &str = "[][((![]+[])[+[]]+(![]+[])[!+[]+!+[]]+(![]+[])[+!+[]]+(!![]+[])[+[]])][(([]+[][((![]+[])[+[]]+(![]+[])[!+[]+!+[]]+(![]+[])[+!+[]]+(!![]+[])[+[]])])[!+[]+!+[]+!+[]]+(!![]+[][((![]+[])[+[]]+(![]+[])[!+[]+!+[]]+(![]+[])[+!+[]]+(!![]+[])[+[]])])[(+!+[]+[])+[+[]]]+([][[]]+[])[+!+[]]+(![]+[])[!+[]+!+[]+!+[]]+(!![]+[])[+[]]+(!![]+[])[+!+[]]+([][[]]+[])[+[]]+([]+[][((![]+[])[+[]]+(![]+[])[!+[]+!+[]]+(![]+[])[+!+[]]+(!![]+[])[+[]])])[!+[]+!+[]+!+[]]+(!![]+[])[+[]]+(!![]+[][((![]+[])[+[]]+(![]+[])[!+[]+!+[]]+(![]+[])[+!+[]]+(!![]+[])[+[]])])[(+!+[]+[])+[+[]]]+(!![]+[])[+!+[]])](((![]+[])[+!+[]]+(![]+[])[!+[]+!+[]]+(!![]+[])[!+[]+!+[]+!+[]]+(!![]+[])[+!+[]]+(!![]+[])[+[]]+([]+[][((![]+[])[+[]]+(![]+[])[!+[]+!+[]]+(![]+[])[+!+[]]+(!![]+[])[+[]])])[(+!+[]+[])+[!+[]+!+[]+!+[]]]+(+!+[]+[])+([]+[][((![]+[])[+[]]+(![]+[])[!+[]+!+[]]+(![]+[])[+!+[]]+(!![]+[])[+[]])])[(+!+[]+[])+[!+[]+!+[]+!+[]+!+[]]]))()";
+
+    #[test]
+    fn successful_decoding() {
+        let jsfuck_decoder = Decoder::<JsfuckDecoder>::new();
+        let result = jsfuck_decoder.crack(HELLO_WORLD, &get_athena_checker());
+        assert!(result.success);
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+    }
+
+    #[test]
+    fn code_handed_to_function_is_the_result() {
+        assert_eq!(evaluate(ALERT).unwrap(), "alert(1)");
+    }
+
+    #[test]
+    fn letters_come_from_false_true_and_undefined() {
+        // (![]+[])[+[]] is "false"[0]
+        assert_eq!(
+            evaluate("(![]+[])[+[]]+(!![]+[])[+!+[]]+([][[]]+[])[+[]]").unwrap(),
+            "fru"
+        );
+    }
+
+    #[test]
+    fn numbers_print_like_javascript() {
+        assert_eq!(number_to_string(1.1e21), "1.1e+21");
+        assert_eq!(number_to_string(1e-7), "1e-7");
+        assert_eq!(number_to_string(0.5), "0.5");
+        assert_eq!(number_to_string(f64::NAN), "NaN");
+    }
+
+    #[test]
+    fn values_which_arent_strings_are_not_results() {
+        // true, written out long enough to get past the length guard
+        assert!(evaluate("!![]+!![]+!![]+!![]+!![]+!![]+!![]+!![]").is_none());
+    }
+
+    #[test]
+    fn broken_programs_are_rejected() {
+        assert!(evaluate("(![]+[])[+[]]+(!![]+[])[+!+[]]+([][[]]+[])[+[]").is_none());
+        assert!(evaluate("[][[]][[]]+(![]+[])[+[]]+(!![]+[])[+!+[]]").is_none());
+    }
+
+    #[test]
+    fn ordinary_text_is_not_evaluated() {
+        assert!(evaluate("call f(x) [note: this is (mostly) fine!]").is_none());
+    }
+
+    #[test]
+    fn jsfuck_handle_panic_if_empty_string() {
+        let jsfuck_decoder = Decoder::<JsfuckDecoder>::new();
+        let result = jsfuck_decoder
+            .crack("", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn jsfuck_handle_panic_if_emoji() {
+        let jsfuck_decoder = Decoder::<JsfuckDecoder>::new();
+        let result = jsfuck_decoder
+            .crack("😂", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+}
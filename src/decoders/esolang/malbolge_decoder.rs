@@ -0,0 +1,320 @@
+//! Run a Malbolge program and return its output
+//! Malbolge was designed to be as hard to program in as possible. It runs on a ternary
+//! machine with 59049 words of memory, decides what each character does from the character
+//! and its position together, and scrambles every instruction after running it. Nobody
+//! writes it by hand, so it turns up in CTFs as machine-made line noise which prints a flag.
+//! The text is only run if it looks like that noise and every character is a valid
+//! instruction where it stands, which ordinary text almost never is.
+//! Call malbolge_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::crack_results::CrackResult;
+use crate::decoders::interface::{check_string_success, Crack, Decoder};
+use crate::decoders::radix::bytes_to_text;
+
+use super::{MAX_OUTPUT, MAX_STEPS};
+
+use log::{debug, info, trace};
+
+/// The words of memory, each ten trits
+const MEMORY: usize = 59049;
+
+/// The fewest characters a program must have, as the shortest useful ones are longer
+const MIN_LENGTH: usize = 20;
+
+/// The fewest different characters, as Malbolge uses most of printable ASCII
+const MIN_DISTINCT: usize = 12;
+
+/// Decides what a character does: the instruction is this table at the character plus its position
+const INSTRUCTIONS: &[u8; 94] = b"+b(29e*j1VMEKLyC})8&m#~W>qxdRp0wkrUo[D7,XTcA\"lI.v%{gJh4G\\-=O@5`_3i<?Z';FNQuY]szf$!BS/|t:Pn6^Ha";
+
+/// What each character is scrambled into after it has run
+const ENCRYPTION: &[u8; 94] = b"5z]&gqtyfr$(we4{WP)H-Zn,[%\\3dL+Q;>U!pJS72FhOA1CB6v^=I_0/8|jsb9m<.TVac`uY*MK'X~xDl}REokN:#?G\"i@";
+
+/// The crazy operation on one pair of trits, indexed by the second operand and then the first
+const CRAZY: [[usize; 3]; 3] = [[1, 0, 0], [1, 0, 2], [2, 2, 1]];
+
+/// The Malbolge decoder, call:
+/// `let malbolge_decoder = Decoder::<MalbolgeDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = malbolge_decoder.crack(input)` to interpret a Malbolge program
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::esolang::malbolge_decoder::{MalbolgeDecoder};
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let malbolge_decoder = Decoder::<MalbolgeDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = malbolge_decoder.crack("(=<`#9]~6ZY32Vx/4Rs+0No-&Jk)\"Fh}|Bcy?`=*z]Kw%oG4UUS0/@-ejc(:'8dc", &checker);
+/// assert_eq!(result.unencrypted_text.unwrap()[0], "Hello World!");
+/// ```
+pub struct MalbolgeDecoder;
+
+impl Crack for Decoder<MalbolgeDecoder> {
+    fn new() -> Decoder<MalbolgeDecoder> {
+        Decoder {
+            name: "Malbolge",
+            description: "Malbolge is an esoteric programming language invented by Ben Olmstead in 1998 to be as difficult to program in as possible. Its instructions depend on where they are in memory and change after every run, so programs look like random punctuation.",
+            link: "https://en.wikipedia.org/wiki/Malbolge",
+            tags: vec!["decoder", "esolang", "malbolge"],
+            popularity: 0.2,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying Malbolge with text {:?}", text);
+        let mut results = CrackResult::new(self, text.to_string());
+
+        if !looks_like_malbolge(text) {
+            debug!("Failed to interpret Malbolge because the text doesn't look like a program");
+            return results;
+        }
+        let Some(memory) = load(text) else {
+            debug!("Failed to interpret Malbolge because a character isn't a valid instruction where it is");
+            return results;
+        };
+        let Some(output) = run(memory) else {
+            debug!("Failed to interpret Malbolge because the program failed or ran too long");
+            return results;
+        };
+        let Some(decoded_text) = bytes_to_text(output) else {
+            debug!("Failed to interpret Malbolge because the output has control characters");
+            return results;
+        };
+        if !check_string_success(&decoded_text, text) {
+            info!(
+                "Failed to interpret Malbolge because check_string_success returned false on string {}",
+                decoded_text
+            );
+            return results;
+        }
+
+        let checker_result = checker.check(&decoded_text);
+        results.unencrypted_text = Some(vec![decoded_text]);
+
+        results.update_checker(&checker_result);
+
+        results
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some((
+            "(=<`#9]~6ZY32Vx/4Rs+0No-&Jk)\"Fh}|Bcy?`=*z]Kw%oG4UUS0/@-ejc(:'8dc",
+            "Hello World!",
+        ))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input must be at least 20 printable ASCII characters besides whitespace, with at least 12 different ones",
+            "At least a quarter of the characters must be punctuation, as in machine-made Malbolge",
+            "Every character must be a valid instruction at its position in memory",
+            "The program must not read input, and must halt within the esolang step limit",
+        ]
+    }
+}
+
+/// Whether the text has the spread of characters Malbolge programs have
+fn looks_like_malbolge(text: &str) -> bool {
+    let program: Vec<u8> = text.bytes().filter(|b| !b.is_ascii_whitespace()).collect();
+    if program.len() < MIN_LENGTH || !program.iter().all(u8::is_ascii_graphic) {
+        return false;
+    }
+    let mut seen = [false; 128];
+    for &c in &program {
+        seen[usize::from(c)] = true;
+    }
+    let distinct = seen.iter().filter(|&&seen| seen).count();
+    let punctuation = program.iter().filter(|c| c.is_ascii_punctuation()).count();
+    distinct >= MIN_DISTINCT && punctuation * 4 >= program.len()
+}
+
+/// The instruction a character stands for at a position in memory
+fn instruction(c: usize, position: usize) -> u8 {
+    INSTRUCTIONS[(c - 33 + position) % 94]
+}
+
+/// The crazy operation, applied trit by trit to two words
+fn crazy(a: usize, d: usize) -> usize {
+    let (mut a, mut d) = (a, d);
+    let mut result = 0;
+    let mut power = 1;
+    for _ in 0..10 {
+        result += CRAZY[d % 3][a % 3] * power;
+        a /= 3;
+        d /= 3;
+        power *= 3;
+    }
+    result
+}
+
+/// Loads a program into memory, None if a character isn't a valid instruction where it is.
+/// The memory after the program is filled by the crazy operation on the two words before.
+fn load(text: &str) -> Option<Vec<usize>> {
+    let mut memory = Vec::with_capacity(MEMORY);
+    for c in text.bytes().filter(|b| !b.is_ascii_whitespace()) {
+        if memory.len() == MEMORY || !c.is_ascii_graphic() {
+            return None;
+        }
+        let c = usize::from(c);
+        if !b"ji*p</vo".contains(&instruction(c, memory.len())) {
+            return None;
+        }
+        memory.push(c);
+    }
+    if memory.len() < 2 {
+        return None;
+    }
+    while memory.len() < MEMORY {
+        let len = memory.len();
+        memory.push(crazy(memory[len - 1], memory[len - 2]));
+    }
+    Some(memory)
+}
+
+/// Runs a loaded program, None if it reads input, runs too long or prints nothing
+fn run(mut memory: Vec<usize>) -> Option<Vec<u8>> {
+    let (mut a, mut c, mut d) = (0, 0, 0);
+    let mut output = Vec::new();
+    for _ in 0..MAX_STEPS {
+        // The reference interpreter spins forever on anything but printable ASCII
+        if !(33..=126).contains(&memory[c]) {
+            return None;
+        }
+        match instruction(memory[c], c) {
+            b'j' => d = memory[d],
+            b'i' => c = memory[d],
+            b'*' => {
+                memory[d] = memory[d] / 3 + memory[d] % 3 * (MEMORY / 3);
+                a = memory[d];
+            }
+            b'p' => {
+                memory[d] = crazy(a, memory[d]);
+                a = memory[d];
+            }
+            b'<' => {
+                output.push((a % 256) as u8);
+                if output.len() > MAX_OUTPUT {
+                    return None;
+                }
+            }
+            b'/' => return None,
+            b'v' => return (!output.is_empty()).then_some(output),
+            _ => {}
+        }
+        if (33..=126).contains(&memory[c]) {
+            memory[c] = usize::from(ENCRYPTION[memory[c] - 33]);
+        }
+        c = (c + 1) % MEMORY;
+        d = (d + 1) % MEMORY;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{load, looks_like_malbolge, run, MalbolgeDecoder};
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    /// The first Malbolge program, which prints Hello World!
+    const HELLO_WORLD: &str = "(=<`#9]~6ZY32Vx/4Rs+0No-&Jk)\"Fh}|Bcy?`=*z]Kw%oG4UUS0/@-ejc(:'8dc";
+
+    #[test]
+    fn successful_decoding() {
+        let malbolge_decoder = Decoder::<MalbolgeDecoder>::new();
+        let result = malbolge_decoder.crack(HELLO_WORLD, &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "Hello World!");
+    }
+
+    #[test]
+    fn whitespace_is_skipped() {
+        let wrapped = format!("{}\n{}", &HELLO_WORLD[..30], &HELLO_WORLD[30..]);
+        assert_eq!(run(load(&wrapped).unwrap()).unwrap(), b"Hello World!");
+    }
+
+    #[test]
+    fn characters_must_be_valid_where_they_are() {
+        // Swapping two characters moves them to positions where they mean nothing
+        let swapped = format!("=({}", &HELLO_WORLD[2..]);
+        assert!(load(&swapped).is_none());
+    }
+
+    #[test]
+    fn programs_reading_input_are_stopped() {
+        // u reads input at position 0, and the rest are no-ops
+        assert!(run(load("uCBA@?>=<;:987654321").unwrap()).is_none());
+    }
+
+    #[test]
+    fn programs_printing_nothing_give_nothing() {
+        assert!(run(load("DCBA@?>=<;:987654321").unwrap()).is_none());
+    }
+
+    #[test]
+    fn ordinary_text_does_not_look_like_malbolge() {
+        assert!(!looks_like_malbolge(
+            "The quick brown fox jumps over the lazy dog"
+        ));
+        assert!(!looks_like_malbolge("!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!"));
+        assert!(looks_like_malbolge(HELLO_WORLD));
+    }
+
+    #[test]
+    fn malbolge_handle_panic_if_empty_string() {
+        let malbolge_decoder = Decoder::<MalbolgeDecoder>::new();
+        let result = malbolge_decoder
+            .crack("", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn malbolge_handle_panic_if_emoji() {
+        let malbolge_decoder = Decoder::<MalbolgeDecoder>::new();
+        let result = malbolge_decoder
+            .crack("😂", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+}
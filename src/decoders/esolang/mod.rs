@@ -0,0 +1,47 @@
+//! Interpreters for esoteric programming languages people hide messages in
+//! Like the Brainfuck interpreter, these only run text which is plainly a program in their
+//! language, and every run is capped in steps and output so a program that loops forever
+//! costs a fraction of a second. Programs which read input are never run, as there is
+//! nothing to give them. Ook! is translated to Brainfuck and run the same way, Whitespace
+//! and Malbolge have their own machines, and JSFuck is evaluated as the small part of
+//! JavaScript it is written in.
+
+/// The jsfuck_decoder module evaluates JSFuck back to the string or code it hides
+pub mod jsfuck_decoder;
+/// The malbolge_decoder module runs Malbolge programs
+pub mod malbolge_decoder;
+/// The ook_interpreter module runs Ook! programs
+pub mod ook_interpreter;
+/// The whitespace_interpreter module runs Whitespace programs
+pub mod whitespace_interpreter;
+
+use brainfuck_exe::Brainfuck;
+use log::debug;
+
+/// The most instructions a program may run before it is given up on
+pub const MAX_STEPS: usize = 200_000;
+
+/// The most output a program may write, as no hidden message needs more
+pub const MAX_OUTPUT: usize = 64 * 1024;
+
+/// The cells a Brainfuck program gets, as many as the original implementation had
+const MEMORY_CELLS: usize = 30_000;
+
+/// Runs a Brainfuck program with the step and memory limits, None if it fails or runs over.
+/// The program must not read input, which the callers check before translating it.
+pub fn run_brainfuck(program: &str) -> Option<Vec<u8>> {
+    let mut output = Vec::new();
+    let run = Brainfuck::new(program)
+        .with_output_ref(&mut output)
+        .with_mem_size(MEMORY_CELLS)
+        .with_instructions_limit(MAX_STEPS)
+        .execute();
+    if let Err(e) = run {
+        debug!("Brainfuck program stopped with error {:?}", e);
+        return None;
+    }
+    if output.is_empty() || output.len() > MAX_OUTPUT {
+        return None;
+    }
+    Some(output)
+}
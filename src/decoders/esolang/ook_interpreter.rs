@@ -0,0 +1,254 @@
+//! Run an Ook! program and return its output
+//! Ook! is Brainfuck written for orangutans: each Brainfuck instruction is a pair of the
+//! words `Ook.`, `Ook?` and `Ook!`, so `Ook. Ook?` moves right and `Ook! Ook.` prints.
+//! Short Ook! leaves the words out and writes only the punctuation. The pairs are
+//! translated back to Brainfuck and run with the step limits of the esolang module.
+//! Call ook_interpreter.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::crack_results::CrackResult;
+use crate::decoders::interface::{check_string_success, Crack, Decoder};
+use crate::decoders::radix::bytes_to_text;
+
+use super::run_brainfuck;
+
+use log::{debug, info, trace};
+
+/// The fewest instructions a program must have, as shorter runs of `Ook.` are just words
+const MIN_INSTRUCTIONS: usize = 10;
+
+/// The Ook! interpreter, call:
+/// `let ook_interpreter = Decoder::<OokInterpreter>::new()` to create a new instance
+/// And then call:
+/// `result = ook_interpreter.crack(input)` to interpret an Ook! program
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::esolang::ook_interpreter::{OokInterpreter};
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let ook_interpreter = Decoder::<OokInterpreter>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = ook_interpreter.crack("Ook. Ook? Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook! Ook? Ook? Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook? Ook! Ook! Ook? Ook! Ook? Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook! Ook. Ook. Ook. Ook! Ook.", &checker);
+/// assert_eq!(result.unencrypted_text.unwrap()[0], "hi");
+/// ```
+pub struct OokInterpreter;
+
+impl Crack for Decoder<OokInterpreter> {
+    fn new() -> Decoder<OokInterpreter> {
+        Decoder {
+            name: "Ook!",
+            description: "Ook! is a joke esoteric programming language designed for orangutans by David Morgan-Mar. It is Brainfuck with each of the eight instructions written as a pair of Ook., Ook? and Ook!",
+            link: "https://esolangs.org/wiki/Ook!",
+            tags: vec!["decoder", "esolang", "ook", "brainfuck"],
+            popularity: 0.3,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying Ook! with text {:?}", text);
+        let mut results = CrackResult::new(self, text.to_string());
+
+        let Some(program) = ook_to_brainfuck(text) else {
+            debug!("Failed to interpret Ook! because the text isn't an Ook! program which prints");
+            return results;
+        };
+        let Some(output) = run_brainfuck(&program) else {
+            debug!("Failed to interpret Ook! because the program failed or ran too long");
+            return results;
+        };
+        let Some(decoded_text) = bytes_to_text(output) else {
+            debug!("Failed to interpret Ook! because the output has control characters");
+            return results;
+        };
+        if !check_string_success(&decoded_text, text) {
+            info!(
+                "Failed to interpret Ook! because check_string_success returned false on string {}",
+                decoded_text
+            );
+            return results;
+        }
+
+        let checker_result = checker.check(&decoded_text);
+        results.unencrypted_text = Some(vec![decoded_text]);
+
+        results.update_checker(&checker_result);
+
+        results
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some(("Ook. Ook? Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook! Ook? Ook? Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook? Ook! Ook! Ook? Ook! Ook? Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook! Ook. Ook. Ook. Ook! Ook.", "hi"))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input must be nothing but `Ook.` `Ook?` `Ook!` words, or only their punctuation for Short Ook!",
+            "The words must pair up into at least 10 Brainfuck instructions, one of them printing",
+            "Programs that read input (`Ook. Ook!`) are not run",
+            "The program must finish within the esolang step limit",
+        ]
+    }
+}
+
+/// The Brainfuck instruction an Ook! pair stands for, None for `Ook? Ook?` which is nothing
+fn instruction(pair: (char, char)) -> Option<char> {
+    let instruction = match pair {
+        ('.', '?') => '>',
+        ('?', '.') => '<',
+        ('.', '.') => '+',
+        ('!', '!') => '-',
+        ('!', '.') => '.',
+        ('.', '!') => ',',
+        ('!', '?') => '[',
+        ('?', '!') => ']',
+        _ => return None,
+    };
+    Some(instruction)
+}
+
+/// The punctuation of each word, None if the text holds anything but Ook! words.
+/// Short Ook! is read the same way, with the words left out.
+fn ook_punctuation(text: &str) -> Option<Vec<char>> {
+    let compact: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    let short = !compact.contains(['O', 'o']);
+    let mut punctuation = Vec::new();
+    let mut rest = compact.as_str();
+    while !rest.is_empty() {
+        if !short {
+            rest = rest
+                .strip_prefix("Ook")
+                .or_else(|| rest.strip_prefix("ook"))?;
+        }
+        let mark = rest.chars().next().filter(|c| ".?!".contains(*c))?;
+        punctuation.push(mark);
+        rest = &rest[1..];
+    }
+    Some(punctuation)
+}
+
+/// Translates an Ook! program into Brainfuck, None if it isn't one worth running
+fn ook_to_brainfuck(text: &str) -> Option<String> {
+    let punctuation = ook_punctuation(text)?;
+    if !punctuation.len().is_multiple_of(2) {
+        return None;
+    }
+    let program: String = punctuation
+        .chunks(2)
+        .map(|pair| instruction((pair[0], pair[1])))
+        .collect::<Option<_>>()?;
+    if program.len() < MIN_INSTRUCTIONS || program.contains(',') || !program.contains('.') {
+        return None;
+    }
+    Some(program)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ook_to_brainfuck, OokInterpreter};
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    /// A program printing hello world
+    const HELLO_WORLD: &str = "Ook. Ook? Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook! Ook? Ook? Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook? Ook! Ook! Ook? Ook! Ook? Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook! Ook. Ook! Ook! Ook! Ook! Ook! Ook! Ook! Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook! Ook. Ook! Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook! Ook. Ook. Ook? Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook! Ook? Ook? Ook. Ook! Ook! Ook! Ook! Ook! Ook! Ook! Ook! Ook! Ook! Ook! Ook! Ook! Ook! Ook! Ook! Ook! Ook! Ook. Ook? Ook! Ook! Ook? Ook! Ook? Ook. Ook! Ook! Ook! Ook! Ook! Ook! Ook! Ook! Ook! Ook! Ook! Ook! Ook! Ook! Ook! Ook. Ook. Ook? Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook! Ook? Ook? Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook? Ook! Ook! Ook? Ook! Ook? Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook! Ook. Ook! Ook! Ook! Ook! Ook! Ook! Ook! Ook! Ook! Ook! Ook! Ook! Ook! Ook! Ook! Ook! Ook! Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook! Ook. Ook! Ook! Ook! Ook! Ook! Ook! Ook! Ook! Ook! Ook! Ook! Ook! Ook! Ook. Ook! Ook! Ook! Ook! Ook! Ook! Ook! Ook! Ook! Ook! Ook! Ook! Ook! Ook! Ook! Ook! Ook! Ook.";
+
+    #[test]
+    fn successful_decoding() {
+        let ook_interpreter = Decoder::<OokInterpreter>::new();
+        let result = ook_interpreter.crack(HELLO_WORLD, &get_athena_checker());
+        assert!(result.success);
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+    }
+
+    #[test]
+    fn pairs_translate_to_brainfuck() {
+        assert_eq!(
+            ook_to_brainfuck("Ook. Ook? Ook. Ook. Ook! Ook? Ook! Ook! Ook? Ook! Ook? Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook! Ook."),
+            Some(">+[-]<++++++.".to_string())
+        );
+    }
+
+    #[test]
+    fn short_ook_is_read() {
+        let short: String = HELLO_WORLD.chars().filter(|c| !c.is_alphabetic()).collect();
+        let ook_interpreter = Decoder::<OokInterpreter>::new();
+        let result = ook_interpreter.crack(&short, &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+    }
+
+    #[test]
+    fn programs_reading_input_are_not_run() {
+        assert!(ook_to_brainfuck("Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook! Ook! Ook.").is_none());
+    }
+
+    #[test]
+    fn endless_loops_are_stopped() {
+        // +[] spins forever
+        let ook_interpreter = Decoder::<OokInterpreter>::new();
+        let result = ook_interpreter.crack("Ook! Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook. Ook! Ook? Ook? Ook! Ook! Ook.", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+
+    #[test]
+    fn text_which_isnt_ook_is_not_run() {
+        assert!(ook_to_brainfuck("Ook. Ook? Ook! said the librarian.").is_none());
+        assert!(ook_to_brainfuck("Wait... what?!").is_none());
+    }
+
+    #[test]
+    fn ook_handle_panic_if_empty_string() {
+        let ook_interpreter = Decoder::<OokInterpreter>::new();
+        let result = ook_interpreter
+            .crack("", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn ook_handle_panic_if_emoji() {
+        let ook_interpreter = Decoder::<OokInterpreter>::new();
+        let result = ook_interpreter
+            .crack("😂", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+}
@@ -0,0 +1,520 @@
+//! Run a Whitespace program and return its output
+//! Whitespace is written only in spaces, tabs and line feeds, and every other character is
+//! a comment, so a program can hide in the blank lines and trailing spaces of a text. It is
+//! a stack machine: each instruction starts with a code saying whether it works on the
+//! stack, arithmetic, the heap, flow control or input and output, and numbers and labels
+//! are written in binary with spaces for 0 and tabs for 1.
+//! The program must print something and stop at an end instruction within the step limit.
+//! Call whitespace_interpreter.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
+use std::collections::HashMap;
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::crack_results::CrackResult;
+use crate::decoders::interface::{check_string_success, Crack, Decoder};
+
+use super::{MAX_OUTPUT, MAX_STEPS};
+
+use log::{debug, info, trace};
+
+/// The deepest subroutine calls may nest, so runaway recursion stops early
+const MAX_CALL_DEPTH: usize = 1024;
+
+/// The largest stack a program may build
+const MAX_STACK: usize = 64 * 1024;
+
+/// The Whitespace interpreter, call:
+/// `let whitespace_interpreter = Decoder::<WhitespaceInterpreter>::new()` to create a new instance
+/// And then call:
+/// `result = whitespace_interpreter.crack(input)` to interpret a Whitespace program
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::esolang::whitespace_interpreter::{WhitespaceInterpreter};
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let whitespace_interpreter = Decoder::<WhitespaceInterpreter>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = whitespace_interpreter.crack("   \t\t \t   \n\t\n     \t\t \t  \t\n\t\n  \n\n\n", &checker);
+/// assert_eq!(result.unencrypted_text.unwrap()[0], "hi");
+/// ```
+pub struct WhitespaceInterpreter;
+
+impl Crack for Decoder<WhitespaceInterpreter> {
+    fn new() -> Decoder<WhitespaceInterpreter> {
+        Decoder {
+            name: "Whitespace",
+            description: "Whitespace is an esoteric programming language by Edwin Brady and Chris Morris in which only spaces, tabs and line feeds mean anything. Everything else is ignored, so programs can be hidden in the blank space of ordinary text.",
+            link: "https://en.wikipedia.org/wiki/Whitespace_(programming_language)",
+            tags: vec!["decoder", "esolang", "whitespace", "steganography"],
+            popularity: 0.2,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying Whitespace with text {:?}", text);
+        let mut results = CrackResult::new(self, text.to_string());
+
+        let Some(program) = parse(text) else {
+            debug!("Failed to interpret Whitespace because the text isn't a Whitespace program");
+            return results;
+        };
+        let Some(decoded_text) = run(&program) else {
+            debug!("Failed to interpret Whitespace because the program failed or ran too long");
+            return results;
+        };
+        if decoded_text
+            .chars()
+            .any(|c| c.is_control() && !c.is_whitespace())
+        {
+            debug!("Failed to interpret Whitespace because the output has control characters");
+            return results;
+        }
+        if !check_string_success(&decoded_text, text) {
+            info!(
+                "Failed to interpret Whitespace because check_string_success returned false on string {}",
+                decoded_text
+            );
+            return results;
+        }
+
+        let checker_result = checker.check(&decoded_text);
+        results.unencrypted_text = Some(vec![decoded_text]);
+
+        results.update_checker(&checker_result);
+
+        results
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some(("   \t\t \t   \n\t\n     \t\t \t  \t\n\t\n  \n\n\n", "hi"))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "The spaces, tabs and line feeds must parse as whole Whitespace instructions",
+            "The program must print something and must not read input",
+            "The program must stop at an end instruction within the esolang step limit",
+        ]
+    }
+}
+
+/// One Whitespace instruction, with labels kept as their string of spaces and tabs
+#[derive(Debug, PartialEq)]
+enum Op {
+    /// Pushes a number
+    Push(i64),
+    /// Copies the top of the stack
+    Duplicate,
+    /// Copies the nth item of the stack to the top
+    Copy(i64),
+    /// Swaps the top two items
+    Swap,
+    /// Drops the top item
+    Discard,
+    /// Drops n items under the top one
+    Slide(i64),
+    /// Adds the top two items
+    Add,
+    /// Subtracts the top item from the one under it
+    Subtract,
+    /// Multiplies the top two items
+    Multiply,
+    /// Divides the item under the top by the top, rounding down
+    Divide,
+    /// The remainder of that division, with the sign of the divisor
+    Modulo,
+    /// Stores the top item at the address under it
+    Store,
+    /// Replaces an address with what is stored there
+    Retrieve,
+    /// Marks a place to jump to
+    Mark(String),
+    /// Calls a subroutine
+    Call(String),
+    /// Jumps to a label
+    Jump(String),
+    /// Jumps if the top item is zero
+    JumpIfZero(String),
+    /// Jumps if the top item is negative
+    JumpIfNegative(String),
+    /// Returns from a subroutine
+    Return,
+    /// Stops the program
+    End,
+    /// Prints the top item as a character
+    PrintChar,
+    /// Prints the top item as a number
+    PrintNumber,
+}
+
+/// Reads Whitespace instructions one token at a time
+struct Parser {
+    /// The spaces (S), tabs (T) and line feeds (L) of the program, comments dropped
+    tokens: Vec<char>,
+    /// The next token to read
+    position: usize,
+}
+
+impl Parser {
+    /// Reads the next token, None at the end of the program
+    fn next(&mut self) -> Option<char> {
+        let token = self.tokens.get(self.position).copied();
+        self.position += 1;
+        token
+    }
+
+    /// Reads a label, which is spaces and tabs ended by a line feed
+    fn label(&mut self) -> Option<String> {
+        let mut label = String::new();
+        loop {
+            match self.next()? {
+                'L' => return Some(label),
+                bit => label.push(bit),
+            }
+        }
+    }
+
+    /// Reads a number, which is a sign and then binary digits ended by a line feed
+    fn number(&mut self) -> Option<i64> {
+        let negative = match self.next()? {
+            'S' => false,
+            'T' => true,
+            _ => return None,
+        };
+        let mut number: i64 = 0;
+        for bit in self.label()?.chars() {
+            number = number.checked_mul(2)?.checked_add(i64::from(bit == 'T'))?;
+        }
+        Some(if negative { -number } else { number })
+    }
+
+    /// Reads the next instruction, None if the tokens don't make one
+    fn op(&mut self) -> Option<Op> {
+        let op = match (self.next()?, self.next()?) {
+            ('S', 'S') => Op::Push(self.number()?),
+            ('S', 'L') => match self.next()? {
+                'S' => Op::Duplicate,
+                'T' => Op::Swap,
+                _ => Op::Discard,
+            },
+            ('S', 'T') => match self.next()? {
+                'S' => Op::Copy(self.number()?),
+                'L' => Op::Slide(self.number()?),
+                _ => return None,
+            },
+            ('T', 'S') => match (self.next()?, self.next()?) {
+                ('S', 'S') => Op::Add,
+                ('S', 'T') => Op::Subtract,
+                ('S', 'L') => Op::Multiply,
+                ('T', 'S') => Op::Divide,
+                ('T', 'T') => Op::Modulo,
+                _ => return None,
+            },
+            ('T', 'T') => match self.next()? {
+                'S' => Op::Store,
+                'T' => Op::Retrieve,
+                _ => return None,
+            },
+            ('L', first) => match (first, self.next()?) {
+                ('S', 'S') => Op::Mark(self.label()?),
+                ('S', 'T') => Op::Call(self.label()?),
+                ('S', 'L') => Op::Jump(self.label()?),
+                ('T', 'S') => Op::JumpIfZero(self.label()?),
+                ('T', 'T') => Op::JumpIfNegative(self.label()?),
+                ('T', 'L') => Op::Return,
+                ('L', 'L') => Op::End,
+                _ => return None,
+            },
+            // Reading input isn't supported, so those programs fail here
+            ('T', 'L') => match (self.next()?, self.next()?) {
+                ('S', 'S') => Op::PrintChar,
+                ('S', 'T') => Op::PrintNumber,
+                _ => return None,
+            },
+            _ => return None,
+        };
+        Some(op)
+    }
+}
+
+/// Parses a Whitespace program, None if it doesn't parse, can't print or never ends
+fn parse(text: &str) -> Option<Vec<Op>> {
+    let tokens: Vec<char> = text
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('S'),
+            '\t' => Some('T'),
+            '\n' => Some('L'),
+            _ => None,
+        })
+        .collect();
+    let mut parser = Parser {
+        tokens,
+        position: 0,
+    };
+    let mut program = Vec::new();
+    while parser.position < parser.tokens.len() {
+        program.push(parser.op()?);
+    }
+    let prints = program
+        .iter()
+        .any(|op| matches!(op, Op::PrintChar | Op::PrintNumber));
+    if !prints || !program.contains(&Op::End) {
+        return None;
+    }
+    Some(program)
+}
+
+/// Divides rounding down, as Whitespace's reference interpreter does, None for zero
+fn floor_div(a: i64, b: i64) -> Option<(i64, i64)> {
+    let quotient = a.checked_div(b)?;
+    let remainder = a.checked_rem(b)?;
+    if remainder != 0 && (remainder < 0) != (b < 0) {
+        return Some((quotient - 1, remainder + b));
+    }
+    Some((quotient, remainder))
+}
+
+/// Runs a parsed program, None if it fails, runs too long or doesn't reach an end
+fn run(program: &[Op]) -> Option<String> {
+    let mut labels = HashMap::new();
+    for (index, op) in program.iter().enumerate() {
+        if let Op::Mark(label) = op {
+            labels.entry(label.as_str()).or_insert(index);
+        }
+    }
+    let mut stack: Vec<i64> = Vec::new();
+    let mut heap: HashMap<i64, i64> = HashMap::new();
+    let mut calls: Vec<usize> = Vec::new();
+    let mut output = String::new();
+    let mut position = 0;
+    for _ in 0..MAX_STEPS {
+        let op = program.get(position)?;
+        position += 1;
+        match op {
+            Op::Push(number) => stack.push(*number),
+            Op::Duplicate => stack.push(*stack.last()?),
+            Op::Copy(n) => {
+                let n = usize::try_from(*n).ok()?;
+                let index = stack.len().checked_sub(n + 1)?;
+                stack.push(stack[index]);
+            }
+            Op::Swap => {
+                let len = stack.len();
+                if len < 2 {
+                    return None;
+                }
+                stack.swap(len - 1, len - 2);
+            }
+            Op::Discard => {
+                stack.pop()?;
+            }
+            Op::Slide(n) => {
+                let top = stack.pop()?;
+                let n = usize::try_from(*n).ok()?;
+                stack.truncate(stack.len().saturating_sub(n));
+                stack.push(top);
+            }
+            Op::Add | Op::Subtract | Op::Multiply | Op::Divide | Op::Modulo => {
+                let b = stack.pop()?;
+                let a = stack.pop()?;
+                let result = match op {
+                    Op::Add => a.checked_add(b)?,
+                    Op::Subtract => a.checked_sub(b)?,
+                    Op::Multiply => a.checked_mul(b)?,
+                    Op::Divide => floor_div(a, b)?.0,
+                    _ => floor_div(a, b)?.1,
+                };
+                stack.push(result);
+            }
+            Op::Store => {
+                let value = stack.pop()?;
+                let address = stack.pop()?;
+                heap.insert(address, value);
+            }
+            Op::Retrieve => {
+                let address = stack.pop()?;
+                stack.push(heap.get(&address).copied().unwrap_or_default());
+            }
+            Op::Mark(_) => {}
+            Op::Call(label) => {
+                if calls.len() >= MAX_CALL_DEPTH {
+                    return None;
+                }
+                calls.push(position);
+                position = *labels.get(label.as_str())?;
+            }
+            Op::Jump(label) => position = *labels.get(label.as_str())?,
+            Op::JumpIfZero(label) => {
+                if stack.pop()? == 0 {
+                    position = *labels.get(label.as_str())?;
+                }
+            }
+            Op::JumpIfNegative(label) => {
+                if stack.pop()? < 0 {
+                    position = *labels.get(label.as_str())?;
+                }
+            }
+            Op::Return => position = calls.pop()?,
+            Op::End => {
+                return if output.is_empty() {
+                    None
+                } else {
+                    Some(output)
+                };
+            }
+            Op::PrintChar => {
+                let c = char::from_u32(u32::try_from(stack.pop()?).ok()?)?;
+                output.push(c);
+            }
+            Op::PrintNumber => output.push_str(&stack.pop()?.to_string()),
+        }
+        if stack.len() > MAX_STACK || output.len() > MAX_OUTPUT {
+            return None;
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, run, WhitespaceInterpreter};
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    /// Writes a program from S, T and L so the tests can be read
+    fn program(source: &str) -> String {
+        source
+            .chars()
+            .filter_map(|c| match c {
+                'S' => Some(' '),
+                'T' => Some('\t'),
+                'L' => Some('\n'),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Writes a program printing the text one character at a time
+    fn printing(text: &str) -> String {
+        let mut source = String::new();
+        for c in text.chars() {
+            let bits: String = format!("{:b}", c as u32)
+                .chars()
+                .map(|bit| if bit == '1' { 'T' } else { 'S' })
+                .collect();
+            source.push_str(&format!("SSS{bits}L TLSS "));
+        }
+        source.push_str("LLL");
+        program(&source)
+    }
+
+    #[test]
+    fn successful_decoding() {
+        let whitespace_interpreter = Decoder::<WhitespaceInterpreter>::new();
+        let result = whitespace_interpreter.crack(&printing("hello world"), &get_athena_checker());
+        assert!(result.success);
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+    }
+
+    #[test]
+    fn comments_are_ignored() {
+        let hidden: String = printing("hi")
+            .split('\n')
+            .collect::<Vec<_>>()
+            .join("nothing_to_see_here\n");
+        assert_eq!(run(&parse(&hidden).unwrap()).unwrap(), "hi");
+    }
+
+    #[test]
+    fn arithmetic_and_the_stack_work() {
+        // push 104, duplicate, print, push 1, add, print, end
+        let source = program("SSSTTSTSSSL SLS TLSS SSSTL TSSS TLSS LLL");
+        assert_eq!(run(&parse(&source).unwrap()).unwrap(), "hi");
+    }
+
+    #[test]
+    fn loops_count_down() {
+        // push 3; label: dup, print number, push 1, subtract, dup, jump to end if zero, jump to label
+        let source = program("SSSTTL LSSSL SLS TLST SSSTL TSST SLS LTSTL LSLSL LSSTL SLL LLL");
+        assert_eq!(run(&parse(&source).unwrap()).unwrap(), "321");
+    }
+
+    #[test]
+    fn endless_loops_are_stopped() {
+        // print 1, then jump to a label forever
+        let source = program("SSSTL TLST LSSSL LSLSL LLL");
+        assert!(run(&parse(&source).unwrap()).is_none());
+    }
+
+    #[test]
+    fn programs_reading_input_are_not_run() {
+        // read a character, then print and end
+        let source = program("SSSTL TLTS SSSTL TLST LLL");
+        assert!(parse(&source).is_none());
+    }
+
+    #[test]
+    fn ordinary_text_is_not_run() {
+        assert!(parse("hello world\nhow are you\n").is_none());
+        assert!(parse("a\ttab separated\tline\n").is_none());
+    }
+
+    #[test]
+    fn whitespace_handle_panic_if_empty_string() {
+        let whitespace_interpreter = Decoder::<WhitespaceInterpreter>::new();
+        let result = whitespace_interpreter
+            .crack("", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn whitespace_handle_panic_if_emoji() {
+        let whitespace_interpreter = Decoder::<WhitespaceInterpreter>::new();
+        let result = whitespace_interpreter
+            .crack("😂", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+}
@@ -0,0 +1,252 @@
+//! Statistical helpers shared by the classical cipher decoders
+//! Covers letter frequencies, the Index of Coincidence, chi-squared scoring against English
+//! and Kasiski examination, which together are enough to break most periodic ciphers.
+
+use crate::storage::ENGLISH_FREQS;
+
+/// Expected Index of Coincidence for English text
+pub const ENGLISH_IOC: f64 = 0.0667;
+
+/// How much a repeated trigram spacing counts towards a key length, relative to the IoC
+const KASISKI_WEIGHT: f64 = 0.01;
+
+/// Turns the ASCII letters of the text into numbers from 0 (A) to 25 (Z), dropping everything else
+pub fn letter_indices(text: &str) -> Vec<u8> {
+    text.bytes()
+        .filter(u8::is_ascii_alphabetic)
+        .map(|b| b.to_ascii_uppercase() - b'A')
+        .collect()
+}
+
+/// Counts how often each letter occurs
+pub fn letter_counts(letters: &[u8]) -> [usize; 26] {
+    let mut counts = [0; 26];
+    for &letter in letters {
+        counts[letter as usize] += 1;
+    }
+    counts
+}
+
+/// The chance that two letters picked at random are the same
+/// English sits around 0.067 while uniformly random letters sit around 0.038.
+/// Works on the counts of any set of symbols, not just letters.
+pub fn index_of_coincidence(counts: &[usize]) -> f64 {
+    let total: usize = counts.iter().sum();
+    if total < 2 {
+        return 0.0;
+    }
+    let matches: usize = counts
+        .iter()
+        .map(|&count| count * count.saturating_sub(1))
+        .sum();
+    matches as f64 / (total * (total - 1)) as f64
+}
+
+/// Splits the letters into `period` columns and averages the Index of Coincidence of each
+/// When the period matches the key length every column is a simple shift of English,
+/// so the average is close to [`ENGLISH_IOC`].
+pub fn average_column_ioc(letters: &[u8], period: usize) -> f64 {
+    if period == 0 {
+        return 0.0;
+    }
+    let total: f64 = (0..period)
+        .map(|i| index_of_coincidence(&letter_counts(&column(letters, i, period))))
+        .sum();
+    total / period as f64
+}
+
+/// Takes every `period`th letter starting at `offset`
+pub fn column(letters: &[u8], offset: usize, period: usize) -> Vec<u8> {
+    letters
+        .iter()
+        .skip(offset)
+        .step_by(period)
+        .copied()
+        .collect()
+}
+
+/// How far the letter counts are from English, lower is more English-like
+pub fn chi_squared(counts: &[usize; 26]) -> f64 {
+    let total: usize = counts.iter().sum();
+    if total == 0 {
+        return f64::MAX;
+    }
+    counts
+        .iter()
+        .zip(ENGLISH_FREQS.iter())
+        .map(|(&count, &freq)| {
+            let expected = freq * total as f64;
+            let diff = count as f64 - expected;
+            diff * diff / expected
+        })
+        .sum()
+}
+
+/// Scores every Caesar shift of the column, most English-like first
+/// Returns pairs of the shift (0 for A, 25 for Z) and its chi-squared score.
+pub fn ranked_shifts(column: &[u8]) -> Vec<(u8, f64)> {
+    let counts = letter_counts(column);
+    let mut shifts: Vec<(u8, f64)> = (0..26u8)
+        .map(|shift| {
+            let mut shifted = [0; 26];
+            for (letter, &count) in counts.iter().enumerate() {
+                shifted[(letter + 26 - shift as usize) % 26] = count;
+            }
+            (shift, chi_squared(&shifted))
+        })
+        .collect();
+    shifts.sort_by(|a, b| a.1.total_cmp(&b.1));
+    shifts
+}
+
+/// Kasiski examination
+/// Finds repeated trigrams and counts how many of the gaps between them each
+/// key length from 2 to `max_length` divides. Index 0 and 1 are always 0.
+pub fn kasiski_support(letters: &[u8], max_length: usize) -> Vec<usize> {
+    let mut support = vec![0; max_length + 1];
+    let mut last_seen = std::collections::HashMap::new();
+    for (position, trigram) in letters.windows(3).enumerate() {
+        if let Some(previous) = last_seen.insert(trigram, position) {
+            let gap = position - previous;
+            for (length, count) in support.iter_mut().enumerate().skip(2) {
+                if gap.is_multiple_of(length) {
+                    *count += 1;
+                }
+            }
+        }
+    }
+    support
+}
+
+/// Estimates the most likely key lengths of a periodic cipher, best first
+///
+/// Each length from 1 to `max_length` is scored by how close its average column IoC is to
+/// English, with a bonus for lengths that Kasiski examination supports.
+pub fn estimate_key_lengths(letters: &[u8], max_length: usize, count: usize) -> Vec<usize> {
+    let max_length = max_length.min(letters.len());
+    let support = kasiski_support(letters, max_length);
+    let most_support = support.iter().copied().max().unwrap_or(0).max(1) as f64;
+
+    let mut scored: Vec<(usize, f64)> = (1..=max_length)
+        .map(|length| {
+            let ioc_distance = (average_column_ioc(letters, length) - ENGLISH_IOC).abs();
+            let kasiski_bonus = KASISKI_WEIGHT * support[length] as f64 / most_support;
+            (length, ioc_distance - kasiski_bonus)
+        })
+        .collect();
+    scored.sort_by(|a, b| a.1.total_cmp(&b.1));
+    scored
+        .into_iter()
+        .take(count)
+        .map(|(length, _)| length)
+        .collect()
+}
+
+/// Shortens a key made of a shorter key repeated, such as LEMONLEMON to LEMON
+pub fn shortest_period(key: &str) -> String {
+    let bytes = key.as_bytes();
+    (1..=bytes.len())
+        .find(|&period| {
+            bytes.len().is_multiple_of(period)
+                && bytes.chunks(period).all(|c| c == &bytes[..period])
+        })
+        .map_or_else(|| key.to_string(), |period| key[..period].to_string())
+}
+
+/// How English-like the text is as a whole, lower is better
+pub fn english_score(text: &str) -> f64 {
+    chi_squared(&letter_counts(&letter_indices(text)))
+}
+
+/// How English-like a run of bytes is, higher is better
+/// Letters score their English frequency and spaces score like a common letter.
+/// Other printable characters score nothing and unprintable bytes are penalised,
+/// which is what lets us pick the right key out of hundreds of XOR candidates.
+pub fn english_byte_fitness(bytes: &[u8]) -> f64 {
+    if bytes.is_empty() {
+        return 0.0;
+    }
+    let total: f64 = bytes
+        .iter()
+        .map(|&b| match b {
+            b'a'..=b'z' => ENGLISH_FREQS[(b - b'a') as usize],
+            b'A'..=b'Z' => ENGLISH_FREQS[(b - b'A') as usize],
+            b' ' => 0.15,
+            b'\n' | b'\r' | b'\t' => 0.0,
+            _ if b.is_ascii_graphic() => 0.0,
+            _ => -1.0,
+        })
+        .sum();
+    total / bytes.len() as f64
+}
+
+/// Counts the bits which differ between two runs of bytes of the same length
+pub fn hamming_distance(a: &[u8], b: &[u8]) -> u32 {
+    a.iter().zip(b).map(|(x, y)| (x ^ y).count_ones()).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// "It was the best of times..." encrypted with the key LEMON
+    const LEMON_TEXT: &str = "Tx iof elq prdx at gtqqg, ve amg gsi icedx at gtqqg, ve amg gsi mur zj iwfosy, wg hee hup ess bq jacytwtbrdw, uh jlw fvr ptaqu zj nsytir, wg hee hup ibcps sr wanvqrhwmfm, ve amg gsi esndsz cs wmsvg, tx iof elq grlwab bq hmfxyieg";
+
+    #[test]
+    fn letter_indices_drop_non_letters() {
+        assert_eq!(letter_indices("a-Z 1!"), vec![0, 25]);
+    }
+
+    #[test]
+    fn index_of_coincidence_of_repeated_letter_is_one() {
+        let counts = letter_counts(&letter_indices("aaaa"));
+        assert!((index_of_coincidence(&counts) - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn index_of_coincidence_needs_two_letters() {
+        assert_eq!(index_of_coincidence(&letter_counts(&[3])), 0.0);
+    }
+
+    #[test]
+    fn ranked_shifts_finds_caesar_shift() {
+        // "meet me at the usual place at ten rather than eight" shifted by 3
+        let shifted = letter_indices("phhw ph dw wkh xvxdo sodfh dw whq udwkhu wkdq hljkw");
+        assert_eq!(ranked_shifts(&shifted)[0].0, 3);
+    }
+
+    #[test]
+    fn kasiski_supports_the_key_length() {
+        let support = kasiski_support(&letter_indices(LEMON_TEXT), 10);
+        let best = (2..=10).max_by_key(|&length| support[length]).unwrap();
+        assert_eq!(best % 5, 0);
+    }
+
+    #[test]
+    fn key_length_estimate_includes_real_length() {
+        let lengths = estimate_key_lengths(&letter_indices(LEMON_TEXT), 20, 3);
+        assert!(lengths.contains(&5), "estimated {:?}", lengths);
+    }
+
+    #[test]
+    fn test_shortest_period() {
+        assert_eq!(shortest_period("LEMONLEMON"), "LEMON");
+        assert_eq!(shortest_period("AAAA"), "A");
+        assert_eq!(shortest_period("LEMONS"), "LEMONS");
+    }
+
+    #[test]
+    fn english_scores_better_than_gibberish() {
+        assert!(english_score("the quick brown fox") < english_score("xqz jvk wqzx"));
+    }
+
+    #[test]
+    fn hamming_distance_counts_differing_bits() {
+        assert_eq!(hamming_distance(b"this is a test", b"wokka wokka!!!"), 37);
+    }
+
+    #[test]
+    fn unprintable_bytes_are_less_fit() {
+        assert!(english_byte_fitness(b"hello world") > english_byte_fitness(b"\x01\x02\x03"));
+    }
+}
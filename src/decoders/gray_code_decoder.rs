@@ -0,0 +1,187 @@
+//! Decode bytes written in Gray code
+//! Gray code orders binary numbers so that counting up only ever changes one bit, which is
+//! why rotary encoders and other electronics use it. Each byte is written as its eight
+//! Gray code bits, just like a binary byte string, and is turned back into a byte by
+//! XORing every bit with all the bits before it.
+//! Call gray_code_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::check_string_success;
+use crate::decoders::radix::{bytes_to_text, parse_bytes};
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::Decoder;
+
+use log::{debug, info, trace};
+
+/// The Gray code decoder, call:
+/// `let gray_code_decoder = Decoder::<GrayCodeDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = gray_code_decoder.crack(input)` to decode a Gray coded byte string
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::gray_code_decoder::{GrayCodeDecoder};
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_gray_code = Decoder::<GrayCodeDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_gray_code.crack("01011100 01010111 01011010 01011010 01011000", &checker).unencrypted_text;
+/// assert!(result.is_some());
+/// assert_eq!(result.unwrap()[0], "hello");
+/// ```
+pub struct GrayCodeDecoder;
+
+impl Crack for Decoder<GrayCodeDecoder> {
+    fn new() -> Decoder<GrayCodeDecoder> {
+        Decoder {
+            name: "Gray code",
+            description: "Gray code, or reflected binary code, orders binary numbers so that neighbouring values differ in only one bit. Text is written as the Gray code of each byte, which looks like ordinary binary but decodes to nonsense if read as it.",
+            link: "https://en.wikipedia.org/wiki/Gray_code",
+            tags: vec!["gray code", "binary", "decoder"],
+            popularity: 0.3,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying Gray code with text {:?}", text);
+        let mut results = CrackResult::new(self, text.to_string());
+
+        let decoded_text = match parse_bytes(text, 2, 8)
+            .map(|bytes| bytes.into_iter().map(gray_to_binary).collect())
+            .and_then(bytes_to_text)
+        {
+            Some(decoded_text) => decoded_text,
+            None => {
+                debug!("Failed to decode Gray code because the input isn't a binary byte string");
+                return results;
+            }
+        };
+
+        if !check_string_success(&decoded_text, text) {
+            info!(
+                "Failed to decode Gray code because check_string_success returned false on string {}",
+                decoded_text
+            );
+            return results;
+        }
+
+        let checker_result = checker.check(&decoded_text);
+        results.unencrypted_text = Some(vec![decoded_text]);
+
+        results.update_checker(&checker_result);
+
+        results
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some(("01011100 01010111 01011010 01011010 01011000", "hello"))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Every number must only use the digits 0 and 1 and be at most 8 bits",
+            "Bytes are separated by whitespace, `,`, `:`, `;` or `\\`, or written continuously with 8 bits each",
+            "Must decode to at least 3 bytes of valid UTF-8 without control characters",
+        ]
+    }
+}
+
+/// Turns a Gray coded byte back into the byte, each bit is the XOR of itself and every
+/// bit above it
+fn gray_to_binary(gray: u8) -> u8 {
+    let mut byte = gray;
+    let mut shifted = gray >> 1;
+    while shifted != 0 {
+        byte ^= shifted;
+        shifted >>= 1;
+    }
+    byte
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{gray_to_binary, GrayCodeDecoder};
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn gray_code_with_spaces_decodes() {
+        let decoder = Decoder::<GrayCodeDecoder>::new();
+        let result = decoder.crack(
+            "01011100 01010111 01011010 01011010 01011000 00110000 01001100 01011000 01001011 01011010 01010110",
+            &get_athena_checker(),
+        );
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+    }
+
+    #[test]
+    fn continuous_gray_code_decodes() {
+        let decoder = Decoder::<GrayCodeDecoder>::new();
+        let result = decoder.crack(
+            "0101110001010111010110100101101001011000",
+            &get_athena_checker(),
+        );
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello");
+    }
+
+    #[test]
+    fn every_byte_round_trips() {
+        for byte in 0..=255u8 {
+            assert_eq!(gray_to_binary(byte ^ (byte >> 1)), byte);
+        }
+    }
+
+    #[test]
+    fn gray_code_rejects_other_digits() {
+        let decoder = Decoder::<GrayCodeDecoder>::new();
+        let result = decoder.crack("01011100 01010121 01011010", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+
+    #[test]
+    fn gray_code_rejects_empty_string() {
+        let decoder = Decoder::<GrayCodeDecoder>::new();
+        let result = decoder.crack("", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+}
@@ -0,0 +1,226 @@
+//! Identifies password hashes and looks them up in the dictionary
+//! The text is recognised as a hash by its length in hex digits or its crypt prefix, and
+//! every word of the dictionary is hashed with the algorithms it could be from until one
+//! matches. The dictionary is the common keys the brute force decoder tries, then the
+//! words of the wordlist. Salted crypt formats such as bcrypt are only identified.
+//! Call hash_identifier.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::brute_force::keys;
+use crate::decoders::crack_results::CrackResult;
+use crate::decoders::interface::{Crack, Decoder};
+use crate::decoders::radix::decode_hex;
+
+use super::identify;
+
+use log::{debug, trace};
+use std::time::Duration;
+
+/// Stop hashing words when less than this is left before the search times out
+const MIN_TIME_TO_TRY: Duration = Duration::from_millis(250);
+
+/// The Hash identifier, call:
+/// `let hash_identifier = Decoder::<HashIdentifier>::new()` to create a new instance
+/// And then call:
+/// `result = hash_identifier.crack(input)` to look the hash up
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::hashes::hash_identifier::{HashIdentifier};
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let hash_identifier = Decoder::<HashIdentifier>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// // The MD5 hash of "password"
+/// let result = hash_identifier.crack("5f4dcc3b5aa765d61d8327deb882cf99", &checker);
+/// assert_eq!(result.unencrypted_text.unwrap()[0], "password");
+/// assert!(result.description.contains("MD5"));
+/// ```
+pub struct HashIdentifier;
+
+impl Crack for Decoder<HashIdentifier> {
+    fn new() -> Decoder<HashIdentifier> {
+        Decoder {
+            name: "Hash identifier",
+            description: "Recognises MD5, NTLM, SHA-1, SHA-2 and crypt hashes such as bcrypt by their length and prefix, and looks them up by hashing the common keys and the words of the wordlist.",
+            link: "https://en.wikipedia.org/wiki/Cryptographic_hash_function",
+            tags: vec!["hash", "md5", "sha1", "sha256", "ntlm", "bcrypt"],
+            popularity: 0.2,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// This function does the actual decoding
+    /// It returns the word the hash is of, if it is in the dictionary
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying the hash identifier with text {:?}", text);
+        let mut results = CrackResult::new(self, text.to_string());
+
+        let algorithms = identify(text);
+        if algorithms.is_empty() {
+            debug!("The hash identifier skipped text which isn't shaped like a hash");
+            return results;
+        }
+        let Some(hash) = decode_hex(text.trim()) else {
+            debug!(
+                "The text looks like a {} hash, which can't be looked up",
+                algorithms[0].name
+            );
+            return results;
+        };
+
+        for word in keys() {
+            if self
+                .remaining_time()
+                .is_some_and(|left| left < MIN_TIME_TO_TRY)
+            {
+                debug!("The hash identifier is almost out of time, stopping the lookup");
+                results.timed_out = true;
+                break;
+            }
+            let Some(algorithm) = algorithms.iter().find(|algorithm| {
+                algorithm
+                    .hash
+                    .is_some_and(|hash_word| hash_word(word.as_bytes()) == hash)
+            }) else {
+                continue;
+            };
+            trace!(
+                "The hash identifier found the {} hash of {word:?}",
+                algorithm.name
+            );
+            let checker_result = checker.check(word);
+            results.unencrypted_text = Some(vec![word.to_string()]);
+            results.description = format!(
+                "{} The text is the {} hash of a word in the dictionary.",
+                self.description, algorithm.name
+            );
+            results.update_checker(&checker_result);
+            return results;
+        }
+
+        debug!(
+            "The text looks like a {} hash of a word which isn't in the dictionary",
+            algorithms
+                .iter()
+                .map(|algorithm| algorithm.name)
+                .collect::<Vec<_>>()
+                .join(" or ")
+        );
+        results
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some(("5f4dcc3b5aa765d61d8327deb882cf99", "password"))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input must be 32, 40, 56, 64, 96 or 128 hex digits, or a bcrypt, MD5-crypt or SHA-crypt hash",
+            "Only MD5, NTLM, SHA-1 and SHA-2 hashes are looked up, crypt hashes are only identified",
+            "The hash must be of one of the common keys or a word of the wordlist",
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HashIdentifier;
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn sha1_hash_is_looked_up() {
+        let decoder = Decoder::<HashIdentifier>::new();
+        let result = decoder.crack(
+            "e5e9fa1ba31ecd1ae84f75caaa474f3a663f05f4",
+            &get_athena_checker(),
+        );
+        assert_eq!(result.unencrypted_text.unwrap()[0], "secret");
+        assert!(result.description.contains("SHA-1"));
+    }
+
+    #[test]
+    fn sha256_hash_is_looked_up() {
+        let decoder = Decoder::<HashIdentifier>::new();
+        let result = decoder.crack(
+            "1c8bfe8f801d79745c4631d09fff36c82aa37fc4cce4fc946683d7b336b63032",
+            &get_athena_checker(),
+        );
+        assert_eq!(result.unencrypted_text.unwrap()[0], "letmein");
+    }
+
+    #[test]
+    fn uppercase_ntlm_hash_is_looked_up() {
+        let decoder = Decoder::<HashIdentifier>::new();
+        let result = decoder.crack("8846F7EAEE8FB117AD06BDD830B7586C", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "password");
+        assert!(result.description.contains("NTLM"));
+    }
+
+    #[test]
+    fn hash_of_an_unknown_word_gives_nothing() {
+        let decoder = Decoder::<HashIdentifier>::new();
+        let result = decoder.crack("0123456789abcdef0123456789abcdef", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+
+    #[test]
+    fn bcrypt_is_only_identified() {
+        let decoder = Decoder::<HashIdentifier>::new();
+        let result = decoder.crack(
+            "$2b$12$GhvMmNVjRW29ulnudl.LbuAnUtN/LRfe1JsBm1Xu6LE3059z5Tr8m",
+            &get_athena_checker(),
+        );
+        assert!(result.unencrypted_text.is_none());
+    }
+
+    #[test]
+    fn hash_identifier_rejects_empty_string() {
+        let decoder = Decoder::<HashIdentifier>::new();
+        let result = decoder.crack("", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+
+    #[test]
+    fn hash_identifier_rejects_emoji() {
+        let decoder = Decoder::<HashIdentifier>::new();
+        let result = decoder.crack("😂", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+}
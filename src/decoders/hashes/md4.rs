@@ -0,0 +1,82 @@
+//! The MD4 hash from RFC 1320
+//! MD4 is the older, three round predecessor of MD5. It is long broken, but NTLM password
+//! hashes are still MD4 of the UTF-16LE password, so it is needed to look them up.
+
+use super::pad;
+
+/// The order the second round reads the words in
+const SECOND_ORDER: [usize; 16] = [0, 4, 8, 12, 1, 5, 9, 13, 2, 6, 10, 14, 3, 7, 11, 15];
+
+/// The order the third round reads the words in
+const THIRD_ORDER: [usize; 16] = [0, 8, 4, 12, 2, 10, 6, 14, 1, 9, 5, 13, 3, 11, 7, 15];
+
+/// The MD4 hash of the data
+pub fn md4(data: &[u8]) -> Vec<u8> {
+    let mut state: [u32; 4] = [0x6745_2301, 0xefcd_ab89, 0x98ba_dcfe, 0x1032_5476];
+    for block in pad(data, false).chunks(64) {
+        let words: [u32; 16] = std::array::from_fn(|i| {
+            u32::from_le_bytes([
+                block[i * 4],
+                block[i * 4 + 1],
+                block[i * 4 + 2],
+                block[i * 4 + 3],
+            ])
+        });
+        let [mut a, mut b, mut c, mut d] = state;
+        for step in 0..48 {
+            let (mixed, word, constant, shift) = match step / 16 {
+                0 => ((b & c) | (!b & d), step, 0, [3, 7, 11, 19][step % 4]),
+                1 => (
+                    (b & c) | (b & d) | (c & d),
+                    SECOND_ORDER[step % 16],
+                    0x5a82_7999,
+                    [3, 5, 9, 13][step % 4],
+                ),
+                _ => (
+                    b ^ c ^ d,
+                    THIRD_ORDER[step % 16],
+                    0x6ed9_eba1,
+                    [3, 9, 11, 15][step % 4],
+                ),
+            };
+            let sum = a
+                .wrapping_add(mixed)
+                .wrapping_add(words[word])
+                .wrapping_add(constant)
+                .rotate_left(shift);
+            a = d;
+            d = c;
+            c = b;
+            b = sum;
+        }
+        for (word, new) in state.iter_mut().zip([a, b, c, d]) {
+            *word = word.wrapping_add(new);
+        }
+    }
+    state.iter().flat_map(|word| word.to_le_bytes()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::md4;
+
+    #[test]
+    fn rfc_1320_examples() {
+        let examples = [
+            ("", "31d6cfe0d16ae931b73c59d7e0c089c0"),
+            ("abc", "a448017aaf21d8525fc10ae87aa6729d"),
+            ("message digest", "d9130a8164549fe818874806e1c7014b"),
+            (
+                "12345678901234567890123456789012345678901234567890123456789012345678901234567890",
+                "e33b4ddc9c38f2199c3e7b164fcc0536",
+            ),
+        ];
+        for (message, hash) in examples {
+            let hex: String = md4(message.as_bytes())
+                .iter()
+                .map(|b| format!("{b:02x}"))
+                .collect();
+            assert_eq!(hex, hash);
+        }
+    }
+}
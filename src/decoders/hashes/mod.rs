@@ -0,0 +1,236 @@
+//! Recognising password hashes and looking them up in a dictionary
+//! Hashes can't be decoded, only guessed, so without this every decoder fails on them
+//! without a word. The format of a hash gives away the likely algorithm: its length in hex
+//! digits, or a prefix such as `$2b$` for bcrypt. Hashes of an algorithm we can compute are
+//! looked up by hashing the common keys and the words of the wordlist set with
+//! `wordlist_path` or `--wordlist`.
+
+/// The hash_identifier module looks up hashes in the dictionary
+pub mod hash_identifier;
+/// The md4 module implements the MD4 hash, which NTLM is built on
+pub mod md4;
+/// The md5 module implements the MD5 hash
+pub mod md5;
+/// The sha1 module implements the SHA-1 hash
+pub mod sha1;
+
+use sha2::{Digest, Sha224, Sha256, Sha384, Sha512};
+
+/// Hashes a word the way an algorithm does
+pub type HashFunction = fn(&[u8]) -> Vec<u8>;
+
+/// A hash algorithm a text may have come from
+pub struct Algorithm {
+    /// The name of the algorithm
+    pub name: &'static str,
+    /// Hashes a word the way the algorithm does, None if we can't compute it
+    pub hash: Option<HashFunction>,
+}
+
+/// The algorithms written as this many hex digits, most likely first
+static HEX_ALGORITHMS: [(usize, &[Algorithm]); 6] = [
+    (
+        32,
+        &[
+            Algorithm {
+                name: "MD5",
+                hash: Some(md5::md5),
+            },
+            Algorithm {
+                name: "NTLM",
+                hash: Some(ntlm),
+            },
+        ],
+    ),
+    (
+        40,
+        &[Algorithm {
+            name: "SHA-1",
+            hash: Some(sha1::sha1),
+        }],
+    ),
+    (
+        56,
+        &[Algorithm {
+            name: "SHA-224",
+            hash: Some(|word| Sha224::digest(word).to_vec()),
+        }],
+    ),
+    (
+        64,
+        &[Algorithm {
+            name: "SHA-256",
+            hash: Some(|word| Sha256::digest(word).to_vec()),
+        }],
+    ),
+    (
+        96,
+        &[Algorithm {
+            name: "SHA-384",
+            hash: Some(|word| Sha384::digest(word).to_vec()),
+        }],
+    ),
+    (
+        128,
+        &[Algorithm {
+            name: "SHA-512",
+            hash: Some(|word| Sha512::digest(word).to_vec()),
+        }],
+    ),
+];
+
+/// Unix crypt formats, recognised by their prefix and the length of what follows it
+/// These are salted and deliberately slow, so they are only identified.
+static CRYPT_FORMATS: [(&str, Algorithm); 6] = [
+    ("$2a$", CRYPT_BCRYPT),
+    ("$2b$", CRYPT_BCRYPT),
+    ("$2y$", CRYPT_BCRYPT),
+    (
+        "$1$",
+        Algorithm {
+            name: "MD5-crypt",
+            hash: None,
+        },
+    ),
+    (
+        "$5$",
+        Algorithm {
+            name: "SHA-256-crypt",
+            hash: None,
+        },
+    ),
+    (
+        "$6$",
+        Algorithm {
+            name: "SHA-512-crypt",
+            hash: None,
+        },
+    ),
+];
+
+/// bcrypt, which has three prefixes
+const CRYPT_BCRYPT: Algorithm = Algorithm {
+    name: "bcrypt",
+    hash: None,
+};
+
+/// The characters crypt formats write their salts and hashes with
+const CRYPT_ALPHABET: &str = "./ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+
+/// The algorithms the text could be a hash from, most likely first, empty if it isn't one
+pub fn identify(text: &str) -> Vec<&'static Algorithm> {
+    let text = text.trim();
+    if text.chars().all(|c| c.is_ascii_hexdigit()) {
+        return HEX_ALGORITHMS
+            .iter()
+            .find(|(length, _)| *length == text.len())
+            .map(|(_, algorithms)| algorithms.iter().collect())
+            .unwrap_or_default();
+    }
+    CRYPT_FORMATS
+        .iter()
+        .filter(|(prefix, algorithm)| {
+            text.strip_prefix(prefix)
+                .is_some_and(|rest| is_crypt_hash(rest, algorithm.name))
+        })
+        .map(|(_, algorithm)| algorithm)
+        .collect()
+}
+
+/// Whether what follows a crypt prefix is laid out the way the algorithm writes it
+fn is_crypt_hash(rest: &str, name: &str) -> bool {
+    let in_alphabet = |text: &str| text.chars().all(|c| CRYPT_ALPHABET.contains(c));
+    if name == "bcrypt" {
+        // A two digit cost, then 22 characters of salt and 31 of hash
+        return rest.split_once('$').is_some_and(|(cost, hash)| {
+            cost.len() == 2
+                && cost.chars().all(|c| c.is_ascii_digit())
+                && hash.len() == 53
+                && in_alphabet(hash)
+        });
+    }
+    // A salt of up to 16 characters, then the hash
+    let hash_length = match name {
+        "MD5-crypt" => 22,
+        "SHA-256-crypt" => 43,
+        _ => 86,
+    };
+    rest.split_once('$').is_some_and(|(salt, hash)| {
+        !salt.is_empty() && salt.len() <= 16 && hash.len() == hash_length && in_alphabet(hash)
+    })
+}
+
+/// The NTLM hash of a password, MD4 of its UTF-16LE encoding
+fn ntlm(word: &[u8]) -> Vec<u8> {
+    let utf16: Vec<u8> = String::from_utf8_lossy(word)
+        .encode_utf16()
+        .flat_map(u16::to_le_bytes)
+        .collect();
+    md4::md4(&utf16)
+}
+
+/// Pads a message the way MD4, MD5 and SHA-1 do: a one bit, zeros, and the length in bits
+/// as eight bytes, little-endian for MD4 and MD5 and big-endian for SHA-1
+fn pad(message: &[u8], big_endian: bool) -> Vec<u8> {
+    let bits = (message.len() as u64).wrapping_mul(8);
+    let mut padded = message.to_vec();
+    padded.push(0x80);
+    while padded.len() % 64 != 56 {
+        padded.push(0);
+    }
+    if big_endian {
+        padded.extend(bits.to_be_bytes());
+    } else {
+        padded.extend(bits.to_le_bytes());
+    }
+    padded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{identify, ntlm};
+
+    /// The names of the algorithms the text could be from
+    fn names(text: &str) -> Vec<&str> {
+        identify(text)
+            .iter()
+            .map(|algorithm| algorithm.name)
+            .collect()
+    }
+
+    #[test]
+    fn hex_hashes_are_identified_by_length() {
+        assert_eq!(names("5f4dcc3b5aa765d61d8327deb882cf99"), ["MD5", "NTLM"]);
+        assert_eq!(names("e5e9fa1ba31ecd1ae84f75caaa474f3a663f05f4"), ["SHA-1"]);
+        assert_eq!(
+            names("1c8bfe8f801d79745c4631d09fff36c82aa37fc4cce4fc946683d7b336b63032"),
+            ["SHA-256"]
+        );
+        assert!(names("5f4dcc3b5aa765d61d8327deb882cf").is_empty());
+    }
+
+    #[test]
+    fn crypt_formats_are_identified_by_prefix() {
+        assert_eq!(
+            names("$2b$12$GhvMmNVjRW29ulnudl.LbuAnUtN/LRfe1JsBm1Xu6LE3059z5Tr8m"),
+            ["bcrypt"]
+        );
+        assert_eq!(names("$1$saltsalt$qjXMvbEw8oaL.CzflDugX/"), ["MD5-crypt"]);
+        assert!(names("$2b$12$tooshort").is_empty());
+    }
+
+    #[test]
+    fn ntlm_hashes_utf16() {
+        let hex: String = ntlm(b"password")
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect();
+        assert_eq!(hex, "8846f7eaee8fb117ad06bdd830b7586c");
+    }
+
+    #[test]
+    fn text_is_not_a_hash() {
+        assert!(names("hello world").is_empty());
+        assert!(names("").is_empty());
+    }
+}
@@ -15,8 +15,6 @@ pub struct HexadecimalDecoder;
 enum Error {
     /// Error when the input is not divisible by 2
     InvalidLength,
-    /// Error if the result isn't UTF-8
-    InvalidUtf8,
 }
 
 impl Crack for Decoder<HexadecimalDecoder> {
@@ -36,17 +34,27 @@ impl Crack for Decoder<HexadecimalDecoder> {
     /// Else the Option returns nothing and the error is logged in Trace
     fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
         trace!("Trying hexadecimal with text {:?}", text);
-        let decoded_text: Result<String, Error> = hexadecimal_to_string(text);
+        let decoded: Result<Vec<u8>, Error> = hexadecimal_to_bytes(text);
         let mut results = CrackResult::new(self, text.to_string());
 
-        if decoded_text.is_err() {
-            debug!("Failed to decode hexadecimal: {:?}", decoded_text);
+        if decoded.is_err() {
+            debug!("Failed to decode hexadecimal: {:?}", decoded);
             return results;
         }
 
-        trace!("Decoded text for hexadecimal: {:?}", decoded_text);
+        trace!("Decoded bytes for hexadecimal: {:?}", decoded);
 
-        let decoded_text = decoded_text.unwrap();
+        let decoded_text = match String::from_utf8(decoded.unwrap()) {
+            Ok(decoded_text) => decoded_text,
+            Err(e) => {
+                // Bytes which aren't text are passed on as they are, for decoders such as gzip
+                let bytes = e.into_bytes();
+                let checker_result = checker.check_bytes(&bytes);
+                results.unencrypted_bytes = Some(vec![bytes]);
+                results.update_checker(&checker_result);
+                return results;
+            }
+        };
 
         if !check_string_success(&decoded_text, text) {
             info!(
@@ -79,10 +87,29 @@ impl Crack for Decoder<HexadecimalDecoder> {
     fn get_link(&self) -> &str {
         self.link
     }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some((
+            "537068696e78206f6620626c61636b2071756172747a2c206a75646765206d7920766f772e",
+            "Sphinx of black quartz, judge my vow.",
+        ))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "`0x` prefixes and non-hexadecimal characters are stripped",
+            "Must leave an even number of hexadecimal digits",
+            "Decoded bytes which aren't valid UTF-8 are passed on as bytes, for decoders such as gzip",
+        ]
+    }
 }
 
-/// Decodes hexadecimal to string
-fn hexadecimal_to_string(hex: &str) -> Result<String, Error> {
+/// Decodes hexadecimal to bytes
+fn hexadecimal_to_bytes(hex: &str) -> Result<Vec<u8>, Error> {
     // Remove "0x" delimiters
     let hex = hex.replace("0x", "");
     // Remove all non-hexadecimal characters from the string
@@ -96,15 +123,11 @@ fn hexadecimal_to_string(hex: &str) -> Result<String, Error> {
         return Err(Error::InvalidLength);
     }
 
-    // Iterate over the vector of bytes in pairs
-    let mut result = String::new();
-    for pair in bytes.chunks(2) {
-        // Parse the pair of bytes as a hexadecimal number and push the corresponding
-        // ASCII character to the result string
-        result.push(u8::from_str_radix(std::str::from_utf8(pair).unwrap(), 16).unwrap() as char);
-    }
-
-    String::from_utf8(result.into()).map_err(|_| Error::InvalidUtf8)
+    // Parse each pair of hexadecimal digits as one byte
+    Ok(bytes
+        .chunks(2)
+        .map(|pair| u8::from_str_radix(std::str::from_utf8(pair).unwrap(), 16).unwrap())
+        .collect())
 }
 
 #[cfg(test)]
@@ -210,16 +233,19 @@ mod tests {
     #[test]
     fn hexadecimal_handles_panics() {
         // This tests if Hexadecimal can handle panics
-        // It should return Some
+        // It should return Some bytes
         // This is because Hexadecimal can technically decode it, but it will be gibberish
+        // which isn't UTF-8, so it's passed on as bytes rather than text
         let hexadecimal_decoder = Decoder::<HexadecimalDecoder>::new();
-        let result = hexadecimal_decoder
-            .crack(
-                "hello my name is panicky mc panic face!",
-                &get_athena_checker(),
-            )
-            .unencrypted_text;
-        assert!(result.is_some());
+        let result = hexadecimal_decoder.crack(
+            "hello my name is panicky mc panic face!",
+            &get_athena_checker(),
+        );
+        assert!(result.unencrypted_text.is_none());
+        assert_eq!(
+            result.unencrypted_bytes,
+            Some(vec![vec![0xEA, 0xEA, 0xCC, 0xAC, 0xFA, 0xCE]])
+        );
     }
 
     #[test]
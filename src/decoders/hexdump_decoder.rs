@@ -0,0 +1,325 @@
+//! Decodes the output of `xxd` and `hexdump -C` back into the bytes that were dumped
+//! Both print an offset, the bytes in hex, and the printable ones again in a gutter:
+//! `00000000: 6865 6c6c 6f0a  hello.` for xxd and
+//! `00000000  68 65 6c 6c 6f 0a  |hello.|` for hexdump, which also ends with the length
+//! and writes `*` for lines repeating the one before. The gutter is dropped, and every
+//! offset has to match the bytes read so far, so other text with hex in it isn't read.
+//! Bytes which aren't UTF-8 are passed on as bytes.
+//! Call hexdump_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::check_string_success;
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::Decoder;
+use super::radix::bytes_to_text;
+
+use log::{debug, info, trace};
+
+/// The most bytes a dump is rebuilt to, so a `*` before a huge offset can't use up the memory
+const MAX_LENGTH: usize = 16 * 1024 * 1024;
+
+/// The Hexdump decoder, call:
+/// `let hexdump_decoder = Decoder::<HexdumpDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = hexdump_decoder.crack(input)` to decode a hexdump
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::hexdump_decoder::{HexdumpDecoder};
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_hexdump = Decoder::<HexdumpDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_hexdump.crack("00000000: 6865 6c6c 6f20 776f 726c 64    hello world", &checker);
+/// assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+/// ```
+pub struct HexdumpDecoder;
+
+impl Crack for Decoder<HexdumpDecoder> {
+    fn new() -> Decoder<HexdumpDecoder> {
+        Decoder {
+            name: "Hexdump",
+            description: "Hexdumps from tools like xxd and hexdump -C show data as lines of an offset, the bytes in hex and the printable ones as text. They are how binary data gets pasted into chats and bug reports.",
+            link: "https://en.wikipedia.org/wiki/Hex_dump",
+            tags: vec!["hexdump", "decoder", "hex"],
+            popularity: 0.4,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying hexdump with text {:?}", text);
+        let mut results = CrackResult::new(self, text.to_string());
+
+        let Some(bytes) = read_hexdump(text) else {
+            debug!(
+                "Failed to decode hexdump because the lines aren't a dump with matching offsets"
+            );
+            return results;
+        };
+        let decoded_text = match String::from_utf8(bytes) {
+            Ok(decoded_text) => decoded_text,
+            Err(e) => {
+                // Bytes which aren't text are passed on as they are, for decoders such as gzip
+                let bytes = e.into_bytes();
+                let checker_result = checker.check_bytes(&bytes);
+                results.unencrypted_bytes = Some(vec![bytes]);
+                results.update_checker(&checker_result);
+                return results;
+            }
+        };
+        let Some(decoded_text) = bytes_to_text(decoded_text.into_bytes()) else {
+            debug!("Failed to decode hexdump because the bytes hold control characters");
+            return results;
+        };
+        if !check_string_success(&decoded_text, text) {
+            info!(
+                "Failed to decode hexdump because check_string_success returned false on string {}",
+                decoded_text
+            );
+            return results;
+        }
+
+        let checker_result = checker.check(&decoded_text);
+        results.unencrypted_text = Some(vec![decoded_text]);
+
+        results.update_checker(&checker_result);
+
+        results
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some((
+            "00000000: 6865 6c6c 6f20 776f 726c 64    hello world",
+            "hello world",
+        ))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Every line must start with a hex offset, followed by `:` for xxd or whitespace for hexdump -C",
+            "Each offset must equal the number of bytes on the lines before, `*` lines standing for repeats",
+            "Hex must come in whole bytes, with the gutter after two spaces for xxd or between `|`s for hexdump -C",
+            "Decoded bytes which aren't valid UTF-8 are passed on as bytes, for decoders such as gzip",
+        ]
+    }
+}
+
+/// The bytes written in hex on one line of a dump, the offset and gutter left out.
+/// hexdump -C puts its gutter between `|`s, while xxd puts two spaces before it.
+fn line_bytes(columns: &str, xxd: bool) -> Option<Vec<u8>> {
+    let hex = if xxd {
+        columns.trim_start().split("  ").next()?
+    } else {
+        columns.split('|').next()?
+    };
+    let mut bytes = Vec::new();
+    for group in hex.split_whitespace() {
+        if !group.len().is_multiple_of(2) || !group.bytes().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        for i in (0..group.len()).step_by(2) {
+            bytes.push(u8::from_str_radix(&group[i..i + 2], 16).ok()?);
+        }
+    }
+    Some(bytes)
+}
+
+/// Rebuilds the dumped bytes, None if the text isn't a hexdump or an offset is wrong
+fn read_hexdump(text: &str) -> Option<Vec<u8>> {
+    let mut bytes: Vec<u8> = Vec::new();
+    let mut previous: Vec<u8> = Vec::new();
+    let mut repeating = false;
+    let mut xxd = None;
+    for line in text.lines().map(str::trim_end) {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if line.trim() == "*" {
+            repeating = true;
+            continue;
+        }
+        let digits = line.bytes().take_while(u8::is_ascii_hexdigit).count();
+        if digits < 4 {
+            return None;
+        }
+        let offset = usize::from_str_radix(&line[..digits], 16).ok()?;
+        let rest = &line[digits..];
+        let this_xxd = rest.starts_with(':');
+        if *xxd.get_or_insert(this_xxd) != this_xxd {
+            return None;
+        }
+        // A * stands for as many copies of the line before as fit up to this offset
+        if repeating {
+            if previous.is_empty() || offset < bytes.len() || offset > MAX_LENGTH {
+                return None;
+            }
+            while bytes.len() < offset {
+                let missing = offset - bytes.len();
+                bytes.extend(previous.iter().take(missing));
+            }
+            repeating = false;
+        }
+        if offset != bytes.len() {
+            return None;
+        }
+        let columns = if this_xxd { &rest[1..] } else { rest };
+        if !this_xxd && !columns.is_empty() && !columns.starts_with(char::is_whitespace) {
+            return None;
+        }
+        if columns.trim().is_empty() {
+            // hexdump ends with a line of just the length
+            continue;
+        }
+        let line = line_bytes(columns, this_xxd)?;
+        if line.is_empty() {
+            return None;
+        }
+        bytes.extend(&line);
+        previous = line;
+    }
+    if bytes.is_empty() || repeating {
+        return None;
+    }
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_hexdump, HexdumpDecoder};
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn successful_decoding() {
+        let hexdump_decoder = Decoder::<HexdumpDecoder>::new();
+        let result = hexdump_decoder.crack(
+            "00000000: 6865 6c6c 6f20 776f 726c 64    hello world",
+            &get_athena_checker(),
+        );
+        assert!(result.success);
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+    }
+
+    #[test]
+    fn xxd_lines_are_joined() {
+        let dump = "00000000: 5468 6520 7175 6963 6b20 6272 6f77 6e20  The quick brown \n00000010: 666f 7820 6a75 6d70 730a                 fox jumps.\n";
+        assert_eq!(
+            read_hexdump(dump).unwrap(),
+            b"The quick brown fox jumps\n".to_vec()
+        );
+    }
+
+    #[test]
+    fn hexdump_canonical_output_is_decoded() {
+        let dump = "00000000  54 68 65 20 71 75 69 63  6b 20 62 72 6f 77 6e 20  |The quick brown |\n00000010  66 6f 78 0a                                       |fox.|\n00000014\n";
+        assert_eq!(
+            read_hexdump(dump).unwrap(),
+            b"The quick brown fox\n".to_vec()
+        );
+    }
+
+    #[test]
+    fn hex_in_the_gutter_is_ignored() {
+        let dump = "00000000: 6361 6665 2062 6565 66                cafe beef";
+        assert_eq!(read_hexdump(dump).unwrap(), b"cafe beef".to_vec());
+    }
+
+    #[test]
+    fn repeated_lines_are_filled_in() {
+        let dump = "00000000  61 61 61 61 61 61 61 61  61 61 61 61 61 61 61 61  |aaaaaaaaaaaaaaaa|\n*\n00000030  62 0a                                             |b.|\n00000032\n";
+        let mut expected = vec![b'a'; 48];
+        expected.extend(b"b\n");
+        assert_eq!(read_hexdump(dump).unwrap(), expected);
+    }
+
+    #[test]
+    fn huge_repeats_are_rejected() {
+        let dump = "00000000  61 61  |aa|\n*\nffffffffff\n";
+        assert!(read_hexdump(dump).is_none());
+    }
+
+    #[test]
+    fn wrong_offsets_are_rejected() {
+        let dump = "00000000: 6865 6c6c  hell\n00000010: 6f0a  o.";
+        assert!(read_hexdump(dump).is_none());
+    }
+
+    #[test]
+    fn binary_dumps_are_passed_on_as_bytes() {
+        let hexdump_decoder = Decoder::<HexdumpDecoder>::new();
+        let result = hexdump_decoder.crack(
+            "00000000: 1f8b 0800 0000 0000 0003                ..........",
+            &get_athena_checker(),
+        );
+        assert!(result.unencrypted_text.is_none());
+        assert_eq!(
+            result.unencrypted_bytes.unwrap()[0],
+            vec![0x1f, 0x8b, 0x08, 0, 0, 0, 0, 0, 0, 0x03]
+        );
+    }
+
+    #[test]
+    fn ordinary_text_is_not_decoded() {
+        assert!(read_hexdump("deadbeef is a word").is_none());
+        assert!(read_hexdump("2024 was a year").is_none());
+    }
+
+    #[test]
+    fn hexdump_handle_panic_if_empty_string() {
+        let hexdump_decoder = Decoder::<HexdumpDecoder>::new();
+        let result = hexdump_decoder
+            .crack("", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn hexdump_handle_panic_if_emoji() {
+        let hexdump_decoder = Decoder::<HexdumpDecoder>::new();
+        let result = hexdump_decoder
+            .crack("😂", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+}
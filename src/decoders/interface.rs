@@ -1,4 +1,5 @@
 use crate::checkers::CheckerTypes;
+use std::time::Duration;
 
 use super::crack_results::CrackResult;
 
@@ -76,6 +77,12 @@ pub trait Crack {
         Self: Sized;
     /// Crack is the function that actually does the decoding
     fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult;
+    /// Decodes a value which isn't text, such as compressed data from the decoder before
+    /// Returns None for decoders which only read text, which is the default.
+    /// Decoders which read binary data override this so the search can run them on it.
+    fn crack_bytes(&self, _bytes: &[u8], _checker: &CheckerTypes) -> Option<CrackResult> {
+        None
+    }
     /// Get all tags for the current decoder
     fn get_tags(&self) -> &Vec<&str>;
     /// Get the name of the current decoder
@@ -92,6 +99,22 @@ pub trait Crack {
         // would override this to return its actual popularity value.
         0.5
     }
+    /// Gets an example input for the decoder along with the text it decodes to
+    /// This is used to generate the decoder documentation
+    fn get_example(&self) -> Option<(&str, &str)> {
+        None
+    }
+    /// Gets the conditions an input has to meet before the decoder will decode it
+    /// This is used to generate the decoder documentation
+    fn get_guards(&self) -> &[&str] {
+        &[]
+    }
+    /// How long is left before the search times out, None if no search is running
+    /// Expensive decoders can use this to budget their work and return their best
+    /// candidates so far, rather than being cut off with nothing when time runs out.
+    fn remaining_time(&self) -> Option<Duration> {
+        crate::timer::remaining_time()
+    }
 }
 
 /// Returns a boolean of True if the string is successfully changed
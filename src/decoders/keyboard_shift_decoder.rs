@@ -0,0 +1,276 @@
+//! Decode text typed with the hands one key off
+//! Shifting every key one place left, right, up or down on the keyboard is a common lazy
+//! obfuscation, and what happens by accident when a hand starts on the wrong keys, so
+//! `hello` comes out as `jr;;p`. Every direction is tried on each layout named in the
+//! `keyboard_layouts` setting (QWERTY unless set, with AZERTY and Dvorak known too).
+//! Keys at the edge of the keyboard and characters which aren't on it are left alone.
+//! Call keyboard_shift_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
+use crate::checkers::CheckerTypes;
+use crate::config::get_config;
+use crate::decoders::interface::check_string_success;
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::Decoder;
+
+use log::{debug, info, trace};
+
+/// A keyboard layout, as each row of keys unshifted and then with shift held, after the
+/// column its first key sits in, so keys line up with the ones above and below them
+type Layout = [(usize, &'static str, &'static str); 4];
+
+/// The US QWERTY layout
+const QWERTY: Layout = [
+    (0, "`1234567890-=", "~!@#$%^&*()_+"),
+    (1, "qwertyuiop[]\\", "QWERTYUIOP{}|"),
+    (1, "asdfghjkl;'", "ASDFGHJKL:\""),
+    (1, "zxcvbnm,./", "ZXCVBNM<>?"),
+];
+
+/// The French AZERTY layout
+const AZERTY: Layout = [
+    (0, "²&é\"'(-è_çà)=", "~1234567890°+"),
+    (1, "azertyuiop^$", "AZERTYUIOP¨£"),
+    (1, "qsdfghjklmù*", "QSDFGHJKLM%µ"),
+    (0, "<wxcvbn,;:!", ">WXCVBN?./§"),
+];
+
+/// The US Dvorak layout
+const DVORAK: Layout = [
+    (0, "`1234567890[]", "~!@#$%^&*(){}"),
+    (1, "',.pyfgcrl/=\\", "\"<>PYFGCRL?+|"),
+    (1, "aoeuidhtns-", "AOEUIDHTNS_"),
+    (1, ";qjkxbmwvz", ":QJKXBMWVZ"),
+];
+
+/// The layouts which can be named in `keyboard_layouts`
+const LAYOUTS: [(&str, Layout); 3] = [("qwerty", QWERTY), ("azerty", AZERTY), ("dvorak", DVORAK)];
+
+/// The ways a key can be moved, as the name and the change in row and column
+const DIRECTIONS: [(&str, isize, isize); 4] = [
+    ("left", 0, -1),
+    ("right", 0, 1),
+    ("up", -1, 0),
+    ("down", 1, 0),
+];
+
+/// The Keyboard shift decoder, call:
+/// `let keyboard_shift_decoder = Decoder::<KeyboardShiftDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = keyboard_shift_decoder.crack(input)` to decode keyboard shifted text
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::keyboard_shift_decoder::{KeyboardShiftDecoder};
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_keyboard_shift = Decoder::<KeyboardShiftDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_keyboard_shift.crack("jr;;p ept;f", &checker);
+/// // Every layout and direction is returned, with the one the checkers matched first
+/// assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+/// assert_eq!(result.key.unwrap(), "qwerty, one key left");
+/// ```
+pub struct KeyboardShiftDecoder;
+
+impl Crack for Decoder<KeyboardShiftDecoder> {
+    fn new() -> Decoder<KeyboardShiftDecoder> {
+        Decoder {
+            name: "Keyboard shift",
+            description: "A keyboard shift replaces every key with its neighbour one place left, right, up or down on the keyboard, as happens when typing with the hands on the wrong keys. It is a quick way of scrambling text without any key to remember.",
+            link: "https://www.dcode.fr/keyboard-shift-cipher",
+            tags: vec!["keyboard", "decoder", "substitution"],
+            popularity: 0.4,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying keyboard shift with text {:?}", text);
+        let mut results = CrackResult::new(self, text.to_string());
+
+        if !text.chars().any(|c| c.is_alphabetic()) {
+            debug!("Failed to decode keyboard shift because the text has no letters");
+            return results;
+        }
+
+        let mut candidates = Vec::new();
+        let mut keys = Vec::new();
+        for name in &get_config().keyboard_layouts {
+            let Some((name, layout)) = LAYOUTS
+                .iter()
+                .find(|(known, _)| known.eq_ignore_ascii_case(name))
+            else {
+                debug!("Skipping keyboard layout {} as it isn't one we know", name);
+                continue;
+            };
+            for (direction, rows, columns) in DIRECTIONS {
+                let decoded_text = shift(text, layout, rows, columns);
+                if !check_string_success(&decoded_text, text) {
+                    info!(
+                        "Skipping keyboard shift {} on {} as it changed nothing",
+                        direction, name
+                    );
+                    continue;
+                }
+                let key = format!("{name}, one key {direction}");
+                let checker_result = checker.check(&decoded_text);
+                if checker_result.is_identified && !results.success {
+                    // The match goes first, and every other candidate is still returned
+                    candidates.insert(0, decoded_text);
+                    keys.insert(0, key);
+                    results.update_checker(&checker_result);
+                } else {
+                    candidates.push(decoded_text);
+                    keys.push(key);
+                }
+            }
+        }
+        if candidates.is_empty() {
+            return results;
+        }
+        if results.success {
+            results.key = keys.into_iter().next();
+        }
+        results.unencrypted_text = Some(candidates);
+
+        results
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some(("jr;;p ept;f", "hello world"))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input must contain at least one letter",
+            "Tries moving every key left, right, up and down on each layout in `keyboard_layouts`",
+            "Decoded text must differ from the input",
+        ]
+    }
+}
+
+/// Moves every key of the text by the given rows and columns on the layout.
+/// Keys which would fall off the keyboard, and characters not on it, stay as they are.
+fn shift(text: &str, layout: &Layout, rows: isize, columns: isize) -> String {
+    text.chars()
+        .map(|c| {
+            for (row, (offset, lower, upper)) in layout.iter().enumerate() {
+                for (shifted, keys) in [lower, upper].into_iter().enumerate() {
+                    let Some(column) = keys.chars().position(|key| key == c) else {
+                        continue;
+                    };
+                    let target = row
+                        .checked_add_signed(rows)
+                        .zip((column + offset).checked_add_signed(columns))
+                        .and_then(|(row, column)| {
+                            let (offset, lower, upper) = layout.get(row)?;
+                            let keys = if shifted == 0 { lower } else { upper };
+                            keys.chars().nth(column.checked_sub(*offset)?)
+                        });
+                    return target.unwrap_or(c);
+                }
+            }
+            c
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{shift, KeyboardShiftDecoder, AZERTY, DVORAK, QWERTY};
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn successful_decoding() {
+        let keyboard_shift_decoder = Decoder::<KeyboardShiftDecoder>::new();
+        let result = keyboard_shift_decoder.crack("jr;;p ept;f", &get_athena_checker());
+        assert!(result.success);
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+        assert_eq!(result.key.unwrap(), "qwerty, one key left");
+    }
+
+    #[test]
+    fn every_direction_is_returned() {
+        let keyboard_shift_decoder = Decoder::<KeyboardShiftDecoder>::new();
+        let result = keyboard_shift_decoder.crack("gwkki qieks", &get_athena_checker());
+        let candidates = result.unencrypted_text.unwrap();
+        assert_eq!(candidates.len(), 4);
+        assert!(candidates.contains(&"hello world".to_string()));
+    }
+
+    #[test]
+    fn rows_move_up_and_down() {
+        assert_eq!(shift("qaz", &QWERTY, -1, 0), "1qa");
+        assert_eq!(shift("qaz", &QWERTY, 1, 0), "azz");
+    }
+
+    #[test]
+    fn capitals_stay_capitals() {
+        assert_eq!(shift("Jr;;p", &QWERTY, 0, -1), "Hello");
+    }
+
+    #[test]
+    fn other_layouts_are_known() {
+        assert_eq!(shift("jrmmp", &AZERTY, 0, -1), "hello");
+        assert_eq!(shift("tu//e", &DVORAK, 0, -1), "hello");
+    }
+
+    #[test]
+    fn keyboard_shift_handle_panic_if_empty_string() {
+        let keyboard_shift_decoder = Decoder::<KeyboardShiftDecoder>::new();
+        let result = keyboard_shift_decoder
+            .crack("", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn keyboard_shift_handle_panic_if_emoji() {
+        let keyboard_shift_decoder = Decoder::<KeyboardShiftDecoder>::new();
+        let result = keyboard_shift_decoder
+            .crack("😂", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+}
@@ -0,0 +1,151 @@
+//! The AES block cipher, with the CBC and GCM modes
+//! AES encrypts 16 byte blocks with a 16, 24 or 32 byte key. CBC chains the blocks together
+//! with an IV. GCM runs AES as a counter mode stream cipher and authenticates the ciphertext
+//! with a tag, so a wrong key or nonce is always noticed. The cipher and both modes come
+//! from the RustCrypto crates, this module only picks the key size.
+
+use ::aes::cipher::block_padding::NoPadding;
+use ::aes::cipher::{BlockCipherDecrypt, BlockCipherEncrypt, BlockModeDecrypt, InnerIvInit};
+use ::aes::cipher::{BlockSizeUser, KeyInit};
+use aes_gcm::aead::consts::{U12, U16};
+use aes_gcm::aead::AeadInOut;
+use aes_gcm::AesGcm;
+
+/// The size of an AES block in bytes
+pub const BLOCK_SIZE: usize = 16;
+
+/// The size of a GCM authentication tag in bytes
+pub const TAG_SIZE: usize = 16;
+
+/// The size of the nonce GCM is used with almost everywhere, in bytes
+pub const GCM_NONCE_SIZE: usize = 12;
+
+/// An AES key expanded into its round keys, for whichever of the three key sizes it is
+#[derive(Clone)]
+pub enum Aes {
+    /// AES-128, with a 16 byte key
+    Key128(::aes::Aes128),
+    /// AES-192, with a 24 byte key
+    Key192(::aes::Aes192),
+    /// AES-256, with a 32 byte key
+    Key256(::aes::Aes256),
+}
+
+impl Aes {
+    /// Expands the key, None unless it is 16, 24 or 32 bytes long
+    pub fn new(key: &[u8]) -> Option<Aes> {
+        match key.len() {
+            16 => ::aes::Aes128::new_from_slice(key).ok().map(Aes::Key128),
+            24 => ::aes::Aes192::new_from_slice(key).ok().map(Aes::Key192),
+            32 => ::aes::Aes256::new_from_slice(key).ok().map(Aes::Key256),
+            _ => None,
+        }
+    }
+
+    /// Decrypts CBC mode ciphertext with the IV, None unless it is a whole number of blocks
+    /// Padding is left on the plaintext.
+    pub fn decrypt_cbc(&self, ciphertext: &[u8], iv: &[u8; BLOCK_SIZE]) -> Option<Vec<u8>> {
+        if ciphertext.is_empty() || !ciphertext.len().is_multiple_of(BLOCK_SIZE) {
+            return None;
+        }
+        match self {
+            Aes::Key128(cipher) => decrypt_cbc(cipher, ciphertext, iv),
+            Aes::Key192(cipher) => decrypt_cbc(cipher, ciphertext, iv),
+            Aes::Key256(cipher) => decrypt_cbc(cipher, ciphertext, iv),
+        }
+    }
+
+    /// Decrypts GCM mode ciphertext with a 12 byte nonce and no associated data, None if
+    /// the tag doesn't match
+    pub fn decrypt_gcm(
+        &self,
+        nonce: &[u8; GCM_NONCE_SIZE],
+        ciphertext: &[u8],
+        tag: &[u8; TAG_SIZE],
+    ) -> Option<Vec<u8>> {
+        match self {
+            Aes::Key128(cipher) => decrypt_gcm(cipher, nonce, ciphertext, tag),
+            Aes::Key192(cipher) => decrypt_gcm(cipher, nonce, ciphertext, tag),
+            Aes::Key256(cipher) => decrypt_gcm(cipher, nonce, ciphertext, tag),
+        }
+    }
+}
+
+/// Decrypts whole blocks of CBC mode ciphertext with one of the AES ciphers
+fn decrypt_cbc<C: BlockCipherDecrypt + Clone>(
+    cipher: &C,
+    ciphertext: &[u8],
+    iv: &[u8; BLOCK_SIZE],
+) -> Option<Vec<u8>> {
+    let mut plaintext = ciphertext.to_vec();
+    cbc::Decryptor::inner_iv_init(cipher.clone(), iv.as_slice().try_into().ok()?)
+        .decrypt_padded::<NoPadding>(&mut plaintext)
+        .ok()?;
+    Some(plaintext)
+}
+
+/// Decrypts GCM mode ciphertext with one of the AES ciphers, None if the tag doesn't match
+fn decrypt_gcm<C: BlockSizeUser<BlockSize = U16> + BlockCipherEncrypt + Clone>(
+    cipher: &C,
+    nonce: &[u8; GCM_NONCE_SIZE],
+    ciphertext: &[u8],
+    tag: &[u8; TAG_SIZE],
+) -> Option<Vec<u8>> {
+    let gcm = AesGcm::<C, U12>::from(cipher.clone());
+    let mut plaintext = ciphertext.to_vec();
+    gcm.decrypt_inout_detached(
+        &(*nonce).into(),
+        &[],
+        plaintext.as_mut_slice().into(),
+        &(*tag).into(),
+    )
+    .ok()?;
+    Some(plaintext)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Aes;
+
+    /// Decodes hex which is known to be good
+    fn hex(text: &str) -> Vec<u8> {
+        (0..text.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&text[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn bad_key_lengths_are_rejected() {
+        assert!(Aes::new(&[0; 15]).is_none());
+        assert!(Aes::new(&[0; 33]).is_none());
+    }
+
+    #[test]
+    fn cbc_decrypts() {
+        let key: Vec<u8> = (0..16).collect();
+        let iv: [u8; 16] = std::array::from_fn(|i| i as u8 + 16);
+        let ciphertext = hex("9febb9354d4b1b8da53a788f2d6ca8d263ccc764a363ed0b5eaaf772074979e4");
+        let plaintext = Aes::new(&key)
+            .unwrap()
+            .decrypt_cbc(&ciphertext, &iv)
+            .unwrap();
+        assert_eq!(plaintext, b"attack at dawn, bring snacks\x04\x04\x04\x04");
+    }
+
+    #[test]
+    fn gcm_decrypts_and_checks_the_tag() {
+        let key: Vec<u8> = (0..32).collect();
+        let nonce: [u8; 12] = std::array::from_fn(|i| i as u8);
+        let sealed = hex("2a67b36fe588a73bec35b7ffd98c5802efb2a75b91107f084a028039c497cecdce2f8275b805b62b235251");
+        let (ciphertext, tag) = sealed.split_at(sealed.len() - 16);
+        let mut tag: [u8; 16] = tag.try_into().unwrap();
+        let aes = Aes::new(&key).unwrap();
+        assert_eq!(
+            aes.decrypt_gcm(&nonce, ciphertext, &tag).unwrap(),
+            b"meet me at the old oak tree"
+        );
+        tag[0] ^= 1;
+        assert!(aes.decrypt_gcm(&nonce, ciphertext, &tag).is_none());
+    }
+}
@@ -0,0 +1,63 @@
+//! The ChaCha20-Poly1305 authenticated cipher from RFC 8439
+//! ChaCha20 is a stream cipher keyed with 32 bytes and a 12 byte nonce, and Poly1305
+//! authenticates the ciphertext with a one-time key taken from the first block of keystream.
+//! Both come from the RustCrypto `chacha20poly1305` crate.
+
+use chacha20poly1305::aead::{AeadInOut, KeyInit};
+use chacha20poly1305::ChaCha20Poly1305;
+
+/// The size of a ChaCha20-Poly1305 key in bytes
+pub const KEY_SIZE: usize = 32;
+
+/// The size of a ChaCha20-Poly1305 nonce in bytes
+pub const NONCE_SIZE: usize = 12;
+
+/// The size of a Poly1305 tag in bytes
+pub const TAG_SIZE: usize = 16;
+
+/// Decrypts ciphertext sealed with no associated data, None if the tag doesn't match
+pub fn decrypt(
+    key: &[u8; KEY_SIZE],
+    nonce: &[u8; NONCE_SIZE],
+    ciphertext: &[u8],
+    tag: &[u8; TAG_SIZE],
+) -> Option<Vec<u8>> {
+    let mut plaintext = ciphertext.to_vec();
+    ChaCha20Poly1305::new(&(*key).into())
+        .decrypt_inout_detached(
+            &(*nonce).into(),
+            &[],
+            plaintext.as_mut_slice().into(),
+            &(*tag).into(),
+        )
+        .ok()?;
+    Some(plaintext)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::decrypt;
+
+    /// Decodes hex which is known to be good
+    fn hex(text: &str) -> Vec<u8> {
+        (0..text.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&text[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn decrypts_and_checks_the_tag() {
+        let key: [u8; 32] = std::array::from_fn(|i| i as u8);
+        let nonce: [u8; 12] = std::array::from_fn(|i| i as u8);
+        let sealed = hex("e49e6d74097ac060d6f71f87f0782e0ca5149288301f8dcd94f24acac43a13bd4a00feb20b65ecfbd62bdd");
+        let (ciphertext, tag) = sealed.split_at(sealed.len() - 16);
+        let mut tag: [u8; 16] = tag.try_into().unwrap();
+        assert_eq!(
+            decrypt(&key, &nonce, ciphertext, &tag).unwrap(),
+            b"meet me at the old oak tree"
+        );
+        tag[15] ^= 1;
+        assert!(decrypt(&key, &nonce, ciphertext, &tag).is_none());
+    }
+}
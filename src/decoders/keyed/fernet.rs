@@ -0,0 +1,114 @@
+//! Fernet tokens, the authenticated encryption format of Python's `cryptography`
+//! A token is a version byte of 0x80, an eight byte timestamp, a 16 byte IV, AES-128-CBC
+//! ciphertext with PKCS#7 padding and an HMAC-SHA256 of everything before it. The 32 byte
+//! key is the HMAC key followed by the AES key, and is usually written in URL-safe Base64.
+
+use sha2::{Digest, Sha256};
+
+use super::aes::{Aes, BLOCK_SIZE};
+
+/// The size of a Fernet key in bytes
+pub const KEY_SIZE: usize = 32;
+
+/// The byte every token starts with
+const VERSION: u8 = 0x80;
+
+/// The version byte, the timestamp and the IV
+const HEADER_SIZE: usize = 1 + 8 + BLOCK_SIZE;
+
+/// The size of the HMAC-SHA256 at the end of a token
+const HMAC_SIZE: usize = 32;
+
+/// How many bytes HMAC-SHA256 hashes its key into
+const HMAC_BLOCK_SIZE: usize = 64;
+
+/// Whether the bytes are laid out like a token, with the version byte and whole blocks
+pub fn is_token(token: &[u8]) -> bool {
+    token.first() == Some(&VERSION)
+        && token.len() >= HEADER_SIZE + BLOCK_SIZE + HMAC_SIZE
+        && (token.len() - HEADER_SIZE - HMAC_SIZE).is_multiple_of(BLOCK_SIZE)
+}
+
+/// Decrypts a token, None if it isn't one, the HMAC doesn't match or the padding is wrong
+pub fn decrypt(key: &[u8; KEY_SIZE], token: &[u8]) -> Option<Vec<u8>> {
+    if !is_token(token) {
+        return None;
+    }
+    let (signing_key, encryption_key) = key.split_at(KEY_SIZE / 2);
+    let (signed, hmac) = token.split_at(token.len() - HMAC_SIZE);
+    if hmac_sha256(signing_key, signed) != hmac {
+        return None;
+    }
+    let iv: [u8; BLOCK_SIZE] = std::array::from_fn(|i| signed[9 + i]);
+    let plaintext = Aes::new(encryption_key)?.decrypt_cbc(&signed[HEADER_SIZE..], &iv)?;
+    unpad(plaintext)
+}
+
+/// The HMAC-SHA256 of the message under the key, from RFC 2104
+fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    let mut block = [0; HMAC_BLOCK_SIZE];
+    if key.len() > HMAC_BLOCK_SIZE {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+    let inner = Sha256::new()
+        .chain_update(block.map(|b| b ^ 0x36))
+        .chain_update(message)
+        .finalize();
+    Sha256::new()
+        .chain_update(block.map(|b| b ^ 0x5c))
+        .chain_update(inner)
+        .finalize()
+        .into()
+}
+
+/// Removes PKCS#7 padding, None if there isn't any
+fn unpad(mut plaintext: Vec<u8>) -> Option<Vec<u8>> {
+    let size = usize::from(*plaintext.last()?);
+    let end = plaintext.len().checked_sub(size)?;
+    if !(1..=BLOCK_SIZE).contains(&size) || plaintext[end..].iter().any(|&b| usize::from(b) != size)
+    {
+        return None;
+    }
+    plaintext.truncate(end);
+    Some(plaintext)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decrypt, hmac_sha256, is_token};
+    use base64::{engine::general_purpose::URL_SAFE, Engine};
+
+    /// A token made with Python's `cryptography` for the key 00 01 02 .. 1f
+    const TOKEN: &str = "gAAAAABlU_EAAAECAwQFBgcICQoLDA0OD2MuBJ5lu29qrcyDTUFKDjsqqfjRiqt4Xt6nN3-k3pnWzwnR79N3JZxTxRrbgW2sRlkOx6yjf0HwZlcStltv6DaYDpqJafUMNVhrNJZIfeow";
+
+    #[test]
+    fn rfc_4231_hmac() {
+        // Test case 2 of RFC 4231
+        let hmac = hmac_sha256(b"Jefe", b"what do ya want for nothing?");
+        let hex: String = hmac.iter().map(|b| format!("{b:02x}")).collect();
+        assert_eq!(
+            hex,
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn token_decrypts() {
+        let key: [u8; 32] = std::array::from_fn(|i| i as u8);
+        let token = URL_SAFE.decode(TOKEN).unwrap();
+        assert!(is_token(&token));
+        assert_eq!(
+            decrypt(&key, &token).unwrap(),
+            b"the treasure is under the bridge"
+        );
+    }
+
+    #[test]
+    fn wrong_key_is_rejected() {
+        let key = [0; 32];
+        let token = URL_SAFE.decode(TOKEN).unwrap();
+        assert!(decrypt(&key, &token).is_none());
+    }
+}
@@ -0,0 +1,391 @@
+//! Decrypts AES-CBC, AES-GCM, ChaCha20-Poly1305 and Fernet with the keys the user passed
+//! Every key is read as text, hex, Base64 and the SHA-256 of its text, and each reading of
+//! the right length is tried with every cipher and layout: the IV or nonce before the
+//! ciphertext, the tag after it or straight after the nonce, a zero IV for CBC, and the
+//! Fernet token structure. The authenticated ciphers only give plaintext for the right key
+//! and layout, so their decryptions are trusted even if the checkers don't accept them.
+//! Call keyed_decryption_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
+use crate::checkers::CheckerTypes;
+use crate::config::get_config;
+use crate::decoders::brute_force::strip_padding;
+use crate::decoders::compression::payloads;
+use crate::decoders::crack_results::CrackResult;
+use crate::decoders::interface::{check_string_success, Crack, Decoder};
+use crate::decoders::radix::bytes_to_text;
+
+use super::aes::{self, Aes};
+use super::{chacha20_poly1305, fernet, key_readings};
+
+use log::{debug, trace};
+
+/// The Keyed decryption decoder, call:
+/// `let keyed_decryption_decoder = Decoder::<KeyedDecryptionDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = keyed_decryption_decoder.crack(input)` to decrypt with the keys from `--key` and `--keyfile`
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::keyed::keyed_decryption_decoder::{KeyedDecryptionDecoder};
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_keyed = Decoder::<KeyedDecryptionDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// // Nothing is decrypted until keys are passed with `--key` or `--keyfile`
+/// let result = decode_keyed.crack("6465666768696a6b6c6d6e6f70717273a8339652cfb56fe0ed57ba77137da20c", &checker);
+/// assert!(result.unencrypted_text.is_none());
+/// ```
+pub struct KeyedDecryptionDecoder;
+
+impl Crack for Decoder<KeyedDecryptionDecoder> {
+    fn new() -> Decoder<KeyedDecryptionDecoder> {
+        Decoder {
+            name: "Keyed decryption",
+            description: "Decrypts AES-CBC, AES-GCM, ChaCha20-Poly1305 and Fernet with the keys passed with --key or --keyfile, finding the IV, nonce and tag in the usual layouts, so the layers around an encrypted core can still be taken off automatically.",
+            link: "https://en.wikipedia.org/wiki/Authenticated_encryption",
+            tags: vec!["keyed", "decryption", "aes", "chacha20", "fernet"],
+            popularity: 0.1,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// This function does the actual decoding
+    /// It reads the ciphertext out of the text as hex, Base64 or raw bytes, and decrypts
+    /// every reading with every key.
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying keyed decryption with text {:?}", text);
+        decrypt_with_keys(
+            self,
+            text,
+            payloads(text),
+            &get_config().decryption_keys,
+            checker,
+        )
+    }
+    /// Decrypts bytes from the decoder before, which couldn't be passed on as text
+    fn crack_bytes(&self, bytes: &[u8], checker: &CheckerTypes) -> Option<CrackResult> {
+        trace!("Trying keyed decryption with {} bytes", bytes.len());
+        let text = String::from_utf8_lossy(bytes);
+        Some(decrypt_with_keys(
+            self,
+            &text,
+            vec![("raw bytes", bytes.to_vec())],
+            &get_config().decryption_keys,
+            checker,
+        ))
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "At least one key must be passed with --key or --keyfile",
+            "Input must be bytes passed on by the decoder before, hex, Base64, or characters below U+0100 including at least one that isn't printable ASCII",
+            "AES keys must read as 16, 24 or 32 bytes, ChaCha20-Poly1305 and Fernet keys as 32",
+            "Decryptions must be valid UTF-8 without control characters",
+        ]
+    }
+}
+
+/// A decrypted candidate, with what it took to get it
+struct Decryption {
+    /// The key as the user passed it
+    key: String,
+    /// How the key was read
+    key_reading: &'static str,
+    /// The cipher and layout which decrypted it
+    cipher: String,
+    /// How the ciphertext was read out of the input
+    reading: &'static str,
+    /// The plaintext
+    plaintext: String,
+}
+
+/// Decrypts the payloads with every key, `text` being what they were read from
+fn decrypt_with_keys(
+    decoder: &Decoder<KeyedDecryptionDecoder>,
+    text: &str,
+    payloads: Vec<(&'static str, Vec<u8>)>,
+    keys: &[String],
+    checker: &CheckerTypes,
+) -> CrackResult {
+    let mut results = CrackResult::new(decoder, text.to_string());
+    if keys.is_empty() {
+        trace!("Keyed decryption skipped as no keys were passed");
+        return results;
+    }
+
+    let mut candidates: Vec<Decryption> = Vec::new();
+    for key in keys {
+        for (key_reading, key_bytes) in key_readings(key) {
+            for (reading, ciphertext) in &payloads {
+                for (cipher, plaintext) in decryptions(&key_bytes, ciphertext) {
+                    let Some(plaintext) = bytes_to_text(plaintext) else {
+                        continue;
+                    };
+                    if !check_string_success(&plaintext, text)
+                        || candidates.iter().any(|seen| seen.plaintext == plaintext)
+                    {
+                        continue;
+                    }
+                    let checker_result = checker.check(&plaintext);
+                    let decryption = Decryption {
+                        key: key.clone(),
+                        key_reading,
+                        cipher,
+                        reading,
+                        plaintext,
+                    };
+                    if checker_result.is_identified {
+                        trace!(
+                            "Keyed decryption identified plaintext with {}",
+                            decryption.cipher
+                        );
+                        describe(&mut results, decoder, &decryption);
+                        results.unencrypted_text = Some(vec![decryption.plaintext]);
+                        results.update_checker(&checker_result);
+                        return results;
+                    }
+                    candidates.push(decryption);
+                }
+            }
+        }
+    }
+
+    let Some(first) = candidates.first() else {
+        debug!("Keyed decryption found no key and layout giving text");
+        return results;
+    };
+    describe(&mut results, decoder, first);
+    results.unencrypted_text = Some(
+        candidates
+            .into_iter()
+            .map(|candidate| candidate.plaintext)
+            .collect(),
+    );
+    results
+}
+
+/// Every decryption of the ciphertext with the key, named by cipher and layout
+/// The authenticated layouts come first, as they can only decrypt with the right key.
+fn decryptions(key: &[u8], ciphertext: &[u8]) -> Vec<(String, Vec<u8>)> {
+    let mut decryptions = Vec::new();
+    if let Ok(fernet_key) = <[u8; fernet::KEY_SIZE]>::try_from(key) {
+        if let Some(plaintext) = fernet::decrypt(&fernet_key, ciphertext) {
+            decryptions.push(("Fernet".to_string(), plaintext));
+        }
+        if let Some(plaintext) = chacha20_poly1305_decrypt(&fernet_key, ciphertext) {
+            decryptions.push((
+                "ChaCha20-Poly1305 with the nonce before the ciphertext and the tag after it"
+                    .to_string(),
+                plaintext,
+            ));
+        }
+    }
+
+    let Some(cipher) = Aes::new(key) else {
+        return decryptions;
+    };
+    let bits = key.len() * 8;
+    let sealed_size = aes::GCM_NONCE_SIZE + aes::TAG_SIZE;
+    if ciphertext.len() > sealed_size {
+        let (nonce, rest) = ciphertext.split_at(aes::GCM_NONCE_SIZE);
+        let nonce = nonce.try_into().expect("the nonce is 12 bytes");
+        let (sealed, tag) = rest.split_at(rest.len() - aes::TAG_SIZE);
+        let tag = tag.try_into().expect("the tag is 16 bytes");
+        if let Some(plaintext) = cipher.decrypt_gcm(nonce, sealed, tag) {
+            decryptions.push((
+                format!("AES-{bits}-GCM with the nonce before the ciphertext and the tag after it"),
+                plaintext,
+            ));
+        }
+        let (tag, sealed) = rest.split_at(aes::TAG_SIZE);
+        let tag = tag.try_into().expect("the tag is 16 bytes");
+        if let Some(plaintext) = cipher.decrypt_gcm(nonce, sealed, tag) {
+            decryptions.push((
+                format!("AES-{bits}-GCM with the nonce and the tag before the ciphertext"),
+                plaintext,
+            ));
+        }
+    }
+    if ciphertext.len() > aes::BLOCK_SIZE {
+        let (iv, rest) = ciphertext.split_at(aes::BLOCK_SIZE);
+        let iv = iv.try_into().expect("the IV is 16 bytes");
+        if let Some(plaintext) = cipher.decrypt_cbc(rest, iv) {
+            decryptions.push((
+                format!("AES-{bits}-CBC with the IV before the ciphertext"),
+                strip_padding(plaintext),
+            ));
+        }
+    }
+    if let Some(plaintext) = cipher.decrypt_cbc(ciphertext, &[0; aes::BLOCK_SIZE]) {
+        decryptions.push((
+            format!("AES-{bits}-CBC with a zero IV"),
+            strip_padding(plaintext),
+        ));
+    }
+    decryptions
+}
+
+/// Decrypts ChaCha20-Poly1305 laid out as the nonce, the ciphertext and then the tag
+fn chacha20_poly1305_decrypt(
+    key: &[u8; chacha20_poly1305::KEY_SIZE],
+    ciphertext: &[u8],
+) -> Option<Vec<u8>> {
+    if ciphertext.len() <= chacha20_poly1305::NONCE_SIZE + chacha20_poly1305::TAG_SIZE {
+        return None;
+    }
+    let (nonce, rest) = ciphertext.split_at(chacha20_poly1305::NONCE_SIZE);
+    let (sealed, tag) = rest.split_at(rest.len() - chacha20_poly1305::TAG_SIZE);
+    chacha20_poly1305::decrypt(key, nonce.try_into().ok()?, sealed, tag.try_into().ok()?)
+}
+
+/// Records the key, cipher and readings of the decryption in the results
+fn describe(
+    results: &mut CrackResult,
+    decoder: &Decoder<KeyedDecryptionDecoder>,
+    decryption: &Decryption,
+) {
+    results.key = Some(decryption.key.clone());
+    results.description = format!(
+        "{} It was decrypted with {}, reading the key as {} and the ciphertext as {}.",
+        decoder.description, decryption.cipher, decryption.key_reading, decryption.reading
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decrypt_with_keys, KeyedDecryptionDecoder};
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::{
+            compression::payloads,
+            interface::{Crack, Decoder},
+        },
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    /// Decrypts the text with one key
+    fn decrypt(text: &str, key: &str) -> crate::decoders::crack_results::CrackResult {
+        let decoder = Decoder::<KeyedDecryptionDecoder>::new();
+        decrypt_with_keys(
+            &decoder,
+            text,
+            payloads(text),
+            &[key.to_string()],
+            &get_athena_checker(),
+        )
+    }
+
+    #[test]
+    fn aes_cbc_with_the_iv_first_decrypts() {
+        let result = decrypt(
+            "6465666768696a6b6c6d6e6f70717273a8339652cfb56fe0ed57ba77137da20c",
+            "000102030405060708090a0b0c0d0e0f",
+        );
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+        assert!(result
+            .description
+            .contains("AES-128-CBC with the IV before the ciphertext, reading the key as hex"));
+    }
+
+    #[test]
+    fn aes_gcm_with_a_passphrase_decrypts() {
+        let result = decrypt(
+            "MjM0NTY3ODk6Ozw9Yxs4sxl/k88z4L/yt2NKgFFnRNeqQensD11L9RlLXzCzdkJ5",
+            "correct horse battery staple",
+        );
+        assert_eq!(result.unencrypted_text.unwrap()[0], "the eagle has landed");
+        assert!(result.description.contains("AES-256-GCM"));
+        assert!(result.description.contains("the SHA-256 of its text"));
+        assert_eq!(result.key.unwrap(), "correct horse battery staple");
+    }
+
+    #[test]
+    fn aes_gcm_with_the_tag_first_decrypts() {
+        let result = decrypt(
+            "32333435363738393a3b3c3daa41e9ec0f5d4bf5194b5f30b3764279631b38b3197f93cf33e0bff2b7634a80516744d7",
+            "correct horse battery staple",
+        );
+        assert_eq!(result.unencrypted_text.unwrap()[0], "the eagle has landed");
+        assert!(result
+            .description
+            .contains("the nonce and the tag before the ciphertext"));
+    }
+
+    #[test]
+    fn chacha20_poly1305_decrypts() {
+        let result = decrypt(
+            "32333435363738393a3b3c3dff452ad3ff620cc438d85a81bb54dfb558ffaf93a399befd6aa6e4cfb7f51201650bd0f4",
+            "correct horse battery staple",
+        );
+        assert_eq!(result.unencrypted_text.unwrap()[0], "the eagle has landed");
+        assert!(result.description.contains("ChaCha20-Poly1305"));
+    }
+
+    #[test]
+    fn fernet_token_decrypts() {
+        let result = decrypt(
+            "gAAAAABlU_EAAAECAwQFBgcICQoLDA0OD2MuBJ5lu29qrcyDTUFKDjsqqfjRiqt4Xt6nN3-k3pnWzwnR79N3JZxTxRrbgW2sRlkOx6yjf0HwZlcStltv6DaYDpqJafUMNVhrNJZIfeow",
+            "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8=",
+        );
+        assert_eq!(
+            result.unencrypted_text.unwrap()[0],
+            "the treasure is under the bridge"
+        );
+        assert!(result.description.contains("Fernet"));
+    }
+
+    #[test]
+    fn wrong_key_decrypts_nothing() {
+        let result = decrypt(
+            "MjM0NTY3ODk6Ozw9Yxs4sxl/k88z4L/yt2NKgFFnRNeqQensD11L9RlLXzCzdkJ5",
+            "the wrong passphrase",
+        );
+        assert!(result.unencrypted_text.is_none());
+    }
+
+    #[test]
+    fn keyed_decryption_rejects_empty_string() {
+        let decoder = Decoder::<KeyedDecryptionDecoder>::new();
+        let result = decoder.crack("", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+
+    #[test]
+    fn keyed_decryption_rejects_emoji() {
+        let decoder = Decoder::<KeyedDecryptionDecoder>::new();
+        let result = decoder.crack("😂", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+}
@@ -0,0 +1,79 @@
+//! Decryption with keys the user already has
+//! When the key is known, modern ciphers are just another layer to take off. The keys are
+//! passed with `--key` or `--keyfile` and these decoders use them with AES-CBC, AES-GCM,
+//! ChaCha20-Poly1305 and Fernet on ciphertext read from hex, Base64 or raw bytes, working
+//! out where the IV, nonce and tag are from the usual layouts. Keys are never written to
+//! the config file.
+
+/// The aes module decrypts AES in the CBC and GCM modes
+pub mod aes;
+/// The chacha20_poly1305 module decrypts the ChaCha20-Poly1305 authenticated cipher
+pub mod chacha20_poly1305;
+/// The fernet module decrypts Fernet tokens
+pub mod fernet;
+/// The keyed_decryption_decoder module decrypts with the keys the user passed
+pub mod keyed_decryption_decoder;
+
+use base64::{
+    engine::general_purpose::{STANDARD, URL_SAFE},
+    Engine,
+};
+use sha2::{Digest, Sha256};
+
+use crate::decoders::radix::decode_hex;
+
+/// Every way a key could be written, named by how it was read
+/// Keys are read as their text, as hex and as Base64, and a passphrase is hashed with
+/// SHA-256 into a 32 byte key as many tools do. Readings giving the same bytes are kept once.
+pub fn key_readings(key: &str) -> Vec<(&'static str, Vec<u8>)> {
+    let trimmed = key.trim();
+    let mut readings = vec![("text", trimmed.as_bytes().to_vec())];
+    if let Some(bytes) = decode_hex(trimmed) {
+        readings.push(("hex", bytes));
+    }
+    if let Ok(bytes) = STANDARD
+        .decode(trimmed)
+        .or_else(|_| URL_SAFE.decode(trimmed))
+    {
+        readings.push(("Base64", bytes));
+    }
+    readings.push((
+        "the SHA-256 of its text",
+        Sha256::digest(trimmed.as_bytes()).to_vec(),
+    ));
+
+    let mut unique: Vec<(&'static str, Vec<u8>)> = Vec::new();
+    for (reading, bytes) in readings {
+        if !bytes.is_empty() && unique.iter().all(|(_, seen)| *seen != bytes) {
+            unique.push((reading, bytes));
+        }
+    }
+    unique
+}
+
+#[cfg(test)]
+mod tests {
+    use super::key_readings;
+
+    #[test]
+    fn keys_are_read_every_way() {
+        let readings = key_readings("000102030405060708090a0b0c0d0e0f");
+        let names: Vec<&str> = readings.iter().map(|(name, _)| *name).collect();
+        assert_eq!(names, ["text", "hex", "Base64", "the SHA-256 of its text"]);
+        assert_eq!(readings[1].1, (0..16).collect::<Vec<u8>>());
+    }
+
+    #[test]
+    fn fernet_keys_are_read_as_base64() {
+        let readings = key_readings("AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8=");
+        assert!(readings
+            .iter()
+            .any(|(name, bytes)| *name == "Base64" && *bytes == (0..32).collect::<Vec<u8>>()));
+    }
+
+    #[test]
+    fn only_hex_digits_are_read_as_hex() {
+        let readings = key_readings("+f+f");
+        assert!(readings.iter().all(|(name, _)| *name != "hex"));
+    }
+}
@@ -0,0 +1,468 @@
+//! Undo leetspeak and lookalike characters
+//! Leetspeak writes letters as digits and symbols that look like them, so `hello` becomes
+//! `h3ll0`, and homoglyphs swap letters for ones from other scripts or Unicode styles
+//! that look the same, such as a Cyrillic `а` or a fullwidth `ａ`. Both leave text that
+//! reads fine to a person but not to the checkers. Lookalikes are folded back to ASCII
+//! first, with Unicode compatibility normalisation and a table of letters from other
+//! scripts, and then leet characters inside words are turned back into letters. Where a
+//! symbol could be more than one letter, such as `1` for `i` or `l`, the reading which
+//! makes the most common word in the bundled English word list is used.
+//! Call leetspeak_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::check_string_success;
+use crate::storage::wordlists::ENGLISH_WORDS;
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::Decoder;
+
+use log::{debug, info, trace};
+use unicode_normalization::UnicodeNormalization;
+
+/// Leet spellings made of more than one character, longest first so they are matched first
+const LEET_SEQUENCES: [(&str, char); 12] = [
+    ("|\\/|", 'm'),
+    ("/\\/\\", 'm'),
+    ("\\/\\/", 'w'),
+    ("|\\|", 'n'),
+    ("|_|", 'u'),
+    ("|-|", 'h'),
+    ("]-[", 'h'),
+    ("/\\", 'a'),
+    ("\\/", 'v'),
+    ("|<", 'k'),
+    ("|)", 'd'),
+    ("()", 'o'),
+];
+
+/// Leet characters and the letters they can stand for, the usual one first
+const LEET_CHARACTERS: [(char, &str); 17] = [
+    ('4', "a"),
+    ('@', "a"),
+    ('8', "b"),
+    ('(', "c"),
+    ('3', "e"),
+    ('6', "g"),
+    ('9', "g"),
+    ('#', "h"),
+    ('1', "il"),
+    ('!', "il"),
+    ('|', "li"),
+    ('0', "o"),
+    ('5', "s"),
+    ('$', "s"),
+    ('7', "t"),
+    ('+', "t"),
+    ('2', "z"),
+];
+
+/// Letters from other scripts which look like ASCII letters
+const HOMOGLYPHS: [(char, char); 55] = [
+    // Cyrillic
+    ('А', 'A'),
+    ('В', 'B'),
+    ('Е', 'E'),
+    ('К', 'K'),
+    ('М', 'M'),
+    ('Н', 'H'),
+    ('О', 'O'),
+    ('Р', 'P'),
+    ('С', 'C'),
+    ('Т', 'T'),
+    ('Х', 'X'),
+    ('У', 'Y'),
+    ('Ѕ', 'S'),
+    ('І', 'I'),
+    ('Ј', 'J'),
+    ('а', 'a'),
+    ('е', 'e'),
+    ('о', 'o'),
+    ('р', 'p'),
+    ('с', 'c'),
+    ('у', 'y'),
+    ('х', 'x'),
+    ('ѕ', 's'),
+    ('і', 'i'),
+    ('ј', 'j'),
+    ('ԁ', 'd'),
+    ('һ', 'h'),
+    ('ԛ', 'q'),
+    ('ԝ', 'w'),
+    // Greek
+    ('Α', 'A'),
+    ('Β', 'B'),
+    ('Ε', 'E'),
+    ('Ζ', 'Z'),
+    ('Η', 'H'),
+    ('Ι', 'I'),
+    ('Κ', 'K'),
+    ('Μ', 'M'),
+    ('Ν', 'N'),
+    ('Ο', 'O'),
+    ('Ρ', 'P'),
+    ('Τ', 'T'),
+    ('Υ', 'Y'),
+    ('Χ', 'X'),
+    ('α', 'a'),
+    ('ι', 'i'),
+    ('κ', 'k'),
+    ('ν', 'v'),
+    ('ο', 'o'),
+    ('ρ', 'p'),
+    // Latin letters which look like others, and typographic punctuation
+    ('ı', 'i'),
+    ('ȷ', 'j'),
+    ('‘', '\''),
+    ('’', '\''),
+    ('“', '"'),
+    ('”', '"'),
+];
+
+/// Punctuation at the ends of a word which is kept as it is, rather than read as leet
+const WORD_PUNCTUATION: &str = ".,!?;:'\"()";
+
+/// The most readings of one word which are looked up, as each ambiguous symbol doubles them
+const MAX_READINGS: usize = 64;
+
+/// The Leetspeak decoder, call:
+/// `let leetspeak_decoder = Decoder::<LeetspeakDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = leetspeak_decoder.crack(input)` to undo leetspeak and homoglyphs
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::leetspeak_decoder::{LeetspeakDecoder};
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_leetspeak = Decoder::<LeetspeakDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_leetspeak.crack("7h15 15 4 53cr37 m3554g3", &checker);
+/// assert_eq!(result.unencrypted_text.unwrap()[0], "this is a secret message");
+/// // The Cyrillic а and о look just like a and o
+/// let result = decode_leetspeak.crack("pаsswоrd", &checker);
+/// assert_eq!(result.unencrypted_text.unwrap()[0], "password");
+/// ```
+pub struct LeetspeakDecoder;
+
+impl Crack for Decoder<LeetspeakDecoder> {
+    fn new() -> Decoder<LeetspeakDecoder> {
+        Decoder {
+            name: "Leetspeak",
+            description: "Leetspeak replaces letters with digits and symbols which look like them, such as 3 for E and 0 for O. Homoglyphs do the same with letters from other scripts and Unicode styles, so text looks normal but isn't the same characters.",
+            link: "https://en.wikipedia.org/wiki/Leet",
+            tags: vec!["leetspeak", "homoglyph", "decoder", "substitution"],
+            popularity: 0.5,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying leetspeak with text {:?}", text);
+        let mut results = CrackResult::new(self, text.to_string());
+
+        let folded = fold_homoglyphs(text);
+        if !folded.chars().any(|c| c.is_ascii_alphabetic()) {
+            debug!("Failed to decode leetspeak because the text has no letters");
+            return results;
+        }
+        let decoded_text = undo_leet(&folded);
+        if !check_string_success(&decoded_text, text) {
+            info!(
+                "Failed to decode leetspeak because check_string_success returned false on string {}",
+                decoded_text
+            );
+            return results;
+        }
+
+        // Encodings like Base64 are full of digits, so keep only readings which found words
+        if ENGLISH_WORDS.count_words_in(&decoded_text) <= ENGLISH_WORDS.count_words_in(text) {
+            debug!("Failed to decode leetspeak because no more English words were found");
+            return results;
+        }
+
+        let checker_result = checker.check(&decoded_text);
+        results.unencrypted_text = Some(vec![decoded_text]);
+        results.update_checker(&checker_result);
+
+        results
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some(("h3ll0 w0rld", "hello world"))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input must contain at least one letter once lookalikes are folded to ASCII",
+            "Leet characters are read in words with letters, and in words of only symbols when they make a known word",
+            "Decoded text must have more words from the bundled English word list than the input",
+        ]
+    }
+}
+
+/// Folds lookalike characters back to ASCII
+/// Compatibility normalisation turns fullwidth, mathematical and circled letters into
+/// plain ones. Letters from other scripts are only swapped in words where every one of
+/// them looks like an ASCII letter, so real Cyrillic or Greek words are left alone.
+fn fold_homoglyphs(text: &str) -> String {
+    let normalised: String = text.nfkc().collect();
+    split_keeping_whitespace(&normalised)
+        .map(|word| {
+            let all_lookalikes = word
+                .chars()
+                .filter(|c| !c.is_ascii())
+                .all(|c| !c.is_alphabetic() || homoglyph(c).is_some());
+            if !all_lookalikes {
+                return word.to_string();
+            }
+            word.chars().map(|c| homoglyph(c).unwrap_or(c)).collect()
+        })
+        .collect()
+}
+
+/// The ASCII character a lookalike from another script stands for
+fn homoglyph(c: char) -> Option<char> {
+    HOMOGLYPHS
+        .iter()
+        .find(|(lookalike, _)| *lookalike == c)
+        .map(|(_, ascii)| *ascii)
+}
+
+/// Splits text into words and the runs of whitespace between them, keeping both
+fn split_keeping_whitespace(text: &str) -> impl Iterator<Item = &str> {
+    let mut rest = text;
+    std::iter::from_fn(move || {
+        let first = rest.chars().next()?;
+        let end = rest
+            .find(|c: char| c.is_whitespace() != first.is_whitespace())
+            .unwrap_or(rest.len());
+        let (piece, remaining) = rest.split_at(end);
+        rest = remaining;
+        Some(piece)
+    })
+}
+
+/// Turns leet characters back into letters
+/// Words with letters in are always read. Words of only symbols and digits, such as `15`,
+/// are only read when the text has other leet words and they make a known word, so plain
+/// numbers are kept.
+fn undo_leet(text: &str) -> String {
+    let has_leet_words = split_keeping_whitespace(text)
+        .any(|word| word.chars().any(|c| c.is_ascii_alphabetic()) && word.chars().any(is_leet));
+    split_keeping_whitespace(text)
+        .map(|word| {
+            if word.chars().any(|c| c.is_ascii_alphabetic()) {
+                return undo_leet_word(word).0;
+            }
+            match undo_leet_word(word) {
+                (reading, Some(_)) if has_leet_words => reading,
+                _ => word.to_string(),
+            }
+        })
+        .collect()
+}
+
+/// Whether the character can stand for a letter
+fn is_leet(c: char) -> bool {
+    LEET_CHARACTERS.iter().any(|(leet, _)| *leet == c)
+        || LEET_SEQUENCES
+            .iter()
+            .any(|(sequence, _)| sequence.starts_with(c))
+}
+
+/// Reads one word, keeping punctuation at its ends and picking the most common reading
+/// Also gives where the reading is in the word list, if it is there.
+fn undo_leet_word(word: &str) -> (String, Option<usize>) {
+    // Punctuation which is part of a leet spelling such as `|)` stays in the word
+    let start = if LEET_SEQUENCES
+        .iter()
+        .any(|(sequence, _)| word.starts_with(sequence))
+    {
+        0
+    } else {
+        word.len()
+            - word
+                .trim_start_matches(|c| WORD_PUNCTUATION.contains(c))
+                .len()
+    };
+    let end = if LEET_SEQUENCES
+        .iter()
+        .any(|(sequence, _)| word.ends_with(sequence))
+    {
+        word.len()
+    } else {
+        word.trim_end_matches(|c| WORD_PUNCTUATION.contains(c))
+            .len()
+    };
+    if start >= end {
+        return (word.to_string(), None);
+    }
+    let (prefix, core, suffix) = (&word[..start], &word[start..end], &word[end..]);
+    let mut letters = core.chars().filter(|c| c.is_ascii_alphabetic()).peekable();
+    let uppercase = letters.peek().is_some() && letters.all(|c| c.is_ascii_uppercase());
+
+    // Every place in the word, as the letters it could be
+    let mut places: Vec<Vec<char>> = Vec::new();
+    let mut rest = core;
+    while let Some(c) = rest.chars().next() {
+        if let Some((sequence, letter)) = LEET_SEQUENCES
+            .iter()
+            .find(|(sequence, _)| rest.starts_with(sequence))
+        {
+            places.push(vec![*letter]);
+            rest = &rest[sequence.len()..];
+            continue;
+        }
+        match LEET_CHARACTERS.iter().find(|(leet, _)| *leet == c) {
+            Some((_, letters)) => places.push(letters.chars().collect()),
+            None => places.push(vec![c]),
+        }
+        rest = &rest[c.len_utf8()..];
+    }
+
+    let mut readings = vec![String::new()];
+    for letters in &places {
+        if readings.len() * letters.len() > MAX_READINGS {
+            // Too many readings to try, so the rest of the word takes the usual letters
+            readings
+                .iter_mut()
+                .for_each(|reading| reading.push(letters[0]));
+            continue;
+        }
+        readings = readings
+            .iter()
+            .flat_map(|reading| {
+                letters.iter().map(move |letter| {
+                    let mut reading = reading.clone();
+                    reading.push(*letter);
+                    reading
+                })
+            })
+            .collect();
+    }
+    let (best, rank) = readings
+        .into_iter()
+        .map(|reading| {
+            let rank = ENGLISH_WORDS.rank(&reading.to_ascii_lowercase());
+            (reading, rank)
+        })
+        .min_by_key(|(_, rank)| rank.unwrap_or(usize::MAX))
+        .unwrap_or_default();
+    let best = if uppercase {
+        best.to_ascii_uppercase()
+    } else {
+        best
+    };
+    (format!("{prefix}{best}{suffix}"), rank)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fold_homoglyphs, undo_leet, LeetspeakDecoder};
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn successful_decoding() {
+        let leetspeak_decoder = Decoder::<LeetspeakDecoder>::new();
+        let result = leetspeak_decoder.crack("7h15 15 4 53cr37 m3554g3", &get_athena_checker());
+        assert_eq!(
+            result.unencrypted_text.unwrap()[0],
+            "this is a secret message"
+        );
+    }
+
+    #[test]
+    fn ambiguous_symbols_make_the_most_common_word() {
+        // 1 is i in this and l in hello
+        assert_eq!(undo_leet("th1s he11o"), "this hello");
+    }
+
+    #[test]
+    fn sequences_and_capitals() {
+        assert_eq!(undo_leet("|-|3ll0 \\/\\/0r|)"), "hello word");
+        assert_eq!(undo_leet("H4CK3R |\\/|4N"), "HACKER MAN");
+    }
+
+    #[test]
+    fn punctuation_and_numbers_are_kept() {
+        assert_eq!(undo_leet("h3ll0! it costs 250"), "hello! it costs 250");
+        assert_eq!(undo_leet("it costs 15"), "it costs 15");
+    }
+
+    #[test]
+    fn homoglyphs_are_folded() {
+        assert_eq!(fold_homoglyphs("ｈｅｌｌｏ"), "hello");
+        assert_eq!(fold_homoglyphs("𝐡𝐞𝐥𝐥𝐨"), "hello");
+        assert_eq!(fold_homoglyphs("pаypаl"), "paypal");
+    }
+
+    #[test]
+    fn real_cyrillic_words_are_kept() {
+        assert_eq!(fold_homoglyphs("привет"), "привет");
+    }
+
+    #[test]
+    fn encodings_full_of_digits_are_not_decoded() {
+        let leetspeak_decoder = Decoder::<LeetspeakDecoder>::new();
+        let result = leetspeak_decoder.crack("SGVsbG8gV29ybGQ=", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+
+    #[test]
+    fn leetspeak_handle_panic_if_empty_string() {
+        let leetspeak_decoder = Decoder::<LeetspeakDecoder>::new();
+        let result = leetspeak_decoder
+            .crack("", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn leetspeak_handle_panic_if_emoji() {
+        let leetspeak_decoder = Decoder::<LeetspeakDecoder>::new();
+        let result = leetspeak_decoder
+            .crack("😂", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+}
@@ -38,6 +38,10 @@ pub mod citrix_ctx1_decoder;
 /// The crack_results module defines the CrackResult
 /// Each and every decoder return same CrackResult
 pub mod crack_results;
+/// The custom module holds decoders registered at runtime by users of the library
+pub mod custom;
+/// The plugin_decoder module runs decoders written as WebAssembly plugins
+pub mod plugin_decoder;
 /// The url_decoder module decodes url
 pub mod url_decoder;
 
@@ -77,6 +81,94 @@ pub mod brainfuck_interpreter;
 /// The vigenere_decoder module decodes Vigenère cipher text
 pub mod vigenere_decoder;
 
+/// Letter numbering and mapping shared by the classical cipher decoders
+pub mod alphabet;
+/// Statistical helpers shared by the classical cipher decoders
+pub mod frequency_analysis;
+/// Parses byte strings written as numbers, shared by the octal, decimal, Base62 and Base36 decoders
+pub mod radix;
+
+/// The affine_decoder module decodes the affine cipher
+pub mod affine_decoder;
+/// The ascii85_decoder module decodes Ascii85 and its variants
+pub mod ascii85_decoder;
+/// The base122_decoder module decodes base122
+pub mod base122_decoder;
+/// The base2048_decoder module decodes base2048
+pub mod base2048_decoder;
+/// The base32768_decoder module decodes base32768
+pub mod base32768_decoder;
+/// The base32_variant_decoder module decodes base32 with the base32hex, Crockford and z-base-32 alphabets
+pub mod base32_variant_decoder;
+/// The base36_decoder module decodes base36
+pub mod base36_decoder;
+/// The base45_decoder module decodes base45
+pub mod base45_decoder;
+/// The base62_decoder module decodes base62
+pub mod base62_decoder;
+/// The base92_decoder module decodes base92
+pub mod base92_decoder;
+/// The bcd_decoder module decodes packed and unpacked binary-coded decimal
+pub mod bcd_decoder;
+/// Decryptors which try common keys and the wordlist against RC4, DES and XOR
+pub mod brute_force;
+/// The bubble_babble_decoder module decodes Bubble Babble fingerprints
+pub mod bubble_babble_decoder;
+/// The charset_decoder module decodes UTF-16, UTF-32, EBCDIC and code page text
+pub mod charset_decoder;
+/// Classical ciphers whose keys are searched for with n-gram fitness
+pub mod classical;
+/// The columnar_transposition_decoder module cracks columnar transposition
+pub mod columnar_transposition_decoder;
+/// Decoders which decompress gzip, zlib, raw deflate, bzip2, brotli and LZMA payloads
+pub mod compression;
+/// The decimal_decoder module decodes decimal byte strings such as `104 101 108`
+pub mod decimal_decoder;
+/// The dna_decoder module decodes text written as DNA bases or codons
+pub mod dna_decoder;
+/// Interpreters for esoteric programming languages such as Ook!, Whitespace, Malbolge and JSFuck
+pub mod esolang;
+/// The gray_code_decoder module decodes bytes written in Gray code
+pub mod gray_code_decoder;
+/// Identifiers which recognise password hashes and look them up in the dictionary
+pub mod hashes;
+/// The hexdump_decoder module decodes xxd and hexdump -C output
+pub mod hexdump_decoder;
+/// The jwt_decoder module decodes JSON Web Tokens
+pub mod jwt_decoder;
+/// The keyboard_shift_decoder module decodes text typed one key off on the keyboard
+pub mod keyboard_shift_decoder;
+/// Decryptors which use the keys passed with --key and --keyfile on AES, ChaCha20-Poly1305 and Fernet
+pub mod keyed;
+/// The leetspeak_decoder module undoes leetspeak and lookalike characters
+pub mod leetspeak_decoder;
+/// The octal_decoder module decodes octal byte strings such as `150 145 154`
+pub mod octal_decoder;
+/// The phone_keypad_decoder module decodes multi-tap and T9 phone keypad presses
+pub mod phone_keypad_decoder;
+/// The skey_decoder module decodes the RFC 2289 S/KEY six word format
+pub mod skey_decoder;
+/// The substitution_solver module breaks monoalphabetic substitution ciphers
+pub mod substitution_solver;
+/// The text_transform_decoder module undoes simple rearrangements of the text
+pub mod text_transform_decoder;
+/// The unicode_escape_decoder module decodes `\u` and `\x` escape sequences
+pub mod unicode_escape_decoder;
+/// The utf7_decoder module decodes UTF-7
+pub mod utf7_decoder;
+/// The uuencode_decoder module decodes uuencode
+pub mod uuencode_decoder;
+/// The whitespace_stego_decoder module decodes messages hidden in trailing spaces and tabs
+pub mod whitespace_stego_decoder;
+/// The xor_decoder module cracks single-byte and repeating-key XOR
+pub mod xor_decoder;
+/// The xxencode_decoder module decodes xxencode
+pub mod xxencode_decoder;
+/// The yenc_decoder module decodes yEnc
+pub mod yenc_decoder;
+/// The zero_width_decoder module decodes messages hidden in zero-width characters and Unicode tags
+pub mod zero_width_decoder;
+pub use self::custom::{clear_custom_decoders, register_decoder};
 use atbash_decoder::AtbashDecoder;
 use base32_decoder::Base32Decoder;
 use base58_bitcoin_decoder::Base58BitcoinDecoder;
@@ -103,7 +195,62 @@ use url_decoder::URLDecoder;
 use vigenere_decoder::VigenereDecoder;
 use z85_decoder::Z85Decoder;
 
+use affine_decoder::AffineDecoder;
+use ascii85_decoder::Ascii85Decoder;
+use base122_decoder::Base122Decoder;
+use base2048_decoder::Base2048Decoder;
+use base32768_decoder::Base32768Decoder;
+use base32_variant_decoder::Base32VariantDecoder;
+use base36_decoder::Base36Decoder;
+use base45_decoder::Base45Decoder;
+use base62_decoder::Base62Decoder;
+use base92_decoder::Base92Decoder;
+use bcd_decoder::BcdDecoder;
 use brainfuck_interpreter::BrainfuckInterpreter;
+use brute_force::brute_force_cipher_decoder::BruteForceCipherDecoder;
+use bubble_babble_decoder::BubbleBabbleDecoder;
+use charset_decoder::CharsetDecoder;
+use classical::adfgvx_decoder::AdfgvxDecoder;
+use classical::autokey_decoder::AutokeyDecoder;
+use classical::beaufort_decoder::BeaufortDecoder;
+use classical::bifid_decoder::BifidDecoder;
+use classical::enigma_decoder::EnigmaDecoder;
+use classical::playfair_decoder::PlayfairDecoder;
+use classical::polybius_decoder::PolybiusDecoder;
+use classical::tap_code_decoder::TapCodeDecoder;
+use columnar_transposition_decoder::ColumnarTranspositionDecoder;
+use compression::brotli_decoder::BrotliDecoder;
+use compression::bzip2_decoder::Bzip2Decoder;
+use compression::deflate_decoder::DeflateDecoder;
+use compression::gzip_decoder::GzipDecoder;
+use compression::lzma_decoder::LzmaDecoder;
+use compression::zlib_decoder::ZlibDecoder;
+use decimal_decoder::DecimalDecoder;
+use dna_decoder::DnaDecoder;
+use esolang::jsfuck_decoder::JsfuckDecoder;
+use esolang::malbolge_decoder::MalbolgeDecoder;
+use esolang::ook_interpreter::OokInterpreter;
+use esolang::whitespace_interpreter::WhitespaceInterpreter;
+use gray_code_decoder::GrayCodeDecoder;
+use hashes::hash_identifier::HashIdentifier;
+use hexdump_decoder::HexdumpDecoder;
+use jwt_decoder::JwtDecoder;
+use keyboard_shift_decoder::KeyboardShiftDecoder;
+use keyed::keyed_decryption_decoder::KeyedDecryptionDecoder;
+use leetspeak_decoder::LeetspeakDecoder;
+use octal_decoder::OctalDecoder;
+use phone_keypad_decoder::PhoneKeypadDecoder;
+use skey_decoder::SkeyDecoder;
+use substitution_solver::SubstitutionSolver;
+use text_transform_decoder::TextTransformDecoder;
+use unicode_escape_decoder::UnicodeEscapeDecoder;
+use utf7_decoder::Utf7Decoder;
+use uuencode_decoder::UuencodeDecoder;
+use whitespace_stego_decoder::WhitespaceStegoDecoder;
+use xor_decoder::XorDecoder;
+use xxencode_decoder::XxencodeDecoder;
+use yenc_decoder::YencDecoder;
+use zero_width_decoder::ZeroWidthDecoder;
 
 use once_cell::sync::Lazy;
 use std::collections::HashMap;
@@ -161,6 +308,116 @@ pub enum DecoderType {
     BrainfuckInterpreter(brainfuck_interpreter::BrainfuckInterpreter),
     /// vigenere decoder
     VigenereDecoder(vigenere_decoder::VigenereDecoder),
+    /// the ascii85_decoder module decodes Ascii85 and its variants
+    Ascii85Decoder(ascii85_decoder::Ascii85Decoder),
+    /// the xor_decoder module cracks single-byte and repeating-key XOR
+    XorDecoder(xor_decoder::XorDecoder),
+    /// the octal_decoder module decodes octal byte strings such as `150 145 154`
+    OctalDecoder(octal_decoder::OctalDecoder),
+    /// the decimal_decoder module decodes decimal byte strings such as `104 101 108`
+    DecimalDecoder(decimal_decoder::DecimalDecoder),
+    /// the affine_decoder module decodes the affine cipher
+    AffineDecoder(affine_decoder::AffineDecoder),
+    /// the columnar_transposition_decoder module cracks columnar transposition
+    ColumnarTranspositionDecoder(columnar_transposition_decoder::ColumnarTranspositionDecoder),
+    /// the substitution_solver module breaks monoalphabetic substitution ciphers
+    SubstitutionSolver(substitution_solver::SubstitutionSolver),
+    /// the autokey_decoder module cracks the autokey cipher
+    AutokeyDecoder(classical::autokey_decoder::AutokeyDecoder),
+    /// the beaufort_decoder module cracks the Beaufort cipher
+    BeaufortDecoder(classical::beaufort_decoder::BeaufortDecoder),
+    /// the playfair_decoder module cracks the Playfair cipher
+    PlayfairDecoder(classical::playfair_decoder::PlayfairDecoder),
+    /// the polybius_decoder module decodes Polybius square coordinates
+    PolybiusDecoder(classical::polybius_decoder::PolybiusDecoder),
+    /// the bifid_decoder module cracks the Bifid cipher
+    BifidDecoder(classical::bifid_decoder::BifidDecoder),
+    /// the adfgvx_decoder module cracks the ADFGVX and ADFGX ciphers
+    AdfgvxDecoder(classical::adfgvx_decoder::AdfgvxDecoder),
+    /// cracks Enigma M3 messages
+    EnigmaDecoder(classical::enigma_decoder::EnigmaDecoder),
+    /// base62 decoder
+    Base62Decoder(base62_decoder::Base62Decoder),
+    /// base36 decoder
+    Base36Decoder(base36_decoder::Base36Decoder),
+    /// base45 decoder
+    Base45Decoder(base45_decoder::Base45Decoder),
+    /// base92 decoder
+    Base92Decoder(base92_decoder::Base92Decoder),
+    /// base122 decoder
+    Base122Decoder(base122_decoder::Base122Decoder),
+    /// base32768 decoder
+    Base32768Decoder(base32768_decoder::Base32768Decoder),
+    /// base2048 decoder
+    Base2048Decoder(base2048_decoder::Base2048Decoder),
+    /// base32 variants decoder
+    Base32VariantDecoder(base32_variant_decoder::Base32VariantDecoder),
+    /// uuencode decoder
+    UuencodeDecoder(uuencode_decoder::UuencodeDecoder),
+    /// xxencode decoder
+    XxencodeDecoder(xxencode_decoder::XxencodeDecoder),
+    /// yEnc decoder
+    YencDecoder(yenc_decoder::YencDecoder),
+    /// gzip decoder
+    GzipDecoder(compression::gzip_decoder::GzipDecoder),
+    /// zlib decoder
+    ZlibDecoder(compression::zlib_decoder::ZlibDecoder),
+    /// raw deflate decoder
+    DeflateDecoder(compression::deflate_decoder::DeflateDecoder),
+    /// bzip2 decoder
+    Bzip2Decoder(compression::bzip2_decoder::Bzip2Decoder),
+    /// brotli decoder
+    BrotliDecoder(compression::brotli_decoder::BrotliDecoder),
+    /// xz and lzma decoder
+    LzmaDecoder(compression::lzma_decoder::LzmaDecoder),
+    /// charset decoder
+    CharsetDecoder(charset_decoder::CharsetDecoder),
+    /// UTF-7 decoder
+    Utf7Decoder(utf7_decoder::Utf7Decoder),
+    /// JWT decoder
+    JwtDecoder(jwt_decoder::JwtDecoder),
+    /// unicode escape decoder
+    UnicodeEscapeDecoder(unicode_escape_decoder::UnicodeEscapeDecoder),
+    /// hexdump decoder
+    HexdumpDecoder(hexdump_decoder::HexdumpDecoder),
+    /// Ook! interpreter
+    OokInterpreter(esolang::ook_interpreter::OokInterpreter),
+    /// whitespace interpreter
+    WhitespaceInterpreter(esolang::whitespace_interpreter::WhitespaceInterpreter),
+    /// JSFuck decoder
+    JsfuckDecoder(esolang::jsfuck_decoder::JsfuckDecoder),
+    /// malbolge decoder
+    MalbolgeDecoder(esolang::malbolge_decoder::MalbolgeDecoder),
+    /// Keyboard shift decoder
+    KeyboardShiftDecoder(keyboard_shift_decoder::KeyboardShiftDecoder),
+    /// Phone keypad decoder
+    PhoneKeypadDecoder(phone_keypad_decoder::PhoneKeypadDecoder),
+    /// Tap code decoder
+    TapCodeDecoder(classical::tap_code_decoder::TapCodeDecoder),
+    /// Leetspeak decoder
+    LeetspeakDecoder(leetspeak_decoder::LeetspeakDecoder),
+    /// Text transform decoder
+    TextTransformDecoder(text_transform_decoder::TextTransformDecoder),
+    /// Gray code decoder
+    GrayCodeDecoder(gray_code_decoder::GrayCodeDecoder),
+    /// BCD decoder
+    BcdDecoder(bcd_decoder::BcdDecoder),
+    /// DNA decoder
+    DnaDecoder(dna_decoder::DnaDecoder),
+    /// Bubble Babble decoder
+    BubbleBabbleDecoder(bubble_babble_decoder::BubbleBabbleDecoder),
+    /// S/KEY decoder
+    SkeyDecoder(skey_decoder::SkeyDecoder),
+    /// Brute force cipher decoder
+    BruteForceCipherDecoder(brute_force::brute_force_cipher_decoder::BruteForceCipherDecoder),
+    /// Decrypts AES, ChaCha20-Poly1305 and Fernet with the keys passed with --key or --keyfile
+    KeyedDecryptionDecoder(keyed::keyed_decryption_decoder::KeyedDecryptionDecoder),
+    /// Hash identifier looking hashes up in the dictionary
+    HashIdentifier(hashes::hash_identifier::HashIdentifier),
+    /// Zero-width decoder
+    ZeroWidthDecoder(zero_width_decoder::ZeroWidthDecoder),
+    /// Whitespace steganography decoder
+    WhitespaceStegoDecoder(whitespace_stego_decoder::WhitespaceStegoDecoder),
 }
 
 /// Wrapper struct to hold Decoders for DECODER_MAP
@@ -251,5 +508,135 @@ pub static DECODER_MAP: Lazy<HashMap<&str, DecoderBox>> = Lazy::new(|| {
             "Brainfuck",
             DecoderBox::new(Decoder::<BrainfuckInterpreter>::new()),
         ),
+        ("Ascii85", DecoderBox::new(Decoder::<Ascii85Decoder>::new())),
+        ("XOR", DecoderBox::new(Decoder::<XorDecoder>::new())),
+        ("Octal", DecoderBox::new(Decoder::<OctalDecoder>::new())),
+        ("Decimal", DecoderBox::new(Decoder::<DecimalDecoder>::new())),
+        ("Affine", DecoderBox::new(Decoder::<AffineDecoder>::new())),
+        (
+            "Columnar Transposition",
+            DecoderBox::new(Decoder::<ColumnarTranspositionDecoder>::new()),
+        ),
+        (
+            "Substitution Solver",
+            DecoderBox::new(Decoder::<SubstitutionSolver>::new()),
+        ),
+        ("Autokey", DecoderBox::new(Decoder::<AutokeyDecoder>::new())),
+        (
+            "Beaufort",
+            DecoderBox::new(Decoder::<BeaufortDecoder>::new()),
+        ),
+        (
+            "Playfair",
+            DecoderBox::new(Decoder::<PlayfairDecoder>::new()),
+        ),
+        (
+            "Polybius Square",
+            DecoderBox::new(Decoder::<PolybiusDecoder>::new()),
+        ),
+        ("Bifid", DecoderBox::new(Decoder::<BifidDecoder>::new())),
+        ("ADFGVX", DecoderBox::new(Decoder::<AdfgvxDecoder>::new())),
+        ("Enigma", DecoderBox::new(Decoder::<EnigmaDecoder>::new())),
+        ("Base62", DecoderBox::new(Decoder::<Base62Decoder>::new())),
+        ("Base36", DecoderBox::new(Decoder::<Base36Decoder>::new())),
+        ("Base45", DecoderBox::new(Decoder::<Base45Decoder>::new())),
+        ("Base92", DecoderBox::new(Decoder::<Base92Decoder>::new())),
+        ("Base122", DecoderBox::new(Decoder::<Base122Decoder>::new())),
+        (
+            "Base32768",
+            DecoderBox::new(Decoder::<Base32768Decoder>::new()),
+        ),
+        (
+            "Base2048",
+            DecoderBox::new(Decoder::<Base2048Decoder>::new()),
+        ),
+        (
+            "Base32 Variants",
+            DecoderBox::new(Decoder::<Base32VariantDecoder>::new()),
+        ),
+        (
+            "Uuencode",
+            DecoderBox::new(Decoder::<UuencodeDecoder>::new()),
+        ),
+        (
+            "XXencode",
+            DecoderBox::new(Decoder::<XxencodeDecoder>::new()),
+        ),
+        ("yEnc", DecoderBox::new(Decoder::<YencDecoder>::new())),
+        ("Gzip", DecoderBox::new(Decoder::<GzipDecoder>::new())),
+        ("Zlib", DecoderBox::new(Decoder::<ZlibDecoder>::new())),
+        ("Deflate", DecoderBox::new(Decoder::<DeflateDecoder>::new())),
+        ("Bzip2", DecoderBox::new(Decoder::<Bzip2Decoder>::new())),
+        ("Brotli", DecoderBox::new(Decoder::<BrotliDecoder>::new())),
+        ("LZMA", DecoderBox::new(Decoder::<LzmaDecoder>::new())),
+        ("Charset", DecoderBox::new(Decoder::<CharsetDecoder>::new())),
+        ("UTF-7", DecoderBox::new(Decoder::<Utf7Decoder>::new())),
+        ("JWT", DecoderBox::new(Decoder::<JwtDecoder>::new())),
+        (
+            "Unicode escapes",
+            DecoderBox::new(Decoder::<UnicodeEscapeDecoder>::new()),
+        ),
+        ("Hexdump", DecoderBox::new(Decoder::<HexdumpDecoder>::new())),
+        ("Ook!", DecoderBox::new(Decoder::<OokInterpreter>::new())),
+        (
+            "Whitespace",
+            DecoderBox::new(Decoder::<WhitespaceInterpreter>::new()),
+        ),
+        ("JSFuck", DecoderBox::new(Decoder::<JsfuckDecoder>::new())),
+        (
+            "Malbolge",
+            DecoderBox::new(Decoder::<MalbolgeDecoder>::new()),
+        ),
+        (
+            "Keyboard shift",
+            DecoderBox::new(Decoder::<KeyboardShiftDecoder>::new()),
+        ),
+        (
+            "Phone keypad",
+            DecoderBox::new(Decoder::<PhoneKeypadDecoder>::new()),
+        ),
+        (
+            "Tap code",
+            DecoderBox::new(Decoder::<TapCodeDecoder>::new()),
+        ),
+        (
+            "Leetspeak",
+            DecoderBox::new(Decoder::<LeetspeakDecoder>::new()),
+        ),
+        (
+            "Text transform",
+            DecoderBox::new(Decoder::<TextTransformDecoder>::new()),
+        ),
+        (
+            "Gray code",
+            DecoderBox::new(Decoder::<GrayCodeDecoder>::new()),
+        ),
+        ("BCD", DecoderBox::new(Decoder::<BcdDecoder>::new())),
+        ("DNA", DecoderBox::new(Decoder::<DnaDecoder>::new())),
+        (
+            "Bubble Babble",
+            DecoderBox::new(Decoder::<BubbleBabbleDecoder>::new()),
+        ),
+        ("S/KEY", DecoderBox::new(Decoder::<SkeyDecoder>::new())),
+        (
+            "Brute force ciphers",
+            DecoderBox::new(Decoder::<BruteForceCipherDecoder>::new()),
+        ),
+        (
+            "Keyed decryption",
+            DecoderBox::new(Decoder::<KeyedDecryptionDecoder>::new()),
+        ),
+        (
+            "Hash identifier",
+            DecoderBox::new(Decoder::<HashIdentifier>::new()),
+        ),
+        (
+            "Zero-width",
+            DecoderBox::new(Decoder::<ZeroWidthDecoder>::new()),
+        ),
+        (
+            "Whitespace steganography",
+            DecoderBox::new(Decoder::<WhitespaceStegoDecoder>::new()),
+        ),
     ])
 });
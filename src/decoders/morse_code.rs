@@ -8,9 +8,16 @@ use super::interface::Decoder;
 use log::{debug, info, trace};
 use regex::Regex;
 
+/// The fewest dots and dashes the input needs before we try decoding it
+/// Anything shorter decodes to a letter or two, which checkers too easily accept.
+const MIN_SYMBOLS: usize = 5;
+
+/// Characters which separate words, on top of new lines
+const WORD_SEPARATORS: [char; 5] = ['/', '\\', '|', ':', ','];
+
 /// Morse Code Decoder
-/// Does not support decoding of morse code with / instead of a space
-/// or new lines for new words.
+/// Letters are separated by spaces and words by `/`, `|`, `\`, `:`, `,` or new lines.
+/// Dots and dashes can also be written as `·` and `–`, or as `0` and `1`.
 pub struct MorseCodeDecoder;
 
 impl Crack for Decoder<MorseCodeDecoder> {
@@ -30,8 +37,11 @@ impl Crack for Decoder<MorseCodeDecoder> {
     /// Else the Option returns nothing and the error is logged in Trace
     fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
         trace!("Trying Morse Code with text {:?}", text);
-        // TODO support new line and slash morse code
         let text = normalise_morse_string(text);
+        if text.matches(['.', '-']).count() < MIN_SYMBOLS {
+            debug!("Failed to decode Morse Code because the input has too few symbols");
+            return CrackResult::new(self, text);
+        }
         let decoded_text: Option<String> = text.split(' ').map(morse_to_alphanumeric).collect();
 
         // remove leading and trailing spaces, and collapse repeated spaces into a single space
@@ -78,14 +88,58 @@ impl Crack for Decoder<MorseCodeDecoder> {
     fn get_link(&self) -> &str {
         self.link
     }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some((
+            ".... . .-.. .-.. --- / .-- --- .-. .-.. -.. -.-.--",
+            "HELLO WORLD!",
+        ))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input must contain at least 5 dots and dashes",
+            "Every token must be a known Morse code sequence",
+            "Words may be separated by `/`, `|`, `\\`, `:`, `,` or new lines",
+            "Dots and dashes may be written as `.` `-`, `·` `–` or `0` `1`",
+        ]
+    }
 }
 
 /// Replace new lines, line breaks, and other delimiters with the standard delimiter '/'
+/// and alternative dots and dashes with `.` and `-`
 fn normalise_morse_string(text: &str) -> String {
+    let text: String = text
+        .chars()
+        .map(|c| match c {
+            '·' | '•' | '∙' => '.',
+            '–' | '—' | '−' | '_' => '-',
+            c => c,
+        })
+        .collect();
+    let text = if is_binary_morse(&text) {
+        text.replace('0', ".").replace('1', "-")
+    } else {
+        text
+    };
     // The replace function supports patterns https://doc.rust-lang.org/std/str/pattern/trait.Pattern.html#impl-Pattern%3C%27a%3E-3
     // Spaces are included before and after so that '/' gets split into a separate token
     text.to_lowercase()
-        .replace(['/', '\\', ':', ',', '\n', '\r'], " / ")
+        .replace(WORD_SEPARATORS, " / ")
+        .replace(['\n', '\r'], " / ")
+}
+
+/// Morse written with `0` for dots and `1` for dashes, and nothing else but separators
+fn is_binary_morse(text: &str) -> bool {
+    let mut symbols = text
+        .chars()
+        .filter(|c| !c.is_whitespace() && !WORD_SEPARATORS.contains(c))
+        .peekable();
+    symbols.peek().is_some() && symbols.all(|c| c == '0' || c == '1')
 }
 
 /// Maps morse code to its alphanumeric character, returns None for invalid morse-code
@@ -299,6 +353,50 @@ mod tests {
         assert_eq!(result.unencrypted_text.unwrap()[0], "HELLO WORLD!");
     }
 
+    #[test]
+    fn test_morse_code_pipe() {
+        let decoder = Decoder::<MorseCodeDecoder>::new();
+        let result = decoder.crack(
+            r".... . .-.. .-.. --- | .-- --- .-. .-.. -.. -.-.--",
+            &get_athena_checker(),
+        );
+        assert_eq!(result.unencrypted_text.unwrap()[0], "HELLO WORLD!");
+    }
+
+    #[test]
+    fn test_morse_code_middle_dots_and_en_dashes() {
+        let decoder = Decoder::<MorseCodeDecoder>::new();
+        let result = decoder.crack(
+            "···· · ·–·· ·–·· ––– / ·–– ––– ·–· ·–·· –·· –·–·––",
+            &get_athena_checker(),
+        );
+        assert_eq!(result.unencrypted_text.unwrap()[0], "HELLO WORLD!");
+    }
+
+    #[test]
+    fn test_morse_code_binary_style() {
+        let decoder = Decoder::<MorseCodeDecoder>::new();
+        let result = decoder.crack(
+            "0000 0 0100 0100 111 / 011 111 010 0100 100 101011",
+            &get_athena_checker(),
+        );
+        assert_eq!(result.unencrypted_text.unwrap()[0], "HELLO WORLD!");
+    }
+
+    #[test]
+    fn test_morse_code_needs_minimum_symbols() {
+        let decoder = Decoder::<MorseCodeDecoder>::new();
+        let result = decoder.crack(".. -", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+
+    #[test]
+    fn binary_is_not_binary_morse() {
+        assert!(is_binary_morse("0101 1 / 00"));
+        assert!(!is_binary_morse("0101 2"));
+        assert!(!is_binary_morse(" / "));
+    }
+
     #[test]
     fn test_morse_code_colon_tight() {
         let decoder = Decoder::<MorseCodeDecoder>::new();
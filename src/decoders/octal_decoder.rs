@@ -0,0 +1,169 @@
+//! Decode a octal byte string
+//! Performs error handling and returns a string
+//! Call octal_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::check_string_success;
+use crate::decoders::radix::{bytes_to_text, parse_bytes};
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::Decoder;
+
+use log::{debug, info, trace};
+
+/// The Octal decoder, call:
+/// `let octal_decoder = Decoder::<OctalDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = octal_decoder.crack(input)` to decode a octal byte string
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::octal_decoder::{OctalDecoder};
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_octal = Decoder::<OctalDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_octal.crack("150 145 154 154 157 040 167 157 162 154 144", &checker).unencrypted_text;
+/// assert!(result.is_some());
+/// assert_eq!(result.unwrap()[0], "hello world");
+/// ```
+pub struct OctalDecoder;
+
+impl Crack for Decoder<OctalDecoder> {
+    fn new() -> Decoder<OctalDecoder> {
+        Decoder {
+            name: "Octal",
+            description: "Octal is the base-8 number system. Text is often written as the octal value of each byte, such as 150 145 154 154 157 for hello, which is also how C and many shells escape bytes.",
+            link: "https://en.wikipedia.org/wiki/Octal",
+            tags: vec!["octal", "base", "decoder"],
+            popularity: 0.7,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying octal with text {:?}", text);
+        let mut results = CrackResult::new(self, text.to_string());
+
+        let decoded_text = match parse_bytes(text, 8, 3).and_then(bytes_to_text) {
+            Some(decoded_text) => decoded_text,
+            None => {
+                debug!("Failed to decode octal because the input isn't a octal byte string");
+                return results;
+            }
+        };
+
+        if !check_string_success(&decoded_text, text) {
+            info!(
+                "Failed to decode octal because check_string_success returned false on string {}",
+                decoded_text
+            );
+            return results;
+        }
+
+        let checker_result = checker.check(&decoded_text);
+        results.unencrypted_text = Some(vec![decoded_text]);
+
+        results.update_checker(&checker_result);
+
+        results
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some(("150 145 154 154 157 040 167 157 162 154 144", "hello world"))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Every number must only use the digits 0 to 7 and be at most 377",
+            "Bytes are separated by whitespace, `,`, `:`, `;` or `\\`, or written continuously with 3 digits each",
+            "Must decode to at least 3 bytes of valid UTF-8 without control characters",
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OctalDecoder;
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn octal_with_spaces_decodes() {
+        let decoder = Decoder::<OctalDecoder>::new();
+        let result = decoder.crack(
+            "150 145 154 154 157 040 167 157 162 154 144",
+            &get_athena_checker(),
+        );
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+    }
+
+    #[test]
+    fn octal_escapes_decode() {
+        let decoder = Decoder::<OctalDecoder>::new();
+        let result = decoder.crack(
+            "\\150\\145\\154\\154\\157\\040\\167\\157\\162\\154\\144",
+            &get_athena_checker(),
+        );
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+    }
+
+    #[test]
+    fn continuous_octal_decodes() {
+        let decoder = Decoder::<OctalDecoder>::new();
+        let result = decoder.crack("150145154154157040167157162154144", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+    }
+
+    #[test]
+    fn octal_rejects_eights_and_nines() {
+        let decoder = Decoder::<OctalDecoder>::new();
+        let result = decoder.crack("150 145 158 154 157", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+
+    #[test]
+    fn octal_rejects_empty_string() {
+        let decoder = Decoder::<OctalDecoder>::new();
+        let result = decoder.crack("", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+}
@@ -0,0 +1,283 @@
+//! Decode text typed on a phone keypad
+//! Old phones put three or four letters on each of the keys 2 to 9. Multi-tap presses a
+//! key once for its first letter, twice for its second and so on, so `hello` is
+//! `44 33 555 555 666`. T9 presses each key once and lets a dictionary work out the word,
+//! so `hello` is `43556`, and each run of digits is read as the most common word in the
+//! bundled English word list with those keys. 0 is the space in both.
+//! Call phone_keypad_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::check_string_success;
+use crate::storage::wordlists::ENGLISH_WORDS;
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::Decoder;
+
+use log::{debug, info, trace};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+
+/// The letters on each key, from key 2 to key 9
+const KEYS: [&str; 8] = ["abc", "def", "ghi", "jkl", "mno", "pqrs", "tuv", "wxyz"];
+
+/// The fewest key groups the text must have, as a lone number is rarely a word
+const MIN_GROUPS: usize = 2;
+
+/// The most common word for every sequence of keys, built the first time T9 is decoded
+static T9_WORDS: Lazy<HashMap<String, &'static str>> = Lazy::new(|| {
+    let mut words = HashMap::new();
+    for word in ENGLISH_WORDS.words() {
+        if let Some(keys) = word.chars().map(key_for).collect::<Option<String>>() {
+            // The list is most common first, so the first word for some keys is kept
+            words.entry(keys).or_insert(word);
+        }
+    }
+    words
+});
+
+/// The Phone keypad decoder, call:
+/// `let phone_keypad_decoder = Decoder::<PhoneKeypadDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = phone_keypad_decoder.crack(input)` to decode multi-tap or T9 key presses
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::phone_keypad_decoder::{PhoneKeypadDecoder};
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_phone_keypad = Decoder::<PhoneKeypadDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_phone_keypad.crack("44 33 555 555 666 0 9 666 777 555 3", &checker);
+/// assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+/// let result = decode_phone_keypad.crack("43556 96753", &checker);
+/// assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+/// ```
+pub struct PhoneKeypadDecoder;
+
+impl Crack for Decoder<PhoneKeypadDecoder> {
+    fn new() -> Decoder<PhoneKeypadDecoder> {
+        Decoder {
+            name: "Phone keypad",
+            description: "Phone keypads put three or four letters on each digit key. Multi-tap spells a letter by pressing its key once for each place it has on the key, and T9 presses each key once and picks the word from a dictionary.",
+            link: "https://en.wikipedia.org/wiki/T9_(predictive_text)",
+            tags: vec!["phone", "keypad", "decoder", "substitution"],
+            popularity: 0.4,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying phone keypad with text {:?}", text);
+        let mut results = CrackResult::new(self, text.to_string());
+
+        if !text
+            .chars()
+            .all(|c| c.is_ascii_digit() || c.is_ascii_whitespace())
+        {
+            debug!("Failed to decode phone keypad because the text isn't all digits");
+            return results;
+        }
+
+        let mut candidates = Vec::new();
+        for decoded_text in [decode_multi_tap(text), decode_t9(text)]
+            .into_iter()
+            .flatten()
+        {
+            if !check_string_success(&decoded_text, text) {
+                info!(
+                    "Failed to decode phone keypad because check_string_success returned false on string {}",
+                    decoded_text
+                );
+                continue;
+            }
+            let checker_result = checker.check(&decoded_text);
+            if checker_result.is_identified && !results.success {
+                // The match goes first, and the other reading is still returned
+                candidates.insert(0, decoded_text);
+                results.update_checker(&checker_result);
+            } else {
+                candidates.push(decoded_text);
+            }
+        }
+        if candidates.is_empty() {
+            debug!("Failed to decode phone keypad because the text isn't multi-tap or T9");
+            return results;
+        }
+        results.unencrypted_text = Some(candidates);
+
+        results
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some(("44 33 555 555 666 0 9 666 777 555 3", "hello world"))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input must be only digits and whitespace, with at least two groups of digits",
+            "Multi-tap groups must repeat one key no more times than it has letters",
+            "Every T9 group must spell a word in the bundled English word list",
+        ]
+    }
+}
+
+/// The key a letter is on, None for anything which isn't a lowercase ASCII letter
+fn key_for(letter: char) -> Option<char> {
+    let key = KEYS.iter().position(|letters| letters.contains(letter))?;
+    char::from_digit(key as u32 + 2, 10)
+}
+
+/// Reads groups of presses of one key, such as `44 33 555 555 666`, None if it isn't multi-tap
+fn decode_multi_tap(text: &str) -> Option<String> {
+    let groups: Vec<&str> = text.split_whitespace().collect();
+    if groups.len() < MIN_GROUPS {
+        return None;
+    }
+    groups
+        .iter()
+        .map(|group| {
+            let key = group.chars().next()?;
+            if !group.chars().all(|c| c == key) {
+                return None;
+            }
+            if key == '0' {
+                return (group.len() == 1).then_some(' ');
+            }
+            let letters = KEYS.get(key.to_digit(10)?.checked_sub(2)? as usize)?;
+            letters.chars().nth(group.len() - 1)
+        })
+        .collect()
+}
+
+/// Reads each run of keys as the most common word typed with them, None if it isn't T9
+fn decode_t9(text: &str) -> Option<String> {
+    let groups: Vec<&str> = text
+        .split(|c: char| c == '0' || c.is_ascii_whitespace())
+        .filter(|group| !group.is_empty())
+        .collect();
+    if groups.len() < MIN_GROUPS {
+        return None;
+    }
+    let words = groups
+        .iter()
+        .map(|group| T9_WORDS.get(*group).copied())
+        .collect::<Option<Vec<&str>>>()?;
+    Some(words.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_multi_tap, decode_t9, PhoneKeypadDecoder};
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn successful_decoding() {
+        let phone_keypad_decoder = Decoder::<PhoneKeypadDecoder>::new();
+        let result = phone_keypad_decoder
+            .crack("44 33 555 555 666 0 9 666 777 555 3", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+    }
+
+    #[test]
+    fn t9_decoding() {
+        let phone_keypad_decoder = Decoder::<PhoneKeypadDecoder>::new();
+        let result = phone_keypad_decoder.crack("8447 47 2 732738 6377243", &get_athena_checker());
+        assert_eq!(
+            result.unencrypted_text.unwrap()[0],
+            "this is a secret message"
+        );
+    }
+
+    #[test]
+    fn t9_picks_the_most_common_word() {
+        // 4663 is good, home, gone and hood among others
+        assert_eq!(decode_t9("4663 4663").unwrap(), "good good");
+    }
+
+    #[test]
+    fn t9_words_can_be_split_by_zero() {
+        assert_eq!(decode_t9("8430352404704373").unwrap(), "the flag is here");
+    }
+
+    #[test]
+    fn t9_needs_every_group_to_be_a_word() {
+        assert!(decode_t9("43556 99999").is_none());
+    }
+
+    #[test]
+    fn multi_tap_rejects_too_many_presses() {
+        // 2 only has three letters, while 7 and 9 have four
+        assert!(decode_multi_tap("2222 33").is_none());
+        assert_eq!(decode_multi_tap("7777 9999").unwrap(), "sz");
+    }
+
+    #[test]
+    fn multi_tap_rejects_mixed_keys() {
+        assert!(decode_multi_tap("43 33").is_none());
+    }
+
+    #[test]
+    fn single_numbers_are_rejected() {
+        let phone_keypad_decoder = Decoder::<PhoneKeypadDecoder>::new();
+        let result = phone_keypad_decoder.crack("4663", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+
+    #[test]
+    fn phone_keypad_handle_panic_if_empty_string() {
+        let phone_keypad_decoder = Decoder::<PhoneKeypadDecoder>::new();
+        let result = phone_keypad_decoder
+            .crack("", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn phone_keypad_handle_panic_if_emoji() {
+        let phone_keypad_decoder = Decoder::<PhoneKeypadDecoder>::new();
+        let result = phone_keypad_decoder
+            .crack("😂", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+}
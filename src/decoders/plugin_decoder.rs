@@ -0,0 +1,373 @@
+//! Decoders written as WebAssembly modules, so they can be written in any language and
+//! added without recompiling ciphey
+//!
+//! Every `.wasm` file in the plugins directory, `~/.ciphey/plugins` unless `plugins_dir` is
+//! set in the config, is loaded when cracking starts and searched like any other decoder.
+//! The decoder is named after the file, so `rot8000.wasm` is the `rot8000` decoder.
+//!
+//! A plugin exports its memory and two functions:
+//!
+//! - `alloc(len: i32) -> i32` gives the offset of `len` free bytes, where the input is written
+//! - `decode(ptr: i32, len: i32) -> i64` decodes the `len` bytes at `ptr`, and returns the
+//!   offset of the decoded bytes in the high 32 bits and their length in the low 32 bits,
+//!   or a negative number if the input isn't encoded the plugin's way
+//!
+//! Plugins are sandboxed. They can import nothing, so have no way to reach files, the
+//! network or anything else outside their own memory, which is capped at
+//! [`MAX_PLUGIN_MEMORY`]. Every decode runs in a fresh instance with [`PLUGIN_FUEL`] to
+//! spend, roughly one unit per instruction, and is stopped when it runs out.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::crack_results::CrackResult;
+use crate::decoders::custom::register_decoder;
+use crate::decoders::interface::{check_string_success, Crack, Decoder};
+use crate::decoders::radix::bytes_to_text;
+
+use log::{debug, trace};
+use once_cell::sync::Lazy;
+use std::path::{Path, PathBuf};
+use wasmi::{Engine, Linker, Module, Store, StoreLimits, StoreLimitsBuilder};
+
+/// How much fuel a plugin has for each decode, roughly one unit per instruction
+pub const PLUGIN_FUEL: u64 = 10_000_000;
+
+/// The most memory a plugin can have, in bytes
+pub const MAX_PLUGIN_MEMORY: usize = 16 * 1024 * 1024;
+
+/// The engine plugins are compiled and run by, which meters their fuel
+static ENGINE: Lazy<Engine> = Lazy::new(|| {
+    let mut config = wasmi::Config::default();
+    config.consume_fuel(true);
+    Engine::new(&config)
+});
+
+/// A decoder loaded from a WebAssembly module
+pub struct PluginDecoder {
+    /// The plugin's name, description and tags
+    decoder: Decoder<PluginDecoder>,
+    /// The compiled module, None for a plugin with nothing loaded, which decodes nothing
+    module: Option<Module>,
+}
+
+impl PluginDecoder {
+    /// Compiles the WebAssembly module at the path into a decoder named after the file
+    ///
+    /// # Errors
+    ///
+    /// Returns why the plugin can't be used if the file can't be read or compiled, imports
+    /// anything, or doesn't export the plugin functions.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let bytes = std::fs::read(path).map_err(|e| e.to_string())?;
+        let module = Module::new(&ENGINE, bytes).map_err(|e| e.to_string())?;
+        if let Some(import) = module.imports().next() {
+            return Err(format!(
+                "plugins can't import anything, but it imports {}::{}",
+                import.module(),
+                import.name()
+            ));
+        }
+        for export in ["memory", "alloc", "decode"] {
+            if module.get_export(export).is_none() {
+                return Err(format!("it doesn't export {export}"));
+            }
+        }
+        let name = path.file_stem().map_or_else(
+            || "plugin".to_string(),
+            |stem| stem.to_string_lossy().to_string(),
+        );
+        // Plugins are loaded once, so their names are leaked to give them the lifetime
+        // results need
+        let description = format!("A WebAssembly plugin loaded from {}", path.display());
+        Ok(PluginDecoder {
+            decoder: Decoder {
+                name: Box::leak(name.into_boxed_str()),
+                description: Box::leak(description.into_boxed_str()),
+                link: "",
+                tags: vec!["plugin", "decoder"],
+                popularity: 0.5,
+                phantom: std::marker::PhantomData,
+            },
+            module: Some(module),
+        })
+    }
+
+    /// Runs the plugin on the bytes, giving what it decoded them to
+    /// None if the plugin says they aren't encoded its way.
+    fn decode(&self, input: &[u8]) -> Result<Option<Vec<u8>>, wasmi::Error> {
+        let Some(module) = &self.module else {
+            return Ok(None);
+        };
+        let limits = StoreLimitsBuilder::new()
+            .memory_size(MAX_PLUGIN_MEMORY)
+            .instances(1)
+            .build();
+        let mut store = Store::new(&ENGINE, limits);
+        store.limiter(|limits: &mut StoreLimits| limits);
+        store.set_fuel(PLUGIN_FUEL)?;
+        let instance = Linker::new(&ENGINE).instantiate_and_start(&mut store, module)?;
+        let memory = instance
+            .get_memory(&store, "memory")
+            .ok_or_else(|| wasmi::Error::new("the plugin doesn't export a memory"))?;
+        let alloc = instance.get_typed_func::<i32, i32>(&store, "alloc")?;
+        let decode = instance.get_typed_func::<(i32, i32), i64>(&store, "decode")?;
+
+        let len = i32::try_from(input.len())
+            .map_err(|_| wasmi::Error::new("the input is too long for the plugin"))?;
+        let ptr = alloc.call(&mut store, len)?;
+        memory.write(&mut store, ptr as u32 as usize, input)?;
+        let packed = decode.call(&mut store, (ptr, len))?;
+        if packed < 0 {
+            return Ok(None);
+        }
+        let start = (packed >> 32) as u32 as usize;
+        let end = start + (packed as u32 as usize);
+        memory
+            .data(&store)
+            .get(start..end)
+            .map(|output| Some(output.to_vec()))
+            .ok_or_else(|| wasmi::Error::new("the plugin's output is outside its memory"))
+    }
+
+    /// Decodes the bytes of the input, and checks what they decode to
+    fn crack_input(&self, text: &str, input: &[u8], checker: &CheckerTypes) -> CrackResult {
+        let mut results = CrackResult::new(&self.decoder, text.to_string());
+        if input.is_empty() {
+            return results;
+        }
+        if self.remaining_time().is_some_and(|time| time.is_zero()) {
+            results.timed_out = true;
+            return results;
+        }
+        let decoded = match self.decode(input) {
+            Ok(Some(decoded)) => decoded,
+            Ok(None) => {
+                trace!("The {} plugin didn't decode the input", self.decoder.name);
+                return results;
+            }
+            Err(e) => {
+                debug!("The {} plugin failed: {}", self.decoder.name, e);
+                return results;
+            }
+        };
+        if decoded == input {
+            return results;
+        }
+        let decoded_text = match String::from_utf8(decoded) {
+            Ok(decoded_text) => decoded_text,
+            Err(e) => {
+                // Bytes which aren't text are passed on as they are, for decoders such as gzip
+                let bytes = e.into_bytes();
+                let checker_result = checker.check_bytes(&bytes);
+                results.unencrypted_bytes = Some(vec![bytes]);
+                results.update_checker(&checker_result);
+                return results;
+            }
+        };
+        let Some(decoded_text) = bytes_to_text(decoded_text.into_bytes()) else {
+            debug!(
+                "The {} plugin decoded to control characters",
+                self.decoder.name
+            );
+            return results;
+        };
+        if !check_string_success(&decoded_text, text) {
+            return results;
+        }
+        let checker_result = checker.check(&decoded_text);
+        results.unencrypted_text = Some(vec![decoded_text]);
+        results.update_checker(&checker_result);
+        results
+    }
+}
+
+impl Crack for PluginDecoder {
+    fn new() -> PluginDecoder {
+        PluginDecoder {
+            decoder: Decoder {
+                name: "plugin",
+                description: "A WebAssembly plugin with nothing loaded",
+                link: "",
+                tags: vec!["plugin"],
+                popularity: 0.0,
+                phantom: std::marker::PhantomData,
+            },
+            module: None,
+        }
+    }
+
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!(
+            "Trying the {} plugin with text {:?}",
+            self.decoder.name,
+            text
+        );
+        self.crack_input(text, text.as_bytes(), checker)
+    }
+    fn crack_bytes(&self, bytes: &[u8], checker: &CheckerTypes) -> Option<CrackResult> {
+        trace!(
+            "Trying the {} plugin with {} bytes",
+            self.decoder.name,
+            bytes.len()
+        );
+        Some(self.crack_input(&String::from_utf8_lossy(bytes), bytes, checker))
+    }
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.decoder.tags
+    }
+    fn get_name(&self) -> &str {
+        self.decoder.name
+    }
+    fn get_description(&self) -> &str {
+        self.decoder.description
+    }
+    fn get_link(&self) -> &str {
+        self.decoder.link
+    }
+    fn get_popularity(&self) -> f32 {
+        self.decoder.popularity
+    }
+}
+
+/// The directory plugins are loaded from when the config doesn't name one
+pub fn default_plugins_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".ciphey").join("plugins"))
+}
+
+/// Loads every `.wasm` file in the directory, in name order
+/// Each file gives the plugin, or why it couldn't be loaded. A missing directory has none.
+pub fn load_plugins(directory: &Path) -> Vec<(PathBuf, Result<PluginDecoder, String>)> {
+    let Ok(entries) = std::fs::read_dir(directory) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "wasm")
+        })
+        .collect();
+    paths.sort();
+    paths
+        .into_iter()
+        .map(|path| {
+            let plugin = PluginDecoder::load(&path);
+            (path, plugin)
+        })
+        .collect()
+}
+
+/// Registers every plugin in the directory which loads, warning about the rest
+/// Gives how many were registered.
+pub fn register_plugins(directory: &Path) -> usize {
+    let mut registered = 0;
+    for (path, plugin) in load_plugins(directory) {
+        match plugin {
+            Ok(plugin) => {
+                trace!("Loaded the {} plugin", plugin.get_name());
+                register_decoder(Box::new(plugin));
+                registered += 1;
+            }
+            Err(reason) => crate::cli_pretty_printing::warning_plugin_not_loaded(&path, &reason),
+        }
+    }
+    registered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checkers::{
+        athena::Athena,
+        checker_type::{Check, Checker},
+    };
+
+    /// A plugin which rotates letters by 13, like ROT13
+    const ROT13: &str = r#"
+        (module
+          (memory (export "memory") 1)
+          (func (export "alloc") (param i32) (result i32) (i32.const 1024))
+          (func (export "decode") (param $ptr i32) (param $len i32) (result i64)
+            (local $i i32) (local $c i32) (local $base i32)
+            (block $done
+              (loop $next
+                (br_if $done (i32.ge_u (local.get $i) (local.get $len)))
+                (local.set $c (i32.load8_u (i32.add (local.get $ptr) (local.get $i))))
+                (local.set $base (i32.const 0))
+                (if (i32.and (i32.ge_u (local.get $c) (i32.const 97)) (i32.le_u (local.get $c) (i32.const 122)))
+                  (then (local.set $base (i32.const 97))))
+                (if (i32.and (i32.ge_u (local.get $c) (i32.const 65)) (i32.le_u (local.get $c) (i32.const 90)))
+                  (then (local.set $base (i32.const 65))))
+                (if (local.get $base)
+                  (then (local.set $c (i32.add (local.get $base)
+                    (i32.rem_u (i32.add (i32.sub (local.get $c) (local.get $base)) (i32.const 13)) (i32.const 26))))))
+                (i32.store8 (i32.add (i32.const 8192) (local.get $i)) (local.get $c))
+                (local.set $i (i32.add (local.get $i) (i32.const 1)))
+                (br $next)))
+            (i64.or (i64.shl (i64.const 8192) (i64.const 32)) (i64.extend_i32_u (local.get $len)))))
+    "#;
+
+    /// A plugin which never stops decoding
+    const ENDLESS: &str = r#"
+        (module
+          (memory (export "memory") 1)
+          (func (export "alloc") (param i32) (result i32) (i32.const 0))
+          (func (export "decode") (param i32 i32) (result i64) (loop $forever (br $forever)) (i64.const -1)))
+    "#;
+
+    fn plugin(directory: &Path, name: &str, source: &str) -> PathBuf {
+        let path = directory.join(format!("{name}.wasm"));
+        std::fs::write(&path, source).unwrap();
+        path
+    }
+
+    fn plugins_dir(test: &str) -> PathBuf {
+        let directory = std::env::temp_dir().join(format!("ciphey-plugins-{test}"));
+        let _ = std::fs::remove_dir_all(&directory);
+        std::fs::create_dir_all(&directory).unwrap();
+        directory
+    }
+
+    fn get_athena_checker() -> CheckerTypes {
+        CheckerTypes::CheckAthena(Checker::<Athena>::new())
+    }
+
+    #[test]
+    fn plugins_decode_and_are_named_after_their_file() {
+        let directory = plugins_dir("decode");
+        let decoder = PluginDecoder::load(&plugin(&directory, "rot13", ROT13)).unwrap();
+        assert_eq!(decoder.get_name(), "rot13");
+        let result = decoder.crack("uryyb jbeyq", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+    }
+
+    #[test]
+    fn plugins_are_stopped_when_their_fuel_runs_out() {
+        let directory = plugins_dir("fuel");
+        let decoder = PluginDecoder::load(&plugin(&directory, "endless", ENDLESS)).unwrap();
+        let result = decoder.crack("anything", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+
+    #[test]
+    fn plugins_which_import_or_lack_exports_are_not_loaded() {
+        let directory = plugins_dir("invalid");
+        plugin(
+            &directory,
+            "imports",
+            r#"(module (import "env" "read_file" (func)) (memory (export "memory") 1))"#,
+        );
+        plugin(&directory, "empty", "(module)");
+        plugin(&directory, "rot13", ROT13);
+        std::fs::write(directory.join("notes.txt"), "not a plugin").unwrap();
+
+        let loaded = load_plugins(&directory);
+        let names: Vec<String> = loaded
+            .iter()
+            .map(|(path, _)| path.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(names, ["empty.wasm", "imports.wasm", "rot13.wasm"]);
+        assert!(loaded[0].1.as_ref().is_err_and(|e| e.contains("export")));
+        assert!(loaded[1].1.as_ref().is_err_and(|e| e.contains("import")));
+        assert!(loaded[2].1.is_ok());
+    }
+}
@@ -0,0 +1,227 @@
+//! Parses text written as a sequence of numbers, one per byte, in any radix
+//! Shared by the octal and decimal decoders. Bytes can be separated by spaces,
+//! commas, colons, semicolons or backslashes (as in `\150\145`), or written
+//! continuously with every byte padded to the same number of digits.
+//! It also reads text as one big number, as Base62 and Base36 write their bytes.
+
+use num::{BigUint, ToPrimitive, Zero};
+
+/// Characters which can separate the numbers of a byte string
+const SEPARATORS: [char; 4] = [',', ':', ';', '\\'];
+
+/// The fewest bytes we will decode, anything shorter is too likely to be a coincidence
+pub const MIN_BYTES: usize = 3;
+
+/// Parses a byte string in the given radix
+///
+/// `width` is how many digits each byte takes when the bytes are written without separators,
+/// such as 3 for octal `150145154`. Returns None if any number isn't a valid byte,
+/// or if there are fewer than [`MIN_BYTES`] bytes.
+pub fn parse_bytes(text: &str, radix: u32, width: usize) -> Option<Vec<u8>> {
+    let tokens: Vec<&str> = text
+        .split(|c: char| c.is_whitespace() || SEPARATORS.contains(&c))
+        .filter(|token| !token.is_empty())
+        .map(|token| strip_prefix(token, radix))
+        .collect();
+
+    let bytes = match tokens.as_slice() {
+        // One long token is the continuous form, so split it into fixed width bytes
+        [token] if token.len() > width => {
+            if !token.len().is_multiple_of(width) || !token.is_ascii() {
+                return None;
+            }
+            token
+                .as_bytes()
+                .chunks(width)
+                .map(|chunk| parse_byte(std::str::from_utf8(chunk).ok()?, radix))
+                .collect::<Option<Vec<u8>>>()?
+        }
+        tokens => tokens
+            .iter()
+            .map(|token| parse_byte(token, radix))
+            .collect::<Option<Vec<u8>>>()?,
+    };
+
+    if bytes.len() < MIN_BYTES {
+        return None;
+    }
+    Some(bytes)
+}
+
+/// Parses a single number as a byte, rejecting anything above 255
+fn parse_byte(token: &str, radix: u32) -> Option<u8> {
+    if token.is_empty() || !token.chars().all(|c| c.is_digit(radix)) {
+        return None;
+    }
+    u8::from_str_radix(token, radix).ok()
+}
+
+/// Removes the prefix programming languages write before numbers in this radix
+fn strip_prefix(token: &str, radix: u32) -> &str {
+    let prefix = match radix {
+        2 => "0b",
+        8 => "0o",
+        16 => "0x",
+        _ => return token,
+    };
+    token
+        .strip_prefix(prefix)
+        .or_else(|| token.strip_prefix(&prefix.to_uppercase()))
+        .unwrap_or(token)
+}
+
+/// Turns the bytes into text, `None` if they aren't valid UTF-8
+/// or contain control characters other than whitespace
+pub fn bytes_to_text(bytes: Vec<u8>) -> Option<String> {
+    let text = String::from_utf8(bytes).ok()?;
+    if text.chars().any(|c| c.is_control() && !c.is_whitespace()) {
+        return None;
+    }
+    Some(text)
+}
+
+/// Decodes an even number of hex digits into bytes, None if the text isn't that
+/// Unlike [`parse_bytes`] there are no separators or prefixes, just the digits.
+pub fn decode_hex(text: &str) -> Option<Vec<u8>> {
+    if text.is_empty()
+        || !text.len().is_multiple_of(2)
+        || !text.bytes().all(|b| b.is_ascii_hexdigit())
+    {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&text[i..i + 2], 16).ok())
+        .collect()
+}
+
+/// Takes each character as the byte with its value, None if one is above U+00FF
+/// This is how bytes which aren't UTF-8 end up written in text, one character per byte.
+pub fn chars_as_bytes(text: &str) -> Option<Vec<u8>> {
+    text.chars()
+        .map(|c| u8::try_from(u32::from(c)).ok())
+        .collect()
+}
+
+/// Reads the whole text as one number written with the digits of `alphabet`,
+/// most significant digit first. Returns None if a character isn't in the alphabet.
+pub fn parse_big_number(text: &str, alphabet: &[u8]) -> Option<BigUint> {
+    let radix = BigUint::from(alphabet.len());
+    text.bytes().try_fold(BigUint::zero(), |number, c| {
+        let digit = alphabet.iter().position(|&d| d == c)?;
+        Some(number * &radix + digit)
+    })
+}
+
+/// The bytes of the number the text writes with the digits of `alphabet`
+/// Like Base58, every leading zero digit stands for a leading zero byte,
+/// which the number itself can't hold.
+pub fn big_number_bytes(text: &str, alphabet: &[u8]) -> Option<Vec<u8>> {
+    let number = parse_big_number(text, alphabet)?;
+    let zeros = text.bytes().take_while(|&c| c == alphabet[0]).count();
+    let mut bytes = vec![0; zeros];
+    if !number.is_zero() {
+        bytes.extend(number.to_bytes_be());
+    }
+    Some(bytes)
+}
+
+/// The number in decimal if it fits in 64 bits, the size of the IDs that
+/// URL shorteners and databases write in Base62 and Base36
+pub fn small_number(number: &BigUint) -> Option<String> {
+    number.to_u64().map(|number| number.to_string())
+}
+
+/// The ways to read the text as one big number: its bytes as text, then the number
+/// itself in decimal. The number is only given for text mixing digits and letters,
+/// as IDs do, so that ordinary words aren't turned into numbers.
+pub fn big_number_readings(text: &str, alphabet: &[u8]) -> Vec<String> {
+    let mut readings = Vec::new();
+    let Some(number) = parse_big_number(text, alphabet) else {
+        return readings;
+    };
+    if let Some(decoded) = big_number_bytes(text, alphabet).and_then(bytes_to_text) {
+        readings.push(decoded);
+    }
+    let has_digit = text.bytes().any(|c| c.is_ascii_digit());
+    let has_letter = text.bytes().any(|c| c.is_ascii_alphabetic());
+    if has_digit && has_letter {
+        readings.extend(small_number(&number));
+    }
+    readings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn separated_numbers_are_parsed() {
+        assert_eq!(parse_bytes("104 105 33", 10, 3), Some(b"hi!".to_vec()));
+        assert_eq!(parse_bytes("104,105,33", 10, 3), Some(b"hi!".to_vec()));
+        assert_eq!(parse_bytes("\\150\\151\\41", 8, 3), Some(b"hi!".to_vec()));
+        assert_eq!(parse_bytes("0o150 0o151 0o41", 8, 3), Some(b"hi!".to_vec()));
+    }
+
+    #[test]
+    fn continuous_numbers_are_split_by_width() {
+        assert_eq!(parse_bytes("104105033", 10, 3), Some(b"hi!".to_vec()));
+        assert_eq!(parse_bytes("10410533", 10, 3), None);
+    }
+
+    #[test]
+    fn numbers_above_a_byte_are_rejected() {
+        assert_eq!(parse_bytes("104 256 33", 10, 3), None);
+        assert_eq!(parse_bytes("150 400 41", 8, 3), None);
+    }
+
+    #[test]
+    fn digits_outside_the_radix_are_rejected() {
+        assert_eq!(parse_bytes("150 158 41", 8, 3), None);
+    }
+
+    #[test]
+    fn too_few_bytes_are_rejected() {
+        assert_eq!(parse_bytes("104 105", 10, 3), None);
+    }
+
+    #[test]
+    fn big_numbers_keep_leading_zero_bytes() {
+        let alphabet = b"0123456789";
+        assert_eq!(big_number_bytes("00258", alphabet), Some(vec![0, 0, 1, 2]));
+        assert_eq!(big_number_bytes("0", alphabet), Some(vec![0]));
+        assert_eq!(big_number_bytes("25a", alphabet), None);
+    }
+
+    #[test]
+    fn only_numbers_fitting_64_bits_are_small() {
+        let alphabet = b"0123456789";
+        let number = parse_big_number("18446744073709551615", alphabet).unwrap();
+        assert_eq!(
+            small_number(&number),
+            Some("18446744073709551615".to_string())
+        );
+        let number = parse_big_number("18446744073709551616", alphabet).unwrap();
+        assert_eq!(small_number(&number), None);
+    }
+
+    #[test]
+    fn control_characters_are_not_text() {
+        assert_eq!(bytes_to_text(vec![104, 105]), Some("hi".to_string()));
+        assert_eq!(bytes_to_text(vec![104, 1]), None);
+    }
+
+    #[test]
+    fn only_plain_hex_digits_are_decoded() {
+        assert_eq!(decode_hex("1f8B"), Some(vec![0x1f, 0x8b]));
+        assert_eq!(decode_hex("1f8"), None);
+        assert_eq!(decode_hex("+f"), None);
+        assert_eq!(decode_hex(""), None);
+    }
+
+    #[test]
+    fn characters_below_u0100_are_bytes() {
+        assert_eq!(chars_as_bytes("\u{1f}\u{8b}"), Some(vec![0x1f, 0x8b]));
+        assert_eq!(chars_as_bytes("\u{100}"), None);
+    }
+}
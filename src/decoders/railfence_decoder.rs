@@ -6,6 +6,7 @@
 
 use crate::checkers::CheckerTypes;
 use crate::decoders::interface::check_string_success;
+use crate::storage::ngrams::quadgram_score;
 use gibberish_or_not::Sensitivity;
 
 use super::crack_results::CrackResult;
@@ -14,6 +15,12 @@ use super::interface::Decoder;
 
 use log::{info, trace};
 
+/// The fewest rails tried
+const MIN_RAILS: usize = 2;
+
+/// The most rails tried
+const MAX_RAILS: usize = 10;
+
 /// Railfence Decoder
 pub struct RailfenceDecoder;
 
@@ -35,38 +42,47 @@ impl Crack for Decoder<RailfenceDecoder> {
     fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
         trace!("Trying railfence with text {:?}", text);
         let mut results = CrackResult::new(self, text.to_string());
-        let mut decoded_strings = Vec::new();
 
         // Use the checker with Low sensitivity for Railfence cipher
         let checker_with_sensitivity = checker.with_sensitivity(Sensitivity::Low);
 
-        for rails in 2..10 {
+        let mut candidates: Vec<(usize, usize, String, f64)> = (MIN_RAILS..=MAX_RAILS)
             // Should be less than (rail * 2 - 3). This is the max offset
-            for offset in 0..=(rails * 2 - 3) {
+            .flat_map(|rails| (0..=(rails * 2 - 3)).map(move |offset| (rails, offset)))
+            .map(|(rails, offset)| {
                 let decoded_text = railfence_decoder(text, rails, offset);
-                decoded_strings.push(decoded_text);
-                let borrowed_decoded_text = &decoded_strings[decoded_strings.len() - 1];
-                if !check_string_success(borrowed_decoded_text, text) {
-                    info!(
-                    "Failed to decode railfence because check_string_success returned false on string {}. This means the string is 'funny' as it wasn't modified.",
-                    borrowed_decoded_text
+                let score = quadgram_score(&decoded_text);
+                (rails, offset, decoded_text, score)
+            })
+            .collect();
+        if let Some((_, _, unchanged, _)) = candidates
+            .iter()
+            .find(|(_, _, decoded_text, _)| !check_string_success(decoded_text, text))
+        {
+            info!(
+                "Failed to decode railfence because check_string_success returned false on string {}. This means the string is 'funny' as it wasn't modified.",
+                unchanged
+            );
+            return results;
+        }
+
+        // Check the most English-like first, so the right one is usually found straight away
+        candidates.sort_by(|a, b| b.3.total_cmp(&a.3));
+        for (rails, offset, decoded_text, _) in &candidates {
+            let checker_result = checker_with_sensitivity.check(decoded_text);
+            if checker_result.is_identified {
+                trace!(
+                    "Found a match with railfence {} rails and {} offset",
+                    rails,
+                    offset
                 );
-                    return results;
-                }
-                let checker_result = checker_with_sensitivity.check(borrowed_decoded_text);
-                if checker_result.is_identified {
-                    trace!(
-                        "Found a match with railfence {} rails and {} offset",
-                        rails,
-                        offset
-                    );
-                    results.unencrypted_text = Some(vec![borrowed_decoded_text.to_string()]);
-                    results.update_checker(&checker_result);
-                    return results;
-                }
+                results.unencrypted_text = Some(vec![decoded_text.to_string()]);
+                results.key = Some(format!("{} rails, offset {}", rails, offset));
+                results.update_checker(&checker_result);
+                return results;
             }
         }
-        results.unencrypted_text = Some(decoded_strings);
+        results.unencrypted_text = Some(candidates.into_iter().map(|c| c.2).collect());
         results
     }
     /// Gets all tags for this decoder
@@ -85,6 +101,21 @@ impl Crack for Decoder<RailfenceDecoder> {
     fn get_link(&self) -> &str {
         self.link
     }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some(("WECRLTEERDSOEEFEAOCAIVDEN", "WEAREDISCOVEREDFLEEATONCE"))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Tries 2 to 10 rails with every possible offset, most English-like first",
+            "Decoded text must differ from the input",
+        ]
+    }
 }
 
 /// Decodes a text encoded with the Rail Fence Cipher with the specified number of rails and offset
@@ -170,6 +201,28 @@ mod tests {
         assert_eq!(result.unencrypted_text.unwrap()[0], expected);
     }
 
+    /// Encrypts the text by reading the zigzag off one rail at a time
+    fn encode(text: &str, rails: usize) -> String {
+        let cycle = rails * 2 - 2;
+        let mut chars: Vec<(usize, char)> = text
+            .chars()
+            .enumerate()
+            .map(|(i, c)| (rails - 1 - (rails - 1).abs_diff(i % cycle), c))
+            .collect();
+        chars.sort_by_key(|&(rail, _)| rail);
+        chars.into_iter().map(|(_, c)| c).collect()
+    }
+
+    #[test]
+    fn railfence_decodes_ten_rails() {
+        let railfence_decoder = Decoder::<RailfenceDecoder>::new();
+        let plaintext = "the quick brown fox jumps over the lazy dog and runs away";
+        let result = railfence_decoder.crack(&encode(plaintext, 10), &get_athena_checker());
+        assert!(result.success);
+        assert_eq!(result.unencrypted_text.unwrap()[0], plaintext);
+        assert_eq!(result.key.as_deref(), Some("10 rails, offset 0"));
+    }
+
     #[test]
     fn railfence_handles_panic_if_empty_string() {
         // This tests if Railfence can handle an empty string
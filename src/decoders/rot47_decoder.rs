@@ -55,7 +55,11 @@ impl Crack for Decoder<ROT47Decoder> {
             // If checkers return true, exit early with the correct result
             if checker_result.is_identified {
                 trace!("Found a match with rot47 shift {}", shift);
-                results.unencrypted_text = Some(vec![borrowed_decoded_text.to_string()]);
+                // Return every shift so other checkers can still weigh in, with the match first
+                decoded_strings.extend(((shift + 1)..94).map(|rest| rot47_to_alphabet(text, rest)));
+                decoded_strings.rotate_left(shift as usize - 1);
+                results.unencrypted_text = Some(decoded_strings);
+                results.key = Some(shift.to_string());
                 results.update_checker(&checker_result);
                 return results;
             }
@@ -79,6 +83,21 @@ impl Crack for Decoder<ROT47Decoder> {
     fn get_link(&self) -> &str {
         self.link
     }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some(("w6==@[ (@C=5P", "Hello, World!"))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Tries every shift from 1 to 93",
+            "Decoded text must differ from the input",
+        ]
+    }
 }
 
 /// Maps rot47 to the alphabet (up to ROT94 with the ROT47 alphabet)
@@ -151,6 +170,16 @@ mod tests {
         assert_eq!(result.unencrypted_text.unwrap()[0], expected);
     }
 
+    #[test]
+    fn successful_decoding_returns_every_shift() {
+        let rot47_decoder = Decoder::<ROT47Decoder>::new();
+        let result = rot47_decoder.crack("w6==@[ (@C=5P", &get_athena_checker());
+        let decoded = result.unencrypted_text.unwrap();
+        assert_eq!(decoded.len(), 93);
+        assert_eq!(decoded[0], "Hello, World!");
+        assert!(result.key.is_some());
+    }
+
     #[test]
     fn rot47_handles_panic_if_empty_string() {
         // This tests if ROT47 can handle an empty string
@@ -0,0 +1,217 @@
+//! Decode the S/KEY six word format
+//! RFC 2289 one-time passwords are 64 bits long and are written as six short English words
+//! so they can be typed from a printed list. Each word is one of 2048 in a fixed
+//! dictionary and stands for 11 bits, which gives 66 bits: the 64 of the password and a
+//! two bit checksum. Longer values are written as several groups of six words. Bytes which
+//! aren't text are returned as hex, which is how the passwords are usually shown.
+//! Call skey_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::check_string_success;
+use crate::decoders::radix::bytes_to_text;
+use crate::storage::wordlists::SKEY_WORDS;
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::Decoder;
+
+use log::{debug, info, trace};
+
+/// How many words write each 64 bit value
+const WORDS_PER_VALUE: usize = 6;
+
+/// The S/KEY decoder, call:
+/// `let skey_decoder = Decoder::<SkeyDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = skey_decoder.crack(input)` to decode S/KEY words
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::skey_decoder::{SkeyDecoder};
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_skey = Decoder::<SkeyDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// // An example from RFC 2289
+/// let result = decode_skey.crack("INCH SEA ANNE LONG AHEM TOUR", &checker).unencrypted_text;
+/// assert!(result.is_some());
+/// assert_eq!(result.unwrap()[0], "9e876134d90499dd");
+/// ```
+pub struct SkeyDecoder;
+
+impl Crack for Decoder<SkeyDecoder> {
+    fn new() -> Decoder<SkeyDecoder> {
+        Decoder {
+            name: "S/KEY",
+            description: "The RFC 2289 six word format writes 64 bit one-time passwords as six short English words from a dictionary of 2048, with a two bit checksum, so they are easy to read and type.",
+            link: "https://datatracker.ietf.org/doc/html/rfc2289",
+            tags: vec!["skey", "otp", "decoder"],
+            popularity: 0.2,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying S/KEY with text {:?}", text);
+        let mut results = CrackResult::new(self, text.to_string());
+
+        let Some(bytes) = decode_skey(text) else {
+            debug!("Failed to decode S/KEY because the input isn't groups of six dictionary words with valid checksums");
+            return results;
+        };
+        let decoded_text = bytes_to_text(bytes.clone()).unwrap_or_else(|| encode_hex(&bytes));
+
+        if !check_string_success(&decoded_text, text) {
+            info!(
+                "Failed to decode S/KEY because check_string_success returned false on string {}",
+                decoded_text
+            );
+            return results;
+        }
+
+        let checker_result = checker.check(&decoded_text);
+        results.unencrypted_text = Some(vec![decoded_text]);
+
+        results.update_checker(&checker_result);
+
+        results
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some(("DAWN COO SINK TOWN WANG PAR", "password"))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input must be groups of six words, all from the RFC 2289 dictionary, in any case",
+            "The two bit checksum of every group must match",
+            "Bytes which aren't text are returned as hex",
+        ]
+    }
+}
+
+/// Decodes groups of six words into eight bytes each, None if a word isn't in the
+/// dictionary or a checksum doesn't match
+fn decode_skey(text: &str) -> Option<Vec<u8>> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    if words.is_empty() || !words.len().is_multiple_of(WORDS_PER_VALUE) {
+        return None;
+    }
+    let mut bytes = Vec::with_capacity(words.len() / WORDS_PER_VALUE * 8);
+    for group in words.chunks(WORDS_PER_VALUE) {
+        let bits = group.iter().try_fold(0u128, |bits, word| {
+            let index = SKEY_WORDS.rank(&word.to_uppercase())?;
+            Some(bits << 11 | index as u128)
+        })?;
+        let value = (bits >> 2) as u64;
+        if checksum(value) != (bits & 3) as u64 {
+            return None;
+        }
+        bytes.extend(value.to_be_bytes());
+    }
+    Some(bytes)
+}
+
+/// The RFC 2289 checksum, the sum of the value's pairs of bits modulo 4
+fn checksum(value: u64) -> u64 {
+    (0..32).map(|pair| (value >> (pair * 2)) & 3).sum::<u64>() & 3
+}
+
+/// Encodes bytes as lowercase hex
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_skey, SkeyDecoder};
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn rfc_examples_decode() {
+        let examples = [
+            ("INCH SEA ANNE LONG AHEM TOUR", 0x9E876134D90499DD),
+            ("EASE OIL FUM CURE AWRY AVIS", 0x7965E05436F5029F),
+            ("BAIL TUFT BITS GANG CHEF THY", 0x50FE1962C4965880),
+            ("FACT HOOF AT FIST SITE KENT", 0x7CD34C1040ADD14B),
+            ("ULAN NEW ARMY FUSE SUIT EYED", 0xF205753943DE4CF9),
+            ("LONG IVY JULY AJAR BOND LEE", 0xB203E28FA525BE47),
+        ];
+        for (words, value) in examples {
+            assert_eq!(decode_skey(words).unwrap(), u64::to_be_bytes(value));
+        }
+    }
+
+    #[test]
+    fn several_groups_decode_to_text() {
+        let decoder = Decoder::<SkeyDecoder>::new();
+        let result = decoder.crack(
+            "coat nee hit slam bid mart deny cant boo tub etc grub",
+            &get_athena_checker(),
+        );
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world!!!!!");
+    }
+
+    #[test]
+    fn bad_checksum_is_rejected() {
+        assert!(decode_skey("INCH SEA ANNE LONG AHEM TOUT").is_none());
+    }
+
+    #[test]
+    fn words_outside_the_dictionary_are_rejected() {
+        assert!(decode_skey("INCH SEA ANNE LONG AHEM HELLO").is_none());
+        assert!(decode_skey("INCH SEA ANNE LONG AHEM").is_none());
+    }
+
+    #[test]
+    fn skey_rejects_empty_string() {
+        let decoder = Decoder::<SkeyDecoder>::new();
+        let result = decoder.crack("", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+
+    #[test]
+    fn skey_rejects_emoji() {
+        let decoder = Decoder::<SkeyDecoder>::new();
+        let result = decoder.crack("😂", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+}
@@ -100,6 +100,21 @@ impl Crack for Decoder<SubstitutionGenericDecoder> {
     fn get_link(&self) -> &str {
         self.link
     }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some(("XXXX X XYXX XYXX YYY", "HELLO"))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input must contain between 2 and 4 unique symbols",
+            "2 symbols are mapped onto binary, 3 or 4 symbols onto Morse code",
+        ]
+    }
 }
 
 /// Generate all permutations of a symbol set
@@ -129,6 +144,8 @@ fn permute(
 }
 
 #[cfg(test)]
+// The tests fail with `assert!(false, ...)` when nothing was decoded
+#[allow(clippy::assertions_on_constants)]
 mod tests {
     use super::SubstitutionGenericDecoder;
     use crate::{
@@ -0,0 +1,369 @@
+//! Break a monoalphabetic substitution cipher
+//! Every letter is swapped for another one, so there are 26! keys and brute force is out.
+//! We start from the key which lines the letter frequencies up with English, then use
+//! simulated annealing: swap two letters of the key, keep the swap if the quadgram score
+//! goes up, and early on sometimes keep it even if the score goes down. The search stops
+//! once the `substitution_iterations` budget in the config is used up, or with the best key
+//! so far when the search is almost out of time.
+
+use crate::checkers::CheckerTypes;
+use crate::config::get_config;
+use crate::decoders::interface::check_string_success;
+use crate::storage::ngrams::ENGLISH_QUADGRAMS;
+use crate::storage::ENGLISH_FREQS;
+
+use super::alphabet::{index_to_letter, map_letters, ALPHABET_LENGTH};
+use super::crack_results::CrackResult;
+use super::frequency_analysis::{index_of_coincidence, letter_counts, letter_indices};
+use super::interface::Crack;
+use super::interface::Decoder;
+
+use log::{debug, info, trace};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::time::Duration;
+
+/// The fewest letters we will try to solve, shorter texts have many keys which score well
+const MIN_LETTERS: usize = 60;
+
+/// Substitution keeps the letter frequencies, only moving them to other letters,
+/// so the ciphertext should have English's Index of Coincidence of about 0.067.
+const MIN_INDEX_OF_COINCIDENCE: f64 = 0.055;
+
+/// How much worse, in log probability of the whole text, a swap can score and still
+/// often be kept at the start of the search. The temperature cools to 0 by the end.
+const START_TEMPERATURE: f64 = 8.0;
+
+/// The seed for the random swaps, so the same ciphertext is always solved the same way
+const SEED: u64 = 26;
+
+/// How many swaps are tried between checks of how long the search has left
+const STEPS_PER_TIME_CHECK: u32 = 1_000;
+
+/// With less time than this left in the search, the best key so far is returned
+pub const MIN_TIME_TO_ANNEAL: Duration = Duration::from_millis(250);
+
+/// A key, where `key[cipher letter]` is the plaintext letter it stands for
+type Key = [u8; ALPHABET_LENGTH as usize];
+
+/// The substitution solver, call:
+/// `let substitution_solver = Decoder::<SubstitutionSolver>::new()` to create a new instance
+/// And then call:
+/// `result = substitution_solver.crack(input)` to solve a substitution cipher
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::substitution_solver::SubstitutionSolver;
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let solve_substitution = Decoder::<SubstitutionSolver>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = solve_substitution.crack(
+///     "Rth axi xzotrtacdh nhhghl wxzpfhi rth qjllau drjzld hbhle hbhqzqo ra xzotr rth olhjr xjpg. Dtzgd gjddzqo rth lawne wajdr ihghqihi aq zrd drhjie fhjp, jqi zq yalre ehjld th tji qhbhl aqwh xhr zr oa ijln, qar hbhq iclzqo rth ualdr uzqrhl dralpd.",
+///     &checker,
+/// );
+/// assert_eq!(
+///     result.unencrypted_text.unwrap()[0],
+///     "The old lighthouse keeper climbed the narrow stairs every evening to light the great lamp. Ships passing the rocky coast depended on its steady beam, and in forty years he had never once let it go dark, not even during the worst winter storms."
+/// );
+/// ```
+pub struct SubstitutionSolver;
+
+impl Crack for Decoder<SubstitutionSolver> {
+    fn new() -> Decoder<SubstitutionSolver> {
+        Decoder {
+            name: "Substitution Solver",
+            description: "A monoalphabetic substitution cipher swaps every letter for another letter, using the same swap all the way through. The key is recovered by simulated annealing on English quadgram scores, starting from the key which matches English letter frequencies.",
+            link: "https://en.wikipedia.org/wiki/Substitution_cipher",
+            tags: vec!["substitution", "cipher", "classic", "decryption"],
+            popularity: 0.4,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying substitution solver with text {:?}", text);
+        let mut results = CrackResult::new(self, text.to_string());
+
+        let letters = letter_indices(text);
+        if letters.len() < MIN_LETTERS {
+            info!("Failed to solve substitution because the text has too few letters");
+            return results;
+        }
+        let counts = letter_counts(&letters);
+        if index_of_coincidence(&counts) < MIN_INDEX_OF_COINCIDENCE {
+            info!("Failed to solve substitution because the letter frequencies aren't English");
+            return results;
+        }
+
+        let out_of_time = || {
+            self.remaining_time()
+                .is_some_and(|left| left < MIN_TIME_TO_ANNEAL)
+        };
+        let (key, timed_out) = solve(
+            &letters,
+            &counts,
+            get_config().substitution_iterations,
+            &out_of_time,
+        );
+        results.timed_out = timed_out;
+        let decoded_text = map_letters(text, |letter| key[letter as usize]);
+        if !check_string_success(&decoded_text, text) {
+            info!("Failed to solve substitution because the best key changed nothing");
+            return results;
+        }
+
+        let checker_result = checker.check(&decoded_text);
+        results.unencrypted_text = Some(vec![decoded_text]);
+        if checker_result.is_identified {
+            let mapping = format_key(&key, &counts);
+            trace!("Solved substitution with key {}", mapping);
+            results.description = format!(
+                "{} Recovered key mapping ciphertext ABCDEFGHIJKLMNOPQRSTUVWXYZ to plaintext {}.",
+                self.description, mapping
+            );
+            results.key = Some(mapping);
+            results.update_checker(&checker_result);
+        }
+        results
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some((
+            "Rth axi xzotrtacdh nhhghl wxzpfhi rth qjllau drjzld hbhle hbhqzqo ra xzotr rth olhjr xjpg. Dtzgd gjddzqo rth lawne wajdr ihghqihi aq zrd drhjie fhjp, jqi zq yalre ehjld th tji qhbhl aqwh xhr zr oa ijln, qar hbhq iclzqo rth ualdr uzqrhl dralpd.",
+            "The old lighthouse keeper climbed the narrow stairs every evening to light the great lamp. Ships passing the rocky coast depended on its steady beam, and in forty years he had never once let it go dark, not even during the worst winter storms.",
+        ))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input must contain at least 60 letters",
+            "Letter frequencies must look like English (Index of Coincidence of at least 0.055)",
+            "Scores at most `substitution_iterations` keys from the config, fewer if the search is almost out of time",
+        ]
+    }
+}
+
+/// Anneals from the frequency key, returning the best key seen within the budget and
+/// whether `out_of_time` cut the search short
+fn solve(
+    letters: &[u8],
+    counts: &[usize; 26],
+    iterations: u32,
+    out_of_time: &impl Fn() -> bool,
+) -> (Key, bool) {
+    let (key, timed_out) = anneal(
+        letters,
+        frequency_key(counts).to_vec(),
+        iterations,
+        out_of_time,
+    );
+    let key = key
+        .try_into()
+        .expect("annealing keeps the length of the key");
+    (key, timed_out)
+}
+
+/// Anneals the key, returning the best key seen within the budget and whether
+/// `out_of_time` cut the search short
+/// `key[symbol]` is the plaintext letter the symbol stands for, so ciphers with more
+/// symbols than letters can be solved too, as long as every letter is somewhere in the key.
+/// Each step swaps two random letters of the key. Swaps which score better are kept, and
+/// swaps which score worse are sometimes kept too, less often as the temperature cools
+/// to nothing, so the search can climb out of keys which only a double swap would improve.
+pub fn anneal(
+    symbols: &[u8],
+    mut key: Vec<u8>,
+    iterations: u32,
+    out_of_time: &impl Fn() -> bool,
+) -> (Vec<u8>, bool) {
+    let mut rng = StdRng::seed_from_u64(SEED);
+    let fitness = |key: &[u8]| {
+        let decoded: Vec<u8> = symbols.iter().map(|&symbol| key[symbol as usize]).collect();
+        ENGLISH_QUADGRAMS.score_letters(&decoded)
+    };
+
+    let mut score = fitness(&key);
+    let mut best_key = key.clone();
+    let mut best_score = score;
+
+    for step in 0..iterations {
+        if step % STEPS_PER_TIME_CHECK == 0 && out_of_time() {
+            debug!("Annealing is almost out of time, returning the best key after {step} swaps");
+            return (best_key, true);
+        }
+        let temperature = START_TEMPERATURE * (1.0 - f64::from(step) / f64::from(iterations));
+        let (a, b) = (
+            rng.random_range(0..key.len()),
+            rng.random_range(0..key.len()),
+        );
+        if a == b || key[a] == key[b] {
+            continue;
+        }
+        key.swap(a, b);
+        let swapped_score = fitness(&key);
+        let accepted = swapped_score > score
+            || rng.random::<f64>() < ((swapped_score - score) / temperature).exp();
+        if accepted {
+            score = swapped_score;
+            if score > best_score {
+                best_key.clone_from(&key);
+                best_score = score;
+            }
+        } else {
+            key.swap(a, b);
+        }
+    }
+    (best_key, false)
+}
+
+/// The key which maps the most common cipher letter to E, the next to T, and so on
+fn frequency_key(counts: &[usize; 26]) -> Key {
+    symbol_frequency_key(counts)
+        .try_into()
+        .expect("there is a letter for every cipher letter")
+}
+
+/// The key which maps the most common symbol to E, the next to T, and so on
+/// Symbols past the 26th are given the rarest letters again, so every letter is in the key
+/// however many symbols the cipher has.
+pub fn symbol_frequency_key(counts: &[usize]) -> Vec<u8> {
+    let mut symbol_order: Vec<usize> = (0..counts.len()).collect();
+    symbol_order.sort_by_key(|&symbol| std::cmp::Reverse(counts[symbol]));
+    let mut english_order: Vec<u8> = (0..ALPHABET_LENGTH).collect();
+    english_order.sort_by(|&a, &b| ENGLISH_FREQS[b as usize].total_cmp(&ENGLISH_FREQS[a as usize]));
+
+    let mut key = vec![0; counts.len()];
+    let letters = english_order
+        .iter()
+        .chain(english_order.iter().rev().cycle());
+    for (symbol, &letter) in symbol_order.into_iter().zip(letters) {
+        key[symbol] = letter;
+    }
+    key
+}
+
+/// Writes the plaintext letter each cipher letter from A to Z stands for
+/// Letters which never appear in the ciphertext can't be recovered, so they are shown as `?`.
+fn format_key(key: &Key, counts: &[usize; 26]) -> String {
+    key.iter()
+        .zip(counts)
+        .map(|(&plain, &count)| {
+            if count == 0 {
+                '?'
+            } else {
+                index_to_letter(plain, true)
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checkers::{
+        athena::Athena,
+        checker_type::{Check, Checker},
+        CheckerTypes,
+    };
+
+    /// Uses 22 letters, so four letters of the key can't be recovered
+    const CIPHERTEXT: &str = "Rth axi xzotrtacdh nhhghl wxzpfhi rth qjllau drjzld hbhle hbhqzqo ra xzotr rth olhjr xjpg. Dtzgd gjddzqo rth lawne wajdr ihghqihi aq zrd drhjie fhjp, jqi zq yalre ehjld th tji qhbhl aqwh xhr zr oa ijln, qar hbhq iclzqo rth ualdr uzqrhl dralpd.";
+
+    /// The plaintext of `CIPHERTEXT`
+    const PLAINTEXT: &str = "The old lighthouse keeper climbed the narrow stairs every evening to light the great lamp. Ships passing the rocky coast depended on its steady beam, and in forty years he had never once let it go dark, not even during the worst winter storms.";
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn successful_decoding() {
+        let decoder = Decoder::<SubstitutionSolver>::new();
+        let result = decoder.crack(CIPHERTEXT, &get_athena_checker());
+        assert!(result.success);
+        assert_eq!(result.unencrypted_text.unwrap()[0], PLAINTEXT);
+        assert!(result
+            .description
+            .ends_with("to plaintext OVUSYBPEDA?R?KGMNT?HW?CLFI."));
+        assert_eq!(result.key.as_deref(), Some("OVUSYBPEDA?R?KGMNT?HW?CLFI"));
+    }
+
+    #[test]
+    fn frequency_key_maps_the_commonest_letter_to_e() {
+        let letters = letter_indices("qqqqq zzzz xxx");
+        let key = frequency_key(&letter_counts(&letters));
+        assert_eq!(key[(b'q' - b'a') as usize], b'e' - b'a');
+        assert_eq!(key[(b'z' - b'a') as usize], b't' - b'a');
+        assert_eq!(key[(b'x' - b'a') as usize], b'a' - b'a');
+    }
+
+    #[test]
+    fn zero_iterations_keeps_the_frequency_key() {
+        let letters = letter_indices(CIPHERTEXT);
+        let counts = letter_counts(&letters);
+        assert_eq!(
+            solve(&letters, &counts, 0, &|| false),
+            (frequency_key(&counts), false)
+        );
+    }
+
+    #[test]
+    fn running_out_of_time_returns_the_best_key_so_far() {
+        let letters = letter_indices(CIPHERTEXT);
+        let counts = letter_counts(&letters);
+        assert_eq!(
+            solve(&letters, &counts, 30_000, &|| true),
+            (frequency_key(&counts), true)
+        );
+    }
+
+    #[test]
+    fn unused_letters_are_unknown_in_the_key() {
+        let mut counts = [1; 26];
+        counts[0] = 0;
+        let key: Key = std::array::from_fn(|i| i as u8);
+        assert_eq!(format_key(&key, &counts), "?BCDEFGHIJKLMNOPQRSTUVWXYZ");
+    }
+
+    #[test]
+    fn short_text_is_not_decoded() {
+        let decoder = Decoder::<SubstitutionSolver>::new();
+        let result = decoder.crack("Rth vczwn flauq yam", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+
+    #[test]
+    fn substitution_handles_panic_if_emoji() {
+        let decoder = Decoder::<SubstitutionSolver>::new();
+        let result = decoder.crack("😂", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+}
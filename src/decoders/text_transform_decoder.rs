@@ -0,0 +1,286 @@
+//! Undo simple rearrangements of the characters of the text
+//! These are the low effort ways of hiding text which keep every character and only
+//! move them about: reversing each word, swapping every pair of characters, writing the
+//! characters at even places and then the ones at odd places, and rotating the whole text
+//! round by some number of places. Each is cheap, so every one is tried and all of them
+//! are returned, with the one the checkers matched first. Reversing the whole text is
+//! left to the Reverse decoder.
+//! Call text_transform_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::check_string_success;
+use crate::storage::ngrams::quadgram_score;
+use crate::storage::wordlists::ENGLISH_WORDS;
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::Decoder;
+
+use log::{debug, trace};
+
+/// The fewest characters worth rearranging
+const MIN_LENGTH: usize = 4;
+
+/// The longest text every rotation is scored for, as each one is scored in full
+const MAX_ROTATION_LENGTH: usize = 1024;
+
+/// The Text transform decoder, call:
+/// `let text_transform_decoder = Decoder::<TextTransformDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = text_transform_decoder.crack(input)` to undo a rearrangement of the text
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::text_transform_decoder::{TextTransformDecoder};
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_text_transform = Decoder::<TextTransformDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_text_transform.crack("siht si a terces egassem", &checker);
+/// // Every transform is returned, with the one the checkers matched first
+/// assert_eq!(result.unencrypted_text.unwrap()[0], "this is a secret message");
+/// assert_eq!(result.key.unwrap(), "each word reversed");
+/// ```
+pub struct TextTransformDecoder;
+
+impl Crack for Decoder<TextTransformDecoder> {
+    fn new() -> Decoder<TextTransformDecoder> {
+        Decoder {
+            name: "Text transform",
+            description: "Simple rearrangements which keep every character: each word reversed, every pair of characters swapped, the characters at even and odd places written one after the other, or the whole text rotated round by some places.",
+            link: "https://www.dcode.fr/reverse-text",
+            tags: vec!["transform", "decoder", "reciprocal"],
+            popularity: 0.2,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying text transforms with text {:?}", text);
+        let mut results = CrackResult::new(self, text.to_string());
+
+        let chars: Vec<char> = text.chars().collect();
+        if chars.len() < MIN_LENGTH {
+            debug!("Failed to transform text because it is too short");
+            return results;
+        }
+
+        let mut transforms = vec![
+            (String::from("each word reversed"), reverse_words(text)),
+            (String::from("pairs swapped"), swap_pairs(&chars)),
+            (
+                String::from("even and odd places interleaved"),
+                interleave(&chars),
+            ),
+        ];
+        if let Some((places, rotated)) = best_rotation(&chars) {
+            transforms.push((format!("rotated by {places}"), rotated));
+        }
+
+        let mut candidates = Vec::new();
+        let mut keys = Vec::new();
+        for (key, decoded_text) in transforms {
+            if !check_string_success(&decoded_text, text) || candidates.contains(&decoded_text) {
+                continue;
+            }
+            let checker_result = checker.check(&decoded_text);
+            if checker_result.is_identified && !results.success {
+                // The match goes first, and every other candidate is still returned
+                candidates.insert(0, decoded_text);
+                keys.insert(0, key);
+                results.update_checker(&checker_result);
+            } else {
+                candidates.push(decoded_text);
+                keys.push(key);
+            }
+        }
+        if candidates.is_empty() {
+            debug!("Failed to transform text because every transform gave the text back");
+            return results;
+        }
+        if results.success {
+            results.key = keys.into_iter().next();
+        }
+        results.unencrypted_text = Some(candidates);
+
+        results
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some(("olleh dlrow", "hello world"))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input must be at least 4 characters",
+            "Only the most English-like rotation is returned, and only for text up to 1024 characters",
+            "Transforms which give the input back are skipped",
+        ]
+    }
+}
+
+/// Reverses every word in place, keeping the whitespace between them
+fn reverse_words(text: &str) -> String {
+    let mut reversed = String::with_capacity(text.len());
+    let mut word = Vec::new();
+    for c in text.chars() {
+        if c.is_whitespace() {
+            reversed.extend(word.drain(..).rev());
+            reversed.push(c);
+        } else {
+            word.push(c);
+        }
+    }
+    reversed.extend(word.into_iter().rev());
+    reversed
+}
+
+/// Swaps the first character with the second, the third with the fourth and so on
+fn swap_pairs(chars: &[char]) -> String {
+    chars.chunks(2).flat_map(|pair| pair.iter().rev()).collect()
+}
+
+/// Undoes writing the characters at even places first and the ones at odd places after
+fn interleave(chars: &[char]) -> String {
+    let (even, odd) = chars.split_at(chars.len().div_ceil(2));
+    let mut interleaved = String::with_capacity(chars.len());
+    for (i, c) in even.iter().enumerate() {
+        interleaved.push(*c);
+        if let Some(c) = odd.get(i) {
+            interleaved.push(*c);
+        }
+    }
+    interleaved
+}
+
+/// The rotation of the text which scores as the most English, and how many places it
+/// moves the text left. Rotations are ranked by how many whole English words they have and
+/// then by quadgrams, as quadgrams skip spaces and so can't tell where the text was cut.
+/// Rotations starting or ending with whitespace are skipped, as the spaces would have been
+/// rotated with the words.
+fn best_rotation(chars: &[char]) -> Option<(usize, String)> {
+    if chars.len() > MAX_ROTATION_LENGTH {
+        return None;
+    }
+    (1..chars.len())
+        .filter(|&places| !chars[places].is_whitespace() && !chars[places - 1].is_whitespace())
+        .map(|places| {
+            let rotated: String = chars[places..].iter().chain(&chars[..places]).collect();
+            (places, rotated)
+        })
+        .map(|(places, rotated)| {
+            (
+                ENGLISH_WORDS.count_words_in(&rotated),
+                quadgram_score(&rotated),
+                places,
+                rotated,
+            )
+        })
+        .max_by(|a, b| a.0.cmp(&b.0).then(a.1.total_cmp(&b.1)))
+        .map(|(_, _, places, rotated)| (places, rotated))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{best_rotation, interleave, reverse_words, swap_pairs, TextTransformDecoder};
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    /// The characters of the text, for the transforms which work on them
+    fn chars(text: &str) -> Vec<char> {
+        text.chars().collect()
+    }
+
+    #[test]
+    fn successful_decoding() {
+        let text_transform_decoder = Decoder::<TextTransformDecoder>::new();
+        let result =
+            text_transform_decoder.crack("siht si a terces egassem", &get_athena_checker());
+        assert!(result.success);
+        assert_eq!(
+            result.unencrypted_text.unwrap()[0],
+            "this is a secret message"
+        );
+        assert_eq!(result.key.unwrap(), "each word reversed");
+    }
+
+    #[test]
+    fn words_are_reversed_in_place() {
+        assert_eq!(reverse_words("olleh  dlrow\n!"), "hello  world\n!");
+    }
+
+    #[test]
+    fn pairs_are_swapped() {
+        assert_eq!(swap_pairs(&chars("ehll oowlrd")), "hello world");
+    }
+
+    #[test]
+    fn even_and_odd_places_are_interleaved() {
+        assert_eq!(interleave(&chars("hlowrdel ol")), "hello world");
+        assert_eq!(interleave(&chars("hloel")), "hello");
+    }
+
+    #[test]
+    fn rotations_are_scored() {
+        // The secret message rotated four places left
+        let (places, rotated) = best_rotation(&chars("secret messagethe ")).unwrap();
+        assert_eq!(rotated, "the secret message");
+        assert_eq!(places, 14);
+    }
+
+    #[test]
+    fn text_transform_handle_panic_if_empty_string() {
+        let text_transform_decoder = Decoder::<TextTransformDecoder>::new();
+        let result = text_transform_decoder
+            .crack("", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn text_transform_handle_panic_if_emoji() {
+        let text_transform_decoder = Decoder::<TextTransformDecoder>::new();
+        let result = text_transform_decoder
+            .crack("😂", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+}
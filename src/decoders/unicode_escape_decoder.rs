@@ -0,0 +1,327 @@
+//! Decodes escape sequences such as `\u00e9` and `\xc3\xa9`
+//! Programming languages and JSON write characters as `\uXXXX` (with surrogate pairs for
+//! characters past U+FFFF), `\u{X}` and `\UXXXXXXXX`, and bytes as `\xNN`. Runs of `\x`
+//! bytes are put back together as UTF-8, so `\xc3\xa9` is é. The usual one letter escapes
+//! such as `\n` and `\"` are decoded too, and anything else after a backslash is kept as it
+//! is, so Windows paths survive. Bytes which aren't UTF-8 are passed on as bytes.
+//! Call unicode_escape_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::check_string_success;
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::Decoder;
+use super::radix::bytes_to_text;
+
+use log::{debug, info, trace};
+
+/// The Unicode escape decoder, call:
+/// `let unicode_escape_decoder = Decoder::<UnicodeEscapeDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = unicode_escape_decoder.crack(input)` to decode escape sequences
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::unicode_escape_decoder::{UnicodeEscapeDecoder};
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_escapes = Decoder::<UnicodeEscapeDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_escapes.crack("\\u0068\\u0065\\u006c\\u006c\\u006f \\x77\\x6f\\x72\\x6c\\x64", &checker);
+/// assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+/// ```
+pub struct UnicodeEscapeDecoder;
+
+impl Crack for Decoder<UnicodeEscapeDecoder> {
+    fn new() -> Decoder<UnicodeEscapeDecoder> {
+        Decoder {
+            name: "Unicode escapes",
+            description: "Source code and JSON write characters they can't or won't write directly as escape sequences, such as \\u00e9 for a code point or \\xc3 for a byte. Shellcode and obfuscated scripts are often pasted as long runs of them.",
+            link: "https://en.wikipedia.org/wiki/Escape_sequences_in_C",
+            tags: vec!["escape", "decoder", "unicode"],
+            popularity: 0.5,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying Unicode escapes with text {:?}", text);
+        let mut results = CrackResult::new(self, text.to_string());
+
+        let Some(bytes) = unescape(text) else {
+            debug!("Failed to decode Unicode escapes because there is no \\u or \\x escape");
+            return results;
+        };
+        let decoded_text = match String::from_utf8(bytes) {
+            Ok(decoded_text) => decoded_text,
+            Err(e) => {
+                // Bytes which aren't text are passed on as they are, for decoders such as gzip
+                let bytes = e.into_bytes();
+                let checker_result = checker.check_bytes(&bytes);
+                results.unencrypted_bytes = Some(vec![bytes]);
+                results.update_checker(&checker_result);
+                return results;
+            }
+        };
+        let Some(decoded_text) = bytes_to_text(decoded_text.into_bytes()) else {
+            debug!("Failed to decode Unicode escapes because they are control characters");
+            return results;
+        };
+        if !check_string_success(&decoded_text, text) {
+            info!(
+                "Failed to decode Unicode escapes because check_string_success returned false on string {}",
+                decoded_text
+            );
+            return results;
+        }
+
+        let checker_result = checker.check(&decoded_text);
+        results.unencrypted_text = Some(vec![decoded_text]);
+
+        results.update_checker(&checker_result);
+
+        results
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some((
+            "\\u0068\\u0065\\u006c\\u006c\\u006f \\x77\\x6f\\x72\\x6c\\x64",
+            "hello world",
+        ))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input must contain at least one `\\uXXXX`, `\\u{X}`, `\\UXXXXXXXX` or `\\xNN` escape",
+            "`\\u` escapes must be whole characters, with surrogates in pairs",
+            "Decoded bytes which aren't valid UTF-8 are passed on as bytes, for decoders such as gzip",
+            "Decoded text must not contain control characters other than whitespace",
+        ]
+    }
+}
+
+/// Reads `digits` hex digits from the start of the text, None if they aren't all there
+fn hex_digits(text: &str, digits: usize) -> Option<u32> {
+    let number = text.get(..digits)?;
+    if !number.bytes().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    u32::from_str_radix(number, 16).ok()
+}
+
+/// Decodes the text after a backslash, with how many bytes of it the escape used.
+/// Gives None for escapes other than `\u`, `\U` and `\x`, which are handled by the caller.
+fn code_escape(rest: &str) -> Option<(Vec<u8>, usize)> {
+    let mut utf8 = [0; 4];
+    match rest.as_bytes().first()? {
+        b'x' => {
+            let byte = hex_digits(&rest[1..], 2)?;
+            Some((vec![byte as u8], 3))
+        }
+        b'U' => {
+            let c = char::from_u32(hex_digits(&rest[1..], 8)?)?;
+            Some((c.encode_utf8(&mut utf8).as_bytes().to_vec(), 9))
+        }
+        b'u' if rest[1..].starts_with('{') => {
+            let end = rest.find('}')?;
+            let digits = &rest[2..end];
+            if digits.is_empty() || digits.len() > 6 {
+                return None;
+            }
+            let c = char::from_u32(hex_digits(digits, digits.len())?)?;
+            Some((c.encode_utf8(&mut utf8).as_bytes().to_vec(), end + 1))
+        }
+        b'u' => {
+            let unit = hex_digits(&rest[1..], 4)?;
+            let (c, used) = match unit {
+                0xD800..=0xDBFF => {
+                    let low = rest[5..]
+                        .strip_prefix("\\u")
+                        .and_then(|low| hex_digits(low, 4))?;
+                    let units = [unit as u16, low as u16];
+                    (char::decode_utf16(units).next()?.ok()?, 11)
+                }
+                _ => (char::from_u32(unit)?, 5),
+            };
+            Some((c.encode_utf8(&mut utf8).as_bytes().to_vec(), used))
+        }
+        _ => None,
+    }
+}
+
+/// What a one letter escape such as `\n` stands for, None for letters which aren't escapes
+fn simple_escape(c: char) -> Option<char> {
+    let unescaped = match c {
+        'n' => '\n',
+        't' => '\t',
+        'r' => '\r',
+        '\\' => '\\',
+        '"' => '"',
+        '\'' => '\'',
+        '/' => '/',
+        _ => return None,
+    };
+    Some(unescaped)
+}
+
+/// Decodes every escape in the text into bytes, None if there is no `\u`, `\U` or `\x` escape.
+/// Broken escapes are kept as they are written.
+fn unescape(text: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(text.len());
+    let mut escapes = 0;
+    let mut rest = text;
+    while let Some(start) = rest.find('\\') {
+        bytes.extend_from_slice(&rest.as_bytes()[..start]);
+        let after = &rest[start + 1..];
+        if let Some((decoded, used)) = code_escape(after) {
+            bytes.extend(decoded);
+            escapes += 1;
+            rest = &after[used..];
+            continue;
+        }
+        match after.chars().next().and_then(simple_escape) {
+            Some(c) => {
+                bytes.push(c as u8);
+                rest = &after[1..];
+            }
+            None => {
+                bytes.push(b'\\');
+                rest = after;
+            }
+        }
+    }
+    bytes.extend_from_slice(rest.as_bytes());
+    if escapes == 0 {
+        return None;
+    }
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{unescape, UnicodeEscapeDecoder};
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn successful_decoding() {
+        let unicode_escape_decoder = Decoder::<UnicodeEscapeDecoder>::new();
+        let result = unicode_escape_decoder.crack(
+            "\\x68\\x65\\x6c\\x6c\\x6f\\x20\\x77\\x6f\\x72\\x6c\\x64",
+            &get_athena_checker(),
+        );
+        assert!(result.success);
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+    }
+
+    #[test]
+    fn every_form_of_code_point_is_decoded() {
+        assert_eq!(unescape("caf\\u00e9").unwrap(), "café".as_bytes());
+        assert_eq!(unescape("caf\\u{e9}").unwrap(), "café".as_bytes());
+        assert_eq!(unescape("\\U0001F600").unwrap(), "😀".as_bytes());
+        assert_eq!(unescape("\\ud83d\\ude00").unwrap(), "😀".as_bytes());
+    }
+
+    #[test]
+    fn byte_escapes_are_joined_as_utf8() {
+        assert_eq!(unescape("caf\\xc3\\xa9").unwrap(), "café".as_bytes());
+    }
+
+    #[test]
+    fn simple_escapes_are_decoded_alongside() {
+        assert_eq!(
+            unescape("\\\"\\u0068i\\\"\\n").unwrap(),
+            b"\"hi\"\n".to_vec()
+        );
+    }
+
+    #[test]
+    fn other_backslashes_are_kept() {
+        assert_eq!(
+            unescape("C:\\users\\xavier\\x41").unwrap(),
+            b"C:\\users\\xavierA".to_vec()
+        );
+    }
+
+    #[test]
+    fn unpaired_surrogates_are_kept_as_written() {
+        assert!(unescape("\\ud83d alone").is_none());
+    }
+
+    #[test]
+    fn bytes_which_arent_text_are_passed_on() {
+        let unicode_escape_decoder = Decoder::<UnicodeEscapeDecoder>::new();
+        let result = unicode_escape_decoder.crack("\\x1f\\x8b\\x08\\x00", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+        assert_eq!(
+            result.unencrypted_bytes.unwrap()[0],
+            vec![0x1f, 0x8b, 0x08, 0x00]
+        );
+    }
+
+    #[test]
+    fn text_without_escapes_is_not_decoded() {
+        let unicode_escape_decoder = Decoder::<UnicodeEscapeDecoder>::new();
+        let result = unicode_escape_decoder
+            .crack("C:\\Windows\\System32", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn unicode_escape_handle_panic_if_empty_string() {
+        let unicode_escape_decoder = Decoder::<UnicodeEscapeDecoder>::new();
+        let result = unicode_escape_decoder
+            .crack("", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn unicode_escape_handle_panic_if_emoji() {
+        let unicode_escape_decoder = Decoder::<UnicodeEscapeDecoder>::new();
+        let result = unicode_escape_decoder
+            .crack("😂\\u", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+}
@@ -49,30 +49,50 @@ impl Crack for Decoder<URLDecoder> {
     /// Else the Option returns nothing and the error is logged in Trace
     fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
         trace!("Trying url with text {:?}", text);
-        let decoded_text: Option<String> = decode_url_no_error_handling(text);
-
-        trace!("Decoded text for url: {:?}", decoded_text);
         let mut results = CrackResult::new(self, text.to_string());
 
-        if decoded_text.is_none() {
-            debug!("Failed to decode url because URLDecoder::decode_url_no_error_handling returned None");
+        // Without a % there is nothing to decode, so don't waste search time on it
+        if !text.contains('%') {
+            debug!("Failed to decode url because the text has no percent-encoding");
             return results;
         }
 
-        let decoded_text = decoded_text.unwrap();
-        if !check_string_success(&decoded_text, text) {
-            info!(
-                "Failed to decode url because check_string_success returned false on string {}",
-                decoded_text
-            );
-            return results;
+        // Text is often encoded more than once, so keep decoding while it changes
+        let mut layers: Vec<String> = Vec::new();
+        let mut current = text.to_string();
+        while layers.len() < MAX_DECODE_DEPTH && current.contains('%') {
+            let decoded_text = match decode_url_no_error_handling(&current) {
+                Some(decoded_text) => decoded_text,
+                None => break,
+            };
+            if !check_string_success(&decoded_text, &current) {
+                break;
+            }
+            layers.push(decoded_text.clone());
+            current = decoded_text;
         }
+        trace!("Decoded layers for url: {:?}", layers);
 
-        let checker_result = checker.check(&decoded_text);
-        results.unencrypted_text = Some(vec![decoded_text]);
+        if layers.is_empty() {
+            info!("Failed to decode url because no percent-encoding could be decoded");
+            return results;
+        }
 
-        results.update_checker(&checker_result);
+        // The most decoded text is most likely the plaintext, but a text can
+        // legitimately contain percent signs, so check the shallower layers too
+        layers.reverse();
+        for index in 0..layers.len() {
+            let checker_result = checker.check(&layers[index]);
+            if checker_result.is_identified {
+                let identified = layers.remove(index);
+                layers.insert(0, identified);
+                results.unencrypted_text = Some(layers);
+                results.update_checker(&checker_result);
+                return results;
+            }
+        }
 
+        results.unencrypted_text = Some(layers);
         results
     }
     /// Gets all tags for this decoder
@@ -91,21 +111,68 @@ impl Crack for Decoder<URLDecoder> {
     fn get_link(&self) -> &str {
         self.link
     }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some((
+            "This%20is%20an%20example%20of%20a%20URL%20encoded%20string%20%3C%3E%3F%3D%7B%7D%7C",
+            "This is an example of a URL encoded string <>?={}|",
+        ))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input must contain `%`",
+            "`+` decodes to a space and `%` not followed by two hex digits is kept as it is",
+            "Decodes repeatedly, up to 5 times, for double encoded text",
+            "Decoded bytes must be valid UTF-8",
+        ]
+    }
 }
 
-/// helper function
+/// The most times we decode one text, for text which was encoded more than once
+const MAX_DECODE_DEPTH: usize = 5;
+
+/// Decodes one layer of percent-encoding
+/// `+` becomes a space, and a `%` which isn't followed by two hex digits is kept as it is.
+/// Returns None if the decoded bytes aren't valid UTF-8.
 fn decode_url_no_error_handling(text: &str) -> Option<String> {
-    // Runs the code to decode url
-    // Doesn't perform error handling, call from_url
-    if let Ok(decoded_text) = urlencoding::decode(text) {
-        return Some(decoded_text.into_owned());
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' => match bytes.get(i + 1..i + 3).and_then(hex_byte) {
+                Some(byte) => {
+                    decoded.push(byte);
+                    i += 3;
+                    continue;
+                }
+                None => decoded.push(b'%'),
+            },
+            b'+' => decoded.push(b' '),
+            byte => decoded.push(byte),
+        }
+        i += 1;
     }
-    None
+    String::from_utf8(decoded).ok()
+}
+
+/// Parses two hex digits as a byte
+fn hex_byte(digits: &[u8]) -> Option<u8> {
+    let digits = std::str::from_utf8(digits).ok()?;
+    if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    u8::from_str_radix(digits, 16).ok()
 }
 
 #[cfg(test)]
 mod tests {
-    use super::URLDecoder;
+    use super::{decode_url_no_error_handling, URLDecoder};
     use crate::{
         checkers::{
             athena::Athena,
@@ -135,6 +202,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn url_decodes_plus_as_space() {
+        let url_decoder = Decoder::<URLDecoder>::new();
+        let result = url_decoder.crack("hello+world%21+how+are+you", &get_athena_checker());
+        assert_eq!(
+            result.unencrypted_text.unwrap()[0],
+            "hello world! how are you"
+        );
+    }
+
+    #[test]
+    fn url_decodes_double_encoding() {
+        let url_decoder = Decoder::<URLDecoder>::new();
+        let result = url_decoder.crack(
+            "hello%2520world%2521%2520how%2520are%2520you",
+            &get_athena_checker(),
+        );
+        assert_eq!(
+            result.unencrypted_text.unwrap()[0],
+            "hello world! how are you"
+        );
+    }
+
+    #[test]
+    fn url_keeps_invalid_sequences() {
+        assert_eq!(
+            decode_url_no_error_handling("100% sure %2"),
+            Some("100% sure %2".to_string())
+        );
+        assert_eq!(
+            decode_url_no_error_handling("%zz%41"),
+            Some("%zzA".to_string())
+        );
+        assert_eq!(decode_url_no_error_handling("%ff%fe"), None);
+    }
+
     #[test]
     fn url_handles_panics() {
         // This tests if URL can handle panics
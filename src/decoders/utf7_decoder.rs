@@ -0,0 +1,256 @@
+//! Decodes UTF-7
+//! UTF-7 writes text in 7-bit ASCII for old mail systems. Most characters are written as
+//! themselves, and the rest as UTF-16 written in Base64 between a `+` and an optional `-`.
+//! `+-` stands for a plus sign itself. Since any character can be shifted, it has been used
+//! to smuggle `<script>` past filters in email headers and old Exchange servers.
+//! A shifted run has to hold whole UTF-16 characters with zero bits left over, which
+//! plus signs in ordinary text almost never are, so the decoder only fires on real UTF-7.
+//! Call utf7_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::check_string_success;
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::Decoder;
+
+use log::{debug, info, trace};
+
+/// The UTF-7 decoder, call:
+/// `let utf7_decoder = Decoder::<Utf7Decoder>::new()` to create a new instance
+/// And then call:
+/// `result = utf7_decoder.crack(input)` to decode a UTF-7 string
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::utf7_decoder::{Utf7Decoder};
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_utf7 = Decoder::<Utf7Decoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_utf7.crack("hello +AHcAbwByAGwAZA-", &checker);
+/// assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+/// ```
+pub struct Utf7Decoder;
+
+impl Crack for Decoder<Utf7Decoder> {
+    fn new() -> Decoder<Utf7Decoder> {
+        Decoder {
+            name: "UTF-7",
+            description: "UTF-7 writes Unicode text in 7-bit ASCII for old mail systems, writing the characters it can't send as UTF-16 in Base64 between a + and a -. As any character can be written that way, it has been used to smuggle markup past filters.",
+            link: "https://en.wikipedia.org/wiki/UTF-7",
+            tags: vec!["utf7", "decoder", "unicode", "email"],
+            popularity: 0.2,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying UTF-7 with text {:?}", text);
+        let mut results = CrackResult::new(self, text.to_string());
+
+        let Some(decoded_text) = decode_utf7(text) else {
+            debug!("Failed to decode UTF-7 because the text has no valid shifted run");
+            return results;
+        };
+        if !check_string_success(&decoded_text, text) {
+            info!(
+                "Failed to decode UTF-7 because check_string_success returned false on string {}",
+                decoded_text
+            );
+            return results;
+        }
+
+        let checker_result = checker.check(&decoded_text);
+        results.unencrypted_text = Some(vec![decoded_text]);
+
+        results.update_checker(&checker_result);
+
+        results
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some(("hello +AHcAbwByAGwAZA-", "hello world"))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input must have at least one + followed by Base64, other than +- for a plus sign",
+            "Every shifted run must hold whole UTF-16 characters, with fewer than 6 bits left over and those bits zero",
+            "Decoded text must not contain control characters other than whitespace",
+        ]
+    }
+}
+
+/// The value of a Base64 character in a shifted run, None for characters which end it
+fn base64_value(c: char) -> Option<u32> {
+    let value = match c {
+        'A'..='Z' => u32::from(c) - u32::from('A'),
+        'a'..='z' => u32::from(c) - u32::from('a') + 26,
+        '0'..='9' => u32::from(c) - u32::from('0') + 52,
+        '+' => 62,
+        '/' => 63,
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// Decodes one shifted run of Base64 into the UTF-16 characters it holds
+fn decode_run(run: &[u32]) -> Option<String> {
+    let mut units = Vec::new();
+    let mut bits = 0u32;
+    let mut count = 0;
+    for &value in run {
+        bits = (bits << 6) | value;
+        count += 6;
+        if count >= 16 {
+            count -= 16;
+            units.push((bits >> count) as u16);
+            bits &= (1 << count) - 1;
+        }
+    }
+    // What is left over is padding, which has to be shorter than a character and zero
+    if units.is_empty() || count >= 6 || bits != 0 {
+        return None;
+    }
+    char::decode_utf16(units)
+        .collect::<Result<String, _>>()
+        .ok()
+}
+
+/// Decodes UTF-7, None if there is no shifted run or one of them isn't valid
+fn decode_utf7(text: &str) -> Option<String> {
+    let mut decoded = String::new();
+    let mut shifted = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '+' {
+            decoded.push(c);
+            continue;
+        }
+        if chars.next_if_eq(&'-').is_some() {
+            decoded.push('+');
+            continue;
+        }
+        let mut run = Vec::new();
+        while let Some(value) = chars.peek().copied().and_then(base64_value) {
+            run.push(value);
+            chars.next();
+        }
+        decoded.push_str(&decode_run(&run)?);
+        shifted = true;
+        // A - after the run only ends it
+        chars.next_if_eq(&'-');
+    }
+    if !shifted
+        || decoded
+            .chars()
+            .any(|c| c.is_control() && !c.is_whitespace())
+    {
+        return None;
+    }
+    Some(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_utf7, Utf7Decoder};
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn successful_decoding() {
+        let utf7_decoder = Decoder::<Utf7Decoder>::new();
+        let result = utf7_decoder.crack("hello +AHcAbwByAGwAZA-", &get_athena_checker());
+        assert!(result.success);
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+    }
+
+    #[test]
+    fn examples_from_the_rfc() {
+        assert_eq!(decode_utf7("Hi Mom -+Jjo--!").unwrap(), "Hi Mom -☺-!");
+        assert_eq!(decode_utf7("A+ImIDkQ.").unwrap(), "A≢Α.");
+        assert_eq!(decode_utf7("+ZeVnLIqe-").unwrap(), "日本語");
+    }
+
+    #[test]
+    fn smuggled_markup_is_decoded() {
+        assert_eq!(
+            decode_utf7("+ADw-script+AD4-alert(1)+ADw-/script+AD4-").unwrap(),
+            "<script>alert(1)</script>"
+        );
+    }
+
+    #[test]
+    fn escaped_plus_signs_are_kept() {
+        assert_eq!(decode_utf7("1 +- 1 = +ADI-").unwrap(), "1 + 1 = 2");
+    }
+
+    #[test]
+    fn plus_signs_in_ordinary_text_are_not_decoded() {
+        assert!(decode_utf7("1+1=2").is_none());
+        assert!(decode_utf7("C++ and C+-").is_none());
+        assert!(decode_utf7("no plus signs here").is_none());
+    }
+
+    #[test]
+    fn leftover_bits_must_be_zero() {
+        assert!(decode_utf7("+AHd-").is_none());
+    }
+
+    #[test]
+    fn utf7_handle_panic_if_empty_string() {
+        let utf7_decoder = Decoder::<Utf7Decoder>::new();
+        let result = utf7_decoder
+            .crack("", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn utf7_handle_panic_if_emoji() {
+        let utf7_decoder = Decoder::<Utf7Decoder>::new();
+        let result = utf7_decoder
+            .crack("😂", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+}
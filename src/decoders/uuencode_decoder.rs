@@ -0,0 +1,286 @@
+//! Decodes a uuencoded payload
+//! Uuencoding was how binaries were sent over email and Usenet before MIME. The data sits
+//! between a `begin 644 name` header and an `end` trailer, in lines which start with a
+//! character giving how many bytes the line holds, followed by every three bytes written
+//! as four characters from space to backtick. Lines without the header are decoded too,
+//! as long as every line is exactly as long as its first character says.
+//! XXencoding has the same layout with a different alphabet, so [`decode_uu_lines`] is
+//! shared with the XXencode decoder.
+//! Call uuencode_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::check_string_success;
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::Decoder;
+use super::radix::bytes_to_text;
+
+use log::{debug, info, trace};
+
+/// The Uuencode decoder, call:
+/// `let uuencode_decoder = Decoder::<UuencodeDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = uuencode_decoder.crack(input)` to decode a uuencoded string
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::uuencode_decoder::{UuencodeDecoder};
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_uuencode = Decoder::<UuencodeDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_uuencode.crack("begin 644 hello.txt\n+:&5L;&\\@=V]R;&0`\n`\nend\n", &checker);
+/// assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+/// assert!(result.description.contains("hello.txt"));
+/// ```
+pub struct UuencodeDecoder;
+
+impl Crack for Decoder<UuencodeDecoder> {
+    fn new() -> Decoder<UuencodeDecoder> {
+        Decoder {
+            name: "Uuencode",
+            description: "Uuencoding writes every three bytes as four characters from space to backtick, in lines that start with how many bytes they hold, between a begin line with the file's permissions and name and an end line. It was used to send files over email and Usenet before MIME.",
+            link: "https://en.wikipedia.org/wiki/Uuencoding",
+            tags: vec!["uuencode", "decoder", "usenet", "email"],
+            popularity: 0.2,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying Uuencode with text {:?}", text);
+        let mut results = CrackResult::new(self, text.to_string());
+
+        let Some((name, bytes)) = decode_uu_lines(text, uu_value) else {
+            debug!("Failed to decode uuencode because the lines aren't uuencoded");
+            return results;
+        };
+        let Some(decoded_text) = bytes_to_text(bytes) else {
+            debug!("Failed to decode uuencode because the payload isn't readable text");
+            return results;
+        };
+        if !check_string_success(&decoded_text, text) {
+            info!(
+                "Failed to decode uuencode because check_string_success returned false on string {}",
+                decoded_text
+            );
+            return results;
+        }
+
+        if let Some(name) = name {
+            results.description = format!("{} The file was named {}.", self.description, name);
+        }
+        let checker_result = checker.check(&decoded_text);
+        results.unencrypted_text = Some(vec![decoded_text]);
+
+        results.update_checker(&checker_result);
+
+        results
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some((
+            "begin 644 hello.txt\n+:&5L;&\\@=V]R;&0`\n`\nend\n",
+            "hello world",
+        ))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input must either start with a `begin <mode> <name>` line, or have every line exactly as long as its length character says",
+            "Input may only contain characters from space to backtick, so lowercase text is never decoded",
+            "Decoded bytes must be valid UTF-8 without control characters",
+        ]
+    }
+}
+
+/// The value of one uuencoded character, None for characters outside space to backtick
+/// Backtick is the same as space, so lines don't end in spaces that could get stripped.
+fn uu_value(c: u8) -> Option<u8> {
+    match c {
+        b' '..=b'`' => Some((c - b' ') & 0x3F),
+        _ => None,
+    }
+}
+
+/// Decodes lines laid out the way uuencode lays them out, where `value` reads one character.
+/// Returns the file name from the `begin` header if there is one, and the decoded bytes.
+/// After a header, lines which lost some trailing characters are padded out and an `end`
+/// line finishes the data. Without a header every line has to be exactly as long as its
+/// length character says, which plain text almost never is.
+pub fn decode_uu_lines(
+    text: &str,
+    value: fn(u8) -> Option<u8>,
+) -> Option<(Option<String>, Vec<u8>)> {
+    let mut lines = text
+        .lines()
+        .skip_while(|line| line.trim().is_empty())
+        .peekable();
+    let header = lines.peek()?.trim_end();
+    let name = match header.strip_prefix("begin ") {
+        Some(rest) => {
+            let (mode, name) = rest.split_once(' ')?;
+            if mode.is_empty() || !mode.bytes().all(|c| matches!(c, b'0'..=b'7')) {
+                return None;
+            }
+            lines.next();
+            Some(name.trim().to_string())
+        }
+        None => None,
+    };
+    let strict = name.is_none();
+
+    let mut bytes = Vec::new();
+    for line in lines {
+        let line = line.as_bytes();
+        if line == b"end" {
+            break;
+        }
+        let Some((&length, characters)) = line.split_first() else {
+            if strict {
+                return None;
+            }
+            break;
+        };
+        let length = usize::from(value(length)?);
+        if length == 0 {
+            break;
+        }
+        let needed = length.div_ceil(3) * 4;
+        if strict && characters.len() != needed {
+            return None;
+        }
+        let mut values = characters
+            .iter()
+            .take(needed)
+            .map(|&c| value(c))
+            .collect::<Option<Vec<u8>>>()?;
+        values.resize(needed, 0);
+        let line_bytes = values.chunks(4).flat_map(|group| {
+            let number = group
+                .iter()
+                .fold(0u32, |number, &v| (number << 6) | u32::from(v));
+            [(number >> 16) as u8, (number >> 8) as u8, number as u8]
+        });
+        bytes.extend(line_bytes.take(length));
+    }
+    if bytes.is_empty() {
+        return None;
+    }
+    Some((name, bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_uu_lines, uu_value, UuencodeDecoder};
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn successful_decoding() {
+        let uuencode_decoder = Decoder::<UuencodeDecoder>::new();
+        let result = uuencode_decoder.crack(
+            "begin 644 hello.txt\n+:&5L;&\\@=V]R;&0`\n`\nend\n",
+            &get_athena_checker(),
+        );
+        assert!(result.success);
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+        assert!(result.description.contains("hello.txt"));
+    }
+
+    #[test]
+    fn multiple_lines_are_joined() {
+        let text = "begin 600 fox.txt\nM5&AE(\'%U:6-K(&)R;W=N(&9O>\"!J=6UP<R!O=F5R(\'1H92!L87IY(&1O9R!A\nC;F0@:V5E<\',@<G5N;FEN9R!F87(@:6YT;R!T:&4@;FEG:\'0`\n`\nend";
+        let (name, bytes) = decode_uu_lines(text, uu_value).unwrap();
+        assert_eq!(name.as_deref(), Some("fox.txt"));
+        assert_eq!(
+            bytes,
+            b"The quick brown fox jumps over the lazy dog and keeps running far into the night"
+        );
+    }
+
+    #[test]
+    fn bodies_without_a_header_are_decoded() {
+        let (name, bytes) = decode_uu_lines("+:&5L;&\\@=V]R;&0`", uu_value).unwrap();
+        assert_eq!(name, None);
+        assert_eq!(bytes, b"hello world");
+    }
+
+    #[test]
+    fn stripped_trailing_spaces_are_restored_after_a_header() {
+        // The padding of the last group would be a space, which some mailers strip
+        let (_, bytes) = decode_uu_lines("begin 644 a\n!80\n`\nend", uu_value).unwrap();
+        assert_eq!(bytes, b"a");
+    }
+
+    #[test]
+    fn lines_of_the_wrong_length_need_a_header() {
+        assert!(decode_uu_lines("!80", uu_value).is_none());
+    }
+
+    #[test]
+    fn ordinary_text_is_not_decoded() {
+        let uuencode_decoder = Decoder::<UuencodeDecoder>::new();
+        let result = uuencode_decoder
+            .crack("HELLO WORLD", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn uuencode_handle_panic_if_empty_string() {
+        let uuencode_decoder = Decoder::<UuencodeDecoder>::new();
+        let result = uuencode_decoder
+            .crack("", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn uuencode_handle_panic_if_emoji() {
+        let uuencode_decoder = Decoder::<UuencodeDecoder>::new();
+        let result = uuencode_decoder
+            .crack("😂", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+}
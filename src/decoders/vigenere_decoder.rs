@@ -1,18 +1,27 @@
 //! Vigenère cipher decoder with automated key detection
-//! Uses Index of Coincidence (IoC) for key length detection and frequency analysis for key discovery
-//! Returns Option<String> with the decrypted text if successful
+//! Uses Kasiski examination and the Index of Coincidence (IoC) for key length detection
+//! and frequency analysis for key discovery
+//! Returns the top candidate plaintexts, with the recovered key in the description
 //! Uses Medium sensitivity for gibberish detection as the default.
 
 use super::crack_results::CrackResult;
 use super::interface::{Crack, Decoder};
 use crate::checkers::CheckerTypes;
+use crate::decoders::frequency_analysis::{
+    column, english_score, estimate_key_lengths, letter_indices, ranked_shifts, shortest_period,
+};
 use crate::decoders::interface::check_string_success;
-use crate::storage::ENGLISH_FREQS;
 use gibberish_or_not::Sensitivity;
 use log::{debug, info, trace};
 
-/// Expected Index of Coincidence for English text
-const EXPECTED_IOC: f64 = 0.0667;
+/// The longest key we try, longer keys need far more ciphertext to break
+const MAX_KEY_LENGTH: usize = 20;
+
+/// How many of the most likely key lengths we recover a key for
+const KEY_LENGTHS_TO_TRY: usize = 5;
+
+/// How many candidate plaintexts we return
+const TOP_CANDIDATES: usize = 3;
 
 /// The Vigenère decoder struct
 pub struct VigenereDecoder;
@@ -21,7 +30,7 @@ impl Crack for Decoder<VigenereDecoder> {
     fn new() -> Decoder<VigenereDecoder> {
         Decoder {
             name: "Vigenere",
-            description: "A polyalphabetic substitution cipher using a keyword to shift each letter. This implementation automatically detects the key length and breaks the cipher. Uses Medium sensitivity for gibberish detection.",
+            description: "A polyalphabetic substitution cipher using a keyword to shift each letter. This implementation automatically detects the key length with Kasiski examination and the Index of Coincidence, then breaks the cipher with frequency analysis. Uses Medium sensitivity for gibberish detection.",
             link: "https://en.wikipedia.org/wiki/Vigen%C3%A8re_cipher",
             tags: vec!["substitution", "classical"],
             popularity: 0.6,
@@ -29,55 +38,71 @@ impl Crack for Decoder<VigenereDecoder> {
         }
     }
 
+    /// Estimates the most likely key lengths with Kasiski examination and the Index of
+    /// Coincidence, recovers a key for each with frequency analysis and returns the
+    /// most English-like plaintexts, best first.
+    /// The plaintext the checker identifies is moved to the front and its key is
+    /// recorded in the description.
     fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
         trace!("Attempting Vigenère decryption on text: {:?}", text);
         let mut results = CrackResult::new(self, text.to_string());
 
-        // Clean the input text (remove non-alphabetic characters)
-        let clean_text: String = text.chars().filter(|c| c.is_ascii_alphabetic()).collect();
-
-        if clean_text.is_empty() {
+        let letters = letter_indices(text);
+        if letters.is_empty() {
             debug!("No valid characters found in input text");
             return results;
         }
 
-        // Try key lengths from 1 to 20 (typical Vigenère key length range)
-        let mut best_key_length = 0;
-        let mut best_ioc = 0.0;
-
-        for key_length in 1..=20 {
-            let ioc = calculate_average_ioc(&clean_text, key_length);
-            if (ioc - EXPECTED_IOC).abs() < (best_ioc - EXPECTED_IOC).abs() {
-                best_ioc = ioc;
-                best_key_length = key_length;
+        // Different key lengths often recover the same key repeated,
+        // so only keep one candidate per distinct key
+        let mut candidates: Vec<(String, String)> = Vec::new();
+        for key_length in estimate_key_lengths(&letters, MAX_KEY_LENGTH, KEY_LENGTHS_TO_TRY) {
+            let key = shortest_period(&find_key(&letters, key_length));
+            if candidates
+                .iter()
+                .any(|(candidate_key, _)| *candidate_key == key)
+            {
+                continue;
+            }
+            let plaintext = decrypt(text, &key);
+            if check_string_success(&plaintext, text) {
+                candidates.push((key, plaintext));
             }
         }
 
-        if best_key_length == 0 {
-            debug!("Failed to determine key length");
-            return results;
-        }
-
-        // Find the key using frequency analysis
-        let key = find_key(&clean_text, best_key_length);
-
-        // Decrypt using the found key
-        let decrypted = decrypt(&clean_text, &key);
-
-        // Reconstruct original formatting
-        let final_text = reconstruct_formatting(text, &decrypted);
-
-        if !check_string_success(&final_text, text) {
+        if candidates.is_empty() {
             info!("Failed Vigenère decoding validation");
             return results;
         }
 
+        candidates.sort_by(|a, b| english_score(&a.1).total_cmp(&english_score(&b.1)));
+        candidates.truncate(TOP_CANDIDATES);
+
         // Use Medium sensitivity for Vigenere decoder
         let checker_with_sensitivity = checker.with_sensitivity(Sensitivity::Medium);
-        let checker_result = checker_with_sensitivity.check(&final_text);
+        let mut checker_result = None;
+        for index in 0..candidates.len() {
+            let result = checker_with_sensitivity.check(&candidates[index].1);
+            if result.is_identified {
+                candidates.swap(0, index);
+                checker_result = Some(result);
+                break;
+            }
+        }
 
-        results.unencrypted_text = Some(vec![final_text]);
-        results.update_checker(&checker_result);
+        let key = candidates[0].0.clone();
+        debug!("Vigenère recovered key {}", key);
+        results.description = format!("{} Recovered key: {}", self.description, key);
+        results.key = Some(key);
+        results.unencrypted_text = Some(
+            candidates
+                .into_iter()
+                .map(|(_, plaintext)| plaintext)
+                .collect(),
+        );
+        if let Some(checker_result) = checker_result {
+            results.update_checker(&checker_result);
+        }
 
         results
     }
@@ -99,90 +124,37 @@ impl Crack for Decoder<VigenereDecoder> {
     fn get_link(&self) -> &str {
         self.link
     }
-}
-
-/// Calculate Index of Coincidence for text split into key_length columns
-fn calculate_average_ioc(text: &str, key_length: usize) -> f64 {
-    let mut total_ioc = 0.0;
-    let text_bytes: Vec<u8> = text.bytes().collect();
-
-    for i in 0..key_length {
-        let mut freqs = [0; 26];
-        let mut count = 0;
-
-        for j in (i..text_bytes.len()).step_by(key_length) {
-            if text_bytes[j].is_ascii_uppercase() {
-                freqs[(text_bytes[j] - b'A') as usize] += 1;
-                count += 1;
-            } else if text_bytes[j].is_ascii_lowercase() {
-                freqs[(text_bytes[j] - b'a') as usize] += 1;
-                count += 1;
-            }
-        }
-
-        if count > 1 {
-            let mut column_ioc = 0.0;
-            for freq in freqs.iter() {
-                column_ioc += (*freq as f64) * ((*freq - 1) as f64);
-            }
-            column_ioc /= (count * (count - 1)) as f64;
-            total_ioc += column_ioc;
-        }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some(("Tx iof elq prdx at gtqqg, ve amg gsi icedx at gtqqg, ve amg gsi mur zj iwfosy, wg hee hup ess bq jacytwtbrdw, uh jlw fvr ptaqu zj nsytir, wg hee hup ibcps sr wanvqrhwmfm, ve amg gsi esndsz cs wmsvg, tx iof elq grlwab bq hmfxyieg", "It was the best of times, it was the worst of times, it was the age of wisdom, it was the age of foolishness, it was the epoch of belief, it was the epoch of incredulity, it was the season of light, it was the season of darkness"))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input must contain at least one ASCII letter",
+            "Tries the five most likely key lengths from 1 to 20",
+            "Decoded text must differ from the input",
+        ]
     }
-
-    total_ioc / key_length as f64
 }
 
 /// Find the encryption key using frequency analysis
-fn find_key(text: &str, key_length: usize) -> String {
-    let mut key = String::with_capacity(key_length);
-    let text_bytes: Vec<u8> = text.bytes().collect();
-
-    for i in 0..key_length {
-        let mut freqs = [0.0; 26];
-        let mut count = 0;
-
-        // Calculate frequency distribution for this column
-        for j in (i..text_bytes.len()).step_by(key_length) {
-            if text_bytes[j].is_ascii_alphabetic() {
-                let idx = (text_bytes[j].to_ascii_uppercase() - b'A') as usize;
-                freqs[idx] += 1.0;
-                count += 1;
-            }
-        }
-
-        // Normalize frequencies
-        if count > 0 {
-            for freq in freqs.iter_mut() {
-                *freq /= count as f64;
-            }
-        }
-
-        // Try each possible shift and calculate chi-squared statistic
-        let mut best_shift = 0;
-        let mut best_chi_squared = f64::MAX;
-
-        for shift in 0..26 {
-            let mut chi_squared = 0.0;
-            for j in 0..26 {
-                let expected = ENGLISH_FREQS[j];
-                let observed = freqs[(j + shift) % 26];
-                let diff = observed - expected;
-                chi_squared += diff * diff / expected;
-            }
-            if chi_squared < best_chi_squared {
-                best_chi_squared = chi_squared;
-                best_shift = shift;
-            }
-        }
-
-        key.push((b'A' + best_shift as u8) as char);
-    }
-
-    key
+/// Each column of the ciphertext is a Caesar shift, so the most English-like shift is its key letter
+fn find_key(letters: &[u8], key_length: usize) -> String {
+    (0..key_length)
+        .map(|i| {
+            let best_shift = ranked_shifts(&column(letters, i, key_length))[0].0;
+            (b'A' + best_shift) as char
+        })
+        .collect()
 }
 
 /// Decrypt text using the found key
+/// Only letters are decrypted and advance the key, so the original formatting is kept
 fn decrypt(text: &str, key: &str) -> String {
     let key_bytes: Vec<u8> = key.bytes().collect();
     let mut result = String::with_capacity(text.len());
@@ -190,11 +162,10 @@ fn decrypt(text: &str, key: &str) -> String {
 
     for c in text.chars() {
         if c.is_ascii_alphabetic() {
-            let shift = (key_bytes[key_idx % key_bytes.len()] - b'A') as i8;
+            let shift = key_bytes[key_idx % key_bytes.len()] - b'A';
             let base = if c.is_ascii_uppercase() { b'A' } else { b'a' };
-            let pos = ((c as u8) - base) as i8;
-            let new_pos = ((pos - shift + 26) % 26) as u8;
-            result.push((base + new_pos) as char);
+            let pos = (c as u8) - base;
+            result.push((base + (pos + 26 - shift) % 26) as char);
             key_idx += 1;
         } else {
             result.push(c);
@@ -204,24 +175,6 @@ fn decrypt(text: &str, key: &str) -> String {
     result
 }
 
-/// Reconstruct original text formatting
-fn reconstruct_formatting(original: &str, decrypted: &str) -> String {
-    let mut result = String::with_capacity(original.len());
-    let mut dec_iter = decrypted.chars().filter(|c| c.is_ascii_alphabetic());
-
-    for c in original.chars() {
-        if c.is_ascii_alphabetic() {
-            if let Some(dec_char) = dec_iter.next() {
-                result.push(dec_char);
-            }
-        } else {
-            result.push(c);
-        }
-    }
-
-    result
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -281,4 +234,17 @@ mod tests {
             .unencrypted_text;
         assert!(result.is_none());
     }
+
+    #[test]
+    fn test_vigenere_recovers_key() {
+        let vigenere_decoder = Decoder::<VigenereDecoder>::new();
+        let (input, expected) = vigenere_decoder.get_example().unwrap();
+        let result = vigenere_decoder.crack(input, &get_athena_checker());
+
+        assert_eq!(result.key.as_deref(), Some("LEMON"));
+        assert!(result.description.ends_with("Recovered key: LEMON"));
+        let candidates = result.unencrypted_text.unwrap();
+        assert!(candidates.len() <= TOP_CANDIDATES);
+        assert_eq!(candidates[0], expected);
+    }
 }
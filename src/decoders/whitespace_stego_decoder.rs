@@ -0,0 +1,244 @@
+//! Decode messages hidden in the spaces and tabs at the ends of lines
+//! Whitespace steganography writes a message's bits as spaces and tabs where nobody looks,
+//! after the last word of each line, or as lines of nothing but whitespace. The spaces and
+//! tabs ending every line are read in order, eight to a byte, with a space as 0 and a tab
+//! as 1 and then the other way round.
+//! Call whitespace_stego_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::check_string_success;
+use crate::decoders::radix::bytes_to_text;
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::Decoder;
+
+use log::{debug, trace};
+
+/// The fewest spaces and tabs worth decoding, one byte's worth of bits
+const MIN_BITS: usize = 8;
+
+/// The whitespace steganography decoder, call:
+/// `let whitespace_stego_decoder = Decoder::<WhitespaceStegoDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = whitespace_stego_decoder.crack(input)` to decode a message hidden in trailing whitespace
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::whitespace_stego_decoder::{WhitespaceStegoDecoder};
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_whitespace = Decoder::<WhitespaceStegoDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// // "hi" as 01101000 01101001, a byte after each line with tabs as 1
+/// let hidden = "roses are red \t\t \t   \nviolets are blue \t\t \t  \t";
+/// let result = decode_whitespace.crack(hidden, &checker).unencrypted_text.unwrap();
+/// assert!(result.contains(&"hi".to_string()));
+/// ```
+pub struct WhitespaceStegoDecoder;
+
+impl Crack for Decoder<WhitespaceStegoDecoder> {
+    fn new() -> Decoder<WhitespaceStegoDecoder> {
+        Decoder {
+            name: "Whitespace steganography",
+            description: "A message hidden as bits in the spaces and tabs at the ends of lines, with a space standing for 0 and a tab for 1 or the other way round.",
+            link: "https://darkside.com.au/snow/",
+            tags: vec!["whitespace", "steganography", "decoder"],
+            popularity: 0.3,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying whitespace steganography with text {:?}", text);
+        let mut results = CrackResult::new(self, text.to_string());
+
+        let Some(whitespace) = trailing_whitespace(text) else {
+            debug!("Failed to decode whitespace steganography because no line ends in tabs");
+            return results;
+        };
+
+        let mut candidates = Vec::new();
+        let mut keys = Vec::new();
+        for tab in [1, 0] {
+            let Some(decoded_text) = whitespace_to_bytes(&whitespace, tab).and_then(bytes_to_text)
+            else {
+                continue;
+            };
+            if !check_string_success(&decoded_text, text) || candidates.contains(&decoded_text) {
+                continue;
+            }
+            let key = format!("space={} tab={}", 1 - tab, tab);
+            let checker_result = checker.check(&decoded_text);
+            if checker_result.is_identified && !results.success {
+                // The match goes first, and the other reading is still returned
+                candidates.insert(0, decoded_text);
+                keys.insert(0, key);
+                results.update_checker(&checker_result);
+            } else {
+                candidates.push(decoded_text);
+                keys.push(key);
+            }
+        }
+        if candidates.is_empty() {
+            debug!("Failed to decode whitespace steganography because no reading gave text");
+            return results;
+        }
+        if results.success {
+            results.key = keys.into_iter().next();
+        }
+        results.unencrypted_text = Some(candidates);
+
+        results
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some((
+            "roses are red \t\t \t   \nviolets are blue \t\t \t  \t",
+            "hi",
+        ))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "The lines must end in at least 8 spaces and tabs altogether, with at least one tab",
+            "The spaces and tabs must make a whole number of bytes",
+        ]
+    }
+}
+
+/// Whether the lines end in whole bytes' worth of spaces and tabs, with at least one tab
+/// Text which reads as plaintext is still cracked when this is true.
+pub fn hides_whitespace(text: &str) -> bool {
+    trailing_whitespace(text).is_some_and(|whitespace| whitespace.len().is_multiple_of(8))
+}
+
+/// The spaces and tabs ending each line, in order, None if there aren't enough or no tabs
+fn trailing_whitespace(text: &str) -> Option<Vec<char>> {
+    let whitespace: Vec<char> = text
+        .lines()
+        .flat_map(|line| {
+            let kept = line.trim_end_matches([' ', '\t']).len();
+            line[kept..].chars()
+        })
+        .collect();
+    (whitespace.len() >= MIN_BITS && whitespace.contains(&'\t')).then_some(whitespace)
+}
+
+/// Reads the whitespace eight to a byte, a tab giving the bit `tab` and a space the other
+fn whitespace_to_bytes(whitespace: &[char], tab: u8) -> Option<Vec<u8>> {
+    if !whitespace.len().is_multiple_of(8) {
+        return None;
+    }
+    Some(
+        whitespace
+            .chunks(8)
+            .map(|byte| {
+                byte.iter().fold(0, |value, c| {
+                    value << 1 | if *c == '\t' { tab } else { 1 - tab }
+                })
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{trailing_whitespace, whitespace_to_bytes, WhitespaceStegoDecoder};
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    /// Hides a byte of the text after each line of the cover, tabs as `tab`
+    fn hide(cover: &str, text: &str, tab: u8) -> String {
+        cover
+            .lines()
+            .zip(text.bytes())
+            .map(|(line, byte)| {
+                let bits: String = (0..8)
+                    .rev()
+                    .map(|i| if byte >> i & 1 == tab { '\t' } else { ' ' })
+                    .collect();
+                format!("{}{}", line, bits)
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// A cover text with a line for every character of "hello world"
+    const COVER: &str = "one\ntwo\nthree\nfour\nfive\nsix\nseven\neight\nnine\nten\neleven";
+
+    #[test]
+    fn tabs_as_ones_decode() {
+        let decoder = Decoder::<WhitespaceStegoDecoder>::new();
+        let result = decoder.crack(&hide(COVER, "hello world", 1), &get_athena_checker());
+        assert!(result.success);
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+        assert_eq!(result.key.unwrap(), "space=0 tab=1");
+    }
+
+    #[test]
+    fn tabs_as_zeros_decode() {
+        let decoder = Decoder::<WhitespaceStegoDecoder>::new();
+        let result = decoder.crack(&hide(COVER, "hello world", 0), &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+        assert_eq!(result.key.unwrap(), "space=1 tab=0");
+    }
+
+    #[test]
+    fn lines_of_only_whitespace_are_read_too() {
+        let whitespace = trailing_whitespace(" \t\t \t   \n \t\t \t  \t").unwrap();
+        assert_eq!(whitespace_to_bytes(&whitespace, 1).unwrap(), b"hi");
+    }
+
+    #[test]
+    fn whitespace_needs_tabs_and_whole_bytes() {
+        assert!(trailing_whitespace("hello world        \nbye").is_none());
+        assert!(trailing_whitespace("hello\t").is_none());
+        let whitespace = trailing_whitespace("a \t\t \t   \t").unwrap();
+        assert!(whitespace_to_bytes(&whitespace, 1).is_none());
+    }
+
+    #[test]
+    fn whitespace_rejects_empty_string() {
+        let decoder = Decoder::<WhitespaceStegoDecoder>::new();
+        let result = decoder.crack("", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+}
@@ -0,0 +1,397 @@
+//! Cracks single-byte and repeating-key XOR
+//! Single-byte XOR is brute forced. For repeating-key XOR the key size is found with
+//! the normalised Hamming distance between blocks, and each byte of the key is then
+//! found by treating its column as single-byte XOR.
+//! The input is either hex, or raw text that contains unprintable characters.
+//! Call xor_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::frequency_analysis::{english_byte_fitness, hamming_distance};
+use crate::decoders::interface::check_string_success;
+use crate::decoders::radix::decode_hex;
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::Decoder;
+
+use log::{debug, trace};
+use std::time::Duration;
+
+/// The shortest ciphertext we try, shorter inputs give too many false positives
+const MIN_LENGTH: usize = 4;
+
+/// The longest repeating key we look for
+const MAX_KEY_SIZE: usize = 40;
+
+/// How many of the most likely key sizes we recover a key for, along with their divisors
+const KEY_SIZES_TO_TRY: usize = 5;
+
+/// How many blocks we compare when scoring a key size, to bound the work on long inputs
+const MAX_BLOCKS: usize = 64;
+
+/// How many of the best single-byte keys we keep as candidates
+const SINGLE_BYTE_KEYS_TO_TRY: usize = 3;
+
+/// How many candidate plaintexts we return when none are identified
+const TOP_CANDIDATES: usize = 5;
+
+/// The share of a candidate which has to be printable for it to be considered
+const MIN_PRINTABLE_RATIO: f64 = 0.95;
+
+/// Stop running checkers when less than this is left before the search times out
+const MIN_TIME_TO_CHECK: Duration = Duration::from_millis(250);
+
+/// The XOR decoder, call:
+/// `let xor_decoder = Decoder::<XorDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = xor_decoder.crack(input)` to crack XOR encrypted hex or text
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::xor_decoder::{XorDecoder};
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_xor = Decoder::<XorDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_xor.crack("1b37373331363f78151b7f2b783431333d78397828372d363c78373e783a393b3736", &checker);
+/// assert_eq!(result.unencrypted_text.unwrap()[0], "Cooking MC's like a pound of bacon");
+/// assert_eq!(result.key.unwrap(), "X");
+/// ```
+pub struct XorDecoder;
+
+impl Crack for Decoder<XorDecoder> {
+    fn new() -> Decoder<XorDecoder> {
+        Decoder {
+            name: "XOR",
+            description: "XOR encryption combines each byte of the plaintext with a byte of the key using exclusive or. This decoder brute forces single-byte keys and breaks repeating keys using Hamming distance and frequency analysis.",
+            link: "https://en.wikipedia.org/wiki/XOR_cipher",
+            tags: vec!["xor", "decryption", "classical"],
+            popularity: 0.4,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// This function does the actual decoding
+    /// Candidates are run through the checker shortest key first, and the key of the
+    /// identified candidate is stored in the result.
+    /// If nothing is identified, the most English-like candidates are returned.
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying XOR with text {:?}", text);
+        let mut results = CrackResult::new(self, text.to_string());
+
+        let ciphertext = match ciphertext_bytes(text) {
+            Some(bytes) => bytes,
+            None => {
+                debug!("XOR skipped text which is neither hex nor unprintable");
+                return results;
+            }
+        };
+
+        let mut candidates: Vec<(Vec<u8>, String)> = Vec::new();
+        for key in candidate_keys(&ciphertext) {
+            let plaintext = match decrypt(&ciphertext, &key) {
+                Some(plaintext) => plaintext,
+                None => continue,
+            };
+            if !check_string_success(&plaintext, text)
+                || candidates.iter().any(|(_, seen)| *seen == plaintext)
+            {
+                continue;
+            }
+            candidates.push((key, plaintext));
+        }
+
+        if candidates.is_empty() {
+            debug!("Failed to crack XOR because no key gave printable text");
+            return results;
+        }
+
+        // Check the shortest keys first, as a longer key can always be made to fit
+        // the text a little better and we want the simplest key that works
+        candidates.sort_by(|a, b| {
+            a.0.len().cmp(&b.0.len()).then(
+                english_byte_fitness(b.1.as_bytes())
+                    .total_cmp(&english_byte_fitness(a.1.as_bytes())),
+            )
+        });
+
+        for (key, plaintext) in &candidates {
+            // Checking is the slow part, so stop and return the fittest candidates when time is short
+            if self
+                .remaining_time()
+                .is_some_and(|left| left < MIN_TIME_TO_CHECK)
+            {
+                debug!("XOR is almost out of time, returning its best candidates so far");
+                results.timed_out = true;
+                break;
+            }
+            let checker_result = checker.check(plaintext);
+            if checker_result.is_identified {
+                trace!("XOR identified plaintext with key {:?}", key);
+                results.unencrypted_text = Some(vec![plaintext.clone()]);
+                results.key = Some(format_key(key));
+                results.update_checker(&checker_result);
+                return results;
+            }
+        }
+
+        candidates.sort_by(|a, b| {
+            english_byte_fitness(b.1.as_bytes()).total_cmp(&english_byte_fitness(a.1.as_bytes()))
+        });
+        candidates.truncate(TOP_CANDIDATES);
+        results.key = Some(format_key(&candidates[0].0));
+        results.unencrypted_text = Some(
+            candidates
+                .into_iter()
+                .map(|(_, plaintext)| plaintext)
+                .collect(),
+        );
+        results
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some((
+            "1b37373331363f78151b7f2b783431333d78397828372d363c78373e783a393b3736",
+            "Cooking MC's like a pound of bacon",
+        ))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input must be hex, or text containing unprintable characters",
+            "Ciphertext must be at least 4 bytes",
+            "Repeating keys are tried up to 40 bytes long",
+            "At least 95% of the plaintext must be printable",
+        ]
+    }
+}
+
+/// Gets the bytes to crack from the input
+/// Hex is decoded, and other text is only worth cracking if it has unprintable characters
+/// in it, as XOR of ordinary text with a printable key is usually also printable.
+fn ciphertext_bytes(text: &str) -> Option<Vec<u8>> {
+    let trimmed = text.trim();
+    let bytes = if let Some(bytes) = decode_hex(trimmed) {
+        bytes
+    } else if text
+        .chars()
+        .any(|c| c.is_control() && !c.is_ascii_whitespace())
+    {
+        text.as_bytes().to_vec()
+    } else {
+        return None;
+    };
+    if bytes.len() < MIN_LENGTH {
+        return None;
+    }
+    Some(bytes)
+}
+
+/// Encodes bytes as lowercase hex
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Every key worth trying: the best single-byte keys, then a repeating key for each
+/// likely key size
+fn candidate_keys(ciphertext: &[u8]) -> Vec<Vec<u8>> {
+    let mut keys: Vec<Vec<u8>> = ranked_single_byte_keys(ciphertext)
+        .into_iter()
+        .take(SINGLE_BYTE_KEYS_TO_TRY)
+        .map(|key| vec![key])
+        .collect();
+    for key_size in likely_key_sizes(ciphertext) {
+        let key: Vec<u8> = (0..key_size)
+            .map(|offset| {
+                let column: Vec<u8> = ciphertext
+                    .iter()
+                    .skip(offset)
+                    .step_by(key_size)
+                    .copied()
+                    .collect();
+                ranked_single_byte_keys(&column)[0]
+            })
+            .collect();
+        if !keys.contains(&key) {
+            keys.push(key);
+        }
+    }
+    keys
+}
+
+/// Scores all 255 non-zero single-byte keys, most English-like plaintext first
+fn ranked_single_byte_keys(ciphertext: &[u8]) -> Vec<u8> {
+    let mut scored: Vec<(u8, f64)> = (1..=255u8)
+        .map(|key| {
+            let plaintext: Vec<u8> = ciphertext.iter().map(|b| b ^ key).collect();
+            (key, english_byte_fitness(&plaintext))
+        })
+        .collect();
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+    scored.into_iter().map(|(key, _)| key).collect()
+}
+
+/// Estimates the most likely repeating key sizes
+/// Blocks of ciphertext encrypted with the same key are closer together in Hamming
+/// distance than random bytes, so the key sizes with the smallest normalised distance
+/// between blocks are most likely. Multiples of the real key size score just as well,
+/// so the divisors of the best sizes are tried too. Returned smallest first.
+fn likely_key_sizes(ciphertext: &[u8]) -> Vec<usize> {
+    let max_key_size = MAX_KEY_SIZE.min(ciphertext.len() / 2);
+    let mut scored: Vec<(usize, f64)> = (2..=max_key_size)
+        .map(|key_size| (key_size, normalised_block_distance(ciphertext, key_size)))
+        .collect();
+    scored.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+    let mut key_sizes: Vec<usize> = scored
+        .into_iter()
+        .take(KEY_SIZES_TO_TRY)
+        .flat_map(|(key_size, _)| (2..=key_size).filter(move |d| key_size.is_multiple_of(*d)))
+        .collect();
+    key_sizes.sort_unstable();
+    key_sizes.dedup();
+    key_sizes
+}
+
+/// Averages the Hamming distance between every pair of the first blocks, per byte
+fn normalised_block_distance(ciphertext: &[u8], key_size: usize) -> f64 {
+    let blocks: Vec<&[u8]> = ciphertext.chunks_exact(key_size).take(MAX_BLOCKS).collect();
+    let mut total = 0.0;
+    let mut pairs = 0;
+    for (i, first) in blocks.iter().enumerate() {
+        for second in &blocks[i + 1..] {
+            total += hamming_distance(first, second) as f64 / key_size as f64;
+            pairs += 1;
+        }
+    }
+    if pairs == 0 {
+        return f64::MAX;
+    }
+    total / pairs as f64
+}
+
+/// XORs the ciphertext with the repeating key
+/// Returns None unless the result is valid UTF-8 and nearly all printable
+fn decrypt(ciphertext: &[u8], key: &[u8]) -> Option<String> {
+    let plaintext: Vec<u8> = ciphertext
+        .iter()
+        .zip(key.iter().cycle())
+        .map(|(b, k)| b ^ k)
+        .collect();
+    let printable = plaintext
+        .iter()
+        .filter(|b| b.is_ascii_graphic() || b.is_ascii_whitespace())
+        .count();
+    if (printable as f64) < MIN_PRINTABLE_RATIO * plaintext.len() as f64 {
+        return None;
+    }
+    String::from_utf8(plaintext).ok()
+}
+
+/// Shows the key as text if it is printable, otherwise as hex
+fn format_key(key: &[u8]) -> String {
+    if key.iter().all(u8::is_ascii_graphic) {
+        String::from_utf8_lossy(key).to_string()
+    } else {
+        format!("0x{}", encode_hex(key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checkers::{
+        athena::Athena,
+        checker_type::{Check, Checker},
+        CheckerTypes,
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    /// Encrypts the plaintext with the repeating key and hex encodes it
+    fn encrypt(plaintext: &str, key: &[u8]) -> String {
+        let bytes: Vec<u8> = plaintext
+            .bytes()
+            .zip(key.iter().cycle())
+            .map(|(b, k)| b ^ k)
+            .collect();
+        encode_hex(&bytes)
+    }
+
+    #[test]
+    fn xor_cracks_single_byte_key() {
+        let decoder = Decoder::<XorDecoder>::new();
+        let result = decoder.crack(
+            "1b37373331363f78151b7f2b783431333d78397828372d363c78373e783a393b3736",
+            &get_athena_checker(),
+        );
+        assert_eq!(
+            result.unencrypted_text.unwrap()[0],
+            "Cooking MC's like a pound of bacon"
+        );
+        assert_eq!(result.key.unwrap(), "X");
+    }
+
+    #[test]
+    fn xor_cracks_repeating_key() {
+        let plaintext = "It was the best of times, it was the worst of times, it was the age of wisdom, it was the age of foolishness, it was the epoch of belief, it was the epoch of incredulity, it was the season of light, it was the season of darkness";
+        let decoder = Decoder::<XorDecoder>::new();
+        let result = decoder.crack(&encrypt(plaintext, b"ICE"), &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], plaintext);
+        assert_eq!(result.key.unwrap(), "ICE");
+    }
+
+    #[test]
+    fn xor_shows_unprintable_keys_as_hex() {
+        assert_eq!(format_key(&[0x01, 0xff]), "0x01ff");
+        assert_eq!(format_key(b"key"), "key");
+    }
+
+    #[test]
+    fn xor_ignores_ordinary_text() {
+        let decoder = Decoder::<XorDecoder>::new();
+        let result = decoder.crack("hello world", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+
+    #[test]
+    fn xor_handle_panic_if_empty_string() {
+        let decoder = Decoder::<XorDecoder>::new();
+        let result = decoder.crack("", &get_athena_checker()).unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn xor_handle_panic_if_emoji() {
+        let decoder = Decoder::<XorDecoder>::new();
+        let result = decoder.crack("😂", &get_athena_checker()).unencrypted_text;
+        assert!(result.is_none());
+    }
+}
@@ -0,0 +1,198 @@
+//! Decodes an xxencoded payload
+//! XXencoding lays its data out exactly like uuencoding, with `begin` and `end` lines
+//! and a length character at the start of every line, but writes six bits as one of
+//! `+-`, the digits and the letters, which survive the gateways that mangled uuencode's
+//! punctuation. The line reading is shared with the Uuencode decoder.
+//! Call xxencode_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::check_string_success;
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::Decoder;
+use super::radix::bytes_to_text;
+use super::uuencode_decoder::decode_uu_lines;
+
+use log::{debug, info, trace};
+
+/// The characters of xxencoding, from 0 to 63
+const ALPHABET: &[u8; 64] = b"+-0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+/// The XXencode decoder, call:
+/// `let xxencode_decoder = Decoder::<XxencodeDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = xxencode_decoder.crack(input)` to decode an xxencoded string
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::xxencode_decoder::{XxencodeDecoder};
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_xxencode = Decoder::<XxencodeDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_xxencode.crack("begin 644 hello.txt\n9O4JgP4wURqxmP4E+\n+\nend\n", &checker);
+/// assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+/// ```
+pub struct XxencodeDecoder;
+
+impl Crack for Decoder<XxencodeDecoder> {
+    fn new() -> Decoder<XxencodeDecoder> {
+        Decoder {
+            name: "XXencode",
+            description: "XXencoding is uuencoding with an alphabet of only +, -, digits and letters, so the data survives mail gateways that change punctuation. Lines start with how many bytes they hold and sit between a begin line and an end line.",
+            link: "https://en.wikipedia.org/wiki/Xxencoding",
+            tags: vec!["xxencode", "decoder", "usenet", "email"],
+            popularity: 0.1,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying XXencode with text {:?}", text);
+        let mut results = CrackResult::new(self, text.to_string());
+
+        let Some((name, bytes)) = decode_uu_lines(text, xx_value) else {
+            debug!("Failed to decode xxencode because the lines aren't xxencoded");
+            return results;
+        };
+        let Some(decoded_text) = bytes_to_text(bytes) else {
+            debug!("Failed to decode xxencode because the payload isn't readable text");
+            return results;
+        };
+        if !check_string_success(&decoded_text, text) {
+            info!(
+                "Failed to decode xxencode because check_string_success returned false on string {}",
+                decoded_text
+            );
+            return results;
+        }
+
+        if let Some(name) = name {
+            results.description = format!("{} The file was named {}.", self.description, name);
+        }
+        let checker_result = checker.check(&decoded_text);
+        results.unencrypted_text = Some(vec![decoded_text]);
+
+        results.update_checker(&checker_result);
+
+        results
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some((
+            "begin 644 hello.txt\n9O4JgP4wURqxmP4E+\n+\nend\n",
+            "hello world",
+        ))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input must either start with a `begin <mode> <name>` line, or have every line exactly as long as its length character says",
+            "Input may only contain `+`, `-`, digits and letters",
+            "Decoded bytes must be valid UTF-8 without control characters",
+        ]
+    }
+}
+
+/// The value of one xxencoded character, None for characters outside the alphabet
+fn xx_value(c: u8) -> Option<u8> {
+    ALPHABET.iter().position(|&x| x == c).map(|v| v as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{xx_value, XxencodeDecoder};
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::{
+            interface::{Crack, Decoder},
+            uuencode_decoder::decode_uu_lines,
+        },
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn successful_decoding() {
+        let xxencode_decoder = Decoder::<XxencodeDecoder>::new();
+        let result = xxencode_decoder.crack(
+            "begin 644 hello.txt\n9O4JgP4wURqxmP4E+\n+\nend\n",
+            &get_athena_checker(),
+        );
+        assert!(result.success);
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+        assert!(result.description.contains("hello.txt"));
+    }
+
+    #[test]
+    fn bodies_without_a_header_are_decoded() {
+        let (name, bytes) = decode_uu_lines("9O4JgP4wURqxmP4E+", xx_value).unwrap();
+        assert_eq!(name, None);
+        assert_eq!(bytes, b"hello world");
+    }
+
+    #[test]
+    fn uuencoded_text_is_not_xxencoded() {
+        let xxencode_decoder = Decoder::<XxencodeDecoder>::new();
+        let result = xxencode_decoder
+            .crack(
+                "begin 644 hello.txt\n+:&5L;&\\@=V]R;&0`\n`\nend\n",
+                &get_athena_checker(),
+            )
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn ordinary_words_are_not_decoded() {
+        let xxencode_decoder = Decoder::<XxencodeDecoder>::new();
+        let result = xxencode_decoder
+            .crack("hello", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn xxencode_handle_panic_if_emoji() {
+        let xxencode_decoder = Decoder::<XxencodeDecoder>::new();
+        let result = xxencode_decoder
+            .crack("😂", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+}
@@ -0,0 +1,283 @@
+//! Decodes a yEnc payload
+//! yEnc was made for binaries on Usenet, where it replaced uuencode. Every byte is sent
+//! as itself plus 42, and the few results which would break a news article (null, line
+//! feeds, carriage returns and `=`) are escaped as `=` and the byte plus 64. The data sits
+//! between a `=ybegin` line with the size and name and a `=yend` line with the size and
+//! usually a CRC32. As the text is read as characters, characters up to U+00FF stand for
+//! the byte with that value, which is how a Latin-1 reading of the article looks.
+//! Call yenc_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::check_string_success;
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::Decoder;
+use super::radix::{bytes_to_text, chars_as_bytes};
+
+use log::{debug, info, trace};
+
+/// What yEnc adds to every byte
+const OFFSET: u8 = 42;
+
+/// What yEnc adds again to bytes written after an `=`
+const ESCAPE_OFFSET: u8 = 64;
+
+/// The yEnc decoder, call:
+/// `let yenc_decoder = Decoder::<YencDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = yenc_decoder.crack(input)` to decode a yEnc string
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::yenc_decoder::{YencDecoder};
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_yenc = Decoder::<YencDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_yenc.crack(
+///     "=ybegin line=128 size=11 name=hello.txt\n\u{92}\u{8f}\u{96}\u{96}\u{99}J\u{a1}\u{99}\u{9c}\u{96}\u{8e}\n=yend size=11 crc32=0d4a1185\n",
+///     &checker,
+/// );
+/// assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+/// ```
+pub struct YencDecoder;
+
+impl Crack for Decoder<YencDecoder> {
+    fn new() -> Decoder<YencDecoder> {
+        Decoder {
+            name: "yEnc",
+            description: "yEnc sends every byte as itself plus 42, escaping the few values that would break a news article with an = sign. It became the usual way to post binaries to Usenet, with a =ybegin line giving the size and name and a =yend line giving the size and a CRC32.",
+            link: "https://en.wikipedia.org/wiki/YEnc",
+            tags: vec!["yenc", "decoder", "usenet"],
+            popularity: 0.1,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying yEnc with text {:?}", text);
+        let mut results = CrackResult::new(self, text.to_string());
+
+        let Some((name, bytes)) = decode_yenc(text) else {
+            debug!("Failed to decode yEnc because there is no valid =ybegin and =yend payload");
+            return results;
+        };
+        let Some(decoded_text) = bytes_to_text(bytes) else {
+            debug!("Failed to decode yEnc because the payload isn't readable text");
+            return results;
+        };
+        if !check_string_success(&decoded_text, text) {
+            info!(
+                "Failed to decode yEnc because check_string_success returned false on string {}",
+                decoded_text
+            );
+            return results;
+        }
+
+        if let Some(name) = name {
+            results.description = format!("{} The file was named {}.", self.description, name);
+        }
+        let checker_result = checker.check(&decoded_text);
+        results.unencrypted_text = Some(vec![decoded_text]);
+
+        results.update_checker(&checker_result);
+
+        results
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some((
+            "=ybegin line=128 size=11 name=hello.txt\n\u{92}\u{8f}\u{96}\u{96}\u{99}J\u{a1}\u{99}\u{9c}\u{96}\u{8e}\n=yend size=11 crc32=0d4a1185\n",
+            "hello world",
+        ))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input must start with a `=ybegin` line and have a `=yend` line after the data",
+            "Data may only contain characters up to U+00FF",
+            "The size and CRC32 on the `=yend` line must match the data when they are given",
+            "Decoded bytes must be valid UTF-8 without control characters",
+        ]
+    }
+}
+
+/// The value of `key` on a yEnc header line, None if it isn't there
+/// The name runs to the end of the line, as file names can have spaces in them.
+fn field<'a>(line: &'a str, key: &str) -> Option<&'a str> {
+    if key == "name" {
+        return line.split_once(" name=").map(|(_, name)| name.trim());
+    }
+    line.split_whitespace()
+        .find_map(|word| word.strip_prefix(key)?.strip_prefix('='))
+}
+
+/// The CRC32 of some bytes, as yEnc and zip files use it
+fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(u32::MAX, |crc, &byte| {
+        (0..8).fold(crc ^ u32::from(byte), |crc, _| {
+            (crc >> 1) ^ (0xEDB8_8320 & (crc & 1).wrapping_neg())
+        })
+    })
+}
+
+/// Decodes a yEnc payload into the file name and bytes, None if it isn't one
+/// The size and checksum are checked when the trailer gives them. For one part of
+/// a multipart post the size and `pcrc32` are of that part.
+fn decode_yenc(text: &str) -> Option<(Option<String>, Vec<u8>)> {
+    let mut lines = text.lines().skip_while(|line| line.trim().is_empty());
+    let header = lines.next()?;
+    if !header.starts_with("=ybegin ") {
+        return None;
+    }
+    let name = field(header, "name").map(str::to_string);
+
+    let mut bytes = Vec::new();
+    let mut multipart = false;
+    let mut trailer = None;
+    for line in lines {
+        if line.starts_with("=ypart ") {
+            multipart = true;
+            continue;
+        }
+        if line.starts_with("=yend") {
+            trailer = Some(line);
+            break;
+        }
+        let mut escaped = false;
+        for byte in chars_as_bytes(line)? {
+            if escaped {
+                bytes.push(byte.wrapping_sub(ESCAPE_OFFSET).wrapping_sub(OFFSET));
+                escaped = false;
+            } else if byte == b'=' {
+                escaped = true;
+            } else {
+                bytes.push(byte.wrapping_sub(OFFSET));
+            }
+        }
+        if escaped {
+            return None;
+        }
+    }
+
+    let trailer = trailer?;
+    if let Some(size) = field(trailer, "size") {
+        if size.parse::<usize>().ok()? != bytes.len() {
+            return None;
+        }
+    }
+    let checksum = field(trailer, if multipart { "pcrc32" } else { "crc32" });
+    if let Some(checksum) = checksum {
+        if u32::from_str_radix(checksum, 16).ok()? != crc32(&bytes) {
+            return None;
+        }
+    }
+    if bytes.is_empty() {
+        return None;
+    }
+    Some((name, bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{crc32, decode_yenc, YencDecoder};
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    /// "hello world" as yEnc data
+    const HELLO_WORLD: &str = "\u{92}\u{8f}\u{96}\u{96}\u{99}J\u{a1}\u{99}\u{9c}\u{96}\u{8e}";
+
+    #[test]
+    fn successful_decoding() {
+        let yenc_decoder = Decoder::<YencDecoder>::new();
+        let text = format!(
+            "=ybegin line=128 size=11 name=hello world.txt\n{HELLO_WORLD}\n=yend size=11 crc32=0d4a1185\n"
+        );
+        let result = yenc_decoder.crack(&text, &get_athena_checker());
+        assert!(result.success);
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+        assert!(result.description.contains("hello world.txt"));
+    }
+
+    #[test]
+    fn escaped_bytes_are_decoded() {
+        // = is 19 before the offset, so it is escaped as = and then 125
+        let text = "=ybegin size=2 name=a\n=}K\n=yend size=2";
+        assert_eq!(decode_yenc(text).unwrap().1, b"\x13!");
+    }
+
+    #[test]
+    fn parts_are_checked_with_their_own_checksum() {
+        let text = format!(
+            "=ybegin part=1 line=128 size=11 name=a\n=ypart begin=1 end=11\n{HELLO_WORLD}\n=yend size=11 part=1 pcrc32=0d4a1185 crc32=00000000"
+        );
+        assert_eq!(decode_yenc(&text).unwrap().1, b"hello world");
+    }
+
+    #[test]
+    fn wrong_sizes_and_checksums_are_rejected() {
+        let size = format!("=ybegin size=11 name=a\n{HELLO_WORLD}\n=yend size=12");
+        assert!(decode_yenc(&size).is_none());
+        let checksum = format!("=ybegin size=11 name=a\n{HELLO_WORLD}\n=yend crc32=0d4a1186");
+        assert!(decode_yenc(&checksum).is_none());
+    }
+
+    #[test]
+    fn a_trailer_is_required() {
+        let text = format!("=ybegin size=11 name=a\n{HELLO_WORLD}\n");
+        assert!(decode_yenc(&text).is_none());
+    }
+
+    #[test]
+    fn crc32_matches_the_usual_checksum() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn yenc_handle_panic_if_emoji() {
+        let yenc_decoder = Decoder::<YencDecoder>::new();
+        let result = yenc_decoder
+            .crack("😂", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+}
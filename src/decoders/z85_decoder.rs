@@ -89,6 +89,21 @@ impl Crack for Decoder<Z85Decoder> {
     fn get_link(&self) -> &str {
         self.link
     }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some(("nm=QNzY&b1A+]nf", "Hello World!"))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input must be valid Z85",
+            "Decoded bytes must be valid UTF-8",
+        ]
+    }
 }
 
 /// helper function
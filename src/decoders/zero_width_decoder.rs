@@ -0,0 +1,380 @@
+//! Decode messages hidden in zero-width characters and Unicode tags
+//! Zero-width steganography hides a message between the letters of a cover text with
+//! characters which take up no space, such as the zero-width space, non-joiner and joiner.
+//! Tools use two of them as the bits 0 and 1, a third to separate the characters, or four
+//! as two bits each, and there is no agreed pairing of characters and bits, so every one is
+//! tried and only the ones giving text are kept. Unicode tag characters, from U+E0020 to
+//! U+E007E, are invisible copies of printable ASCII and are read as the ASCII they copy.
+//! Call zero_width_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::check_string_success;
+use crate::decoders::radix::bytes_to_text;
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::Decoder;
+
+use log::{debug, trace};
+
+/// The characters which take up no space, in the order their bits are assigned
+const ZERO_WIDTH: [char; 9] = [
+    '\u{200B}', '\u{200C}', '\u{200D}', '\u{2060}', '\u{FEFF}', '\u{200E}', '\u{200F}', '\u{202C}',
+    '\u{180E}',
+];
+
+/// The fewest zero-width characters worth decoding, one byte's worth of bits
+const MIN_SYMBOLS: usize = 8;
+
+/// The first tag character, which copies the space at U+0020 with the rest of ASCII
+/// following it
+const TAG_OFFSET: u32 = 0xE0000;
+
+/// The zero-width decoder, call:
+/// `let zero_width_decoder = Decoder::<ZeroWidthDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = zero_width_decoder.crack(input)` to decode a message hidden in zero-width characters
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ciphey::decoders::zero_width_decoder::{ZeroWidthDecoder};
+/// use ciphey::decoders::interface::{Crack, Decoder};
+/// use ciphey::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_zero_width = Decoder::<ZeroWidthDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// // "hi" as bits, with the zero-width space as 0 and the non-joiner as 1
+/// let hidden = "nothing\u{200B}\u{200C}\u{200C}\u{200B}\u{200C}\u{200B}\u{200B}\u{200B}\
+///               \u{200B}\u{200C}\u{200C}\u{200B}\u{200C}\u{200B}\u{200B}\u{200C} here";
+/// let result = decode_zero_width.crack(hidden, &checker).unencrypted_text.unwrap();
+/// assert!(result.contains(&"hi".to_string()));
+///
+/// // Tag characters copy ASCII
+/// let result = decode_zero_width.crack("hi\u{E0068}\u{E0065}\u{E0079}", &checker).unencrypted_text.unwrap();
+/// assert!(result.contains(&"hey".to_string()));
+/// ```
+pub struct ZeroWidthDecoder;
+
+impl Crack for Decoder<ZeroWidthDecoder> {
+    fn new() -> Decoder<ZeroWidthDecoder> {
+        Decoder {
+            name: "Zero-width",
+            description: "A message hidden in a cover text with characters that take up no space, either zero-width spaces, joiners and non-joiners standing for bits, or Unicode tag characters copying ASCII.",
+            link: "https://330k.github.io/misc_tools/unicode_steganography.html",
+            tags: vec!["zero-width", "steganography", "unicode", "decoder"],
+            popularity: 0.4,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying zero-width with text {:?}", text);
+        let mut results = CrackResult::new(self, text.to_string());
+
+        let mut readings = Vec::new();
+        if let Some(tagged) = read_tags(text) {
+            readings.push((String::from("Unicode tags"), tagged));
+        }
+        let symbols: Vec<char> = text.chars().filter(|c| ZERO_WIDTH.contains(c)).collect();
+        if symbols.len() >= MIN_SYMBOLS {
+            readings.extend(zero_width_readings(&symbols));
+        }
+        if readings.is_empty() {
+            debug!("Failed to decode zero-width because there are no hidden characters to read");
+            return results;
+        }
+
+        let mut candidates = Vec::new();
+        let mut keys = Vec::new();
+        for (key, decoded_text) in readings {
+            if !check_string_success(&decoded_text, text) || candidates.contains(&decoded_text) {
+                continue;
+            }
+            let checker_result = checker.check(&decoded_text);
+            if checker_result.is_identified && !results.success {
+                // The match goes first, and every other reading is still returned
+                candidates.insert(0, decoded_text);
+                keys.insert(0, key);
+                results.update_checker(&checker_result);
+            } else {
+                candidates.push(decoded_text);
+                keys.push(key);
+            }
+        }
+        if candidates.is_empty() {
+            debug!("Failed to decode zero-width because no reading gave text");
+            return results;
+        }
+        if results.success {
+            results.key = keys.into_iter().next();
+        }
+        results.unencrypted_text = Some(candidates);
+
+        results
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the description for the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link for the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets the popularity for the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets an example input for the current decoder and what it decodes to
+    fn get_example(&self) -> Option<(&str, &str)> {
+        Some(("hi\u{E0068}\u{E0065}\u{E0079}", "hey"))
+    }
+    /// Gets the conditions the input has to meet for the current decoder
+    fn get_guards(&self) -> &[&str] {
+        &[
+            "Input must hide at least 8 zero-width characters, or Unicode tag characters",
+            "Two different zero-width characters are read as bits, three as bits and a separator between characters, and four as two bits each",
+        ]
+    }
+}
+
+/// Whether the text holds tag characters or enough zero-width characters to hide a message
+/// Text which reads as plaintext is still cracked when this is true.
+pub fn hides_characters(text: &str) -> bool {
+    read_tags(text).is_some()
+        || text.chars().filter(|c| ZERO_WIDTH.contains(c)).count() >= MIN_SYMBOLS
+}
+
+/// The ASCII the tag characters copy, None if there are none
+fn read_tags(text: &str) -> Option<String> {
+    let copied: String = text
+        .chars()
+        .filter_map(|c| {
+            let ascii = (c as u32).checked_sub(TAG_OFFSET)?;
+            (0x20..=0x7E)
+                .contains(&ascii)
+                .then(|| char::from(ascii as u8))
+        })
+        .collect();
+    (!copied.is_empty()).then_some(copied)
+}
+
+/// The name a zero-width character is written with in keys, such as U+200B
+fn code_point(c: char) -> String {
+    format!("U+{:04X}", c as u32)
+}
+
+/// Every reading of the zero-width characters, keyed by which character stands for what
+fn zero_width_readings(symbols: &[char]) -> Vec<(String, String)> {
+    let used: Vec<char> = ZERO_WIDTH
+        .iter()
+        .copied()
+        .filter(|c| symbols.contains(c))
+        .collect();
+    match used.len() {
+        2 => [[0, 1], [1, 0]]
+            .iter()
+            .filter_map(|bits| {
+                let value = |c: &char| bits[usize::from(*c == used[1])];
+                let decoded =
+                    bits_to_bytes(symbols.iter().map(value), 1).and_then(bytes_to_text)?;
+                Some((bit_key(&used, bits), decoded))
+            })
+            .collect(),
+        3 => used
+            .iter()
+            .flat_map(|&separator| {
+                let bit_symbols: Vec<char> =
+                    used.iter().copied().filter(|c| *c != separator).collect();
+                [[0, 1], [1, 0]].into_iter().filter_map(move |bits| {
+                    let decoded = separated_characters(symbols, separator, &bit_symbols, bits)?;
+                    let key = format!(
+                        "{} between characters, {}",
+                        code_point(separator),
+                        bit_key(&bit_symbols, &bits)
+                    );
+                    Some((key, decoded))
+                })
+            })
+            .collect(),
+        4 => two_bit_assignments()
+            .into_iter()
+            .filter_map(|bits| {
+                let value = |c: &char| bits[used.iter().position(|u| u == c).unwrap_or(0)];
+                let decoded =
+                    bits_to_bytes(symbols.iter().map(value), 2).and_then(bytes_to_text)?;
+                Some((bit_key(&used, &bits), decoded))
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Says which character stands for which bits, as in U+200B=0 U+200C=1
+fn bit_key(used: &[char], bits: &[u8]) -> String {
+    let width = if used.len() == 4 { 2 } else { 1 };
+    used.iter()
+        .zip(bits)
+        .map(|(c, bits)| format!("{}={:0width$b}", code_point(*c), bits, width = width))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Packs values of `width` bits into bytes, the first value giving the highest bits
+/// None unless the values fill a whole number of bytes.
+fn bits_to_bytes(values: impl Iterator<Item = u8>, width: usize) -> Option<Vec<u8>> {
+    let values: Vec<u8> = values.collect();
+    let per_byte = 8 / width;
+    if values.is_empty() || !values.len().is_multiple_of(per_byte) {
+        return None;
+    }
+    Some(
+        values
+            .chunks(per_byte)
+            .map(|byte| byte.iter().fold(0, |value, bits| value << width | bits))
+            .collect(),
+    )
+}
+
+/// Reads the bits between each `separator` as one character's code point
+fn separated_characters(
+    symbols: &[char],
+    separator: char,
+    bit_symbols: &[char],
+    bits: [u8; 2],
+) -> Option<String> {
+    let decoded: String = symbols
+        .split(|c| *c == separator)
+        .filter(|group| !group.is_empty())
+        .map(|group| {
+            if group.len() > 21 {
+                return None;
+            }
+            let code = group.iter().fold(0, |code, c| {
+                code << 1 | u32::from(bits[usize::from(*c == bit_symbols[1])])
+            });
+            char::from_u32(code).filter(|c| !c.is_control() || c.is_whitespace())
+        })
+        .collect::<Option<String>>()?;
+    (!decoded.is_empty()).then_some(decoded)
+}
+
+/// Every way of giving four characters their own two bits
+fn two_bit_assignments() -> Vec<[u8; 4]> {
+    (0..=u8::MAX)
+        .map(|n| [n >> 6, (n >> 4) & 3, (n >> 2) & 3, n & 3])
+        .filter(|bits| (0..4).all(|i| !bits[i + 1..].contains(&bits[i])))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_tags, two_bit_assignments, zero_width_readings, ZeroWidthDecoder};
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    /// Hides the text's bits after the cover, writing each bit with `zero` or `one`
+    fn hide(cover: &str, text: &str, zero: char, one: char) -> String {
+        let bits: String = text
+            .bytes()
+            .flat_map(|byte| (0..8).rev().map(move |i| byte >> i & 1))
+            .map(|bit| if bit == 1 { one } else { zero })
+            .collect();
+        format!("{}{}", cover, bits)
+    }
+
+    #[test]
+    fn two_characters_are_read_as_bits() {
+        let decoder = Decoder::<ZeroWidthDecoder>::new();
+        let hidden = hide("Nothing to see", "hello world", '\u{200B}', '\u{200C}');
+        let result = decoder.crack(&hidden, &get_athena_checker());
+        assert!(result.success);
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+        assert_eq!(result.key.unwrap(), "U+200B=0 U+200C=1");
+    }
+
+    #[test]
+    fn either_pairing_of_characters_and_bits_is_tried() {
+        let decoder = Decoder::<ZeroWidthDecoder>::new();
+        let hidden = hide("Nothing to see", "hello world", '\u{200D}', '\u{200B}');
+        let result = decoder.crack(&hidden, &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+        assert_eq!(result.key.unwrap(), "U+200B=1 U+200D=0");
+    }
+
+    #[test]
+    fn a_third_character_separates_characters() {
+        // "hi" as the code points 1101000 and 1101001, separated by the joiner
+        let symbols: Vec<char> = "1101000j1101001"
+            .chars()
+            .map(|c| match c {
+                '0' => '\u{200B}',
+                '1' => '\u{200C}',
+                _ => '\u{200D}',
+            })
+            .collect();
+        let readings = zero_width_readings(&symbols);
+        assert!(readings.iter().any(
+            |(key, text)| text == "hi" && key == "U+200D between characters, U+200B=0 U+200C=1"
+        ));
+    }
+
+    #[test]
+    fn four_characters_are_read_as_two_bits_each() {
+        // "ho" is 01 10 10 00 01 10 11 11
+        let (zero, one, two, three) = ('\u{200B}', '\u{200C}', '\u{200D}', '\u{2060}');
+        let symbols = [one, two, two, zero, one, two, three, three];
+        let readings = zero_width_readings(&symbols);
+        assert!(readings.iter().any(|(_, text)| text == "ho"));
+        assert_eq!(two_bit_assignments().len(), 24);
+    }
+
+    #[test]
+    fn tags_are_read_as_the_ascii_they_copy() {
+        assert_eq!(
+            read_tags("flag\u{E0001}\u{E0068}\u{E0069}\u{E007F}").unwrap(),
+            "hi"
+        );
+        assert!(read_tags("no tags here").is_none());
+    }
+
+    #[test]
+    fn zero_width_rejects_text_without_hidden_characters() {
+        let decoder = Decoder::<ZeroWidthDecoder>::new();
+        let result = decoder.crack("hello world", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+        // An emoji sequence joins its emoji with a zero width joiner
+        let result = decoder.crack("👨‍👩‍👧", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+
+    #[test]
+    fn zero_width_rejects_empty_string() {
+        let decoder = Decoder::<ZeroWidthDecoder>::new();
+        let result = decoder.crack("", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+}
@@ -0,0 +1,348 @@
+//! Events a search publishes as it runs, for showing its progress live
+//! The search publishes what it is doing here instead of each front end reaching into it:
+//! the RPC service streams the events to its clients, library users observe them with
+//! [`crate::perform_cracking_with_observer`], and with `RUST_LOG=trace` every event is
+//! logged. Nothing is built or sent while nobody is subscribed and trace logging
+//! is off, so searches which aren't watched pay nothing for them.
+//!
+//! ```
+//! use ciphey::events::{subscribe, SearchEvent};
+//! let events = subscribe();
+//! ciphey::events::publish(|| SearchEvent::TimedOut);
+//! assert_eq!(events.try_recv(), Ok(SearchEvent::TimedOut));
+//! ```
+
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender, TryRecvError};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::checkers::checker_result::CheckResult;
+use crate::decoders::crack_results::CrackResult;
+use crate::storage::history::RunOutcome;
+use crate::DecoderResult;
+
+/// The most characters of a text an event carries, longer texts are cut short
+const MAX_EVENT_TEXT_CHARS: usize = 200;
+
+/// Every subscriber and the sender its events go to
+static SUBSCRIBERS: Mutex<Vec<(u64, Sender<SearchEvent>)>> = Mutex::new(Vec::new());
+
+/// How many subscribers there are, so publishing can be skipped without locking
+static SUBSCRIBER_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// The id the next subscriber is given
+static NEXT_SUBSCRIBER: AtomicU64 = AtomicU64::new(0);
+
+/// Something which happened during a search
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum SearchEvent {
+    /// A search of the input started, and will stop after `timeout` seconds
+    Started {
+        /// The input being searched, cut short if it is long
+        input: String,
+        /// How many seconds the search may take
+        timeout: u32,
+    },
+    /// The answer was already in the cache, so nothing was searched
+    CacheHit {
+        /// The cached plaintext, cut short if it is long
+        plaintext: String,
+    },
+    /// A decoder ran on a text
+    DecoderAttempted {
+        /// The decoder which ran
+        decoder: &'static str,
+        /// Whether a checker identified what it decoded as plaintext
+        succeeded: bool,
+        /// How long it took in milliseconds, None if its result was remembered from an
+        /// earlier search instead of decoded again
+        runtime_ms: Option<f64>,
+    },
+    /// Some decodes were decoded further
+    NodesExpanded {
+        /// How many decoders deep the search is
+        depth: u32,
+        /// How many decodes were decoded further this time
+        nodes: usize,
+        /// How many decodes are waiting to be decoded further
+        queued: usize,
+    },
+    /// A decode looked more like plaintext than the candidates so far
+    CandidateFound {
+        /// The decoded text, cut short if it is long
+        text: String,
+        /// How much like plaintext it is, from 0.0 to 1.0
+        score: f32,
+        /// The decoders which reached it, in order
+        path: Vec<&'static str>,
+    },
+    /// A checker was confident enough a text is plaintext, before the human checker is asked
+    CheckerSucceeded {
+        /// The checker which identified it
+        checker: &'static str,
+        /// The text, cut short if it is long
+        text: String,
+        /// How confident the checker is, from 0.0 to 1.0
+        confidence: f32,
+    },
+    /// The human checker is asking whether a text is the plaintext, while the search
+    /// carries on
+    HumanCheckerPrompt {
+        /// What the checker thinks the text is
+        description: String,
+        /// The text, cut short if it is long
+        text: String,
+    },
+    /// The human checker's question was answered, or counted as `human_checker_default`
+    /// because nobody answered in time
+    HumanCheckerAnswered {
+        /// The text, cut short if it is long
+        text: String,
+        /// Whether the text is the plaintext
+        accepted: bool,
+    },
+    /// A checker identified a plaintext and the human checker agreed, if it was asked
+    PlaintextFound {
+        /// The plaintext, cut short if it is long
+        text: String,
+        /// The checker which identified it
+        checker: &'static str,
+        /// The decoders which reached it, in order
+        path: Vec<&'static str>,
+    },
+    /// The search ran out of time
+    TimedOut,
+    /// The search was cancelled
+    Cancelled,
+    /// The run ended, however it ended
+    Finished {
+        /// How it ended: solved, cached, plaintext, unsolved or cancelled
+        outcome: &'static str,
+    },
+}
+
+impl SearchEvent {
+    /// A `CandidateFound` event for a candidate scoring `score`
+    pub fn candidate(candidate: &DecoderResult, score: f32) -> Self {
+        SearchEvent::CandidateFound {
+            text: shorten(candidate.text.first().map_or("", String::as_str)),
+            score,
+            path: decoder_names(candidate),
+        }
+    }
+
+    /// A `DecoderAttempted` event for a decoder's result
+    pub fn decoder_attempted(result: &CrackResult) -> Self {
+        SearchEvent::DecoderAttempted {
+            decoder: result.decoder,
+            succeeded: result.success,
+            runtime_ms: result.runtime.map(|runtime| runtime.as_secs_f64() * 1000.0),
+        }
+    }
+
+    /// A `CheckerSucceeded` event for a checker's verdict
+    pub fn checker_succeeded(check: &CheckResult) -> Self {
+        SearchEvent::CheckerSucceeded {
+            checker: check.checker_name,
+            text: shorten(&check.text),
+            confidence: check.confidence,
+        }
+    }
+
+    /// A `PlaintextFound` event for a plaintext
+    pub fn plaintext(result: &DecoderResult) -> Self {
+        SearchEvent::PlaintextFound {
+            text: shorten(result.text.first().map_or("", String::as_str)),
+            checker: result.path.last().map_or("", |step| step.checker_name),
+            path: decoder_names(result),
+        }
+    }
+
+    /// A `Finished` event for a run which ended with `outcome`
+    pub fn finished(outcome: RunOutcome) -> Self {
+        SearchEvent::Finished {
+            outcome: outcome.as_str(),
+        }
+    }
+}
+
+/// The names of the decoders on a result's path
+fn decoder_names(result: &DecoderResult) -> Vec<&'static str> {
+    result.path.iter().map(|step| step.decoder).collect()
+}
+
+/// Cuts a text to `MAX_EVENT_TEXT_CHARS`, so events stay small
+pub fn shorten(text: &str) -> String {
+    match text.char_indices().nth(MAX_EVENT_TEXT_CHARS) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+/// Receives every event published while it exists, and unsubscribes when dropped
+#[derive(Debug)]
+pub struct Subscription {
+    /// Which subscriber this is
+    id: u64,
+    /// Where its events arrive
+    receiver: Receiver<SearchEvent>,
+}
+
+impl Subscription {
+    /// The next event, waiting at most `timeout` for one
+    ///
+    /// # Errors
+    /// Returns `RecvTimeoutError::Timeout` if nothing was published in time.
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<SearchEvent, RecvTimeoutError> {
+        self.receiver.recv_timeout(timeout)
+    }
+
+    /// The next event if one has been published, without waiting
+    ///
+    /// # Errors
+    /// Returns `TryRecvError::Empty` if there isn't one.
+    pub fn try_recv(&self) -> Result<SearchEvent, TryRecvError> {
+        self.receiver.try_recv()
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        let mut subscribers = lock_subscribers();
+        subscribers.retain(|(id, _)| *id != self.id);
+        SUBSCRIBER_COUNT.store(subscribers.len(), Ordering::Relaxed);
+    }
+}
+
+/// Starts receiving every event published from now on
+pub fn subscribe() -> Subscription {
+    let id = NEXT_SUBSCRIBER.fetch_add(1, Ordering::Relaxed);
+    let (sender, receiver) = channel();
+    let mut subscribers = lock_subscribers();
+    subscribers.push((id, sender));
+    SUBSCRIBER_COUNT.store(subscribers.len(), Ordering::Relaxed);
+    Subscription { id, receiver }
+}
+
+/// Publishes the event `event` builds to every subscriber
+/// The event is only built if someone is subscribed or trace logging is on.
+pub fn publish(event: impl FnOnce() -> SearchEvent) {
+    let subscribed = SUBSCRIBER_COUNT.load(Ordering::Relaxed) > 0;
+    if !subscribed && !log::log_enabled!(log::Level::Trace) {
+        return;
+    }
+    let event = event();
+    log::trace!("Search event: {:?}", event);
+    if subscribed {
+        for (_, sender) in lock_subscribers().iter() {
+            // A subscription being dropped removes itself, so a failed send can be ignored
+            let _ = sender.send(event.clone());
+        }
+    }
+}
+
+/// Runs `work` on another thread, handing `observer` every event published until it ends
+/// The observer runs on this thread, so it needn't be `Send`.
+pub fn observe<T: Send>(
+    mut observer: impl FnMut(SearchEvent),
+    work: impl FnOnce() -> T + Send,
+) -> T {
+    let subscription = subscribe();
+    std::thread::scope(|scope| {
+        let work = scope.spawn(work);
+        loop {
+            match subscription.recv_timeout(Duration::from_millis(20)) {
+                Ok(event) => observer(event),
+                Err(_) if !work.is_finished() => {}
+                Err(_) => break,
+            }
+        }
+        // Whatever was published just before the work ended is still waiting
+        while let Ok(event) = subscription.try_recv() {
+            observer(event);
+        }
+        work.join()
+            .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+    })
+}
+
+/// Locks the subscribers, recovering them if a thread panicked while holding them
+fn lock_subscribers() -> std::sync::MutexGuard<'static, Vec<(u64, Sender<SearchEvent>)>> {
+    SUBSCRIBERS
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Whether the subscription got `event`, skipping events of searches other tests run
+    fn received(subscription: &Subscription, event: &SearchEvent) -> bool {
+        std::iter::from_fn(|| subscription.try_recv().ok()).any(|got| got == *event)
+    }
+
+    /// An event no search publishes, so a test can tell its own events apart
+    fn test_event(input: &str) -> SearchEvent {
+        SearchEvent::Started {
+            input: input.to_string(),
+            timeout: 0,
+        }
+    }
+
+    #[test]
+    fn every_subscriber_gets_every_event_until_it_unsubscribes() {
+        let first = subscribe();
+        let second = subscribe();
+        publish(|| test_event("to both"));
+        assert!(received(&first, &test_event("to both")));
+        assert!(received(&second, &test_event("to both")));
+
+        let second_id = second.id;
+        drop(second);
+        assert!(!lock_subscribers().iter().any(|(id, _)| *id == second_id));
+        publish(|| test_event("to the first"));
+        assert!(received(&first, &test_event("to the first")));
+    }
+
+    #[test]
+    fn observers_get_the_events_published_while_the_work_runs() {
+        let mut seen = Vec::new();
+        let answer = observe(
+            |event| seen.push(event),
+            || {
+                publish(|| test_event("observed"));
+                42
+            },
+        );
+        assert_eq!(answer, 42);
+        assert!(seen.contains(&test_event("observed")));
+    }
+
+    #[test]
+    fn events_are_written_with_their_name() {
+        let event = SearchEvent::NodesExpanded {
+            depth: 2,
+            nodes: 8,
+            queued: 40,
+        };
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["event"], "nodes_expanded");
+        assert_eq!(json["queued"], 40);
+        assert_eq!(
+            serde_json::to_value(SearchEvent::finished(RunOutcome::Solved)).unwrap()["outcome"],
+            "solved"
+        );
+    }
+
+    #[test]
+    fn long_texts_are_cut_short() {
+        assert_eq!(shorten("short"), "short");
+        let long = "é".repeat(MAX_EVENT_TEXT_CHARS + 10);
+        assert_eq!(shorten(&long).chars().count(), MAX_EVENT_TEXT_CHARS + 1);
+    }
+}
@@ -1,16 +1,23 @@
 //! Proposal: https://broadleaf-angora-7db.notion.site/Filtration-System-7143b36a42f1466faea3077bfc7e859e
 //! Given a filter object, return an array of decoders/crackers which have been filtered
 
+use std::collections::HashSet;
 use std::sync::mpsc::channel;
+use std::sync::Arc;
+use std::time::Instant;
 
-use crate::checkers::CheckerTypes;
+use crate::checkers::{CheckerTypes, CHECKER_MAP};
 use crate::cli_pretty_printing;
+use crate::config::{get_config, Config};
 use crate::decoders::atbash_decoder::AtbashDecoder;
 use crate::decoders::base32_decoder::Base32Decoder;
 use crate::decoders::base58_bitcoin_decoder::Base58BitcoinDecoder;
 use crate::decoders::base58_monero_decoder::Base58MoneroDecoder;
 use crate::decoders::binary_decoder::BinaryDecoder;
 use crate::decoders::hexadecimal_decoder::HexadecimalDecoder;
+use crate::events::{self, SearchEvent};
+use crate::searchers::record_decoder_run;
+use crate::storage::edge_cache;
 use crate::DecoderResult;
 
 use crate::decoders::base58_flickr_decoder::Base58FlickrDecoder;
@@ -24,6 +31,7 @@ use crate::decoders::braille_decoder::BrailleDecoder;
 use crate::decoders::caesar_decoder::CaesarDecoder;
 use crate::decoders::citrix_ctx1_decoder::CitrixCTX1Decoder;
 use crate::decoders::crack_results::CrackResult;
+use crate::decoders::custom::{custom_decoders, SharedDecoder};
 use crate::decoders::interface::{Crack, Decoder};
 use crate::decoders::morse_code::MorseCodeDecoder;
 use crate::decoders::railfence_decoder::RailfenceDecoder;
@@ -34,10 +42,75 @@ use crate::decoders::url_decoder::URLDecoder;
 use crate::decoders::vigenere_decoder::VigenereDecoder;
 use crate::decoders::z85_decoder::Z85Decoder;
 
+use crate::decoders::affine_decoder::AffineDecoder;
+use crate::decoders::ascii85_decoder::Ascii85Decoder;
+use crate::decoders::base122_decoder::Base122Decoder;
+use crate::decoders::base2048_decoder::Base2048Decoder;
+use crate::decoders::base32768_decoder::Base32768Decoder;
+use crate::decoders::base32_variant_decoder::Base32VariantDecoder;
+use crate::decoders::base36_decoder::Base36Decoder;
+use crate::decoders::base45_decoder::Base45Decoder;
+use crate::decoders::base62_decoder::Base62Decoder;
+use crate::decoders::base92_decoder::Base92Decoder;
+use crate::decoders::bcd_decoder::BcdDecoder;
 use crate::decoders::brainfuck_interpreter::BrainfuckInterpreter;
+use crate::decoders::brute_force::brute_force_cipher_decoder::BruteForceCipherDecoder;
+use crate::decoders::bubble_babble_decoder::BubbleBabbleDecoder;
+use crate::decoders::charset_decoder::CharsetDecoder;
+use crate::decoders::classical::adfgvx_decoder::AdfgvxDecoder;
+use crate::decoders::classical::autokey_decoder::AutokeyDecoder;
+use crate::decoders::classical::beaufort_decoder::BeaufortDecoder;
+use crate::decoders::classical::bifid_decoder::BifidDecoder;
+use crate::decoders::classical::enigma_decoder::EnigmaDecoder;
+use crate::decoders::classical::playfair_decoder::PlayfairDecoder;
+use crate::decoders::classical::polybius_decoder::PolybiusDecoder;
+use crate::decoders::classical::tap_code_decoder::TapCodeDecoder;
+use crate::decoders::columnar_transposition_decoder::ColumnarTranspositionDecoder;
+use crate::decoders::compression::brotli_decoder::BrotliDecoder;
+use crate::decoders::compression::bzip2_decoder::Bzip2Decoder;
+use crate::decoders::compression::deflate_decoder::DeflateDecoder;
+use crate::decoders::compression::gzip_decoder::GzipDecoder;
+use crate::decoders::compression::lzma_decoder::LzmaDecoder;
+use crate::decoders::compression::zlib_decoder::ZlibDecoder;
+use crate::decoders::decimal_decoder::DecimalDecoder;
+use crate::decoders::dna_decoder::DnaDecoder;
+use crate::decoders::esolang::jsfuck_decoder::JsfuckDecoder;
+use crate::decoders::esolang::malbolge_decoder::MalbolgeDecoder;
+use crate::decoders::esolang::ook_interpreter::OokInterpreter;
+use crate::decoders::esolang::whitespace_interpreter::WhitespaceInterpreter;
+use crate::decoders::gray_code_decoder::GrayCodeDecoder;
+use crate::decoders::hashes::hash_identifier::HashIdentifier;
+use crate::decoders::hexdump_decoder::HexdumpDecoder;
+use crate::decoders::jwt_decoder::JwtDecoder;
+use crate::decoders::keyboard_shift_decoder::KeyboardShiftDecoder;
+use crate::decoders::keyed::keyed_decryption_decoder::KeyedDecryptionDecoder;
+use crate::decoders::leetspeak_decoder::LeetspeakDecoder;
+use crate::decoders::octal_decoder::OctalDecoder;
+use crate::decoders::phone_keypad_decoder::PhoneKeypadDecoder;
+use crate::decoders::skey_decoder::SkeyDecoder;
+use crate::decoders::substitution_solver::SubstitutionSolver;
+use crate::decoders::text_transform_decoder::TextTransformDecoder;
+use crate::decoders::unicode_escape_decoder::UnicodeEscapeDecoder;
+use crate::decoders::utf7_decoder::Utf7Decoder;
+use crate::decoders::uuencode_decoder::UuencodeDecoder;
+use crate::decoders::whitespace_stego_decoder::WhitespaceStegoDecoder;
+use crate::decoders::xor_decoder::XorDecoder;
+use crate::decoders::xxencode_decoder::XxencodeDecoder;
+use crate::decoders::yenc_decoder::YencDecoder;
+use crate::decoders::zero_width_decoder::ZeroWidthDecoder;
 
 use log::trace;
 use rayon::prelude::*;
+use sha2::{Digest, Sha256};
+
+/// Tags for decoders which only work on text written in a particular alphabet, with the
+/// characters of that alphabet. Decoders with one of these tags are only run on text made
+/// of nothing but those characters and whitespace.
+const ALPHABET_TAGS: [(&str, &str); 3] = [
+    ("digits", "0123456789"),
+    ("adfgvx", "ADFGVXadfgvx"),
+    ("dna", "ACGTUacgtu"),
+];
 
 /// The struct which contains all of the decoders
 /// Where decoders is crackers, decryptors, etc.
@@ -47,10 +120,88 @@ use rayon::prelude::*;
 /// Relevant docs: https://doc.rust-lang.org/book/ch17-02-trait-objects.html
 pub struct Decoders {
     /// Components is a vector of decoders.
-    pub components: Vec<Box<dyn Crack + Sync>>,
+    pub components: Vec<SharedDecoder>,
 }
 
 impl Decoders {
+    /// Drops the decoders the configured chain rules or depth limits forbid after this path
+    pub fn apply_chain_rules(&mut self, path: &[CrackResult]) {
+        let config = get_config();
+        let previous = path.last().map(|step| step.decoder);
+        self.components.retain(|decoder| {
+            let name = decoder.get_name();
+            let uses = path.iter().filter(|step| step.decoder == name).count();
+            config.is_chain_allowed(name, previous, path.len())
+                && config.is_depth_allowed(name, path.len(), uses)
+        });
+    }
+
+    /// The names of the decoders `wanted` picks out, as `only_decoders` and
+    /// `exclude_decoders` are matched
+    /// A decoder's name picks just that decoder, ignoring case, spaces and punctuation.
+    /// Anything else picks every decoder tagged with it, so `base` picks every base encoding.
+    pub fn matching(&self, wanted: &str) -> Vec<&str> {
+        let wanted = normalise_name(wanted);
+        let named: Vec<&str> = self
+            .components
+            .iter()
+            .map(|decoder| decoder.get_name())
+            .filter(|name| normalise_name(name) == wanted)
+            .collect();
+        if !named.is_empty() {
+            return named;
+        }
+        self.components
+            .iter()
+            .filter(|decoder| {
+                decoder
+                    .get_tags()
+                    .iter()
+                    .any(|tag| normalise_name(tag) == wanted)
+            })
+            .map(|decoder| decoder.get_name())
+            .collect()
+    }
+
+    /// Drops the decoders `only_decoders` leaves out and the ones `exclude_decoders` picks
+    pub fn apply_decoder_selection(&mut self, config: &Config) {
+        if config.only_decoders.is_empty() && config.exclude_decoders.is_empty() {
+            return;
+        }
+        let picked = |wanted: &[String]| -> HashSet<String> {
+            wanted
+                .iter()
+                .flat_map(|wanted| self.matching(wanted))
+                .map(String::from)
+                .collect()
+        };
+        let only = picked(&config.only_decoders);
+        let excluded = picked(&config.exclude_decoders);
+        self.components.retain(|decoder| {
+            let name = decoder.get_name();
+            (config.only_decoders.is_empty() || only.contains(name)) && !excluded.contains(name)
+        });
+    }
+
+    /// Drops the decoders tagged with an alphabet the text isn't written in
+    /// Decoders tagged with an alphabet it is written in are moved to the front, so
+    /// they are tried first.
+    pub fn apply_alphabet_rules(&mut self, text: &str) {
+        let alphabets: Vec<&str> = ALPHABET_TAGS
+            .iter()
+            .filter(|(_, characters)| is_written_in(text, characters))
+            .map(|(tag, _)| *tag)
+            .collect();
+        self.components.retain(|decoder| {
+            decoder.get_tags().iter().all(|tag| {
+                !ALPHABET_TAGS.iter().any(|(alphabet, _)| alphabet == tag)
+                    || alphabets.contains(tag)
+            })
+        });
+        self.components
+            .sort_by_key(|decoder| !decoder.get_tags().iter().any(|tag| alphabets.contains(tag)));
+    }
+
     /// Iterate over all of the decoders and run .crack(text) on them
     /// Then if the checker succeed, we short-circuit the iterator
     /// and stop all processing as soon as possible.
@@ -58,13 +209,54 @@ impl Decoders {
     /// https://doc.rust-lang.org/book/ch17-02-trait-objects.html
     /// Which allows us to have multiple different structs in the same vector
     /// But each struct shciphey the same `.crack()` method, so it's fine.
+    /// What a decoder made of the text in an earlier search is read from the edge cache
+    /// instead of running it again.
     pub fn run(&self, text: &str, checker: CheckerTypes) -> MyResults {
         trace!("Running .crack() on all decoders");
+        let cached = edge_cache::lookup(text);
+        self.run_each(|decoder| {
+            if let Some(result) = cached.get(decoder.get_name()) {
+                trace!("Using the cached result of {}", decoder.get_name());
+                return Some((edge_cache::recheck(result.clone(), &checker), false));
+            }
+            let result = decoder.crack(text, &checker);
+            edge_cache::record(&result);
+            Some((result, true))
+        })
+    }
+
+    /// Runs `.crack_bytes(bytes)` on the decoders which read bytes, for values which aren't text
+    /// Decoders which only read text are skipped. Like `run`, this short-circuits on success.
+    pub fn run_bytes(&self, bytes: &[u8], checker: CheckerTypes) -> MyResults {
+        trace!("Running .crack_bytes() on all decoders");
+        self.run_each(|decoder| {
+            decoder
+                .crack_bytes(bytes, &checker)
+                .map(|result| (result, true))
+        })
+    }
+
+    /// Runs every decoder in parallel with `crack`, which gives None for decoders that were skipped
+    /// `crack` also says whether the decoder was run, as results read from the edge cache
+    /// aren't timed for the decoder statistics.
+    fn run_each<F>(&self, crack: F) -> MyResults
+    where
+        F: Fn(&(dyn Crack + Sync)) -> Option<(CrackResult, bool)> + Sync,
+    {
         let (sender, receiver) = channel();
         self.components
-            .into_par_iter()
+            .par_iter()
             .try_for_each_with(sender, |s, i| {
-                let results = i.crack(text, &checker);
+                let started = Instant::now();
+                let Some((mut results, ran)) = crack(i.as_ref()) else {
+                    return Some(());
+                };
+                if ran {
+                    let runtime = started.elapsed();
+                    record_decoder_run(i.get_name(), runtime);
+                    results.runtime = Some(runtime);
+                }
+                events::publish(|| SearchEvent::decoder_attempted(&results));
                 if results.success {
                     cli_pretty_printing::success(&format!(
                         "DEBUG: filtration_system - Decoder {} succeeded, short-circuiting",
@@ -90,7 +282,7 @@ impl Decoders {
             // if we recv success, break.
             if result.success {
                 cli_pretty_printing::success(&format!("DEBUG: filtration_system - Received successful result from {}, returning Break", result.decoder));
-                return MyResults::Break(result);
+                return MyResults::Break(Box::new(result));
             }
             all_results.push(result)
         }
@@ -103,12 +295,19 @@ impl Decoders {
     }
 }
 
+/// True if the text has something besides whitespace, and that is all from these characters
+fn is_written_in(text: &str, characters: &str) -> bool {
+    let mut symbols = text.chars().filter(|c| !c.is_whitespace()).peekable();
+    symbols.peek().is_some() && symbols.all(|c| characters.contains(c))
+}
+
 /// [`Enum`] for our custom results.
 /// if our checker succeed, we return `Break` variant contining [`CrackResult`]
 /// else we return `Continue` with the decoded results.
 pub enum MyResults {
     /// Variant containing successful [`CrackResult`]
-    Break(CrackResult),
+    /// Boxed as it is much larger than `Continue`.
+    Break(Box<CrackResult>),
     /// Contains [`Vec`] of [`CrackResult`] for further processing
     Continue(Vec<CrackResult>),
 }
@@ -118,7 +317,7 @@ impl MyResults {
     /// as we aren't using it, it's just used in tests
     pub fn _break_value(self) -> Option<CrackResult> {
         match self {
-            MyResults::Break(val) => Some(val),
+            MyResults::Break(val) => Some(*val),
             MyResults::Continue(_) => None,
         }
     }
@@ -148,14 +347,14 @@ impl DecoderFilter {
     }
 
     /// Add a tag to exclude
+    #[allow(dead_code)]
     pub fn exclude_tag(mut self, tag: &str) -> Self {
         self.exclude_tags.push(tag.to_string());
         self
     }
 
     /// Check if a decoder matches the filter
-    #[allow(clippy::borrowed_box)]
-    pub fn matches(&self, decoder: &Box<dyn Crack + Sync>) -> bool {
+    pub fn matches(&self, decoder: &(dyn Crack + Send + Sync)) -> bool {
         let tags = decoder.get_tags();
 
         // If include_tags is not empty, at least one tag must match
@@ -194,6 +393,7 @@ pub fn get_decoder_tagged_decoders(text_struct: &DecoderResult) -> Decoders {
 }
 
 /// Get decoders without the "decoder" tag
+#[allow(dead_code)]
 pub fn get_non_decoder_tagged_decoders(text_struct: &DecoderResult) -> Decoders {
     trace!("Getting non-decoder-tagged decoders");
     let filter = DecoderFilter::new().exclude_tag("decoder");
@@ -201,7 +401,8 @@ pub fn get_non_decoder_tagged_decoders(text_struct: &DecoderResult) -> Decoders
 }
 
 /// Filter decoders based on custom tags
-pub fn filter_decoders_by_tags(_text_struct: &DecoderResult, filter: &DecoderFilter) -> Decoders {
+/// Decoders which need the text to be in an alphabet it isn't written in are left out too
+pub fn filter_decoders_by_tags(text_struct: &DecoderResult, filter: &DecoderFilter) -> Decoders {
     trace!("Filtering decoders by tags");
 
     // Get all decoders
@@ -211,12 +412,16 @@ pub fn filter_decoders_by_tags(_text_struct: &DecoderResult, filter: &DecoderFil
     let filtered_components = all_decoders
         .components
         .into_iter()
-        .filter(|decoder| filter.matches(decoder))
+        .filter(|decoder| filter.matches(decoder.as_ref()))
         .collect();
 
-    Decoders {
+    let mut decoders = Decoders {
         components: filtered_components,
+    };
+    if let Some(text) = text_struct.text.first() {
+        decoders.apply_alphabet_rules(text);
     }
+    decoders
 }
 
 /// Get all available decoders
@@ -225,9 +430,70 @@ pub fn get_all_decoders() -> Decoders {
     filter_and_get_decoders(&DecoderResult::default())
 }
 
-/// Currently takes no args as this is just a spike to get all the basic functionality working
-pub fn filter_and_get_decoders(_text_struct: &DecoderResult) -> Decoders {
+/// Gets the decoders to run on the text
+/// Decoders the user has toggled off or left out with `only_decoders` and
+/// `exclude_decoders`, or which a chain rule or depth limit forbids after the text's path,
+/// are left out
+pub fn filter_and_get_decoders(text_struct: &DecoderResult) -> Decoders {
     trace!("Filtering and getting all decoders");
+    let mut decoders = enabled_decoders(get_config());
+    decoders.apply_chain_rules(&text_struct.path);
+    decoders
+}
+
+/// The decoders this config runs, leaving out ones toggled off or left out with
+/// `only_decoders` and `exclude_decoders`
+pub fn enabled_decoders(config: &Config) -> Decoders {
+    let mut decoders = get_registered_decoders();
+    decoders.apply_decoder_selection(config);
+    decoders
+        .components
+        .retain(|decoder| config.is_decoder_enabled(decoder.get_name()));
+    decoders
+}
+
+/// Whether the decoder named `name` runs, see [`filter_and_get_decoders`]
+pub fn is_decoder_running(name: &str) -> bool {
+    enabled_decoders(get_config())
+        .components
+        .iter()
+        .any(|decoder| decoder.get_name() == name)
+}
+
+/// Lowercases the name and strips everything that isn't a letter or a digit, so names
+/// typed by the user match however they are written
+pub fn normalise_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Fingerprint of the decoders and checkers this config runs
+/// Results found with a different set of them, such as before a decoder was added or
+/// toggled off, can't be trusted to be what a search would find now.
+pub fn registry_fingerprint(config: &Config) -> String {
+    let mut names: Vec<String> = enabled_decoders(config)
+        .components
+        .iter()
+        .map(|decoder| format!("decoder:{}", decoder.get_name()))
+        .collect();
+    names.extend(
+        CHECKER_MAP
+            .keys()
+            .filter(|name| config.is_checker_enabled(name))
+            .map(|name| format!("checker:{}", name)),
+    );
+    names.sort();
+    Sha256::digest(names.join("\n").as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Get every decoder ciphey knows about, including ones the user has toggled off
+/// Decoders registered at runtime come after ciphey's own, in the order they were registered.
+pub fn get_registered_decoders() -> Decoders {
     let vigenere = Decoder::<VigenereDecoder>::new();
     let binary = Decoder::<BinaryDecoder>::new();
     let hexadecimal = Decoder::<HexadecimalDecoder>::new();
@@ -253,35 +519,145 @@ pub fn filter_and_get_decoders(_text_struct: &DecoderResult) -> Decoders {
     let substitution_generic = Decoder::<SubstitutionGenericDecoder>::new();
 
     let brainfuck = Decoder::<BrainfuckInterpreter>::new();
-
-    Decoders {
-        components: vec![
-            Box::new(vigenere),
-            Box::new(reversedecoder),
-            Box::new(base64),
-            Box::new(base58_bitcoin),
-            Box::new(base58_monero),
-            Box::new(base58_ripple),
-            Box::new(base58_flickr),
-            Box::new(base91),
-            Box::new(base65536),
-            Box::new(binary),
-            Box::new(hexadecimal),
-            Box::new(base32),
-            Box::new(morsecodedecoder),
-            Box::new(atbashdecoder),
-            Box::new(caesardecoder),
-            Box::new(railfencedecoder),
-            Box::new(citrix_ctx1),
-            Box::new(url),
-            Box::new(rot47decoder),
-            Box::new(z85),
-            Box::new(a1z26decoder),
-            Box::new(brailledecoder),
-            Box::new(substitution_generic),
-            Box::new(brainfuck),
-        ],
-    }
+    let whitespace_stego_decoder = Decoder::<WhitespaceStegoDecoder>::new();
+    let zero_width_decoder = Decoder::<ZeroWidthDecoder>::new();
+    let hash_identifier = Decoder::<HashIdentifier>::new();
+    let keyed_decryption = Decoder::<KeyedDecryptionDecoder>::new();
+    let brute_force_cipher_decoder = Decoder::<BruteForceCipherDecoder>::new();
+    let skey_decoder = Decoder::<SkeyDecoder>::new();
+    let bubble_babble_decoder = Decoder::<BubbleBabbleDecoder>::new();
+    let dna_decoder = Decoder::<DnaDecoder>::new();
+    let bcd_decoder = Decoder::<BcdDecoder>::new();
+    let gray_code_decoder = Decoder::<GrayCodeDecoder>::new();
+    let text_transform = Decoder::<TextTransformDecoder>::new();
+    let leetspeak = Decoder::<LeetspeakDecoder>::new();
+    let tap_code = Decoder::<TapCodeDecoder>::new();
+    let phone_keypad = Decoder::<PhoneKeypadDecoder>::new();
+    let keyboard_shift = Decoder::<KeyboardShiftDecoder>::new();
+    let malbolge = Decoder::<MalbolgeDecoder>::new();
+    let jsfuck = Decoder::<JsfuckDecoder>::new();
+    let whitespace = Decoder::<WhitespaceInterpreter>::new();
+    let ook = Decoder::<OokInterpreter>::new();
+    let hexdump = Decoder::<HexdumpDecoder>::new();
+    let unicode_escape = Decoder::<UnicodeEscapeDecoder>::new();
+    let jwt = Decoder::<JwtDecoder>::new();
+    let utf7 = Decoder::<Utf7Decoder>::new();
+    let charset = Decoder::<CharsetDecoder>::new();
+    let lzma = Decoder::<LzmaDecoder>::new();
+    let brotli = Decoder::<BrotliDecoder>::new();
+    let bzip2 = Decoder::<Bzip2Decoder>::new();
+    let deflate = Decoder::<DeflateDecoder>::new();
+    let zlib = Decoder::<ZlibDecoder>::new();
+    let gzip = Decoder::<GzipDecoder>::new();
+    let yenc = Decoder::<YencDecoder>::new();
+    let xxencode = Decoder::<XxencodeDecoder>::new();
+    let uuencode = Decoder::<UuencodeDecoder>::new();
+    let base32_variant = Decoder::<Base32VariantDecoder>::new();
+    let base2048 = Decoder::<Base2048Decoder>::new();
+    let base32768 = Decoder::<Base32768Decoder>::new();
+    let base122 = Decoder::<Base122Decoder>::new();
+    let base92 = Decoder::<Base92Decoder>::new();
+    let base45 = Decoder::<Base45Decoder>::new();
+    let base36 = Decoder::<Base36Decoder>::new();
+    let base62 = Decoder::<Base62Decoder>::new();
+    let enigma = Decoder::<EnigmaDecoder>::new();
+    let adfgvx = Decoder::<AdfgvxDecoder>::new();
+    let bifid = Decoder::<BifidDecoder>::new();
+    let polybius = Decoder::<PolybiusDecoder>::new();
+    let playfair = Decoder::<PlayfairDecoder>::new();
+    let beaufort = Decoder::<BeaufortDecoder>::new();
+    let autokey = Decoder::<AutokeyDecoder>::new();
+    let substitution_solver = Decoder::<SubstitutionSolver>::new();
+    let columnar = Decoder::<ColumnarTranspositionDecoder>::new();
+    let affine = Decoder::<AffineDecoder>::new();
+    let decimal = Decoder::<DecimalDecoder>::new();
+    let octal = Decoder::<OctalDecoder>::new();
+    let xor = Decoder::<XorDecoder>::new();
+    let ascii85 = Decoder::<Ascii85Decoder>::new();
+
+    let mut components: Vec<SharedDecoder> = vec![
+        Arc::new(vigenere),
+        Arc::new(reversedecoder),
+        Arc::new(base64),
+        Arc::new(base58_bitcoin),
+        Arc::new(base58_monero),
+        Arc::new(base58_ripple),
+        Arc::new(base58_flickr),
+        Arc::new(base91),
+        Arc::new(base65536),
+        Arc::new(binary),
+        Arc::new(hexadecimal),
+        Arc::new(base32),
+        Arc::new(morsecodedecoder),
+        Arc::new(atbashdecoder),
+        Arc::new(caesardecoder),
+        Arc::new(railfencedecoder),
+        Arc::new(citrix_ctx1),
+        Arc::new(url),
+        Arc::new(rot47decoder),
+        Arc::new(z85),
+        Arc::new(a1z26decoder),
+        Arc::new(brailledecoder),
+        Arc::new(substitution_generic),
+        Arc::new(brainfuck),
+        Arc::new(ascii85),
+        Arc::new(xor),
+        Arc::new(octal),
+        Arc::new(decimal),
+        Arc::new(affine),
+        Arc::new(columnar),
+        Arc::new(substitution_solver),
+        Arc::new(autokey),
+        Arc::new(beaufort),
+        Arc::new(playfair),
+        Arc::new(polybius),
+        Arc::new(bifid),
+        Arc::new(adfgvx),
+        Arc::new(enigma),
+        Arc::new(base62),
+        Arc::new(base36),
+        Arc::new(base45),
+        Arc::new(base92),
+        Arc::new(base122),
+        Arc::new(base32768),
+        Arc::new(base2048),
+        Arc::new(base32_variant),
+        Arc::new(uuencode),
+        Arc::new(xxencode),
+        Arc::new(yenc),
+        Arc::new(gzip),
+        Arc::new(zlib),
+        Arc::new(deflate),
+        Arc::new(bzip2),
+        Arc::new(brotli),
+        Arc::new(lzma),
+        Arc::new(charset),
+        Arc::new(utf7),
+        Arc::new(jwt),
+        Arc::new(unicode_escape),
+        Arc::new(hexdump),
+        Arc::new(ook),
+        Arc::new(whitespace),
+        Arc::new(jsfuck),
+        Arc::new(malbolge),
+        Arc::new(keyboard_shift),
+        Arc::new(phone_keypad),
+        Arc::new(tap_code),
+        Arc::new(leetspeak),
+        Arc::new(text_transform),
+        Arc::new(gray_code_decoder),
+        Arc::new(bcd_decoder),
+        Arc::new(dna_decoder),
+        Arc::new(bubble_babble_decoder),
+        Arc::new(skey_decoder),
+        Arc::new(brute_force_cipher_decoder),
+        Arc::new(keyed_decryption),
+        Arc::new(hash_identifier),
+        Arc::new(zero_width_decoder),
+        Arc::new(whitespace_stego_decoder),
+    ];
+    components.extend(custom_decoders());
+    Decoders { components }
 }
 
 /// Get a specific decoder by name
@@ -312,9 +688,11 @@ mod tests {
     };
 
     use super::{
-        filter_and_get_decoders, filter_decoders_by_tags, get_decoder_by_name,
-        get_decoder_tagged_decoders, get_non_decoder_tagged_decoders, DecoderFilter,
+        filter_and_get_decoders, filter_decoders_by_tags, get_all_decoders, get_decoder_by_name,
+        get_decoder_tagged_decoders, get_non_decoder_tagged_decoders, get_registered_decoders,
+        registry_fingerprint, DecoderFilter, MyResults,
     };
+    use crate::config::Config;
 
     #[test]
     fn it_works() {
@@ -331,6 +709,25 @@ mod tests {
         assert_eq!(true, true);
     }
 
+    #[test]
+    fn run_bytes_only_runs_decoders_which_read_bytes() {
+        let decoders = filter_and_get_decoders(&DecoderResult::default());
+        let checker = CheckerTypes::CheckAthena(Checker::<Athena>::new());
+        // "hello world" gzipped
+        let gzip = [
+            0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x03, 0xcb, 0x48, 0xcd, 0xc9,
+            0xc9, 0x57, 0x28, 0xcf, 0x2f, 0xca, 0x49, 0x01, 0x00, 0x85, 0x11, 0x4a, 0x0d, 0x0b,
+            0x00, 0x00, 0x00,
+        ];
+        match decoders.run_bytes(&gzip, checker) {
+            MyResults::Break(result) => {
+                assert_eq!(result.decoder, "Gzip");
+                assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+            }
+            MyResults::Continue(_) => panic!("gzip bytes should have been decompressed"),
+        }
+    }
+
     #[test]
     fn test_decoder_filter_include_tag() {
         let filter = DecoderFilter::new().include_tag("base");
@@ -475,4 +872,91 @@ mod tests {
             "Should return empty decoders for nonexistent name"
         );
     }
+
+    /// The names of the decoders which would be run on the text, in order
+    fn decoder_names_for(text: &str) -> Vec<String> {
+        let text_struct = DecoderResult {
+            text: vec![text.to_string()],
+            path: vec![],
+            bytes: None,
+        };
+        filter_decoders_by_tags(&text_struct, &DecoderFilter::new())
+            .components
+            .iter()
+            .map(|decoder| decoder.get_name().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn decoders_are_picked_by_name_before_tag() {
+        let decoders = get_registered_decoders();
+        assert_eq!(decoders.matching("base58-bitcoin"), ["Base58 Bitcoin"]);
+        // Base64 is also tagged url, but the URL decoder is called url
+        assert_eq!(decoders.matching("URL"), ["URL"]);
+        let hex = decoders.matching("hex");
+        assert!(hex.contains(&"Hexadecimal") && hex.contains(&"Hexdump"));
+        assert!(decoders.matching("not a decoder").is_empty());
+    }
+
+    #[test]
+    fn only_and_exclude_decoders_shrink_the_registry() {
+        let config = Config {
+            only_decoders: vec![String::from("base")],
+            exclude_decoders: vec![String::from("base64")],
+            ..Default::default()
+        };
+        let mut decoders = get_registered_decoders();
+        decoders.apply_decoder_selection(&config);
+        let names: Vec<&str> = decoders
+            .components
+            .iter()
+            .map(|decoder| decoder.get_name())
+            .collect();
+        assert!(names.contains(&"Base32") && names.contains(&"Hexadecimal"));
+        assert!(!names.contains(&"Base64") && !names.contains(&"Brainfuck"));
+    }
+
+    #[test]
+    fn alphabet_decoders_are_left_out_of_other_text() {
+        let names = decoder_names_for("Hello, world!");
+        assert!(!names.iter().any(|name| name == "Polybius Square"));
+        assert!(!names.iter().any(|name| name == "ADFGVX"));
+        assert!(names.iter().any(|name| name == "Bifid"));
+    }
+
+    #[test]
+    fn digits_put_polybius_first() {
+        let names = decoder_names_for("23 15 31 31 34");
+        assert_eq!(names[0], "Polybius Square");
+        assert!(!names.iter().any(|name| name == "ADFGVX"));
+    }
+
+    #[test]
+    fn adfgvx_letters_put_adfgvx_first() {
+        let names = decoder_names_for("ADFGV XXAGD");
+        assert_eq!(names[0], "ADFGVX");
+        assert!(!names.iter().any(|name| name == "Polybius Square"));
+    }
+
+    #[test]
+    fn alphabet_decoders_are_kept_when_listing_every_decoder() {
+        let names: Vec<String> = get_all_decoders()
+            .components
+            .iter()
+            .map(|decoder| decoder.get_name().to_string())
+            .collect();
+        assert!(names.iter().any(|name| name == "Polybius Square"));
+        assert!(names.iter().any(|name| name == "ADFGVX"));
+    }
+
+    #[test]
+    fn toggling_a_decoder_changes_the_registry_fingerprint() {
+        let config = crate::config::Config::default();
+        let fingerprint = registry_fingerprint(&config);
+        assert_eq!(registry_fingerprint(&config), fingerprint);
+
+        let mut toggled = crate::config::Config::default();
+        toggled.decoder_toggles.insert("Base64".to_string(), false);
+        assert_ne!(registry_fingerprint(&toggled), fingerprint);
+    }
 }
@@ -10,6 +10,10 @@
 /// The main crate for the ciphey project.
 /// This provides the library API interface for ciphey.
 mod api_library_input_struct;
+/// Cracks many inputs at once, sharing setup and results between them
+pub mod batch;
+/// Stopping a search early from outside, see [`CancellationToken`]
+pub mod cancellation;
 /// Checkers is a module that contains the functions that check if the input is plaintext
 pub mod checkers;
 /// CLI Arg Parsing library
@@ -34,18 +38,32 @@ pub mod cli_pretty_printing;
 /// The Config module enables a configuration module
 /// Like a global API to access config details
 pub mod config;
+/// Seeded generators for layered test cases
+pub mod corpus;
 /// Decoders are the functions that actually perform the decodings.
 pub mod decoders;
+/// Events a search publishes as it runs, for showing its progress live
+pub mod events;
 /// The filtration system builds what decoders to use at runtime
 /// By default it will use them all.
 mod filtration_system;
+/// Guesses what an input is from cheap probes, without cracking it
+pub mod identify;
+/// A JSON-RPC service streaming the progress of each search
+pub mod rpc;
+/// Scans directories and ZIP archives for encoded strings and cracks them
+pub mod scan;
 /// The searcher is the thing which searches for the plaintext
 /// It is the core of the program.
 mod searchers;
+/// An HTTP server answering requests to crack texts
+pub mod server;
 /// Storage module for dictionaries and invisible characters
 pub mod storage;
 /// Timer for internal use
 mod timer;
+/// Cracks lines as they are written to a file, a FIFO or stdin
+pub mod watch;
 
 use checkers::{
     athena::Athena,
@@ -62,6 +80,27 @@ use crate::{
 };
 
 use self::decoders::crack_results::CrackResult;
+use self::storage::history::RunOutcome;
+pub use cancellation::CancellationToken;
+
+/// Makes sure the warning about falling back to an in-memory database is only printed once
+static DATABASE_FALLBACK_WARNING: std::sync::Once = std::sync::Once::new();
+
+/// Makes sure the warning about the chosen storage backend being unusable is only printed once
+static STORAGE_FALLBACK_WARNING: std::sync::Once = std::sync::Once::new();
+
+/// Makes sure the warnings about `only_decoders` and `exclude_decoders` naming no decoder
+/// are only printed once
+static UNKNOWN_DECODERS_WARNING: std::sync::Once = std::sync::Once::new();
+
+/// Every plaintext the last search in all_results mode found, best first
+static ALL_RESULTS: std::sync::Mutex<Vec<DecoderResult>> = std::sync::Mutex::new(Vec::new());
+
+/// How the last text cracked ended, None before any has been
+static LAST_RUN_OUTCOME: std::sync::Mutex<Option<RunOutcome>> = std::sync::Mutex::new(None);
+
+/// Makes sure plugins are only loaded and registered once, however many times cracking starts
+static PLUGINS_LOADED: std::sync::Once = std::sync::Once::new();
 
 /// The main function to call which performs the cracking.
 /// ```rust
@@ -102,8 +141,102 @@ use self::decoders::crack_results::CrackResult;
 /// assert!(result.is_none());
 /// ```
 pub fn perform_cracking(text: &str, config: Config) -> Option<DecoderResult> {
+    perform_cracking_with_cancel(text, config, &CancellationToken::new())
+}
+
+/// Performs the cracking like [`perform_cracking`], stopping early once `cancel` is cancelled
+///
+/// A cancelled search returns what it had found by then: the plaintext if one was found,
+/// otherwise None with the decodes which looked most like plaintext so far in
+/// [`best_candidates`]. In all_results mode the plaintexts found so far are in
+/// [`all_results`].
+pub fn perform_cracking_with_cancel(
+    text: &str,
+    config: Config,
+    cancel: &CancellationToken,
+) -> Option<DecoderResult> {
     let start_time = SystemTime::now();
+    prepare_cracking(config);
+    crack_prepared_text(text, start_time, cancel).0
+}
 
+/// Performs the cracking like [`perform_cracking`], handing `observer` every
+/// [`events::SearchEvent`] as the search runs, for drawing a progress bar or a log
+///
+/// The observer is called on this thread while the search runs on another, and is given
+/// every decoder attempt, cache hit, checker success, human checker prompt, new best
+/// candidate and the end of the search. It sees the events of any search running in the
+/// process at the time, so only one should run while it is observed. To be able to
+/// cancel the search as well, call [`perform_cracking_with_cancel`] inside
+/// [`events::observe`].
+/// ```rust
+/// use ciphey::config::Config;
+/// use ciphey::events::SearchEvent;
+/// use ciphey::perform_cracking_with_observer;
+/// # let _test_db = ciphey::TestDatabase::default();
+/// # ciphey::set_test_db_path();
+/// let mut decoders_tried = 0;
+/// let mut finished = false;
+/// let result = perform_cracking_with_observer(
+///     "aGVsbG8gdGhlcmUgb2JzZXJ2ZXI=",
+///     Config::default(),
+///     |event| match event {
+///         SearchEvent::DecoderAttempted { .. } => decoders_tried += 1,
+///         SearchEvent::Finished { .. } => finished = true,
+///         _ => {}
+///     },
+/// );
+/// assert_eq!(result.unwrap().text[0], "hello there observer");
+/// assert!(decoders_tried > 0 && finished);
+/// ```
+pub fn perform_cracking_with_observer(
+    text: &str,
+    config: Config,
+    observer: impl FnMut(events::SearchEvent),
+) -> Option<DecoderResult> {
+    events::observe(observer, || {
+        perform_cracking_with_cancel(text, config, &CancellationToken::new())
+    })
+}
+
+/// Performs the cracking like [`perform_cracking_with_cancel`] on bytes, such as a file read
+/// as it is
+///
+/// Bytes which are UTF-8 text are cracked as that text. Other bytes, such as a gzipped
+/// file, are only decoded by the decoders which read bytes, and as the cache only holds
+/// texts they are neither looked up in it nor written to it.
+pub fn perform_cracking_bytes_with_cancel(
+    bytes: &[u8],
+    config: Config,
+    cancel: &CancellationToken,
+) -> Option<DecoderResult> {
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        return perform_cracking_with_cancel(text, config, cancel);
+    }
+    let start_time = SystemTime::now();
+    prepare_cracking(config);
+    let input = DecoderResult::from_bytes(bytes.to_vec());
+    let text = input.text[0].clone();
+    let result = if config::get_config().all_results {
+        let mut results = searchers::search_for_all_plaintexts(input, cancel);
+        searchers::rank_results(&mut results);
+        let best = results.first().cloned();
+        *ALL_RESULTS
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) = results;
+        best
+    } else {
+        searchers::search_for_plaintext(input, cancel)
+    };
+    let outcome = search_outcome(result.as_ref(), cancel);
+    finish_run(&text, start_time, result.as_ref(), outcome);
+    result
+}
+
+/// Sets up everything a cracking session needs before any text is cracked
+/// This sets the global config and initialises the database, so it only has to
+/// happen once no matter how many texts are cracked afterwards.
+fn prepare_cracking(config: Config) {
     // If top_results is enabled, ensure human_checker_on is disabled
     let mut modified_config = config;
     if modified_config.top_results {
@@ -111,28 +244,296 @@ pub fn perform_cracking(text: &str, config: Config) -> Option<DecoderResult> {
         // Clear any previous results when starting a new cracking session
         storage::wait_athena_storage::clear_plaintext_results();
     }
+    // Every plaintext found would stop the search to ask the human checker
+    if modified_config.all_results {
+        modified_config.human_checker_on = false;
+    }
 
-    config::set_global_config(modified_config);
-    let text = text.to_string();
+    storage::memory_cache::set_capacity(modified_config.memory_cache_entries);
+    storage::database::set_cache_limits(storage::database::CacheLimits::from_config(
+        &modified_config,
+    ));
 
     /* Initializing database */
-    let db_result = storage::database::setup_database();
+    if modified_config.no_database {
+        storage::database::disable_database();
+    } else if let Some(path) = &modified_config.database_path {
+        storage::database::use_database_path(std::path::PathBuf::from(path));
+    }
+    let storage_unavailable = choose_storage(&modified_config).err();
+    let db_result = storage::backend::storage().setup();
+
+    // Decoders and checkers toggled with `ciphey toggle` are saved in the database.
+    // They sit underneath the config file, so merge them in before the config is frozen
+    if db_result.is_ok() {
+        match storage::backend::storage().read_settings() {
+            Ok(settings) => modified_config.merge_stored_toggles(&settings),
+            Err(e) => log::debug!("Could not read saved decoder and checker toggles: {}", e),
+        }
+    }
+
+    let plugins_dir = modified_config
+        .plugins_dir
+        .as_ref()
+        .map(std::path::PathBuf::from)
+        .or_else(decoders::plugin_decoder::default_plugins_dir);
+    config::set_global_config(modified_config);
+
+    if let Some(plugins_dir) = plugins_dir {
+        PLUGINS_LOADED.call_once(|| {
+            let loaded = decoders::plugin_decoder::register_plugins(&plugins_dir);
+            log::debug!("Loaded {} plugins from {}", loaded, plugins_dir.display());
+        });
+    }
+
+    // Plugins are loaded by now, so a name matching nothing is a typo which would quietly
+    // run every decoder or none
+    UNKNOWN_DECODERS_WARNING.call_once(|| {
+        let config = config::get_config();
+        let registered = filtration_system::get_registered_decoders();
+        for wanted in config.only_decoders.iter().chain(&config.exclude_decoders) {
+            if registered.matching(wanted).is_empty() {
+                cli_pretty_printing::warning_unknown_decoder(wanted);
+            }
+        }
+    });
+
+    // Cached results are only trusted if the same decoders and checkers would run now
+    storage::database::set_registry_fingerprint(filtration_system::registry_fingerprint(
+        config::get_config(),
+    ));
+
+    if let Some(reason) = storage_unavailable {
+        STORAGE_FALLBACK_WARNING
+            .call_once(|| cli_pretty_printing::warning_storage_unavailable(&reason));
+    }
+    if let Some(reason) = storage::database::fallback_reason() {
+        DATABASE_FALLBACK_WARNING
+            .call_once(|| cli_pretty_printing::warning_database_unavailable(reason));
+    }
+
     match db_result {
         Ok(_) => (),
         Err(e) => {
             cli_pretty_printing::warning(&format!(
-                "DEBUG: lib.rs - SQLite database failed to initialize. Encountered error: {}",
+                "DEBUG: lib.rs - {} storage failed to initialize. Encountered error: {}",
+                storage::backend::storage().name(),
                 e
             ));
         }
     };
+}
+
+/// Switches to the storage backend the config asks for, keeping SQLite if it can't be used
+/// `--no-database` keeps everything in SQLite's in-memory database whatever the backend is.
+///
+/// Returns why the backend can't be used if it can't.
+fn choose_storage(config: &Config) -> Result<(), String> {
+    if config.no_database {
+        return Ok(());
+    }
+    let chosen: Box<dyn storage::backend::Storage> = match config.storage_backend {
+        storage::backend::StorageBackend::Sqlite => return Ok(()),
+        storage::backend::StorageBackend::Memory => {
+            Box::new(storage::backend::MemoryStorage::new())
+        }
+        storage::backend::StorageBackend::Redis => redis_storage(config)?,
+    };
+    storage::backend::use_storage(chosen);
+    Ok(())
+}
+
+/// The Redis backend at `storage_url`, once it answers
+#[cfg(feature = "redis")]
+fn redis_storage(config: &Config) -> Result<Box<dyn storage::backend::Storage>, String> {
+    use storage::backend::Storage as _;
+    let url = config
+        .storage_url
+        .as_deref()
+        .ok_or_else(|| String::from("storage_url isn't set in the config file"))?;
+    let redis = storage::backend::RedisStorage::new(url).map_err(|e| e.to_string())?;
+    redis.setup().map_err(|e| e.to_string())?;
+    Ok(Box::new(redis))
+}
+
+/// The Redis backend, which this build of ciphey doesn't have
+#[cfg(not(feature = "redis"))]
+fn redis_storage(_config: &Config) -> Result<Box<dyn storage::backend::Storage>, String> {
+    Err(String::from(
+        "this ciphey was built without the redis feature",
+    ))
+}
+
+/// Cracks a single text once `prepare_cracking` has run, recording the run in the history
+/// The search stops early if `cancel` is cancelled. Gives what was found and how.
+fn crack_prepared_text(
+    text: &str,
+    start_time: SystemTime,
+    cancel: &CancellationToken,
+) -> (Option<DecoderResult>, RunOutcome) {
+    let (result, outcome) = crack_text(text, start_time, cancel);
+    finish_run(text, start_time, result.as_ref(), outcome);
+    (result, outcome)
+}
+
+/// Records how a run ended, in the history and for [`last_run_outcome`]
+fn finish_run(
+    text: &str,
+    start_time: SystemTime,
+    result: Option<&DecoderResult>,
+    outcome: RunOutcome,
+) {
+    storage::history::record_run(text, start_time, result, outcome);
+    events::publish(|| events::SearchEvent::finished(outcome));
+    *LAST_RUN_OUTCOME
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner) = Some(outcome);
+}
+
+/// How a search ended, given what it found
+fn search_outcome(result: Option<&DecoderResult>, cancel: &CancellationToken) -> RunOutcome {
+    match result {
+        Some(_) => RunOutcome::Solved,
+        None if cancel.is_cancelled() => RunOutcome::Cancelled,
+        None => RunOutcome::Unsolved,
+    }
+}
+
+/// Cracks a single text, giving what was found and how
+/// Checks the cache first, then whether the input is already plaintext, then searches.
+fn crack_text(
+    text: &str,
+    start_time: SystemTime,
+    cancel: &CancellationToken,
+) -> (Option<DecoderResult>, RunOutcome) {
+    let text = text.to_string();
+
+    if config::get_config().all_results {
+        let result = crack_all_results(&text, start_time, cancel);
+        let outcome = search_outcome(result.as_ref(), cancel);
+        return (result, outcome);
+    }
+
+    if !config::get_config().skip_cache {
+        if let Some(result) = cached_result(&text) {
+            events::publish(|| events::SearchEvent::CacheHit {
+                plaintext: events::shorten(result.text.first().map_or("", String::as_str)),
+            });
+            return (Some(result), RunOutcome::Cached);
+        }
+    }
+
+    let initial_check_for_plaintext = check_if_input_text_is_plaintext(&text);
+    if initial_check_for_plaintext.is_identified {
+        // Steganography hides its message in text which already reads as plaintext
+        if let Some(output) = crack_hidden_message(&text) {
+            cache_success(&text, start_time, &output);
+            return (Some(output), RunOutcome::Solved);
+        }
+        debug!(
+            "The input text provided to the program {} is the plaintext. Returning early.",
+            text
+        );
+        cli_pretty_printing::return_early_because_input_text_is_plaintext();
+
+        let output = input_as_plaintext(&text, &initial_check_for_plaintext);
+        cache_success(&text, start_time, &output);
+        return (Some(output), RunOutcome::Plaintext);
+    }
+
+    // Build a new search tree
+    // This starts us with a node with no parents
+    // let search_tree = searchers::Tree::new(text.to_string());
+    cli_pretty_printing::success(&format!(
+        "DEBUG: lib.rs - Calling search_for_plaintext with text: {}",
+        text
+    ));
+    // Perform the search algorithm
+    // It will either return a failure or success.
+    let result = searchers::search_for_plaintext(DecoderResult::from(text.as_str()), cancel);
+    cli_pretty_printing::success(&format!(
+        "DEBUG: lib.rs - Result from search_for_plaintext: {:?}",
+        result.is_some()
+    ));
+    if let Some(ref res) = result {
+        cli_pretty_printing::success(&format!(
+            "DEBUG: lib.rs - Result has {} decoders in path",
+            res.path.len()
+        ));
+    }
+
+    if let Some(output) = &result {
+        cache_success(&text, start_time, output);
+    }
+
+    let outcome = search_outcome(result.as_ref(), cancel);
+    (result, outcome)
+}
+
+/// The message hidden in plaintext by steganography, if a running decoder finds one
+/// A search of plaintext finds its reversals and rotations before anything hidden in it, so
+/// only the steganography decoders are tried, and only on text carrying hidden characters
+/// or trailing whitespace.
+fn crack_hidden_message(text: &str) -> Option<DecoderResult> {
+    if !decoders::zero_width_decoder::hides_characters(text)
+        && !decoders::whitespace_stego_decoder::hides_whitespace(text)
+    {
+        return None;
+    }
+    let checker = checkers::CheckerTypes::CheckAthena(Checker::<Athena>::new());
+    filtration_system::enabled_decoders(get_config())
+        .components
+        .iter()
+        .filter(|decoder| decoder.get_tags().contains(&"steganography"))
+        .map(|decoder| decoder.crack(text, &checker))
+        .find(|crack_result| crack_result.success)
+        .map(|crack_result| DecoderResult {
+            text: crack_result.unencrypted_text.clone().unwrap_or_default(),
+            path: vec![crack_result],
+            bytes: None,
+        })
+}
+
+/// The result cached for this text, if it is confident enough and wasn't rejected
+/// Hot texts are answered from memory, the rest from the storage backend.
+fn cached_result(text: &str) -> Option<DecoderResult> {
+    // Results accepted with less confidence than is now asked for are searched again
+    let min_confidence = config::get_config().min_confidence;
+    if let Some(result) = storage::memory_cache::get(text) {
+        let confidence = result
+            .path
+            .last()
+            .map_or(1.0, |accepted| accepted.confidence);
+        let rejected = result
+            .text
+            .first()
+            .is_some_and(|plaintext| checkers::denylist::was_rejected(plaintext));
+        if confidence >= min_confidence && !rejected {
+            log::debug!("In-memory cache hit for text: {}", text);
+            storage::stats::record_memory_cache_hit();
+            return Some(result);
+        }
+    }
 
     /*  Checks to see if the encoded text already exists in the cache
      *  returns cached result if so
      */
-    let cache_result = storage::database::read_cache(&text);
+    let cache_result = storage::backend::storage().read_cache(text);
     match cache_result {
         Ok(cache_row) => match cache_row {
+            Some(row) if row.confidence < min_confidence => {
+                log::debug!(
+                    "Ignoring cached result for text {} with confidence {}",
+                    text,
+                    row.confidence
+                );
+            }
+            Some(row) if checkers::denylist::was_rejected(&row.decoded_text) => {
+                log::debug!(
+                    "Ignoring cached result for text {} as its plaintext was rejected",
+                    text
+                );
+            }
             Some(row) => {
                 log::debug!("Cache hit for text: {}", text);
                 cli_pretty_printing::success(&format!(
@@ -156,17 +557,25 @@ pub fn perform_cracking(text: &str, config: Config) -> Option<DecoderResult> {
                         }
                     })
                     .collect();
-                if let Ok(path) = path_result {
-                    return Some(DecoderResult {
+                if let Ok(mut path) = path_result {
+                    // The confidence is kept in its own column rather than in the path
+                    if let Some(accepted) = path.last_mut() {
+                        accepted.confidence = row.confidence;
+                    }
+                    let result = DecoderResult {
                         text: vec![row.decoded_text],
+                        bytes: bytes_from_path(&path),
                         path,
-                    });
+                    };
+                    storage::memory_cache::put(text, result.clone());
+                    storage::stats::record_cache_lookup(true);
+                    return Some(result);
                 }
             }
             None => {
                 cli_pretty_printing::success(&format!(
                     "DEBUG: lib.rs - Did not find text \"{}\" in cache",
-                    text.clone()
+                    text
                 ));
             }
         },
@@ -178,71 +587,94 @@ pub fn perform_cracking(text: &str, config: Config) -> Option<DecoderResult> {
         }
     }
 
-    let initial_check_for_plaintext = check_if_input_text_is_plaintext(&text);
-    if initial_check_for_plaintext.is_identified {
-        debug!(
-            "The input text provided to the program {} is the plaintext. Returning early.",
-            text
-        );
-        cli_pretty_printing::return_early_because_input_text_is_plaintext();
-
-        let mut crack_result = CrackResult::new(&Decoder::default(), text.to_string());
-        crack_result.checker_name = initial_check_for_plaintext.checker_name;
+    storage::stats::record_cache_lookup(false);
+    None
+}
 
-        let output = DecoderResult {
-            text: vec![text.clone()],
-            path: vec![crack_result],
-        };
-
-        let cache_result = success_result_to_cache(&text, start_time, &output);
-        match cache_result {
-            Ok(_) => (),
-            Err(e) => {
-                cli_pretty_printing::warning(&format!(
-                    "DEBUG: lib.rs - Error inserting decoder result into cache table: {}",
-                    e
-                ));
-            }
-        };
+/// Cracks a text in all_results mode, searching until the timeout for every plaintext
+/// The cache only holds one plaintext for each text, so it isn't read, but the best
+/// plaintext found is written to it. Input which is already plaintext is one of the results.
+fn crack_all_results(
+    text: &str,
+    start_time: SystemTime,
+    cancel: &CancellationToken,
+) -> Option<DecoderResult> {
+    let initial_check_for_plaintext = check_if_input_text_is_plaintext(text);
+    let mut results = searchers::search_for_all_plaintexts(DecoderResult::from(text), cancel);
+    if initial_check_for_plaintext.is_identified {
+        results.push(input_as_plaintext(text, &initial_check_for_plaintext));
+    }
+    searchers::rank_results(&mut results);
+    if let Some(best) = results.first() {
+        cache_success(text, start_time, best);
+    }
+    let best = results.first().cloned();
+    *ALL_RESULTS
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner) = results;
+    best
+}
 
-        return Some(output);
+/// The result for input which is already plaintext
+fn input_as_plaintext(text: &str, check_result: &CheckResult) -> DecoderResult {
+    let mut crack_result = CrackResult::new(&Decoder::default(), text.to_string());
+    crack_result.checker_name = check_result.checker_name;
+    crack_result.confidence = check_result.confidence;
+    DecoderResult {
+        text: vec![text.to_string()],
+        path: vec![crack_result],
+        bytes: None,
     }
+}
 
-    // Build a new search tree
-    // This starts us with a node with no parents
-    // let search_tree = searchers::Tree::new(text.to_string());
-    cli_pretty_printing::success(&format!(
-        "DEBUG: lib.rs - Calling search_for_plaintext with text: {}",
-        text
-    ));
-    // Perform the search algorithm
-    // It will either return a failure or success.
-    let result = searchers::search_for_plaintext(text.clone());
-    cli_pretty_printing::success(&format!(
-        "DEBUG: lib.rs - Result from search_for_plaintext: {:?}",
-        result.is_some()
-    ));
-    if let Some(ref res) = result {
-        cli_pretty_printing::success(&format!(
-            "DEBUG: lib.rs - Result has {} decoders in path",
-            res.path.len()
+/// Stores a successful result in the cache, warning if it can't be
+fn cache_success(text: &str, start_time: SystemTime, result: &DecoderResult) {
+    if let Err(e) = success_result_to_cache(&text.to_string(), start_time, result) {
+        cli_pretty_printing::warning(&format!(
+            "DEBUG: lib.rs - Error inserting decoder result into cache table: {}",
+            e
         ));
     }
+}
 
-    if let Some(output) = &result {
-        let cache_result = success_result_to_cache(&text, start_time, output);
-        match cache_result {
-            Ok(_) => (),
-            Err(e) => {
-                cli_pretty_printing::warning(&format!(
-                    "DEBUG: lib.rs - Error inserting decoder result into cache table: {}",
-                    e
-                ));
-            }
-        };
-    }
+/// The decode which looked most like plaintext in the last search, and how likely it is to
+/// be plaintext from 0 to 1
+/// When `perform_cracking` returns None no checker identified any decode, but the Entropy
+/// Checker's scores still pick out the decode closest to plaintext, if one came close.
+pub fn most_likely_candidate() -> Option<(DecoderResult, f32)> {
+    searchers::most_likely_candidate()
+}
 
-    result
+/// The decodes which looked most like plaintext in the last search, most likely first, and
+/// how likely each is to be plaintext from 0 to 1
+/// Up to `show_candidates` of them are kept, for when nothing was identified.
+pub fn best_candidates() -> Vec<(DecoderResult, f32)> {
+    searchers::best_candidates()
+}
+
+/// Every distinct plaintext the last search in all_results mode found, best first
+/// Ranked by the confidence of the checker which accepted each, then by how few decoders
+/// it took to reach it. `perform_cracking` returns the first of these.
+pub fn all_results() -> Vec<DecoderResult> {
+    ALL_RESULTS
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .clone()
+}
+
+/// How the last text cracked ended: solved by a search, found in the cache, already
+/// plaintext, unsolved or cancelled. None if no text has been cracked yet.
+pub fn last_run_outcome() -> Option<RunOutcome> {
+    *LAST_RUN_OUTCOME
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+/// Whether the A* search of this text ran out of time and saved a checkpoint
+/// Cracking the text again with `resume` set carries on from the checkpoint instead of
+/// starting over.
+pub fn has_checkpoint(text: &str) -> bool {
+    searchers::checkpoint::exists(text)
 }
 
 /// Checks if the given input is plaintext or not
@@ -264,7 +696,7 @@ fn success_result_to_cache(
     text: &String,
     start_time: SystemTime,
     result: &DecoderResult,
-) -> Result<usize, rusqlite::Error> {
+) -> Result<(), storage::backend::StorageError> {
     let stop_time = SystemTime::now();
     let execution_time_ms: i64 = match stop_time.duration_since(start_time) {
         Ok(duration) => duration.as_millis().try_into().unwrap_or(-2),
@@ -278,14 +710,42 @@ fn success_result_to_cache(
     let cache_entry = storage::database::CacheEntry {
         uuid: uuid::Uuid::new_v4(),
         encoded_text: String::from(text),
-        decoded_text: match result.text.last() {
+        // Decoders which return several decodes put the identified one first
+        decoded_text: match result.text.first() {
             Some(d_text) => String::from(d_text),
             None => String::new(),
         },
         path: result.path.clone(),
         execution_time_ms,
-    };
-    storage::database::insert_cache(&cache_entry)
+        checker: String::new(),
+        sensitivity: None,
+        confidence: 0.0,
+    }
+    .with_provenance_from_path();
+    // Remember it the same way reading it back from the database would give it
+    storage::memory_cache::put(
+        text,
+        DecoderResult {
+            text: vec![cache_entry.decoded_text.clone()],
+            path: cache_entry.path.clone(),
+            bytes: result.bytes.clone(),
+        },
+    );
+    storage::backend::storage().insert_cache(&cache_entry)
+}
+
+/// The bytes the last decoder of a cached path gave, when it gave bytes rather than text
+/// Only the text is cached, so this is how a decoded file such as a ZIP archive comes back.
+fn bytes_from_path(path: &[CrackResult]) -> Option<Vec<u8>> {
+    let last = path.last()?;
+    if last
+        .unencrypted_text
+        .as_ref()
+        .is_some_and(|text| !text.is_empty())
+    {
+        return None;
+    }
+    last.unencrypted_bytes.as_ref()?.first().cloned()
 }
 
 /// DecoderResult is the result of decoders
@@ -298,6 +758,10 @@ pub struct DecoderResult {
     /// The CrackResult contains more than just each decoder, such as the keys used
     /// or the checkers used.
     pub path: Vec<CrackResult>,
+    /// The value as bytes, when the last decoder gave something that isn't UTF-8 text
+    /// `text` then holds a lossy rendering of it for display, and only decoders which
+    /// read bytes are run on it.
+    pub bytes: Option<Vec<u8>>,
 }
 
 /// Creates a default DecoderResult with Default as the text / path
@@ -306,17 +770,40 @@ impl Default for DecoderResult {
         DecoderResult {
             text: vec!["Default".to_string()],
             path: vec![CrackResult::new(&Decoder::default(), "Default".to_string())],
+            bytes: None,
+        }
+    }
+}
+
+/// The input to a search, a text no decoder has decoded yet
+impl From<&str> for DecoderResult {
+    fn from(text: &str) -> Self {
+        DecoderResult {
+            text: vec![text.to_string()],
+            path: vec![],
+            bytes: None,
         }
     }
 }
 
 /// Lets us create a new decoderResult with given text
 impl DecoderResult {
+    /// The input to a search when it is bytes which aren't UTF-8 text, such as a binary file
+    /// The text is a lossy rendering of them, and only decoders which read bytes run on them.
+    pub fn from_bytes(bytes: Vec<u8>) -> Self {
+        DecoderResult {
+            text: vec![String::from_utf8_lossy(&bytes).into_owned()],
+            path: vec![],
+            bytes: Some(bytes),
+        }
+    }
+
     /// It's only used in tests so it thinks its dead code
     fn _new(text: &str) -> Self {
         DecoderResult {
             text: vec![text.to_string()],
             path: vec![CrackResult::new(&Decoder::default(), "Default".to_string())],
+            bytes: None,
         }
     }
 }
@@ -360,8 +847,16 @@ impl Drop for TestDatabase {
     fn drop(&mut self) {
         let mut db_file_path = self.path.as_path().to_path_buf();
         db_file_path.push("database.sqlite");
+        crate::storage::database::close_idle_connections();
         let _ = std::fs::remove_file(&db_file_path);
+        for suffix in ["-wal", "-shm"] {
+            let mut sidecar = db_file_path.clone().into_os_string();
+            sidecar.push(suffix);
+            let _ = std::fs::remove_file(sidecar);
+        }
         let _ = std::fs::remove_dir(&self.path);
+        // The database is gone, so results remembered from it are too
+        crate::storage::memory_cache::clear();
     }
 }
 
@@ -413,6 +908,23 @@ mod tests {
         assert!(result.unwrap().path.len() == 1);
     }
 
+    #[test]
+    fn test_perform_cracking_finds_message_hidden_in_plaintext() {
+        let _test_db = TestDatabase::default();
+        set_test_db_path();
+
+        // "hello world" as zero width spaces for 0 and zero width non-joiners for 1
+        let hidden: String = "hello world"
+            .bytes()
+            .flat_map(|byte| (0..8).rev().map(move |i| byte >> i & 1))
+            .map(|bit| if bit == 1 { '\u{200C}' } else { '\u{200B}' })
+            .collect();
+        let text = format!("hello there my friend, how are you doing today{}", hidden);
+        let result = perform_cracking(&text, Config::default()).unwrap();
+        assert_eq!(result.text[0], "hello world");
+        assert_eq!(result.path[0].decoder, "Zero-width");
+    }
+
     #[ignore]
     #[test]
     // Previously this would decode to `Fchohs as 13 dzoqsg!` because the English checker wasn't that good
@@ -1,15 +1,39 @@
-use ciphey::cli::parse_cli_args;
+use std::time::Instant;
+
+use ciphey::cli::json_output::print_json_result;
+use ciphey::cli::{cancel_on_ctrl_c, parse_cli_args};
 use ciphey::cli_pretty_printing::{program_exiting_successful_decoding, success};
-use ciphey::perform_cracking;
+use ciphey::config::OutputFormat;
+use ciphey::decoders::hashes::identify;
+use ciphey::{perform_cracking_bytes_with_cancel, CancellationToken};
 
 fn main() {
     // Turn CLI arguments into a library object
-    let (text, config) = parse_cli_args();
-    let result = perform_cracking(&text, config);
+    let (input, config) = parse_cli_args();
+    // Binary input is shown and looked up as a lossy rendering of its bytes
+    let text = String::from_utf8_lossy(&input).into_owned();
+    // The terminal UI shows the search and prints the result itself
+    if config.tui {
+        if let Err(e) = ciphey::cli::tui::run_tui(&input, config) {
+            eprintln!("{}", ciphey::cli_pretty_printing::warning(&e));
+            std::process::exit(1);
+        }
+        return;
+    }
+    let output_format = config.output_format;
+    let started = Instant::now();
+    // Ctrl-C stops the search and shows the best decodes found so far
+    let cancel = CancellationToken::new();
+    cancel_on_ctrl_c(&cancel);
+    let result = perform_cracking_bytes_with_cancel(&input, config, &cancel);
     success(&format!(
         "DEBUG: main.rs - Result from perform_cracking: {:?}",
         result.is_some()
     ));
+    if output_format == OutputFormat::Json {
+        print_json_result(&text, result.as_ref(), started.elapsed());
+        return;
+    }
     match result {
         // TODO: As result have array of CrackResult used,
         // we can print in better way with more info
@@ -22,7 +46,22 @@ fn main() {
         }
         None => {
             success("DEBUG: main.rs - Got None result, calling failed_to_decode");
-            ciphey::cli_pretty_printing::failed_to_decode()
+            if cancel.is_cancelled() {
+                ciphey::cli_pretty_printing::search_cancelled();
+            } else {
+                ciphey::cli_pretty_printing::failed_to_decode();
+            }
+            ciphey::cli_pretty_printing::best_candidates(&ciphey::best_candidates());
+            if ciphey::has_checkpoint(&text) {
+                ciphey::cli_pretty_printing::search_can_be_resumed();
+            }
+            let algorithms: Vec<&str> = identify(&text)
+                .iter()
+                .map(|algorithm| algorithm.name)
+                .collect();
+            if !algorithms.is_empty() {
+                ciphey::cli_pretty_printing::input_looks_like_a_hash(&algorithms);
+            }
         }
     }
 }
@@ -0,0 +1,342 @@
+//! A long-lived JSON-RPC 2.0 service streaming a search's progress, for `ciphey rpc`
+//! Requests and responses are one JSON object on each line, over stdin and stdout or a TCP
+//! connection. While a text is cracked its events are sent as `progress` notifications
+//! naming the request, so a client can show the search live, and `cancel` stops it.
+//!
+//! ```text
+//! → {"jsonrpc": "2.0", "id": 1, "method": "crack", "params": {"text": "aGk=", "timeout": 5}}
+//! ← {"jsonrpc": "2.0", "method": "progress", "params": {"id": 1, "event": "started", ...}}
+//! ← {"jsonrpc": "2.0", "method": "progress", "params": {"id": 1, "event": "nodes_expanded", ...}}
+//! ← {"jsonrpc": "2.0", "id": 1, "result": {"outcome": "solved", "plaintext": "hi", ...}}
+//! ```
+//!
+//! | Method     | Params             | Result                                               |
+//! |------------|--------------------|------------------------------------------------------|
+//! | `crack`    | `text`, `timeout`  | The same object as `--output-format json`            |
+//! | `cancel`   | `id` of a `crack`  | `{"cancelled": true}`, or false if it had finished   |
+//! | `decoders` |                    | Every decoder, as `ciphey docs decoders --format json` |
+//! | `stats`    |                    | What `ciphey stats` shows                            |
+
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::cli::docs::get_decoder_docs;
+use crate::server::{crack_for_client, CrackRequest};
+use crate::CancellationToken;
+
+/// The request wasn't JSON
+pub const PARSE_ERROR: i64 = -32700;
+
+/// The request wasn't a JSON-RPC request this service accepts
+pub const INVALID_REQUEST: i64 = -32600;
+
+/// There is no such method
+pub const METHOD_NOT_FOUND: i64 = -32601;
+
+/// The params weren't what the method takes
+pub const INVALID_PARAMS: i64 = -32602;
+
+/// The method failed, such as the statistics not being readable
+pub const SERVER_ERROR: i64 = -32000;
+
+/// Where responses and notifications are written, shared by every search of a connection
+pub type RpcWriter = Arc<Mutex<Box<dyn Write + Send>>>;
+
+/// A JSON-RPC request
+#[derive(Debug, Deserialize)]
+struct RpcRequest {
+    /// Must be "2.0"
+    jsonrpc: String,
+    /// What the response is matched up with, None for a notification
+    #[serde(default)]
+    id: Option<Value>,
+    /// The method to call
+    method: String,
+    /// The method's params
+    #[serde(default)]
+    params: Value,
+}
+
+/// The params of `cancel`
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CancelParams {
+    /// The id of the `crack` request to cancel
+    id: Value,
+}
+
+/// The searches a connection has running, by the id of the request which started them
+type Running = Arc<Mutex<HashMap<String, CancellationToken>>>;
+
+/// Writes a message on its own line
+fn send(writer: &RpcWriter, message: &Value) {
+    let mut writer = writer
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    let written = writeln!(writer, "{}", message).and_then(|()| writer.flush());
+    if let Err(e) = written {
+        log::debug!("Could not write to the RPC client: {}", e);
+    }
+}
+
+/// A successful response
+pub fn result_response(id: &Value, result: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "result": result })
+}
+
+/// An error response
+pub fn error_response(id: &Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+/// Parses the params a method takes
+fn params<T: serde::de::DeserializeOwned>(params: Value) -> Result<T, (i64, String)> {
+    serde_json::from_value(params).map_err(|e| (INVALID_PARAMS, e.to_string()))
+}
+
+/// Answers every request read from `reader` until it ends, writing to `writer`
+/// Searches run on their own threads, so `cancel` can be read while they run. Once the
+/// reader ends the searches still running are waited for, so each gets its response.
+pub fn serve_rpc(reader: impl BufRead, writer: RpcWriter) {
+    let running: Running = Arc::default();
+    let mut searches = Vec::new();
+    for line in reader.lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let request: RpcRequest = match serde_json::from_str(&line) {
+            Ok(request) => request,
+            Err(e) => {
+                let (code, message) = if serde_json::from_str::<Value>(&line).is_ok() {
+                    (INVALID_REQUEST, format!("Not a JSON-RPC request: {}", e))
+                } else {
+                    (PARSE_ERROR, format!("Not JSON: {}", e))
+                };
+                send(&writer, &error_response(&Value::Null, code, &message));
+                continue;
+            }
+        };
+        if request.jsonrpc != "2.0" {
+            let id = request.id.unwrap_or(Value::Null);
+            let message = "Only JSON-RPC 2.0 is spoken";
+            send(&writer, &error_response(&id, INVALID_REQUEST, message));
+            continue;
+        }
+        if let Some(search) = handle_request(request, &writer, &running) {
+            searches.push(search);
+        }
+        searches.retain(|search: &std::thread::JoinHandle<()>| !search.is_finished());
+    }
+    for search in searches {
+        let _ = search.join();
+    }
+}
+
+/// Answers a request, giving the thread cracking the text if it starts a search
+fn handle_request(
+    request: RpcRequest,
+    writer: &RpcWriter,
+    running: &Running,
+) -> Option<std::thread::JoinHandle<()>> {
+    let answer = |id: &Option<Value>, answer: Result<Value, (i64, String)>| {
+        // Notifications aren't answered, even when they fail
+        if let Some(id) = id {
+            let response = match answer {
+                Ok(result) => result_response(id, result),
+                Err((code, message)) => error_response(id, code, &message),
+            };
+            send(writer, &response);
+        }
+    };
+    match request.method.as_str() {
+        "crack" => {
+            // A crack sent as a notification would have nowhere to send its result
+            let id = request.id?;
+            let crack: CrackRequest = match params(request.params) {
+                Ok(crack) => crack,
+                Err(e) => {
+                    answer(&Some(id), Err(e));
+                    return None;
+                }
+            };
+            let key = id.to_string();
+            let cancel = CancellationToken::new();
+            {
+                let mut running = running
+                    .lock()
+                    .unwrap_or_else(std::sync::PoisonError::into_inner);
+                if running.contains_key(&key) {
+                    let message = format!("A search with the id {} is already running", key);
+                    answer(&Some(id), Err((INVALID_REQUEST, message)));
+                    return None;
+                }
+                running.insert(key.clone(), cancel.clone());
+            }
+            let writer = Arc::clone(writer);
+            let running = Arc::clone(running);
+            Some(std::thread::spawn(move || {
+                let mut on_event = |event| {
+                    let mut params = serde_json::to_value(event).unwrap_or_default();
+                    if let Value::Object(fields) = &mut params {
+                        fields.insert(String::from("id"), id.clone());
+                    }
+                    let notification =
+                        json!({ "jsonrpc": "2.0", "method": "progress", "params": params });
+                    send(&writer, &notification);
+                };
+                let result =
+                    crack_for_client(&crack.text, crack.timeout, &cancel, Some(&mut on_event));
+                running
+                    .lock()
+                    .unwrap_or_else(std::sync::PoisonError::into_inner)
+                    .remove(&key);
+                let result = serde_json::to_value(result).unwrap_or_default();
+                send(&writer, &result_response(&id, result));
+            }))
+        }
+        "cancel" => {
+            let cancelled = params::<CancelParams>(request.params).map(|params| {
+                let running = running
+                    .lock()
+                    .unwrap_or_else(std::sync::PoisonError::into_inner);
+                let search = running.get(&params.id.to_string());
+                search.inspect(|cancel| cancel.cancel());
+                json!({ "cancelled": search.is_some() })
+            });
+            answer(&request.id, cancelled);
+            None
+        }
+        "decoders" => {
+            let decoders = serde_json::to_value(get_decoder_docs()).unwrap_or_default();
+            answer(&request.id, Ok(decoders));
+            None
+        }
+        "stats" => {
+            let stats = crate::storage::stats::gather()
+                .map(|report| serde_json::to_value(report).unwrap_or_default())
+                .map_err(|e| {
+                    let message = format!("Could not read the statistics: {}", e);
+                    (SERVER_ERROR, message)
+                });
+            answer(&request.id, stats);
+            None
+        }
+        method => {
+            let message = format!("There is no method {}", method);
+            answer(&request.id, Err((METHOD_NOT_FOUND, message)));
+            None
+        }
+    }
+}
+
+/// Answers every connection made to `listener` until the process ends, each on its own
+/// thread, after [`crate::server::prepare_service`]
+pub fn serve_rpc_on(listener: &TcpListener) {
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                log::warn!("Could not accept a connection: {}", e);
+                continue;
+            }
+        };
+        let Ok(writer) = stream.try_clone() else {
+            continue;
+        };
+        let writer: RpcWriter = Arc::new(Mutex::new(Box::new(writer)));
+        std::thread::spawn(move || serve_rpc(std::io::BufReader::new(stream), writer));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A writer the tests can read back from
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Serves the requests, giving every message written back
+    fn serve(requests: &str) -> Vec<Value> {
+        let captured = Captured::default();
+        let writer: RpcWriter = Arc::new(Mutex::new(Box::new(captured.clone())));
+        serve_rpc(requests.as_bytes(), writer);
+        let written = captured.0.lock().unwrap().clone();
+        String::from_utf8(written)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn bad_requests_get_json_rpc_errors() {
+        let responses = serve(concat!(
+            "not json\n",
+            "{\"id\": 1}\n",
+            "{\"jsonrpc\": \"1.0\", \"id\": 2, \"method\": \"stats\"}\n",
+            "{\"jsonrpc\": \"2.0\", \"id\": 3, \"method\": \"nope\"}\n",
+            "{\"jsonrpc\": \"2.0\", \"id\": 4, \"method\": \"crack\", \"params\": {}}\n",
+            "{\"jsonrpc\": \"2.0\", \"method\": \"nope\"}\n",
+        ));
+        let codes: Vec<&Value> = responses
+            .iter()
+            .map(|response| &response["error"]["code"])
+            .collect();
+        assert_eq!(
+            codes,
+            [
+                PARSE_ERROR,
+                INVALID_REQUEST,
+                INVALID_REQUEST,
+                METHOD_NOT_FOUND,
+                INVALID_PARAMS
+            ]
+        );
+        assert_eq!(responses[3]["id"], 3);
+    }
+
+    #[test]
+    fn cancelling_a_finished_search_says_so() {
+        let responses = serve(
+            "{\"jsonrpc\": \"2.0\", \"id\": 1, \"method\": \"cancel\", \"params\": {\"id\": 7}}\n",
+        );
+        assert_eq!(responses[0]["result"]["cancelled"], false);
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn cracks_stream_progress_before_their_result() {
+        crate::set_test_db_path();
+        crate::server::prepare_service(crate::config::Config::default());
+        let responses = serve(concat!(
+            "{\"jsonrpc\": \"2.0\", \"id\": \"a\", \"method\": \"crack\",",
+            " \"params\": {\"text\": \"aGVsbG8gdGhlcmUgcnBjIGNsaWVudA==\"}}\n",
+        ));
+        let (result, progress) = responses.split_last().unwrap();
+        assert_eq!(result["id"], "a");
+        assert_eq!(result["result"]["plaintext"], "hello there rpc client");
+        assert!(progress
+            .iter()
+            .all(|message| message["method"] == "progress" && message["params"]["id"] == "a"));
+        assert_eq!(progress.last().unwrap()["params"]["event"], "finished");
+    }
+}
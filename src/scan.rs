@@ -0,0 +1,461 @@
+//! Scans every file in a directory or ZIP archive for encoded strings and cracks them
+//! Each file is read as text and the `scan_extractors` in the config pull candidate encoded
+//! strings out of it, such as runs of Base64 or hex. Every candidate is cracked with
+//! [`crate::batch::perform_batch_cracking`], so a string which turns up in many files is
+//! only searched for once, and the report says what each file held.
+//!
+//! ZIP archives, whether the path given or found in a directory, are scanned entry by entry
+//! without being extracted to disk.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{Cursor, Read, Seek};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::batch::{perform_batch_cracking, MAX_BATCH_SIZE};
+use crate::config::Config;
+use crate::storage::history::RunOutcome;
+use crate::DecoderResult;
+
+/// A regex pulling candidate encoded strings out of scanned files
+/// ```toml
+/// [[scan_extractors]]
+/// name = "jwt"
+/// pattern = "eyJ[A-Za-z0-9_-]+\\.[A-Za-z0-9_-]+\\.[A-Za-z0-9_-]*"
+/// ```
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct ScanExtractor {
+    /// What the extractor finds, shown next to each string it found
+    pub name: String,
+    /// The regex, every match of which is a candidate
+    pub pattern: String,
+}
+
+/// The extractors used when the config doesn't list any: runs of hex and of Base64
+/// Hex is first, so runs of hex, which are also Base64 characters, are named as hex.
+pub fn default_extractors() -> Vec<ScanExtractor> {
+    vec![
+        ScanExtractor {
+            name: String::from("hex"),
+            pattern: String::from("\\b(?:[0-9a-fA-F]{2}){8,}\\b"),
+        },
+        ScanExtractor {
+            name: String::from("base64"),
+            pattern: String::from("[A-Za-z0-9+/_-]{16,}={0,2}"),
+        },
+    ]
+}
+
+/// A string an extractor found in a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Candidate {
+    /// The string found
+    pub text: String,
+    /// The name of the extractor which found it
+    pub extractor: String,
+    /// How many bytes into the file it was first found
+    pub offset: usize,
+}
+
+/// A candidate which was cracked
+#[derive(Debug, Clone)]
+pub struct Finding {
+    /// Where and how the string was found
+    pub candidate: Candidate,
+    /// The plaintext and the path to it
+    pub result: DecoderResult,
+    /// How the candidate's run ended
+    pub outcome: RunOutcome,
+    /// How long the candidate took to crack
+    pub duration: Duration,
+}
+
+/// What was found in one file
+#[derive(Debug, Clone, Default)]
+pub struct FileReport {
+    /// The file, with `!` and the entry name for entries of ZIP archives
+    pub path: String,
+    /// How many distinct candidates the extractors found in it
+    pub candidates: usize,
+    /// The candidates which were cracked, in the order they appear in the file
+    pub findings: Vec<Finding>,
+    /// Why the file couldn't be scanned, if it couldn't
+    pub error: Option<String>,
+}
+
+/// What a scan found in every file
+#[derive(Debug, Clone, Default)]
+pub struct ScanReport {
+    /// Every file scanned, in the order they were walked
+    pub files: Vec<FileReport>,
+    /// How many distinct candidates weren't cracked, as a scan cracks at most
+    /// `MAX_BATCH_SIZE` of them, the first found first
+    pub skipped: usize,
+}
+
+/// Errors which stop a scan before it starts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScanError {
+    /// The path to scan can't be read
+    Unreadable {
+        /// The path given
+        path: String,
+        /// Why it can't be read
+        reason: String,
+    },
+    /// An extractor's pattern isn't a valid regex
+    BadExtractor {
+        /// The name of the extractor
+        name: String,
+        /// Why the pattern isn't valid
+        reason: String,
+    },
+}
+
+impl std::fmt::Display for ScanError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScanError::Unreadable { path, reason } => {
+                write!(f, "Can't scan '{}': {}", path, reason)
+            }
+            ScanError::BadExtractor { name, reason } => {
+                write!(f, "The {} extractor isn't a valid regex: {}", name, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ScanError {}
+
+/// Compiles the extractors, keeping their names
+///
+/// # Errors
+/// Returns `ScanError::BadExtractor` for the first pattern which isn't a valid regex.
+pub fn compile_extractors(extractors: &[ScanExtractor]) -> Result<Vec<(String, Regex)>, ScanError> {
+    extractors
+        .iter()
+        .map(|extractor| {
+            Regex::new(&extractor.pattern)
+                .map(|regex| (extractor.name.clone(), regex))
+                .map_err(|e| ScanError::BadExtractor {
+                    name: extractor.name.clone(),
+                    reason: e.to_string(),
+                })
+        })
+        .collect()
+}
+
+/// Every distinct string the extractors find in the contents, in the order they appear
+/// A string found by several extractors is kept once, under the first extractor listed.
+pub fn extract_candidates(contents: &str, extractors: &[(String, Regex)]) -> Vec<Candidate> {
+    let mut seen = HashSet::new();
+    let mut candidates: Vec<Candidate> = extractors
+        .iter()
+        .flat_map(|(name, regex)| {
+            regex.find_iter(contents).map(move |found| Candidate {
+                text: found.as_str().to_string(),
+                extractor: name.clone(),
+                offset: found.start(),
+            })
+        })
+        .filter(|candidate| seen.insert(candidate.text.clone()))
+        .collect();
+    candidates.sort_by_key(|candidate| candidate.offset);
+    candidates
+}
+
+/// Whether the bytes are a ZIP archive
+fn is_zip(bytes: &[u8]) -> bool {
+    bytes.starts_with(b"PK\x03\x04")
+}
+
+/// Calls `visit` with the name and contents of every file under `path`, reading into ZIP
+/// archives. Files larger than `max_bytes` are skipped, 0 meaning there is no limit.
+///
+/// # Errors
+/// Returns `ScanError::Unreadable` if `path` itself can't be read. Files under it which
+/// can't be read are given to `visit` with their error instead.
+fn walk<F>(path: &Path, max_bytes: usize, visit: &mut F) -> Result<(), ScanError>
+where
+    F: FnMut(String, Result<Vec<u8>, String>),
+{
+    let unreadable = |e: std::io::Error| ScanError::Unreadable {
+        path: path.display().to_string(),
+        reason: e.to_string(),
+    };
+    let metadata = fs::metadata(path).map_err(unreadable)?;
+    if metadata.is_file() {
+        visit_file(path, metadata.len(), max_bytes, visit);
+        return Ok(());
+    }
+    walk_directory(path, max_bytes, visit);
+    Ok(())
+}
+
+/// Visits every file in a directory and the directories in it, in order of their names
+fn walk_directory<F>(directory: &Path, max_bytes: usize, visit: &mut F)
+where
+    F: FnMut(String, Result<Vec<u8>, String>),
+{
+    let entries = match fs::read_dir(directory) {
+        Ok(entries) => entries,
+        Err(e) => {
+            visit(directory.display().to_string(), Err(e.to_string()));
+            return;
+        }
+    };
+    let mut entries: Vec<PathBuf> = entries.flatten().map(|entry| entry.path()).collect();
+    entries.sort();
+    for entry in entries {
+        // Symbolic links are skipped so a link to a parent can't loop forever
+        match fs::symlink_metadata(&entry) {
+            Ok(metadata) if metadata.is_dir() => walk_directory(&entry, max_bytes, visit),
+            Ok(metadata) if metadata.is_file() => {
+                visit_file(&entry, metadata.len(), max_bytes, visit);
+            }
+            Ok(_) => {}
+            Err(e) => visit(entry.display().to_string(), Err(e.to_string())),
+        }
+    }
+}
+
+/// Reads a file for [`walk`], visiting each entry instead if it is a ZIP archive
+fn visit_file<F>(path: &Path, len: u64, max_bytes: usize, visit: &mut F)
+where
+    F: FnMut(String, Result<Vec<u8>, String>),
+{
+    let name = path.display().to_string();
+    if max_bytes != 0 && len > max_bytes as u64 {
+        visit(
+            name,
+            Err(format!(
+                "It is longer than {} bytes, so it was skipped",
+                max_bytes
+            )),
+        );
+        return;
+    }
+    match fs::read(path) {
+        Ok(bytes) if is_zip(&bytes) => {
+            if let Err(e) = visit_zip(&name, Cursor::new(bytes), max_bytes, visit) {
+                visit(name, Err(e));
+            }
+        }
+        Ok(bytes) => visit(name, Ok(bytes)),
+        Err(e) => visit(name, Err(e.to_string())),
+    }
+}
+
+/// Visits every file in a ZIP archive, named `archive!entry`
+///
+/// # Errors
+/// Returns why the archive can't be read if it isn't a ZIP archive after all.
+fn visit_zip<R, F>(
+    archive_name: &str,
+    reader: R,
+    max_bytes: usize,
+    visit: &mut F,
+) -> Result<(), String>
+where
+    R: Read + Seek,
+    F: FnMut(String, Result<Vec<u8>, String>),
+{
+    let mut archive = zip::ZipArchive::new(reader).map_err(|e| e.to_string())?;
+    for index in 0..archive.len() {
+        let mut entry = match archive.by_index(index) {
+            Ok(entry) => entry,
+            Err(e) => {
+                visit(format!("{}!#{}", archive_name, index), Err(e.to_string()));
+                continue;
+            }
+        };
+        if entry.is_dir() {
+            continue;
+        }
+        let name = format!("{}!{}", archive_name, entry.name());
+        if max_bytes != 0 && entry.size() > max_bytes as u64 {
+            visit(
+                name,
+                Err(format!(
+                    "It is longer than {} bytes, so it was skipped",
+                    max_bytes
+                )),
+            );
+            continue;
+        }
+        let mut bytes = Vec::new();
+        match entry.read_to_end(&mut bytes) {
+            Ok(_) => visit(name, Ok(bytes)),
+            Err(e) => visit(name, Err(e.to_string())),
+        }
+    }
+    Ok(())
+}
+
+/// Scans every file under `path`, or in it if it is a ZIP archive, cracking the strings
+/// the config's `scan_extractors` find
+///
+/// Like a batch, the human checker, `top_results` and `all_results` are turned off. Files
+/// longer than `max_input_bytes` are skipped.
+///
+/// # Errors
+/// Returns a `ScanError` if `path` can't be read or an extractor isn't a valid regex.
+///
+/// # Panics
+///
+/// Never, the candidates are cut to `MAX_BATCH_SIZE` before they are cracked.
+pub fn perform_scan(path: &Path, config: Config) -> Result<ScanReport, ScanError> {
+    let extractors = compile_extractors(&config.scan_extractors)?;
+    let mut files: Vec<(FileReport, Vec<Candidate>)> = Vec::new();
+    walk(path, config.max_input_bytes, &mut |name, contents| {
+        let mut report = FileReport {
+            path: name,
+            ..FileReport::default()
+        };
+        let candidates = match contents {
+            Ok(bytes) => extract_candidates(&String::from_utf8_lossy(&bytes), &extractors),
+            Err(e) => {
+                report.error = Some(e);
+                Vec::new()
+            }
+        };
+        report.candidates = candidates.len();
+        files.push((report, candidates));
+    })?;
+
+    // Strings found in several files are only cracked once
+    let mut seen = HashSet::new();
+    let mut texts: Vec<&str> = files
+        .iter()
+        .flat_map(|(_, candidates)| candidates.iter().map(|candidate| candidate.text.as_str()))
+        .filter(|text| seen.insert(*text))
+        .collect();
+    let skipped = texts.len().saturating_sub(MAX_BATCH_SIZE);
+    texts.truncate(MAX_BATCH_SIZE);
+    let mut cracked = HashMap::new();
+    let items = perform_batch_cracking(&texts, config).expect("texts were cut to a batch");
+    for item in items {
+        if let Some(result) = item.result {
+            cracked.insert(item.input, (result, item.outcome, item.duration));
+        }
+    }
+
+    let files = files
+        .into_iter()
+        .map(|(mut report, candidates)| {
+            report.findings = candidates
+                .into_iter()
+                .filter_map(|candidate| {
+                    let (result, outcome, duration) = cracked.get(&candidate.text)?.clone();
+                    Some(Finding {
+                        candidate,
+                        result,
+                        outcome,
+                        duration,
+                    })
+                })
+                .collect();
+            report
+        })
+        .collect();
+    Ok(ScanReport { files, skipped })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn base64_and_hex_runs_are_extracted_once_each() {
+        let extractors = compile_extractors(&default_extractors()).unwrap();
+        let contents = "user=bob token=aGVsbG8gdGhlcmUgZnJpZW5k id=68656c6c6f20776f726c64 \
+                        again aGVsbG8gdGhlcmUgZnJpZW5k short=aGk=";
+        let candidates = extract_candidates(contents, &extractors);
+        let found: Vec<(&str, &str)> = candidates
+            .iter()
+            .map(|candidate| (candidate.text.as_str(), candidate.extractor.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("aGVsbG8gdGhlcmUgZnJpZW5k", "base64"),
+                ("68656c6c6f20776f726c64", "hex"),
+            ]
+        );
+        assert_eq!(candidates[0].offset, 15);
+    }
+
+    #[test]
+    fn invalid_extractors_are_named() {
+        let extractors = [ScanExtractor {
+            name: String::from("broken"),
+            pattern: String::from("(unclosed"),
+        }];
+        let error = compile_extractors(&extractors).unwrap_err();
+        assert!(error.to_string().starts_with("The broken extractor"));
+    }
+
+    #[test]
+    fn zip_entries_are_visited_by_name() {
+        let mut archive = Vec::new();
+        {
+            let mut writer = zip::ZipWriter::new(Cursor::new(&mut archive));
+            let options = zip::write::FileOptions::default();
+            writer.start_file("logs/app.log", options).unwrap();
+            writer.write_all(b"token=aGVsbG8gdGhlcmUgZnJpZW5k").unwrap();
+            writer.add_directory("empty/", options).unwrap();
+            writer.finish().unwrap();
+        }
+        assert!(is_zip(&archive));
+
+        let mut visited = Vec::new();
+        visit_zip(
+            "dump.zip",
+            Cursor::new(archive),
+            0,
+            &mut |name, contents| {
+                visited.push((name, contents.unwrap()));
+            },
+        )
+        .unwrap();
+        assert_eq!(visited.len(), 1);
+        assert_eq!(visited[0].0, "dump.zip!logs/app.log");
+        assert_eq!(visited[0].1, b"token=aGVsbG8gdGhlcmUgZnJpZW5k");
+    }
+
+    #[test]
+    fn directories_are_walked_in_order_skipping_large_files() {
+        let root = std::env::temp_dir().join("ciphey_scan_walk_test");
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("nested")).unwrap();
+        fs::write(root.join("a.txt"), "small").unwrap();
+        fs::write(root.join("nested").join("b.txt"), "also small").unwrap();
+        fs::write(root.join("z.txt"), "x".repeat(100)).unwrap();
+
+        let mut visited = Vec::new();
+        walk(&root, 50, &mut |name, contents| {
+            visited.push((name, contents.is_ok()));
+        })
+        .unwrap();
+        let names: Vec<String> = visited
+            .iter()
+            .map(|(name, _)| {
+                Path::new(name)
+                    .strip_prefix(&root)
+                    .unwrap()
+                    .display()
+                    .to_string()
+            })
+            .collect();
+        assert_eq!(names, ["a.txt", "nested/b.txt", "z.txt"]);
+        assert!(visited[0].1 && visited[1].1);
+        assert!(!visited[2].1);
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
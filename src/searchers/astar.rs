@@ -33,17 +33,18 @@
 //! - Batch processing extracts multiple nodes from the priority queue
 //! - Special result nodes handle successful decodings in a thread-safe manner
 
-use crate::cli_pretty_printing;
-use crate::cli_pretty_printing::decoded_how_many_times;
 use crate::filtration_system::get_all_decoders;
 use crate::filtration_system::{get_decoder_by_name, get_decoder_tagged_decoders, MyResults};
 use crossbeam::channel::Sender;
 
 use log::{debug, trace};
 use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
 use std::collections::BinaryHeap;
+use std::hash::{Hash, Hasher};
 use std::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering as AtomicOrdering};
 use std::sync::{Arc, Mutex};
+use std::time::Instant;
 
 // Add imports for parallel processing
 use dashmap::DashSet;
@@ -51,12 +52,18 @@ use rayon::prelude::*;
 
 use crate::checkers::athena::Athena;
 use crate::checkers::checker_type::{Check, Checker};
+use crate::checkers::human_checker;
 use crate::checkers::CheckerTypes;
 use crate::config::get_config;
+use crate::decoders::crack_results::CrackResult;
+use crate::events::{self, SearchEvent};
+use crate::searchers::checkpoint::{
+    self, CheckpointNode, SearchCheckpoint, MAX_CHECKPOINT_NODES, MAX_CHECKPOINT_SEEN,
+};
 use crate::searchers::helper_functions::{
-    calculate_string_worth, generate_heuristic, update_decoder_stats,
+    calculate_string_worth, generate_heuristic, record_decoder_run, update_decoder_stats,
 };
-use crate::storage::wait_athena_storage;
+use crate::searchers::{offer_candidate, report_plaintext};
 use crate::DecoderResult;
 
 /// Threshold for pruning the seen_strings HashSet to prevent excessive memory usage
@@ -68,19 +75,29 @@ const INITIAL_PRUNE_THRESHOLD: usize = PRUNE_THRESHOLD;
 /// Maximum depth for search (used for dynamic threshold adjustment)
 const MAX_DEPTH: u32 = 100;
 
-/// Number of nodes to process in parallel
+/// Fewest nodes to process in parallel, more are processed when there are more threads
 const PARALLEL_BATCH_SIZE: usize = 10;
 
-/// Calculate a hash for a string to use in the seen_strings set
-fn calculate_hash(text: &str) -> String {
-    use std::collections::hash_map::DefaultHasher;
-    use std::hash::{Hash, Hasher};
-
+/// Calculate a hash for a string, or bytes which aren't text, to use in the seen_strings set
+fn calculate_hash<T: Hash + ?Sized>(value: &T) -> String {
     let mut hasher = DefaultHasher::new();
-    text.hash(&mut hasher);
+    value.hash(&mut hasher);
     hasher.finish().to_string()
 }
 
+/// The texts a decoder gave, or if it gave bytes which aren't text instead, the first of those
+/// Bytes come with a lossy rendering of them as their text, so they can be shown and scored.
+pub fn decoded_value(result: &CrackResult) -> (Vec<String>, Option<Vec<u8>>) {
+    match (&result.unencrypted_text, &result.unencrypted_bytes) {
+        (Some(text), _) if !text.is_empty() => (text.clone(), None),
+        (_, Some(bytes)) if !bytes.is_empty() => (
+            vec![String::from_utf8_lossy(&bytes[0]).to_string()],
+            Some(bytes[0].clone()),
+        ),
+        _ => (Vec::new(), None),
+    }
+}
+
 /// A* search node with priority based on f = g + h
 ///
 /// Each node represents a state in the search space, with:
@@ -135,39 +152,74 @@ impl PartialEq for AStarNode {
 
 impl Eq for AStarNode {}
 
+impl From<AStarNode> for CheckpointNode {
+    fn from(node: AStarNode) -> Self {
+        CheckpointNode {
+            text: node.state.text,
+            bytes: node.state.bytes,
+            path: node.state.path,
+            cost: node.cost,
+            heuristic: node.heuristic,
+            next_decoder_name: node.next_decoder_name,
+        }
+    }
+}
+
+impl From<CheckpointNode> for AStarNode {
+    fn from(node: CheckpointNode) -> Self {
+        AStarNode {
+            state: DecoderResult {
+                text: node.text,
+                path: node.path,
+                bytes: node.bytes,
+            },
+            cost: node.cost,
+            heuristic: node.heuristic,
+            total_cost: node.cost as f32 + node.heuristic,
+            next_decoder_name: node.next_decoder_name,
+        }
+    }
+}
+
 /// Thread-safe priority queue wrapper for A* open set
 struct ThreadSafePriorityQueue {
+    /// The underlying binary heap, guarded by a mutex
     queue: Mutex<BinaryHeap<AStarNode>>,
 }
 
 impl ThreadSafePriorityQueue {
+    /// Creates an empty priority queue
     fn new() -> Self {
         ThreadSafePriorityQueue {
             queue: Mutex::new(BinaryHeap::new()),
         }
     }
 
+    /// Pushes a node onto the queue
     fn push(&self, node: AStarNode) {
         let mut queue = self.queue.lock().unwrap();
         queue.push(node);
     }
 
+    /// Pops the node with the lowest total cost
     fn pop(&self) -> Option<AStarNode> {
         let mut queue = self.queue.lock().unwrap();
         queue.pop()
     }
 
+    /// Returns true if the queue has no nodes
     fn is_empty(&self) -> bool {
         let queue = self.queue.lock().unwrap();
         queue.is_empty()
     }
 
+    /// Returns the number of nodes in the queue
     fn len(&self) -> usize {
         let queue = self.queue.lock().unwrap();
         queue.len()
     }
 
-    // Extract a batch of nodes with highest priority
+    /// Extract a batch of nodes with highest priority
     fn extract_batch(&self, batch_size: usize) -> Vec<AStarNode> {
         let mut queue = self.queue.lock().unwrap();
         let mut batch = Vec::with_capacity(batch_size);
@@ -184,6 +236,27 @@ impl ThreadSafePriorityQueue {
     }
 }
 
+/// The special node marking a decoder's successful result, processed before anything else
+fn result_node(current_node: &AStarNode, res: &CrackResult) -> AStarNode {
+    let mut decoders_used = current_node.state.path.clone();
+    // A decoder can succeed with bytes which aren't text, such as a ZIP archive
+    let (text, bytes) = decoded_value(res);
+    decoders_used.push(res.clone());
+
+    // Create a special "result" node with a very low total_cost to ensure it's processed first
+    AStarNode {
+        state: DecoderResult {
+            text,
+            path: decoders_used,
+            bytes,
+        },
+        cost: current_node.cost + 1,
+        heuristic: -1000.0,  // Very negative to ensure highest priority
+        total_cost: -1000.0, // Very negative to ensure highest priority
+        next_decoder_name: Some("__RESULT__".to_string()), // Special marker
+    }
+}
+
 /// Expands a single node and returns a vector of new nodes
 fn expand_node(
     current_node: &AStarNode,
@@ -200,7 +273,11 @@ fn expand_node(
 
     // Determine which decoders to use based on next_decoder_name
     let mut decoders;
-    if let Some(decoder_name) = &current_node.next_decoder_name {
+    if current_node.state.bytes.is_some() {
+        // Only the few decoders which read bytes can run on bytes, so all of them are tried
+        trace!("Using every decoder which reads bytes");
+        decoders = get_all_decoders();
+    } else if let Some(decoder_name) = &current_node.next_decoder_name {
         // If we have a specific decoder name, filter all decoders to only include that one
         trace!("Using specific decoder: {}", decoder_name);
         // use get decoder by name from filtration
@@ -213,6 +290,9 @@ fn expand_node(
         decoders = get_decoder_tagged_decoders(&current_node.state);
     }
 
+    // Leave out decoders the user's chain rules forbid here
+    decoders.apply_chain_rules(&current_node.state.path);
+
     // Prevent reciprocal decoders from being applied consecutively
     if let Some(last_decoder) = current_node.state.path.last() {
         if last_decoder.checker_description.contains("reciprocal") {
@@ -238,7 +318,10 @@ fn expand_node(
         let checker = CheckerTypes::CheckAthena(athena_checker);
         // since we only have decoders with the same name
         // we are cheating and just run that one decoder lol
-        let decoder_results = decoders.run(&current_node.state.text[0], checker);
+        let decoder_results = match &current_node.state.bytes {
+            Some(bytes) => decoders.run_bytes(bytes, checker),
+            None => decoders.run(&current_node.state.text[0], checker),
+        };
 
         // Process decoder results
         match decoder_results {
@@ -247,23 +330,7 @@ fn expand_node(
                 // This part remains mostly unchanged, but instead of sending results directly,
                 // we'll return a special marker node that indicates a successful result
                 if res.success {
-                    let mut decoders_used = current_node.state.path.clone();
-                    let text = res.unencrypted_text.clone().unwrap_or_default();
-                    decoders_used.push(res.clone());
-
-                    // Create a special "result" node with a very low total_cost to ensure it's processed first
-                    let result_node = AStarNode {
-                        state: DecoderResult {
-                            text: text.clone(),
-                            path: decoders_used,
-                        },
-                        cost: current_node.cost + 1,
-                        heuristic: -1000.0, // Very negative to ensure highest priority
-                        total_cost: -1000.0, // Very negative to ensure highest priority
-                        next_decoder_name: Some("__RESULT__".to_string()), // Special marker
-                    };
-
-                    new_nodes.push(result_node);
+                    new_nodes.push(result_node(current_node, &res));
                 }
             }
             MyResults::Continue(results) => {
@@ -277,8 +344,8 @@ fn expand_node(
                     // Clone path to avoid modifying the original
                     let mut decoders_used = current_node.state.path.clone();
 
-                    // Get decoded text
-                    let text = r.unencrypted_text.clone().unwrap_or_default();
+                    // Get decoded text, or the bytes if it isn't text
+                    let (text, bytes) = decoded_value(&r);
 
                     // Skip if text is empty or already seen
                     if text.is_empty() {
@@ -288,13 +355,17 @@ fn expand_node(
 
                     // Check if string is worth being decoded
                     // uses string heuristics. if heuristic is too low, it goes bye bye!
-                    if !calculate_string_worth(&text[0]) {
+                    // Bytes are kept, as only decoders which read bytes will be run on them
+                    if bytes.is_none() && !calculate_string_worth(&text[0]) {
                         update_decoder_stats(r.decoder, false);
                         continue;
                     }
 
                     // Check if we've seen this string before to prevent cycles
-                    let text_hash = calculate_hash(&text[0]);
+                    let text_hash = match &bytes {
+                        Some(bytes) => calculate_hash(bytes.as_slice()),
+                        None => calculate_hash(text[0].as_str()),
+                    };
                     if !seen_strings.insert(text_hash) {
                         update_decoder_stats(r.decoder, false);
                         continue;
@@ -311,6 +382,7 @@ fn expand_node(
                         state: DecoderResult {
                             text,
                             path: decoders_used,
+                            bytes,
                         },
                         cost,
                         heuristic,
@@ -329,13 +401,15 @@ fn expand_node(
     }
 
     // If no decoder-tagged decoders or they didn't produce results,
-    // try all available decoders
-    if new_nodes.is_empty() {
+    // try all available decoders. Bytes have already been given to all of them.
+    if new_nodes.is_empty() && current_node.state.bytes.is_none() {
         // This part remains similar to the original implementation
         // but adapted to return nodes instead of adding them to open_set
 
         // Get all decoders
-        let all_decoders = get_all_decoders();
+        let mut all_decoders = get_all_decoders();
+        all_decoders.apply_chain_rules(&current_node.state.path);
+        all_decoders.apply_alphabet_rules(&current_node.state.text[0]);
 
         // Process each decoder
         for decoder in all_decoders.components {
@@ -361,10 +435,19 @@ fn expand_node(
             // Run the decoder
             let athena_checker = Checker::<Athena>::new();
             let checker = CheckerTypes::CheckAthena(athena_checker);
-            let result = decoder.crack(&current_node.state.text[0], &checker);
+            let started = Instant::now();
+            let mut result = decoder.crack(&current_node.state.text[0], &checker);
+            let runtime = started.elapsed();
+            record_decoder_run(decoder.get_name(), runtime);
+            result.runtime = Some(runtime);
 
             // Process the result
-            if let Some(decoded_text) = &result.unencrypted_text {
+            if result.success {
+                new_nodes.push(result_node(current_node, &result));
+                break;
+            }
+            let (decoded_text, bytes) = decoded_value(&result);
+            if !decoded_text.is_empty() {
                 if let Some(first_text) = decoded_text.first() {
                     // Skip if text is empty
                     if first_text.is_empty() {
@@ -373,7 +456,10 @@ fn expand_node(
                     }
 
                     // Check if we've seen this string before
-                    let text_hash = calculate_hash(first_text);
+                    let text_hash = match &bytes {
+                        Some(bytes) => calculate_hash(bytes.as_slice()),
+                        None => calculate_hash(first_text.as_str()),
+                    };
                     if !seen_strings.insert(text_hash) {
                         update_decoder_stats(decoder.get_name(), false);
                         continue;
@@ -392,6 +478,7 @@ fn expand_node(
                         state: DecoderResult {
                             text: decoded_text.clone(),
                             path: decoders_used,
+                            bytes,
                         },
                         cost,
                         heuristic,
@@ -445,33 +532,55 @@ fn expand_node(
 /// - `input`: The initial text to decode
 /// - `result_sender`: Channel to send the result when found
 /// - `stop`: Atomic boolean to signal when to stop the search
-pub fn astar(input: String, result_sender: Sender<Option<DecoderResult>>, stop: Arc<AtomicBool>) {
-    // Calculate heuristic before moving input
-    let initial_heuristic = generate_heuristic(&input, &[], &None);
-
-    let initial = DecoderResult {
-        text: vec![input],
-        path: vec![],
+pub fn astar(
+    input: DecoderResult,
+    result_sender: Sender<Option<DecoderResult>>,
+    stop: Arc<AtomicBool>,
+) {
+    // The checkpoint of a search which ran out of time is saved under its input
+    let checkpoint_key = input.text[0].clone();
+    let resumed = if get_config().resume {
+        checkpoint::load(&checkpoint_key)
+    } else {
+        None
     };
 
+    let initial_heuristic = generate_heuristic(&input.text[0], &[], &None);
+    let initial = input;
+
     // Thread-safe set to track visited states to prevent cycles
-    let seen_strings = DashSet::new();
+    let mut seen_strings = DashSet::new();
     let seen_results = DashSet::new(); // Track unique results
     let _seen_count = Arc::new(AtomicUsize::new(0));
 
     // Thread-safe priority queue for open set
     let open_set = ThreadSafePriorityQueue::new();
 
-    // Add initial node to open set
-    open_set.push(AStarNode {
-        state: initial,
-        cost: 0,
-        heuristic: initial_heuristic,
-        total_cost: 0.0,
-        next_decoder_name: None,
-    });
-
     let curr_depth = Arc::new(AtomicU32::new(1));
+
+    match resumed {
+        // Carry on from where the last search of this input got to
+        Some(resumed) => {
+            debug!(
+                "Resuming the search from a checkpoint of {} decodes",
+                resumed.nodes.len()
+            );
+            curr_depth.store(resumed.depth, AtomicOrdering::Relaxed);
+            seen_strings.extend(resumed.seen);
+            for node in resumed.nodes {
+                open_set.push(AStarNode::from(node));
+            }
+        }
+        // Add initial node to open set
+        None => open_set.push(AStarNode {
+            state: initial,
+            cost: 0,
+            heuristic: initial_heuristic,
+            total_cost: 0.0,
+            next_decoder_name: None,
+        }),
+    }
+
     let prune_threshold = Arc::new(AtomicUsize::new(INITIAL_PRUNE_THRESHOLD));
 
     // Main A* loop
@@ -483,7 +592,11 @@ pub fn astar(input: String, result_sender: Sender<Option<DecoderResult>>, stop:
         );
 
         // Extract a batch of nodes to process in parallel
-        let batch_size = std::cmp::min(PARALLEL_BATCH_SIZE, open_set.len());
+        // Each thread gets a node, so no core is left idle on wide searches
+        let batch_size = std::cmp::min(
+            PARALLEL_BATCH_SIZE.max(rayon::current_num_threads()),
+            open_set.len(),
+        );
         let batch = open_set.extract_batch(batch_size);
 
         trace!("Processing batch of {} nodes in parallel", batch.len());
@@ -518,69 +631,15 @@ pub fn astar(input: String, result_sender: Sender<Option<DecoderResult>>, stop:
                     }
 
                     debug!("DEBUG: Found result node with text: {:?}", node.state.text);
-                    // Found a result node
-                    decoded_how_many_times(curr_depth.load(AtomicOrdering::Relaxed));
-
-                    cli_pretty_printing::success(&format!(
-                        "DEBUG: astar.rs - Sending successful result with {} decoders",
-                        node.state.path.len()
-                    ));
-
-                    // If in top_results mode, store the result in the WaitAthena storage
-                    if get_config().top_results {
-                        // Store the first text in the vector (there should only be one)
-                        if let Some(plaintext) = node.state.text.first() {
-                            debug!(
-                                "DEBUG: Processing result in top_results mode with plaintext: {}",
-                                plaintext
-                            );
-                            // Get the last decoder used
-                            let decoder_name = if let Some(last_decoder) = node.state.path.last() {
-                                last_decoder.decoder.to_string()
-                            } else {
-                                "Unknown".to_string()
-                            };
-
-                            // Get the checker name from the last decoder
-                            let checker_name = if let Some(last_decoder) = node.state.path.last() {
-                                last_decoder.checker_name.to_string()
-                            } else {
-                                "Unknown".to_string()
-                            };
-
-                            // Only store results that have a valid checker name
-                            if !checker_name.is_empty() && checker_name != "Unknown" {
-                                log::trace!(
-                                    "Storing plaintext in WaitAthena storage: {} (decoder: {}, checker: {})",
-                                    plaintext,
-                                    decoder_name,
-                                    checker_name
-                                );
-                                wait_athena_storage::add_plaintext_result(
-                                    plaintext.clone(),
-                                    format!(
-                                        "Decoded successfully at depth {}",
-                                        curr_depth.load(AtomicOrdering::Relaxed)
-                                    ),
-                                    checker_name,
-                                    decoder_name,
-                                );
-                            }
-                        }
-                    }
-
-                    // Send the result
-                    result_sender
-                        .send(Some(node.state.clone()))
-                        .expect("Should successfully send the result");
-
-                    // Only stop if not in top_results mode
-                    if !get_config().top_results {
-                        // Stop further iterations
-                        stop.store(true, AtomicOrdering::Relaxed);
+                    if report_plaintext(
+                        &node.state,
+                        curr_depth.load(AtomicOrdering::Relaxed),
+                        &result_sender,
+                        &stop,
+                    ) {
+                        checkpoint::clear(&checkpoint_key);
                         return;
                     }
-                    // In top_results mode, continue searching
                 }
             }
         }
@@ -589,13 +648,22 @@ pub fn astar(input: String, result_sender: Sender<Option<DecoderResult>>, stop:
         for node in new_nodes {
             if let Some(decoder_name) = &node.next_decoder_name {
                 if decoder_name != "__RESULT__" {
+                    offer_candidate(&node.state);
+                    human_checker::attach_decode(&node.state);
                     open_set.push(node);
                 }
             } else {
+                human_checker::attach_decode(&node.state);
                 open_set.push(node);
             }
         }
 
+        events::publish(|| SearchEvent::NodesExpanded {
+            depth: curr_depth.load(AtomicOrdering::Relaxed),
+            nodes: batch.len(),
+            queued: open_set.len(),
+        });
+
         // Update current depth based on the nodes in the open set
         if let Some(top_node) = open_set.pop() {
             let new_depth = top_node.cost;
@@ -621,12 +689,30 @@ pub fn astar(input: String, result_sender: Sender<Option<DecoderResult>>, stop:
         }
     }
 
-    // If we get here, we've exhausted all possibilities without finding a solution
-    if !stop.load(AtomicOrdering::Relaxed) {
-        result_sender
-            .send(None)
-            .expect("Should successfully send the result");
+    // Stopped by the timeout or cancelled, so save where the search got to for --resume
+    if stop.load(AtomicOrdering::Relaxed) {
+        let checkpoint = SearchCheckpoint {
+            nodes: open_set
+                .extract_batch(MAX_CHECKPOINT_NODES)
+                .into_iter()
+                .map(CheckpointNode::from)
+                .collect(),
+            seen: seen_strings
+                .iter()
+                .take(MAX_CHECKPOINT_SEEN)
+                .map(|seen| seen.key().clone())
+                .collect(),
+            depth: curr_depth.load(AtomicOrdering::Relaxed),
+        };
+        checkpoint::save(&checkpoint_key, &checkpoint);
+        return;
     }
+
+    // If we get here, we've exhausted all possibilities without finding a solution
+    checkpoint::clear(&checkpoint_key);
+    result_sender
+        .send(None)
+        .expect("Should successfully send the result");
 }
 
 #[cfg(test)]
@@ -640,20 +726,56 @@ mod tests {
         let stop = Arc::new(AtomicBool::new(false));
 
         // Run A* with empty input
-        astar("".to_string(), sender, stop);
+        astar("".into(), sender, stop);
 
         // Should receive None since there's nothing to decode
         let result = receiver.recv().unwrap();
         assert!(result.is_none());
     }
 
+    #[test]
+    fn astar_passes_bytes_between_decoders() {
+        let (sender, receiver) = bounded::<Option<DecoderResult>>(1);
+        let stop = Arc::new(AtomicBool::new(false));
+
+        // Unpadded Base64 of gzipped "hello world", which the gzip decoder can't read itself
+        astar(
+            "H4sIAAAAAAACA8tIzcnJVyjPL8pJAQCFEUoNCwAAAA".into(),
+            sender,
+            stop,
+        );
+
+        let result = receiver.recv().unwrap().expect("should decode");
+        assert_eq!(result.text[0], "hello world");
+        let path: Vec<&str> = result.path.iter().map(|step| step.decoder).collect();
+        assert_eq!(path, vec!["Base64", "Gzip"]);
+    }
+
+    #[test]
+    fn plaintext_found_after_the_expected_decoder_failed_is_a_result() {
+        // Base64 is expected next, but only Hexadecimal decodes this
+        let node = AStarNode {
+            state: DecoderResult::from("68656c6c6f207468657265206465636f64657273206c697374"),
+            cost: 1,
+            heuristic: 0.0,
+            total_cost: 1.0,
+            next_decoder_name: Some(String::from("Base64")),
+        };
+        let nodes = expand_node(&node, &DashSet::new(), &Arc::new(AtomicBool::new(false)), 0);
+        let result = nodes
+            .iter()
+            .find(|node| node.next_decoder_name.as_deref() == Some("__RESULT__"))
+            .expect("the plaintext should be a result");
+        assert_eq!(result.state.text[0], "hello there decoders list");
+    }
+
     #[test]
     fn astar_prevents_cycles() {
         let (sender, receiver) = bounded::<Option<DecoderResult>>(1);
         let stop = Arc::new(AtomicBool::new(false));
 
         // Run A* with input that could cause cycles
-        astar("AAAA".to_string(), sender, stop);
+        astar("AAAA".into(), sender, stop);
 
         // Should eventually complete without hanging
         let _ = receiver.recv().unwrap();
@@ -668,7 +790,7 @@ mod tests {
         let stop = Arc::new(AtomicBool::new(false));
 
         // Run A* in a separate thread with Base64 encoded "Hello World"
-        let input = "SGVsbG8gV29ybGQ=".to_string();
+        let input = DecoderResult::from("SGVsbG8gV29ybGQ=");
         let stop_clone = stop.clone();
 
         std::thread::spawn(move || {
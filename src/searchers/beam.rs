@@ -0,0 +1,69 @@
+//! # Beam Search
+//!
+//! Beam search decodes one depth at a time like breadth first search, but only the `width`
+//! decodes of each depth the heuristic ranks most promising are decoded further.
+//! It reaches deep plaintexts breadth first search would take too long to get to,
+//! at the cost of missing plaintexts behind decodes which looked unpromising.
+
+use crossbeam::channel::Sender;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+use super::bfs::search_by_depth;
+use super::helper_functions::generate_heuristic;
+use crate::DecoderResult;
+
+/// Searches for the plaintext, decoding at most `width` decodes of each depth further
+pub fn beam(
+    input: DecoderResult,
+    result_sender: Sender<Option<DecoderResult>>,
+    stop: Arc<AtomicBool>,
+    width: usize,
+) {
+    search_by_depth(input, result_sender, stop, |decodes| {
+        most_promising(decodes, width)
+    });
+}
+
+/// The `width` decodes with the lowest heuristic, most promising first
+fn most_promising(decodes: Vec<DecoderResult>, width: usize) -> Vec<DecoderResult> {
+    let mut ranked: Vec<(f32, DecoderResult)> = decodes
+        .into_iter()
+        .map(|decode| {
+            (
+                generate_heuristic(&decode.text[0], &decode.path, &None),
+                decode,
+            )
+        })
+        .collect();
+    ranked.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+    ranked.truncate(width.max(1));
+    ranked.into_iter().map(|(_, decode)| decode).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossbeam::channel::bounded;
+
+    #[test]
+    fn beam_succeeds() {
+        let (tx, rx) = bounded::<Option<DecoderResult>>(1);
+        let stopper = Arc::new(AtomicBool::new(false));
+        beam("MTkyLjE2OC4wLjE=".into(), tx, stopper, 3);
+        let result = rx.recv().unwrap();
+        assert_eq!(result.unwrap().text[0], "192.168.0.1");
+    }
+
+    #[test]
+    fn only_the_most_promising_decodes_are_kept() {
+        let decodes = vec![
+            DecoderResult::_new("\u{0}\u{0}\u{0}\u{0}\u{0}"),
+            DecoderResult::_new("hello there"),
+            DecoderResult::_new("ab"),
+        ];
+        let kept = most_promising(decodes, 1);
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].text[0], "hello there");
+    }
+}
@@ -1,99 +1,179 @@
-use crate::cli_pretty_printing::decoded_how_many_times;
 use crate::filtration_system::MyResults;
 use crossbeam::channel::Sender;
 
+use dashmap::DashSet;
 use log::trace;
-use std::collections::HashSet;
-use std::sync::atomic::AtomicBool;
+use rayon::prelude::*;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
+use super::astar::decoded_value;
+use super::{offer_candidate, report_plaintext};
+use crate::checkers::human_checker;
+use crate::config::get_config;
+use crate::events::{self, SearchEvent};
 use crate::DecoderResult;
 
+/// What decoding a text led to
+pub enum Expansion {
+    /// A decoder found the plaintext
+    Plaintext(DecoderResult),
+    /// The decodes worth decoding further, none of them new plaintext
+    Decodes(Vec<DecoderResult>),
+}
+
 /// Breadth first search is our search algorithm
 /// https://en.wikipedia.org/wiki/Breadth-first_search
-#[allow(dead_code)]
-pub fn bfs(input: String, result_sender: Sender<Option<DecoderResult>>, stop: Arc<AtomicBool>) {
-    let initial = DecoderResult {
-        text: vec![input],
-        path: vec![],
-    };
-    let mut seen_strings = HashSet::new();
+pub fn bfs(
+    input: DecoderResult,
+    result_sender: Sender<Option<DecoderResult>>,
+    stop: Arc<AtomicBool>,
+) {
+    search_by_depth(input, result_sender, stop, |decodes| decodes);
+}
+
+/// Searches every decode of one depth before the next, as breadth first and beam search do
+/// The decodes of each depth are decoded in parallel, and `keep` chooses which of the
+/// decodes they give are decoded further.
+pub fn search_by_depth<F>(
+    input: DecoderResult,
+    result_sender: Sender<Option<DecoderResult>>,
+    stop: Arc<AtomicBool>,
+    keep: F,
+) where
+    F: Fn(Vec<DecoderResult>) -> Vec<DecoderResult>,
+{
+    let initial = input;
+    let seen_strings = DashSet::new();
     // all strings to search through
     let mut current_strings = vec![initial];
+    let config = get_config();
+    let stops_at_first = !config.top_results && !config.all_results;
 
     let mut curr_depth: u32 = 1; // as we have input string, so we start from 1
 
     // loop through all of the strings in the vec
-    while !current_strings.is_empty() && !stop.load(std::sync::atomic::Ordering::Relaxed) {
+    while !current_strings.is_empty() && !stop.load(Ordering::Relaxed) {
         trace!("Number of potential decodings: {}", current_strings.len());
         trace!("Current depth is {:?}", curr_depth);
 
-        let mut new_strings: Vec<DecoderResult> = vec![];
-
-        current_strings.into_iter().try_for_each(|current_string| {
-            let res = super::perform_decoding(&current_string);
-
-            match res {
-                // if it's Break variant, we have cracked the text successfully
-                // so just stop processing further.
-                MyResults::Break(res) => {
-                    let mut decoders_used = current_string.path;
-                    let text = res.unencrypted_text.clone().unwrap_or_default();
-                    decoders_used.push(res);
-                    let result_text = DecoderResult {
-                        text,
-                        path: decoders_used,
-                    };
-
-                    decoded_how_many_times(curr_depth);
-                    result_sender
-                        .send(Some(result_text))
-                        .expect("Should succesfully send the result");
-
-                    // stop further iterations
-                    stop.store(true, std::sync::atomic::Ordering::Relaxed);
-                    None // short-circuits the iterator
+        // Once a plaintext is found the rest of the depth isn't worth decoding
+        let found = AtomicBool::new(false);
+        let expansions: Vec<Expansion> = current_strings
+            .par_iter()
+            .map(|current_string| {
+                if stop.load(Ordering::Relaxed) || found.load(Ordering::Relaxed) {
+                    return Expansion::Decodes(Vec::new());
                 }
-                MyResults::Continue(results_vec) => {
-                    new_strings.extend(results_vec.into_iter().flat_map(|mut r| {
-                        let mut decoders_used = current_string.path.clone();
-                        // text is a vector of strings
-                        let mut text = r.unencrypted_text.take().unwrap_or_default();
-
-                        text.retain(|s| {
-                            !check_if_string_cant_be_decoded(s) && seen_strings.insert(s.clone())
-                        });
-
-                        if text.is_empty() {
-                            return None;
-                        }
+                let expansion = expand(current_string, &seen_strings);
+                if stops_at_first && matches!(expansion, Expansion::Plaintext(_)) {
+                    found.store(true, Ordering::Relaxed);
+                }
+                expansion
+            })
+            .collect();
 
-                        decoders_used.push(r);
-                        Some(DecoderResult {
-                            // and this is a vector of strings
-                            // TODO we should probably loop through all `text` and create Text structs for each one
-                            // and append those structs
-                            // I think we should keep text as a single string
-                            // and just create more of them....
-                            text,
-                            path: decoders_used.to_vec(),
-                        })
-                    }));
-                    Some(()) // indicate we want to continue processing
+        let mut new_strings: Vec<DecoderResult> = vec![];
+        for expansion in expansions {
+            match expansion {
+                Expansion::Plaintext(result) => {
+                    if report_plaintext(&result, curr_depth, &result_sender, &stop) {
+                        return;
+                    }
                 }
+                Expansion::Decodes(decodes) => new_strings.extend(decodes),
             }
+        }
+
+        let expanded = current_strings.len();
+        current_strings = keep(new_strings);
+        events::publish(|| SearchEvent::NodesExpanded {
+            depth: curr_depth,
+            nodes: expanded,
+            queued: current_strings.len(),
         });
-
-        current_strings = new_strings;
         curr_depth += 1;
 
         trace!("Refreshed the vector, {:?}", current_strings);
     }
-    result_sender.try_send(None).ok();
+    if !stop.load(Ordering::Relaxed) {
+        result_sender.try_send(None).ok();
+    }
+}
+
+/// Runs every decoder on the text, giving the plaintext if one found it and otherwise the
+/// decodes which haven't been seen before
+/// Each of those decodes is offered as a candidate for when no plaintext is found.
+pub fn expand(current_string: &DecoderResult, seen_strings: &DashSet<String>) -> Expansion {
+    match super::perform_decoding(current_string) {
+        // if it's Break variant, we have cracked the text successfully
+        MyResults::Break(res) => {
+            let mut decoders_used = current_string.path.clone();
+            // A decoder can succeed with bytes which aren't text, such as a ZIP archive
+            let (text, bytes) = decoded_value(&res);
+            decoders_used.push(*res);
+            Expansion::Plaintext(DecoderResult {
+                text,
+                path: decoders_used,
+                bytes,
+            })
+        }
+        MyResults::Continue(results_vec) => {
+            let decodes: Vec<DecoderResult> = results_vec
+                .into_iter()
+                .filter_map(|mut r| {
+                    let mut decoders_used = current_string.path.clone();
+                    // Bytes which aren't text are passed on with a lossy rendering of them
+                    if r.unencrypted_text
+                        .as_ref()
+                        .is_none_or(|text| text.is_empty())
+                    {
+                        let bytes = r.unencrypted_bytes.as_ref()?.first()?.clone();
+                        let lossy = String::from_utf8_lossy(&bytes).to_string();
+                        if !seen_strings.insert(lossy.clone()) {
+                            return None;
+                        }
+                        decoders_used.push(r);
+                        return Some(DecoderResult {
+                            text: vec![lossy],
+                            path: decoders_used,
+                            bytes: Some(bytes),
+                        });
+                    }
+                    // text is a vector of strings
+                    let mut text = r.unencrypted_text.take().unwrap_or_default();
+
+                    text.retain(|s| {
+                        !check_if_string_cant_be_decoded(s) && seen_strings.insert(s.clone())
+                    });
+
+                    if text.is_empty() {
+                        return None;
+                    }
+
+                    decoders_used.push(r);
+                    Some(DecoderResult {
+                        // and this is a vector of strings
+                        // TODO we should probably loop through all `text` and create Text structs for each one
+                        // and append those structs
+                        // I think we should keep text as a single string
+                        // and just create more of them....
+                        text,
+                        path: decoders_used,
+                        bytes: None,
+                    })
+                })
+                .collect();
+            for decode in &decodes {
+                offer_candidate(decode);
+                human_checker::attach_decode(decode);
+            }
+            Expansion::Decodes(decodes)
+        }
+    }
 }
 
 /// If this returns False it will not attempt to decode that string
-#[allow(dead_code)]
 fn check_if_string_cant_be_decoded(text: &str) -> bool {
     text.len() <= 2
 }
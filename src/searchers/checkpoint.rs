@@ -0,0 +1,114 @@
+//! # Search Checkpoints
+//!
+//! When an A* search runs out of time or is cancelled, the most promising decodes it hadn't
+//! tried yet and the decodes it had already seen are saved to the database. Running it again
+//! with `--resume` carries on from there instead of starting over, so a long crack can be
+//! given more time in several goes. The checkpoint is removed once the search finishes.
+
+use serde::{Deserialize, Serialize};
+
+use crate::decoders::crack_results::CrackResult;
+use crate::storage::backend::storage;
+
+/// The most unexplored decodes a checkpoint keeps, the most promising ones
+pub const MAX_CHECKPOINT_NODES: usize = 5_000;
+
+/// The most seen decodes a checkpoint keeps, on top of which ones may be decoded again
+pub const MAX_CHECKPOINT_SEEN: usize = 100_000;
+
+/// A decode the search hadn't tried decoding further yet
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CheckpointNode {
+    /// The decoded text
+    pub text: Vec<String>,
+    /// The decoded bytes, when they aren't text
+    pub bytes: Option<Vec<u8>>,
+    /// The decoders which produced it
+    pub path: Vec<CrackResult>,
+    /// How many decoders it took
+    pub cost: u32,
+    /// How promising the heuristic thought it was
+    pub heuristic: f32,
+    /// The decoder to try first when it is decoded further
+    pub next_decoder_name: Option<String>,
+}
+
+/// Where a search got to
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct SearchCheckpoint {
+    /// The decodes still to be tried, most promising first
+    pub nodes: Vec<CheckpointNode>,
+    /// Hashes of the decodes already seen, so they aren't decoded again
+    pub seen: Vec<String>,
+    /// How deep the search had got
+    pub depth: u32,
+}
+
+/// The checkpoint saved for this input, None if there isn't one or it can't be read
+pub fn load(input: &str) -> Option<SearchCheckpoint> {
+    let row = match storage().read_checkpoint(input) {
+        Ok(row) => row?,
+        Err(e) => {
+            log::debug!("Could not read the search checkpoint: {}", e);
+            return None;
+        }
+    };
+    serde_json::from_str(&row.frontier)
+        .map_err(|e| log::warn!("Ignoring an unreadable search checkpoint: {}", e))
+        .ok()
+}
+
+/// Saves where the search of this input got to, replacing any earlier checkpoint
+pub fn save(input: &str, checkpoint: &SearchCheckpoint) {
+    let saved = serde_json::to_string(checkpoint)
+        .map_err(|e| e.to_string())
+        .and_then(|frontier| {
+            storage()
+                .upsert_checkpoint(input, &frontier)
+                .map_err(|e| e.to_string())
+        });
+    if let Err(e) = saved {
+        log::warn!("Could not save the search checkpoint: {}", e);
+    }
+}
+
+/// Removes the checkpoint of this input, as its search has finished
+pub fn clear(input: &str) {
+    if let Err(e) = storage().delete_checkpoint(input) {
+        log::debug!("Could not remove the search checkpoint: {}", e);
+    }
+}
+
+/// Whether a checkpoint is saved for this input, which `--resume` would carry on from
+pub fn exists(input: &str) -> bool {
+    matches!(storage().read_checkpoint(input), Ok(Some(_)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoders::interface::Decoder;
+
+    #[test]
+    fn checkpoints_survive_being_written_as_json() {
+        let checkpoint = SearchCheckpoint {
+            nodes: vec![CheckpointNode {
+                text: vec!["decoded".to_string()],
+                bytes: None,
+                path: vec![CrackResult::new(&Decoder::default(), "encoded".to_string())],
+                cost: 1,
+                heuristic: 0.5,
+                next_decoder_name: Some("Base64".to_string()),
+            }],
+            seen: vec!["1234".to_string()],
+            depth: 2,
+        };
+        let json = serde_json::to_string(&checkpoint).unwrap();
+        let read: SearchCheckpoint = serde_json::from_str(&json).unwrap();
+        assert_eq!(read.nodes.len(), 1);
+        assert_eq!(read.nodes[0].text, ["decoded"]);
+        assert_eq!(read.nodes[0].path[0].encrypted_text, "encoded");
+        assert_eq!(read.seen, ["1234"]);
+        assert_eq!(read.depth, 2);
+    }
+}
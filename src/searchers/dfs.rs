@@ -0,0 +1,76 @@
+//! # Depth First Search
+//!
+//! Depth first search follows the most recent decode as deep as it goes before backing up
+//! to try the others. It finds plaintexts behind long chains of decoders quickly when the
+//! first decoders tried are the right ones, but can spend a long time down a wrong chain,
+//! so chains are cut off at `max_depth` decoders, or `DEFAULT_MAX_DEPTH` if it isn't set.
+
+use crossbeam::channel::Sender;
+use dashmap::DashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use super::bfs::{expand, Expansion};
+use super::report_plaintext;
+use crate::config::get_config;
+use crate::DecoderResult;
+
+/// How many decoders a chain can have before depth first search stops following it, when
+/// `max_depth` isn't set
+const DEFAULT_MAX_DEPTH: usize = 10;
+
+/// Searches for the plaintext, following each chain of decoders as deep as it goes first
+pub fn dfs(
+    input: DecoderResult,
+    result_sender: Sender<Option<DecoderResult>>,
+    stop: Arc<AtomicBool>,
+) {
+    let max_depth = get_config().max_depth.unwrap_or(DEFAULT_MAX_DEPTH);
+    let seen_strings = DashSet::new();
+    let mut stack = vec![input];
+
+    while let Some(current) = stack.pop() {
+        if stop.load(Ordering::Relaxed) {
+            return;
+        }
+        if current.path.len() >= max_depth {
+            continue;
+        }
+        match expand(&current, &seen_strings) {
+            Expansion::Plaintext(result) => {
+                let depth = u32::try_from(result.path.len()).unwrap_or(u32::MAX);
+                if report_plaintext(&result, depth, &result_sender, &stop) {
+                    return;
+                }
+            }
+            // Pushed in reverse so the first decode is followed first
+            Expansion::Decodes(decodes) => stack.extend(decodes.into_iter().rev()),
+        }
+    }
+    if !stop.load(Ordering::Relaxed) {
+        result_sender.try_send(None).ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossbeam::channel::bounded;
+
+    #[test]
+    fn dfs_succeeds() {
+        let (tx, rx) = bounded::<Option<DecoderResult>>(1);
+        let stopper = Arc::new(AtomicBool::new(false));
+        dfs("MTkyLjE2OC4wLjE=".into(), tx, stopper);
+        let result = rx.recv().unwrap();
+        assert_eq!(result.unwrap().text[0], "192.168.0.1");
+    }
+
+    #[test]
+    fn dfs_gives_up_on_text_it_cant_decode() {
+        let (tx, rx) = bounded::<Option<DecoderResult>>(1);
+        let stopper = Arc::new(AtomicBool::new(false));
+        dfs("".into(), tx, stopper);
+        assert!(rx.recv().unwrap().is_none());
+    }
+}
@@ -4,10 +4,51 @@
 //! for decoding encrypted or encoded text.
 
 use crate::decoders::interface::Crack;
+use crate::storage::backend::storage;
+use crate::storage::database::DecoderStatsRow;
 use crate::CrackResult;
 use once_cell::sync::Lazy;
-use std::collections::HashMap;
-use std::sync::Mutex;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Mutex, PoisonError, RwLock};
+use std::time::Duration;
+
+/// Relative length change at which a decode counts as fully changing the text
+/// Base64 shrinks text by about 25%, so a quarter is plenty.
+const FULL_LENGTH_CHANGE: f32 = 0.25;
+
+/// The most a decode which changed nothing adds to the heuristic
+const NO_OP_PENALTY: f32 = 0.4;
+
+/// The most a decode which looks nothing like plaintext adds to the heuristic
+const UNLIKELY_PLAINTEXT_PENALTY: f32 = 0.5;
+
+/// The most a decoder which has rarely led to a plaintext in earlier searches adds to the
+/// heuristic
+const LEARNED_FAILURE_PENALTY: f32 = 0.5;
+
+/// The most a decoder which has been slow in earlier searches adds to the heuristic
+const SLOW_DECODER_PENALTY: f32 = 0.25;
+
+/// Average runtime in microseconds at which a decoder counts as fully slow
+const SLOW_DECODER_MICROS: f32 = 50_000.0;
+
+/// How many times a decoder has to have been run before its statistics are trusted
+/// Decoders with less history, such as newly added ones, aren't penalised.
+const MIN_LEARNED_ATTEMPTS: i64 = 20;
+
+/// How many times each decoder was run in the current search and how long that took in
+/// microseconds, saved to the database when the search ends
+static RUN_STATS: Lazy<Mutex<HashMap<String, (i64, i64)>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// How many times each decoder was run in the last search and how long that took in
+/// microseconds, kept for the run history once the search's statistics have been saved
+static LAST_RUN_STATS: Lazy<Mutex<HashMap<String, (i64, i64)>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// What the decoder statistics of earlier searches add to the heuristic of each decoder
+static LEARNED_PENALTIES: Lazy<RwLock<HashMap<String, f32>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
 
 /// Track decoder success rates for adaptive learning
 pub static DECODER_SUCCESS_RATES: Lazy<Mutex<HashMap<String, (usize, usize)>>> =
@@ -27,8 +68,114 @@ pub fn update_decoder_stats(decoder: &str, success: bool) {
         *successes += 1;
     }
     *total += 1;
+}
+
+/// Records that a decoder was run in the current search and how long it took
+pub fn record_decoder_run(decoder: &str, runtime: Duration) {
+    let mut stats = RUN_STATS.lock().unwrap_or_else(PoisonError::into_inner);
+    let (attempts, runtime_micros) = stats.entry(decoder.to_string()).or_insert((0, 0));
+    *attempts += 1;
+    *runtime_micros += i64::try_from(runtime.as_micros()).unwrap_or(i64::MAX);
+}
 
-    // TODO: Write this data to a file for persistence
+/// Reads the decoder statistics of earlier searches from the database, for the heuristic
+/// of the search about to start
+/// Without a database, or before any search has been saved, no decoder is penalised.
+pub fn load_learned_stats() {
+    RUN_STATS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clear();
+    let penalties = match storage().read_decoder_stats() {
+        Ok(rows) => learned_penalties(&rows),
+        Err(e) => {
+            log::debug!("Could not read the decoder statistics: {}", e);
+            HashMap::new()
+        }
+    };
+    *LEARNED_PENALTIES
+        .write()
+        .unwrap_or_else(PoisonError::into_inner) = penalties;
+}
+
+/// Adds the decoders run in the current search to their statistics in the database
+/// Every step of the path to each plaintext found counts as a success for its decoder.
+pub fn save_run_stats(plaintext_paths: &[&[CrackResult]]) {
+    let run_stats = std::mem::take(&mut *RUN_STATS.lock().unwrap_or_else(PoisonError::into_inner));
+    LAST_RUN_STATS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone_from(&run_stats);
+    let mut successes: HashMap<&str, i64> = HashMap::new();
+    for step in plaintext_paths.iter().flat_map(|path| path.iter()) {
+        *successes.entry(step.decoder).or_insert(0) += 1;
+    }
+    for (decoder, (attempts, runtime_micros)) in &run_stats {
+        let successes = successes.get(decoder.as_str()).copied().unwrap_or(0);
+        // A plaintext found in the cache or another way can't count as more than the decodes run
+        let successes = successes.min(*attempts);
+        if let Err(e) = storage().add_decoder_stats(decoder, *attempts, successes, *runtime_micros)
+        {
+            log::debug!("Could not save the statistics of {}: {}", decoder, e);
+            return;
+        }
+    }
+}
+
+/// How many times each decoder was run in the last search and how long that took in
+/// microseconds, forgetting them so the next run without a search has none
+pub fn take_last_run_stats() -> HashMap<String, (i64, i64)> {
+    std::mem::take(
+        &mut *LAST_RUN_STATS
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner),
+    )
+}
+
+/// What the statistics of each decoder add to the heuristic of decodes it makes
+///
+/// A decoder's success rate is compared to the best success rate of any decoder, so the
+/// decoder most often on the way to plaintext adds nothing and one which never has adds
+/// the full `LEARNED_FAILURE_PENALTY`. Slow decoders add up to `SLOW_DECODER_PENALTY` more.
+/// Decoders run fewer than `MIN_LEARNED_ATTEMPTS` times are left out.
+pub fn learned_penalties(rows: &[DecoderStatsRow]) -> HashMap<String, f32> {
+    let trusted: Vec<&DecoderStatsRow> = rows
+        .iter()
+        .filter(|row| row.attempts >= MIN_LEARNED_ATTEMPTS)
+        .collect();
+    let success_rate = |row: &DecoderStatsRow| row.successes as f32 / row.attempts as f32;
+    let best_rate = trusted
+        .iter()
+        .map(|row| success_rate(row))
+        .fold(0.0, f32::max);
+
+    trusted
+        .iter()
+        .map(|row| {
+            let relative_rate = if best_rate > 0.0 {
+                success_rate(row) / best_rate
+            } else {
+                // Nothing has succeeded yet, so no decoder is worse than another
+                1.0
+            };
+            let average_micros = row.runtime_micros as f32 / row.attempts as f32;
+            let slowness = (average_micros / SLOW_DECODER_MICROS).min(1.0);
+            let penalty =
+                (1.0 - relative_rate) * LEARNED_FAILURE_PENALTY + slowness * SLOW_DECODER_PENALTY;
+            (row.decoder.clone(), penalty)
+        })
+        .collect()
+}
+
+/// What the decoder statistics of earlier searches add to the heuristic of this decoder's
+/// decodes
+fn learned_penalty(decoder: &str) -> f32 {
+    LEARNED_PENALTIES
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(decoder)
+        .copied()
+        .unwrap_or(0.0)
 }
 
 /// Get the success rate of a decoder
@@ -40,6 +187,7 @@ pub fn update_decoder_stats(decoder: &str, success: bool) {
 /// # Returns
 ///
 /// * The success rate as a float between 0.0 and 1.0
+#[allow(dead_code)]
 pub fn get_decoder_success_rate(decoder: &str) -> f32 {
     let stats = DECODER_SUCCESS_RATES.lock().unwrap();
     if let Some((successes, total)) = stats.get(decoder) {
@@ -153,6 +301,11 @@ pub fn calculate_non_printable_ratio(text: &str) -> f32 {
 /// 2. Adaptive depth penalty (higher heuristic for deeper paths, with increasing penalty as depth grows)
 /// 3. String quality component (higher heuristic for lower quality strings)
 /// 4. Uncommon sequence penalty (higher heuristic for uncommon decoder sequences)
+/// 5. No-op penalty (higher heuristic when the last decode barely changed the length or charset)
+/// 6. Plaintext likelihood (higher heuristic when the Entropy Checker scored the decode as
+///    unlike plaintext)
+/// 7. Learned decoder statistics (higher heuristic when the last decoder has rarely led to
+///    plaintext or has been slow in earlier searches)
 ///
 /// # Parameters
 ///
@@ -200,9 +353,57 @@ pub fn generate_heuristic(
         }
     }
 
+    // 5. Penalty for decodes which look like they did nothing
+    // Real decodes usually change the length (base64 shrinks by ~25%) or the characters used
+    if let Some(change) = path.last().and_then(calculate_transform_change) {
+        base_score += (1.0 - change) * NO_OP_PENALTY;
+    }
+
+    // 6. Penalty for decodes which don't look like plaintext
+    // Decodes no checker identified carry the Entropy Checker's scores to rank them by
+    if let Some(scores) = path.last().and_then(|step| step.scores) {
+        base_score += (1.0 - scores.plaintext_likelihood()) * UNLIKELY_PLAINTEXT_PENALTY;
+    }
+
+    // 7. Penalty for decoders which have rarely helped, or been slow, in earlier searches
+    if let Some(step) = path.last() {
+        base_score += learned_penalty(step.decoder);
+    }
+
     base_score
 }
 
+/// How much a decode changed the text, from its input to its first output
+///
+/// Takes the larger of the relative length change, scaled so a 25% change counts fully,
+/// and the Jaccard distance between the sets of characters used.
+///
+/// # Returns
+///
+/// * A value between 0.0 (unchanged) and 1.0 (completely changed),
+///   or None if the decode produced no text
+pub fn calculate_transform_change(step: &CrackResult) -> Option<f32> {
+    let after = step.unencrypted_text.as_ref()?.first()?;
+    let before = &step.encrypted_text;
+    if before.is_empty() {
+        return Some(if after.is_empty() { 0.0 } else { 1.0 });
+    }
+
+    let length_delta = (after.len() as f32 - before.len() as f32).abs() / before.len() as f32;
+    let length_change = (length_delta / FULL_LENGTH_CHANGE).min(1.0);
+
+    let before_chars: HashSet<char> = before.chars().collect();
+    let after_chars: HashSet<char> = after.chars().collect();
+    let union = before_chars.union(&after_chars).count();
+    let charset_change = if union == 0 {
+        0.0
+    } else {
+        1.0 - before_chars.intersection(&after_chars).count() as f32 / union as f32
+    };
+
+    Some(length_change.max(charset_change))
+}
+
 /// Determines if a string is too short to be meaningfully decoded
 /// or is of too low quality to be worth decoding
 ///
@@ -223,6 +424,7 @@ pub fn generate_heuristic(
 ///
 /// Filtering out these strings early saves computational resources and
 /// prevents the search from exploring unproductive paths.
+#[allow(dead_code)]
 pub fn check_if_string_cant_be_decoded(text: &str) -> bool {
     // Check for strings that are too short
     if text.len() <= 2 {
@@ -247,6 +449,7 @@ pub fn check_if_string_cant_be_decoded(text: &str) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::checkers::entropy::TextScores;
     use crate::Decoder;
 
     #[test]
@@ -270,6 +473,57 @@ mod tests {
         assert!(depth_0 >= 0.0);
     }
 
+    #[test]
+    fn test_no_op_decodes_are_penalised() {
+        let mut base64 = CrackResult::new(&Decoder::default(), "aGVsbG8gd29ybGQ=".to_string());
+        base64.unencrypted_text = Some(vec!["hello world".to_string()]);
+        let mut no_op = CrackResult::new(&Decoder::default(), "hello world".to_string());
+        no_op.unencrypted_text = Some(vec!["hello world".to_string()]);
+
+        assert_eq!(calculate_transform_change(&no_op), Some(0.0));
+        assert_eq!(calculate_transform_change(&base64), Some(1.0));
+        assert!(
+            generate_heuristic("hello world", &[base64], &None)
+                < generate_heuristic("hello world", &[no_op], &None)
+        );
+    }
+
+    #[test]
+    fn test_unlikely_plaintext_is_penalised() {
+        let mut english = CrackResult::new(&Decoder::default(), "x".to_string());
+        english.scores = Some(TextScores::of(b"the cat sat on the mat by the door"));
+        let mut gibberish = CrackResult::new(&Decoder::default(), "x".to_string());
+        gibberish.scores = Some(TextScores::of(b"Zm9vYmFyYmF6cXV4cXV1eHF1dXV6"));
+
+        assert!(
+            generate_heuristic("x", &[english], &None)
+                < generate_heuristic("x", &[gibberish], &None)
+        );
+    }
+
+    #[test]
+    fn test_learned_penalties_favour_helpful_fast_decoders() {
+        let row =
+            |decoder: &str, attempts: i64, successes: i64, runtime_micros: i64| DecoderStatsRow {
+                decoder: decoder.to_string(),
+                attempts,
+                successes,
+                runtime_micros,
+                timestamp: String::new(),
+            };
+        let penalties = learned_penalties(&[
+            row("Helpful", 100, 20, 1_000),
+            row("Unhelpful", 100, 0, 1_000),
+            row("Slow", 100, 20, 100 * 100_000),
+            row("New", 5, 0, 0),
+        ]);
+
+        assert!(penalties["Helpful"] < 0.01);
+        assert!((penalties["Unhelpful"] - LEARNED_FAILURE_PENALTY).abs() < 0.01);
+        assert!((penalties["Slow"] - SLOW_DECODER_PENALTY).abs() < 0.01);
+        assert!(!penalties.contains_key("New"));
+    }
+
     #[test]
     fn test_calculate_non_printable_ratio() {
         // Test normal text
@@ -3,27 +3,140 @@
 //! Click here to find out more:
 //! https://broadleaf-angora-7db.notion.site/Search-Nodes-Edges-What-should-they-look-like-b74c43ca7ac341a1a5cfdbeb84a7eef0
 
+use std::collections::HashMap;
 use std::sync::atomic::AtomicBool;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::thread;
 
-use crossbeam::channel::bounded;
+use crossbeam::channel::{unbounded, Receiver, Sender};
+use log::debug;
+use once_cell::sync::Lazy;
 
 use crate::checkers::athena::Athena;
 use crate::checkers::checker_type::{Check, Checker};
+use crate::checkers::human_checker;
 use crate::checkers::CheckerTypes;
-use crate::config::get_config;
+use crate::cli_pretty_printing::{self, decoded_how_many_times};
+use crate::config::{get_config, SearchStrategy};
+use crate::decoders::crack_results::CrackResult;
+use crate::events::{self, SearchEvent};
 use crate::filtration_system::{filter_and_get_decoders, MyResults};
+use crate::storage::{self, wait_athena_storage};
+use crate::CancellationToken;
 use crate::{timer, DecoderResult};
 /// This module provides access to the A* search algorithm
 /// which uses a heuristic to prioritize decoders.
 mod astar;
+/// This module provides access to the beam search
+/// which only keeps the most promising decodes of each depth.
+mod beam;
 /// This module provides access to the breadth first search
 /// which searches for the plaintext.
 mod bfs;
+/// This module saves where a search which ran out of time got to, so it can be resumed.
+pub mod checkpoint;
+/// This module provides access to the depth first search
+/// which follows each chain of decoders as deep as it goes before trying the next.
+mod dfs;
 /// This module contains helper functions used by the A* search algorithm.
 mod helper_functions;
 
+pub use helper_functions::record_decoder_run;
+
+/// Decodes less likely than this to be plaintext aren't worth showing when the search fails
+const MIN_CANDIDATE_LIKELIHOOD: f32 = 0.4;
+
+/// The decodes of the current search which looked most like plaintext without any checker
+/// identifying them, and how likely each is to be plaintext, most likely first
+static BEST_CANDIDATES: Lazy<Mutex<Vec<(DecoderResult, f32)>>> =
+    Lazy::new(|| Mutex::new(Vec::new()));
+
+/// A way of searching the decodes of the input for the plaintext
+///
+/// A searcher sends every plaintext it finds to `result_sender`, and None if it runs out of
+/// decodes to try. It stops at the first plaintext unless `top_results` or `all_results` is
+/// set, and as soon as `stop` is set.
+pub trait Searcher: Send + Sync {
+    /// Searches the decodes of the input until the plaintext is found or it is stopped
+    /// The input is the text or bytes to decode with no decoders in its path yet.
+    fn search(
+        &self,
+        input: DecoderResult,
+        result_sender: Sender<Option<DecoderResult>>,
+        stop: Arc<AtomicBool>,
+    );
+}
+
+/// Searches with the A* search, ranking decodes by how promising the heuristic thinks they are
+pub struct AStarSearcher;
+
+impl Searcher for AStarSearcher {
+    fn search(
+        &self,
+        input: DecoderResult,
+        result_sender: Sender<Option<DecoderResult>>,
+        stop: Arc<AtomicBool>,
+    ) {
+        astar::astar(input, result_sender, stop);
+    }
+}
+
+/// Searches every decode of one depth before any of the next
+pub struct BreadthFirstSearcher;
+
+impl Searcher for BreadthFirstSearcher {
+    fn search(
+        &self,
+        input: DecoderResult,
+        result_sender: Sender<Option<DecoderResult>>,
+        stop: Arc<AtomicBool>,
+    ) {
+        bfs::bfs(input, result_sender, stop);
+    }
+}
+
+/// Follows each chain of decoders as deep as it goes before trying the next
+pub struct DepthFirstSearcher;
+
+impl Searcher for DepthFirstSearcher {
+    fn search(
+        &self,
+        input: DecoderResult,
+        result_sender: Sender<Option<DecoderResult>>,
+        stop: Arc<AtomicBool>,
+    ) {
+        dfs::dfs(input, result_sender, stop);
+    }
+}
+
+/// Searches one depth at a time, only carrying the `width` most promising decodes of each
+/// depth on to the next
+pub struct BeamSearcher {
+    /// How many decodes of each depth are decoded further
+    pub width: usize,
+}
+
+impl Searcher for BeamSearcher {
+    fn search(
+        &self,
+        input: DecoderResult,
+        result_sender: Sender<Option<DecoderResult>>,
+        stop: Arc<AtomicBool>,
+    ) {
+        beam::beam(input, result_sender, stop, self.width);
+    }
+}
+
+/// The searcher for a search strategy, beams being as wide as `beam_width`
+pub fn searcher_for(strategy: SearchStrategy, beam_width: usize) -> Box<dyn Searcher> {
+    match strategy {
+        SearchStrategy::AStar => Box::new(AStarSearcher),
+        SearchStrategy::BreadthFirst => Box::new(BreadthFirstSearcher),
+        SearchStrategy::DepthFirst => Box::new(DepthFirstSearcher),
+        SearchStrategy::Beam => Box::new(BeamSearcher { width: beam_width }),
+    }
+}
+
 /*pub struct Tree <'a> {
     // Wrap in a box because
     // https://doc.rust-lang.org/error-index.html#E0072
@@ -32,6 +145,7 @@ mod helper_functions;
 }*/
 
 /// Performs the search algorithm.
+/// The search stops early, with whatever it had found, once `cancel` is cancelled.
 ///
 /// When we perform the decryptions, we will get a vector of Some<String>
 /// We need to loop through these and determine:
@@ -41,58 +155,151 @@ mod helper_functions;
 ///    We can return an Option? An Enum? And then match on that
 ///    So if we return CrackSuccess we return
 ///    Else if we return an array, we add it to the children and go again.
-pub fn search_for_plaintext(input: String) -> Option<DecoderResult> {
+pub fn search_for_plaintext(
+    input: DecoderResult,
+    cancel: &CancellationToken,
+) -> Option<DecoderResult> {
+    // In top_results mode, we don't need to return a result immediately
+    // as the timer will display all results when it expires
+    // The search carries on, but only the first result found is returned.
+    let top_results_mode = get_config().top_results;
+    search(input, top_results_mode, cancel).into_iter().next()
+}
+
+/// Searches until the timeout for every distinct plaintext the input decodes to, rather than
+/// stopping at the first
+/// The plaintexts are in the order they were found, see `rank_results` to order them.
+pub fn search_for_all_plaintexts(
+    input: DecoderResult,
+    cancel: &CancellationToken,
+) -> Vec<DecoderResult> {
+    search(input, true, cancel)
+}
+
+/// How many times each decoder was run in the last search and how long that took in
+/// microseconds, empty once they have been taken
+pub fn take_last_run_stats() -> std::collections::HashMap<String, (i64, i64)> {
+    helper_functions::take_last_run_stats()
+}
+
+/// Orders plaintexts best first, by the confidence of the checker which accepted them and
+/// then by the number of decoders it took to reach them
+pub fn rank_results(results: &mut [DecoderResult]) {
+    let confidence =
+        |result: &DecoderResult| result.path.last().map_or(0.0, |step| step.confidence);
+    results.sort_by(|a, b| {
+        confidence(b)
+            .total_cmp(&confidence(a))
+            .then(a.path.len().cmp(&b.path.len()))
+    });
+}
+
+/// Runs the search, giving the distinct plaintexts found in the order they were found
+/// Unless `keep_searching` is set, the search stops at the first plaintext.
+/// The search is guided by how each decoder fared in earlier searches, and how they fared
+/// in this one is saved for the next along with what each decoder made of each text.
+fn search(
+    input: DecoderResult,
+    keep_searching: bool,
+    cancel: &CancellationToken,
+) -> Vec<DecoderResult> {
+    helper_functions::load_learned_stats();
+    let found = run_search(input, keep_searching, cancel);
+    let paths: Vec<&[CrackResult]> = found.iter().map(|result| result.path.as_slice()).collect();
+    helper_functions::save_run_stats(&paths);
+    storage::edge_cache::flush();
+    found
+}
+
+/// Runs the A* search on another thread until it finds a plaintext, or every plaintext
+/// before the timeout if `keep_searching` is set
+/// Cancelling `cancel` ends the search like the timeout does.
+fn run_search(
+    input: DecoderResult,
+    keep_searching: bool,
+    cancel: &CancellationToken,
+) -> Vec<DecoderResult> {
     let config = get_config();
+    lock_candidates().clear();
+    human_checker::forget_questions();
     let timeout = config.timeout;
+    events::publish(|| SearchEvent::Started {
+        input: events::shorten(input.text.first().map_or("", String::as_str)),
+        timeout,
+    });
     let timer = timer::start(timeout);
 
-    let (result_sender, result_recv) = bounded::<Option<DecoderResult>>(1);
+    // Unbounded so a searcher reporting several plaintexts at once never blocks, which
+    // would stop it noticing it was told to stop
+    let (result_sender, result_recv) = unbounded::<Option<DecoderResult>>();
     // For stopping the thread
     let stop = Arc::new(AtomicBool::new(false));
     let s = stop.clone();
-    // Use A* search algorithm instead of BFS
-    let handle = thread::spawn(move || astar::astar(input, result_sender, s));
-
-    // In top_results mode, we don't need to return a result immediately
-    // as the timer will display all results when it expires
-    let top_results_mode = config.top_results;
+    let searcher = searcher_for(config.search_strategy, config.beam_width);
+    let pool = search_pool(config.threads);
+    let handle = thread::spawn(move || match pool {
+        Some(pool) => pool.install(|| searcher.search(input, result_sender, s)),
+        None => searcher.search(input, result_sender, s),
+    });
 
-    // If we're in top_results mode, we'll store the first result to return
-    // at the end of the timer
-    let mut first_result = None;
+    let mut found: Vec<DecoderResult> = Vec::new();
 
     loop {
         if let Ok(res) = result_recv.try_recv() {
             log::info!("Found potential plaintext result");
             log::trace!("Result details: {:?}", res);
 
-            // In top_results mode, we store the first result but don't stop the search
-            if top_results_mode {
-                if first_result.is_none() {
-                    first_result = res;
-                }
-                // Continue searching for more results
-            } else {
+            keep_new_result(&mut found, res);
+            if !keep_searching {
                 // In normal mode, we stop the search and return the result
                 stop.store(true, std::sync::atomic::Ordering::Relaxed);
                 // Wait for the thread to finish
                 handle.join().unwrap();
-                return res;
+                return found;
             }
+            // Otherwise continue searching for more results
+        }
+
+        // The user said yes to a question the search carried on past
+        if let Some(accepted) = human_checker::take_accepted() {
+            log::info!("The human checker accepted a plaintext");
+            events::publish(|| SearchEvent::plaintext(&accepted));
+            found.push(accepted);
+            stop.store(true, std::sync::atomic::Ordering::Relaxed);
+            handle.join().unwrap();
+            return found;
+        }
+
+        if cancel.is_cancelled() {
+            stop.store(true, std::sync::atomic::Ordering::Relaxed);
+            log::info!("Search cancelled");
+            events::publish(|| SearchEvent::Cancelled);
+            handle.join().unwrap();
+            drain_results(&mut found, &result_recv);
+            human_checker::forget_questions();
+            return found;
         }
 
         if timer.try_recv().is_ok() {
             stop.store(true, std::sync::atomic::Ordering::Relaxed);
             log::info!("Search timer expired");
-            // Wait for the thread to finish to ensure any ongoing human checker interaction completes
+            events::publish(|| SearchEvent::TimedOut);
             handle.join().unwrap();
-
-            // In top_results mode, return the first result we found (if any)
-            if top_results_mode {
-                return first_result;
+            // A search stopping at the first plaintext only counts those found in time
+            if keep_searching {
+                drain_results(&mut found, &result_recv);
+            }
+            // Questions nobody has answered yet may still be the plaintext
+            if found.is_empty() {
+                if let Some(accepted) = human_checker::wait_for_answers(cancel) {
+                    events::publish(|| SearchEvent::plaintext(&accepted));
+                    found.push(accepted);
+                }
             }
+            human_checker::forget_questions();
 
-            return None;
+            // Only searches which keep going after a result have found any by now
+            return found;
         }
 
         // Small sleep to prevent CPU spinning
@@ -100,15 +307,217 @@ pub fn search_for_plaintext(input: String) -> Option<DecoderResult> {
     }
 }
 
+/// Keeps a plaintext the searcher reported unless the same one was already found
+fn keep_new_result(found: &mut Vec<DecoderResult>, result: Option<DecoderResult>) {
+    if let Some(result) = result {
+        if !found.iter().any(|seen| seen.text == result.text) {
+            found.push(result);
+        }
+    }
+}
+
+/// Keeps the plaintexts the searcher reported after the search last looked, once it has stopped
+fn drain_results(found: &mut Vec<DecoderResult>, results: &Receiver<Option<DecoderResult>>) {
+    for result in results.try_iter() {
+        keep_new_result(found, result);
+    }
+}
+
+/// The thread pools searches decode on, keyed by how many threads they have
+/// Each is started by the first search asking for that many threads, so a batch of inputs
+/// shares one pool rather than starting new threads for each, and a caller asking for a
+/// different number of threads gets a pool of its own.
+static SEARCH_POOLS: Lazy<Mutex<HashMap<usize, Arc<rayon::ThreadPool>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// The thread pool the search decodes on when `threads` is set, None to decode on rayon's
+/// global pool with a thread on every core
+fn search_pool(threads: usize) -> Option<Arc<rayon::ThreadPool>> {
+    if threads == 0 {
+        return None;
+    }
+    let mut pools = SEARCH_POOLS
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    if let Some(pool) = pools.get(&threads) {
+        return Some(Arc::clone(pool));
+    }
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .thread_name(|index| format!("ciphey-search-{}", index))
+        .build()
+        .map_err(|e| log::warn!("Could not start {} search threads: {}", threads, e))
+        .ok()?;
+    let pool = Arc::new(pool);
+    pools.insert(threads, Arc::clone(&pool));
+    Some(pool)
+}
+
+/// Hands a plaintext a searcher found to the search
+/// In `top_results` mode it is also stored for the list shown when the timer expires.
+///
+/// # Returns
+///
+/// * `true` if the searcher should stop, as only the first plaintext is wanted
+pub fn report_plaintext(
+    result: &DecoderResult,
+    depth: u32,
+    result_sender: &Sender<Option<DecoderResult>>,
+    stop: &AtomicBool,
+) -> bool {
+    let config = get_config();
+    // In all_results mode the results are listed together once the search ends
+    if !config.all_results {
+        decoded_how_many_times(depth);
+    }
+
+    cli_pretty_printing::success(&format!(
+        "DEBUG: searchers - Sending successful result with {} decoders",
+        result.path.len()
+    ));
+
+    // If in top_results mode, store the result in the WaitAthena storage
+    if config.top_results {
+        // Store the first text in the vector (there should only be one)
+        if let Some(plaintext) = result.text.first() {
+            debug!(
+                "DEBUG: Processing result in top_results mode with plaintext: {}",
+                plaintext
+            );
+            // Get the last decoder used
+            let decoder_name = if let Some(last_decoder) = result.path.last() {
+                last_decoder.decoder.to_string()
+            } else {
+                "Unknown".to_string()
+            };
+
+            // Get the checker name from the last decoder
+            let checker_name = if let Some(last_decoder) = result.path.last() {
+                last_decoder.checker_name.to_string()
+            } else {
+                "Unknown".to_string()
+            };
+
+            // Only store results that have a valid checker name
+            if !checker_name.is_empty() && checker_name != "Unknown" {
+                log::trace!(
+                    "Storing plaintext in WaitAthena storage: {} (decoder: {}, checker: {})",
+                    plaintext,
+                    decoder_name,
+                    checker_name
+                );
+                wait_athena_storage::add_plaintext_result(
+                    plaintext.clone(),
+                    format!("Decoded successfully at depth {}", depth),
+                    checker_name,
+                    decoder_name,
+                );
+            }
+        }
+    }
+
+    events::publish(|| SearchEvent::plaintext(result));
+
+    // Send the result
+    result_sender
+        .send(Some(result.clone()))
+        .expect("Should successfully send the result");
+
+    // Only stop if not in top_results or all_results mode
+    if !config.top_results && !config.all_results {
+        stop.store(true, std::sync::atomic::Ordering::Relaxed);
+        return true;
+    }
+    // In top_results mode, continue searching
+    false
+}
+
+/// The decode of the last search which looked most like plaintext, and how likely it is to
+/// be plaintext from 0 to 1, if any looked likely enough to be worth showing
+pub fn most_likely_candidate() -> Option<(DecoderResult, f32)> {
+    lock_candidates().first().cloned()
+}
+
+/// The decodes of the last search which looked most like plaintext, most likely first, and
+/// how likely each is to be plaintext from 0 to 1
+/// As many are kept as `show_candidates` asks for, and only ones likely enough to be worth
+/// showing.
+pub fn best_candidates() -> Vec<(DecoderResult, f32)> {
+    lock_candidates().clone()
+}
+
+/// Remembers the decode if it looks more like plaintext than the candidates so far
+/// Only decodes the Entropy Checker scored are considered.
+fn offer_candidate(candidate: &DecoderResult) {
+    let Some(likelihood) = candidate
+        .path
+        .last()
+        .and_then(|step| step.scores)
+        .map(|scores| scores.plaintext_likelihood())
+    else {
+        return;
+    };
+    if likelihood < MIN_CANDIDATE_LIKELIHOOD {
+        return;
+    }
+    // The most likely candidate is kept even when none are shown, for most_likely_candidate
+    let limit = get_config().show_candidates.max(1);
+    if keep_best_candidates(&mut lock_candidates(), candidate, likelihood, limit) {
+        events::publish(|| SearchEvent::candidate(candidate, likelihood));
+    }
+}
+
+/// Adds the candidate to the list if it is one of the `limit` most likely, keeping the list
+/// most likely first with each plaintext in it once
+/// Returns whether it was added.
+fn keep_best_candidates(
+    candidates: &mut Vec<(DecoderResult, f32)>,
+    candidate: &DecoderResult,
+    likelihood: f32,
+    limit: usize,
+) -> bool {
+    if let Some(seen) = candidates
+        .iter()
+        .position(|(seen, _)| seen.text == candidate.text)
+    {
+        // The same text reached another way is only worth keeping if it scores better
+        if candidates[seen].1 >= likelihood {
+            return false;
+        }
+        candidates.remove(seen);
+    }
+    if candidates.len() >= limit
+        && candidates
+            .last()
+            .is_some_and(|(_, least)| *least >= likelihood)
+    {
+        return false;
+    }
+    let position = candidates.partition_point(|(_, better)| *better >= likelihood);
+    candidates.insert(position, (candidate.clone(), likelihood));
+    candidates.truncate(limit);
+    true
+}
+
+/// Locks the best candidates, recovering them if a search thread panicked
+fn lock_candidates() -> std::sync::MutexGuard<'static, Vec<(DecoderResult, f32)>> {
+    BEST_CANDIDATES
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
 /// Performs the decodings by getting all of the decoders
 /// and calling `.run` which in turn loops through them and calls
 /// `.crack()`.
-#[allow(dead_code)]
 fn perform_decoding(text: &DecoderResult) -> MyResults {
-    let decoders = filter_and_get_decoders(text);
+    let mut decoders = filter_and_get_decoders(text);
+    decoders.apply_alphabet_rules(&text.text[0]);
     let athena_checker = Checker::<Athena>::new();
     let checker = CheckerTypes::CheckAthena(athena_checker);
-    decoders.run(&text.text[0], checker)
+    match &text.bytes {
+        Some(bytes) => decoders.run_bytes(bytes, checker),
+        None => decoders.run(&text.text[0], checker),
+    }
 }
 
 #[cfg(test)]
@@ -125,6 +534,31 @@ mod tests {
         checker.check(input).is_identified
     }
 
+    #[test]
+    fn plaintexts_reported_before_the_search_stopped_are_kept() {
+        let (sender, receiver) = unbounded();
+        // A searcher can report several at once without waiting for them to be read
+        for text in ["hello world", "goodbye world", "hello world"] {
+            sender.send(Some(DecoderResult::_new(text))).unwrap();
+        }
+        sender.send(None).unwrap();
+
+        let mut found = vec![DecoderResult::_new("goodbye world")];
+        drain_results(&mut found, &receiver);
+        let texts: Vec<&str> = found.iter().map(|result| result.text[0].as_str()).collect();
+        assert_eq!(texts, ["goodbye world", "hello world"]);
+    }
+
+    #[test]
+    fn each_thread_count_gets_its_own_pool() {
+        assert!(search_pool(0).is_none());
+        let two = search_pool(2).unwrap();
+        let three = search_pool(3).unwrap();
+        assert_eq!(two.current_num_threads(), 2);
+        assert_eq!(three.current_num_threads(), 3);
+        assert!(Arc::ptr_eq(&two, &search_pool(2).unwrap()));
+    }
+
     #[test]
     fn exit_condition_succeeds() {
         let result = exit_condition("https://www.google.com");
@@ -155,4 +589,59 @@ mod tests {
         let result = perform_decoding(&dc);
         assert!(result._break_value().is_none());
     }
+
+    #[test]
+    fn only_the_most_likely_distinct_candidates_are_kept() {
+        let candidate = |text: &str| DecoderResult::_new(text);
+        let mut candidates = Vec::new();
+        keep_best_candidates(&mut candidates, &candidate("a"), 0.5, 2);
+        keep_best_candidates(&mut candidates, &candidate("b"), 0.7, 2);
+        keep_best_candidates(&mut candidates, &candidate("c"), 0.4, 2);
+        keep_best_candidates(&mut candidates, &candidate("a"), 0.6, 2);
+        keep_best_candidates(&mut candidates, &candidate("b"), 0.45, 2);
+        let kept: Vec<(&str, f32)> = candidates
+            .iter()
+            .map(|(candidate, likelihood)| (candidate.text[0].as_str(), *likelihood))
+            .collect();
+        assert_eq!(kept, [("b", 0.7), ("a", 0.6)]);
+    }
+
+    #[test]
+    fn decoders_and_checkers_can_be_shared_between_threads() {
+        fn assert_send_sync<T: Send + Sync + ?Sized>() {}
+        assert_send_sync::<crate::decoders::custom::SharedDecoder>();
+        assert_send_sync::<crate::filtration_system::Decoders>();
+        assert_send_sync::<CheckerTypes>();
+        assert_send_sync::<dyn Searcher>();
+    }
+
+    #[test]
+    fn search_pool_has_the_threads_asked_for() {
+        assert!(search_pool(0).is_none());
+        assert_eq!(search_pool(3).unwrap().current_num_threads(), 3);
+    }
+
+    #[test]
+    fn results_are_ranked_by_confidence_then_path_length() {
+        use crate::decoders::crack_results::CrackResult;
+        use crate::decoders::interface::Decoder;
+
+        let result = |text: &str, decoders: usize, confidence: f32| {
+            let mut step = CrackResult::new(&Decoder::default(), String::new());
+            step.confidence = confidence;
+            DecoderResult {
+                text: vec![text.to_string()],
+                path: vec![step; decoders],
+                bytes: None,
+            }
+        };
+        let mut results = vec![
+            result("long", 3, 0.9),
+            result("unsure", 1, 0.5),
+            result("short", 1, 0.9),
+        ];
+        rank_results(&mut results);
+        let texts: Vec<&str> = results.iter().map(|r| r.text[0].as_str()).collect();
+        assert_eq!(texts, ["short", "long", "unsure"]);
+    }
 }
@@ -0,0 +1,636 @@
+//! A small HTTP server so ciphey can sit behind a web UI, for `ciphey serve`
+//! It speaks just enough HTTP/1.1 for JSON requests and answers: every request is read
+//! whole, answered and the connection closed. Each connection gets its own thread, but
+//! the search and its results are global, so texts are cracked one at a time and
+//! requests to crack wait their turn.
+//!
+//! | Endpoint         | What it does                                                     |
+//! |------------------|------------------------------------------------------------------|
+//! | `POST /crack`    | Cracks `{"text": "...", "timeout": 5}`, answering like `--output-format json` |
+//! | `POST /crack/batch` | Cracks `{"inputs": ["...", "..."]}` like `ciphey batch`, answering with each result and how long it took |
+//! | `GET /decoders`  | Lists every decoder, as `ciphey docs decoders --format json`      |
+//! | `GET /stats`     | What the database recorded about earlier searches, as `ciphey stats` |
+//! | `GET /health`    | `{"status": "ok"}` once the server is listening                  |
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::channel;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+
+use serde::{Deserialize, Serialize};
+
+use crate::batch::crack_prepared_batch;
+use crate::cli::docs::get_decoder_docs;
+use crate::cli::json_output::JsonResult;
+use crate::config::Config;
+use crate::events::{self, SearchEvent};
+use crate::{crack_prepared_text, prepare_cracking, CancellationToken};
+
+/// The port `ciphey serve` listens on when not given one
+pub const DEFAULT_PORT: u16 = 8080;
+
+/// The longest request body accepted, 1 MiB
+pub const MAX_BODY_BYTES: usize = 1024 * 1024;
+
+/// The longest a request line or header may be
+const MAX_LINE_BYTES: usize = 8 * 1024;
+
+/// The most headers a request may have
+const MAX_HEADERS: usize = 100;
+
+/// How long a connection may take to send its request
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Held while a text is cracked, as the search and its results are global
+static CRACKING: Mutex<()> = Mutex::new(());
+
+/// A request read from a connection
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    /// The method, such as `GET`
+    pub method: String,
+    /// The path without its query string, such as `/crack`
+    pub path: String,
+    /// The headers, with their names in lower case
+    pub headers: Vec<(String, String)>,
+    /// The body, empty if there isn't one
+    pub body: Vec<u8>,
+}
+
+/// A response to write to a connection
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    /// The status code, such as 200
+    pub status: u16,
+    /// Headers to send as well as the content type and length
+    pub headers: Vec<(&'static str, String)>,
+    /// The JSON body
+    pub body: String,
+}
+
+impl Response {
+    /// A response with `value` as its JSON body
+    pub fn json(status: u16, value: &impl Serialize) -> Self {
+        Response {
+            status,
+            headers: Vec::new(),
+            body: serde_json::to_string(value)
+                .unwrap_or_else(|e| format!("{{\"error\":\"{}\"}}", e)),
+        }
+    }
+
+    /// A response with `{"error": message}` as its body
+    pub fn error(status: u16, message: &str) -> Self {
+        Response::json(status, &serde_json::json!({ "error": message }))
+    }
+}
+
+/// Why a request couldn't be read
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HttpError {
+    /// The connection closed or failed before a whole request was read
+    Io(String),
+    /// The request isn't HTTP this server understands, answered with this status
+    Bad {
+        /// The status to answer with
+        status: u16,
+        /// What was wrong with the request
+        reason: String,
+    },
+}
+
+impl HttpError {
+    /// A bad request answered with `status`
+    fn bad(status: u16, reason: &str) -> Self {
+        HttpError::Bad {
+            status,
+            reason: reason.to_string(),
+        }
+    }
+}
+
+/// The body of `POST /crack`
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CrackRequest {
+    /// The text to crack
+    pub text: String,
+    /// How many seconds to search for, at most the server's timeout
+    #[serde(default)]
+    pub timeout: Option<u32>,
+}
+
+/// The body of `POST /crack/batch`
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+pub struct BatchRequest {
+    /// The texts to crack, at most `MAX_BATCH_SIZE` of them
+    pub inputs: Vec<String>,
+}
+
+/// What was found cracking one input of `POST /crack/batch`
+#[derive(Debug, Serialize)]
+pub struct BatchResult {
+    /// Everything found cracking the input, as `POST /crack` answers
+    #[serde(flatten)]
+    pub result: JsonResult,
+    /// Whether it was answered by an earlier input of the batch instead of searched for
+    pub deduplicated: bool,
+}
+
+/// The answer to `POST /crack/batch`
+#[derive(Debug, Serialize)]
+pub struct BatchResponse {
+    /// A result for each input, in the same order
+    pub results: Vec<BatchResult>,
+    /// How long the whole batch took in milliseconds
+    pub duration_ms: f64,
+}
+
+/// How `ciphey serve` listens
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServeOptions {
+    /// The address to listen on, such as `127.0.0.1`
+    pub host: String,
+    /// The port to listen on, 0 for any free one
+    pub port: u16,
+}
+
+impl Default for ServeOptions {
+    fn default() -> Self {
+        ServeOptions {
+            host: String::from("127.0.0.1"),
+            port: DEFAULT_PORT,
+        }
+    }
+}
+
+/// The reason phrase for a status code
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        413 => "Payload Too Large",
+        431 => "Request Header Fields Too Large",
+        501 => "Not Implemented",
+        503 => "Service Unavailable",
+        _ => "Internal Server Error",
+    }
+}
+
+/// Reads one line ending in CRLF or LF, without the line ending
+fn read_line(reader: &mut impl BufRead) -> Result<String, HttpError> {
+    let mut line = Vec::new();
+    reader
+        .take(MAX_LINE_BYTES as u64 + 1)
+        .read_until(b'\n', &mut line)
+        .map_err(|e| HttpError::Io(e.to_string()))?;
+    if line.len() > MAX_LINE_BYTES {
+        return Err(HttpError::bad(431, "A request line or header is too long"));
+    }
+    if !line.ends_with(b"\n") {
+        return Err(HttpError::Io(String::from(
+            "The connection closed part way through the request",
+        )));
+    }
+    let line = String::from_utf8(line)
+        .map_err(|_| HttpError::bad(400, "The request line and headers must be UTF-8"))?;
+    Ok(line.trim_end_matches(['\n', '\r']).to_string())
+}
+
+/// Reads a request, refusing bodies longer than `max_body_bytes`
+///
+/// # Errors
+/// Returns `HttpError::Io` if the connection fails or closes early, and `HttpError::Bad`
+/// if the request is malformed, too long or chunked.
+pub fn read_request(
+    reader: &mut impl BufRead,
+    max_body_bytes: usize,
+) -> Result<Request, HttpError> {
+    let request_line = read_line(reader)?;
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target), Some(version), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err(HttpError::bad(
+            400,
+            "The request line isn't METHOD PATH VERSION",
+        ));
+    };
+    if !version.starts_with("HTTP/1.") {
+        return Err(HttpError::bad(400, "Only HTTP/1.0 and HTTP/1.1 are spoken"));
+    }
+    let path = target.split('?').next().unwrap_or(target).to_string();
+
+    let mut headers = Vec::new();
+    loop {
+        let line = read_line(reader)?;
+        if line.is_empty() {
+            break;
+        }
+        if headers.len() == MAX_HEADERS {
+            return Err(HttpError::bad(431, "The request has too many headers"));
+        }
+        let Some((name, value)) = line.split_once(':') else {
+            return Err(HttpError::bad(400, "A header has no ':'"));
+        };
+        headers.push((name.trim().to_ascii_lowercase(), value.trim().to_string()));
+    }
+    let header = |name: &str| {
+        headers
+            .iter()
+            .find(|(header, _)| header == name)
+            .map(|(_, value)| value.as_str())
+    };
+    if header("transfer-encoding").is_some() {
+        return Err(HttpError::bad(
+            501,
+            "Chunked bodies aren't supported, send a Content-Length",
+        ));
+    }
+    let length = match header("content-length") {
+        Some(length) => length
+            .parse::<usize>()
+            .map_err(|_| HttpError::bad(400, "Content-Length isn't a number"))?,
+        None => 0,
+    };
+    if length > max_body_bytes {
+        return Err(HttpError::bad(
+            413,
+            &format!("The body is longer than {} bytes", max_body_bytes),
+        ));
+    }
+    let mut body = vec![0; length];
+    reader
+        .read_exact(&mut body)
+        .map_err(|e| HttpError::Io(e.to_string()))?;
+
+    Ok(Request {
+        method: method.to_string(),
+        path,
+        headers,
+        body,
+    })
+}
+
+/// Writes a response, telling the client the connection will close
+///
+/// # Errors
+/// Returns the error if the connection can't be written to.
+pub fn write_response(writer: &mut impl Write, response: &Response) -> std::io::Result<()> {
+    let mut head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n",
+        response.status,
+        reason_phrase(response.status),
+        response.body.len()
+    );
+    for (name, value) in &response.headers {
+        head.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    head.push_str("\r\n");
+    writer.write_all(head.as_bytes())?;
+    writer.write_all(response.body.as_bytes())?;
+    writer.flush()
+}
+
+/// Answers a request
+/// `prepare_cracking` must have run before any text is cracked, which [`serve`] does.
+pub fn route(request: &Request) -> Response {
+    let allowed = match request.path.as_str() {
+        "/crack" | "/crack/batch" => "POST",
+        "/decoders" | "/stats" | "/health" => "GET",
+        _ => return Response::error(404, &format!("There is no {}", request.path)),
+    };
+    if request.method != allowed {
+        let mut response =
+            Response::error(405, &format!("{} only accepts {}", request.path, allowed));
+        response.headers.push(("Allow", allowed.to_string()));
+        return response;
+    }
+    match request.path.as_str() {
+        "/crack" => crack(&request.body),
+        "/crack/batch" => crack_batch(&request.body),
+        "/decoders" => Response::json(200, &get_decoder_docs()),
+        "/stats" => match crate::storage::stats::gather() {
+            Ok(report) => Response::json(200, &report),
+            Err(e) => Response::error(503, &format!("Could not read the statistics: {}", e)),
+        },
+        _ => Response::json(200, &serde_json::json!({ "status": "ok" })),
+    }
+}
+
+/// Cracks the text in the body of `POST /crack`
+fn crack(body: &[u8]) -> Response {
+    let request: CrackRequest = match serde_json::from_slice(body) {
+        Ok(request) => request,
+        Err(e) => {
+            return Response::error(
+                400,
+                &format!(
+                    "The body must be {{\"text\": ..., \"timeout\": ...}}: {}",
+                    e
+                ),
+            )
+        }
+    };
+
+    let cancel = CancellationToken::new();
+    let json = crack_for_client(&request.text, request.timeout, &cancel, None);
+    Response::json(200, &json)
+}
+
+/// Cracks the inputs in the body of `POST /crack/batch`, one after another
+/// The lock is held for the whole batch, so other requests to crack wait until it is done.
+fn crack_batch(body: &[u8]) -> Response {
+    let request: BatchRequest = match serde_json::from_slice(body) {
+        Ok(request) => request,
+        Err(e) => {
+            return Response::error(
+                400,
+                &format!("The body must be {{\"inputs\": [...]}}: {}", e),
+            )
+        }
+    };
+
+    let _cracking = CRACKING
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    let start = Instant::now();
+    let items = match crack_prepared_batch(&request.inputs) {
+        Ok(items) => items,
+        Err(e) => return Response::error(413, &e.to_string()),
+    };
+    let results = items
+        .iter()
+        .map(|item| BatchResult {
+            result: JsonResult::new(
+                &item.input,
+                item.result.as_ref(),
+                item.outcome,
+                item.duration,
+                &[],
+            ),
+            deduplicated: item.deduplicated,
+        })
+        .collect();
+    Response::json(
+        200,
+        &BatchResponse {
+            results,
+            duration_ms: start.elapsed().as_secs_f64() * 1000.0,
+        },
+    )
+}
+
+/// Cracks a text for a client of `ciphey serve` or `ciphey rpc`, once the texts before it
+/// have been
+///
+/// `timeout` can only shorten the config's timeout, as the search is stopped by cancelling
+/// `cancel` once it passes. `on_event` is given every event of this search as it happens.
+/// [`prepare_service`] must have run first.
+pub fn crack_for_client(
+    text: &str,
+    timeout: Option<u32>,
+    cancel: &CancellationToken,
+    on_event: Option<&mut dyn FnMut(SearchEvent)>,
+) -> JsonResult {
+    let _cracking = CRACKING
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    let start = Instant::now();
+    let crack = || {
+        std::thread::scope(|scope| {
+            // A shorter timeout than the config's is kept by cancelling the search
+            let (done, finished) = channel::<()>();
+            if let Some(timeout) =
+                timeout.filter(|timeout| *timeout < crate::config::get_config().timeout)
+            {
+                let cancel = cancel.clone();
+                scope.spawn(move || {
+                    if finished
+                        .recv_timeout(Duration::from_secs(u64::from(timeout)))
+                        .is_err()
+                    {
+                        cancel.cancel();
+                    }
+                });
+            }
+            let cracked = crack_prepared_text(text, SystemTime::now(), cancel);
+            drop(done);
+            cracked
+        })
+    };
+    // Observed while holding the lock, so only this search's events are received
+    let (result, outcome) = match on_event {
+        Some(on_event) => events::observe(on_event, crack),
+        None => crack(),
+    };
+
+    let candidates = if result.is_none() {
+        crate::best_candidates()
+    } else {
+        Vec::new()
+    };
+    JsonResult::new(text, result.as_ref(), outcome, start.elapsed(), &candidates)
+}
+
+/// Reads a request from the connection, answers it and closes it
+fn handle_connection(stream: TcpStream) {
+    let _ = stream.set_read_timeout(Some(READ_TIMEOUT));
+    let Ok(writer) = stream.try_clone() else {
+        return;
+    };
+    let mut writer = writer;
+    let mut reader = BufReader::new(stream);
+    let response = match read_request(&mut reader, MAX_BODY_BYTES) {
+        Ok(request) => {
+            log::debug!("{} {}", request.method, request.path);
+            route(&request)
+        }
+        Err(HttpError::Bad { status, reason }) => Response::error(status, &reason),
+        Err(HttpError::Io(reason)) => {
+            log::debug!("Dropped a connection: {}", reason);
+            return;
+        }
+    };
+    if let Err(e) = write_response(&mut writer, &response) {
+        log::debug!("Could not answer a request: {}", e);
+    }
+}
+
+/// Starts listening on the host and port in `options`
+///
+/// # Errors
+/// Returns an error message if the address can't be listened on.
+pub fn bind(options: &ServeOptions) -> Result<TcpListener, String> {
+    TcpListener::bind((options.host.as_str(), options.port))
+        .map_err(|e| format!("Can't listen on {}:{}: {}", options.host, options.port, e))
+}
+
+/// Sets the config up once for every text a service cracks
+/// The human checker, `top_results` and `all_results` are turned off and `api_mode` on, so
+/// nothing waits for someone at a terminal. A human checker asking through a webhook is
+/// left as it is.
+pub fn prepare_service(config: Config) {
+    let mut config = config;
+    config.api_mode = true;
+    config.human_checker_on &= !config.human_checker_webhook.is_empty();
+    config.top_results = false;
+    config.all_results = false;
+    prepare_cracking(config);
+}
+
+/// Answers requests on `listener` until the process ends, after [`prepare_service`]
+pub fn serve(listener: &TcpListener, config: Config) {
+    prepare_service(config);
+
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                std::thread::spawn(move || handle_connection(stream));
+            }
+            Err(e) => log::warn!("Could not accept a connection: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reads a request from raw bytes
+    fn parse(raw: &str) -> Result<Request, HttpError> {
+        read_request(&mut raw.as_bytes(), MAX_BODY_BYTES)
+    }
+
+    /// A request with only a method and path
+    fn request(method: &str, path: &str, body: &str) -> Request {
+        Request {
+            method: method.to_string(),
+            path: path.to_string(),
+            headers: Vec::new(),
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    #[test]
+    fn requests_are_read_with_their_headers_and_body() {
+        let request = parse(
+            "POST /crack?verbose=1 HTTP/1.1\r\nHost: localhost\r\nContent-Length: 13\r\n\r\n{\"text\":\"a\"}\n",
+        )
+        .unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/crack");
+        assert_eq!(
+            request.headers[0],
+            ("host".to_string(), "localhost".to_string())
+        );
+        assert_eq!(request.body, b"{\"text\":\"a\"}\n");
+    }
+
+    #[test]
+    fn malformed_requests_are_refused_with_a_status() {
+        let status = |raw: &str| match parse(raw) {
+            Err(HttpError::Bad { status, .. }) => status,
+            other => panic!("expected a bad request, got {:?}", other),
+        };
+        assert_eq!(status("nonsense\r\n\r\n"), 400);
+        assert_eq!(
+            status("POST /crack HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n"),
+            501
+        );
+        assert_eq!(
+            status(&format!(
+                "POST /crack HTTP/1.1\r\nContent-Length: {}\r\n\r\n",
+                MAX_BODY_BYTES + 1
+            )),
+            413
+        );
+        assert!(matches!(
+            parse("GET /health HTTP/1.1\r\n"),
+            Err(HttpError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn unknown_paths_and_methods_are_refused() {
+        assert_eq!(route(&request("GET", "/nope", "")).status, 404);
+        let response = route(&request("GET", "/crack", ""));
+        assert_eq!(response.status, 405);
+        assert_eq!(response.headers, [("Allow", String::from("POST"))]);
+        assert_eq!(
+            route(&request("POST", "/crack", "{\"txt\": 1}")).status,
+            400
+        );
+        assert_eq!(
+            route(&request("POST", "/crack/batch", "{\"text\": \"a\"}")).status,
+            400
+        );
+        let too_many = serde_json::json!({ "inputs": vec![""; crate::batch::MAX_BATCH_SIZE + 1] });
+        assert_eq!(
+            route(&request("POST", "/crack/batch", &too_many.to_string())).status,
+            413
+        );
+    }
+
+    #[test]
+    fn responses_are_written_as_http() {
+        let mut written = Vec::new();
+        write_response(&mut written, &Response::error(404, "There is no /nope")).unwrap();
+        let written = String::from_utf8(written).unwrap();
+        assert!(written.starts_with("HTTP/1.1 404 Not Found\r\n"));
+        assert!(written.contains("Content-Length: 29\r\n"));
+        assert!(written.ends_with("\r\n\r\n{\"error\":\"There is no /nope\"}"));
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn texts_are_cracked_over_http() {
+        crate::set_test_db_path();
+        let listener = bind(&ServeOptions {
+            host: String::from("127.0.0.1"),
+            port: 0,
+        })
+        .unwrap();
+        let address = listener.local_addr().unwrap();
+        std::thread::spawn(move || serve(&listener, Config::default()));
+
+        let body = "{\"text\": \"aGVsbG8gdGhlcmUgc2VydmVyIHVzZXI=\"}";
+        let mut stream = TcpStream::connect(address).unwrap();
+        write!(
+            stream,
+            "POST /crack HTTP/1.1\r\nContent-Length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        let (_, body) = response.split_once("\r\n\r\n").unwrap();
+        let json: serde_json::Value = serde_json::from_str(body).unwrap();
+        assert_eq!(json["plaintext"], "hello there server user");
+
+        let body = "{\"inputs\": [\"aGVsbG8gdGhlcmUgYmF0Y2ggdXNlcg==\", \"aGVsbG8gdGhlcmUgYmF0Y2ggdXNlcg==\"]}";
+        let mut stream = TcpStream::connect(address).unwrap();
+        write!(
+            stream,
+            "POST /crack/batch HTTP/1.1\r\nContent-Length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        let (_, body) = response.split_once("\r\n\r\n").unwrap();
+        let json: serde_json::Value = serde_json::from_str(body).unwrap();
+        let results = json["results"].as_array().unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0]["plaintext"], "hello there batch user");
+        assert_eq!(results[1]["deduplicated"], true);
+        assert!(results[0]["duration_ms"].is_number());
+    }
+}
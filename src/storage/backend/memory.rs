@@ -0,0 +1,408 @@
+//! A backend which keeps everything in memory for as long as the program runs
+//! Nothing touches the disk, which suits read-only systems and throwaway containers.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::hash::Hash;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use super::{Storage, StorageError};
+use crate::checkers::checker_result::CheckResult;
+use crate::storage::database::{
+    current_cache_limits, get_timestamp, registry_fingerprint, CacheEntry, CacheRow, CheckpointRow,
+    DecoderStatsRow, EdgeEntry, EdgeRow, ExportedCacheRow, SettingRow, CACHE_VERSION,
+};
+
+/// Values which are evicted least recently used first once there are too many
+#[derive(Debug)]
+struct Recency<K, V> {
+    /// Each value with when it was last used
+    values: HashMap<K, (u64, V)>,
+    /// The keys of the values by when they were last used
+    order: BTreeMap<u64, K>,
+    /// Goes up every time a value is used
+    clock: u64,
+}
+
+impl<K: Clone + Eq + Hash, V> Default for Recency<K, V> {
+    fn default() -> Self {
+        Recency {
+            values: HashMap::new(),
+            order: BTreeMap::new(),
+            clock: 0,
+        }
+    }
+}
+
+impl<K: Clone + Eq + Hash, V> Recency<K, V> {
+    /// The value of this key, marked as just used
+    fn get(&mut self, key: &K) -> Option<&V> {
+        self.clock += 1;
+        let (used, _) = self.values.get_mut(key)?;
+        self.order.remove(used);
+        *used = self.clock;
+        self.order.insert(self.clock, key.clone());
+        self.values.get(key).map(|(_, value)| value)
+    }
+
+    /// The value of this key, without marking it as used
+    fn peek(&self, key: &K) -> Option<&V> {
+        self.values.get(key).map(|(_, value)| value)
+    }
+
+    /// Keeps a value, evicting the least recently used ones beyond `limit`, where 0 is no limit
+    /// Returns the keys of the evicted values.
+    fn insert(&mut self, key: K, value: V, limit: usize) -> Vec<K> {
+        self.clock += 1;
+        if let Some((used, _)) = self.values.insert(key.clone(), (self.clock, value)) {
+            self.order.remove(&used);
+        }
+        self.order.insert(self.clock, key);
+        let mut evicted = Vec::new();
+        while limit > 0 && self.values.len() > limit {
+            let Some((_, oldest)) = self.order.pop_first() else {
+                break;
+            };
+            self.values.remove(&oldest);
+            evicted.push(oldest);
+        }
+        evicted
+    }
+}
+
+/// The text, decoder and settings an edge is kept under
+type EdgeKey = (String, String, String);
+
+/// Everything [`MemoryStorage`] keeps
+#[derive(Debug, Default)]
+struct Tables {
+    /// Cached results by the text they decode
+    cache: Recency<String, ExportedCacheRow>,
+    /// What each decoder made of a text, by the text, decoder and settings
+    edges: Recency<EdgeKey, EdgeRow>,
+    /// The decoders which have been run on a text with some settings, by the text and settings
+    decoders_run: HashMap<(String, String), HashSet<String>>,
+    /// Rejected plaintexts with the checker which accepted them
+    rejections: Vec<(String, String)>,
+    /// Confirmed plaintexts with the checker which accepted them
+    confirmations: Vec<(String, String)>,
+    /// How each decoder has fared
+    decoder_stats: HashMap<String, DecoderStatsRow>,
+    /// Checkpoints by the text being searched
+    checkpoints: HashMap<String, CheckpointRow>,
+    /// Named counters
+    counters: HashMap<String, i64>,
+}
+
+/// Keeps everything in memory, so it is forgotten when the program exits
+/// The cache and the decodes of every text met stay within `cache_max_entries` and
+/// `edge_cache_max_entries`, evicting the least recently used first. Toggles made with
+/// `ciphey toggle` are saved in the SQLite database, so none are read from here.
+#[derive(Debug, Default)]
+pub struct MemoryStorage {
+    /// Every table, behind one lock as searches only touch them briefly
+    tables: Mutex<Tables>,
+}
+
+impl MemoryStorage {
+    /// An empty in-memory backend
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The tables, even if a thread panicked while holding them
+    fn tables(&self) -> MutexGuard<'_, Tables> {
+        self.tables.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Storage for MemoryStorage {
+    fn name(&self) -> &'static str {
+        "memory"
+    }
+
+    fn setup(&self) -> Result<(), StorageError> {
+        Ok(())
+    }
+
+    fn read_cache(&self, encoded_text: &str) -> Result<Option<CacheRow>, StorageError> {
+        let registry = registry_fingerprint();
+        let mut tables = self.tables();
+        Ok(tables
+            .cache
+            .get(&encoded_text.to_string())
+            .filter(|row| row.version == CACHE_VERSION && row.registry == registry)
+            .cloned()
+            .map(CacheRow::from))
+    }
+
+    fn insert_cache(&self, entry: &CacheEntry) -> Result<(), StorageError> {
+        let limit = current_cache_limits().max_entries;
+        self.tables()
+            .cache
+            .insert(entry.encoded_text.clone(), entry.to_exported_row(), limit);
+        Ok(())
+    }
+
+    fn read_edges(&self, encoded_text: &str, settings: &str) -> Result<Vec<EdgeRow>, StorageError> {
+        let tables = self.tables();
+        let Some(decoders) = tables
+            .decoders_run
+            .get(&(encoded_text.to_string(), settings.to_string()))
+        else {
+            return Ok(Vec::new());
+        };
+        Ok(decoders
+            .iter()
+            .filter_map(|decoder| {
+                tables.edges.peek(&(
+                    encoded_text.to_string(),
+                    decoder.clone(),
+                    settings.to_string(),
+                ))
+            })
+            .map(|edge| EdgeRow {
+                encoded_text: edge.encoded_text.clone(),
+                decoder: edge.decoder.clone(),
+                settings: edge.settings.clone(),
+                result: edge.result.clone(),
+                timestamp: edge.timestamp.clone(),
+            })
+            .collect())
+    }
+
+    fn insert_edges(&self, edges: &[EdgeEntry]) -> Result<(), StorageError> {
+        let limit = current_cache_limits().max_edges;
+        let timestamp = get_timestamp();
+        let mut tables = self.tables();
+        for edge in edges {
+            tables
+                .decoders_run
+                .entry((edge.encoded_text.clone(), edge.settings.clone()))
+                .or_default()
+                .insert(edge.decoder.clone());
+            let key = (
+                edge.encoded_text.clone(),
+                edge.decoder.clone(),
+                edge.settings.clone(),
+            );
+            let row = EdgeRow {
+                encoded_text: edge.encoded_text.clone(),
+                decoder: edge.decoder.clone(),
+                settings: edge.settings.clone(),
+                result: edge.result.clone(),
+                timestamp: timestamp.clone(),
+            };
+            for (text, decoder, settings) in tables.edges.insert(key, row, limit) {
+                let group = (text, settings);
+                if let Some(decoders) = tables.decoders_run.get_mut(&group) {
+                    decoders.remove(&decoder);
+                    if decoders.is_empty() {
+                        tables.decoders_run.remove(&group);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn read_rejected_plaintexts(&self) -> Result<Vec<String>, StorageError> {
+        let mut plaintexts: Vec<String> = self
+            .tables()
+            .rejections
+            .iter()
+            .map(|(plaintext, _)| plaintext.clone())
+            .collect();
+        plaintexts.sort();
+        plaintexts.dedup();
+        Ok(plaintexts)
+    }
+
+    fn insert_rejection(
+        &self,
+        plaintext: &str,
+        check_result: &CheckResult,
+    ) -> Result<(), StorageError> {
+        self.tables()
+            .rejections
+            .push((plaintext.to_string(), check_result.checker_name.to_string()));
+        Ok(())
+    }
+
+    fn read_confirmed_plaintexts(&self) -> Result<Vec<String>, StorageError> {
+        let mut plaintexts: Vec<String> = self
+            .tables()
+            .confirmations
+            .iter()
+            .map(|(plaintext, _)| plaintext.clone())
+            .collect();
+        plaintexts.sort();
+        plaintexts.dedup();
+        Ok(plaintexts)
+    }
+
+    fn insert_confirmation(
+        &self,
+        plaintext: &str,
+        check_result: &CheckResult,
+    ) -> Result<(), StorageError> {
+        self.tables()
+            .confirmations
+            .push((plaintext.to_string(), check_result.checker_name.to_string()));
+        Ok(())
+    }
+
+    fn read_settings(&self) -> Result<Vec<SettingRow>, StorageError> {
+        Ok(Vec::new())
+    }
+
+    fn read_decoder_stats(&self) -> Result<Vec<DecoderStatsRow>, StorageError> {
+        let mut stats: Vec<DecoderStatsRow> =
+            self.tables().decoder_stats.values().cloned().collect();
+        stats.sort_by(|a, b| a.decoder.cmp(&b.decoder));
+        Ok(stats)
+    }
+
+    fn add_decoder_stats(
+        &self,
+        decoder: &str,
+        attempts: i64,
+        successes: i64,
+        runtime_micros: i64,
+    ) -> Result<(), StorageError> {
+        let mut tables = self.tables();
+        let row = tables
+            .decoder_stats
+            .entry(decoder.to_string())
+            .or_insert_with(|| DecoderStatsRow {
+                decoder: decoder.to_string(),
+                ..DecoderStatsRow::default()
+            });
+        row.attempts += attempts;
+        row.successes += successes;
+        row.runtime_micros += runtime_micros;
+        row.timestamp = get_timestamp();
+        Ok(())
+    }
+
+    fn read_checkpoint(&self, encoded_text: &str) -> Result<Option<CheckpointRow>, StorageError> {
+        Ok(self
+            .tables()
+            .checkpoints
+            .get(encoded_text)
+            .map(|row| CheckpointRow {
+                encoded_text: row.encoded_text.clone(),
+                frontier: row.frontier.clone(),
+                timestamp: row.timestamp.clone(),
+            }))
+    }
+
+    fn upsert_checkpoint(&self, encoded_text: &str, frontier: &str) -> Result<(), StorageError> {
+        self.tables().checkpoints.insert(
+            encoded_text.to_string(),
+            CheckpointRow {
+                encoded_text: encoded_text.to_string(),
+                frontier: frontier.to_string(),
+                timestamp: get_timestamp(),
+            },
+        );
+        Ok(())
+    }
+
+    fn delete_checkpoint(&self, encoded_text: &str) -> Result<(), StorageError> {
+        self.tables().checkpoints.remove(encoded_text);
+        Ok(())
+    }
+
+    fn add_counters(&self, counters: &[(&str, i64)]) -> Result<(), StorageError> {
+        let mut tables = self.tables();
+        for (name, amount) in counters {
+            *tables.counters.entry((*name).to_string()).or_default() += amount;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checkers::checker_type::{Check, Checker};
+    use crate::checkers::english::EnglishChecker;
+    use crate::decoders::base64_decoder::Base64Decoder;
+    use crate::decoders::crack_results::CrackResult;
+    use crate::decoders::interface::{Crack, Decoder};
+
+    #[test]
+    fn cached_results_are_read_back() {
+        let storage = MemoryStorage::new();
+        let mut step = CrackResult::new(&Decoder::<Base64Decoder>::new(), "aGk=".to_string());
+        step.success = true;
+        let entry = CacheEntry {
+            uuid: uuid::Uuid::new_v4(),
+            encoded_text: "aGk=".to_string(),
+            decoded_text: "hi".to_string(),
+            path: vec![step],
+            execution_time_ms: 3,
+            checker: "English Checker".to_string(),
+            sensitivity: None,
+            confidence: 0.75,
+        };
+        storage.insert_cache(&entry).unwrap();
+
+        let row = storage.read_cache("aGk=").unwrap().unwrap();
+        assert_eq!(row.uuid, entry.uuid);
+        assert_eq!(row.decoded_text, "hi");
+        assert_eq!(row.path.len(), 1);
+        assert!(row.successful);
+        assert_eq!(row.checker, "English Checker");
+        assert!(storage.read_cache("bm8=").unwrap().is_none());
+    }
+
+    #[test]
+    fn least_recently_used_values_are_evicted_first() {
+        let mut recency = Recency::default();
+        recency.insert("a", 1, 2);
+        recency.insert("b", 2, 2);
+        assert_eq!(recency.get(&"a"), Some(&1));
+        recency.insert("c", 3, 2);
+        assert_eq!(recency.get(&"b"), None);
+        assert_eq!(recency.get(&"a"), Some(&1));
+        assert_eq!(recency.get(&"c"), Some(&3));
+    }
+
+    #[test]
+    fn statistics_checkpoints_and_rejections_are_kept() {
+        let storage = MemoryStorage::new();
+        storage.add_decoder_stats("Base64", 2, 1, 10).unwrap();
+        storage.add_decoder_stats("Base64", 3, 0, 5).unwrap();
+        let stats = storage.read_decoder_stats().unwrap();
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].attempts, 5);
+        assert_eq!(stats[0].runtime_micros, 15);
+
+        storage.upsert_checkpoint("text", "[]").unwrap();
+        assert_eq!(
+            storage.read_checkpoint("text").unwrap().unwrap().frontier,
+            "[]"
+        );
+        storage.delete_checkpoint("text").unwrap();
+        assert!(storage.read_checkpoint("text").unwrap().is_none());
+
+        let check_result = CheckResult::new(&Checker::<EnglishChecker>::new());
+        storage.insert_rejection("nope", &check_result).unwrap();
+        storage.insert_rejection("nope", &check_result).unwrap();
+        assert_eq!(storage.read_rejected_plaintexts().unwrap(), vec!["nope"]);
+        storage.insert_confirmation("yes", &check_result).unwrap();
+        assert_eq!(storage.read_confirmed_plaintexts().unwrap(), vec!["yes"]);
+
+        storage
+            .insert_edges(&[EdgeEntry {
+                encoded_text: "text".to_string(),
+                decoder: "Base64".to_string(),
+                settings: "s".to_string(),
+                result: "{}".to_string(),
+            }])
+            .unwrap();
+        assert_eq!(storage.read_edges("text", "s").unwrap().len(), 1);
+        assert!(storage.read_edges("text", "other").unwrap().is_empty());
+    }
+}
@@ -0,0 +1,208 @@
+//! Where searches keep what they learn, behind the [`Storage`] trait
+//! Searches read and write the cache, the decodes of every text they meet, the plaintexts
+//! the user rejected, the saved toggles, decoder statistics and checkpoints through
+//! [`storage`], so ciphey can run where a SQLite file can't be written.
+//!
+//! The SQLite database is the default. [`MemoryStorage`] keeps everything for as long as the
+//! program runs, and with the `redis` feature [`RedisStorage`] shares it between machines.
+//! The maintenance subcommands such as `ciphey stats` and `ciphey cache export` always work
+//! on the SQLite database.
+
+use std::fmt;
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+
+use crate::checkers::checker_result::CheckResult;
+use crate::storage::database::{
+    CacheEntry, CacheRow, CheckpointRow, DecoderStatsRow, EdgeEntry, EdgeRow, SettingRow,
+};
+
+mod memory;
+#[cfg(feature = "redis")]
+mod redis;
+mod sqlite;
+
+pub use memory::MemoryStorage;
+#[cfg(feature = "redis")]
+pub use redis::RedisStorage;
+pub use sqlite::SqliteStorage;
+
+/// The backend chosen with [`use_storage`]
+static STORAGE: OnceLock<Box<dyn Storage>> = OnceLock::new();
+
+/// The backends which can be chosen with `storage_backend` in the config file
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageBackend {
+    /// The SQLite database at `database_path`
+    #[default]
+    Sqlite,
+    /// Kept in memory for as long as the program runs
+    Memory,
+    /// A Redis server at `storage_url`, shared by everyone pointed at it.
+    /// Only available when ciphey is built with the `redis` feature
+    Redis,
+}
+
+/// Why a backend couldn't be read from or written to
+#[derive(Debug)]
+pub enum StorageError {
+    /// The SQLite database failed
+    Sqlite(rusqlite::Error),
+    /// A remote backend couldn't be reached or answered with an error
+    Remote(String),
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageError::Sqlite(e) => write!(f, "{}", e),
+            StorageError::Remote(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for StorageError {}
+
+impl From<rusqlite::Error> for StorageError {
+    fn from(error: rusqlite::Error) -> Self {
+        StorageError::Sqlite(error)
+    }
+}
+
+/// Everything a search reads and writes while it runs
+/// Cached results are only returned if this version of ciphey cached them with the same
+/// decoders and checkers, see [`crate::storage::database::registry_fingerprint`].
+pub trait Storage: Send + Sync {
+    /// Name of the backend, shown in logs
+    fn name(&self) -> &'static str;
+
+    /// Gets the backend ready, such as by creating the tables it needs
+    ///
+    /// # Errors
+    /// Returns a StorageError if the backend can't be used.
+    fn setup(&self) -> Result<(), StorageError>;
+
+    /// The cached result for this text, None if there isn't one
+    ///
+    /// # Errors
+    /// Returns a StorageError if the backend can't be read.
+    fn read_cache(&self, encoded_text: &str) -> Result<Option<CacheRow>, StorageError>;
+
+    /// Caches the result of a search
+    ///
+    /// # Errors
+    /// Returns a StorageError if the backend can't be written to.
+    fn insert_cache(&self, entry: &CacheEntry) -> Result<(), StorageError>;
+
+    /// What each decoder made of this text with these settings in earlier searches
+    ///
+    /// # Errors
+    /// Returns a StorageError if the backend can't be read.
+    fn read_edges(&self, encoded_text: &str, settings: &str) -> Result<Vec<EdgeRow>, StorageError>;
+
+    /// Keeps what decoders made of texts met during a search
+    ///
+    /// # Errors
+    /// Returns a StorageError if the backend can't be written to.
+    fn insert_edges(&self, edges: &[EdgeEntry]) -> Result<(), StorageError>;
+
+    /// Every plaintext rejected with the human checker
+    ///
+    /// # Errors
+    /// Returns a StorageError if the backend can't be read.
+    fn read_rejected_plaintexts(&self) -> Result<Vec<String>, StorageError>;
+
+    /// Remembers that the user rejected a plaintext the checker accepted
+    ///
+    /// # Errors
+    /// Returns a StorageError if the backend can't be written to.
+    fn insert_rejection(
+        &self,
+        plaintext: &str,
+        check_result: &CheckResult,
+    ) -> Result<(), StorageError>;
+
+    /// Every plaintext confirmed with the human checker
+    ///
+    /// # Errors
+    /// Returns a StorageError if the backend can't be read.
+    fn read_confirmed_plaintexts(&self) -> Result<Vec<String>, StorageError>;
+
+    /// Remembers that the user confirmed a plaintext the checker accepted
+    ///
+    /// # Errors
+    /// Returns a StorageError if the backend can't be written to.
+    fn insert_confirmation(
+        &self,
+        plaintext: &str,
+        check_result: &CheckResult,
+    ) -> Result<(), StorageError>;
+
+    /// Every decoder and checker toggled with `ciphey toggle`
+    ///
+    /// # Errors
+    /// Returns a StorageError if the backend can't be read.
+    fn read_settings(&self) -> Result<Vec<SettingRow>, StorageError>;
+
+    /// How every decoder has fared in earlier searches
+    ///
+    /// # Errors
+    /// Returns a StorageError if the backend can't be read.
+    fn read_decoder_stats(&self) -> Result<Vec<DecoderStatsRow>, StorageError>;
+
+    /// Adds the attempts, successes and runtime of a search to a decoder's statistics
+    ///
+    /// # Errors
+    /// Returns a StorageError if the backend can't be written to.
+    fn add_decoder_stats(
+        &self,
+        decoder: &str,
+        attempts: i64,
+        successes: i64,
+        runtime_micros: i64,
+    ) -> Result<(), StorageError>;
+
+    /// The checkpoint saved when the search of this text ran out of time
+    ///
+    /// # Errors
+    /// Returns a StorageError if the backend can't be read.
+    fn read_checkpoint(&self, encoded_text: &str) -> Result<Option<CheckpointRow>, StorageError>;
+
+    /// Saves where the search of this text got to, replacing any earlier checkpoint
+    ///
+    /// # Errors
+    /// Returns a StorageError if the backend can't be written to.
+    fn upsert_checkpoint(&self, encoded_text: &str, frontier: &str) -> Result<(), StorageError>;
+
+    /// Removes the checkpoint of this text
+    ///
+    /// # Errors
+    /// Returns a StorageError if the backend can't be written to.
+    fn delete_checkpoint(&self, encoded_text: &str) -> Result<(), StorageError>;
+
+    /// Adds to named counters, such as how many texts were answered from the cache
+    ///
+    /// # Errors
+    /// Returns a StorageError if the backend can't be written to.
+    fn add_counters(&self, counters: &[(&str, i64)]) -> Result<(), StorageError>;
+}
+
+/// Uses this backend for the rest of the run instead of the SQLite database
+///
+/// Has to be called before the first search, and returns false without changing anything
+/// if a backend has already been chosen.
+pub fn use_storage(storage: Box<dyn Storage>) -> bool {
+    STORAGE.set(storage).is_ok()
+}
+
+/// The backend in use, the SQLite database unless [`use_storage`] chose another
+pub fn storage() -> &'static dyn Storage {
+    STORAGE.get_or_init(|| Box::new(SqliteStorage)).as_ref()
+}
+
+/// Whether the backend in use is the SQLite database
+pub fn uses_sqlite() -> bool {
+    storage().name() == SqliteStorage.name()
+}
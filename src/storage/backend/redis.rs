@@ -0,0 +1,576 @@
+//! A backend on a Redis server, so a team's machines share one cache
+//! Only built with the `redis` feature. It speaks just enough of the Redis protocol over a
+//! plain TCP connection for the commands it needs, so it pulls in no other crates.
+//!
+//! Everything is kept under keys starting with `ciphey:`. Cached results expire after
+//! `cache_max_age_days`, and as Redis has no cheap way to count keys by prefix the number of
+//! results kept is bounded by the server's own `maxmemory` policy instead of
+//! `cache_max_entries`.
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+use sha2::{Digest, Sha256};
+
+use super::{Storage, StorageError};
+use crate::checkers::checker_result::CheckResult;
+use crate::storage::database::{
+    current_cache_limits, get_timestamp, registry_fingerprint, CacheEntry, CacheRow, CheckpointRow,
+    DecoderStatsRow, EdgeEntry, EdgeRow, ExportedCacheRow, SettingRow, CACHE_VERSION,
+};
+
+/// What every key starts with, so ciphey can share a server with other programs
+const KEY_PREFIX: &str = "ciphey:";
+
+/// How long connecting, and each command, waits for the server
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The port Redis listens on unless the URL says otherwise
+const DEFAULT_PORT: u16 = 6379;
+
+/// Where the server is and how to log in, read from a `redis://` URL
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RedisUrl {
+    /// Host name or address of the server
+    host: String,
+    /// Port the server listens on
+    port: u16,
+    /// User to log in as, if the server uses access control lists
+    username: Option<String>,
+    /// Password to log in with
+    password: Option<String>,
+    /// Number of the database to use
+    database: u32,
+}
+
+impl RedisUrl {
+    /// Reads a URL like `redis://:password@host:6379/0`
+    fn parse(url: &str) -> Result<Self, StorageError> {
+        let invalid = |why: &str| StorageError::Remote(format!("Invalid Redis URL {url}: {why}"));
+        let rest = url
+            .strip_prefix("redis://")
+            .ok_or_else(|| invalid("it has to start with redis://"))?;
+        let (authority, database) = match rest.split_once('/') {
+            Some((authority, "")) => (authority, 0),
+            Some((authority, database)) => (
+                authority,
+                database
+                    .parse()
+                    .map_err(|_| invalid("the database has to be a number"))?,
+            ),
+            None => (rest, 0),
+        };
+        let (credentials, address) = match authority.rsplit_once('@') {
+            Some((credentials, address)) => (Some(credentials), address),
+            None => (None, authority),
+        };
+        let (username, password) = match credentials.map(|c| c.split_once(':')) {
+            Some(Some((username, password))) => (
+                (!username.is_empty()).then(|| username.to_string()),
+                Some(password.to_string()),
+            ),
+            Some(None) => (None, credentials.map(str::to_string)),
+            None => (None, None),
+        };
+        let (host, port) = match address.rsplit_once(':') {
+            Some((host, port)) => (
+                host,
+                port.parse()
+                    .map_err(|_| invalid("the port has to be a number"))?,
+            ),
+            None => (address, DEFAULT_PORT),
+        };
+        if host.is_empty() {
+            return Err(invalid("it has no host"));
+        }
+        Ok(RedisUrl {
+            host: host.to_string(),
+            port,
+            username,
+            password,
+            database,
+        })
+    }
+}
+
+/// A reply from the server
+#[derive(Debug, PartialEq)]
+enum Reply {
+    /// A short status such as OK
+    Status(String),
+    /// A number
+    Integer(i64),
+    /// A string, None if the key doesn't exist
+    Bulk(Option<Vec<u8>>),
+    /// Several replies, such as the fields and values of a hash
+    Array(Vec<Reply>),
+}
+
+impl Reply {
+    /// The reply as text, None if it is missing
+    fn into_string(self) -> Option<String> {
+        match self {
+            Reply::Status(text) => Some(text),
+            Reply::Integer(number) => Some(number.to_string()),
+            Reply::Bulk(bytes) => bytes.map(|bytes| String::from_utf8_lossy(&bytes).into_owned()),
+            Reply::Array(_) => None,
+        }
+    }
+
+    /// The replies of an array as text, and no replies for anything else
+    fn into_strings(self) -> Vec<String> {
+        match self {
+            Reply::Array(replies) => replies.into_iter().filter_map(Reply::into_string).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// The fields and values of a hash returned by HGETALL
+    fn into_hash(self) -> HashMap<String, String> {
+        let mut strings = self.into_strings().into_iter();
+        let mut hash = HashMap::new();
+        while let (Some(field), Some(value)) = (strings.next(), strings.next()) {
+            hash.insert(field, value);
+        }
+        hash
+    }
+}
+
+/// Writes a command the way the server reads it, as an array of strings
+fn encode_command(args: &[&[u8]]) -> Vec<u8> {
+    let mut command = format!("*{}\r\n", args.len()).into_bytes();
+    for arg in args {
+        command.extend_from_slice(format!("${}\r\n", arg.len()).as_bytes());
+        command.extend_from_slice(arg);
+        command.extend_from_slice(b"\r\n");
+    }
+    command
+}
+
+/// Reads one reply from the server
+fn read_reply(reader: &mut impl BufRead) -> Result<Reply, StorageError> {
+    let mut line = String::new();
+    reader.read_line(&mut line).map_err(remote_error)?;
+    let line = line.trim_end_matches(['\r', '\n']);
+    let invalid = || StorageError::Remote(format!("Unexpected reply from Redis: {line}"));
+    let (kind, rest) = line.split_at_checked(1).ok_or_else(invalid)?;
+    let length = || rest.parse::<i64>().map_err(|_| invalid());
+    match kind {
+        "+" => Ok(Reply::Status(rest.to_string())),
+        "-" => Err(StorageError::Remote(format!("Redis refused: {rest}"))),
+        ":" => Ok(Reply::Integer(length()?)),
+        "$" => {
+            let Ok(length) = usize::try_from(length()?) else {
+                return Ok(Reply::Bulk(None));
+            };
+            let mut bytes = vec![0; length + 2];
+            reader.read_exact(&mut bytes).map_err(remote_error)?;
+            bytes.truncate(length);
+            Ok(Reply::Bulk(Some(bytes)))
+        }
+        "*" => {
+            let Ok(length) = usize::try_from(length()?) else {
+                return Ok(Reply::Array(Vec::new()));
+            };
+            (0..length)
+                .map(|_| read_reply(reader))
+                .collect::<Result<_, _>>()
+                .map(Reply::Array)
+        }
+        _ => Err(invalid()),
+    }
+}
+
+/// Wraps an error talking to the server
+fn remote_error(error: impl std::fmt::Display) -> StorageError {
+    StorageError::Remote(format!("Could not talk to Redis: {error}"))
+}
+
+/// Hash of a text, so keys stay short however long the text is
+fn digest(text: &str) -> String {
+    format!("{:x}", Sha256::digest(text.as_bytes()))
+}
+
+/// Keeps everything on a Redis server
+/// One connection is opened and shared, and opened again if the server drops it.
+#[derive(Debug)]
+pub struct RedisStorage {
+    /// Where the server is
+    url: RedisUrl,
+    /// The open connection, None until the first command or after it broke
+    connection: Mutex<Option<BufReader<TcpStream>>>,
+}
+
+impl RedisStorage {
+    /// A backend on the server at this `redis://` URL
+    /// Nothing is sent until the first command, so an unreachable server is only noticed
+    /// by [`Storage::setup`].
+    ///
+    /// # Errors
+    /// Returns a StorageError if the URL can't be read.
+    pub fn new(url: &str) -> Result<Self, StorageError> {
+        Ok(RedisStorage {
+            url: RedisUrl::parse(url)?,
+            connection: Mutex::new(None),
+        })
+    }
+
+    /// Connects to the server, logs in and picks the database
+    fn connect(&self) -> Result<BufReader<TcpStream>, StorageError> {
+        let address = (self.url.host.as_str(), self.url.port)
+            .to_socket_addrs()
+            .map_err(remote_error)?
+            .next()
+            .ok_or_else(|| remote_error(format!("{} has no address", self.url.host)))?;
+        let stream = TcpStream::connect_timeout(&address, TIMEOUT).map_err(remote_error)?;
+        stream
+            .set_read_timeout(Some(TIMEOUT))
+            .map_err(remote_error)?;
+        stream
+            .set_write_timeout(Some(TIMEOUT))
+            .map_err(remote_error)?;
+        let mut connection = BufReader::new(stream);
+        if let Some(password) = &self.url.password {
+            match &self.url.username {
+                Some(username) => send(
+                    &mut connection,
+                    &[b"AUTH", username.as_bytes(), password.as_bytes()],
+                )?,
+                None => send(&mut connection, &[b"AUTH", password.as_bytes()])?,
+            };
+        }
+        if self.url.database != 0 {
+            send(
+                &mut connection,
+                &[b"SELECT", self.url.database.to_string().as_bytes()],
+            )?;
+        }
+        Ok(connection)
+    }
+
+    /// The connection, even if a thread panicked while holding it
+    fn connection(&self) -> MutexGuard<'_, Option<BufReader<TcpStream>>> {
+        self.connection
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Sends a command and reads its reply, connecting again once if the connection broke
+    fn command(&self, args: &[&[u8]]) -> Result<Reply, StorageError> {
+        let mut connection = self.connection();
+        for attempt in 0..2 {
+            if connection.is_none() {
+                *connection = Some(self.connect()?);
+            }
+            let Some(open) = connection.as_mut() else {
+                continue;
+            };
+            match send(open, args) {
+                Ok(reply) => return Ok(reply),
+                // The server answered, so the connection is fine and trying again won't help
+                Err(StorageError::Remote(e)) if e.starts_with("Redis refused") => {
+                    return Err(StorageError::Remote(e))
+                }
+                Err(e) if attempt == 1 => {
+                    *connection = None;
+                    return Err(e);
+                }
+                Err(_) => *connection = None,
+            }
+        }
+        Err(remote_error("the connection kept breaking"))
+    }
+
+    /// The key of the cached result of a text, which only this version and these decoders
+    /// and checkers read
+    fn cache_key(encoded_text: &str) -> String {
+        format!(
+            "{KEY_PREFIX}cache:{CACHE_VERSION}:{}:{}",
+            registry_fingerprint(),
+            digest(encoded_text)
+        )
+    }
+
+    /// The key of the hash of what each decoder made of a text with some settings
+    fn edges_key(encoded_text: &str, settings: &str) -> String {
+        format!(
+            "{KEY_PREFIX}edges:{}:{}",
+            digest(settings),
+            digest(encoded_text)
+        )
+    }
+
+    /// The key of the checkpoint of a text
+    fn checkpoint_key(encoded_text: &str) -> String {
+        format!("{KEY_PREFIX}checkpoint:{}", digest(encoded_text))
+    }
+
+    /// The key of the statistics of a decoder
+    fn decoder_stats_key(decoder: &str) -> String {
+        format!("{KEY_PREFIX}decoder_stats:{decoder}")
+    }
+
+    /// Makes a key expire after `cache_max_age_days`, unless there is no limit
+    fn expire(&self, key: &str) -> Result<(), StorageError> {
+        let days = current_cache_limits().max_age_days;
+        if days > 0 {
+            let seconds = (u64::from(days) * 24 * 60 * 60).to_string();
+            self.command(&[b"EXPIRE", key.as_bytes(), seconds.as_bytes()])?;
+        }
+        Ok(())
+    }
+}
+
+/// Sends a command on a connection and reads its reply
+fn send(connection: &mut BufReader<TcpStream>, args: &[&[u8]]) -> Result<Reply, StorageError> {
+    connection
+        .get_mut()
+        .write_all(&encode_command(args))
+        .map_err(remote_error)?;
+    read_reply(connection)
+}
+
+impl Storage for RedisStorage {
+    fn name(&self) -> &'static str {
+        "redis"
+    }
+
+    fn setup(&self) -> Result<(), StorageError> {
+        self.command(&[b"PING"])?;
+        Ok(())
+    }
+
+    fn read_cache(&self, encoded_text: &str) -> Result<Option<CacheRow>, StorageError> {
+        let reply = self.command(&[b"GET", Self::cache_key(encoded_text).as_bytes()])?;
+        Ok(reply
+            .into_string()
+            .and_then(|json| serde_json::from_str::<ExportedCacheRow>(&json).ok())
+            .map(CacheRow::from))
+    }
+
+    fn insert_cache(&self, entry: &CacheEntry) -> Result<(), StorageError> {
+        let key = Self::cache_key(&entry.encoded_text);
+        let json = serde_json::to_string(&entry.to_exported_row()).map_err(remote_error)?;
+        self.command(&[b"SET", key.as_bytes(), json.as_bytes()])?;
+        self.expire(&key)
+    }
+
+    fn read_edges(&self, encoded_text: &str, settings: &str) -> Result<Vec<EdgeRow>, StorageError> {
+        let key = Self::edges_key(encoded_text, settings);
+        let reply = self.command(&[b"HGETALL", key.as_bytes()])?;
+        Ok(reply
+            .into_hash()
+            .into_iter()
+            .map(|(decoder, result)| EdgeRow {
+                encoded_text: encoded_text.to_string(),
+                decoder,
+                settings: settings.to_string(),
+                result,
+                timestamp: String::new(),
+            })
+            .collect())
+    }
+
+    fn insert_edges(&self, edges: &[EdgeEntry]) -> Result<(), StorageError> {
+        for edge in edges {
+            let key = Self::edges_key(&edge.encoded_text, &edge.settings);
+            self.command(&[
+                b"HSET",
+                key.as_bytes(),
+                edge.decoder.as_bytes(),
+                edge.result.as_bytes(),
+            ])?;
+            self.expire(&key)?;
+        }
+        Ok(())
+    }
+
+    fn read_rejected_plaintexts(&self) -> Result<Vec<String>, StorageError> {
+        let key = format!("{KEY_PREFIX}rejections");
+        let mut plaintexts = self.command(&[b"SMEMBERS", key.as_bytes()])?.into_strings();
+        plaintexts.sort();
+        Ok(plaintexts)
+    }
+
+    fn insert_rejection(
+        &self,
+        plaintext: &str,
+        _check_result: &CheckResult,
+    ) -> Result<(), StorageError> {
+        let key = format!("{KEY_PREFIX}rejections");
+        self.command(&[b"SADD", key.as_bytes(), plaintext.as_bytes()])?;
+        Ok(())
+    }
+
+    fn read_confirmed_plaintexts(&self) -> Result<Vec<String>, StorageError> {
+        let key = format!("{KEY_PREFIX}confirmations");
+        let mut plaintexts = self.command(&[b"SMEMBERS", key.as_bytes()])?.into_strings();
+        plaintexts.sort();
+        Ok(plaintexts)
+    }
+
+    fn insert_confirmation(
+        &self,
+        plaintext: &str,
+        _check_result: &CheckResult,
+    ) -> Result<(), StorageError> {
+        let key = format!("{KEY_PREFIX}confirmations");
+        self.command(&[b"SADD", key.as_bytes(), plaintext.as_bytes()])?;
+        Ok(())
+    }
+
+    fn read_settings(&self) -> Result<Vec<SettingRow>, StorageError> {
+        Ok(Vec::new())
+    }
+
+    fn read_decoder_stats(&self) -> Result<Vec<DecoderStatsRow>, StorageError> {
+        let key = format!("{KEY_PREFIX}decoders");
+        let mut decoders = self.command(&[b"SMEMBERS", key.as_bytes()])?.into_strings();
+        decoders.sort();
+        decoders
+            .into_iter()
+            .map(|decoder| {
+                let key = Self::decoder_stats_key(&decoder);
+                let hash = self.command(&[b"HGETALL", key.as_bytes()])?.into_hash();
+                let number = |field: &str| {
+                    hash.get(field)
+                        .and_then(|value| value.parse().ok())
+                        .unwrap_or_default()
+                };
+                Ok(DecoderStatsRow {
+                    attempts: number("attempts"),
+                    successes: number("successes"),
+                    runtime_micros: number("runtime_micros"),
+                    timestamp: hash.get("timestamp").cloned().unwrap_or_default(),
+                    decoder,
+                })
+            })
+            .collect()
+    }
+
+    fn add_decoder_stats(
+        &self,
+        decoder: &str,
+        attempts: i64,
+        successes: i64,
+        runtime_micros: i64,
+    ) -> Result<(), StorageError> {
+        let decoders = format!("{KEY_PREFIX}decoders");
+        self.command(&[b"SADD", decoders.as_bytes(), decoder.as_bytes()])?;
+        let key = Self::decoder_stats_key(decoder);
+        for (field, amount) in [
+            ("attempts", attempts),
+            ("successes", successes),
+            ("runtime_micros", runtime_micros),
+        ] {
+            self.command(&[
+                b"HINCRBY",
+                key.as_bytes(),
+                field.as_bytes(),
+                amount.to_string().as_bytes(),
+            ])?;
+        }
+        self.command(&[
+            b"HSET",
+            key.as_bytes(),
+            b"timestamp",
+            get_timestamp().as_bytes(),
+        ])?;
+        Ok(())
+    }
+
+    fn read_checkpoint(&self, encoded_text: &str) -> Result<Option<CheckpointRow>, StorageError> {
+        let key = Self::checkpoint_key(encoded_text);
+        let mut hash = self.command(&[b"HGETALL", key.as_bytes()])?.into_hash();
+        Ok(hash.remove("frontier").map(|frontier| CheckpointRow {
+            encoded_text: encoded_text.to_string(),
+            frontier,
+            timestamp: hash.remove("timestamp").unwrap_or_default(),
+        }))
+    }
+
+    fn upsert_checkpoint(&self, encoded_text: &str, frontier: &str) -> Result<(), StorageError> {
+        let key = Self::checkpoint_key(encoded_text);
+        self.command(&[
+            b"HSET",
+            key.as_bytes(),
+            b"frontier",
+            frontier.as_bytes(),
+            b"timestamp",
+            get_timestamp().as_bytes(),
+        ])?;
+        Ok(())
+    }
+
+    fn delete_checkpoint(&self, encoded_text: &str) -> Result<(), StorageError> {
+        let key = Self::checkpoint_key(encoded_text);
+        self.command(&[b"DEL", key.as_bytes()])?;
+        Ok(())
+    }
+
+    fn add_counters(&self, counters: &[(&str, i64)]) -> Result<(), StorageError> {
+        let key = format!("{KEY_PREFIX}counters");
+        for (name, amount) in counters {
+            self.command(&[
+                b"HINCRBY",
+                key.as_bytes(),
+                name.as_bytes(),
+                amount.to_string().as_bytes(),
+            ])?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn urls_are_read() {
+        assert_eq!(
+            RedisUrl::parse("redis://:secret@cache.internal:6380/2").unwrap(),
+            RedisUrl {
+                host: "cache.internal".to_string(),
+                port: 6380,
+                username: None,
+                password: Some("secret".to_string()),
+                database: 2,
+            }
+        );
+        let url = RedisUrl::parse("redis://localhost").unwrap();
+        assert_eq!(url.port, DEFAULT_PORT);
+        assert_eq!(url.database, 0);
+        assert!(RedisUrl::parse("http://localhost").is_err());
+        assert!(RedisUrl::parse("redis://localhost:port").is_err());
+    }
+
+    #[test]
+    fn commands_are_encoded_as_arrays_of_strings() {
+        assert_eq!(
+            encode_command(&[b"SET", b"key", b"two words"]),
+            b"*3\r\n$3\r\nSET\r\n$3\r\nkey\r\n$9\r\ntwo words\r\n"
+        );
+    }
+
+    #[test]
+    fn replies_are_read() {
+        let mut replies: &[u8] =
+            b"+OK\r\n:42\r\n$-1\r\n$5\r\nhe\r\nl\r\n*2\r\n$1\r\na\r\n$1\r\nb\r\n-ERR wrong\r\n";
+        assert_eq!(
+            read_reply(&mut replies).unwrap(),
+            Reply::Status("OK".to_string())
+        );
+        assert_eq!(read_reply(&mut replies).unwrap(), Reply::Integer(42));
+        assert_eq!(read_reply(&mut replies).unwrap(), Reply::Bulk(None));
+        assert_eq!(
+            read_reply(&mut replies).unwrap().into_string().unwrap(),
+            "he\r\nl"
+        );
+        assert_eq!(read_reply(&mut replies).unwrap().into_hash()["a"], "b");
+        assert!(read_reply(&mut replies).is_err());
+    }
+}
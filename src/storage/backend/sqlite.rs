@@ -0,0 +1,105 @@
+//! The default backend, the SQLite database in [`crate::storage::database`]
+
+use super::{Storage, StorageError};
+use crate::checkers::checker_result::CheckResult;
+use crate::storage::database::{
+    self, CacheEntry, CacheRow, CheckpointRow, DecoderStatsRow, EdgeEntry, EdgeRow, SettingRow,
+};
+
+/// Keeps everything in the SQLite database at `database_path`
+/// Which file that is, or whether an in-memory database is used instead, is chosen with
+/// [`database::use_database_path`] and [`database::disable_database`].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SqliteStorage;
+
+impl Storage for SqliteStorage {
+    fn name(&self) -> &'static str {
+        "sqlite"
+    }
+
+    fn setup(&self) -> Result<(), StorageError> {
+        Ok(database::setup_database()?)
+    }
+
+    fn read_cache(&self, encoded_text: &str) -> Result<Option<CacheRow>, StorageError> {
+        Ok(database::read_cache(encoded_text)?)
+    }
+
+    fn insert_cache(&self, entry: &CacheEntry) -> Result<(), StorageError> {
+        database::insert_cache(entry)?;
+        Ok(())
+    }
+
+    fn read_edges(&self, encoded_text: &str, settings: &str) -> Result<Vec<EdgeRow>, StorageError> {
+        Ok(database::read_edges(encoded_text, settings)?)
+    }
+
+    fn insert_edges(&self, edges: &[EdgeEntry]) -> Result<(), StorageError> {
+        database::insert_edges(edges)?;
+        Ok(())
+    }
+
+    fn read_rejected_plaintexts(&self) -> Result<Vec<String>, StorageError> {
+        Ok(database::read_rejected_plaintexts()?)
+    }
+
+    fn insert_rejection(
+        &self,
+        plaintext: &str,
+        check_result: &CheckResult,
+    ) -> Result<(), StorageError> {
+        database::insert_human_rejection(uuid::Uuid::new_v4(), plaintext, check_result)?;
+        Ok(())
+    }
+
+    fn read_confirmed_plaintexts(&self) -> Result<Vec<String>, StorageError> {
+        Ok(database::read_confirmed_plaintexts()?)
+    }
+
+    fn insert_confirmation(
+        &self,
+        plaintext: &str,
+        check_result: &CheckResult,
+    ) -> Result<(), StorageError> {
+        database::insert_confirmed_plaintext(uuid::Uuid::new_v4(), plaintext, check_result)?;
+        Ok(())
+    }
+
+    fn read_settings(&self) -> Result<Vec<SettingRow>, StorageError> {
+        Ok(database::read_settings()?)
+    }
+
+    fn read_decoder_stats(&self) -> Result<Vec<DecoderStatsRow>, StorageError> {
+        Ok(database::read_decoder_stats()?)
+    }
+
+    fn add_decoder_stats(
+        &self,
+        decoder: &str,
+        attempts: i64,
+        successes: i64,
+        runtime_micros: i64,
+    ) -> Result<(), StorageError> {
+        database::add_decoder_stats(decoder, attempts, successes, runtime_micros)?;
+        Ok(())
+    }
+
+    fn read_checkpoint(&self, encoded_text: &str) -> Result<Option<CheckpointRow>, StorageError> {
+        Ok(database::read_checkpoint(encoded_text)?)
+    }
+
+    fn upsert_checkpoint(&self, encoded_text: &str, frontier: &str) -> Result<(), StorageError> {
+        database::upsert_checkpoint(encoded_text, frontier)?;
+        Ok(())
+    }
+
+    fn delete_checkpoint(&self, encoded_text: &str) -> Result<(), StorageError> {
+        database::delete_checkpoint(encoded_text)?;
+        Ok(())
+    }
+
+    fn add_counters(&self, counters: &[(&str, i64)]) -> Result<(), StorageError> {
+        database::add_counters(counters)?;
+        Ok(())
+    }
+}
@@ -7,12 +7,175 @@
 use super::super::CheckResult;
 use super::super::CrackResult;
 use chrono::DateTime;
-use std::sync::OnceLock;
+use serde::{Deserialize, Serialize};
+use std::ops::{Deref, DerefMut};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock, PoisonError};
+use std::time::Duration;
 use uuid::Uuid;
 
 /// Holds the global path to the database
 pub static DB_PATH: OnceLock<Option<std::path::PathBuf>> = OnceLock::new();
 
+/// Set once the database file turned out to be unusable, or was turned off, and an in-memory
+/// database is used instead
+static FALLBACK: OnceLock<FallbackDatabase> = OnceLock::new();
+
+/// Every connection to this URI shares the same in-memory database
+const FALLBACK_URI: &str = "file:ciphey_fallback?mode=memory&cache=shared";
+
+/// How many connections are kept open for reuse once they are no longer in use
+const MAX_IDLE_CONNECTIONS: usize = 8;
+
+/// How many compiled statements each connection keeps for reuse
+const STATEMENT_CACHE_CAPACITY: usize = 64;
+
+/// How long a connection waits for another one to finish writing before giving up
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How many results the cache keeps when the config doesn't say otherwise
+pub const DEFAULT_CACHE_MAX_ENTRIES: usize = 10_000;
+
+/// How many decodes the edges table keeps when the config doesn't say otherwise
+pub const DEFAULT_EDGE_CACHE_MAX_ENTRIES: usize = 1_000_000;
+
+/// How many days unused cache rows are kept when the config doesn't say otherwise
+pub const DEFAULT_CACHE_MAX_AGE_DAYS: u32 = 90;
+
+/// The limits inserts into the cache and edges tables evict rows to stay within
+/// Nothing is evicted until [`set_cache_limits`] is called.
+static CACHE_LIMITS: Mutex<CacheLimits> = Mutex::new(CacheLimits {
+    max_entries: 0,
+    max_edges: 0,
+    max_age_days: 0,
+});
+
+/// How much the cache and edges tables keep, where 0 is no limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheLimits {
+    /// The most rows the cache table keeps
+    pub max_entries: usize,
+    /// The most rows the edges table keeps
+    pub max_edges: usize,
+    /// How many days rows are kept without being used
+    pub max_age_days: u32,
+}
+
+impl CacheLimits {
+    /// The limits set in the config
+    pub fn from_config(config: &crate::config::Config) -> Self {
+        CacheLimits {
+            max_entries: config.cache_max_entries,
+            max_edges: config.edge_cache_max_entries,
+            max_age_days: config.cache_max_age_days,
+        }
+    }
+}
+
+/// The version of ciphey which cached a result, stored with it
+/// Results cached by another version are misses, as its decoders may have decoded
+/// differently.
+pub const CACHE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Fingerprint of the decoders and checkers which ran, stored with every cached result
+/// Results cached when a different set ran are misses. Set with [`set_registry_fingerprint`].
+static REGISTRY_FINGERPRINT: Mutex<String> = Mutex::new(String::new());
+
+/// Sets the fingerprint of the decoders and checkers stored with cached results
+/// Only results cached with the same fingerprint and by this version of ciphey are read
+/// back, so adding or toggling decoders doesn't keep answering from results found
+/// without them.
+pub fn set_registry_fingerprint(fingerprint: String) {
+    *REGISTRY_FINGERPRINT
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner) = fingerprint;
+}
+
+/// The fingerprint set with [`set_registry_fingerprint`]
+pub fn registry_fingerprint() -> String {
+    REGISTRY_FINGERPRINT
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .clone()
+}
+
+/// How many rows were removed from the cache and edges tables
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct PrunedRows {
+    /// Rows removed from the cache table
+    pub results: usize,
+    /// Rows removed from the edges table
+    pub edges: usize,
+}
+
+/// The in-memory database used for the rest of the run when the database file can't be used
+struct FallbackDatabase {
+    /// Why the database file couldn't be used, None if it was turned off
+    reason: Option<String>,
+    /// A shared in-memory database is dropped when its last connection closes,
+    /// so one is held open for as long as the program runs
+    _keepalive: Mutex<rusqlite::Connection>,
+}
+
+/// Connections kept open between calls, so each call doesn't pay for opening the database
+/// and compiling its statements again
+struct ConnectionPool {
+    /// The database the idle connections are connected to
+    target: Option<PathBuf>,
+    /// Connections which aren't in use
+    idle: Vec<rusqlite::Connection>,
+}
+
+/// The idle connections to the database in use
+static POOL: Mutex<ConnectionPool> = Mutex::new(ConnectionPool {
+    target: None,
+    idle: Vec::new(),
+});
+
+/// A connection taken from the pool, which goes back to it when dropped
+/// Each thread takes its own, so calls on different threads don't wait for each other
+/// except while one of them is writing.
+struct PooledConnection {
+    /// Only None while it is being dropped
+    conn: Option<rusqlite::Connection>,
+    /// The database it is connected to, None for a private in-memory one
+    target: Option<PathBuf>,
+}
+
+impl Deref for PooledConnection {
+    type Target = rusqlite::Connection;
+
+    fn deref(&self) -> &rusqlite::Connection {
+        self.conn
+            .as_ref()
+            .expect("Pooled connections are only taken when dropped")
+    }
+}
+
+impl DerefMut for PooledConnection {
+    fn deref_mut(&mut self) -> &mut rusqlite::Connection {
+        self.conn
+            .as_mut()
+            .expect("Pooled connections are only taken when dropped")
+    }
+}
+
+impl Drop for PooledConnection {
+    fn drop(&mut self) {
+        let (Some(conn), Some(target)) = (self.conn.take(), self.target.take()) else {
+            return;
+        };
+        // A transaction left open would be carried into the next use
+        if !conn.is_autocommit() {
+            return;
+        }
+        let mut pool = POOL.lock().unwrap_or_else(PoisonError::into_inner);
+        if pool.target.as_ref() == Some(&target) && pool.idle.len() < MAX_IDLE_CONNECTIONS {
+            pool.idle.push(conn);
+        }
+    }
+}
+
 #[derive(Debug)]
 /// Struct representing a row in the human_rejection table
 pub struct HumanRejectionRow {
@@ -52,6 +215,12 @@ pub struct CacheRow {
     pub execution_time_ms: i64,
     /// When the decoding was run
     pub timestamp: String,
+    /// Name of the checker that accepted the decoded text, empty if none did
+    pub checker: String,
+    /// Sensitivity the accepting checker ran with, such as "Low"
+    pub sensitivity: Option<String>,
+    /// How confident the accepting checker was, from 0.0 to 1.0
+    pub confidence: f32,
 }
 
 impl PartialEq for CacheRow {
@@ -63,9 +232,171 @@ impl PartialEq for CacheRow {
             && self.successful == other.successful
             && self.execution_time_ms == other.execution_time_ms
             && self.timestamp == other.timestamp
+            && self.checker == other.checker
+            && self.sensitivity == other.sensitivity
+            && self.confidence == other.confidence
     }
 }
 
+#[derive(Debug, PartialEq)]
+/// Struct representing a row in the settings table
+/// Each row is the enable state a user chose for a single decoder or checker
+pub struct SettingRow {
+    /// What is being toggled, either "decoder" or "checker"
+    pub kind: String,
+    /// Name of the decoder or checker as it appears in the registry
+    pub name: String,
+    /// Whether the decoder or checker is enabled
+    pub enabled: bool,
+    /// When the setting was last changed
+    pub timestamp: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+/// Struct representing a row in the decoder_stats table
+/// Each row is how a single decoder has fared over every search the database remembers
+pub struct DecoderStatsRow {
+    /// Name of the decoder as it appears in the registry
+    pub decoder: String,
+    /// How many times the decoder was run
+    pub attempts: i64,
+    /// How many of those decodes were on the way to a plaintext
+    pub successes: i64,
+    /// How long all of those decodes took together, in microseconds
+    pub runtime_micros: i64,
+    /// When the statistics were last updated
+    pub timestamp: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+/// A row of the cache table as it is exported and imported
+/// Unlike [`CacheRow`] it keeps which version of ciphey cached it and with which decoders,
+/// so imported results are only used where they would have been found.
+pub struct ExportedCacheRow {
+    /// Uuid of the cache entry
+    pub uuid: String,
+    /// Text before it is decoded
+    pub encoded_text: String,
+    /// Text after it is decoded
+    pub decoded_text: String,
+    /// The decoding path, as the JSON it is stored as
+    pub path: String,
+    /// Whether or not the decoding was successful
+    pub successful: bool,
+    /// How long the decoding took in milliseconds
+    pub execution_time_ms: i64,
+    /// When the decoding was run
+    pub timestamp: String,
+    /// Name of the checker that accepted the decoded text, empty if none did
+    pub checker: String,
+    /// Sensitivity the accepting checker ran with, such as "Low"
+    pub sensitivity: Option<String>,
+    /// How confident the accepting checker was, from 0.0 to 1.0
+    pub confidence: f32,
+    /// The version of ciphey which cached it
+    pub version: String,
+    /// Fingerprint of the decoders and checkers which ran when it was cached
+    pub registry: String,
+}
+
+#[derive(Debug, PartialEq, serde::Serialize)]
+/// How many rows of a table share a value, such as the results whose first decoder was Base64
+pub struct CountRow {
+    /// The value the rows share
+    pub name: String,
+    /// How many rows have it
+    pub count: i64,
+}
+
+#[derive(Debug, PartialEq)]
+/// Struct representing a row in the checkpoints table
+/// Each row is where a search of a text which ran out of time got to, so it can be resumed
+pub struct CheckpointRow {
+    /// Text the search was decoding
+    pub encoded_text: String,
+    /// The search's frontier and the decodes it had seen, as JSON
+    pub frontier: String,
+    /// When the checkpoint was saved
+    pub timestamp: String,
+}
+
+#[derive(Debug, PartialEq)]
+/// Struct representing a row in the edges table
+/// Each row is what one decoder made of a text, met anywhere in a search, so later
+/// searches which come across the same text don't run the decoder on it again
+pub struct EdgeRow {
+    /// Text the decoder was run on
+    pub encoded_text: String,
+    /// Name of the decoder
+    pub decoder: String,
+    /// Fingerprint of the settings the decoder ran with
+    pub settings: String,
+    /// What the decoder returned, as CrackResult JSON
+    pub result: String,
+    /// When the decoder was run
+    pub timestamp: String,
+}
+
+#[derive(Debug)]
+/// Represents an entry into the edges table
+pub struct EdgeEntry {
+    /// Text the decoder was run on
+    pub encoded_text: String,
+    /// Name of the decoder
+    pub decoder: String,
+    /// Fingerprint of the settings the decoder ran with
+    pub settings: String,
+    /// What the decoder returned, as CrackResult JSON
+    pub result: String,
+}
+
+#[derive(Debug, PartialEq)]
+/// Struct representing a row in the runs table
+/// Each row is one text ciphey was asked to crack and what became of it
+pub struct RunRow {
+    /// Number of the run, counting up from 1
+    pub id: i64,
+    /// Text the run was asked to crack
+    pub input: String,
+    /// How the run ended, such as "solved" or "cached"
+    pub outcome: String,
+    /// The plaintext found, None if none was
+    pub plaintext: Option<String>,
+    /// Ordered list of decoding attempts to the plaintext, as CrackResult JSON
+    pub path: Vec<String>,
+    /// How many times each decoder ran and for how long, as JSON
+    pub decoder_timings: String,
+    /// The config the run used, as JSON
+    pub config: String,
+    /// How long the run took in milliseconds
+    pub duration_ms: i64,
+    /// The version of ciphey which ran it
+    pub version: String,
+    /// When the run finished
+    pub timestamp: String,
+}
+
+#[derive(Debug)]
+/// Represents an entry into the runs table
+pub struct RunEntry {
+    /// Text the run was asked to crack
+    pub input: String,
+    /// How the run ended, such as "solved" or "cached"
+    pub outcome: String,
+    /// The plaintext found, None if none was
+    pub plaintext: Option<String>,
+    /// Ordered list of decoding attempts to the plaintext
+    pub path: Vec<CrackResult>,
+    /// How many times each decoder ran and for how long, as JSON
+    pub decoder_timings: String,
+    /// The config the run used, as JSON
+    pub config: String,
+    /// How long the run took in milliseconds
+    pub duration_ms: i64,
+}
+
 #[derive(Debug)]
 /// Represents an entry into the cache table
 pub struct CacheEntry {
@@ -79,35 +410,204 @@ pub struct CacheEntry {
     pub path: Vec<CrackResult>,
     /// How long the decoding took in milliseconds
     pub execution_time_ms: i64,
+    /// Name of the checker that accepted the decoded text, empty if none did
+    pub checker: String,
+    /// Sensitivity the accepting checker ran with, such as "Low"
+    pub sensitivity: Option<String>,
+    /// How confident the accepting checker was, from 0.0 to 1.0
+    pub confidence: f32,
+}
+
+impl CacheEntry {
+    /// Fills in which checker accepted the result from the last step of the path
+    pub fn with_provenance_from_path(mut self) -> Self {
+        if let Some(accepted) = self.path.last() {
+            self.checker = accepted.checker_name.to_string();
+            self.sensitivity = accepted
+                .checker_sensitivity
+                .map(|sensitivity| format!("{:?}", sensitivity));
+            self.confidence = accepted.confidence;
+        }
+        self
+    }
+
+    /// The row this entry is stored as, stamped with this version of ciphey and the
+    /// decoders and checkers which are running
+    pub fn to_exported_row(&self) -> ExportedCacheRow {
+        let path: Vec<String> = self
+            .path
+            .iter()
+            .map(|crack_result| crack_result.get_json().unwrap_or_default())
+            .collect();
+        ExportedCacheRow {
+            uuid: self.uuid.to_string(),
+            encoded_text: self.encoded_text.clone(),
+            decoded_text: self.decoded_text.clone(),
+            path: serde_json::to_string(&path).unwrap_or_default(),
+            successful: self.path.last().is_some_and(|last| last.success),
+            execution_time_ms: self.execution_time_ms,
+            timestamp: get_timestamp(),
+            checker: self.checker.clone(),
+            sensitivity: self.sensitivity.clone(),
+            confidence: self.confidence,
+            version: CACHE_VERSION.to_string(),
+            registry: registry_fingerprint(),
+        }
+    }
+}
+
+impl From<ExportedCacheRow> for CacheRow {
+    fn from(row: ExportedCacheRow) -> Self {
+        CacheRow {
+            uuid: Uuid::parse_str(&row.uuid).unwrap_or_default(),
+            encoded_text: row.encoded_text,
+            decoded_text: row.decoded_text,
+            path: serde_json::from_str(&row.path).unwrap_or_default(),
+            successful: row.successful,
+            execution_time_ms: row.execution_time_ms,
+            timestamp: row.timestamp,
+            checker: row.checker,
+            sensitivity: row.sensitivity,
+            confidence: row.confidence,
+        }
+    }
 }
 
 /// Helper function get a DateTime formatted timestamp
-fn get_timestamp() -> String {
+pub fn get_timestamp() -> String {
     let timestamp: DateTime<chrono::Local> = std::time::SystemTime::now().into();
     timestamp.format("%Y-%m-%d %T").to_string()
 }
 
-/// Returns the path to the database file
-fn get_database_path() -> std::path::PathBuf {
+/// Uses the database at this path instead of the default one
+///
+/// Has to be called before [`setup_database`], and does nothing if a path has already been chosen.
+/// Creates the folder the database goes in if it doesn't exist yet.
+pub fn use_database_path(path: std::path::PathBuf) {
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        if let Err(e) = std::fs::create_dir_all(parent) {
+            log::warn!(
+                "Could not create the database folder {}: {}",
+                parent.display(),
+                e
+            );
+        }
+    }
+    let _ = DB_PATH.set(Some(path));
+}
+
+/// Keeps everything in memory for the rest of the run, so nothing is read from or written to
+/// the database file
+///
+/// Has to be called before [`setup_database`], and does nothing if a path has already been chosen.
+pub fn disable_database() {
+    let _ = DB_PATH.set(None);
+}
+
+/// Whether [`disable_database`] turned the database file off
+pub fn database_disabled() -> bool {
+    matches!(DB_PATH.get(), Some(None))
+}
+
+/// The database file chosen with [`use_database_path`] or [`setup_database`], None if none has
+/// been chosen yet or the database is turned off
+pub fn chosen_database_path() -> Option<std::path::PathBuf> {
+    DB_PATH.get().cloned().flatten()
+}
+
+/// Returns the path to the default database file, `~/.ciphey/database.sqlite`
+///
+/// # Panics
+/// Panics if the home directory can't be found.
+pub fn get_database_path() -> std::path::PathBuf {
     let mut path = dirs::home_dir().expect("Could not find home directory");
     path.push(".ciphey");
     path.push("database.sqlite");
     path
 }
 
-/// Opens and returns a Connection to the SQLite database
+/// The database connections go to, None for a private in-memory database
+fn connection_target() -> Option<PathBuf> {
+    if FALLBACK.get().is_some() {
+        return Some(PathBuf::from(FALLBACK_URI));
+    }
+    chosen_database_path()
+}
+
+/// Returns a Connection to the SQLite database, reusing an idle one if there is one
 ///
 /// If a path is specified in DB_PATH, returns a Connection to that path
 /// Otherwise, opens a Connection to an in-memory database
-fn get_db_connection() -> Result<rusqlite::Connection, rusqlite::Error> {
-    match DB_PATH.get() {
-        Some(Some(path)) => rusqlite::Connection::open(path),
-        _ => rusqlite::Connection::open_in_memory(),
+fn get_db_connection() -> Result<PooledConnection, rusqlite::Error> {
+    let target = connection_target();
+    if let Some(target) = &target {
+        let mut pool = POOL.lock().unwrap_or_else(PoisonError::into_inner);
+        // Connections to the database file are no use once the fallback is in use
+        if pool.target.as_ref() != Some(target) {
+            pool.idle.clear();
+            pool.target = Some(target.clone());
+        }
+        if let Some(conn) = pool.idle.pop() {
+            return Ok(PooledConnection {
+                conn: Some(conn),
+                target: Some(target.clone()),
+            });
+        }
+    }
+    let conn = match &target {
+        Some(target) => open_connection(target)?,
+        None => rusqlite::Connection::open_in_memory()?,
+    };
+    Ok(PooledConnection {
+        conn: Some(conn),
+        target,
+    })
+}
+
+/// Opens a new connection, in write-ahead logging mode so reading never waits for writing
+fn open_connection(target: &Path) -> Result<rusqlite::Connection, rusqlite::Error> {
+    let conn = rusqlite::Connection::open(target)?;
+    conn.set_prepared_statement_cache_capacity(STATEMENT_CACHE_CAPACITY);
+    conn.busy_timeout(BUSY_TIMEOUT)?;
+    // A read-only database can't change its journal mode, which ensure_writable reports
+    match conn.pragma_update_and_check(None, "journal_mode", "WAL", |row| {
+        row.get::<usize, String>(0)
+    }) {
+        Ok(mode) if mode.eq_ignore_ascii_case("wal") => {
+            conn.pragma_update(None, "synchronous", "NORMAL")?;
+        }
+        Ok(_) => {}
+        Err(e) => log::debug!("Could not use write-ahead logging: {}", e),
     }
+    Ok(conn)
+}
+
+/// Closes the idle connections, such as before the database file is removed or replaced
+pub fn close_idle_connections() {
+    POOL.lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .idle
+        .clear();
+}
+
+/// Runs a statement, compiling it only the first time a connection runs it
+fn execute_cached<P: rusqlite::Params>(
+    conn: &rusqlite::Connection,
+    sql: &str,
+    params: P,
+) -> Result<usize, rusqlite::Error> {
+    conn.prepare_cached(sql)?.execute(params)
 }
 
 /// Public wrapper for setting up database
 ///
+/// If the database file can't be opened, written to or read, the rest of the run
+/// uses an in-memory database instead. [`fallback_reason`] says why.
+/// After [`disable_database`] the in-memory database is used without trying the file.
+///
 /// # Errors
 ///
 /// On error setting up the database, returns a rusqlite::Error
@@ -127,13 +627,74 @@ pub fn setup_database() -> Result<(), rusqlite::Error> {
             }
         }
     };
+    if database_disabled() {
+        return use_memory(None);
+    }
+    // A config file kept outside ~/.ciphey no longer makes the directory, so make it here
+    if let Some(directory) =
+        chosen_database_path().and_then(|path| path.parent().map(PathBuf::from))
+    {
+        let _ = std::fs::create_dir_all(directory);
+    }
+    if let Err(e) = init_database().and_then(|conn| ensure_writable(&conn)) {
+        let path = chosen_database_path().map_or_else(
+            || String::from("in memory"),
+            |path| path.display().to_string(),
+        );
+        use_memory(Some(format!(
+            "Could not use the database at {}: {}",
+            path, e
+        )))?;
+    }
+    Ok(())
+}
+
+/// Switches every later connection over to a shared in-memory database
+/// The reason is None when the database was turned off rather than found unusable.
+fn use_memory(reason: Option<String>) -> Result<(), rusqlite::Error> {
+    if FALLBACK.get().is_some() {
+        return Ok(());
+    }
+    let keepalive = rusqlite::Connection::open(FALLBACK_URI)?;
+    let _ = FALLBACK.set(FallbackDatabase {
+        reason,
+        _keepalive: Mutex::new(keepalive),
+    });
     init_database()?;
     Ok(())
 }
 
+/// Why the database file isn't being used, None if it is or it was turned off
+pub fn fallback_reason() -> Option<&'static str> {
+    FALLBACK
+        .get()
+        .and_then(|fallback| fallback.reason.as_deref())
+}
+
+/// Fails if the connection can only read, such as when the database file is read-only
+fn ensure_writable(conn: &rusqlite::Connection) -> Result<(), rusqlite::Error> {
+    if conn.is_readonly(rusqlite::DatabaseName::Main)? {
+        return Err(rusqlite::Error::SqliteFailure(
+            rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_READONLY),
+            Some(String::from("the database file is read-only")),
+        ));
+    }
+    Ok(())
+}
+
 /// Initializes database with default schema
-fn init_database() -> Result<rusqlite::Connection, rusqlite::Error> {
+fn init_database() -> Result<PooledConnection, rusqlite::Error> {
     let conn = get_db_connection()?;
+    create_schema(&conn)?;
+    Ok(conn)
+}
+
+/// Creates every table and index which doesn't exist yet
+///
+/// # Errors
+///
+/// Returns rusqlite::Error if the database can't be read or written
+pub fn create_schema(conn: &rusqlite::Connection) -> Result<(), rusqlite::Error> {
     // Initializing cache table
     conn.execute(
         "CREATE TABLE IF NOT EXISTS cache (
@@ -143,10 +704,29 @@ fn init_database() -> Result<rusqlite::Connection, rusqlite::Error> {
             path JSON NOT NULL,
             successful BOOLEAN NOT NULL DEFAULT true,
             execution_time_ms INTEGER NOT NULL,
-            timestamp DATETIME DEFAULT CURRENT_TIMESTAMP
+            timestamp DATETIME DEFAULT CURRENT_TIMESTAMP,
+            checker TEXT NOT NULL DEFAULT '',
+            sensitivity TEXT,
+            confidence REAL NOT NULL DEFAULT 0,
+            last_used DATETIME,
+            version TEXT NOT NULL DEFAULT '',
+            registry TEXT NOT NULL DEFAULT ''
     );",
         (),
     )?;
+    // Databases made before results recorded who accepted them need the new columns
+    add_missing_columns(
+        conn,
+        "cache",
+        &[
+            ("checker", "TEXT NOT NULL DEFAULT ''"),
+            ("sensitivity", "TEXT"),
+            ("confidence", "REAL NOT NULL DEFAULT 0"),
+            ("last_used", "DATETIME"),
+            ("version", "TEXT NOT NULL DEFAULT ''"),
+            ("registry", "TEXT NOT NULL DEFAULT ''"),
+        ],
+    )?;
     conn.execute(
         "CREATE INDEX IF NOT EXISTS idx_cache_encoded_text
             ON cache(encoded_text);",
@@ -168,7 +748,122 @@ fn init_database() -> Result<rusqlite::Connection, rusqlite::Error> {
         (),
     )?;
 
-    Ok(conn)
+    // Initializing the table of plaintexts the human checker confirmed
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS confirmed_plaintexts (
+            uuid TEXT PRIMARY KEY NOT NULL,
+            plaintext TEXT NOT NULL,
+            checker TEXT NOT NULL,
+            timestamp DATETIME DEFAULT CURRENT_TIMESTAMP
+    );",
+        (),
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_confirmed_plaintext
+            ON confirmed_plaintexts(plaintext);",
+        (),
+    )?;
+
+    // Initializing settings table
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS settings (
+            kind TEXT NOT NULL,
+            name TEXT NOT NULL,
+            enabled BOOLEAN NOT NULL,
+            timestamp DATETIME DEFAULT CURRENT_TIMESTAMP,
+            PRIMARY KEY (kind, name)
+    );",
+        (),
+    )?;
+
+    // Initializing decoder statistics table
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS decoder_stats (
+            decoder TEXT PRIMARY KEY NOT NULL,
+            attempts INTEGER NOT NULL DEFAULT 0,
+            successes INTEGER NOT NULL DEFAULT 0,
+            runtime_micros INTEGER NOT NULL DEFAULT 0,
+            timestamp DATETIME DEFAULT CURRENT_TIMESTAMP
+    );",
+        (),
+    )?;
+
+    // Initializing search checkpoints table
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS checkpoints (
+            encoded_text TEXT PRIMARY KEY NOT NULL,
+            frontier JSON NOT NULL,
+            timestamp DATETIME DEFAULT CURRENT_TIMESTAMP
+    );",
+        (),
+    )?;
+
+    // Initializing decoder edges table
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS edges (
+            encoded_text TEXT NOT NULL,
+            decoder TEXT NOT NULL,
+            settings TEXT NOT NULL,
+            result JSON NOT NULL,
+            timestamp DATETIME DEFAULT CURRENT_TIMESTAMP,
+            PRIMARY KEY (encoded_text, decoder, settings)
+    );",
+        (),
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_edges_timestamp ON edges(timestamp);",
+        (),
+    )?;
+
+    // Initializing run history table
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS runs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            input TEXT NOT NULL,
+            outcome TEXT NOT NULL,
+            plaintext TEXT,
+            path JSON NOT NULL,
+            decoder_timings JSON NOT NULL,
+            config JSON NOT NULL,
+            duration_ms INTEGER NOT NULL,
+            version TEXT NOT NULL,
+            timestamp DATETIME DEFAULT CURRENT_TIMESTAMP
+    );",
+        (),
+    )?;
+
+    // Initializing counters table
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS counters (
+            name TEXT PRIMARY KEY NOT NULL,
+            value INTEGER NOT NULL DEFAULT 0,
+            timestamp DATETIME DEFAULT CURRENT_TIMESTAMP
+    );",
+        (),
+    )?;
+
+    Ok(())
+}
+
+/// Adds each column the table doesn't have yet, so older databases keep working
+fn add_missing_columns(
+    conn: &rusqlite::Connection,
+    table: &str,
+    columns: &[(&str, &str)],
+) -> Result<(), rusqlite::Error> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({table});"))?;
+    let existing: Vec<String> = stmt
+        .query_map([], |row| row.get::<usize, String>(1))?
+        .collect::<Result<_, _>>()?;
+    for (name, definition) in columns {
+        if !existing.iter().any(|column| column == name) {
+            conn.execute(
+                &format!("ALTER TABLE {table} ADD COLUMN {name} {definition};"),
+                (),
+            )?;
+        }
+    }
+    Ok(())
 }
 
 /// Adds a new cache record to the cache table
@@ -196,9 +891,18 @@ pub fn insert_cache(cache_entry: &CacheEntry) -> Result<usize, rusqlite::Error>
     };
 
     let path_json = serde_json::to_string(&path).unwrap();
+    let registry = registry_fingerprint();
     let mut conn = get_db_connection()?;
     let transaction = conn.transaction()?;
-    let conn_result = transaction.execute(
+    // Results cached by another version or decoder set are never read again, so they are
+    // replaced rather than kept alongside
+    execute_cached(
+        &transaction,
+        "DELETE FROM cache WHERE encoded_text = $1 AND (version IS NOT $2 OR registry IS NOT $3)",
+        (&cache_entry.encoded_text, CACHE_VERSION, &registry),
+    )?;
+    let conn_result = execute_cached(
+        &transaction,
         "INSERT INTO cache (
             uuid,
             encoded_text,
@@ -206,8 +910,13 @@ pub fn insert_cache(cache_entry: &CacheEntry) -> Result<usize, rusqlite::Error>
             path,
             successful,
             execution_time_ms,
-            timestamp)
-            VALUES ($1, $2, $3, $4, $5, $6, $7)",
+            timestamp,
+            checker,
+            sensitivity,
+            confidence,
+            version,
+            registry)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)",
         (
             cache_entry.uuid.to_string(),
             cache_entry.encoded_text.clone(),
@@ -216,14 +925,21 @@ pub fn insert_cache(cache_entry: &CacheEntry) -> Result<usize, rusqlite::Error>
             successful,
             cache_entry.execution_time_ms,
             get_timestamp(),
+            cache_entry.checker.clone(),
+            cache_entry.sensitivity.clone(),
+            cache_entry.confidence,
+            CACHE_VERSION,
+            registry,
         ),
     );
+    evict(&transaction, &current_cache_limits())?;
     transaction.commit()?;
     conn_result
 }
 
 /// Searches the database for a cache table row that matches the given encoded
 /// text
+/// Rows cached by another version of ciphey or with other decoders and checkers are misses.
 ///
 /// On cache hit, returns a CacheRow
 /// On cache miss, returns None
@@ -231,10 +947,13 @@ pub fn insert_cache(cache_entry: &CacheEntry) -> Result<usize, rusqlite::Error>
 /// # Errors
 ///
 /// Returns a ``rusqlite::Error``
-pub fn read_cache(encoded_text: &String) -> Result<Option<CacheRow>, rusqlite::Error> {
+pub fn read_cache(encoded_text: &str) -> Result<Option<CacheRow>, rusqlite::Error> {
     let conn = get_db_connection()?;
-    let mut stmt = conn.prepare("SELECT * FROM cache WHERE encoded_text IS $1")?;
-    let mut query = stmt.query_map([encoded_text], |row| {
+    let mut stmt = conn.prepare_cached(
+        "SELECT * FROM cache WHERE encoded_text IS $1 AND version IS $2 AND registry IS $3",
+    )?;
+    let registry = registry_fingerprint();
+    let mut query = stmt.query_map([encoded_text, CACHE_VERSION, &registry], |row| {
         let path_str = row.get_unwrap::<usize, String>(3).to_owned();
         let crack_json_vec: Vec<String> =
             serde_json::from_str(&path_str.clone()).unwrap_or_default();
@@ -247,11 +966,23 @@ pub fn read_cache(encoded_text: &String) -> Result<Option<CacheRow>, rusqlite::E
             successful: row.get_unwrap(4),
             execution_time_ms: row.get_unwrap(5),
             timestamp: row.get_unwrap(6),
+            checker: row.get_unwrap(7),
+            sensitivity: row.get_unwrap(8),
+            confidence: row.get_unwrap(9),
         })
     })?;
     let row = query.next();
     match row {
-        Some(cache_row) => Ok(Some(cache_row?)),
+        Some(cache_row) => {
+            let cache_row = cache_row?;
+            // Marks the row as used, so it is among the last to be evicted
+            execute_cached(
+                &conn,
+                "UPDATE cache SET last_used = $1 WHERE uuid = $2",
+                (get_timestamp(), cache_row.uuid.to_string()),
+            )?;
+            Ok(Some(cache_row))
+        }
         None => Ok(None),
     }
 }
@@ -266,7 +997,8 @@ pub fn read_cache(encoded_text: &String) -> Result<Option<CacheRow>, rusqlite::E
 pub fn delete_cache(encoded_text: &str) -> Result<usize, rusqlite::Error> {
     let mut conn = get_db_connection()?;
     let transaction = conn.transaction()?;
-    let conn_result = transaction.execute(
+    let conn_result = execute_cached(
+        &transaction,
         "DELETE FROM cache WHERE encoded_text = $1",
         (encoded_text.to_owned(),),
     );
@@ -298,15 +1030,21 @@ pub fn update_cache(cache_entry: &CacheEntry) -> Result<usize, rusqlite::Error>
     let path_json = serde_json::to_string(&path).unwrap_or_default();
     let mut conn = get_db_connection()?;
     let transaction = conn.transaction()?;
-    let conn_result = transaction.execute(
+    let conn_result = execute_cached(
+        &transaction,
         "UPDATE cache SET 
             uuid = $1,
             decoded_text = $2,
             path = $3,
             successful = $4,
             execution_time_ms = $5,
-            timestamp = $6
-            WHERE encoded_text = $7;",
+            timestamp = $6,
+            checker = $7,
+            sensitivity = $8,
+            confidence = $9,
+            version = $10,
+            registry = $11
+            WHERE encoded_text = $12;",
         (
             cache_entry.uuid.to_string(),
             cache_entry.decoded_text.clone(),
@@ -314,6 +1052,11 @@ pub fn update_cache(cache_entry: &CacheEntry) -> Result<usize, rusqlite::Error>
             successful,
             cache_entry.execution_time_ms,
             get_timestamp(),
+            cache_entry.checker.clone(),
+            cache_entry.sensitivity.clone(),
+            cache_entry.confidence,
+            CACHE_VERSION,
+            registry_fingerprint(),
             cache_entry.encoded_text.clone(),
         ),
     );
@@ -321,113 +1064,890 @@ pub fn update_cache(cache_entry: &CacheEntry) -> Result<usize, rusqlite::Error>
     conn_result
 }
 
-/// Adds a new decode failure record to the human_rejection table
+/// Adds a new decode failure record to the human_rejection table
+///
+/// Returns the number of successfully inserted rows on success
+///
+/// # Errors
+///
+/// Returns rusqlite::Error on error
+pub fn insert_human_rejection(
+    uuid: Uuid,
+    plaintext: &str,
+    check_result: &CheckResult,
+) -> Result<usize, rusqlite::Error> {
+    let mut conn = get_db_connection()?;
+    let transaction = conn.transaction()?;
+    let conn_result = execute_cached(
+        &transaction,
+        "INSERT INTO human_rejection (
+            uuid,
+            plaintext,
+            checker,
+            timestamp)
+        VALUES ($1, $2, $3, $4)",
+        (
+            uuid.to_string(),
+            plaintext.to_owned(),
+            check_result.checker_name,
+            get_timestamp(),
+        ),
+    );
+    transaction.commit()?;
+    conn_result
+}
+
+/// Searches the database for a human_rejection table row that matches the given plaintext
+///
+/// On match, returns a HumanRejectionRow
+/// Otherwise, returns None
+///
+/// # Errors
+///
+/// Returns a ``rusqlite::Error``
+pub fn read_human_rejection(
+    plaintext: &String,
+) -> Result<Option<HumanRejectionRow>, rusqlite::Error> {
+    let conn = get_db_connection()?;
+    let mut stmt = conn.prepare_cached("SELECT * FROM human_rejection WHERE plaintext IS $1")?;
+    let mut query = stmt.query_map([plaintext], |row| {
+        Ok(HumanRejectionRow {
+            uuid: Uuid::parse_str(row.get_unwrap::<usize, String>(0).as_str()).unwrap_or_default(),
+            plaintext: row.get_unwrap(1),
+            checker: row.get_unwrap(2),
+            timestamp: row.get_unwrap(3),
+        })
+    })?;
+    let row = query.next();
+    match row {
+        Some(cache_row) => Ok(Some(cache_row?)),
+        None => Ok(None),
+    }
+}
+
+/// Returns every plaintext the human checker has rejected, each once
+///
+/// # Errors
+///
+/// Returns a ``rusqlite::Error``
+pub fn read_rejected_plaintexts() -> Result<Vec<String>, rusqlite::Error> {
+    let conn = get_db_connection()?;
+    let mut stmt = conn.prepare_cached("SELECT DISTINCT plaintext FROM human_rejection")?;
+    let rows = stmt.query_map([], |row| row.get(0))?;
+    rows.collect()
+}
+
+/// Adds a plaintext the user confirmed with the human checker to the confirmed_plaintexts table
+///
+/// Returns the number of successfully inserted rows on success
+///
+/// # Errors
+///
+/// Returns rusqlite::Error on error
+pub fn insert_confirmed_plaintext(
+    uuid: Uuid,
+    plaintext: &str,
+    check_result: &CheckResult,
+) -> Result<usize, rusqlite::Error> {
+    let mut conn = get_db_connection()?;
+    let transaction = conn.transaction()?;
+    let conn_result = execute_cached(
+        &transaction,
+        "INSERT INTO confirmed_plaintexts (
+            uuid,
+            plaintext,
+            checker,
+            timestamp)
+        VALUES ($1, $2, $3, $4)",
+        (
+            uuid.to_string(),
+            plaintext.to_owned(),
+            check_result.checker_name,
+            get_timestamp(),
+        ),
+    );
+    transaction.commit()?;
+    conn_result
+}
+
+/// Returns every plaintext the human checker has confirmed, each once
+///
+/// # Errors
+///
+/// Returns a ``rusqlite::Error``
+pub fn read_confirmed_plaintexts() -> Result<Vec<String>, rusqlite::Error> {
+    let conn = get_db_connection()?;
+    let mut stmt = conn.prepare_cached("SELECT DISTINCT plaintext FROM confirmed_plaintexts")?;
+    let rows = stmt.query_map([], |row| row.get(0))?;
+    rows.collect()
+}
+
+/// Updates a human_rejection row for a given plaintext
+///
+/// Returns the number of update rows on success
+///
+/// # Errors
+///
+/// Returns rusqlite::Error on error
+pub fn update_human_rejection(
+    uuid: Uuid,
+    plaintext: &str,
+    check_result: &CheckResult,
+) -> Result<usize, rusqlite::Error> {
+    let mut conn = get_db_connection()?;
+    let transaction = conn.transaction()?;
+    let conn_result = execute_cached(
+        &transaction,
+        "UPDATE human_rejection SET 
+            uuid = $1,
+            checker = $2,
+            timestamp = $3
+            WHERE plaintext = $4;",
+        (
+            uuid.to_string(),
+            check_result.checker_name,
+            get_timestamp(),
+            plaintext.to_owned(),
+        ),
+    );
+    transaction.commit()?;
+    conn_result
+}
+
+/// Removes the human_rejection row corresponding to the given plaintext
+///
+/// Returns number of successfully deleted rows on success
+///
+/// # Errors
+///
+/// Returns sqlite::Error on error
+pub fn delete_human_rejection(plaintext: &str) -> Result<usize, rusqlite::Error> {
+    let mut conn = get_db_connection()?;
+    let transaction = conn.transaction()?;
+    let conn_result = execute_cached(
+        &transaction,
+        "DELETE FROM human_rejection WHERE plaintext = $1",
+        (plaintext.to_owned(),),
+    );
+    transaction.commit()?;
+    conn_result
+}
+
+/// Stores whether a decoder or checker is enabled, replacing any earlier setting for it
+///
+/// Returns the number of inserted or replaced rows on success
+///
+/// # Errors
+///
+/// Returns rusqlite::Error on error
+pub fn upsert_setting(kind: &str, name: &str, enabled: bool) -> Result<usize, rusqlite::Error> {
+    let mut conn = get_db_connection()?;
+    let transaction = conn.transaction()?;
+    let conn_result = execute_cached(
+        &transaction,
+        "INSERT INTO settings (
+            kind,
+            name,
+            enabled,
+            timestamp)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (kind, name) DO UPDATE SET
+            enabled = excluded.enabled,
+            timestamp = excluded.timestamp",
+        (kind.to_owned(), name.to_owned(), enabled, get_timestamp()),
+    );
+    transaction.commit()?;
+    conn_result
+}
+
+/// Searches the settings table for the setting of a single decoder or checker
+///
+/// On match, returns a SettingRow
+/// Otherwise, returns None
+///
+/// # Errors
+///
+/// Returns a ``rusqlite::Error``
+pub fn read_setting(kind: &str, name: &str) -> Result<Option<SettingRow>, rusqlite::Error> {
+    let conn = get_db_connection()?;
+    let mut stmt = conn.prepare_cached("SELECT * FROM settings WHERE kind IS $1 AND name IS $2")?;
+    let mut query = stmt.query_map([kind, name], setting_from_row)?;
+    let row = query.next();
+    match row {
+        Some(setting_row) => Ok(Some(setting_row?)),
+        None => Ok(None),
+    }
+}
+
+/// Returns every row in the settings table
+///
+/// # Errors
+///
+/// Returns a ``rusqlite::Error``
+pub fn read_settings() -> Result<Vec<SettingRow>, rusqlite::Error> {
+    let conn = get_db_connection()?;
+    let mut stmt = conn.prepare_cached("SELECT * FROM settings ORDER BY kind, name")?;
+    let rows = stmt.query_map([], setting_from_row)?;
+    rows.collect()
+}
+
+/// Removes the setting for a single decoder or checker
+///
+/// Returns number of successfully deleted rows on success
+///
+/// # Errors
+///
+/// Returns sqlite::Error on error
+pub fn delete_setting(kind: &str, name: &str) -> Result<usize, rusqlite::Error> {
+    let mut conn = get_db_connection()?;
+    let transaction = conn.transaction()?;
+    let conn_result = execute_cached(
+        &transaction,
+        "DELETE FROM settings WHERE kind = $1 AND name = $2",
+        (kind.to_owned(), name.to_owned()),
+    );
+    transaction.commit()?;
+    conn_result
+}
+
+/// Turns a row from the settings table into a SettingRow
+fn setting_from_row(row: &rusqlite::Row) -> Result<SettingRow, rusqlite::Error> {
+    Ok(SettingRow {
+        kind: row.get(0)?,
+        name: row.get(1)?,
+        enabled: row.get(2)?,
+        timestamp: row.get(3)?,
+    })
+}
+
+/// Adds the attempts, successes and runtime of a search to a decoder's statistics
+///
+/// Returns the number of inserted or updated rows on success
+///
+/// # Errors
+///
+/// Returns rusqlite::Error on error
+pub fn add_decoder_stats(
+    decoder: &str,
+    attempts: i64,
+    successes: i64,
+    runtime_micros: i64,
+) -> Result<usize, rusqlite::Error> {
+    let mut conn = get_db_connection()?;
+    let transaction = conn.transaction()?;
+    let conn_result = execute_cached(
+        &transaction,
+        "INSERT INTO decoder_stats (
+            decoder,
+            attempts,
+            successes,
+            runtime_micros,
+            timestamp)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (decoder) DO UPDATE SET
+            attempts = attempts + excluded.attempts,
+            successes = successes + excluded.successes,
+            runtime_micros = runtime_micros + excluded.runtime_micros,
+            timestamp = excluded.timestamp",
+        (
+            decoder.to_owned(),
+            attempts,
+            successes,
+            runtime_micros,
+            get_timestamp(),
+        ),
+    );
+    transaction.commit()?;
+    conn_result
+}
+
+/// Returns every row in the decoder_stats table
+///
+/// # Errors
+///
+/// Returns a ``rusqlite::Error``
+pub fn read_decoder_stats() -> Result<Vec<DecoderStatsRow>, rusqlite::Error> {
+    let conn = get_db_connection()?;
+    let mut stmt = conn.prepare_cached("SELECT * FROM decoder_stats ORDER BY decoder")?;
+    let rows = stmt.query_map([], |row| {
+        Ok(DecoderStatsRow {
+            decoder: row.get(0)?,
+            attempts: row.get(1)?,
+            successes: row.get(2)?,
+            runtime_micros: row.get(3)?,
+            timestamp: row.get(4)?,
+        })
+    })?;
+    rows.collect()
+}
+
+/// Removes the statistics of a single decoder
+///
+/// Returns number of successfully deleted rows on success
+///
+/// # Errors
+///
+/// Returns sqlite::Error on error
+pub fn delete_decoder_stats(decoder: &str) -> Result<usize, rusqlite::Error> {
+    let mut conn = get_db_connection()?;
+    let transaction = conn.transaction()?;
+    let conn_result = execute_cached(
+        &transaction,
+        "DELETE FROM decoder_stats WHERE decoder = $1",
+        (decoder.to_owned(),),
+    );
+    transaction.commit()?;
+    conn_result
+}
+
+/// Stores where the search of a text got to, replacing any earlier checkpoint of it
+///
+/// Returns the number of inserted or replaced rows on success
+///
+/// # Errors
+///
+/// Returns rusqlite::Error on error
+pub fn upsert_checkpoint(encoded_text: &str, frontier: &str) -> Result<usize, rusqlite::Error> {
+    let mut conn = get_db_connection()?;
+    let transaction = conn.transaction()?;
+    let conn_result = execute_cached(
+        &transaction,
+        "INSERT INTO checkpoints (
+            encoded_text,
+            frontier,
+            timestamp)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (encoded_text) DO UPDATE SET
+            frontier = excluded.frontier,
+            timestamp = excluded.timestamp",
+        (
+            encoded_text.to_owned(),
+            frontier.to_owned(),
+            get_timestamp(),
+        ),
+    );
+    transaction.commit()?;
+    conn_result
+}
+
+/// Searches the checkpoints table for where the search of a text got to
+///
+/// On match, returns a CheckpointRow
+/// Otherwise, returns None
+///
+/// # Errors
+///
+/// Returns a ``rusqlite::Error``
+pub fn read_checkpoint(encoded_text: &str) -> Result<Option<CheckpointRow>, rusqlite::Error> {
+    let conn = get_db_connection()?;
+    let mut stmt = conn.prepare_cached("SELECT * FROM checkpoints WHERE encoded_text IS $1")?;
+    let mut query = stmt.query_map([encoded_text], |row| {
+        Ok(CheckpointRow {
+            encoded_text: row.get(0)?,
+            frontier: row.get(1)?,
+            timestamp: row.get(2)?,
+        })
+    })?;
+    match query.next() {
+        Some(checkpoint_row) => Ok(Some(checkpoint_row?)),
+        None => Ok(None),
+    }
+}
+
+/// Removes the checkpoint of a text
+///
+/// Returns number of successfully deleted rows on success
+///
+/// # Errors
+///
+/// Returns sqlite::Error on error
+pub fn delete_checkpoint(encoded_text: &str) -> Result<usize, rusqlite::Error> {
+    let mut conn = get_db_connection()?;
+    let transaction = conn.transaction()?;
+    let conn_result = execute_cached(
+        &transaction,
+        "DELETE FROM checkpoints WHERE encoded_text = $1",
+        (encoded_text.to_owned(),),
+    );
+    transaction.commit()?;
+    conn_result
+}
+
+/// Stores what decoders made of texts, replacing what the same decoder made of the same
+/// text with the same settings before
+/// Every edge is written in one transaction, as a search meets thousands of them.
+///
+/// Returns the number of inserted or replaced rows on success
+///
+/// # Errors
+///
+/// Returns rusqlite::Error on error
+pub fn insert_edges(edges: &[EdgeEntry]) -> Result<usize, rusqlite::Error> {
+    let timestamp = get_timestamp();
+    let mut conn = get_db_connection()?;
+    let transaction = conn.transaction()?;
+    let mut inserted = 0;
+    {
+        let mut stmt = transaction.prepare_cached(
+            "INSERT INTO edges (
+                encoded_text,
+                decoder,
+                settings,
+                result,
+                timestamp)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (encoded_text, decoder, settings) DO UPDATE SET
+                result = excluded.result,
+                timestamp = excluded.timestamp",
+        )?;
+        for edge in edges {
+            inserted += stmt.execute((
+                &edge.encoded_text,
+                &edge.decoder,
+                &edge.settings,
+                &edge.result,
+                &timestamp,
+            ))?;
+        }
+    }
+    evict(&transaction, &current_cache_limits())?;
+    transaction.commit()?;
+    Ok(inserted)
+}
+
+/// Searches the edges table for what decoders made of a text with these settings
+///
+/// Returns an EdgeRow for every decoder which has been run on the text
+///
+/// # Errors
+///
+/// Returns a ``rusqlite::Error``
+pub fn read_edges(encoded_text: &str, settings: &str) -> Result<Vec<EdgeRow>, rusqlite::Error> {
+    let conn = get_db_connection()?;
+    let mut stmt =
+        conn.prepare_cached("SELECT * FROM edges WHERE encoded_text IS $1 AND settings IS $2")?;
+    let rows = stmt.query_map([encoded_text, settings], |row| {
+        Ok(EdgeRow {
+            encoded_text: row.get(0)?,
+            decoder: row.get(1)?,
+            settings: row.get(2)?,
+            result: row.get(3)?,
+            timestamp: row.get(4)?,
+        })
+    })?;
+    rows.collect()
+}
+
+/// Removes what every decoder made of a text
+///
+/// Returns number of successfully deleted rows on success
+///
+/// # Errors
+///
+/// Returns sqlite::Error on error
+pub fn delete_edges(encoded_text: &str) -> Result<usize, rusqlite::Error> {
+    let mut conn = get_db_connection()?;
+    let transaction = conn.transaction()?;
+    let conn_result = execute_cached(
+        &transaction,
+        "DELETE FROM edges WHERE encoded_text = $1",
+        (encoded_text.to_owned(),),
+    );
+    transaction.commit()?;
+    conn_result
+}
+
+/// Returns every row of the cache table, oldest first, for exporting
+///
+/// # Errors
+///
+/// Returns a ``rusqlite::Error``
+pub fn read_cache_for_export() -> Result<Vec<ExportedCacheRow>, rusqlite::Error> {
+    let conn = get_db_connection()?;
+    let mut stmt = conn.prepare_cached(
+        "SELECT uuid, encoded_text, decoded_text, path, successful, execution_time_ms,
+            timestamp, checker, sensitivity, confidence, version, registry
+        FROM cache ORDER BY timestamp",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok(ExportedCacheRow {
+            uuid: row.get(0)?,
+            encoded_text: row.get(1)?,
+            decoded_text: row.get(2)?,
+            path: row.get(3)?,
+            successful: row.get(4)?,
+            execution_time_ms: row.get(5)?,
+            timestamp: row.get(6)?,
+            checker: row.get(7)?,
+            sensitivity: row.get(8)?,
+            confidence: row.get(9)?,
+            version: row.get(10)?,
+            registry: row.get(11)?,
+        })
+    })?;
+    rows.collect()
+}
+
+/// Adds exported cache rows to the cache table
+/// Rows already in it, or for a text already cached by the same version with the same
+/// decoders, are skipped, so importing the same export twice changes nothing.
+///
+/// Returns the number of rows added on success
+///
+/// # Errors
+///
+/// Returns rusqlite::Error on error
+pub fn import_cache_rows(rows: &[ExportedCacheRow]) -> Result<usize, rusqlite::Error> {
+    let mut conn = get_db_connection()?;
+    let transaction = conn.transaction()?;
+    let mut imported = 0;
+    {
+        let mut stmt = transaction.prepare_cached(
+            "INSERT OR IGNORE INTO cache (
+                uuid,
+                encoded_text,
+                decoded_text,
+                path,
+                successful,
+                execution_time_ms,
+                timestamp,
+                checker,
+                sensitivity,
+                confidence,
+                version,
+                registry)
+            SELECT $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12
+            WHERE NOT EXISTS (
+                SELECT 1 FROM cache
+                WHERE encoded_text = $2 AND version IS $11 AND registry IS $12)",
+        )?;
+        for row in rows {
+            imported += stmt.execute((
+                &row.uuid,
+                &row.encoded_text,
+                &row.decoded_text,
+                &row.path,
+                row.successful,
+                row.execution_time_ms,
+                &row.timestamp,
+                &row.checker,
+                &row.sensitivity,
+                row.confidence,
+                &row.version,
+                &row.registry,
+            ))?;
+        }
+    }
+    evict(&transaction, &current_cache_limits())?;
+    transaction.commit()?;
+    Ok(imported)
+}
+
+/// Adds to counters such as how often the cache was hit, starting any new ones at 0
+///
+/// Returns the number of inserted or updated rows on success
+///
+/// # Errors
+///
+/// Returns rusqlite::Error on error
+pub fn add_counters(counters: &[(&str, i64)]) -> Result<usize, rusqlite::Error> {
+    let timestamp = get_timestamp();
+    let mut conn = get_db_connection()?;
+    let transaction = conn.transaction()?;
+    let mut updated = 0;
+    for (name, amount) in counters {
+        updated += execute_cached(
+            &transaction,
+            "INSERT INTO counters (name, value, timestamp)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (name) DO UPDATE SET
+                value = value + excluded.value,
+                timestamp = excluded.timestamp",
+            (name, amount, &timestamp),
+        )?;
+    }
+    transaction.commit()?;
+    Ok(updated)
+}
+
+/// Returns the value of a counter, 0 if nothing has been added to it
+///
+/// # Errors
+///
+/// Returns a ``rusqlite::Error``
+pub fn read_counter(name: &str) -> Result<i64, rusqlite::Error> {
+    let conn = get_db_connection()?;
+    let mut stmt = conn.prepare_cached("SELECT value FROM counters WHERE name IS $1")?;
+    let mut query = stmt.query_map([name], |row| row.get(0))?;
+    match query.next() {
+        Some(value) => value,
+        None => Ok(0),
+    }
+}
+
+/// How many cached results and how long they took to decode on average, in milliseconds
+///
+/// # Errors
+///
+/// Returns a ``rusqlite::Error``
+pub fn read_cache_summary() -> Result<(i64, f64), rusqlite::Error> {
+    let conn = get_db_connection()?;
+    conn.query_row(
+        "SELECT COUNT(*), COALESCE(AVG(execution_time_ms), 0) FROM cache WHERE execution_time_ms >= 0",
+        (),
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )
+}
+
+/// How many cached results were decoded first by each decoder, most first
+///
+/// # Errors
+///
+/// Returns a ``rusqlite::Error``
+pub fn read_first_decoder_counts(limit: usize) -> Result<Vec<CountRow>, rusqlite::Error> {
+    let conn = get_db_connection()?;
+    // Each step of the path is stored as its own JSON string inside the path array
+    let mut stmt = conn.prepare_cached(
+        "SELECT json_extract(json_extract(path, '$[0]'), '$.decoder') AS first, COUNT(*)
+        FROM cache
+        WHERE first IS NOT NULL AND first != 'Default decoder'
+        GROUP BY first
+        ORDER BY COUNT(*) DESC, first
+        LIMIT $1",
+    )?;
+    let rows = stmt.query_map([limit as i64], |row| {
+        Ok(CountRow {
+            name: row.get(0)?,
+            count: row.get(1)?,
+        })
+    })?;
+    rows.collect()
+}
+
+/// How many plaintexts the user rejected from each checker, most first
+///
+/// # Errors
+///
+/// Returns a ``rusqlite::Error``
+pub fn read_rejection_counts() -> Result<Vec<CountRow>, rusqlite::Error> {
+    let conn = get_db_connection()?;
+    let mut stmt = conn.prepare_cached(
+        "SELECT checker, COUNT(*) FROM human_rejection
+        GROUP BY checker
+        ORDER BY COUNT(*) DESC, checker",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok(CountRow {
+            name: row.get(0)?,
+            count: row.get(1)?,
+        })
+    })?;
+    rows.collect()
+}
+
+/// How many plaintexts the user confirmed from each checker, most first
+///
+/// # Errors
+///
+/// Returns a ``rusqlite::Error``
+pub fn read_confirmation_counts() -> Result<Vec<CountRow>, rusqlite::Error> {
+    let conn = get_db_connection()?;
+    let mut stmt = conn.prepare_cached(
+        "SELECT checker, COUNT(*) FROM confirmed_plaintexts
+        GROUP BY checker
+        ORDER BY COUNT(*) DESC, checker",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok(CountRow {
+            name: row.get(0)?,
+            count: row.get(1)?,
+        })
+    })?;
+    rows.collect()
+}
+
+/// Adds a run to the runs table, forgetting the oldest runs beyond `max_runs`
+/// A `max_runs` of 0 keeps every run.
 ///
-/// Returns the number of successfully inserted rows on success
+/// Returns the number of the run on success
 ///
 /// # Errors
 ///
 /// Returns rusqlite::Error on error
-pub fn insert_human_rejection(
-    uuid: Uuid,
-    plaintext: &str,
-    check_result: &CheckResult,
-) -> Result<usize, rusqlite::Error> {
+pub fn insert_run(run: &RunEntry, max_runs: usize) -> Result<i64, rusqlite::Error> {
+    let path: Vec<String> = run
+        .path
+        .iter()
+        .map(|crack_result| crack_result.get_json().unwrap_or_default())
+        .collect();
+    let path_json = serde_json::to_string(&path).unwrap_or_else(|_| String::from("[]"));
     let mut conn = get_db_connection()?;
     let transaction = conn.transaction()?;
-    let conn_result = transaction.execute(
-        "INSERT INTO human_rejection (
-            uuid,
+    execute_cached(
+        &transaction,
+        "INSERT INTO runs (
+            input,
+            outcome,
             plaintext,
-            checker,
+            path,
+            decoder_timings,
+            config,
+            duration_ms,
+            version,
             timestamp)
-        VALUES ($1, $2, $3, $4)",
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
         (
-            uuid.to_string(),
-            plaintext.to_owned(),
-            check_result.checker_name,
+            &run.input,
+            &run.outcome,
+            &run.plaintext,
+            path_json,
+            &run.decoder_timings,
+            &run.config,
+            run.duration_ms,
+            CACHE_VERSION,
             get_timestamp(),
         ),
-    );
+    )?;
+    let id = transaction.last_insert_rowid();
+    if max_runs > 0 {
+        execute_cached(
+            &transaction,
+            "DELETE FROM runs WHERE id <= $1",
+            [id - i64::try_from(max_runs).unwrap_or(i64::MAX)],
+        )?;
+    }
     transaction.commit()?;
-    conn_result
+    Ok(id)
 }
 
-/// Searches the database for a human_rejection table row that matches the given plaintext
+/// Turns a row of the runs table into a RunRow
+fn run_from_row(row: &rusqlite::Row) -> Result<RunRow, rusqlite::Error> {
+    let path: String = row.get(4)?;
+    Ok(RunRow {
+        id: row.get(0)?,
+        input: row.get(1)?,
+        outcome: row.get(2)?,
+        plaintext: row.get(3)?,
+        path: serde_json::from_str(&path).unwrap_or_default(),
+        decoder_timings: row.get(5)?,
+        config: row.get(6)?,
+        duration_ms: row.get(7)?,
+        version: row.get(8)?,
+        timestamp: row.get(9)?,
+    })
+}
+
+/// Returns the most recent runs, newest first
 ///
-/// On match, returns a HumanRejectionRow
-/// Otherwise, returns None
+/// # Errors
+///
+/// Returns a ``rusqlite::Error``
+pub fn read_runs(limit: usize) -> Result<Vec<RunRow>, rusqlite::Error> {
+    let conn = get_db_connection()?;
+    let mut stmt = conn.prepare_cached("SELECT * FROM runs ORDER BY id DESC LIMIT $1")?;
+    let rows = stmt.query_map([i64::try_from(limit).unwrap_or(i64::MAX)], run_from_row)?;
+    rows.collect()
+}
+
+/// Searches the runs table for the run with this number
 ///
 /// # Errors
 ///
 /// Returns a ``rusqlite::Error``
-pub fn read_human_rejection(
-    plaintext: &String,
-) -> Result<Option<HumanRejectionRow>, rusqlite::Error> {
+pub fn read_run(id: i64) -> Result<Option<RunRow>, rusqlite::Error> {
     let conn = get_db_connection()?;
-    let mut stmt = conn.prepare("SELECT * FROM human_rejection WHERE plaintext IS $1")?;
-    let mut query = stmt.query_map([plaintext], |row| {
-        Ok(HumanRejectionRow {
-            uuid: Uuid::parse_str(row.get_unwrap::<usize, String>(0).as_str()).unwrap_or_default(),
-            plaintext: row.get_unwrap(1),
-            checker: row.get_unwrap(2),
-            timestamp: row.get_unwrap(3),
-        })
-    })?;
-    let row = query.next();
-    match row {
-        Some(cache_row) => Ok(Some(cache_row?)),
+    let mut stmt = conn.prepare_cached("SELECT * FROM runs WHERE id IS $1")?;
+    let mut query = stmt.query_map([id], run_from_row)?;
+    match query.next() {
+        Some(run_row) => Ok(Some(run_row?)),
         None => Ok(None),
     }
 }
 
-/// Updates a human_rejection row for a given plaintext
+/// Sets the limits inserts into the cache and edges tables evict rows to stay within
+pub fn set_cache_limits(limits: CacheLimits) {
+    *CACHE_LIMITS
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner) = limits;
+}
+
+/// The limits set with [`set_cache_limits`]
+pub fn current_cache_limits() -> CacheLimits {
+    *CACHE_LIMITS
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+/// Removes the cache and edges rows which are older or more than the limits allow
 ///
-/// Returns the number of update rows on success
+/// Returns how many rows were removed from each table on success
 ///
 /// # Errors
 ///
-/// Returns rusqlite::Error on error
-pub fn update_human_rejection(
-    uuid: Uuid,
-    plaintext: &str,
-    check_result: &CheckResult,
-) -> Result<usize, rusqlite::Error> {
+/// Returns sqlite::Error on error
+pub fn prune_cache(limits: &CacheLimits) -> Result<PrunedRows, rusqlite::Error> {
     let mut conn = get_db_connection()?;
     let transaction = conn.transaction()?;
-    let conn_result = transaction.execute(
-        "UPDATE human_rejection SET 
-            uuid = $1,
-            checker = $2,
-            timestamp = $3
-            WHERE plaintext = $4;",
-        (
-            uuid.to_string(),
-            check_result.checker_name,
-            get_timestamp(),
-            plaintext.to_owned(),
-        ),
-    );
+    let pruned = evict(&transaction, limits)?;
     transaction.commit()?;
-    conn_result
+    Ok(pruned)
 }
 
-/// Removes the human_rejection row corresponding to the given plaintext
+/// Removes every cache and edges row
 ///
-/// Returns number of successfully deleted rows on success
+/// Returns how many rows were removed from each table on success
 ///
 /// # Errors
 ///
 /// Returns sqlite::Error on error
-pub fn delete_human_rejection(plaintext: &str) -> Result<usize, rusqlite::Error> {
+pub fn clear_cache() -> Result<PrunedRows, rusqlite::Error> {
     let mut conn = get_db_connection()?;
     let transaction = conn.transaction()?;
-    let conn_result = transaction.execute(
-        "DELETE FROM human_rejection WHERE plaintext = $1",
-        (plaintext.to_owned(),),
-    );
+    let pruned = PrunedRows {
+        results: execute_cached(&transaction, "DELETE FROM cache", ())?,
+        edges: execute_cached(&transaction, "DELETE FROM edges", ())?,
+    };
     transaction.commit()?;
-    conn_result
+    Ok(pruned)
+}
+
+/// Removes rows unused for longer than the limits allow, then the rows used longest ago
+/// until each table is within its limit
+fn evict(conn: &rusqlite::Connection, limits: &CacheLimits) -> Result<PrunedRows, rusqlite::Error> {
+    let mut pruned = PrunedRows::default();
+    if limits.max_age_days > 0 {
+        let cutoff: DateTime<chrono::Local> = (std::time::SystemTime::now()
+            - std::time::Duration::from_secs(u64::from(limits.max_age_days) * 24 * 60 * 60))
+        .into();
+        let cutoff = cutoff.format("%Y-%m-%d %T").to_string();
+        pruned.results += execute_cached(
+            conn,
+            "DELETE FROM cache WHERE COALESCE(last_used, timestamp) < $1",
+            (&cutoff,),
+        )?;
+        pruned.edges += execute_cached(conn, "DELETE FROM edges WHERE timestamp < $1", (&cutoff,))?;
+    }
+    if limits.max_entries > 0 {
+        pruned.results += execute_cached(
+            conn,
+            "DELETE FROM cache WHERE uuid IN (
+                SELECT uuid FROM cache
+                ORDER BY COALESCE(last_used, timestamp) ASC
+                LIMIT MAX(0, (SELECT COUNT(*) FROM cache) - $1))",
+            (limits.max_entries as i64,),
+        )?;
+    }
+    if limits.max_edges > 0 {
+        pruned.edges += execute_cached(
+            conn,
+            "DELETE FROM edges WHERE rowid IN (
+                SELECT rowid FROM edges
+                ORDER BY timestamp ASC
+                LIMIT MAX(0, (SELECT COUNT(*) FROM edges) - $1))",
+            (limits.max_edges as i64,),
+        )?;
+    }
+    Ok(pruned)
 }
 
 #[cfg(test)]
@@ -443,6 +1963,7 @@ mod tests {
         english::EnglishChecker,
         CheckerTypes,
     };
+    use gibberish_or_not::Sensitivity;
     use uuid::Uuid;
 
     struct MockDecoder;
@@ -486,6 +2007,9 @@ mod tests {
     fn set_test_db_path() {
         let path = std::path::PathBuf::from(String::from("file::memory:?cache=shared"));
         let _ = DB_PATH.set(Some(path));
+        // The shared in-memory database is dropped with its last connection, so closing the
+        // pooled ones gives each test an empty database
+        close_idle_connections();
     }
 
     /// Helper function for generating a cache row
@@ -510,6 +2034,9 @@ mod tests {
             successful: true,
             execution_time_ms: 100,
             timestamp: String::new(),
+            checker: String::new(),
+            sensitivity: None,
+            confidence: 0.0,
         };
 
         let cache_entry = CacheEntry {
@@ -518,6 +2045,9 @@ mod tests {
             decoded_text: decoded_text.to_owned(),
             path: vec![mock_crack_result.clone()],
             execution_time_ms: 100,
+            checker: String::new(),
+            sensitivity: None,
+            confidence: 0.0,
         };
         (mock_crack_result, expected_cache_row, cache_entry)
     }
@@ -535,6 +2065,9 @@ mod tests {
             checker_description: checker_used.description,
             description: "".to_string(),
             link: checker_used.link,
+            confidence: 0.0,
+            sensitivity: checker_used.sensitivity,
+            scores: None,
         };
 
         let expected_row = HumanRejectionRow {
@@ -588,6 +2121,9 @@ mod tests {
         assert_eq!(name_list[4], "successful");
         assert_eq!(name_list[5], "execution_time_ms");
         assert_eq!(name_list[6], "timestamp");
+        assert_eq!(name_list[7], "checker");
+        assert_eq!(name_list[8], "sensitivity");
+        assert_eq!(name_list[9], "confidence");
 
         let type_result = stmt.query_map([], |row| row.get::<usize, String>(2));
         assert!(type_result.is_ok());
@@ -600,6 +2136,99 @@ mod tests {
         assert_eq!(type_list[4], "BOOLEAN");
         assert_eq!(type_list[5], "INTEGER");
         assert_eq!(type_list[6], "DATETIME");
+        assert_eq!(type_list[7], "TEXT");
+        assert_eq!(type_list[8], "TEXT");
+        assert_eq!(type_list[9], "REAL");
+    }
+
+    #[test]
+    fn read_only_connection_is_not_writable() {
+        let path = std::env::temp_dir().join(format!("ciphey-{}.sqlite", Uuid::new_v4()));
+        create_schema(&rusqlite::Connection::open(&path).unwrap()).unwrap();
+        let conn = rusqlite::Connection::open_with_flags(
+            &path,
+            rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY,
+        )
+        .unwrap();
+        assert!(ensure_writable(&conn).is_err());
+        assert!(ensure_writable(&rusqlite::Connection::open(&path).unwrap()).is_ok());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn garbage_file_is_not_a_database() {
+        let path = std::env::temp_dir().join(format!("ciphey-{}.sqlite", Uuid::new_v4()));
+        std::fs::write(&path, "not a database ".repeat(100)).unwrap();
+        assert!(create_schema(&rusqlite::Connection::open(&path).unwrap()).is_err());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn old_cache_table_gains_provenance_columns() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        conn.execute(
+            "CREATE TABLE cache (uuid TEXT PRIMARY KEY NOT NULL, encoded_text TEXT NOT NULL);",
+            (),
+        )
+        .unwrap();
+        let columns = [
+            ("checker", "TEXT NOT NULL DEFAULT ''"),
+            ("confidence", "REAL"),
+        ];
+        add_missing_columns(&conn, "cache", &columns).unwrap();
+        // Running it again must not try to add them twice
+        add_missing_columns(&conn, "cache", &columns).unwrap();
+
+        let mut stmt = conn.prepare("PRAGMA table_info(cache);").unwrap();
+        let names: Vec<String> = stmt
+            .query_map([], |row| row.get::<usize, String>(1))
+            .unwrap()
+            .map(|name| name.unwrap())
+            .collect();
+        assert_eq!(names, ["uuid", "encoded_text", "checker", "confidence"]);
+    }
+
+    #[test]
+    fn cache_provenance_comes_from_the_last_step() {
+        let checker = Checker::<EnglishChecker>::new().with_sensitivity(Sensitivity::Low);
+        let mut check_result = CheckResult::new(&checker);
+        check_result.is_identified = true;
+        check_result.confidence = 0.75;
+
+        let (mut crack_result, _, cache_entry) =
+            generate_cache_row(Uuid::new_v4(), "provenance", "checked");
+        crack_result.update_checker(&check_result);
+        let cache_entry = CacheEntry {
+            path: vec![crack_result],
+            ..cache_entry
+        }
+        .with_provenance_from_path();
+
+        assert_eq!(cache_entry.checker, checker.name);
+        assert_eq!(cache_entry.sensitivity.as_deref(), Some("Low"));
+        assert_eq!(cache_entry.confidence, 0.75);
+    }
+
+    #[test]
+    fn cache_provenance_is_read_back() {
+        set_test_db_path();
+        let _conn = init_database().unwrap();
+        let (_, _, cache_entry) = generate_cache_row(Uuid::new_v4(), "stored provenance", "ok");
+        let cache_entry = CacheEntry {
+            checker: String::from("English Checker"),
+            sensitivity: Some(String::from("High")),
+            confidence: 0.5,
+            ..cache_entry
+        };
+        insert_cache(&cache_entry).unwrap();
+
+        let row = read_cache(&String::from("stored provenance"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(row.checker, "English Checker");
+        assert_eq!(row.sensitivity.as_deref(), Some("High"));
+        assert_eq!(row.confidence, 0.5);
+        delete_cache("stored provenance").unwrap();
     }
 
     #[test]
@@ -650,6 +2279,9 @@ mod tests {
                 successful: row.get_unwrap(4),
                 execution_time_ms: row.get_unwrap(5),
                 timestamp: row.get_unwrap(6),
+                checker: row.get_unwrap(7),
+                sensitivity: row.get_unwrap(8),
+                confidence: row.get_unwrap(9),
             })
         });
         assert!(query_result.is_ok());
@@ -686,6 +2318,9 @@ mod tests {
                 successful: row.get_unwrap(4),
                 execution_time_ms: row.get_unwrap(5),
                 timestamp: row.get_unwrap(6),
+                checker: row.get_unwrap(7),
+                sensitivity: row.get_unwrap(8),
+                confidence: row.get_unwrap(9),
             })
         });
         assert!(query_result.is_ok());
@@ -733,6 +2368,9 @@ mod tests {
                 successful: row.get_unwrap(4),
                 execution_time_ms: row.get_unwrap(5),
                 timestamp: row.get_unwrap(6),
+                checker: row.get_unwrap(7),
+                sensitivity: row.get_unwrap(8),
+                confidence: row.get_unwrap(9),
             })
         });
         let mut query = query_result.unwrap();
@@ -1544,4 +3182,453 @@ mod tests {
         assert!(update_result.is_ok());
         assert_eq!(update_result.unwrap(), 0);
     }
+
+    #[test]
+    fn correct_settings_table_schema() {
+        set_test_db_path();
+        let conn = init_database().unwrap();
+
+        let mut stmt = conn.prepare("PRAGMA table_info(settings);").unwrap();
+        let name_list: Vec<String> = stmt
+            .query_map([], |row| row.get::<usize, String>(1))
+            .unwrap()
+            .map(|row| row.unwrap())
+            .collect();
+        assert_eq!(name_list, vec!["kind", "name", "enabled", "timestamp"]);
+    }
+
+    #[test]
+    fn setting_read_empty_miss() {
+        set_test_db_path();
+        let _conn = init_database().unwrap();
+
+        let row_result = read_setting("decoder", "Not a decoder");
+        assert!(row_result.is_ok());
+        assert!(row_result.unwrap().is_none());
+    }
+
+    #[test]
+    fn setting_upsert_replaces_earlier_setting() {
+        set_test_db_path();
+        let _conn = init_database().unwrap();
+
+        assert_eq!(upsert_setting("decoder", "Brainfuck", false).unwrap(), 1);
+        assert!(
+            !read_setting("decoder", "Brainfuck")
+                .unwrap()
+                .unwrap()
+                .enabled
+        );
+
+        assert_eq!(upsert_setting("decoder", "Brainfuck", true).unwrap(), 1);
+        let row = read_setting("decoder", "Brainfuck").unwrap().unwrap();
+        assert!(row.enabled);
+        assert_eq!(row.kind, "decoder");
+        assert_eq!(row.name, "Brainfuck");
+
+        assert_eq!(delete_setting("decoder", "Brainfuck").unwrap(), 1);
+    }
+
+    #[test]
+    fn settings_read_all_rows() {
+        set_test_db_path();
+        let _conn = init_database().unwrap();
+
+        upsert_setting("decoder", "Brainfuck", false).unwrap();
+        upsert_setting("checker", "Password Checker", false).unwrap();
+        let rows = read_settings().unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].kind, "checker");
+        assert_eq!(rows[1].name, "Brainfuck");
+
+        delete_setting("decoder", "Brainfuck").unwrap();
+        delete_setting("checker", "Password Checker").unwrap();
+        assert!(read_settings().unwrap().is_empty());
+    }
+
+    #[test]
+    fn setting_delete_missing() {
+        set_test_db_path();
+        let _conn = init_database().unwrap();
+
+        let delete_result = delete_setting("checker", "Not a checker");
+        assert!(delete_result.is_ok());
+        assert_eq!(delete_result.unwrap(), 0);
+    }
+
+    #[test]
+    fn correct_decoder_stats_table_schema() {
+        set_test_db_path();
+        let conn = init_database().unwrap();
+
+        let mut stmt = conn.prepare("PRAGMA table_info(decoder_stats);").unwrap();
+        let name_list: Vec<String> = stmt
+            .query_map([], |row| row.get::<usize, String>(1))
+            .unwrap()
+            .map(|row| row.unwrap())
+            .collect();
+        assert_eq!(
+            name_list,
+            vec![
+                "decoder",
+                "attempts",
+                "successes",
+                "runtime_micros",
+                "timestamp"
+            ]
+        );
+    }
+
+    #[test]
+    fn decoder_stats_accumulate_across_searches() {
+        set_test_db_path();
+        let _conn = init_database().unwrap();
+
+        assert_eq!(add_decoder_stats("Base64", 10, 1, 500).unwrap(), 1);
+        assert_eq!(add_decoder_stats("Base64", 5, 2, 250).unwrap(), 1);
+        add_decoder_stats("Atbash", 3, 0, 30).unwrap();
+
+        let rows = read_decoder_stats().unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].decoder, "Atbash");
+        assert_eq!(rows[1].decoder, "Base64");
+        assert_eq!(
+            (rows[1].attempts, rows[1].successes, rows[1].runtime_micros),
+            (15, 3, 750)
+        );
+
+        assert_eq!(delete_decoder_stats("Base64").unwrap(), 1);
+        assert_eq!(delete_decoder_stats("Atbash").unwrap(), 1);
+        assert_eq!(delete_decoder_stats("Atbash").unwrap(), 0);
+        assert!(read_decoder_stats().unwrap().is_empty());
+    }
+
+    #[test]
+    fn checkpoint_upsert_replaces_earlier_checkpoint() {
+        set_test_db_path();
+        let _conn = init_database().unwrap();
+
+        assert!(read_checkpoint("aGVsbG8=").unwrap().is_none());
+        assert_eq!(upsert_checkpoint("aGVsbG8=", "[1]").unwrap(), 1);
+        assert_eq!(upsert_checkpoint("aGVsbG8=", "[2]").unwrap(), 1);
+        let row = read_checkpoint("aGVsbG8=").unwrap().unwrap();
+        assert_eq!(row.encoded_text, "aGVsbG8=");
+        assert_eq!(row.frontier, "[2]");
+
+        assert_eq!(delete_checkpoint("aGVsbG8=").unwrap(), 1);
+        assert_eq!(delete_checkpoint("aGVsbG8=").unwrap(), 0);
+    }
+
+    #[test]
+    fn edges_are_read_back_for_their_text_and_settings() {
+        set_test_db_path();
+        let _conn = init_database().unwrap();
+
+        let edge = |decoder: &str, settings: &str, result: &str| EdgeEntry {
+            encoded_text: "aGVsbG8=".to_string(),
+            decoder: decoder.to_string(),
+            settings: settings.to_string(),
+            result: result.to_string(),
+        };
+        let edges = [
+            edge("Base64", "a", "{}"),
+            edge("Hexadecimal", "a", "{}"),
+            edge("Base64", "b", "{}"),
+        ];
+        assert_eq!(insert_edges(&edges).unwrap(), 3);
+        // Running a decoder again replaces what it made of the text before
+        assert_eq!(insert_edges(&[edge("Base64", "a", "[]")]).unwrap(), 1);
+
+        let mut read = read_edges("aGVsbG8=", "a").unwrap();
+        read.sort_by(|a, b| a.decoder.cmp(&b.decoder));
+        assert_eq!(read.len(), 2);
+        assert_eq!(read[0].decoder, "Base64");
+        assert_eq!(read[0].result, "[]");
+        assert_eq!(read[1].decoder, "Hexadecimal");
+        assert!(read_edges("aGVsbG8=", "c").unwrap().is_empty());
+
+        assert_eq!(delete_edges("aGVsbG8=").unwrap(), 3);
+        assert!(read_edges("aGVsbG8=", "a").unwrap().is_empty());
+    }
+
+    #[test]
+    fn prune_cache_keeps_the_most_recently_used_results() {
+        set_test_db_path();
+        let conn = init_database().unwrap();
+
+        let texts = ["prune-first", "prune-second", "prune-third"];
+        for text in texts {
+            let entry = CacheEntry {
+                uuid: Uuid::new_v4(),
+                encoded_text: text.to_string(),
+                decoded_text: "decoded".to_string(),
+                path: vec![],
+                execution_time_ms: 1,
+                checker: String::new(),
+                sensitivity: None,
+                confidence: 0.0,
+            };
+            insert_cache(&entry).unwrap();
+        }
+        // All three were cached long ago, but the first has been used since
+        conn.execute(
+            "UPDATE cache SET timestamp = '2000-01-01 00:00:00', last_used = NULL
+                WHERE encoded_text IN ($1, $2, $3)",
+            texts,
+        )
+        .unwrap();
+        read_cache(texts[0]).unwrap();
+
+        let rows: i64 = conn
+            .query_row("SELECT COUNT(*) FROM cache", (), |row| row.get(0))
+            .unwrap();
+        let limits = CacheLimits {
+            max_entries: rows as usize - 2,
+            max_edges: 0,
+            max_age_days: 0,
+        };
+        assert!(prune_cache(&limits).unwrap().results >= 2);
+        assert!(read_cache(texts[0]).unwrap().is_some());
+        assert!(read_cache(texts[1]).unwrap().is_none());
+        assert!(read_cache(texts[2]).unwrap().is_none());
+        delete_cache(texts[0]).unwrap();
+    }
+
+    #[test]
+    fn prune_cache_removes_rows_older_than_the_max_age() {
+        set_test_db_path();
+        let conn = init_database().unwrap();
+
+        let edge = EdgeEntry {
+            encoded_text: "prune-aged".to_string(),
+            decoder: "Base64".to_string(),
+            settings: String::new(),
+            result: "{}".to_string(),
+        };
+        insert_edges(&[edge]).unwrap();
+        let limits = CacheLimits {
+            max_entries: 0,
+            max_edges: 0,
+            max_age_days: 30,
+        };
+        prune_cache(&limits).unwrap();
+        assert_eq!(read_edges("prune-aged", "").unwrap().len(), 1);
+
+        conn.execute(
+            "UPDATE edges SET timestamp = '2000-01-01 00:00:00' WHERE encoded_text = 'prune-aged'",
+            (),
+        )
+        .unwrap();
+        assert!(prune_cache(&limits).unwrap().edges >= 1);
+        assert!(read_edges("prune-aged", "").unwrap().is_empty());
+    }
+
+    #[test]
+    fn results_cached_with_other_decoders_are_misses() {
+        set_test_db_path();
+        let conn = init_database().unwrap();
+
+        let entry = CacheEntry {
+            uuid: Uuid::new_v4(),
+            encoded_text: "stamped".to_string(),
+            decoded_text: "decoded".to_string(),
+            path: vec![],
+            execution_time_ms: 1,
+            checker: String::new(),
+            sensitivity: None,
+            confidence: 0.0,
+        };
+        insert_cache(&entry).unwrap();
+        assert!(read_cache(&entry.encoded_text).unwrap().is_some());
+
+        // As if it was cached by an older version of ciphey
+        conn.execute(
+            "UPDATE cache SET version = '0.0.1' WHERE encoded_text = 'stamped'",
+            (),
+        )
+        .unwrap();
+        assert!(read_cache(&entry.encoded_text).unwrap().is_none());
+
+        // Caching it again replaces the stale row
+        insert_cache(&entry).unwrap();
+        let rows: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM cache WHERE encoded_text = 'stamped'",
+                (),
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(rows, 1);
+        delete_cache(&entry.encoded_text).unwrap();
+    }
+
+    #[test]
+    fn rejected_plaintexts_are_read_once_each() {
+        set_test_db_path();
+        let _conn = init_database().unwrap();
+        let checker_used = Checker::<Athena>::new();
+        let (check_result, _) =
+            generate_human_rejection_row(Uuid::new_v4(), "rejected twice", checker_used);
+        insert_human_rejection(Uuid::new_v4(), "rejected twice", &check_result).unwrap();
+        insert_human_rejection(Uuid::new_v4(), "rejected twice", &check_result).unwrap();
+
+        let rejected = read_rejected_plaintexts().unwrap();
+        assert_eq!(
+            rejected
+                .iter()
+                .filter(|plaintext| *plaintext == "rejected twice")
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn correct_confirmed_plaintexts_table_schema() {
+        set_test_db_path();
+        let conn = init_database().unwrap();
+
+        let mut stmt = conn
+            .prepare("PRAGMA table_info(confirmed_plaintexts);")
+            .unwrap();
+        let name_list: Vec<String> = stmt
+            .query_map([], |row| row.get::<usize, String>(1))
+            .unwrap()
+            .map(|row| row.unwrap())
+            .collect();
+        assert_eq!(name_list, ["uuid", "plaintext", "checker", "timestamp"]);
+    }
+
+    #[test]
+    fn confirmed_plaintexts_are_read_once_each_and_counted() {
+        set_test_db_path();
+        let _conn = init_database().unwrap();
+        let checker_used = Checker::<Athena>::new();
+        let (check_result, _) =
+            generate_human_rejection_row(Uuid::new_v4(), "confirmed twice", checker_used);
+        insert_confirmed_plaintext(Uuid::new_v4(), "confirmed twice", &check_result).unwrap();
+        insert_confirmed_plaintext(Uuid::new_v4(), "confirmed twice", &check_result).unwrap();
+
+        let confirmed = read_confirmed_plaintexts().unwrap();
+        assert_eq!(
+            confirmed
+                .iter()
+                .filter(|plaintext| *plaintext == "confirmed twice")
+                .count(),
+            1
+        );
+        let counts = read_confirmation_counts().unwrap();
+        assert!(counts
+            .iter()
+            .any(|row| row.name == check_result.checker_name && row.count >= 2));
+    }
+
+    #[test]
+    fn connections_are_reused_once_dropped() {
+        set_test_db_path();
+        let conn = init_database().unwrap();
+        drop(conn);
+        assert!(!POOL.lock().unwrap().idle.is_empty());
+    }
+
+    #[test]
+    fn database_files_use_write_ahead_logging() {
+        let dir = std::env::temp_dir().join(format!("ciphey-wal-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let conn = open_connection(&dir.join("database.sqlite")).unwrap();
+        let mode: String = conn
+            .pragma_query_value(None, "journal_mode", |row| row.get(0))
+            .unwrap();
+        assert_eq!(mode, "wal");
+        drop(conn);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn exported_rows_are_imported_once() {
+        set_test_db_path();
+        let _conn = init_database().unwrap();
+
+        let entry = CacheEntry {
+            uuid: Uuid::new_v4(),
+            encoded_text: "exported once".to_string(),
+            decoded_text: "decoded".to_string(),
+            path: vec![],
+            execution_time_ms: 1,
+            checker: String::new(),
+            sensitivity: None,
+            confidence: 0.0,
+        };
+        insert_cache(&entry).unwrap();
+        let exported: Vec<ExportedCacheRow> = read_cache_for_export()
+            .unwrap()
+            .into_iter()
+            .filter(|row| row.encoded_text == entry.encoded_text)
+            .collect();
+        assert_eq!(exported.len(), 1);
+
+        // Already cached, so nothing is added
+        assert_eq!(import_cache_rows(&exported).unwrap(), 0);
+
+        delete_cache(&entry.encoded_text).unwrap();
+        assert_eq!(import_cache_rows(&exported).unwrap(), 1);
+        assert!(read_cache(&entry.encoded_text).unwrap().is_some());
+        delete_cache(&entry.encoded_text).unwrap();
+    }
+
+    #[test]
+    fn counters_add_up_across_calls() {
+        set_test_db_path();
+        let _conn = init_database().unwrap();
+
+        let before = read_counter("test_counter").unwrap();
+        add_counters(&[("test_counter", 2)]).unwrap();
+        add_counters(&[("test_counter", 3), ("other_test_counter", 1)]).unwrap();
+        assert_eq!(read_counter("test_counter").unwrap(), before + 5);
+        assert_eq!(read_counter("never_added_to").unwrap(), 0);
+    }
+
+    #[test]
+    fn first_decoders_are_read_from_the_cached_paths() {
+        set_test_db_path();
+        let _conn = init_database().unwrap();
+
+        let (_, _, entry) = generate_cache_row(Uuid::new_v4(), "first-decoder-test", "hello");
+        let first = entry.path[0].decoder;
+        insert_cache(&entry).unwrap();
+
+        let counts = read_first_decoder_counts(100).unwrap();
+        assert!(counts.iter().any(|row| row.name == first && row.count >= 1));
+        delete_cache("first-decoder-test").unwrap();
+    }
+
+    #[test]
+    fn runs_are_read_back_newest_first_within_the_limit() {
+        set_test_db_path();
+        let _conn = init_database().unwrap();
+
+        let run = |input: &str| RunEntry {
+            input: input.to_string(),
+            outcome: "solved".to_string(),
+            plaintext: Some("hello".to_string()),
+            path: vec![],
+            decoder_timings: "{}".to_string(),
+            config: "{}".to_string(),
+            duration_ms: 5,
+        };
+        let first = insert_run(&run("first run"), 2).unwrap();
+        let second = insert_run(&run("second run"), 2).unwrap();
+        let third = insert_run(&run("third run"), 2).unwrap();
+        assert!(first < second && second < third);
+
+        let runs = read_runs(10).unwrap();
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[0].input, "third run");
+        assert_eq!(runs[1].id, second);
+        assert_eq!(runs[0].version, CACHE_VERSION);
+        assert!(read_run(first).unwrap().is_none());
+        assert_eq!(
+            read_run(third).unwrap().unwrap().plaintext.as_deref(),
+            Some("hello")
+        );
+    }
 }
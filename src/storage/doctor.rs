@@ -0,0 +1,369 @@
+//! Diagnoses and repairs the SQLite database for `ciphey db doctor`
+//! The database is checked with SQLite's own integrity check. Damaged indexes are
+//! rebuilt, and if the tables themselves are damaged every row which can still be
+//! read is copied into a fresh database. The original is always kept as a backup
+//! before anything is replaced.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use rusqlite::Connection;
+
+use super::database::create_schema;
+
+/// The tables ciphey keeps, in the order they are recovered
+const TABLES: [&str; 8] = [
+    "cache",
+    "human_rejection",
+    "settings",
+    "decoder_stats",
+    "checkpoints",
+    "edges",
+    "counters",
+    "runs",
+];
+
+/// What the doctor found and what it did about it
+#[derive(Debug, Default)]
+pub struct DoctorReport {
+    /// Everything that was wrong with the database
+    pub problems: Vec<String>,
+    /// Everything that was done to fix it
+    pub repairs: Vec<String>,
+    /// Whether the database is usable now
+    pub healthy: bool,
+}
+
+impl fmt::Display for DoctorReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for problem in &self.problems {
+            writeln!(f, "Problem: {}", problem)?;
+        }
+        for repair in &self.repairs {
+            writeln!(f, "Repaired: {}", repair)?;
+        }
+        if self.healthy {
+            write!(f, "The database is healthy")
+        } else {
+            write!(f, "The database could not be repaired")
+        }
+    }
+}
+
+/// How many rows of a table could be copied out of a damaged database
+#[derive(Debug, PartialEq, Eq)]
+pub struct TableRecovery {
+    /// The table the rows came from
+    pub table: &'static str,
+    /// Rows copied into the new database
+    pub recovered: usize,
+    /// Whether reading the table stopped early because of damage
+    pub incomplete: bool,
+}
+
+/// Checks the database at the path and repairs whatever it can
+///
+/// # Errors
+/// Returns an error message if the database is damaged and a fresh one can't be written,
+/// such as when its folder is read-only.
+pub fn diagnose_and_repair(path: &Path) -> Result<DoctorReport, String> {
+    let mut report = DoctorReport::default();
+
+    if !path.exists() {
+        report.healthy = true;
+        report.problems.push(format!(
+            "There is no database at {} yet, it will be created on the next run",
+            path.display()
+        ));
+        return Ok(report);
+    }
+    if std::fs::metadata(path).is_ok_and(|metadata| metadata.permissions().readonly()) {
+        report.problems.push(format!(
+            "{} is read-only, so nothing can be cached. Change its permissions to fix this.",
+            path.display()
+        ));
+        return Ok(report);
+    }
+
+    let problems = match integrity_problems(path) {
+        Ok(problems) if problems.is_empty() => {
+            report.healthy = true;
+            return Ok(report);
+        }
+        Ok(problems) => problems,
+        Err(e) => vec![format!("The database can't be read: {}", e)],
+    };
+    report.problems.extend(problems);
+
+    // Damaged indexes are common and can be rebuilt from the tables
+    if rebuild_indexes(path).is_ok() {
+        report.repairs.push(String::from("Rebuilt every index"));
+        if integrity_problems(path).is_ok_and(|problems| problems.is_empty()) {
+            report.healthy = true;
+            return Ok(report);
+        }
+    }
+
+    // Recent writes may only be in the write-ahead log, so move them into the file first
+    super::database::close_idle_connections();
+    let _ = Connection::open(path)
+        .and_then(|conn| conn.execute_batch("PRAGMA wal_checkpoint(TRUNCATE);"));
+
+    let backup = backup_path(path);
+    for (from, to) in sidecar_files(path).into_iter().zip(sidecar_files(&backup)) {
+        let _ = std::fs::copy(from, to);
+    }
+    std::fs::copy(path, &backup).map_err(|e| {
+        format!(
+            "Could not back the database up to {}: {}",
+            backup.display(),
+            e
+        )
+    })?;
+    report.repairs.push(format!(
+        "Backed the damaged database up to {}",
+        backup.display()
+    ));
+
+    let recovering = path.with_extension("recovering");
+    let _ = std::fs::remove_file(&recovering);
+    let recoveries = recover_into(path, &recovering)
+        .map_err(|e| format!("Could not create a fresh database: {}", e))?;
+    std::fs::rename(&recovering, path)
+        .map_err(|e| format!("Could not replace the damaged database: {}", e))?;
+    // A log left over from the damaged database would be replayed into the fresh one
+    for sidecar in sidecar_files(path) {
+        let _ = std::fs::remove_file(sidecar);
+    }
+    for recovery in recoveries {
+        report.repairs.push(format!(
+            "Recovered {} rows from the {} table{}",
+            recovery.recovered,
+            recovery.table,
+            if recovery.incomplete {
+                ", the rest were unreadable"
+            } else {
+                ""
+            }
+        ));
+    }
+    report.healthy = true;
+    Ok(report)
+}
+
+/// Runs SQLite's integrity check, returning every problem it reports
+///
+/// # Errors
+/// Returns rusqlite::Error if the file isn't a database or can't be opened
+pub fn integrity_problems(path: &Path) -> Result<Vec<String>, rusqlite::Error> {
+    let conn = Connection::open(path)?;
+    let mut stmt = conn.prepare("PRAGMA integrity_check;")?;
+    let messages: Vec<String> = stmt
+        .query_map([], |row| row.get::<usize, String>(0))?
+        .collect::<Result<_, _>>()?;
+    Ok(messages
+        .into_iter()
+        .filter(|message| message != "ok")
+        .collect())
+}
+
+/// Rebuilds every index in the database from its table
+///
+/// # Errors
+/// Returns rusqlite::Error if the tables are too damaged to index
+pub fn rebuild_indexes(path: &Path) -> Result<(), rusqlite::Error> {
+    Connection::open(path)?.execute_batch("REINDEX;")
+}
+
+/// Creates a database at `destination` with ciphey's schema, holding every row
+/// of `source` which can still be read
+///
+/// A source which isn't a database at all recovers nothing but still gives an empty database.
+///
+/// # Errors
+/// Returns rusqlite::Error if the destination can't be created
+pub fn recover_into(
+    source: &Path,
+    destination: &Path,
+) -> Result<Vec<TableRecovery>, rusqlite::Error> {
+    let new = Connection::open(destination)?;
+    create_schema(&new)?;
+    let Ok(old) = Connection::open(source) else {
+        return Ok(vec![]);
+    };
+    Ok(TABLES
+        .iter()
+        .filter_map(|table| recover_table(&old, &new, table))
+        .collect())
+}
+
+/// Copies the rows of one table which can be read, None if the table can't be read at all
+fn recover_table(old: &Connection, new: &Connection, table: &'static str) -> Option<TableRecovery> {
+    // Older databases may be missing columns, so only the ones both sides have are copied
+    let columns: Vec<String> = column_names(old, table)
+        .ok()?
+        .into_iter()
+        .filter(|column| {
+            column_names(new, table).is_ok_and(|new_columns| new_columns.contains(column))
+        })
+        .collect();
+    if columns.is_empty() {
+        return None;
+    }
+    let column_list = columns.join(", ");
+    let placeholders = vec!["?"; columns.len()].join(", ");
+
+    let mut select = old
+        .prepare(&format!("SELECT {} FROM {}", column_list, table))
+        .ok()?;
+    let mut insert = new
+        .prepare(&format!(
+            "INSERT OR IGNORE INTO {} ({}) VALUES ({})",
+            table, column_list, placeholders
+        ))
+        .ok()?;
+    let mut rows = select.query([]).ok()?;
+
+    let mut recovery = TableRecovery {
+        table,
+        recovered: 0,
+        incomplete: false,
+    };
+    loop {
+        match rows.next() {
+            Ok(Some(row)) => {
+                let values: Result<Vec<rusqlite::types::Value>, _> =
+                    (0..columns.len()).map(|index| row.get(index)).collect();
+                match values.map(|values| insert.execute(rusqlite::params_from_iter(values))) {
+                    Ok(Ok(inserted)) => recovery.recovered += inserted,
+                    _ => recovery.incomplete = true,
+                }
+            }
+            Ok(None) => break,
+            // Damaged pages end the scan, everything before them has been kept
+            Err(_) => {
+                recovery.incomplete = true;
+                break;
+            }
+        }
+    }
+    Some(recovery)
+}
+
+/// The names of the table's columns, in order
+fn column_names(conn: &Connection, table: &str) -> Result<Vec<String>, rusqlite::Error> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({});", table))?;
+    let names = stmt
+        .query_map([], |row| row.get::<usize, String>(1))?
+        .collect();
+    names
+}
+
+/// The write-ahead log and shared memory files SQLite keeps next to a database
+fn sidecar_files(path: &Path) -> [PathBuf; 2] {
+    ["-wal", "-shm"].map(|suffix| {
+        let mut name = path.as_os_str().to_os_string();
+        name.push(suffix);
+        PathBuf::from(name)
+    })
+}
+
+/// Where the damaged database is copied to before it is replaced, next to the original
+fn backup_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(
+        ".corrupt-{}",
+        chrono::Local::now().format("%Y%m%d%H%M%S")
+    ));
+    path.with_file_name(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A path in the temp folder no other test uses
+    fn temp_database() -> PathBuf {
+        std::env::temp_dir().join(format!("ciphey-doctor-{}.sqlite", uuid::Uuid::new_v4()))
+    }
+
+    /// Removes the database and anything the doctor left next to it
+    fn remove_database(path: &Path) {
+        let prefix = path.file_name().unwrap().to_string_lossy().to_string();
+        for entry in std::fs::read_dir(path.parent().unwrap()).unwrap().flatten() {
+            if entry.file_name().to_string_lossy().starts_with(&prefix) {
+                let _ = std::fs::remove_file(entry.path());
+            }
+        }
+    }
+
+    #[test]
+    fn healthy_database_is_left_alone() {
+        let path = temp_database();
+        create_schema(&Connection::open(&path).unwrap()).unwrap();
+
+        let report = diagnose_and_repair(&path).unwrap();
+        assert!(report.healthy);
+        assert!(report.problems.is_empty());
+        assert!(report.repairs.is_empty());
+        remove_database(&path);
+    }
+
+    #[test]
+    fn missing_database_is_not_a_failure() {
+        let path = temp_database();
+        let report = diagnose_and_repair(&path).unwrap();
+        assert!(report.healthy);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn garbage_file_is_backed_up_and_replaced() {
+        let path = temp_database();
+        std::fs::write(
+            &path,
+            "this is not a database, it is just some text ".repeat(200),
+        )
+        .unwrap();
+
+        let report = diagnose_and_repair(&path).unwrap();
+        assert!(report.healthy);
+        assert!(!report.problems.is_empty());
+        assert!(integrity_problems(&path).unwrap().is_empty());
+        assert_eq!(
+            column_names(&Connection::open(&path).unwrap(), "cache").unwrap()[0],
+            "uuid"
+        );
+        remove_database(&path);
+    }
+
+    #[test]
+    fn readable_rows_are_recovered() {
+        let source = temp_database();
+        let destination = temp_database();
+        let old = Connection::open(&source).unwrap();
+        // A settings table from before the timestamp column existed
+        old.execute_batch(
+            "CREATE TABLE settings (kind TEXT NOT NULL, name TEXT NOT NULL, enabled BOOLEAN NOT NULL);
+             INSERT INTO settings VALUES ('decoder', 'Base64', 0), ('checker', 'English Checker', 1);",
+        )
+        .unwrap();
+
+        let recoveries = recover_into(&source, &destination).unwrap();
+        assert_eq!(
+            recoveries,
+            vec![TableRecovery {
+                table: "settings",
+                recovered: 2,
+                incomplete: false
+            }]
+        );
+        let count: i64 = Connection::open(&destination)
+            .unwrap()
+            .query_row("SELECT COUNT(*) FROM settings", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 2);
+        remove_database(&source);
+        remove_database(&destination);
+    }
+}
@@ -0,0 +1,212 @@
+//! Cache of what each decoder made of each text met during a search, kept in the SQLite
+//! edges table. The cache table only maps whole inputs to their plaintext, so an input
+//! which is one character off from one cracked before would otherwise be decoded from
+//! scratch. With the edges cached, every decode it shares with earlier searches is read
+//! back instead of being run again.
+//!
+//! What a decoder made of a text depends on settings such as the Base64 alphabet and the
+//! keys given, and on the version of ciphey, so edges are kept per fingerprint of those. Whether a decode is
+//! plaintext depends on the checkers, which can change between searches, so the decodes
+//! read back are checked again rather than trusting the verdict they were stored with.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, PoisonError};
+
+use log::debug;
+use once_cell::sync::Lazy;
+use sha2::{Digest, Sha256};
+
+use crate::checkers::CheckerTypes;
+use crate::config::{get_config, Config};
+use crate::decoders::crack_results::CrackResult;
+use crate::storage::backend::storage;
+use crate::storage::database::{self, EdgeEntry};
+
+/// How many edges are held in memory before they are written to the database
+pub const MAX_PENDING_EDGES: usize = 1000;
+
+/// Edges met since they were last written to the database
+static PENDING: Lazy<Mutex<Vec<EdgeEntry>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// Fingerprint of the settings which change what decoders make of a text
+/// Settings which only change how the search runs or how its results are shown, such as the
+/// timeout, leave it the same so edges are shared between those searches. A new version of
+/// ciphey changes it, as its decoders may decode differently. Keys are hashed into it and
+/// never stored.
+pub fn settings_fingerprint(config: &Config) -> String {
+    let settings = serde_json::json!([
+        database::CACHE_VERSION,
+        config.base64_alphabet,
+        config.a1z26_delimiters,
+        config.decryption_keys,
+        config.wordlist_path,
+        config.substitution_iterations,
+        config.playfair_iterations,
+        config.enigma_rotor_combinations,
+        config.keyboard_layouts,
+        config.brute_force_attempts,
+    ]);
+    Sha256::digest(settings.to_string().as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// What each decoder made of this text in earlier searches, keyed by the decoder's name
+/// The verdicts they were stored with are stale, so check them again with [`recheck`].
+pub fn lookup(text: &str) -> HashMap<String, CrackResult> {
+    let settings = settings_fingerprint(get_config());
+    match storage().read_edges(text, &settings) {
+        Ok(rows) => rows
+            .into_iter()
+            .filter_map(
+                |row| match serde_json::from_str::<CrackResult>(&row.result) {
+                    Ok(result) => Some((row.decoder, result)),
+                    Err(e) => {
+                        debug!("Ignoring cached {} edge: {}", row.decoder, e);
+                        None
+                    }
+                },
+            )
+            .collect(),
+        Err(e) => {
+            debug!("Could not read cached edges: {}", e);
+            HashMap::new()
+        }
+    }
+}
+
+/// Keeps what a decoder made of a text, to be written to the database with [`flush`]
+/// Once [`MAX_PENDING_EDGES`] have been kept they are written straight away.
+pub fn record(result: &CrackResult) {
+    let Some(edge) = edge(result) else {
+        return;
+    };
+    let full = {
+        let mut pending = PENDING.lock().unwrap_or_else(PoisonError::into_inner);
+        pending.push(edge);
+        pending.len() >= MAX_PENDING_EDGES
+    };
+    if full {
+        flush();
+    }
+}
+
+/// The edge kept for what a decoder made of a text, None if it shouldn't be kept
+/// Results of decoders which ran out of time aren't kept, as a longer search may find more.
+fn edge(result: &CrackResult) -> Option<EdgeEntry> {
+    if result.timed_out {
+        debug!(
+            "Not caching the {} edge, it ran out of time",
+            result.decoder
+        );
+        return None;
+    }
+    let json = result.get_json().ok()?;
+    Some(EdgeEntry {
+        encoded_text: result.encrypted_text.clone(),
+        decoder: result.decoder.to_string(),
+        settings: settings_fingerprint(get_config()),
+        result: json,
+    })
+}
+
+/// Writes the edges met since they were last written to the database
+pub fn flush() {
+    let edges = std::mem::take(&mut *PENDING.lock().unwrap_or_else(PoisonError::into_inner));
+    if edges.is_empty() {
+        return;
+    }
+    if let Err(e) = storage().insert_edges(&edges) {
+        debug!("Could not cache {} edges: {}", edges.len(), e);
+    }
+}
+
+/// Checks the decodes of a cached result again, as the checkers may have changed since
+/// The first decode the checker identifies is moved to the front, like decoders which
+/// return several decodes do, otherwise the result is a failure.
+pub fn recheck(mut result: CrackResult, checker: &CheckerTypes) -> CrackResult {
+    result.success = false;
+    let Some(mut texts) = result.unencrypted_text.take() else {
+        return result;
+    };
+    let mut first_check = None;
+    for (i, text) in texts.iter().enumerate() {
+        let check = checker.check(text);
+        if check.is_identified {
+            texts.rotate_left(i);
+            result.unencrypted_text = Some(texts);
+            result.update_checker(&check);
+            return result;
+        }
+        first_check.get_or_insert(check);
+    }
+    result.unencrypted_text = Some(texts);
+    if let Some(check) = first_check {
+        result.update_checker(&check);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checkers::athena::Athena;
+    use crate::checkers::checker_type::{Check, Checker};
+    use crate::decoders::interface::Decoder;
+
+    #[test]
+    fn rechecking_finds_plaintext_among_the_decodes() {
+        let mut cached = CrackResult::new(&Decoder::default(), "encoded".to_string());
+        cached.success = true;
+        cached.unencrypted_text = Some(vec![
+            "qwzx vbnm plkj".to_string(),
+            "hello my name is bee and i like honey".to_string(),
+        ]);
+        let checker = CheckerTypes::CheckAthena(Checker::<Athena>::new());
+
+        let result = recheck(cached, &checker);
+        assert!(result.success);
+        assert_eq!(
+            result.unencrypted_text.unwrap()[0],
+            "hello my name is bee and i like honey"
+        );
+    }
+
+    #[test]
+    fn rechecking_fails_decodes_which_are_not_plaintext() {
+        let mut cached = CrackResult::new(&Decoder::default(), "encoded".to_string());
+        cached.success = true;
+        cached.unencrypted_text = Some(vec!["qwzx vbnm plkj".to_string()]);
+        let checker = CheckerTypes::CheckAthena(Checker::<Athena>::new());
+
+        assert!(!recheck(cached, &checker).success);
+    }
+
+    #[test]
+    fn results_cut_short_by_the_timeout_are_not_kept() {
+        let mut result = CrackResult::new(&Decoder::default(), "encoded".to_string());
+        assert!(edge(&result).is_some());
+        result.timed_out = true;
+        assert!(edge(&result).is_none());
+    }
+
+    #[test]
+    fn only_decoding_settings_change_the_fingerprint() {
+        let config = Config::default();
+        let fingerprint = settings_fingerprint(&config);
+
+        let slower = Config {
+            timeout: config.timeout + 10,
+            ..Config::default()
+        };
+        assert_eq!(settings_fingerprint(&slower), fingerprint);
+
+        let keyed = Config {
+            decryption_keys: vec!["secret".to_string()],
+            ..Config::default()
+        };
+        assert_ne!(settings_fingerprint(&keyed), fingerprint);
+        assert!(!settings_fingerprint(&keyed).contains("secret"));
+    }
+}
@@ -0,0 +1,171 @@
+//! Keeps a history of every run, for `ciphey history` and `ciphey replay`
+//! Each text ciphey is asked to crack is recorded with how the run ended, the path to the
+//! plaintext, how long each decoder ran and the config it ran with, so analysts can audit
+//! what ciphey did and when, and run it again the same way.
+//!
+//! The history is kept in the SQLite database, so runs with another storage backend aren't
+//! recorded. `history_max_runs` in the config bounds how many runs are kept.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::get_config;
+use crate::storage::backend::uses_sqlite;
+use crate::storage::database::{self, RunEntry, RunRow};
+use crate::{CrackResult, DecoderResult};
+
+/// How many runs the history keeps when the config doesn't say otherwise
+pub const DEFAULT_HISTORY_MAX_RUNS: usize = 10_000;
+
+/// How a run ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunOutcome {
+    /// A search found the plaintext
+    Solved,
+    /// The plaintext was found in the cache
+    Cached,
+    /// The input was already plaintext
+    Plaintext,
+    /// The search ran out of time without finding the plaintext
+    Unsolved,
+    /// The search was cancelled, such as with Ctrl-C
+    Cancelled,
+}
+
+impl RunOutcome {
+    /// How the outcome is stored in the runs table
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RunOutcome::Solved => "solved",
+            RunOutcome::Cached => "cached",
+            RunOutcome::Plaintext => "plaintext",
+            RunOutcome::Unsolved => "unsolved",
+            RunOutcome::Cancelled => "cancelled",
+        }
+    }
+}
+
+impl fmt::Display for RunOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// How many times a decoder ran in one run and for how long
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DecoderTiming {
+    /// How many times the decoder ran
+    pub attempts: i64,
+    /// How long all of those decodes took together, in microseconds
+    pub runtime_micros: i64,
+}
+
+/// Records a run which has ended in the history
+/// Takes the decoder timings of the search the run made, if it made one.
+pub fn record_run(
+    input: &str,
+    started: SystemTime,
+    result: Option<&DecoderResult>,
+    outcome: RunOutcome,
+) {
+    let timings: BTreeMap<String, DecoderTiming> = crate::searchers::take_last_run_stats()
+        .into_iter()
+        .map(|(decoder, (attempts, runtime_micros))| {
+            (
+                decoder,
+                DecoderTiming {
+                    attempts,
+                    runtime_micros,
+                },
+            )
+        })
+        .collect();
+    let config = get_config();
+    if !config.run_history || !uses_sqlite() {
+        return;
+    }
+    let duration_ms = started.elapsed().map_or(-1, |elapsed| {
+        i64::try_from(elapsed.as_millis()).unwrap_or(i64::MAX)
+    });
+    let run = RunEntry {
+        input: input.to_string(),
+        outcome: outcome.to_string(),
+        plaintext: result.and_then(|result| result.text.first().cloned()),
+        path: result.map(|result| result.path.clone()).unwrap_or_default(),
+        decoder_timings: serde_json::to_string(&timings).unwrap_or_else(|_| String::from("{}")),
+        config: serde_json::to_string(config).unwrap_or_else(|_| String::from("{}")),
+        duration_ms,
+    };
+    if let Err(e) = database::insert_run(&run, config.history_max_runs) {
+        log::debug!("Could not record the run in the history: {}", e);
+    }
+}
+
+/// How long each decoder ran in a recorded run, most time first
+pub fn decoder_timings(run: &RunRow) -> Vec<(String, DecoderTiming)> {
+    let timings: BTreeMap<String, DecoderTiming> =
+        serde_json::from_str(&run.decoder_timings).unwrap_or_default();
+    let mut timings: Vec<(String, DecoderTiming)> = timings.into_iter().collect();
+    timings.sort_by_key(|(_, timing)| std::cmp::Reverse(timing.runtime_micros));
+    timings
+}
+
+/// The plaintext and path a recorded run found, None if it found none
+pub fn recorded_result(run: &RunRow) -> Option<DecoderResult> {
+    let plaintext = run.plaintext.clone()?;
+    let path: Vec<CrackResult> = run
+        .path
+        .iter()
+        .filter_map(|step| serde_json::from_str(step).ok())
+        .collect();
+    Some(DecoderResult {
+        text: vec![plaintext],
+        path,
+        bytes: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A recorded run with the given timings and plaintext
+    fn run(decoder_timings: &str, plaintext: Option<&str>) -> RunRow {
+        RunRow {
+            id: 1,
+            input: "aGk=".to_string(),
+            outcome: RunOutcome::Solved.to_string(),
+            plaintext: plaintext.map(str::to_string),
+            path: vec![],
+            decoder_timings: decoder_timings.to_string(),
+            config: "{}".to_string(),
+            duration_ms: 3,
+            version: database::CACHE_VERSION.to_string(),
+            timestamp: "2026-01-01 00:00:00".to_string(),
+        }
+    }
+
+    #[test]
+    fn decoder_timings_are_slowest_first() {
+        let timings = decoder_timings(&run(
+            r#"{"Base64": {"attempts": 2, "runtime_micros": 10}, "Caesar Cipher": {"attempts": 1, "runtime_micros": 90}}"#,
+            None,
+        ));
+        assert_eq!(timings[0].0, "Caesar Cipher");
+        assert_eq!(timings[1].1.attempts, 2);
+        assert!(decoder_timings(&run("not json", None)).is_empty());
+    }
+
+    #[test]
+    fn only_runs_which_found_a_plaintext_have_a_result() {
+        assert!(recorded_result(&run("{}", None)).is_none());
+        assert_eq!(
+            recorded_result(&run("{}", Some("hi"))).unwrap().text,
+            ["hi"]
+        );
+    }
+}
@@ -0,0 +1,267 @@
+//! Size-bounded least recently used cache of decoding results, kept in memory in front of the
+//! SQLite cache table. Repeated lookups while cracking a batch or serving requests are
+//! answered from here without opening the database. It is shared by every thread, and
+//! counts its hits and misses so the stats can show how much it saves.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+
+use crate::DecoderResult;
+
+/// How many results are kept when the config doesn't say otherwise
+pub const DEFAULT_CAPACITY: usize = 1024;
+
+/// The results shared by every thread, keyed by the text that was cracked
+static MEMORY_CACHE: Lazy<Mutex<LruCache<DecoderResult>>> =
+    Lazy::new(|| Mutex::new(LruCache::new(DEFAULT_CAPACITY)));
+
+/// How many lookups were answered from memory
+static HITS: AtomicU64 = AtomicU64::new(0);
+
+/// How many lookups had to go to the database
+static MISSES: AtomicU64 = AtomicU64::new(0);
+
+/// A map which forgets its least recently used entry once it holds `capacity` entries
+pub struct LruCache<V> {
+    /// The most entries to keep, 0 keeps nothing
+    capacity: usize,
+    /// Each value with the tick it was last used at
+    entries: HashMap<String, (V, u64)>,
+    /// Keys ordered by when they were last used, oldest first
+    recency: BTreeMap<u64, String>,
+    /// Goes up on every use, so higher ticks are more recent
+    tick: u64,
+}
+
+impl<V: Clone> LruCache<V> {
+    /// Creates an empty cache holding at most `capacity` entries
+    pub fn new(capacity: usize) -> Self {
+        LruCache {
+            capacity,
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+            tick: 0,
+        }
+    }
+
+    /// Returns the value for the key, marking it as the most recently used
+    pub fn get(&mut self, key: &str) -> Option<V> {
+        let tick = self.next_tick();
+        let (value, last_used) = self.entries.get_mut(key)?;
+        self.recency.remove(last_used);
+        *last_used = tick;
+        self.recency.insert(tick, key.to_string());
+        Some(value.clone())
+    }
+
+    /// Stores the value, forgetting the least recently used entries if the cache is full
+    pub fn put(&mut self, key: &str, value: V) {
+        if self.capacity == 0 {
+            return;
+        }
+        let tick = self.next_tick();
+        if let Some((_, last_used)) = self.entries.insert(key.to_string(), (value, tick)) {
+            self.recency.remove(&last_used);
+        }
+        self.recency.insert(tick, key.to_string());
+        self.shrink_to_capacity();
+    }
+
+    /// Forgets the value for the key
+    pub fn remove(&mut self, key: &str) {
+        if let Some((_, last_used)) = self.entries.remove(key) {
+            self.recency.remove(&last_used);
+        }
+    }
+
+    /// Changes how many entries are kept, forgetting the oldest ones if there are too many
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.shrink_to_capacity();
+    }
+
+    /// How many entries are stored
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// True if nothing is stored
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The most entries this cache keeps
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Forgets everything
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.recency.clear();
+    }
+
+    /// Moves the clock on by one use
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+
+    /// Forgets the least recently used entries until the cache fits its capacity
+    fn shrink_to_capacity(&mut self) {
+        while self.entries.len() > self.capacity {
+            let Some((_, oldest)) = self.recency.pop_first() else {
+                break;
+            };
+            self.entries.remove(&oldest);
+        }
+    }
+}
+
+/// How well the in-memory cache is doing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryCacheStats {
+    /// Lookups answered from memory
+    pub hits: u64,
+    /// Lookups which had to go to the database
+    pub misses: u64,
+    /// Results currently held
+    pub entries: usize,
+    /// The most results held at once
+    pub capacity: usize,
+}
+
+impl MemoryCacheStats {
+    /// The fraction of lookups answered from memory, 0 if nothing has been looked up
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+}
+
+impl std::fmt::Display for MemoryCacheStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "In-memory cache: {} hits, {} misses ({:.1}% hit rate), {} of {} entries used",
+            self.hits,
+            self.misses,
+            self.hit_rate() * 100.0,
+            self.entries,
+            self.capacity
+        )
+    }
+}
+
+/// Looks up the result for the text, counting a hit or a miss
+///
+/// # Panics
+/// Panics if another thread panicked while holding the cache lock.
+pub fn get(text: &str) -> Option<DecoderResult> {
+    let result = MEMORY_CACHE.lock().unwrap().get(text);
+    let counter = if result.is_some() { &HITS } else { &MISSES };
+    counter.fetch_add(1, Ordering::Relaxed);
+    result
+}
+
+/// Remembers the result for the text
+///
+/// # Panics
+/// Panics if another thread panicked while holding the cache lock.
+pub fn put(text: &str, result: DecoderResult) {
+    MEMORY_CACHE.lock().unwrap().put(text, result);
+}
+
+/// Changes how many results are kept in memory, 0 turns the in-memory cache off
+///
+/// # Panics
+/// Panics if another thread panicked while holding the cache lock.
+pub fn set_capacity(capacity: usize) {
+    MEMORY_CACHE.lock().unwrap().set_capacity(capacity);
+}
+
+/// Forgets every result, such as when the database underneath has been wiped
+///
+/// # Panics
+/// Panics if another thread panicked while holding the cache lock.
+pub fn clear() {
+    MEMORY_CACHE.lock().unwrap().clear();
+}
+
+/// The hit and miss counts since the program started, and how full the cache is
+///
+/// # Panics
+/// Panics if another thread panicked while holding the cache lock.
+pub fn stats() -> MemoryCacheStats {
+    let cache = MEMORY_CACHE.lock().unwrap();
+    MemoryCacheStats {
+        hits: HITS.load(Ordering::Relaxed),
+        misses: MISSES.load(Ordering::Relaxed),
+        entries: cache.len(),
+        capacity: cache.capacity(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn least_recently_used_entry_is_forgotten() {
+        let mut cache = LruCache::new(2);
+        cache.put("a", 1);
+        cache.put("b", 2);
+        // Using "a" makes "b" the oldest
+        assert_eq!(cache.get("a"), Some(1));
+        cache.put("c", 3);
+        assert_eq!(cache.get("b"), None);
+        assert_eq!(cache.get("a"), Some(1));
+        assert_eq!(cache.get("c"), Some(3));
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn replacing_a_value_does_not_grow_the_cache() {
+        let mut cache = LruCache::new(2);
+        cache.put("a", 1);
+        cache.put("a", 2);
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.get("a"), Some(2));
+    }
+
+    #[test]
+    fn shrinking_forgets_the_oldest_entries() {
+        let mut cache = LruCache::new(3);
+        cache.put("a", 1);
+        cache.put("b", 2);
+        cache.put("c", 3);
+        cache.set_capacity(1);
+        assert_eq!(cache.get("c"), Some(3));
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn zero_capacity_keeps_nothing() {
+        let mut cache = LruCache::new(0);
+        cache.put("a", 1);
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn hit_rate_counts_hits_against_every_lookup() {
+        let stats = MemoryCacheStats {
+            hits: 3,
+            misses: 1,
+            entries: 0,
+            capacity: 0,
+        };
+        assert_eq!(stats.hit_rate(), 0.75);
+    }
+}
@@ -3,10 +3,28 @@ use std::collections::HashSet;
 use std::fs;
 use std::path::Path;
 
+/// Module for the Storage trait searches read and write through, and its backends
+pub mod backend;
 /// Module housing functions for managing SQLite database
 pub mod database;
+/// Module for diagnosing and repairing the SQLite database
+pub mod doctor;
+/// Module for caching what each decoder made of each text met during a search
+pub mod edge_cache;
+/// Module for the history of every run, for `ciphey history` and `ciphey replay`
+pub mod history;
+/// Module for the in-memory LRU cache in front of the SQLite cache table
+pub mod memory_cache;
+/// Module for scoring text against n-gram frequencies of English and other languages
+pub mod ngrams;
+/// Module for the statistics `ciphey stats` reports from the database
+pub mod stats;
+/// Module for exporting the cache and decoder statistics as JSON or CSV and importing them
+pub mod transfer;
 /// Module for storing WaitAthena results
 pub mod wait_athena_storage;
+/// Module for word lists, the bundled English one and ones loaded from files
+pub mod wordlists;
 
 /// English letter frequency distribution (A-Z)
 /// Used for frequency analysis in various decoders
@@ -0,0 +1,152 @@
+//! Trigram profiles of the languages plaintext is recognised in besides English
+//! Each profile is the set of letter trigrams seen in some prose of the language, with the
+//! start and end of words counted as letters so common prefixes and endings stand out.
+//! Text in a language has most of its trigrams in that language's profile, while ciphertext
+//! and other languages have few. English is profiled too so English text isn't taken for a
+//! language sharing its alphabet.
+
+use once_cell::sync::Lazy;
+use std::collections::HashSet;
+
+/// Marks the start or end of a word in a trigram
+const WORD_BOUNDARY: char = '_';
+
+/// A letter trigram, with word boundaries written as `_`
+pub type Trigram = [char; 3];
+
+/// The letter trigrams seen in prose of one language
+pub struct LanguageProfile {
+    /// The ISO 639-1 code of the language, as passed to `--language`
+    pub code: &'static str,
+    /// The English name of the language
+    pub name: &'static str,
+    /// Every trigram seen in the language's prose
+    trigrams: HashSet<Trigram>,
+}
+
+impl LanguageProfile {
+    /// Builds a profile from lines of a trigram and how often it was seen
+    /// Lines starting with `#`, and trigrams which aren't three characters long, are skipped.
+    pub fn from_counts(code: &'static str, name: &'static str, counts: &str) -> Self {
+        let trigrams = counts
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(|line| {
+                let (trigram, _count) = line.split_once(char::is_whitespace)?;
+                let chars: Vec<char> = trigram.chars().collect();
+                chars.try_into().ok()
+            })
+            .collect();
+        LanguageProfile {
+            code,
+            name,
+            trigrams,
+        }
+    }
+
+    /// How many different trigrams the profile was built from
+    pub fn len(&self) -> usize {
+        self.trigrams.len()
+    }
+
+    /// Whether the profile has no trigrams
+    pub fn is_empty(&self) -> bool {
+        self.trigrams.is_empty()
+    }
+
+    /// The fraction of the trigrams which are in the profile, from 0 to 1
+    pub fn coverage(&self, trigrams: &[Trigram]) -> f64 {
+        if trigrams.is_empty() {
+            return 0.0;
+        }
+        let seen = trigrams
+            .iter()
+            .filter(|trigram| self.trigrams.contains(*trigram))
+            .count();
+        seen as f64 / trigrams.len() as f64
+    }
+}
+
+/// The profiles bundled into the binary, English first, built the first time they are used
+pub static LANGUAGES: Lazy<Vec<LanguageProfile>> = Lazy::new(|| {
+    [
+        ("en", "English", include_str!("english_trigrams.txt")),
+        ("es", "Spanish", include_str!("spanish_trigrams.txt")),
+        ("fr", "French", include_str!("french_trigrams.txt")),
+        ("de", "German", include_str!("german_trigrams.txt")),
+        ("ru", "Russian", include_str!("russian_trigrams.txt")),
+        ("ar", "Arabic", include_str!("arabic_trigrams.txt")),
+    ]
+    .into_iter()
+    .map(|(code, name, counts)| LanguageProfile::from_counts(code, name, counts))
+    .collect()
+});
+
+/// The profile of the language with this code or English name, ignoring case
+pub fn find_language(code_or_name: &str) -> Option<&'static LanguageProfile> {
+    LANGUAGES.iter().find(|language| {
+        language.code.eq_ignore_ascii_case(code_or_name)
+            || language.name.eq_ignore_ascii_case(code_or_name)
+    })
+}
+
+/// The trigrams of every word of the text, lowercased, with anything but letters
+/// separating words
+pub fn trigrams(text: &str) -> Vec<Trigram> {
+    let lowercase = text.to_lowercase();
+    lowercase
+        .split(|c: char| !c.is_alphabetic())
+        .filter(|word| !word.is_empty())
+        .flat_map(|word| {
+            let padded: Vec<char> = std::iter::once(WORD_BOUNDARY)
+                .chain(word.chars())
+                .chain(std::iter::once(WORD_BOUNDARY))
+                .collect();
+            padded
+                .windows(3)
+                .map(|window| [window[0], window[1], window[2]])
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_profiles_are_loaded() {
+        assert_eq!(LANGUAGES.len(), 6);
+        assert!(LANGUAGES.iter().all(|language| language.len() > 500));
+    }
+
+    #[test]
+    fn trigrams_mark_word_boundaries() {
+        assert_eq!(
+            trigrams("Le chat!"),
+            [
+                ['_', 'l', 'e'],
+                ['l', 'e', '_'],
+                ['_', 'c', 'h'],
+                ['c', 'h', 'a'],
+                ['h', 'a', 't'],
+                ['a', 't', '_'],
+            ]
+        );
+    }
+
+    #[test]
+    fn languages_are_found_by_code_or_name() {
+        assert_eq!(find_language("de").unwrap().name, "German");
+        assert_eq!(find_language("russian").unwrap().code, "ru");
+        assert!(find_language("klingon").is_none());
+    }
+
+    #[test]
+    fn text_covers_its_own_language_best() {
+        let text = trigrams("Der Hund meines Nachbarn bellt jede Nacht.");
+        let german = find_language("de").unwrap().coverage(&text);
+        let french = find_language("fr").unwrap().coverage(&text);
+        assert!(german > french, "{} <= {}", german, french);
+    }
+}
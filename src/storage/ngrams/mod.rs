@@ -0,0 +1,150 @@
+//! N-gram models of English, used to score how English-like a run of letters is
+//! Transposition crackers use quadgram scores to rank column orders and rail counts,
+//! and the scores work just as well for checkers wanting a cheap measure of Englishness.
+//! Only ASCII letters are scored, case is ignored and everything else is skipped.
+
+/// Trigram profiles of the languages plaintext is recognised in
+pub mod languages;
+
+use once_cell::sync::Lazy;
+
+/// Quadgram counts bundled into the binary, one `QUADGRAM COUNT` pair per line
+const ENGLISH_QUADGRAM_COUNTS: &str = include_str!("english_quadgrams.txt");
+
+/// The English quadgram model, built the first time it is used
+pub static ENGLISH_QUADGRAMS: Lazy<NgramModel> =
+    Lazy::new(|| NgramModel::from_counts(4, ENGLISH_QUADGRAM_COUNTS));
+
+/// Log probabilities of every n-gram of one length
+pub struct NgramModel {
+    /// How many letters are in each n-gram
+    n: usize,
+    /// The base 10 log probability of every possible n-gram, indexed by its letters packed
+    /// in base 26. Solvers score millions of n-grams, so this is a flat table rather than a
+    /// map, and single precision so more of it fits in the CPU cache.
+    log_probabilities: Vec<f32>,
+    /// How many different n-grams were seen
+    seen: usize,
+    /// The log probability given to n-grams which were never seen
+    floor: f64,
+}
+
+impl NgramModel {
+    /// Builds a model from lines of an n-gram and how often it was seen
+    /// Lines starting with `#`, and n-grams which aren't `n` letters long, are skipped.
+    pub fn from_counts(n: usize, counts: &str) -> Self {
+        let parsed: Vec<(u32, f64)> = counts
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(|line| {
+                let (ngram, count) = line.split_once(char::is_whitespace)?;
+                let letters: Vec<u8> = ngram.bytes().filter_map(letter_index).collect();
+                if letters.len() != n || ngram.len() != n {
+                    return None;
+                }
+                Some((pack(&letters), count.trim().parse().ok()?))
+            })
+            .collect();
+
+        let total: f64 = parsed.iter().map(|(_, count)| count).sum::<f64>().max(1.0);
+        // N-grams seen too rarely to be worth listing are left out of the counts, so
+        // unseen n-grams get half the probability of one seen once rather than nothing
+        let floor = (0.5 / total).log10();
+        let mut log_probabilities = vec![floor as f32; 26usize.pow(n as u32)];
+        for (key, count) in &parsed {
+            log_probabilities[*key as usize] = (count / total).log10() as f32;
+        }
+        NgramModel {
+            n,
+            log_probabilities,
+            seen: parsed.len(),
+            floor,
+        }
+    }
+
+    /// How many letters are in each n-gram
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    /// How many different n-grams the model was built from
+    pub fn seen(&self) -> usize {
+        self.seen
+    }
+
+    /// The total log probability of every n-gram in the letters, from 0 (A) to 25 (Z)
+    /// Higher is more English-like.
+    ///
+    /// # Panics
+    /// Panics if a letter is above 25.
+    pub fn score_letters(&self, letters: &[u8]) -> f64 {
+        letters
+            .windows(self.n)
+            .map(|ngram| f64::from(self.log_probabilities[pack(ngram) as usize]))
+            .sum()
+    }
+
+    /// The average log probability of the n-grams in the text
+    /// Averaging lets texts of different lengths be compared. Text with fewer
+    /// letters than an n-gram gets the score of an unseen n-gram.
+    pub fn score(&self, text: &str) -> f64 {
+        let letters: Vec<u8> = text.bytes().filter_map(letter_index).collect();
+        if letters.len() < self.n {
+            return self.floor;
+        }
+        self.score_letters(&letters) / (letters.len() - self.n + 1) as f64
+    }
+}
+
+/// How English-like the text is going by its quadgrams, higher is better
+/// English usually averages above -6, shuffled letters below -7.
+pub fn quadgram_score(text: &str) -> f64 {
+    ENGLISH_QUADGRAMS.score(text)
+}
+
+/// The number of an ASCII letter, from 0 (A) to 25 (Z), ignoring case
+fn letter_index(byte: u8) -> Option<u8> {
+    byte.is_ascii_alphabetic()
+        .then(|| byte.to_ascii_uppercase() - b'A')
+}
+
+/// Packs letters into one number so n-grams can be looked up in the table
+fn pack(letters: &[u8]) -> u32 {
+    letters
+        .iter()
+        .fold(0, |key, &letter| key * 26 + letter as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_quadgrams_are_loaded() {
+        assert_eq!(ENGLISH_QUADGRAMS.n(), 4);
+        assert!(ENGLISH_QUADGRAMS.seen() > 1000);
+    }
+
+    #[test]
+    fn english_scores_higher_than_shuffled_letters() {
+        let english = quadgram_score("we are discovered flee at once");
+        let shuffled = quadgram_score("wecrlteerdsoeefeaocaivden");
+        assert!(english > shuffled, "{} <= {}", english, shuffled);
+    }
+
+    #[test]
+    fn case_and_punctuation_are_ignored() {
+        assert_eq!(
+            quadgram_score("The quick, brown fox!"),
+            quadgram_score("THEQUICKBROWNFOX")
+        );
+    }
+
+    #[test]
+    fn counts_are_parsed_with_comments_skipped() {
+        let model = NgramModel::from_counts(2, "# comment\nTH 3\nHE 1\nBAD\nTOOLONG 5\n");
+        assert_eq!(model.seen(), 2);
+        assert!(model.score("th") > model.score("he"));
+        assert!(model.score("he") > model.score("qz"));
+    }
+}
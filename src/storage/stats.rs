@@ -0,0 +1,219 @@
+//! Statistics gathered from the database for `ciphey stats`
+//! The database already records how every decoder fared, what was cached and what the
+//! user rejected. This turns those tables into one report, along with how often the
+//! cache answered a text without searching.
+
+use std::fmt;
+use std::sync::atomic::{AtomicI64, Ordering};
+
+use log::debug;
+use serde::Serialize;
+
+use crate::storage::backend::storage;
+use crate::storage::database::{self, CountRow, DecoderStatsRow};
+
+/// Name of the counter of texts answered from the cache
+const CACHE_HITS: &str = "cache_hits";
+
+/// Name of the counter of texts which had to be searched
+const CACHE_MISSES: &str = "cache_misses";
+
+/// How many of the most common first decoders the report lists
+const FIRST_DECODERS_SHOWN: usize = 10;
+
+/// Texts answered from memory since the counters were last written
+/// Memory hits don't open the database, so they are written with the next lookup which does.
+static PENDING_MEMORY_HITS: AtomicI64 = AtomicI64::new(0);
+
+/// Counts a text answered by the in-memory cache
+pub fn record_memory_cache_hit() {
+    PENDING_MEMORY_HITS.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Counts a text looked up in the database cache, along with the memory hits before it
+pub fn record_cache_lookup(hit: bool) {
+    let memory_hits = PENDING_MEMORY_HITS.swap(0, Ordering::Relaxed);
+    let counters = [
+        (CACHE_HITS, memory_hits + i64::from(hit)),
+        (CACHE_MISSES, i64::from(!hit)),
+    ];
+    if let Err(e) = storage().add_counters(&counters) {
+        debug!("Could not count the cache lookup: {}", e);
+    }
+}
+
+/// How one decoder has fared in every search so far
+#[derive(Debug, PartialEq, Serialize)]
+pub struct DecoderSummary {
+    /// Name of the decoder
+    pub decoder: String,
+    /// How many times it ran
+    pub attempts: i64,
+    /// How many of its decodes were on the way to a plaintext, from 0.0 to 1.0
+    pub success_rate: f64,
+    /// How long it took on average, in microseconds
+    pub average_micros: f64,
+}
+
+impl From<&DecoderStatsRow> for DecoderSummary {
+    fn from(row: &DecoderStatsRow) -> Self {
+        let attempts = row.attempts.max(1) as f64;
+        DecoderSummary {
+            decoder: row.decoder.clone(),
+            attempts: row.attempts,
+            success_rate: row.successes as f64 / attempts,
+            average_micros: row.runtime_micros as f64 / attempts,
+        }
+    }
+}
+
+/// Everything `ciphey stats` reports
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct StatsReport {
+    /// Every decoder which has run, most successful first
+    pub decoders: Vec<DecoderSummary>,
+    /// The decoders cached results were first decoded with, most common first
+    pub first_decoders: Vec<CountRow>,
+    /// How many results are cached
+    pub cached_results: i64,
+    /// How long the cached results took to decode on average, in milliseconds
+    pub average_decode_ms: f64,
+    /// How many texts were answered from the cache
+    pub cache_hits: i64,
+    /// How many texts had to be searched
+    pub cache_misses: i64,
+    /// How many plaintexts the user rejected from each checker, most first
+    pub rejections: Vec<CountRow>,
+    /// How many plaintexts the user confirmed from each checker, most first
+    pub confirmations: Vec<CountRow>,
+}
+
+impl StatsReport {
+    /// The share of texts answered from the cache, None if none have been looked up
+    pub fn cache_hit_rate(&self) -> Option<f64> {
+        let lookups = self.cache_hits + self.cache_misses;
+        (lookups > 0).then(|| self.cache_hits as f64 / lookups as f64)
+    }
+}
+
+/// Reads the report from the database
+///
+/// # Errors
+/// Returns a rusqlite::Error if the database can't be read.
+pub fn gather() -> Result<StatsReport, rusqlite::Error> {
+    let mut decoders: Vec<DecoderSummary> = database::read_decoder_stats()?
+        .iter()
+        .map(DecoderSummary::from)
+        .collect();
+    decoders.sort_by(|a, b| {
+        b.success_rate
+            .total_cmp(&a.success_rate)
+            .then(b.attempts.cmp(&a.attempts))
+    });
+    let (cached_results, average_decode_ms) = database::read_cache_summary()?;
+    Ok(StatsReport {
+        decoders,
+        first_decoders: database::read_first_decoder_counts(FIRST_DECODERS_SHOWN)?,
+        cached_results,
+        average_decode_ms,
+        cache_hits: database::read_counter(CACHE_HITS)?,
+        cache_misses: database::read_counter(CACHE_MISSES)?,
+        rejections: database::read_rejection_counts()?,
+        confirmations: database::read_confirmation_counts()?,
+    })
+}
+
+impl fmt::Display for StatsReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Cache")?;
+        writeln!(
+            f,
+            "  {} results cached, which took {:.0} ms on average to decode",
+            self.cached_results, self.average_decode_ms
+        )?;
+        match self.cache_hit_rate() {
+            Some(rate) => writeln!(
+                f,
+                "  {:.1}% of {} texts were answered from the cache",
+                rate * 100.0,
+                self.cache_hits + self.cache_misses
+            )?,
+            None => writeln!(f, "  No texts have been looked up yet")?,
+        }
+
+        writeln!(f, "\nMost common first decoders")?;
+        if self.first_decoders.is_empty() {
+            writeln!(f, "  None yet")?;
+        }
+        for row in &self.first_decoders {
+            writeln!(f, "  {:<28} {}", row.name, row.count)?;
+        }
+
+        writeln!(f, "\nDecoders")?;
+        if self.decoders.is_empty() {
+            writeln!(f, "  None have run yet")?;
+        } else {
+            writeln!(
+                f,
+                "  {:<28} {:>10} {:>10} {:>12}",
+                "Decoder", "Runs", "Success", "Average"
+            )?;
+        }
+        for decoder in &self.decoders {
+            writeln!(
+                f,
+                "  {:<28} {:>10} {:>9.1}% {:>9.0} µs",
+                decoder.decoder,
+                decoder.attempts,
+                decoder.success_rate * 100.0,
+                decoder.average_micros
+            )?;
+        }
+
+        write!(f, "\nPlaintexts rejected with the human checker")?;
+        if self.rejections.is_empty() {
+            write!(f, "\n  None")?;
+        }
+        for row in &self.rejections {
+            write!(f, "\n  {:<28} {}", row.name, row.count)?;
+        }
+
+        write!(f, "\n\nPlaintexts confirmed with the human checker")?;
+        if self.confirmations.is_empty() {
+            write!(f, "\n  None")?;
+        }
+        for row in &self.confirmations {
+            write!(f, "\n  {:<28} {}", row.name, row.count)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decoder_summaries_average_over_their_runs() {
+        let row = DecoderStatsRow {
+            decoder: "Base64".to_string(),
+            attempts: 4,
+            successes: 1,
+            runtime_micros: 200,
+            timestamp: String::new(),
+        };
+        let summary = DecoderSummary::from(&row);
+        assert_eq!(summary.success_rate, 0.25);
+        assert_eq!(summary.average_micros, 50.0);
+    }
+
+    #[test]
+    fn hit_rate_is_unknown_until_a_text_is_looked_up() {
+        let mut report = StatsReport::default();
+        assert_eq!(report.cache_hit_rate(), None);
+        report.cache_hits = 3;
+        report.cache_misses = 1;
+        assert_eq!(report.cache_hit_rate(), Some(0.75));
+        assert!(report.to_string().contains("75.0% of 4 texts"));
+    }
+}
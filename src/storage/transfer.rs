@@ -0,0 +1,369 @@
+//! Exports the cache and decoder statistics as JSON or CSV, and imports them back
+//! Teams share a warmed cache by importing one export into each of their databases,
+//! and analysts load the CSV into a spreadsheet or pandas.
+//!
+//! Every export is tagged with [`EXPORT_SCHEMA_VERSION`]. Exports from older versions are
+//! imported with anything they lack filled in with defaults, and exports from newer
+//! versions are refused rather than guessed at. Cached results keep the version of ciphey
+//! which found them, so results imported from another version are only used by that one.
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::storage::database::{self, DecoderStatsRow, ExportedCacheRow};
+
+/// The version of the export format, raised whenever a column is added or changed
+pub const EXPORT_SCHEMA_VERSION: u32 = 1;
+
+/// The formats the cache can be exported in
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    /// A single JSON document with every table
+    Json,
+    /// Comma-separated values, one table per file
+    Csv,
+}
+
+/// The tables which can be exported as CSV, where each file holds one
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ExportTable {
+    /// The cached results
+    Cache,
+    /// How each decoder has fared
+    DecoderStats,
+}
+
+/// Everything an export holds
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Export {
+    /// The version of the export format it was written in
+    pub schema_version: u32,
+    /// The version of ciphey which wrote it
+    pub ciphey_version: String,
+    /// The cached results
+    pub cache: Vec<ExportedCacheRow>,
+    /// How each decoder has fared
+    pub decoder_stats: Vec<DecoderStatsRow>,
+}
+
+/// How much an import added
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ImportReport {
+    /// Cached results added
+    pub results: usize,
+    /// Decoders whose statistics were added to
+    pub decoders: usize,
+}
+
+/// The columns of the cache table in CSV exports
+const CACHE_COLUMNS: [&str; 13] = [
+    "schema_version",
+    "uuid",
+    "encoded_text",
+    "decoded_text",
+    "path",
+    "successful",
+    "execution_time_ms",
+    "timestamp",
+    "checker",
+    "sensitivity",
+    "confidence",
+    "version",
+    "registry",
+];
+
+/// The columns of the decoder statistics in CSV exports
+const DECODER_STATS_COLUMNS: [&str; 6] = [
+    "schema_version",
+    "decoder",
+    "attempts",
+    "successes",
+    "runtime_micros",
+    "timestamp",
+];
+
+/// Reads everything an export holds from the database
+///
+/// # Errors
+/// Returns a rusqlite::Error if the database can't be read.
+pub fn read_export() -> Result<Export, rusqlite::Error> {
+    Ok(Export {
+        schema_version: EXPORT_SCHEMA_VERSION,
+        ciphey_version: database::CACHE_VERSION.to_string(),
+        cache: database::read_cache_for_export()?,
+        decoder_stats: database::read_decoder_stats()?,
+    })
+}
+
+/// Writes an export as JSON, or one of its tables as CSV
+///
+/// # Panics
+/// Never, as every field of an export serialises to JSON.
+pub fn render(export: &Export, format: ExportFormat, table: ExportTable) -> String {
+    match format {
+        ExportFormat::Json => {
+            serde_json::to_string_pretty(export).expect("Exports should serialise to JSON")
+        }
+        ExportFormat::Csv => match table {
+            ExportTable::Cache => render_csv(
+                &CACHE_COLUMNS,
+                export.cache.iter().map(|row| {
+                    vec![
+                        export.schema_version.to_string(),
+                        row.uuid.clone(),
+                        row.encoded_text.clone(),
+                        row.decoded_text.clone(),
+                        row.path.clone(),
+                        row.successful.to_string(),
+                        row.execution_time_ms.to_string(),
+                        row.timestamp.clone(),
+                        row.checker.clone(),
+                        row.sensitivity.clone().unwrap_or_default(),
+                        row.confidence.to_string(),
+                        row.version.clone(),
+                        row.registry.clone(),
+                    ]
+                }),
+            ),
+            ExportTable::DecoderStats => render_csv(
+                &DECODER_STATS_COLUMNS,
+                export.decoder_stats.iter().map(|row| {
+                    vec![
+                        export.schema_version.to_string(),
+                        row.decoder.clone(),
+                        row.attempts.to_string(),
+                        row.successes.to_string(),
+                        row.runtime_micros.to_string(),
+                        row.timestamp.clone(),
+                    ]
+                }),
+            ),
+        },
+    }
+}
+
+/// Reads an export written by [`render`]
+/// A CSV file is recognised as the cache or the decoder statistics by its columns.
+///
+/// # Errors
+/// Returns an error message if the export can't be read or is from a newer ciphey.
+pub fn parse(contents: &str, format: ExportFormat) -> Result<Export, String> {
+    let export = match format {
+        ExportFormat::Json => serde_json::from_str::<Export>(contents)
+            .map_err(|e| format!("The export isn't valid JSON: {}", e))?,
+        ExportFormat::Csv => parse_csv_export(contents)?,
+    };
+    if export.schema_version > EXPORT_SCHEMA_VERSION {
+        return Err(format!(
+            "The export is in version {} of the format, but this ciphey only reads up to version {}. Upgrade ciphey to import it.",
+            export.schema_version, EXPORT_SCHEMA_VERSION
+        ));
+    }
+    Ok(export)
+}
+
+/// Adds an export to the database
+/// Cached results already in it are skipped, and decoder statistics are added to the ones
+/// already recorded, so importing the same export twice counts its statistics twice.
+///
+/// # Errors
+/// Returns a rusqlite::Error if the database can't be written to.
+pub fn import(export: &Export) -> Result<ImportReport, rusqlite::Error> {
+    let results = database::import_cache_rows(&export.cache)?;
+    for row in &export.decoder_stats {
+        database::add_decoder_stats(
+            &row.decoder,
+            row.attempts,
+            row.successes,
+            row.runtime_micros,
+        )?;
+    }
+    Ok(ImportReport {
+        results,
+        decoders: export.decoder_stats.len(),
+    })
+}
+
+/// Writes a header and rows as CSV, quoting fields which need it
+fn render_csv(columns: &[&str], rows: impl Iterator<Item = Vec<String>>) -> String {
+    let mut csv = columns.join(",");
+    csv.push('\n');
+    for row in rows {
+        let fields: Vec<String> = row.iter().map(|field| quote_csv_field(field)).collect();
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Quotes a CSV field if it holds a comma, quote or line break
+pub fn quote_csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Splits CSV into records of fields, undoing the quoting of [`quote_csv_field`]
+fn parse_csv(contents: &str) -> Result<Vec<Vec<String>>, String> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = contents.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' if quoted => quoted = false,
+            '"' if field.is_empty() => quoted = true,
+            ',' if !quoted => record.push(std::mem::take(&mut field)),
+            '\r' if !quoted => {}
+            '\n' if !quoted => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            _ => field.push(c),
+        }
+    }
+    if quoted {
+        return Err(String::from("The CSV ends inside a quoted field"));
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    Ok(records)
+}
+
+/// Reads a CSV export of either table
+fn parse_csv_export(contents: &str) -> Result<Export, String> {
+    let mut records = parse_csv(contents)?.into_iter();
+    let header = records
+        .next()
+        .ok_or_else(|| String::from("The CSV is empty"))?;
+    let column = |name: &str| header.iter().position(|column| column == name);
+    let is_cache = column("encoded_text").is_some();
+    if !is_cache && column("decoder").is_none() {
+        return Err(String::from(
+            "The CSV has neither the cache's nor the decoder statistics' columns",
+        ));
+    }
+
+    let mut export = Export::default();
+    for record in records {
+        let field = |name: &str| {
+            column(name)
+                .and_then(|i| record.get(i))
+                .cloned()
+                .unwrap_or_default()
+        };
+        let number = |name: &str| field(name).parse::<i64>().unwrap_or_default();
+        let schema_version = field("schema_version").parse().unwrap_or(1);
+        export.schema_version = export.schema_version.max(schema_version);
+        if is_cache {
+            let sensitivity = field("sensitivity");
+            export.cache.push(ExportedCacheRow {
+                uuid: field("uuid"),
+                encoded_text: field("encoded_text"),
+                decoded_text: field("decoded_text"),
+                path: field("path"),
+                successful: field("successful") != "false",
+                execution_time_ms: number("execution_time_ms"),
+                timestamp: field("timestamp"),
+                checker: field("checker"),
+                sensitivity: (!sensitivity.is_empty()).then_some(sensitivity),
+                confidence: field("confidence").parse().unwrap_or_default(),
+                version: field("version"),
+                registry: field("registry"),
+            });
+        } else {
+            export.decoder_stats.push(DecoderStatsRow {
+                decoder: field("decoder"),
+                attempts: number("attempts"),
+                successes: number("successes"),
+                runtime_micros: number("runtime_micros"),
+                timestamp: field("timestamp"),
+            });
+        }
+    }
+    Ok(export)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An export with one awkward cached result and one decoder
+    fn sample_export() -> Export {
+        Export {
+            schema_version: EXPORT_SCHEMA_VERSION,
+            ciphey_version: database::CACHE_VERSION.to_string(),
+            cache: vec![ExportedCacheRow {
+                uuid: "6a7c7a76-8a8f-4c1c-a8a4-3f0f46f3a1b5".to_string(),
+                encoded_text: "aGVsbG8sIHdvcmxk".to_string(),
+                decoded_text: "hello, \"world\"\nagain".to_string(),
+                path: "[\"{\\\"decoder\\\":\\\"Base64\\\"}\"]".to_string(),
+                successful: true,
+                execution_time_ms: 12,
+                timestamp: "2026-01-01 00:00:00".to_string(),
+                checker: "English Checker".to_string(),
+                sensitivity: Some("Medium".to_string()),
+                confidence: 0.5,
+                version: database::CACHE_VERSION.to_string(),
+                registry: "abc".to_string(),
+            }],
+            decoder_stats: vec![DecoderStatsRow {
+                decoder: "Base64".to_string(),
+                attempts: 10,
+                successes: 4,
+                runtime_micros: 250,
+                timestamp: "2026-01-01 00:00:00".to_string(),
+            }],
+        }
+    }
+
+    #[test]
+    fn json_exports_round_trip() {
+        let export = sample_export();
+        let json = render(&export, ExportFormat::Json, ExportTable::Cache);
+        assert_eq!(parse(&json, ExportFormat::Json).unwrap(), export);
+    }
+
+    #[test]
+    fn csv_exports_round_trip_one_table_at_a_time() {
+        let export = sample_export();
+
+        let cache_csv = render(&export, ExportFormat::Csv, ExportTable::Cache);
+        let cache = parse(&cache_csv, ExportFormat::Csv).unwrap();
+        assert_eq!(cache.cache, export.cache);
+        assert!(cache.decoder_stats.is_empty());
+
+        let stats_csv = render(&export, ExportFormat::Csv, ExportTable::DecoderStats);
+        let stats = parse(&stats_csv, ExportFormat::Csv).unwrap();
+        assert_eq!(stats.decoder_stats, export.decoder_stats);
+        assert!(stats.cache.is_empty());
+    }
+
+    #[test]
+    fn exports_from_older_versions_fill_in_missing_columns() {
+        let csv = "decoder,attempts\nBase64,3\n";
+        let export = parse(csv, ExportFormat::Csv).unwrap();
+        assert_eq!(export.schema_version, 1);
+        assert_eq!(export.decoder_stats[0].attempts, 3);
+        assert_eq!(export.decoder_stats[0].successes, 0);
+    }
+
+    #[test]
+    fn exports_from_newer_versions_are_refused() {
+        let json = format!(
+            "{{\"schema_version\": {}, \"cache\": []}}",
+            EXPORT_SCHEMA_VERSION + 1
+        );
+        assert!(parse(&json, ExportFormat::Json).is_err());
+    }
+}
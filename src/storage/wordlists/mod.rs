@@ -0,0 +1,140 @@
+//! Word lists, for decoders and checkers which need to know whether something is a word
+//! A list remembers the order its words came in, so it can be asked which of several
+//! words is the more likely one as well as whether a word is there at all. The bundled
+//! English list is ordered by how common each word is, the S/KEY list by the bits each
+//! word stands for, and lists loaded from a file keep the order of the file.
+
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+
+/// Common English words bundled into the binary, one per line and most common first
+const ENGLISH_WORD_LINES: &str = include_str!("english_words.txt");
+
+/// The bundled list of common English words, built the first time it is used
+pub static ENGLISH_WORDS: Lazy<Wordlist> = Lazy::new(|| Wordlist::from_lines(ENGLISH_WORD_LINES));
+
+/// The 2048 words of the RFC 2289 S/KEY dictionary in order, one per line
+const SKEY_WORD_LINES: &str = include_str!("skey_words.txt");
+
+/// The S/KEY dictionary, where each word's rank is the 11 bits it stands for
+pub static SKEY_WORDS: Lazy<Wordlist> = Lazy::new(|| Wordlist::from_lines(SKEY_WORD_LINES));
+
+/// A list of words in order of preference
+pub struct Wordlist {
+    /// Every word, in the order they were given
+    words: Vec<String>,
+    /// Where each word first appears in `words`
+    ranks: HashMap<String, usize>,
+}
+
+impl Wordlist {
+    /// Builds a list from the words in the order given, a repeated word keeps its first place
+    pub fn from_words<I, S>(words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut list = Wordlist {
+            words: Vec::new(),
+            ranks: HashMap::new(),
+        };
+        for word in words {
+            let word = word.into();
+            if word.is_empty() || list.ranks.contains_key(&word) {
+                continue;
+            }
+            list.ranks.insert(word.clone(), list.words.len());
+            list.words.push(word);
+        }
+        list
+    }
+
+    /// Builds a list from text with one word per line
+    /// Lines are trimmed, and blank lines and lines starting with `#` are skipped.
+    pub fn from_lines(lines: &str) -> Self {
+        Wordlist::from_words(
+            lines
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#')),
+        )
+    }
+
+    /// Whether the word is in the list, exactly as written
+    pub fn contains(&self, word: &str) -> bool {
+        self.ranks.contains_key(word)
+    }
+
+    /// Where the word is in the list, 0 for the first and most preferred word
+    pub fn rank(&self, word: &str) -> Option<usize> {
+        self.ranks.get(word).copied()
+    }
+
+    /// How many of the words of the text are in the list
+    /// Words are split on whitespace, lose any ASCII punctuation at their ends and are
+    /// looked up in lowercase, which suits lists of lowercase words like the bundled one.
+    pub fn count_words_in(&self, text: &str) -> usize {
+        text.split_whitespace()
+            .filter(|word| {
+                let word = word.trim_matches(|c: char| c.is_ascii_punctuation());
+                self.contains(&word.to_lowercase())
+            })
+            .count()
+    }
+
+    /// Every word in the list, in order
+    pub fn words(&self) -> impl Iterator<Item = &str> {
+        self.words.iter().map(String::as_str)
+    }
+
+    /// How many words are in the list
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Whether the list has no words
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_words_are_loaded() {
+        assert!(ENGLISH_WORDS.len() > 5000);
+        assert_eq!(ENGLISH_WORDS.rank("the"), Some(0));
+        assert!(ENGLISH_WORDS.contains("hello"));
+        assert!(!ENGLISH_WORDS.contains("# Common"));
+    }
+
+    #[test]
+    fn skey_words_are_loaded() {
+        assert_eq!(SKEY_WORDS.len(), 2048);
+        assert_eq!(SKEY_WORDS.rank("A"), Some(0));
+        assert_eq!(SKEY_WORDS.rank("ABED"), Some(571));
+        assert_eq!(SKEY_WORDS.rank("YOKE"), Some(2047));
+    }
+
+    #[test]
+    fn common_words_rank_higher() {
+        let good = ENGLISH_WORDS.rank("good").unwrap();
+        let hood = ENGLISH_WORDS.rank("hood").unwrap_or(usize::MAX);
+        assert!(good < hood);
+    }
+
+    #[test]
+    fn words_in_text_are_counted() {
+        assert_eq!(ENGLISH_WORDS.count_words_in("Hello, World! xqzv h3llo"), 2);
+    }
+
+    #[test]
+    fn repeated_words_keep_their_first_place() {
+        let list = Wordlist::from_lines("b\na\n\n# comment\n b \nc");
+        assert_eq!(list.words().collect::<Vec<_>>(), ["b", "a", "c"]);
+        assert_eq!(list.rank("c"), Some(2));
+        assert!(!list.contains("# comment"));
+    }
+}
@@ -1,30 +1,77 @@
 use crossbeam::channel::{bounded, Receiver};
 use std::sync::atomic::Ordering::Relaxed;
 use std::{
-    sync::atomic::AtomicBool,
+    sync::{atomic::AtomicU64, Mutex},
     thread::{self, sleep},
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use crate::cli_pretty_printing::{countdown_until_program_ends, display_top_results};
 use crate::config::get_config;
 use crate::storage::wait_athena_storage;
 
-/// Indicate whether timer is paused
-static PAUSED: AtomicBool = AtomicBool::new(false);
+/// When the running timer expires, None if no timer has been started
+static DEADLINE: Mutex<Option<Deadline>> = Mutex::new(None);
+
+/// How many timers have been started, used to tell deadlines apart
+static TIMERS_STARTED: AtomicU64 = AtomicU64::new(0);
+
+/// When a timer expires
+#[derive(Clone, Copy, Debug)]
+struct Deadline {
+    /// Which timer this deadline belongs to, so an old timer can't clear a newer one's deadline
+    id: u64,
+    /// When the timer expires
+    expires_at: Instant,
+}
+
+impl Deadline {
+    /// A deadline `duration` after `now`
+    fn new(id: u64, now: Instant, duration: Duration) -> Self {
+        Deadline {
+            id,
+            expires_at: now + duration,
+        }
+    }
+
+    /// How long is left
+    fn remaining(&self, now: Instant) -> Duration {
+        self.expires_at.saturating_duration_since(now)
+    }
+}
+
+/// How long until the running timer expires, None if no timer has been started
+/// Decoders use this through `Crack::remaining_time` to budget expensive work.
+pub fn remaining_time() -> Option<Duration> {
+    let deadline = DEADLINE.lock().ok()?;
+    deadline.map(|deadline| deadline.remaining(Instant::now()))
+}
 
 /// Start the timer with duration in seconds
 pub fn start(duration: u32) -> Receiver<()> {
+    let id = TIMERS_STARTED.fetch_add(1, Relaxed);
+    if let Ok(mut deadline) = DEADLINE.lock() {
+        *deadline = Some(Deadline::new(
+            id,
+            Instant::now(),
+            Duration::from_secs(duration.into()),
+        ));
+    }
     let (sender, recv) = bounded(1);
     thread::spawn(move || {
         let mut time_spent = 0;
 
         while time_spent < duration {
-            if !PAUSED.load(Relaxed) {
-                sleep(Duration::from_secs(1));
-                time_spent += 1;
-                // Some pretty printing support
-                countdown_until_program_ends(time_spent, duration);
+            sleep(Duration::from_secs(1));
+            time_spent += 1;
+            // Some pretty printing support
+            countdown_until_program_ends(time_spent, duration);
+        }
+
+        // No search is running any more, unless a newer timer has been started since
+        if let Ok(mut deadline) = DEADLINE.lock() {
+            if deadline.is_some_and(|deadline| deadline.id == id) {
+                *deadline = None;
             }
         }
 
@@ -69,12 +116,22 @@ fn filter_and_display_results() {
     display_top_results(&results);
 }
 
-/// Pause timer
-pub fn pause() {
-    PAUSED.store(true, Relaxed);
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-/// Resume timer
-pub fn resume() {
-    PAUSED.store(false, Relaxed);
+    #[test]
+    fn deadline_counts_down() {
+        let now = Instant::now();
+        let deadline = Deadline::new(0, now, Duration::from_secs(5));
+        assert_eq!(deadline.remaining(now), Duration::from_secs(5));
+        assert_eq!(
+            deadline.remaining(now + Duration::from_secs(2)),
+            Duration::from_secs(3)
+        );
+        assert_eq!(
+            deadline.remaining(now + Duration::from_secs(9)),
+            Duration::ZERO
+        );
+    }
 }
@@ -0,0 +1,333 @@
+//! Cracks lines as they are written to a file, a FIFO or stdin, for `ciphey watch`
+//! A reader thread follows the source and hands each new line to the cracking loop over a
+//! bounded queue. When cracking falls behind the queue fills and the reader stops reading,
+//! so a log shipper piping into ciphey is slowed down rather than ciphey holding every
+//! line it hasn't got to yet in memory. Each line is searched for at most the config's
+//! `timeout`, like any other input.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{sync_channel, Receiver, SyncSender};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::batch::BatchItem;
+use crate::config::Config;
+use crate::{crack_prepared_text, prepare_cracking, CancellationToken};
+
+/// How many lines wait to be cracked before the reader stops reading
+pub const DEFAULT_QUEUE_SIZE: usize = 64;
+
+/// How many milliseconds pass before a file which had nothing new in it is checked again
+pub const DEFAULT_POLL_MS: u64 = 250;
+
+/// What is watched for new lines
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WatchSource {
+    /// A path, which is followed like `tail -F` if it is a regular file and read until it
+    /// closes if it is anything else, such as a FIFO
+    Path(PathBuf),
+    /// Whatever is piped into ciphey
+    Stdin,
+}
+
+/// How a source is watched
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WatchOptions {
+    /// Crack the lines already in a regular file, instead of only the ones written after
+    /// watching starts
+    pub from_start: bool,
+    /// How often a regular file is checked for new lines
+    pub poll_interval: Duration,
+    /// How many lines wait to be cracked before the reader stops reading
+    pub queue_size: usize,
+}
+
+impl Default for WatchOptions {
+    fn default() -> Self {
+        WatchOptions {
+            from_start: false,
+            poll_interval: Duration::from_millis(DEFAULT_POLL_MS),
+            queue_size: DEFAULT_QUEUE_SIZE,
+        }
+    }
+}
+
+/// Errors from starting to watch a source
+#[derive(Debug)]
+pub enum WatchError {
+    /// The path couldn't be opened
+    Unreadable {
+        /// The path which was watched
+        path: PathBuf,
+        /// Why it couldn't be opened
+        reason: String,
+    },
+}
+
+impl std::fmt::Display for WatchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WatchError::Unreadable { path, reason } => {
+                write!(f, "Can't watch '{}': {}", path.display(), reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for WatchError {}
+
+/// Starts a thread reading lines from `source` into a queue of `options.queue_size`
+///
+/// Blank lines are skipped and line endings are removed. The thread stops when the source
+/// ends, `stop` is cancelled or the receiver is dropped. A regular file never ends, it is
+/// followed until `stop` is cancelled, and is read from the start again if it is truncated
+/// or replaced by a shorter file, as log rotation does.
+///
+/// # Errors
+/// Returns `WatchError::Unreadable` if the path can't be opened.
+pub fn spawn_line_reader(
+    source: WatchSource,
+    options: WatchOptions,
+    stop: &CancellationToken,
+) -> Result<(Receiver<String>, JoinHandle<()>), WatchError> {
+    let (sender, receiver) = sync_channel(options.queue_size.max(1));
+    let stop = stop.clone();
+    let handle = match source {
+        WatchSource::Stdin => std::thread::spawn(move || {
+            read_stream(std::io::stdin().lock(), &sender, &stop);
+        }),
+        WatchSource::Path(path) => {
+            let unreadable = |e: std::io::Error| WatchError::Unreadable {
+                path: path.clone(),
+                reason: e.to_string(),
+            };
+            let file = File::open(&path).map_err(unreadable)?;
+            let is_file = file.metadata().map_err(unreadable)?.is_file();
+            if is_file {
+                std::thread::spawn(move || follow_file(&path, file, options, &sender, &stop))
+            } else {
+                std::thread::spawn(move || read_stream(BufReader::new(file), &sender, &stop))
+            }
+        }
+    };
+    Ok((receiver, handle))
+}
+
+/// Sends the line if it isn't blank, returning false once nothing is receiving
+fn send_line(line: &str, sender: &SyncSender<String>) -> bool {
+    let line = line.trim_end_matches(['\n', '\r']);
+    if line.trim().is_empty() {
+        return true;
+    }
+    sender.send(line.to_string()).is_ok()
+}
+
+/// Sends every line of a stream until it ends
+fn read_stream(mut reader: impl BufRead, sender: &SyncSender<String>, stop: &CancellationToken) {
+    let mut line = String::new();
+    while !stop.is_cancelled() {
+        line.clear();
+        match reader.read_line(&mut line) {
+            Ok(0) => return,
+            Ok(_) => {
+                if !send_line(&line, sender) {
+                    return;
+                }
+            }
+            Err(e) => {
+                log::warn!("Stopped watching after failing to read a line: {}", e);
+                return;
+            }
+        }
+    }
+}
+
+/// Sends every line written to a regular file until `stop` is cancelled
+/// A line is only sent once its line break has been written, so a line written in
+/// several goes is cracked whole.
+fn follow_file(
+    path: &Path,
+    file: File,
+    options: WatchOptions,
+    sender: &SyncSender<String>,
+    stop: &CancellationToken,
+) {
+    let mut reader = BufReader::new(file);
+    let mut position = if options.from_start {
+        0
+    } else {
+        reader.seek(SeekFrom::End(0)).unwrap_or(0)
+    };
+    let mut line = String::new();
+    while !stop.is_cancelled() {
+        match reader.read_line(&mut line) {
+            Ok(0) => {
+                // Nothing new, so check whether the file was truncated or rotated
+                let length = std::fs::metadata(path).map_or(position, |metadata| metadata.len());
+                if length < position {
+                    log::debug!("{} got shorter, reading it from the start", path.display());
+                    match File::open(path) {
+                        Ok(file) => reader = BufReader::new(file),
+                        Err(e) => log::debug!("Could not reopen {}: {}", path.display(), e),
+                    }
+                    position = 0;
+                    line.clear();
+                    continue;
+                }
+                std::thread::sleep(options.poll_interval);
+            }
+            Ok(read) => {
+                position += read as u64;
+                if line.ends_with('\n') {
+                    if !send_line(&line, sender) {
+                        return;
+                    }
+                    line.clear();
+                }
+            }
+            Err(e) => {
+                log::warn!("Stopped watching {}: {}", path.display(), e);
+                return;
+            }
+        }
+    }
+}
+
+/// Cracks every line received until the reader stops or `stop` is cancelled, calling
+/// `on_item` with each result
+///
+/// Like a batch, the human checker, `top_results` and `all_results` are turned off. Lines
+/// longer than `max_input_bytes` are skipped. Lines seen before are answered by the cache
+/// without searching again.
+/// ```rust
+/// use ciphey::config::Config;
+/// use ciphey::watch::perform_watch;
+/// use ciphey::CancellationToken;
+/// # let _test_db = ciphey::TestDatabase::default();
+/// # ciphey::set_test_db_path();
+/// let (sender, receiver) = std::sync::mpsc::sync_channel(1);
+/// std::thread::spawn(move || sender.send(String::from("aGVsbG8gdGhlcmUgZ2VuZXJhbA==")));
+/// let mut plaintexts = Vec::new();
+/// perform_watch(&receiver, Config::default(), &CancellationToken::new(), |item| {
+///     plaintexts.push(item.result.unwrap().text[0].clone())
+/// });
+/// assert_eq!(plaintexts, ["hello there general"]);
+/// ```
+pub fn perform_watch(
+    lines: &Receiver<String>,
+    config: Config,
+    stop: &CancellationToken,
+    mut on_item: impl FnMut(BatchItem),
+) {
+    let mut config = config;
+    config.human_checker_on = false;
+    config.top_results = false;
+    config.all_results = false;
+    let max_input_bytes = config.max_input_bytes;
+    prepare_cracking(config);
+
+    for line in lines {
+        if stop.is_cancelled() {
+            return;
+        }
+        if max_input_bytes != 0 && line.len() > max_input_bytes {
+            log::warn!(
+                "Skipped a line of {} bytes, longer than max_input_bytes",
+                line.len()
+            );
+            continue;
+        }
+        let start = Instant::now();
+        let (result, outcome) = crack_prepared_text(&line, SystemTime::now(), stop);
+        on_item(BatchItem {
+            input: line,
+            result,
+            outcome,
+            duration: start.elapsed(),
+            deduplicated: false,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    /// Options which check a file for new lines often, so the tests don't wait long
+    fn quick_options(from_start: bool) -> WatchOptions {
+        WatchOptions {
+            from_start,
+            poll_interval: Duration::from_millis(10),
+            queue_size: 4,
+        }
+    }
+
+    #[test]
+    fn streams_are_read_line_by_line_without_blank_lines() {
+        let (sender, receiver) = sync_channel(8);
+        read_stream(
+            &b"aGk=\r\n\n   \nbXk=\nno newline"[..],
+            &sender,
+            &CancellationToken::new(),
+        );
+        drop(sender);
+        let lines: Vec<String> = receiver.iter().collect();
+        assert_eq!(lines, ["aGk=", "bXk=", "no newline"]);
+    }
+
+    #[test]
+    fn files_are_followed_as_they_grow_and_after_truncation() {
+        let path = std::env::temp_dir().join("ciphey_watch_follow_test.log");
+        std::fs::write(&path, "already here\n").unwrap();
+        let stop = CancellationToken::new();
+        let (receiver, handle) =
+            spawn_line_reader(WatchSource::Path(path.clone()), quick_options(false), &stop)
+                .unwrap();
+        // Let the reader seek to the end before anything is appended
+        std::thread::sleep(Duration::from_millis(50));
+
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap();
+        file.write_all(b"first ").unwrap();
+        file.flush().unwrap();
+        std::thread::sleep(Duration::from_millis(30));
+        file.write_all(b"line\n").unwrap();
+        file.flush().unwrap();
+        let timeout = Duration::from_secs(5);
+        assert_eq!(receiver.recv_timeout(timeout).unwrap(), "first line");
+
+        std::fs::write(&path, "rotated\n").unwrap();
+        assert_eq!(receiver.recv_timeout(timeout).unwrap(), "rotated");
+
+        stop.cancel();
+        handle.join().unwrap();
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn files_can_be_read_from_the_start() {
+        let path = std::env::temp_dir().join("ciphey_watch_from_start_test.log");
+        std::fs::write(&path, "already here\n").unwrap();
+        let stop = CancellationToken::new();
+        let (receiver, handle) =
+            spawn_line_reader(WatchSource::Path(path.clone()), quick_options(true), &stop).unwrap();
+        let line = receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(line, "already here");
+        stop.cancel();
+        handle.join().unwrap();
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn missing_paths_cant_be_watched() {
+        let source = WatchSource::Path(PathBuf::from("/definitely/not/here.log"));
+        let error = spawn_line_reader(source, WatchOptions::default(), &CancellationToken::new())
+            .unwrap_err();
+        assert!(error.to_string().starts_with("Can't watch"));
+    }
+}
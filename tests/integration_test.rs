@@ -1,10 +1,13 @@
 use ciphey::checkers::checker_result::CheckResult;
 use ciphey::checkers::checker_type::{Check, Checker};
 use ciphey::checkers::english::EnglishChecker;
+use ciphey::checkers::CheckerTypes;
 use ciphey::config::Config;
+use ciphey::corpus::{CorpusGenerator, Encoder};
 use ciphey::decoders::base64_decoder::Base64Decoder;
 use ciphey::decoders::crack_results::CrackResult;
 use ciphey::decoders::interface::{Crack, Decoder};
+use ciphey::decoders::{clear_custom_decoders, register_decoder};
 use ciphey::perform_cracking;
 use ciphey::storage::database;
 use ciphey::{set_test_db_path, TestDatabase};
@@ -88,6 +91,10 @@ fn test_cache_miss_simple_base64() {
     let mut expected_crack_result: CrackResult =
         CrackResult::new(&base64_decoder, encoded_text_1.clone());
     expected_crack_result.unencrypted_text = Some(vec![decoded_text_1.clone()]);
+    expected_crack_result.description = format!(
+        "{} Decoded as standard Base64.",
+        base64_decoder.get_description()
+    );
     let expected_checker = Checker::<EnglishChecker>::new();
     let mut expected_check_result = CheckResult::new(&expected_checker);
     expected_check_result.is_identified = true;
@@ -113,6 +120,10 @@ fn test_cache_hit_simple_base64() {
     let mut expected_crack_result: CrackResult =
         CrackResult::new(&base64_decoder, encoded_text_1.clone());
     expected_crack_result.unencrypted_text = Some(vec![decoded_text_1.clone()]);
+    expected_crack_result.description = format!(
+        "{} Decoded as standard Base64.",
+        base64_decoder.get_description()
+    );
     let expected_checker = Checker::<EnglishChecker>::new();
     let mut expected_check_result = CheckResult::new(&expected_checker);
     expected_check_result.is_identified = true;
@@ -125,6 +136,9 @@ fn test_cache_hit_simple_base64() {
         decoded_text: decoded_text_1.clone(),
         path: vec![expected_crack_result],
         execution_time_ms: 100,
+        checker: String::from("English Checker"),
+        sensitivity: Some(String::from("Medium")),
+        confidence: 0.0,
     });
 
     let config = Config::default();
@@ -143,3 +157,100 @@ fn test_cache_hit_simple_base64() {
     assert_eq!(row.path, expected_path);
     assert!(row.successful);
 }
+
+#[test]
+#[serial]
+fn test_cracks_generated_corpus() {
+    let _test_db = TestDatabase::default();
+    set_test_db_path();
+
+    // Single layers of base encodings only, so the search is quick enough for the test suite
+    let mut generator = CorpusGenerator::new(1506)
+        .with_layers(1, 1)
+        .with_encoders(&[Encoder::Base64, Encoder::Base32, Encoder::Hexadecimal]);
+    for case in generator.cases(5) {
+        let config = Config {
+            human_checker_on: false,
+            ..Default::default()
+        };
+        let result = perform_cracking(&case.ciphertext, config)
+            .unwrap_or_else(|| panic!("Could not crack {:?}", case));
+        assert_eq!(result.text[0], case.plaintext, "{:?}", case);
+    }
+}
+
+/// Undoes an encoding which writes every character twice
+struct Undoubler {
+    decoder: Decoder<()>,
+}
+
+impl Crack for Undoubler {
+    fn new() -> Self {
+        Undoubler {
+            decoder: Decoder {
+                name: "Undoubler",
+                description: "Keeps one of every pair of characters",
+                link: "",
+                tags: vec!["undoubler", "decoder"],
+                popularity: 0.5,
+                phantom: std::marker::PhantomData,
+            },
+        }
+    }
+
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        let mut result = CrackResult::new(&self.decoder, text.to_string());
+        let chars: Vec<char> = text.chars().collect();
+        if chars.is_empty()
+            || !chars.len().is_multiple_of(2)
+            || chars.chunks(2).any(|pair| pair[0] != pair[1])
+        {
+            return result;
+        }
+        let decoded: String = chars.iter().step_by(2).collect();
+        let checker_result = checker.check(&decoded);
+        result.unencrypted_text = Some(vec![decoded]);
+        result.update_checker(&checker_result);
+        result
+    }
+
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.decoder.tags
+    }
+
+    fn get_name(&self) -> &str {
+        self.decoder.name
+    }
+
+    fn get_description(&self) -> &str {
+        self.decoder.description
+    }
+
+    fn get_link(&self) -> &str {
+        self.decoder.link
+    }
+}
+
+#[test]
+#[serial]
+fn test_cracks_with_registered_decoder() {
+    let _test_db = TestDatabase::default();
+    set_test_db_path();
+
+    register_decoder(Box::new(Undoubler::new()));
+    let plaintext = "the quick brown fox jumps over the lazy dog";
+    let doubled: String = plaintext.chars().flat_map(|c| [c, c]).collect();
+    let config = Config {
+        human_checker_on: false,
+        ..Default::default()
+    };
+    let result = perform_cracking(&doubled, config).expect("Could not crack doubled text");
+    assert_eq!(result.text[0], plaintext);
+    assert_eq!(result.path.last().unwrap().decoder, "Undoubler");
+
+    // The path is cached, and reads back with the registered decoder's name
+    let row = database::read_cache(&doubled).unwrap().unwrap();
+    let cached: CrackResult = serde_json::from_str(row.path.last().unwrap()).unwrap();
+    assert_eq!(cached.decoder, "Undoubler");
+    clear_custom_decoders();
+}